@@ -3,7 +3,10 @@ mod resources;
 mod serve;
 mod users;
 
-use std::sync::{Mutex, OnceLock};
+use std::{
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
 
 use cata::{
     telemetry::{posthog, Telemetry},
@@ -19,6 +22,8 @@ use tracing_error::ErrorLayer;
 use tracing_log::AsTrace;
 use tracing_subscriber::{filter::EnvFilter, prelude::*};
 
+use crate::{config, diagnostics};
+
 static PH_KEY: Option<&str> = option_env!("POSTHOG_API_KEY");
 
 // While tracing allows for you to get the global log filter
@@ -49,6 +54,11 @@ pub struct Root {
     /// Disable telemetry
     #[arg(long, global = true)]
     no_telemetry: bool,
+
+    /// Path to a YAML configuration file. See the documentation for what can
+    /// be configured.
+    #[arg(long, global = true, env = "KTY_CONFIG")]
+    config: Option<PathBuf>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -79,8 +89,20 @@ impl Command for Root {
             .with_writer(Mutex::new(self.log_file.clone()))
             .with_filter(filter);
 
+        // Diagnostics bundles get the same logs, wherever `--log-file` is sending
+        // them.
+        let recent = tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(|| diagnostics::LOGS.writer())
+            .with_filter(
+                EnvFilter::builder()
+                    .with_default_directive(self.verbosity.log_level_filter().as_trace().into())
+                    .from_env_lossy(),
+            );
+
         let registry = tracing_subscriber::registry()
             .with(fmt)
+            .with(recent)
             .with(ErrorLayer::default());
 
         if self.no_telemetry {
@@ -93,6 +115,8 @@ impl Command for Root {
             registry.with(telemetry).init();
         }
 
+        config::load(self.config.as_deref())?;
+
         Ok(())
     }
 }
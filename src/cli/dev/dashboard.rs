@@ -1,4 +1,4 @@
-use std::{io::Read, iter::Iterator, os::fd::AsRawFd, pin::Pin, task::Context};
+use std::{io::Read, iter::Iterator, os::fd::AsRawFd, pin::Pin, sync::Arc, task::Context};
 
 use cata::{Command, Container};
 use clap::Parser;
@@ -11,7 +11,13 @@ use tokio::{
     time::Duration,
 };
 
-use crate::{dashboard::Dashboard as UIDashboard, events::Event, io::Writer};
+use crate::{
+    client::{self, preflight::Problem},
+    dashboard::Dashboard as UIDashboard,
+    events::Event,
+    io::Writer,
+    usage::Usage,
+};
 
 static STDIN_TOKEN: mio::Token = mio::Token(0);
 
@@ -91,13 +97,22 @@ impl AsyncRead for Stdin {
 #[async_trait::async_trait]
 impl Command for Dashboard {
     async fn run(&self) -> Result<()> {
+        // Before the terminal is taken over, so that this is readable. Problems
+        // with the credentials themselves are shown by the dashboard.
+        let cfg = kube::Config::infer()
+            .await
+            .map_err(|err| Problem::config(&err.into()))?;
+
         crossterm::terminal::enable_raw_mode()?;
         crossterm::execute!(std::io::stdout(), crossterm::terminal::EnterAlternateScreen)?;
 
         let (stop_tx, mut stop_rx) = unbounded_channel::<()>();
 
+        let usage = Arc::new(Usage::default());
+
         let dashboard = UIDashboard::builder()
-            .client(kube::Client::try_default().await?)
+            .client(client::metered(cfg, usage.clone())?)
+            .usage(usage)
             .build()
             .start(Stdin::new()?, LocalWriter { stop: stop_tx })?;
 
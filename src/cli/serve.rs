@@ -1,4 +1,11 @@
-use std::{net::IpAddr, path::Path, sync::Arc};
+mod incluster;
+mod rbac;
+
+use std::{
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use cata::{Command, Container};
 use clap::Parser;
@@ -11,11 +18,19 @@ use ssh_key::PrivateKey;
 use strum::VariantArray;
 use warp::Filter;
 
+use self::rbac::Requirement;
 use crate::{
+    client::{
+        self,
+        preflight::{self, Problem},
+    },
     health,
+    lease::{LeaderElection, ELECTION},
     openid::{self, Fetch},
     resources,
-    ssh::{self, ControllerBuilder, CurrentPodBuilder, Features},
+    ssh::{self, listen::Mode, ControllerBuilder, CurrentPodBuilder, Features},
+    version,
+    widget::startup::Startup,
 };
 
 static CLIENT_ID: &str = "y2KACghcNrpTlB1Q10h3MYwYYmiMShPD";
@@ -46,6 +61,12 @@ pub struct Serve {
     /// Port to listen on for SSH connections.
     #[clap(long, default_value = "2222")]
     ssh_port: u16,
+    /// Where SSH connections come from. `systemd` accepts connections on a
+    /// socket passed in by socket activation, `inetd` serves one connection
+    /// over stdin and stdout. The health and metrics endpoints aren't served
+    /// in `inetd` mode, there's a process per connection.
+    #[clap(long, value_enum, default_value_t, env = "KTY_LISTEN")]
+    listen: Mode,
     /// Port to listen on for health and metrics related endpoints.
     #[clap(long, default_value = "8080")]
     health_port: u16,
@@ -57,6 +78,18 @@ pub struct Serve {
     #[clap(long, value_parser = load_key, default_value = "")]
     key: KeyPair,
 
+    /// Directory with a projected service account token to authenticate to
+    /// the API server with, laid out like the default service account mount
+    /// (`token`, `namespace` and optionally `ca.crt`). The token is re-read
+    /// as it is rotated. By default, credentials come from a kubeconfig or
+    /// the default service account mount.
+    #[clap(long, env = "KTY_SERVICE_ACCOUNT_DIR")]
+    service_account_dir: Option<PathBuf>,
+    /// PEM bundle of the CA that signed the API server's certificate. This
+    /// takes precedence over any CA that comes with the credentials.
+    #[clap(long, env = "KTY_API_SERVER_CA")]
+    api_server_ca: Option<PathBuf>,
+
     /// Do not create (or update) resources on startup. This allows for reduced
     /// permissions but requires management of the CRDs out of band.
     #[clap(long)]
@@ -90,9 +123,136 @@ pub struct Serve {
     /// when running on a local cluster.
     #[clap(long, env = "POD_IP", default_value_t = local_ip_address::local_ip().unwrap_or("127.0.0.1".parse().unwrap()))]
     pod_ip: IpAddr,
+
+    /// Periodically check for new releases of kty. When there's an update, it
+    /// is shown in the dashboard, `kty version` and the `build_info` metric.
+    #[clap(long, env = "KTY_CHECK_UPDATES")]
+    check_updates: bool,
+    /// URL of the latest release, used with `--check-updates`. This follows
+    /// the format of the GitHub releases API.
+    #[clap(long, default_value = version::RELEASES_URL)]
+    releases_url: String,
+
+    /// Coordinate with other replicas using a `Lease`. Only the replica
+    /// holding the lease does work that should happen once across all of
+    /// them, such as `--check-updates`. Required when running more than one
+    /// replica.
+    #[clap(long, env = "KTY_LEADER_ELECTION")]
+    leader_election: bool,
+    /// Name of the `Lease`, in the server's namespace, used with
+    /// `--leader-election`.
+    #[clap(long, default_value = "kty")]
+    lease_name: String,
+    /// How long the lease is held for without being renewed. Another replica
+    /// takes over at most this long after the leader goes away.
+    #[clap(long, default_value = "15s")]
+    lease_duration: humantime::Duration,
+
+    /// Screen the dashboard opens into, unless users have picked their own
+    /// with `ssh <server> kty startup <screen>`. One of `pods`,
+    /// `deployments`, `capacity` or `last`. The lists can be limited to a
+    /// namespace, eg. `deployments/my-app`.
+    #[clap(long, default_value = "pods", env = "KTY_STARTUP")]
+    startup: Startup,
 }
 
 impl Serve {
+    async fn kube_config(&self) -> Result<kube::Config> {
+        incluster::config(
+            self.service_account_dir.as_deref(),
+            self.api_server_ca.as_deref(),
+        )
+        .await
+        .map_err(|err| Problem::config(&err).into())
+    }
+
+    // Everything the server does with its own credentials, as opposed to
+    // what it does as users through impersonation.
+    fn requirements(&self) -> Vec<Requirement> {
+        let mut requirements = vec![
+            Requirement {
+                verb: "impersonate",
+                group: "",
+                resource: "users",
+                name: None,
+                namespaced: false,
+                reason: "sessions act as the user that logged in",
+                required: false,
+            },
+            Requirement {
+                verb: "impersonate",
+                group: "",
+                resource: "groups",
+                name: None,
+                namespaced: false,
+                reason: "sessions act with the user's groups",
+                required: false,
+            },
+        ];
+
+        for verb in ["get", "patch"] {
+            requirements.push(Requirement {
+                verb,
+                group: "kty.dev",
+                resource: "keys",
+                name: None,
+                namespaced: true,
+                reason: "public keys are remembered after logging in",
+                required: true,
+            });
+            requirements.push(Requirement {
+                verb,
+                group: "kty.dev",
+                resource: "preferences",
+                name: None,
+                namespaced: true,
+                reason: "per-user preferences are stored",
+                required: true,
+            });
+        }
+
+        if !self.no_create {
+            for name in ["keys.kty.dev", "preferences.kty.dev"] {
+                requirements.push(Requirement {
+                    verb: "patch",
+                    group: "apiextensions.k8s.io",
+                    resource: "customresourcedefinitions",
+                    name: Some(name),
+                    namespaced: false,
+                    reason: "CRDs are updated on startup, pass --no-create to skip this",
+                    required: true,
+                });
+            }
+        }
+
+        if self.leader_election {
+            for verb in ["get", "create", "update"] {
+                requirements.push(Requirement {
+                    verb,
+                    group: "coordination.k8s.io",
+                    resource: "leases",
+                    name: None,
+                    namespaced: true,
+                    reason: "--leader-election coordinates replicas with a lease",
+                    required: true,
+                });
+            }
+        }
+
+        requirements
+    }
+
+    async fn elect(&self) -> Result<()> {
+        LeaderElection::new(
+            client::new(self.kube_config().await?)?,
+            &self.lease_name,
+            &self.pod_name,
+            self.lease_duration.into(),
+        )
+        .run()
+        .await
+    }
+
     async fn serve_http(&self) -> Result<()> {
         let metrics = warp::path("metrics").and_then(health::metrics);
 
@@ -104,7 +264,9 @@ impl Serve {
     }
 
     async fn serve_ssh(&self) -> Result<()> {
-        let cfg = kube::Config::infer().await?;
+        let cfg = self.kube_config().await?;
+        let namespace = cfg.default_namespace.clone();
+        let server = cfg.cluster_url.to_string();
 
         let reporter = Reporter {
             controller: CONTROLLER_NAME.into(),
@@ -124,6 +286,11 @@ impl Serve {
             .reporter(Some(reporter.clone()))
             .build()?;
 
+        // Anything wrong with the credentials gets explained here, rather than
+        // as whichever request happened to go out first failing.
+        preflight::check(&ctrl.client()?, Some(server)).await?;
+        rbac::check(ctrl.client()?, &namespace, &self.requirements()).await?;
+
         if !self.no_create {
             resources::create(&Api::all(ctrl.client()?), true).await?;
         }
@@ -161,7 +328,11 @@ impl Serve {
             ))
             .features(self.features.clone())
             .build()?
-            .run(server_cfg, (self.address.clone(), self.ssh_port))
+            .run(
+                server_cfg,
+                &self.listen,
+                (self.address.clone(), self.ssh_port),
+            )
             .await
     }
 }
@@ -171,9 +342,15 @@ impl Command for Serve {
     #[allow(clippy::blocks_in_conditions)]
     #[tracing::instrument(err, skip(self), fields(activity = "serve"))]
     async fn run(&self) -> Result<()> {
+        version::register();
+        ELECTION.set_identity(&self.pod_name);
+        Startup::set_configured(self.startup.clone());
+
         tokio::select! {
-            result = self.serve_http() => result,
+            result = self.serve_http(), if self.listen != Mode::Inetd => result,
             result = self.serve_ssh() => result,
+            result = version::watch(self.releases_url.clone()), if self.check_updates => result,
+            result = self.elect(), if self.leader_election => result,
         }
     }
 }
@@ -0,0 +1,88 @@
+use std::{env, net::IpAddr, path::Path};
+
+use eyre::{eyre, Result, WrapErr};
+
+/// Credentials for the server itself. With `service_account`, these come from
+/// a projected service account token in that directory. Otherwise, they're
+/// inferred the same way `kubectl` does it: a kubeconfig first and then the
+/// default service account mount.
+pub async fn config(service_account: Option<&Path>, ca: Option<&Path>) -> Result<kube::Config> {
+    let mut cfg = match service_account {
+        Some(dir) => projected(dir)?,
+        None => kube::Config::infer().await?,
+    };
+
+    if let Some(ca) = ca {
+        cfg.root_cert = Some(bundle(ca)?);
+    }
+
+    Ok(cfg)
+}
+
+// This follows the layout of `/var/run/secrets/kubernetes.io/serviceaccount`,
+// so any directory that works for that works here too. The token is passed to
+// the client as a file, it re-reads the file every minute which picks up the
+// kubelet rotating it.
+fn projected(dir: &Path) -> Result<kube::Config> {
+    let token = dir.join("token");
+
+    // The client only reads the token once the first request goes out, having
+    // it missing show up then is a lot more confusing than failing here.
+    std::fs::read_to_string(&token).wrap_err_with(|| {
+        format!(
+            "unable to read the service account token at {}, is the projected volume mounted?",
+            token.display()
+        )
+    })?;
+
+    let namespace = dir.join("namespace");
+    let namespace = std::fs::read_to_string(&namespace)
+        .wrap_err_with(|| format!("unable to read the namespace from {}", namespace.display()))?;
+
+    let mut cfg = kube::Config::new(api_server()?);
+    cfg.default_namespace = namespace.trim().to_string();
+    cfg.auth_info.token_file = Some(token.to_string_lossy().to_string());
+
+    let ca = dir.join("ca.crt");
+
+    if ca.exists() {
+        cfg.root_cert = Some(bundle(&ca)?);
+    }
+
+    Ok(cfg)
+}
+
+// Every pod gets these set by the kubelet, the DNS name is a fallback for
+// anything that has been set up without service links.
+fn api_server() -> Result<http::Uri> {
+    let (Ok(host), Ok(port)) = (
+        env::var("KUBERNETES_SERVICE_HOST"),
+        env::var("KUBERNETES_SERVICE_PORT"),
+    ) else {
+        return Ok(http::Uri::from_static("https://kubernetes.default.svc"));
+    };
+
+    let host = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(ip)) => format!("[{ip}]"),
+        _ => host,
+    };
+
+    Ok(format!("https://{host}:{port}").parse()?)
+}
+
+fn bundle(path: &Path) -> Result<Vec<Vec<u8>>> {
+    let content = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("unable to read the API server CA from {}", path.display()))?;
+
+    let certs: Vec<_> = pem::parse_many(content)?
+        .into_iter()
+        .filter(|block| block.tag() == "CERTIFICATE")
+        .map(pem::Pem::into_contents)
+        .collect();
+
+    if certs.is_empty() {
+        return Err(eyre!("no certificates found in {}", path.display()));
+    }
+
+    Ok(certs)
+}
@@ -0,0 +1,100 @@
+use eyre::{eyre, Result};
+use futures::future::try_join_all;
+use k8s_openapi::api::authorization::v1::{
+    ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+};
+use kube::api::{Api, PostParams};
+
+/// Something the server's own credentials need to be allowed to do.
+pub struct Requirement {
+    pub verb: &'static str,
+    pub group: &'static str,
+    pub resource: &'static str,
+    /// For when access is limited to specific objects with `resourceNames`.
+    pub name: Option<&'static str>,
+    /// Cluster scoped when false, otherwise in the server's namespace.
+    pub namespaced: bool,
+    /// What stops working without it, for the error message.
+    pub reason: &'static str,
+    /// Impersonation is often restricted to specific users and groups with
+    /// `resourceNames`, which a review without a name is always denied for.
+    /// Those only warn instead of refusing to start.
+    pub required: bool,
+}
+
+impl std::fmt::Display for Requirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let resource = if self.group.is_empty() {
+            self.resource.to_string()
+        } else {
+            format!("{}.{}", self.resource, self.group)
+        };
+
+        match self.name {
+            Some(name) => write!(f, "{} {resource} {name} ({})", self.verb, self.reason),
+            None => write!(f, "{} {resource} ({})", self.verb, self.reason),
+        }
+    }
+}
+
+/// Check that the server's credentials can do everything in `requirements`.
+/// Without this, missing permissions only show up once a user connects and
+/// something fails part way through their session.
+pub async fn check(
+    client: kube::Client,
+    namespace: &str,
+    requirements: &[Requirement],
+) -> Result<()> {
+    let reviews = Api::<SelfSubjectAccessReview>::all(client);
+
+    let allowed = try_join_all(requirements.iter().map(|requirement| {
+        let reviews = reviews.clone();
+
+        async move {
+            let review = reviews
+                .create(
+                    &PostParams::default(),
+                    &SelfSubjectAccessReview {
+                        spec: SelfSubjectAccessReviewSpec {
+                            resource_attributes: Some(ResourceAttributes {
+                                namespace: requirement.namespaced.then(|| namespace.to_string()),
+                                group: Some(requirement.group.to_string()),
+                                resource: Some(requirement.resource.to_string()),
+                                name: requirement.name.map(ToString::to_string),
+                                verb: Some(requirement.verb.to_string()),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
+            Ok::<_, kube::Error>(review.status.is_some_and(|status| status.allowed))
+        }
+    }))
+    .await?;
+
+    let mut missing = Vec::new();
+
+    for (requirement, allowed) in requirements.iter().zip(allowed) {
+        match (allowed, requirement.required) {
+            (true, _) => {}
+            (false, true) => missing.push(format!("- {requirement}")),
+            (false, false) => tracing::warn!(
+                "unable to confirm the server can {requirement}, this is expected if it is \
+                 limited to specific names"
+            ),
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    Err(eyre!(
+        "the server's credentials are missing permissions it needs, in namespace {namespace}:\n{}",
+        missing.join("\n")
+    ))
+}
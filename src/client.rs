@@ -0,0 +1,30 @@
+pub mod preflight;
+
+use std::sync::Arc;
+
+use kube::client::ClientBuilder;
+use tower::util::{MapResponseLayer, MapResultLayer};
+
+use crate::{
+    credentials, throttle,
+    usage::{MeterLayer, Usage},
+};
+
+/// Build a client for the API server. All clients should be created this way
+/// so that throttling and credential failures are noticed regardless of which
+/// request triggered them.
+///
+/// Credentials come from the config as-is, this includes exec plugins which
+/// the client re-runs as their tokens expire.
+pub fn new(cfg: kube::Config) -> Result<kube::Client, kube::Error> {
+    metered(cfg, Arc::default())
+}
+
+/// Build a client that keeps track of the requests made with it in `usage`.
+pub fn metered(cfg: kube::Config, usage: Arc<Usage>) -> Result<kube::Client, kube::Error> {
+    Ok(ClientBuilder::try_from(cfg)?
+        .with_layer(&MeterLayer::new(usage))
+        .with_layer(&MapResultLayer::new(credentials::observe))
+        .with_layer(&MapResponseLayer::new(throttle::observe))
+        .build())
+}
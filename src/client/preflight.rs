@@ -0,0 +1,209 @@
+use std::{error::Error as _, fmt, time::Duration};
+
+use kube::client::AuthError;
+
+// Long enough for a slow exec plugin to hand back a token, short enough that a
+// cluster which isn't there doesn't look like kty hanging.
+static TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The common ways a kubeconfig goes wrong, each with something that can be
+/// done about it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// There is no kubeconfig, or it can't be read.
+    Config,
+    /// The exec plugin that gets tokens is missing or failed.
+    ExecPlugin,
+    /// The API server turned the credentials down, usually because they've
+    /// expired.
+    Rejected,
+    /// The credentials work, but aren't allowed to do something every session
+    /// needs.
+    Forbidden,
+    /// The API server's certificate isn't signed by the CA that was configured.
+    Certificate,
+    /// Nothing answered at the API server's address.
+    Unreachable,
+    Other,
+}
+
+impl Kind {
+    fn summary(self) -> &'static str {
+        match self {
+            Self::Config => "No usable kubeconfig was found",
+            Self::ExecPlugin => "The kubeconfig's exec plugin couldn't get a token",
+            Self::Rejected => "The API server rejected the credentials",
+            Self::Forbidden => "The credentials aren't allowed to talk to the API server",
+            Self::Certificate => "The API server's certificate couldn't be verified",
+            Self::Unreachable => "The API server couldn't be reached",
+            Self::Other => "Talking to the API server failed",
+        }
+    }
+
+    fn fix(self) -> &'static str {
+        match self {
+            Self::Config => {
+                "Set KUBECONFIG or create ~/.kube/config. When running in a pod, check that the \
+                 service account token is mounted, or pass --service-account-dir."
+            }
+            Self::ExecPlugin => {
+                "The user in the kubeconfig runs a command to get its token. Check that the \
+                 command is installed and on the PATH, then run it yourself to see why it fails. \
+                 Logging in again (eg. `aws sso login`, `gcloud auth login`) usually fixes it. If \
+                 it used to work, the plugin might be out of date, update it along with the \
+                 `apiVersion` it has in the kubeconfig."
+            }
+            Self::Rejected => {
+                "The token has most likely expired or been revoked. Log in again or fetch a new \
+                 kubeconfig. For service accounts, check that the token still exists."
+            }
+            Self::Forbidden => {
+                "Over ssh, everything happens as the user that logged in. The server's service \
+                 account needs to be able to impersonate users and groups, and the user needs to \
+                 be bound to a role."
+            }
+            Self::Certificate => {
+                "The CA in the kubeconfig (`certificate-authority-data`) doesn't match the \
+                 cluster, which happens when a cluster is recreated. Fetch a new kubeconfig, or \
+                 pass --api-server-ca to `kty serve`."
+            }
+            Self::Unreachable => {
+                "Check that the cluster is running and that this machine can get to it, a VPN, \
+                 firewall or proxy could be in the way. The address comes from the kubeconfig's \
+                 `server`."
+            }
+            Self::Other => "The error from the API server is below.",
+        }
+    }
+}
+
+/// Why the API server can't be used, put in terms of what to do about it.
+#[derive(Clone, Debug)]
+pub struct Problem {
+    pub kind: Kind,
+    pub server: Option<String>,
+    /// The underlying error, for when the explanation isn't enough.
+    pub detail: String,
+}
+
+impl Problem {
+    pub fn summary(&self) -> String {
+        match &self.server {
+            Some(server) => format!("{} ({server})", self.kind.summary()),
+            None => self.kind.summary().to_string(),
+        }
+    }
+
+    pub fn fix(&self) -> &'static str {
+        self.kind.fix()
+    }
+
+    pub fn config(err: &eyre::Report) -> Self {
+        Self {
+            kind: Kind::Config,
+            server: None,
+            detail: format!("{err:#}"),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n\n{}\n\n{}", self.summary(), self.fix(), self.detail)
+    }
+}
+
+impl std::error::Error for Problem {}
+
+fn auth(err: &kube::Error) -> Option<&AuthError> {
+    if let kube::Error::Auth(err) = err {
+        return Some(err);
+    }
+
+    let mut source = err.source();
+
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<AuthError>() {
+            return Some(err);
+        }
+
+        source = err.source();
+    }
+
+    None
+}
+
+// TLS and connection errors only show up as text somewhere down the chain,
+// which one depends on the TLS backend and the OS.
+fn chain(err: &kube::Error) -> String {
+    let mut msg = err.to_string();
+    let mut source = err.source();
+
+    while let Some(err) = source {
+        msg.push_str(": ");
+        msg.push_str(&err.to_string());
+
+        source = err.source();
+    }
+
+    msg
+}
+
+/// Work out which kind of problem an error is.
+pub fn classify(err: &kube::Error, server: Option<String>) -> Problem {
+    let detail = chain(err);
+    let lower = detail.to_lowercase();
+
+    let kind = if let Some(err) = auth(err) {
+        match err {
+            AuthError::ReadTokenFile(..) => Kind::Rejected,
+            _ => Kind::ExecPlugin,
+        }
+    } else if let kube::Error::Api(resp) = err {
+        match resp.code {
+            401 => Kind::Rejected,
+            403 => Kind::Forbidden,
+            _ => Kind::Other,
+        }
+    } else if ["certificate", "unknownissuer", "unknown issuer"]
+        .iter()
+        .any(|needle| lower.contains(needle))
+    {
+        Kind::Certificate
+    } else if [
+        "connection refused",
+        "dns error",
+        "failed to lookup address",
+        "no route to host",
+        "network is unreachable",
+        "timed out",
+        "tcp connect error",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+    {
+        Kind::Unreachable
+    } else {
+        Kind::Other
+    };
+
+    Problem {
+        kind,
+        server,
+        detail,
+    }
+}
+
+/// Make a request that needs working credentials, so that anything wrong with
+/// them shows up before it would have in the middle of something else.
+pub async fn check(client: &kube::Client, server: Option<String>) -> Result<(), Problem> {
+    match tokio::time::timeout(TIMEOUT, client.apiserver_version()).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(err)) => Err(classify(&err, server)),
+        Err(_) => Err(Problem {
+            kind: Kind::Unreachable,
+            server,
+            detail: format!("no response after {}s", TIMEOUT.as_secs()),
+        }),
+    }
+}
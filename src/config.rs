@@ -0,0 +1,589 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+use eyre::{eyre, Result, WrapErr};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use ratatui::style::{self, Color, Modifier};
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    resources::{dynamic::Object, pod::level, store::prefetch},
+    widget::{
+        log::highlight::Highlighter,
+        table::{density::Density, Row},
+    },
+};
+
+// Like `crate::cli::LEVEL`, this is process wide so that widgets deep in the
+// tree can get at it without it being threaded through every constructor.
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Server configuration, loaded from the file passed to `--config`. Everything
+/// here is optional and has a reasonable default.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub configmaps: ConfigMaps,
+    pub costs: Costs,
+    pub debug: Debug,
+    pub drift: Drift,
+    pub logs: Logs,
+    pub namespaces: Namespaces,
+    pub quit: Quit,
+    pub reconnect: Reconnect,
+    pub tables: Tables,
+    pub views: Vec<View>,
+}
+
+/// `ConfigMap`s changed from the dashboard get a copy of what they were before
+/// each change, so that it can be put back. See `crate::resources::revision`.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ConfigMaps {
+    /// How many copies to keep for each `ConfigMap`, 0 turns them off.
+    pub revisions: usize,
+}
+
+impl Default for ConfigMaps {
+    fn default() -> Self {
+        Self { revisions: 5 }
+    }
+}
+
+/// Node prices, for estimating what workloads cost. Nothing is estimated
+/// until at least one price is set. See `crate::resources::cost`.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Costs {
+    /// Hourly price of a node, by its instance type label.
+    pub hourly: HashMap<String, f64>,
+    /// Hourly price of nodes whose type isn't in `hourly`.
+    pub default: Option<f64>,
+    /// Put in front of every price.
+    pub currency: String,
+}
+
+impl Costs {
+    pub fn enabled(&self) -> bool {
+        !self.hourly.is_empty() || self.default.is_some()
+    }
+}
+
+impl Default for Costs {
+    fn default() -> Self {
+        Self {
+            hourly: HashMap::new(),
+            default: None,
+            currency: "$".to_string(),
+        }
+    }
+}
+
+/// Debugging tools that get added to pods. See `crate::policy`.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Debug {
+    /// Whether ephemeral containers can be attached to pods for network
+    /// debugging. They can't be removed again without deleting the pod.
+    pub allow_network: bool,
+    /// Image for the network debugging container. It needs a shell and
+    /// `sleep`, everything else is up to whoever is debugging.
+    pub network_image: String,
+}
+
+impl Default for Debug {
+    fn default() -> Self {
+        Self {
+            allow_network: true,
+            network_image: "nicolaka/netshoot:latest".to_string(),
+        }
+    }
+}
+
+/// Where the declared state that live objects are compared against comes from,
+/// either a git repository, a directory of manifests or a bundle pasted in by
+/// the user. Nothing is compared until one of them is set. See
+/// `crate::resources::drift`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Drift {
+    /// Anything `git clone` takes. It is cloned again for every comparison.
+    pub git: Option<String>,
+    /// Branch or tag to compare against, the default branch otherwise.
+    pub branch: Option<String>,
+    /// Directory that has the manifests in it. With `git`, this is relative to
+    /// the root of the repository.
+    pub path: Option<PathBuf>,
+    /// Whether manifests pasted into the drift view, such as the output of
+    /// `kustomize build`, can be compared against instead.
+    pub uploads: bool,
+}
+
+impl Drift {
+    pub fn enabled(&self) -> bool {
+        self.git.is_some() || self.path.is_some() || self.uploads
+    }
+
+    /// Whether there's anything to compare against without an upload.
+    pub fn configured(&self) -> bool {
+        self.git.is_some() || self.path.is_some()
+    }
+}
+
+/// What can be done to namespaces from the dashboard, on top of whatever RBAC
+/// allows. See `crate::policy`.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Namespaces {
+    pub allow_create: bool,
+    pub allow_delete: bool,
+    /// Namespaces that can never be deleted. A trailing `*` matches any
+    /// namespace that starts with what comes before it.
+    pub protected: Vec<String>,
+    /// Users and groups that may only browse some namespaces. Anyone who
+    /// doesn't match one of these sees whatever RBAC lets them.
+    pub restrict: Vec<Restriction>,
+}
+
+/// Namespaces that some users and groups are limited to in the dashboard,
+/// regardless of what RBAC allows. Patterns work like `protected`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Restriction {
+    #[serde(default)]
+    pub users: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    pub allow: Vec<String>,
+}
+
+impl Default for Namespaces {
+    fn default() -> Self {
+        Self {
+            allow_create: true,
+            allow_delete: true,
+            protected: vec![
+                "default".to_string(),
+                "kube-*".to_string(),
+                "kty".to_string(),
+            ],
+            restrict: Vec::new(),
+        }
+    }
+}
+
+/// What it takes to leave the dashboard from its top level screen, so that a
+/// stray escape doesn't end the session. `ctrl-c` always quits right away.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Quit {
+    pub confirm: Confirmation,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confirmation {
+    /// Quit on the first escape, the way it has always worked.
+    None,
+    /// Press escape a second time, shortly after the first.
+    #[default]
+    Double,
+    /// Answer `y` to a prompt.
+    Prompt,
+}
+
+/// Dashboards whose connection goes away are kept running for `window`
+/// seconds, so that reconnecting as the same user picks up right where they
+/// were. 0 ends the dashboard along with the connection.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Reconnect {
+    pub window: u64,
+}
+
+impl Default for Reconnect {
+    fn default() -> Self {
+        Self { window: 60 }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tables {
+    /// Narrowest that a column will be shrunk to when there isn't room to show
+    /// everything. Content that is shorter than this isn't padded out.
+    pub min_width: u16,
+    /// Minimum widths for specific columns, by their (case insensitive) header.
+    /// These take precedence over `min_width`.
+    pub columns: HashMap<String, u16>,
+    /// Kinds, by plural, that are listed every `poll_interval` seconds
+    /// instead of being watched. See `crate::resources::store::poll::Poll`.
+    pub poll: Vec<String>,
+    pub poll_interval: u64,
+    /// Samples kept for the trend column of the pod table, taken every
+    /// `history_interval` seconds.
+    pub history: usize,
+    pub history_interval: u64,
+    /// Roughly how much each session's stores can hold, such as `512Mi`.
+    /// Past this, stores that haven't been looked at in a while are emptied
+    /// until they're needed again. See `crate::resources::store::memory`.
+    pub memory_limit: Option<Quantity>,
+    /// How tables are laid out until someone picks something else for their
+    /// session, see `crate::widget::table::density`.
+    pub density: Density,
+    /// Kinds that are watched from the start of every session instead of when
+    /// they're first viewed, see `crate::resources::store::prefetch`.
+    pub prefetch: Prefetch,
+}
+
+impl Tables {
+    pub fn minimum(&self, column: &str) -> u16 {
+        self.columns
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(column))
+            .map_or(self.min_width, |(_, width)| *width)
+    }
+}
+
+impl Default for Tables {
+    fn default() -> Self {
+        Self {
+            min_width: 8,
+            columns: HashMap::from([("name".to_string(), 20)]),
+            poll: Vec::new(),
+            poll_interval: 30,
+            history: 12,
+            history_interval: 15,
+            memory_limit: None,
+            density: Density::default(),
+            prefetch: Prefetch::default(),
+        }
+    }
+}
+
+/// Either a profile, `lazy` to start nothing until it is viewed or `all` for
+/// every kind with its own view, or a list of specific kinds by plural.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Prefetch {
+    Profile(Profile),
+    Kinds(Vec<String>),
+}
+
+impl Default for Prefetch {
+    fn default() -> Self {
+        Self::Profile(Profile::default())
+    }
+}
+
+impl Prefetch {
+    pub fn includes(&self, plural: &str) -> bool {
+        match self {
+            Self::Profile(Profile::Lazy) => false,
+            Self::Profile(Profile::All) => true,
+            Self::Kinds(kinds) => kinds.iter().any(|kind| kind == plural),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    /// Stores start when something first needs them, for big clusters where
+    /// listing everything up front would be slow and hold a lot of memory.
+    #[default]
+    Lazy,
+    /// Every kind is listed as the session starts, for small clusters where
+    /// that's cheap and every view might as well be ready right away.
+    All,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Logs {
+    /// Rules used to highlight log lines. Setting this replaces the defaults,
+    /// use an empty list to disable highlighting entirely.
+    pub highlight: Vec<Highlight>,
+    /// Admin endpoints for changing the log level of containers that don't
+    /// declare one with an annotation. The first that matches is used.
+    pub levels: Vec<LogLevel>,
+    /// Most lines per second that are kept from a log stream. Anything faster
+    /// is sampled so that the dashboard can keep up, 0 keeps everything.
+    pub max_rate: u32,
+    /// Seconds between two lines before a marker is put between them, when
+    /// timestamps are shown. 0 turns the markers off.
+    pub min_gap: u32,
+}
+
+impl Default for Logs {
+    fn default() -> Self {
+        Self {
+            levels: Vec::new(),
+            max_rate: 500,
+            min_gap: 10,
+            highlight: vec![
+                Highlight::line(r"\b(ERROR|FATAL|error|fatal)\b", Color::Red),
+                Highlight::line(r"\b(WARN|WARNING|warn|warning)\b", Color::Yellow),
+                Highlight::matches(
+                    r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+                    Color::Magenta,
+                ),
+                Highlight::matches(r"\b(?:\d{1,3}\.){3}\d{1,3}(?::\d{1,5})?\b", Color::Cyan),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogLevel {
+    /// Labels the pod needs to have, all of them.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Only this container, otherwise every container in matching pods.
+    pub container: Option<String>,
+    #[serde(default)]
+    pub api: level::Flavor,
+    pub port: u16,
+    pub path: String,
+}
+
+impl LogLevel {
+    pub fn endpoint(&self) -> level::Endpoint {
+        level::Endpoint {
+            api: self.api,
+            port: self.port,
+            path: self.path.clone(),
+        }
+    }
+}
+
+/// A named list that shows up in the resource switcher, such as "payments-prod
+/// pods" or "GPU nodes". Only the objects that match are fetched.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct View {
+    pub name: String,
+    /// Kind (`Pod`) or plural (`pods`), matched case insensitively.
+    pub kind: String,
+    /// Needed when the same kind exists in multiple groups.
+    pub group: Option<String>,
+    pub namespace: Option<String>,
+    /// Label selector, e.g. `app=payments,tier!=canary`.
+    pub selector: Option<String>,
+    /// Shown after the namespace and name, in place of the status and age.
+    #[serde(default)]
+    pub columns: Vec<Column>,
+    /// Column to sort by, prefixed with `-` for descending.
+    pub sort: Option<String>,
+}
+
+impl View {
+    /// Headers of every column in the view, in order.
+    pub fn headers(&self) -> Option<Vec<&str>> {
+        if self.columns.is_empty() {
+            return None;
+        }
+
+        let mut headers = vec!["Namespace", "Name"];
+        headers.extend(self.columns.iter().map(|column| column.name.as_str()));
+
+        Some(headers)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Column {
+    pub name: String,
+    /// Where the value is in the object. Either dotted (`spec.nodeName`,
+    /// `status.containerStatuses.0.restartCount`) or, for keys that have dots
+    /// in them, a JSON pointer (`/metadata/labels/app.kubernetes.io~1name`).
+    pub path: String,
+}
+
+impl Column {
+    pub fn pointer(&self) -> String {
+        if self.path.starts_with('/') {
+            return self.path.clone();
+        }
+
+        format!("/{}", self.path.replace('.', "/"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Style only the text that matched.
+    #[default]
+    Match,
+    /// Style the entire line that contains a match.
+    Line,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Highlight {
+    pub pattern: String,
+    #[serde(default)]
+    pub scope: Scope,
+    pub style: Style,
+}
+
+impl Highlight {
+    fn line(pattern: &str, fg: Color) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            scope: Scope::Line,
+            style: Style {
+                fg: Some(fg),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn matches(pattern: &str, fg: Color) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            scope: Scope::Match,
+            style: Style {
+                fg: Some(fg),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// A subset of `ratatui::style::Style` that can be written by hand. Colors are
+/// anything ratatui can parse, e.g. `red`, `lightblue` or `#ff8800`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Style {
+    #[serde(deserialize_with = "color")]
+    pub fg: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl From<&Style> for style::Style {
+    fn from(style: &Style) -> Self {
+        let mut result = style::Style::default();
+
+        if let Some(fg) = style.fg {
+            result = result.fg(fg);
+        }
+
+        if let Some(bg) = style.bg {
+            result = result.bg(bg);
+        }
+
+        for (enabled, modifier) in [
+            (style.bold, Modifier::BOLD),
+            (style.italic, Modifier::ITALIC),
+            (style.underline, Modifier::UNDERLINED),
+        ] {
+            if enabled {
+                result = result.add_modifier(modifier);
+            }
+        }
+
+        result
+    }
+}
+
+fn color<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|color| Color::from_str(&color).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+impl Config {
+    fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let cfg: Self =
+            serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(content.as_str()))?;
+
+        // Catch invalid patterns at startup instead of when someone first opens
+        // the logs.
+        Highlighter::new(&cfg.logs.highlight)?;
+
+        if cfg.tables.poll_interval == 0 {
+            return Err(eyre!("tables.poll_interval needs to be at least 1 second"));
+        }
+
+        if let Prefetch::Kinds(kinds) = &cfg.tables.prefetch {
+            if let Some(kind) = kinds
+                .iter()
+                .find(|kind| !prefetch::KINDS.contains(&kind.as_str()))
+            {
+                return Err(eyre!(
+                    "tables.prefetch can't include {kind}, expected lazy, all or a list of {}",
+                    prefetch::KINDS.join(", ")
+                ));
+            }
+        }
+
+        if cfg
+            .namespaces
+            .restrict
+            .iter()
+            .any(|restriction| restriction.users.is_empty() && restriction.groups.is_empty())
+        {
+            return Err(eyre!(
+                "namespaces.restrict needs users or groups for every entry, otherwise it applies \
+                 to nobody"
+            ));
+        }
+
+        for view in &cfg.views {
+            let Some(sort) = view.sort.as_deref() else {
+                continue;
+            };
+
+            let column = sort.strip_prefix('-').unwrap_or(sort);
+            let headers: Vec<String> = match view.headers() {
+                Some(headers) => headers.into_iter().map(String::from).collect(),
+                None => Object::columns().into_iter().map(String::from).collect(),
+            };
+
+            if !headers.iter().any(|header| header == column) {
+                return Err(eyre!(
+                    "view {} sorts by {column}, which isn't one of its columns: {}",
+                    view.name,
+                    headers.join(", ")
+                ));
+            }
+        }
+
+        Ok(cfg)
+    }
+}
+
+/// Load the configuration. It can only be loaded once, with `None` using the
+/// defaults.
+pub fn load(path: Option<&Path>) -> Result<()> {
+    let cfg = path
+        .map(|path| {
+            Config::from_file(path).wrap_err_with(|| format!("loading config {}", path.display()))
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    CONFIG.set(cfg).map_err(|_| eyre!("config already loaded"))
+}
+
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
@@ -0,0 +1,64 @@
+use std::sync::{LazyLock, Mutex};
+
+use http::{Response, StatusCode};
+use kube::client::AuthError;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, IntCounter};
+use tower::BoxError;
+
+lazy_static! {
+    static ref AUTH_FAILURES: IntCounter = register_int_counter!(
+        "api_auth_failures_total",
+        "Number of requests that failed because credentials could not be refreshed or were \
+         rejected by the API server"
+    )
+    .unwrap();
+}
+
+/// Tracks whether the API server is currently accepting our credentials.
+///
+/// Exec plugins (`aws eks get-token`, `gke-gcloud-auth-plugin`) are re-run by
+/// the client whenever the token they returned is about to expire. When that
+/// fails, or the API server starts rejecting the token, every store stops
+/// updating without any indication as to why. The most recent failure is kept
+/// around so that the UI can explain what is going on.
+#[derive(Default)]
+pub struct Credentials {
+    failure: Mutex<Option<String>>,
+}
+
+pub static CREDENTIALS: LazyLock<Credentials> = LazyLock::new(Credentials::default);
+
+impl Credentials {
+    fn fail(&self, reason: String) {
+        AUTH_FAILURES.inc();
+
+        tracing::warn!("{reason}");
+
+        *self.failure.lock().unwrap() = Some(reason);
+    }
+
+    fn clear(&self) {
+        self.failure.lock().unwrap().take();
+    }
+
+    pub fn failure(&self) -> Option<String> {
+        self.failure.lock().unwrap().clone()
+    }
+}
+
+pub fn observe<B>(result: Result<Response<B>, BoxError>) -> Result<Response<B>, BoxError> {
+    match &result {
+        Ok(resp) if resp.status() == StatusCode::UNAUTHORIZED => {
+            CREDENTIALS.fail("credentials rejected by API server".to_string());
+        }
+        Ok(_) => CREDENTIALS.clear(),
+        Err(err) => {
+            if let Some(err) = err.downcast_ref::<AuthError>() {
+                CREDENTIALS.fail(format!("unable to refresh credentials: {err}"));
+            }
+        }
+    }
+
+    result
+}
@@ -1,13 +1,14 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use bon::builder;
+use crossterm::event::{DisableBracketedPaste, EnableBracketedPaste};
 use eyre::{eyre, Report, Result};
 use futures::TryStreamExt;
 use lazy_static::lazy_static;
 use prometheus::{register_int_counter, register_int_gauge, IntCounter, IntGauge};
 use ratatui::{
     backend::Backend as BackendTrait,
-    buffer::Buffer,
+    buffer::{Buffer, Cell},
     layout::{Position, Rect},
     widgets::Clear,
     Terminal,
@@ -21,9 +22,12 @@ use tokio::{
 use tokio_util::io::ReaderStream;
 
 use crate::{
-    events::{Broadcast, Event, Input, Keypress, StringError},
+    events::{Broadcast, Decoder, Event, Input, Keypress, StringError},
+    identity::preferences::Storage,
     io::{backend::Backend, Writer},
-    widget::{apex::Apex, Raw, Widget},
+    policy::{self, Scope},
+    usage::{self, Usage},
+    widget::{apex::Apex, copy, link, Raw, Widget},
 };
 
 lazy_static! {
@@ -45,6 +49,13 @@ pub static RENDER_INTERVAL: Duration = Duration::from_millis(1000 / FPS as u64);
 #[builder]
 pub struct Dashboard {
     client: kube::Client,
+    preferences: Option<Storage>,
+    // Where `client` reports its requests, shown in the debug widget.
+    #[builder(default)]
+    usage: Arc<Usage>,
+    // Namespaces that stores are limited to.
+    #[builder(default)]
+    scope: Scope,
 }
 
 impl Dashboard {
@@ -59,40 +70,30 @@ impl Dashboard {
     // - `stdin` or `stout` have not hit EOF
     // - `rx` has not been closed
     // - a `Event::Shutdown` has not been sent
-    // They will continue to run in the background.
+    // They will continue to run in the background. The thread owns the runtime,
+    // so anything spawned by widgets is cancelled when it exits.
     pub fn start<R>(&mut self, stdin: R, stdout: impl Writer) -> Result<UnboundedSender<Event>>
     where
         R: AsyncRead + Send + 'static,
     {
         let (tx, rx) = mpsc::unbounded_channel();
 
-        let reader_tx = tx.clone();
-        tokio::spawn(async move {
-            let stream = ReaderStream::new(stdin);
-            tokio::pin!(stream);
-
-            loop {
-                tokio::select! {
-                    () = reader_tx.closed() => {
-                        break;
-                    }
-                    Ok(Some(msg)) = stream.try_next() => {
-                        reader_tx.send(msg.into())?;
-                    }
-                }
-            }
-
-            Ok::<(), Report>(())
-        });
+        forward(stdin, tx.clone());
 
         let rt = Builder::new_current_thread().enable_all().build()?;
         let client = self.client.clone();
+        let preferences = self.preferences.clone();
+        let usage = self.usage.clone();
+        let scope = self.scope.clone();
 
         std::thread::spawn(move || {
             TOTAL_DASHBOARD_THREADS.inc();
             ACTIVE_DASHBOARD_THREADS.inc();
 
-            if let Err(err) = rt.block_on(run(client, rx, stdout)) {
+            usage::set(usage);
+            policy::set_scope(scope);
+
+            if let Err(err) = rt.block_on(run(client, preferences, rx, stdout)) {
                 tracing::error!("Unhandled dashboard error: {err:?}");
             }
 
@@ -103,6 +104,42 @@ impl Dashboard {
     }
 }
 
+/// Decode `stdin` into events for a dashboard. This is done once for every
+/// connection a dashboard gets, which might be more than one when it has been
+/// resumed.
+pub fn forward<R>(stdin: R, tx: UnboundedSender<Event>)
+where
+    R: AsyncRead + Send + 'static,
+{
+    tokio::spawn(async move {
+        let stream = ReaderStream::new(stdin);
+        tokio::pin!(stream);
+
+        let mut decoder = Decoder::default();
+
+        loop {
+            tokio::select! {
+                () = tx.closed() => {
+                    break;
+                }
+                msg = stream.try_next() => {
+                    // Dropping `tx` once input has gone away is what lets `run`
+                    // notice that the session is over.
+                    let Ok(Some(msg)) = msg else {
+                        break;
+                    };
+
+                    for ev in decoder.decode(&msg) {
+                        tx.send(ev)?;
+                    }
+                }
+            }
+        }
+
+        Ok::<(), Report>(())
+    });
+}
+
 impl std::fmt::Debug for Dashboard {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Dashboard").finish()
@@ -133,6 +170,7 @@ impl Mode {
 
 async fn run(
     client: kube::Client,
+    preferences: Option<Storage>,
     mut rx: UnboundedReceiver<Event>,
 
     stdout: impl Writer,
@@ -146,10 +184,19 @@ async fn run(
     let (backend, window_size) = Backend::with_size(stdout.blocking());
     let mut term = Terminal::new(backend)?;
 
+    crossterm::execute!(stdout.blocking(), EnableBracketedPaste)?;
+
     // kube::Client ends up being cloned by ~every widget, it'd be nice to Arc<> it
     // so that there's not a bunch of copying. Unfortunately, the Api interface
     // doesn't like Arc<>.
-    let mut state = Mode::UI(Box::new(Apex::new(client)));
+    let mut state = Mode::UI(Box::new(Apex::new(client, preferences)));
+
+    // Hyperlinks that are currently on screen, see `widget::link`.
+    let mut links = Vec::new();
+
+    // Whether the last frame went anywhere. A dashboard without a connection
+    // keeps running, but there's no point in drawing it.
+    let mut attached = true;
 
     loop {
         // It is important that this doesn't go *too* fast. Repeatedly writing to the
@@ -167,17 +214,48 @@ async fn run(
             }
         };
 
+        // The channel has already been closed, there's no point in trying to clean up
+        // the client's terminal. Returning drops the runtime, which cancels every
+        // task (stores, log streams, ...) that the widgets had running.
+        if let Event::Shutdown = ev {
+            return Ok(());
+        }
+
         if let Event::Resize(area) = ev {
             let mut size = window_size.lock().unwrap();
             *size = area;
+
+            // Resizing clears the screen, which takes the links with it.
+            links.clear();
+        }
+
+        if !stdout.attached() {
+            attached = false;
+
+            continue;
+        }
+
+        // Whatever was on screen belonged to the previous connection, the new one
+        // starts out blank and needs everything drawn from scratch.
+        if !attached {
+            attached = true;
+
+            term.clear()?;
+            crossterm::execute!(stdout.blocking(), EnableBracketedPaste)?;
+            links.clear();
         }
 
         let result = match state {
-            Mode::UI(ref mut widget) => draw_ui(widget, &mut term, &ev)?,
+            Mode::UI(ref mut widget) => draw_ui(widget, &mut term, &ev, &mut links)?,
             Mode::Raw(ref mut raw_widget, ref mut current_widget) => {
                 let raw_result =
                     draw_raw(raw_widget, &mut term, &mut rx, stdout.non_blocking()).await;
 
+                // Whatever was running in raw mode owns the terminal and may well have
+                // turned bracketed paste off on its way out.
+                crossterm::execute!(stdout.blocking(), EnableBracketedPaste)?;
+                links.clear();
+
                 let area = term.get_frame().area();
 
                 let result = current_widget.dispatch(
@@ -209,6 +287,13 @@ async fn run(
         frame.set_cursor_position(Position::default());
     })?;
 
+    crossterm::execute!(stdout.blocking(), DisableBracketedPaste)?;
+
+    // The connection is about to go away because the dashboard is done, not
+    // because of the network. Closing the channel first keeps it from being
+    // kept around to resume.
+    rx.close();
+
     // This is a somewhat arbitrary sleep to allow for a flush to happen before the
     // channel is shutdown. It seems that this isn't required locally, but when
     // running from a cluster it needs a little bit of time.
@@ -223,11 +308,13 @@ fn draw_ui<W>(
     widget: &mut Box<dyn Widget>,
     term: &mut Terminal<Backend<W>>,
     ev: &Event,
+    links: &mut Vec<(u16, u16, Cell)>,
 ) -> Result<Broadcast>
 where
     W: std::io::Write + Send,
 {
     let mut result = Err(eyre!("no dispatch"));
+    let mut current = Vec::new();
 
     term.try_draw(|frame| {
         let area = frame.area();
@@ -236,11 +323,25 @@ where
             .draw(frame, area)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("{e:?}")));
 
+        current = link::cells(frame.buffer_mut());
+
         result = dispatch(widget, ev, frame.buffer_mut(), area);
 
         draw_result
     })?;
 
+    // The links survive on screen until the cells underneath them are redrawn,
+    // which only happens when they change. Only write them out when that's the
+    // case to keep from sending them every frame.
+    if current != *links {
+        term.backend_mut().overlay(&current)?;
+        *links = current;
+    }
+
+    if let Some(escape) = copy::take() {
+        term.backend_mut().escape(&escape)?;
+    }
+
     result
 }
 
@@ -22,6 +22,9 @@ pub enum Broadcast {
 #[derive(Debug, Clone)]
 pub enum Event {
     Input(Input),
+    // Text pasted while the terminal is in bracketed paste mode. This is kept
+    // separate from `Input` so that it isn't treated as a series of keypresses.
+    Paste(String),
     Resize(WindowSize),
     Goto(Vec<String>),
     Shutdown,
@@ -54,6 +57,103 @@ impl From<Bytes> for Event {
     }
 }
 
+static PASTE_START: &[u8] = b"\x1b[200~";
+static PASTE_END: &[u8] = b"\x1b[201~";
+
+// Anything pasted past this is dropped. A paste ends up in an input box or
+// the raw session as a single event, neither of which has any use for more.
+static MAX_PASTE: usize = 1024 * 1024;
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+// How much of the end of `data` could be the start of `marker`, with the rest
+// of it still to come in the next read.
+fn partial(data: &[u8], marker: &[u8]) -> usize {
+    (1..marker.len())
+        .rev()
+        .find(|len| data.ends_with(&marker[..*len]))
+        .unwrap_or(0)
+}
+
+fn extend(paste: &mut Vec<u8>, data: &[u8]) {
+    let room = MAX_PASTE.saturating_sub(paste.len());
+
+    paste.extend_from_slice(&data[..data.len().min(room)]);
+}
+
+/// Converts raw terminal input into events. Pastes can be larger than a single
+/// read, so anything between the start and end markers is buffered until the
+/// end shows up. The markers themselves can be split across reads too, so
+/// whatever might be the start of one is held on to until the next read.
+#[derive(Default)]
+pub struct Decoder {
+    paste: Option<Vec<u8>>,
+    pending: Vec<u8>,
+}
+
+impl Decoder {
+    pub fn decode(&mut self, data: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        let mut buffered = std::mem::take(&mut self.pending);
+        buffered.extend_from_slice(data);
+
+        let mut data = buffered.as_slice();
+
+        while !data.is_empty() {
+            if let Some(paste) = self.paste.as_mut() {
+                let Some(end) = find(data, PASTE_END) else {
+                    let (content, rest) = data.split_at(data.len() - partial(data, PASTE_END));
+
+                    extend(paste, content);
+                    self.pending = rest.to_vec();
+
+                    break;
+                };
+
+                extend(paste, &data[..end]);
+                events.push(Event::Paste(
+                    String::from_utf8_lossy(&self.paste.take().unwrap_or_default()).into_owned(),
+                ));
+
+                data = &data[end + PASTE_END.len()..];
+
+                continue;
+            }
+
+            let Some(start) = find(data, PASTE_START) else {
+                // A lone escape is a keypress of its own, it takes more of the
+                // marker than that to be worth waiting for the rest.
+                let held = Some(partial(data, PASTE_START))
+                    .filter(|held| *held > 1)
+                    .unwrap_or(0);
+                let (input, rest) = data.split_at(data.len() - held);
+
+                if !input.is_empty() {
+                    events.push(input.into());
+                }
+
+                self.pending = rest.to_vec();
+
+                break;
+            };
+
+            if start > 0 {
+                events.push(data[..start].into());
+            }
+
+            self.paste = Some(Vec::new());
+            data = &data[start + PASTE_START.len()..];
+        }
+
+        events
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StringError(pub String);
 
@@ -194,3 +294,66 @@ impl From<&[u8]> for Keypress {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pastes(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn paste_in_one_read() {
+        let mut decoder = Decoder::default();
+
+        let events = decoder.decode(b"\x1b[200~hello\x1b[201~");
+
+        assert_eq!(pastes(&events), vec!["hello".to_string()]);
+    }
+
+    #[test]
+    fn markers_split_across_reads() {
+        let mut decoder = Decoder::default();
+
+        assert!(decoder.decode(b"\x1b[20").is_empty());
+        assert!(decoder.decode(b"0~hel").is_empty());
+        assert!(decoder.decode(b"lo\x1b[2").is_empty());
+
+        let events = decoder.decode(b"01~");
+
+        assert_eq!(pastes(&events), vec!["hello".to_string()]);
+        assert!(decoder.pending.is_empty());
+        assert!(decoder.paste.is_none());
+    }
+
+    #[test]
+    fn lone_escape_is_not_held() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(decoder.decode(b"\x1b").len(), 1);
+        assert!(decoder.pending.is_empty());
+    }
+
+    #[test]
+    fn paste_is_capped() {
+        let mut decoder = Decoder::default();
+
+        decoder.decode(b"\x1b[200~");
+        decoder.decode(&vec![b'a'; MAX_PASTE]);
+        decoder.decode(&vec![b'b'; MAX_PASTE]);
+
+        let events = decoder.decode(b"\x1b[201~");
+        let pastes = pastes(&events);
+
+        assert_eq!(pastes.len(), 1);
+        assert_eq!(pastes[0].len(), MAX_PASTE);
+        assert!(!pastes[0].contains('b'));
+    }
+}
@@ -7,7 +7,7 @@ use tachyonfx::{CellFilter, CellIterator, Duration, Effect, EffectRenderer, Effe
 use crate::{
     dashboard::RENDER_INTERVAL,
     events::{Broadcast, Event},
-    widget::{BoxWidget, Placement, Widget},
+    widget::{Action, BoxWidget, Placement, Widget},
 };
 
 #[derive(Builder)]
@@ -44,6 +44,10 @@ impl Widget for Animated {
     fn zindex(&self) -> u16 {
         self.widget.zindex()
     }
+
+    fn actions(&self) -> Vec<Action> {
+        self.widget.actions()
+    }
 }
 
 #[derive(Clone, Default)]
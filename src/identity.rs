@@ -1,6 +1,7 @@
 pub mod key;
+pub mod preferences;
 
-use std::fmt::Display;
+use std::{fmt::Display, sync::Arc};
 
 use eyre::Result;
 use k8s_openapi::api::authorization::v1::{
@@ -10,7 +11,10 @@ use k8s_openapi::api::authorization::v1::{
 pub use key::Key;
 use kube::api::{Api, PostParams};
 
-use crate::ssh::{Authenticate, Controller};
+use crate::{
+    ssh::{Authenticate, Controller},
+    usage::Usage,
+};
 
 #[derive(Clone, Debug)]
 pub struct Identity {
@@ -36,6 +40,15 @@ impl Identity {
     pub fn client(&self, ctrl: &Controller) -> Result<kube::Client, kube::Error> {
         ctrl.impersonate(self.name.clone(), self.groups.clone())
     }
+
+    /// A client for this identity that reports its requests to `usage`.
+    pub fn metered_client(
+        &self,
+        ctrl: &Controller,
+        usage: Arc<Usage>,
+    ) -> Result<kube::Client, kube::Error> {
+        ctrl.impersonate_with(self.name.clone(), self.groups.clone(), usage)
+    }
 }
 
 #[async_trait::async_trait]
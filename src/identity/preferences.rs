@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+
+use eyre::Result;
+use kube::{
+    api::{Api, ApiResource, Patch, PatchParams},
+    CustomResource, ResourceExt,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::Identity;
+use crate::{
+    resources::{KubeID, MANAGER},
+    widget::{
+        startup::Startup,
+        table::{self, density::Density},
+        theme::Theme,
+    },
+};
+
+/// Number of recently visited kinds and objects that are kept around. This
+/// matches the number keys available to jump back to them, which favorites
+/// share.
+static MAX_RECENT: usize = 9;
+
+/// Per-user state that should survive across sessions. There's one of these
+/// for each user, stored next to their `Key` resources.
+#[allow(clippy::module_name_repetitions)]
+#[derive(CustomResource, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[kube(
+    group = "kty.dev",
+    version = "v1alpha1",
+    kind = "Preferences",
+    plural = "preferences",
+    namespaced
+)]
+pub struct PreferencesSpec {
+    pub user: String,
+    /// Most recently visited first.
+    #[serde(default)]
+    pub recent: Vec<Visit>,
+    /// Kinds pinned along with the namespace they were listed in, `None` for
+    /// every namespace. These go ahead of the recent ones and stay put.
+    #[serde(default)]
+    pub favorites: Vec<Visit>,
+    #[serde(default)]
+    pub theme: Theme,
+    /// Left out until someone picks one, so that the server's config applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub density: Option<Density>,
+    /// How each kind's table was last sorted and filtered.
+    #[serde(default)]
+    pub tables: BTreeMap<String, table::state::State>,
+    /// Where the dashboard opens into, instead of the server's `--startup`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub startup: Option<Startup>,
+}
+
+/// A kind that has been visited, with enough information to list it again
+/// without waiting for discovery. Visits to a single object also have its
+/// name, and namespace when it has one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Visit {
+    pub group: String,
+    pub version: String,
+    pub kind: String,
+    pub plural: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Visit {
+    pub fn object(resource: &ApiResource, namespace: Option<String>, name: String) -> Self {
+        Self {
+            namespace,
+            name: Some(name),
+            ..resource.into()
+        }
+    }
+}
+
+impl From<&ApiResource> for Visit {
+    fn from(resource: &ApiResource) -> Self {
+        Self {
+            group: resource.group.clone(),
+            version: resource.version.clone(),
+            kind: resource.kind.clone(),
+            plural: resource.plural.clone(),
+            namespace: None,
+            name: None,
+        }
+    }
+}
+
+impl From<&Visit> for ApiResource {
+    fn from(visit: &Visit) -> Self {
+        Self {
+            group: visit.group.clone(),
+            version: visit.version.clone(),
+            api_version: if visit.group.is_empty() {
+                visit.version.clone()
+            } else {
+                format!("{}/{}", visit.group, visit.version)
+            },
+            kind: visit.kind.clone(),
+            plural: visit.plural.clone(),
+        }
+    }
+}
+
+impl Preferences {
+    /// Record a visit, moving it to the front if it was already there.
+    pub fn visit(&mut self, visit: Visit) {
+        self.spec.recent.retain(|v| v != &visit);
+        self.spec.recent.insert(0, visit);
+        self.spec.recent.truncate(MAX_RECENT);
+    }
+
+    /// Pin `visit`, or unpin it if it already was. Returns whether it is
+    /// pinned now, which it can't be once every number key is taken.
+    pub fn favorite(&mut self, visit: Visit) -> bool {
+        if let Some(idx) = self.spec.favorites.iter().position(|v| v == &visit) {
+            self.spec.favorites.remove(idx);
+
+            return false;
+        }
+
+        if self.spec.favorites.len() >= MAX_RECENT {
+            return false;
+        }
+
+        self.spec.favorites.push(visit);
+
+        true
+    }
+
+    /// What each number key jumps to, favorites first and then the recent
+    /// visits that aren't one of them. The `bool` is whether it's a favorite.
+    pub fn strip(&self) -> impl Iterator<Item = (bool, &Visit)> {
+        self.spec
+            .favorites
+            .iter()
+            .map(|visit| (true, visit))
+            .chain(
+                self.spec
+                    .recent
+                    .iter()
+                    .filter(|visit| !self.spec.favorites.contains(visit))
+                    .map(|visit| (false, visit)),
+            )
+            .take(MAX_RECENT)
+    }
+}
+
+/// Loads and saves the preferences for a single user. Users don't need any
+/// permissions of their own for this, it happens with the server's client.
+#[derive(Clone)]
+pub struct Storage {
+    client: kube::Client,
+    user: String,
+}
+
+impl Storage {
+    pub fn new(client: kube::Client, identity: &Identity) -> Self {
+        Self {
+            client,
+            user: identity.name.clone(),
+        }
+    }
+
+    fn default(&self) -> Result<Preferences> {
+        Ok(Preferences::new(
+            self.user.kube_id()?.as_str(),
+            PreferencesSpec {
+                user: self.user.clone(),
+                recent: Vec::new(),
+                favorites: Vec::new(),
+                theme: Theme::default(),
+                density: None,
+                tables: BTreeMap::new(),
+                startup: None,
+            },
+        ))
+    }
+
+    #[tracing::instrument(skip(self), fields(user = self.user))]
+    pub async fn load(&self) -> Result<Preferences> {
+        let default = self.default()?;
+
+        Ok(Api::<Preferences>::default_namespaced(self.client.clone())
+            .get_opt(&default.name_any())
+            .await?
+            .unwrap_or(default))
+    }
+
+    #[tracing::instrument(skip(self, preferences), fields(user = self.user))]
+    pub async fn save(&self, preferences: &Preferences) -> Result<()> {
+        let mut preferences = preferences.clone();
+        preferences.metadata.managed_fields = None;
+        preferences.metadata.resource_version = None;
+
+        Api::<Preferences>::default_namespaced(self.client.clone())
+            .patch(
+                &preferences.name_any(),
+                &PatchParams::apply(MANAGER).force(),
+                &Patch::Apply(&preferences),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visit(plural: &str, namespace: Option<&str>) -> Visit {
+        Visit {
+            group: String::new(),
+            version: "v1".to_string(),
+            kind: plural.to_string(),
+            plural: plural.to_string(),
+            namespace: namespace.map(str::to_string),
+            name: None,
+        }
+    }
+
+    fn preferences() -> Preferences {
+        Preferences::new(
+            "me",
+            PreferencesSpec {
+                user: "me".to_string(),
+                recent: Vec::new(),
+                favorites: Vec::new(),
+                theme: Theme::default(),
+                density: None,
+                tables: BTreeMap::new(),
+                startup: None,
+            },
+        )
+    }
+
+    #[test]
+    fn favorites_go_first() {
+        let mut preferences = preferences();
+
+        preferences.visit(visit("pods", None));
+        preferences.visit(visit("services", None));
+        assert!(preferences.favorite(visit("pods", Some("default"))));
+        assert!(preferences.favorite(visit("services", None)));
+
+        let strip: Vec<_> = preferences
+            .strip()
+            .map(|(favorite, visit)| (favorite, visit.plural.as_str(), visit.namespace.as_deref()))
+            .collect();
+
+        assert_eq!(
+            strip,
+            vec![
+                (true, "pods", Some("default")),
+                (true, "services", None),
+                (false, "pods", None),
+            ]
+        );
+
+        assert!(!preferences.favorite(visit("services", None)));
+        assert_eq!(preferences.spec.favorites.len(), 1);
+    }
+
+    #[test]
+    fn favorites_are_capped() {
+        let mut preferences = preferences();
+
+        for i in 0..MAX_RECENT {
+            assert!(preferences.favorite(visit(&format!("kind{i}"), None)));
+        }
+
+        assert!(!preferences.favorite(visit("pods", None)));
+        assert_eq!(preferences.strip().count(), MAX_RECENT);
+    }
+}
@@ -3,7 +3,10 @@ pub mod backend;
 use std::{
     io::Write,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
     task::{Context, Poll},
 };
 
@@ -68,6 +71,90 @@ impl Writer for Channel {
     }
 }
 
+/// A channel that can be swapped out for another one, so that a dashboard can
+/// outlive the connection it started on. While detached, anything written is
+/// thrown away. See `crate::ssh::session::resume`.
+#[derive(Clone)]
+pub struct Handoff {
+    current: Arc<Mutex<Channel>>,
+    attached: Arc<AtomicBool>,
+}
+
+impl Handoff {
+    pub fn new(channel: Channel) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(channel)),
+            attached: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    pub fn detach(&self) {
+        self.attached.store(false, Ordering::Relaxed);
+    }
+
+    pub fn attach(&self, channel: Channel) {
+        *self.current.lock().unwrap() = channel;
+        self.attached.store(true, Ordering::Relaxed);
+    }
+
+    fn channel(&self) -> Channel {
+        self.current.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl Writer for Handoff {
+    fn blocking(&self) -> impl Write {
+        HandoffWriter {
+            handoff: self.clone(),
+            buf: Vec::new(),
+        }
+    }
+
+    // Raw widgets hold onto this for as long as they run, they end with an error
+    // when the connection goes away.
+    fn non_blocking(&self) -> impl AsyncWrite + Send + Unpin + 'static {
+        let channel = self.channel();
+
+        SshWriter::new(channel.id, channel.handle)
+    }
+
+    async fn shutdown(&self, msg: String) -> Result<()> {
+        self.channel().shutdown(msg).await
+    }
+
+    fn attached(&self) -> bool {
+        self.attached.load(Ordering::Relaxed)
+    }
+}
+
+pub struct HandoffWriter {
+    handoff: Handoff,
+    buf: Vec<u8>,
+}
+
+impl std::io::Write for HandoffWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let buf = std::mem::take(&mut self.buf);
+
+        if !self.handoff.attached() {
+            return Ok(());
+        }
+
+        let channel = self.handoff.channel();
+        let mut writer = SshWriter::new(channel.id, channel.handle);
+
+        writer.write_all(&buf)?;
+        writer.flush()
+    }
+}
+
 pub struct SshWriter {
     id: ChannelId,
     handle: Arc<Handle>,
@@ -176,4 +263,9 @@ pub trait Writer: Send + Sync + 'static {
     async fn shutdown(&self, _msg: String) -> Result<()> {
         Ok(())
     }
+
+    /// Whether there's anyone on the other end to draw for.
+    fn attached(&self) -> bool {
+        true
+    }
 }
@@ -3,6 +3,10 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use crossterm::{
+    cursor::{RestorePosition, SavePosition},
+    queue,
+};
 use ratatui::{
     backend::{Backend as BackendTrait, ClearType, CrosstermBackend, WindowSize},
     buffer::Cell,
@@ -40,6 +44,25 @@ where
             size,
         )
     }
+
+    /// Draw cells on top of what is already on screen without going through
+    /// the usual diffing. The cursor is put back where it was afterwards.
+    pub fn overlay(&mut self, cells: &[(u16, u16, Cell)]) -> io::Result<()> {
+        queue!(self.crossterm, SavePosition)?;
+        self.crossterm
+            .draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell)))?;
+        queue!(self.crossterm, RestorePosition)?;
+
+        self.crossterm.flush()
+    }
+
+    /// Write an escape sequence that doesn't draw anything, such as OSC 52 to
+    /// set the clipboard.
+    pub fn escape(&mut self, escape: &str) -> io::Result<()> {
+        io::Write::write_all(&mut self.crossterm, escape.as_bytes())?;
+
+        io::Write::flush(&mut self.crossterm)
+    }
 }
 
 impl<W> BackendTrait for Backend<W>
@@ -0,0 +1,238 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        LazyLock, Mutex,
+    },
+    time::Duration,
+};
+
+use chrono::Utc;
+use eyre::Result;
+use k8s_openapi::{
+    api::coordination::v1::{Lease, LeaseSpec},
+    apimachinery::pkg::apis::meta::v1::MicroTime,
+};
+use kube::{
+    api::{Api, ObjectMeta, PostParams},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_gauge, IntGauge};
+
+use crate::version::RELEASES;
+
+// The leader publishes the latest release here so that only one replica has to
+// go out and check for it.
+static LATEST_ANNOTATION: &str = "kty.dev/latest-release";
+
+lazy_static! {
+    static ref LEADER: IntGauge = register_int_gauge!(
+        "lease_leader",
+        "1 if this replica currently holds the lease, 0 otherwise"
+    )
+    .unwrap();
+}
+
+/// Which replica is responsible for work that should only happen once across
+/// every replica. When leader election isn't enabled, there's only one replica
+/// and it is always the leader.
+pub struct Election {
+    leader: AtomicBool,
+    identity: Mutex<String>,
+    holder: Mutex<Option<String>>,
+}
+
+pub static ELECTION: LazyLock<Election> = LazyLock::new(|| Election {
+    leader: AtomicBool::new(true),
+    identity: Mutex::default(),
+    holder: Mutex::default(),
+});
+
+impl Election {
+    pub fn is_leader(&self) -> bool {
+        self.leader.load(Ordering::Relaxed)
+    }
+
+    /// Name of this replica, what sessions see as where they landed.
+    pub fn identity(&self) -> String {
+        self.identity.lock().unwrap().clone()
+    }
+
+    /// Name of the replica holding the lease, if it is known.
+    pub fn holder(&self) -> Option<String> {
+        self.holder.lock().unwrap().clone()
+    }
+
+    pub fn set_identity(&self, identity: &str) {
+        *self.identity.lock().unwrap() = identity.to_string();
+    }
+
+    fn set(&self, leader: bool, holder: Option<String>) {
+        if leader != self.is_leader() {
+            tracing::info!(leader, "leadership changed");
+        }
+
+        self.leader.store(leader, Ordering::Relaxed);
+        LEADER.set(i64::from(leader));
+
+        *self.holder.lock().unwrap() = holder;
+    }
+
+    /// Single line description of this replica, suitable for showing to users.
+    pub fn summary(&self) -> String {
+        let identity = self.identity();
+
+        if self.is_leader() {
+            return format!("replica {identity} (leader)");
+        }
+
+        self.holder().map_or_else(
+            || format!("replica {identity}"),
+            |holder| format!("replica {identity} (leader: {holder})"),
+        )
+    }
+}
+
+/// Hold a `Lease` to decide which replica is the leader. Every replica tries
+/// to take the lease once it expires, the API server's optimistic concurrency
+/// makes sure only one of them succeeds.
+pub struct LeaderElection {
+    api: Api<Lease>,
+    name: String,
+    identity: String,
+    duration: Duration,
+}
+
+impl LeaderElection {
+    pub fn new(client: kube::Client, name: &str, identity: &str, duration: Duration) -> Self {
+        ELECTION.set_identity(identity);
+        // Until the lease has been looked at, there's no telling who the leader is.
+        ELECTION.set(false, None);
+
+        Self {
+            api: Api::default_namespaced(client),
+            name: name.to_string(),
+            identity: identity.to_string(),
+            duration,
+        }
+    }
+
+    /// Keep trying to acquire, or renew, the lease. Failures are logged and
+    /// retried, losing the connection to the API server means losing the lease
+    /// but shouldn't take the server down.
+    pub async fn run(&self) -> Result<()> {
+        let mut interval = tokio::time::interval(self.duration / 3);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = self.step().await {
+                tracing::warn!("unable to update lease: {err:?}");
+
+                // Without a renewal, someone else may well have taken over
+                // already.
+                ELECTION.set(false, None);
+            }
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn spec(&self, previous: Option<&LeaseSpec>) -> LeaseSpec {
+        let now = MicroTime(Utc::now());
+
+        let transitioned = previous
+            .and_then(|spec| spec.holder_identity.as_ref())
+            .is_some_and(|holder| holder != &self.identity);
+
+        LeaseSpec {
+            holder_identity: Some(self.identity.clone()),
+            lease_duration_seconds: Some(self.duration.as_secs() as i32),
+            acquire_time: if transitioned || previous.is_none() {
+                Some(now.clone())
+            } else {
+                previous.and_then(|spec| spec.acquire_time.clone())
+            },
+            renew_time: Some(now),
+            lease_transitions: Some(
+                previous
+                    .and_then(|spec| spec.lease_transitions)
+                    .unwrap_or_default()
+                    + i32::from(transitioned),
+            ),
+        }
+    }
+
+    fn annotate(annotations: &mut BTreeMap<String, String>) {
+        if let Some(latest) = RELEASES.latest() {
+            annotations.insert(LATEST_ANNOTATION.to_string(), latest.to_string());
+        }
+    }
+
+    async fn step(&self) -> Result<()> {
+        let Some(mut lease) = self.api.get_opt(&self.name).await? else {
+            let mut lease = Lease {
+                metadata: ObjectMeta {
+                    name: Some(self.name.clone()),
+                    ..Default::default()
+                },
+                spec: Some(self.spec(None)),
+            };
+            Self::annotate(lease.annotations_mut());
+
+            return self.acquire(self.api.create(&PostParams::default(), &lease).await);
+        };
+
+        let spec = lease.spec.clone().unwrap_or_default();
+        let holder = spec.holder_identity.clone();
+
+        let expired = spec.renew_time.as_ref().map_or(true, |MicroTime(renewed)| {
+            let duration = spec.lease_duration_seconds.map_or(self.duration, |secs| {
+                Duration::from_secs(u64::try_from(secs).unwrap_or_default())
+            });
+
+            *renewed + duration < Utc::now()
+        });
+
+        if holder.as_ref() != Some(&self.identity) && !expired {
+            if let Some(latest) = lease
+                .annotations()
+                .get(LATEST_ANNOTATION)
+                .and_then(|latest| semver::Version::parse(latest).ok())
+            {
+                RELEASES.set(latest);
+            }
+
+            ELECTION.set(false, holder);
+
+            return Ok(());
+        }
+
+        lease.spec = Some(self.spec(Some(&spec)));
+        Self::annotate(lease.annotations_mut());
+
+        // The resource version on the lease means this fails if someone else got
+        // there first.
+        self.acquire(
+            self.api
+                .replace(&self.name, &PostParams::default(), &lease)
+                .await,
+        )
+    }
+
+    fn acquire(&self, result: Result<Lease, kube::Error>) -> Result<()> {
+        match result {
+            Ok(_) => {
+                ELECTION.set(true, Some(self.identity.clone()));
+
+                Ok(())
+            }
+            Err(kube::Error::Api(resp)) if resp.code == 409 => {
+                ELECTION.set(false, None);
+
+                Ok(())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+}
@@ -3,15 +3,25 @@
 mod broadcast;
 #[warn(dead_code)]
 mod cli;
+mod client;
+mod config;
+mod credentials;
 mod dashboard;
+mod diagnostics;
 mod events;
 mod fx;
 mod health;
 mod identity;
 mod io;
+mod lease;
 mod openid;
+mod policy;
 mod resources;
+mod share;
 mod ssh;
+mod throttle;
+mod usage;
+mod version;
 mod widget;
 
 use cata::execute;
@@ -23,6 +33,8 @@ use crate::cli::Root;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    ssh::listen::init();
+
     color_eyre::config::HookBuilder::default()
         .display_env_section(false)
         .display_location_section(false)
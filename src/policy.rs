@@ -0,0 +1,169 @@
+use std::cell::RefCell;
+
+use eyre::{eyre, Result};
+
+use crate::{config, identity::Identity};
+
+thread_local! {
+    // Like `crate::usage`, stores are created on the dashboard's thread and
+    // this saves handing the session's identity to every one of them.
+    static SCOPE: RefCell<Scope> = RefCell::default();
+}
+
+#[derive(Clone, Copy)]
+pub enum Action {
+    Create,
+    Delete,
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+    pattern
+        .strip_suffix('*')
+        .map_or(pattern == name, |prefix| name.starts_with(prefix))
+}
+
+/// Whether `action` is allowed on the object of `kind` called `name`. RBAC
+/// decides what a user may do, this is for what the server's operator would
+/// rather nobody did by accident, such as deleting `kube-system`. The error
+/// explains why not and is meant to be shown as is.
+pub fn check(action: Action, kind: &str, name: &str) -> Result<()> {
+    if kind != "Namespace" {
+        return Ok(());
+    }
+
+    let cfg = &config::get().namespaces;
+
+    match action {
+        Action::Create if !cfg.allow_create => Err(eyre!(
+            "creating namespaces has been disabled on this server"
+        )),
+        Action::Delete if !cfg.allow_delete => Err(eyre!(
+            "deleting namespaces has been disabled on this server"
+        )),
+        Action::Delete => match cfg.protected.iter().find(|pattern| matches(pattern, name)) {
+            Some(pattern) => Err(eyre!(
+                "{name} is protected (matches {pattern}) and can't be deleted"
+            )),
+            None => Ok(()),
+        },
+        Action::Create => Ok(()),
+    }
+}
+
+/// The image to attach for network debugging, if the server allows it at all.
+pub fn network_debug() -> Result<&'static str> {
+    let cfg = &config::get().debug;
+
+    if !cfg.allow_network {
+        return Err(eyre!("network debugging has been disabled on this server"));
+    }
+
+    Ok(cfg.network_image.as_str())
+}
+
+/// Namespaces that someone may browse, on top of whatever RBAC allows. This is
+/// for multi-tenant servers where the operator would rather people only saw
+/// their own namespaces, even when they can read more than that.
+#[derive(Clone, Debug, Default)]
+pub struct Scope {
+    // Patterns of the namespaces that are allowed, `None` for everything.
+    allow: Option<Vec<String>>,
+}
+
+impl Scope {
+    /// Everything from the `namespaces.restrict` entries that match `identity`
+    /// by name or group. Matching more than one of them allows what any of
+    /// them do.
+    pub fn new(identity: &Identity) -> Self {
+        let matching: Vec<_> = config::get()
+            .namespaces
+            .restrict
+            .iter()
+            .filter(|restriction| {
+                restriction.users.contains(&identity.name)
+                    || restriction
+                        .groups
+                        .iter()
+                        .any(|group| identity.groups.contains(group))
+            })
+            .collect();
+
+        Self {
+            allow: (!matching.is_empty()).then(|| {
+                matching
+                    .iter()
+                    .flat_map(|restriction| restriction.allow.iter().cloned())
+                    .collect()
+            }),
+        }
+    }
+
+    pub fn allows(&self, namespace: &str) -> bool {
+        self.allow.as_ref().map_or(true, |allow| {
+            allow.iter().any(|pattern| matches(pattern, namespace))
+        })
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        self.allow.is_some()
+    }
+}
+
+/// Make `scope` the one that stores created on this thread are limited to.
+pub fn set_scope(scope: Scope) {
+    SCOPE.set(scope);
+}
+
+pub fn scope() -> Scope {
+    SCOPE.with_borrow(Clone::clone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(allow: &[&str]) -> Scope {
+        Scope {
+            allow: Some(allow.iter().map(ToString::to_string).collect()),
+        }
+    }
+
+    #[test]
+    fn unrestricted() {
+        let scope = Scope::default();
+
+        assert!(!scope.is_restricted());
+        assert!(scope.allows("default"));
+        assert!(scope.allows("kube-system"));
+    }
+
+    #[test]
+    fn exact() {
+        let scope = scope(&["team-a", "shared"]);
+
+        assert!(scope.is_restricted());
+        assert!(scope.allows("team-a"));
+        assert!(scope.allows("shared"));
+        assert!(!scope.allows("team-a-staging"));
+        assert!(!scope.allows("team"));
+        assert!(!scope.allows(""));
+    }
+
+    #[test]
+    fn prefix() {
+        let scope = scope(&["team-a-*"]);
+
+        assert!(scope.allows("team-a-staging"));
+        assert!(scope.allows("team-a-"));
+        assert!(!scope.allows("team-a"));
+        assert!(!scope.allows("team-b-staging"));
+    }
+
+    #[test]
+    fn nothing_allowed() {
+        let scope = scope(&[]);
+
+        assert!(scope.is_restricted());
+        assert!(!scope.allows("default"));
+    }
+}
@@ -1,10 +1,38 @@
 pub mod age;
+pub mod capacity;
+pub mod cascade;
+pub mod certificate;
+pub mod configmap;
 pub mod container;
+pub mod cost;
+pub mod cronjob;
+pub mod daemonset;
+pub mod deployment;
+pub mod deprecation;
+pub mod drift;
+pub mod dynamic;
+pub mod edit;
+pub mod encoded;
+pub mod event;
+pub mod extended;
+pub mod feed;
 pub mod file;
 pub mod install;
+pub mod job;
+pub mod kstatus;
+pub mod link;
+pub mod namespace;
+pub mod node;
 pub mod pod;
+pub mod quantity;
+pub mod revision;
+pub mod secret;
+pub mod selector;
+pub mod service;
+pub mod statefulset;
 pub mod status;
 pub mod store;
+pub mod template;
 pub mod tunnel;
 
 use color_eyre::Section;
@@ -32,7 +60,10 @@ use crate::identity;
 pub static MANAGER: &str = "kkty.dev";
 
 pub(crate) fn all() -> Vec<CustomResourceDefinition> {
-    vec![identity::key::Key::crd()]
+    vec![
+        identity::key::Key::crd(),
+        identity::preferences::Preferences::crd(),
+    ]
 }
 
 pub(crate) async fn create(
@@ -0,0 +1,276 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::AddAssign,
+    sync::Arc,
+};
+
+use k8s_openapi::{
+    api::core::v1::{Node, Pod, ResourceRequirements},
+    apimachinery::pkg::api::resource::Quantity,
+};
+use kube::ResourceExt;
+use ratatui::widgets::Row;
+
+use super::{node::NodeExt, quantity};
+use crate::widget::{table, theme::Indicator};
+
+// Pods that haven't been placed on a node yet still need room, they're shown in
+// their own pool so that it is clear how much is waiting.
+static UNSCHEDULED: &str = "(unscheduled)";
+static DEFAULT_POOL: &str = "(default)";
+
+// Requests above this fraction of allocatable are highlighted, there's unlikely
+// to be room for much more.
+static PRESSURE: f64 = 0.9;
+
+#[derive(Clone, Copy, Default)]
+pub struct Usage {
+    pub cpu: f64,
+    pub memory: f64,
+}
+
+impl Usage {
+    fn from(resources: Option<&BTreeMap<String, Quantity>>) -> Self {
+        let get = |name: &str| {
+            resources
+                .and_then(|resources| resources.get(name))
+                .and_then(quantity::parse)
+                .unwrap_or_default()
+        };
+
+        Self {
+            cpu: get("cpu"),
+            memory: get("memory"),
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            cpu: self.cpu.max(other.cpu),
+            memory: self.memory.max(other.memory),
+        }
+    }
+}
+
+impl AddAssign for Usage {
+    fn add_assign(&mut self, other: Self) {
+        self.cpu += other.cpu;
+        self.memory += other.memory;
+    }
+}
+
+/// What a pod holds onto, following the scheduler: the larger of all the
+/// containers together and any single init container, plus the overhead of the
+/// pod itself. Containers without limits don't add to the limits.
+fn usage(
+    pod: &Pod,
+    field: fn(&ResourceRequirements) -> Option<&BTreeMap<String, Quantity>>,
+) -> Usage {
+    let Some(spec) = pod.spec.as_ref() else {
+        return Usage::default();
+    };
+
+    let of = |resources: Option<&ResourceRequirements>| Usage::from(resources.and_then(field));
+
+    let mut total = spec
+        .containers
+        .iter()
+        .fold(Usage::default(), |mut total, container| {
+            total += of(container.resources.as_ref());
+            total
+        });
+
+    for container in spec.init_containers.iter().flatten() {
+        total = total.max(of(container.resources.as_ref()));
+    }
+
+    total += Usage::from(spec.overhead.as_ref());
+
+    total
+}
+
+/// What the scheduler sets aside for a pod, see `usage`.
+pub fn requests(pod: &Pod) -> Usage {
+    usage(pod, |resources| resources.requests.as_ref())
+}
+
+/// What a node has for pods to request.
+pub fn allocatable(node: &Node) -> Usage {
+    Usage::from(
+        node.status
+            .as_ref()
+            .and_then(|status| status.allocatable.as_ref()),
+    )
+}
+
+/// Requests and limits of the pods in a pool of nodes, compared against what
+/// those nodes have available.
+#[derive(Clone, Default)]
+pub struct Pool {
+    pub name: String,
+    pub nodes: usize,
+    pub pods: usize,
+    pub allocatable: Usage,
+    pub requests: Usage,
+    pub limits: Usage,
+}
+
+impl Pool {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.nodes += other.nodes;
+        self.pods += other.pods;
+        self.allocatable += other.allocatable;
+        self.requests += other.requests;
+        self.limits += other.limits;
+    }
+
+    fn fraction(used: f64, allocatable: f64) -> Option<f64> {
+        (allocatable > 0.0).then(|| used / allocatable)
+    }
+
+    fn cell(used: f64, allocatable: f64, fmt: fn(f64) -> String) -> String {
+        Self::fraction(used, allocatable).map_or_else(
+            || fmt(used),
+            |pct| format!("{} ({:.0}%)", fmt(used), pct * 100.0),
+        )
+    }
+
+    /// Whether there's little room left for new pods in this pool.
+    pub fn pressured(&self) -> bool {
+        [
+            Self::fraction(self.requests.cpu, self.allocatable.cpu),
+            Self::fraction(self.requests.memory, self.allocatable.memory),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|pct| pct > PRESSURE)
+    }
+}
+
+/// Each pool of nodes along with a row for the whole cluster. Pods that are
+/// done, or haven't been scheduled yet, don't count against any pool.
+pub fn pools(nodes: &[Arc<Node>], pods: &[Arc<Pod>]) -> Vec<Pool> {
+    let mut pools: BTreeMap<String, Pool> = BTreeMap::new();
+    let mut lookup = HashMap::new();
+
+    for node in nodes {
+        let name = node.pool().unwrap_or(DEFAULT_POOL);
+        lookup.insert(node.name_any(), name.to_string());
+
+        let pool = pools
+            .entry(name.to_string())
+            .or_insert_with(|| Pool::new(name));
+
+        pool.nodes += 1;
+        pool.allocatable += allocatable(node);
+    }
+
+    let mut unscheduled = Pool::new(UNSCHEDULED);
+
+    for pod in pods {
+        let phase = pod
+            .status
+            .as_ref()
+            .and_then(|status| status.phase.as_deref());
+        if matches!(phase, Some("Succeeded" | "Failed")) {
+            continue;
+        }
+
+        let pool = match pod
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.node_name.as_ref())
+            .and_then(|node| lookup.get(node))
+        {
+            Some(name) => pools.entry(name.clone()).or_insert_with(|| Pool::new(name)),
+            None => &mut unscheduled,
+        };
+
+        pool.pods += 1;
+        pool.requests += requests(pod);
+        pool.limits += usage(pod, |resources| resources.limits.as_ref());
+    }
+
+    let mut total = Pool::new("(total)");
+    for pool in pools.values() {
+        total.add(pool);
+    }
+
+    let mut result: Vec<_> = pools.into_values().collect();
+
+    if unscheduled.pods > 0 {
+        total.add(&unscheduled);
+        result.push(unscheduled);
+    }
+
+    result.push(total);
+
+    result
+}
+
+impl table::Row for Pool {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Pool",
+            "Nodes",
+            "Pods",
+            "CPU Requests",
+            "CPU Limits",
+            "CPU Allocatable",
+            "Memory Requests",
+            "Memory Limits",
+            "Memory Allocatable",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let requests = |cell: String| {
+            if self.pressured() {
+                Indicator::Warning.label(cell)
+            } else {
+                cell
+            }
+        };
+
+        vec![
+            self.name.clone(),
+            self.nodes.to_string(),
+            self.pods.to_string(),
+            requests(Self::cell(
+                self.requests.cpu,
+                self.allocatable.cpu,
+                quantity::cpu,
+            )),
+            Self::cell(self.limits.cpu, self.allocatable.cpu, quantity::cpu),
+            quantity::cpu(self.allocatable.cpu),
+            requests(Self::cell(
+                self.requests.memory,
+                self.allocatable.memory,
+                quantity::bytes,
+            )),
+            Self::cell(self.limits.memory, self.allocatable.memory, quantity::bytes),
+            quantity::bytes(self.allocatable.memory),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        // Only the requests are colored, they're what the scheduler looks at.
+        if self.pressured() {
+            table::highlight(self, &["CPU Requests", "Memory Requests"], style.unhealthy)
+        } else {
+            Row::new(self.cells())
+        }
+        .style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        Some(self.name.clone())
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::{HashMap, HashSet};
+
+use futures::future::join_all;
+use k8s_openapi::api::{
+    apps::v1::{ControllerRevision, ReplicaSet},
+    batch::v1::Job,
+    core::v1::{PersistentVolumeClaim, Pod},
+    discovery::v1::EndpointSlice,
+};
+use kube::{
+    api::{Api, ApiResource, DeleteParams, DynamicObject, ListParams},
+    ResourceExt,
+};
+use ratatui::widgets::Row;
+
+use crate::widget::table;
+
+/// What happens to the objects that an owner takes along when it is deleted.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Propagation {
+    #[default]
+    Background,
+    Foreground,
+    Orphan,
+}
+
+impl Propagation {
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Background => Self::Foreground,
+            Self::Foreground => Self::Orphan,
+            Self::Orphan => Self::Background,
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Background => "the owner goes right away, dependents are cleaned up after it",
+            Self::Foreground => {
+                "the owner sticks around until the dependents that block it are gone"
+            }
+            Self::Orphan => "dependents are left behind without an owner",
+        }
+    }
+
+    pub fn params(self) -> DeleteParams {
+        match self {
+            Self::Background => DeleteParams::background(),
+            Self::Foreground => DeleteParams::foreground(),
+            Self::Orphan => DeleteParams::orphan(),
+        }
+    }
+}
+
+impl std::fmt::Display for Propagation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Background => write!(f, "Background"),
+            Self::Foreground => write!(f, "Foreground"),
+            Self::Orphan => write!(f, "Orphan"),
+        }
+    }
+}
+
+/// Something that the garbage collector removes along with its owner. `depth`
+/// is how far down the chain of owners it is, pods of a deployment's replica
+/// sets are 2.
+#[derive(Clone)]
+pub struct Dependent {
+    pub kind: String,
+    pub name: String,
+    pub depth: usize,
+    /// Set by `blockOwnerDeletion`, a foreground delete waits on these.
+    pub blocking: bool,
+}
+
+impl table::Row for Dependent {
+    fn columns() -> Vec<&'static str> {
+        vec!["Kind", "Name", "Blocks Owner"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            format!("{}{}", "  ".repeat(self.depth.saturating_sub(1)), self.kind),
+            self.name.clone(),
+            if self.blocking { "yes" } else { "" }.to_string(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+}
+
+/// Everything that would go along with an object, along with the kinds that
+/// couldn't be checked.
+pub struct Cascade {
+    pub dependents: Vec<Dependent>,
+    pub unchecked: Vec<String>,
+}
+
+// The kinds that the built in controllers create on behalf of an owner.
+// Anything else, such as objects made by operators, isn't looked for.
+fn resources() -> Vec<ApiResource> {
+    vec![
+        ApiResource::erase::<ReplicaSet>(&()),
+        ApiResource::erase::<Job>(&()),
+        ApiResource::erase::<Pod>(&()),
+        ApiResource::erase::<ControllerRevision>(&()),
+        ApiResource::erase::<EndpointSlice>(&()),
+        ApiResource::erase::<PersistentVolumeClaim>(&()),
+    ]
+}
+
+struct Child {
+    uid: String,
+    dependent: Dependent,
+}
+
+/// Walk owner references in `namespace`, starting at `uid`, to find what the
+/// garbage collector would remove. Kinds that can't be listed are skipped and
+/// end up in `unchecked`.
+pub async fn dependents(client: kube::Client, namespace: &str, uid: &str) -> Cascade {
+    let lists = join_all(resources().into_iter().map(|resource| {
+        let api = Api::<DynamicObject>::namespaced_with(client.clone(), namespace, &resource);
+
+        async move { (api.list(&ListParams::default()).await, resource) }
+    }))
+    .await;
+
+    let mut children: HashMap<String, Vec<Child>> = HashMap::new();
+    let mut unchecked = Vec::new();
+
+    for (result, resource) in lists {
+        let Ok(list) = result else {
+            unchecked.push(resource.plural);
+
+            continue;
+        };
+
+        for object in list.items {
+            for owner in object.owner_references() {
+                children.entry(owner.uid.clone()).or_default().push(Child {
+                    uid: object.uid().unwrap_or_default(),
+                    dependent: Dependent {
+                        kind: resource.kind.clone(),
+                        name: object.name_any(),
+                        depth: 0,
+                        blocking: owner.block_owner_deletion.unwrap_or_default(),
+                    },
+                });
+            }
+        }
+    }
+
+    for siblings in children.values_mut() {
+        siblings.sort_by(|a, b| {
+            (&a.dependent.kind, &a.dependent.name).cmp(&(&b.dependent.kind, &b.dependent.name))
+        });
+    }
+
+    let mut dependents = Vec::new();
+    let mut seen = HashSet::from([uid.to_string()]);
+
+    walk(&children, uid, 1, &mut seen, &mut dependents);
+
+    Cascade {
+        dependents,
+        unchecked,
+    }
+}
+
+// Depth first so that everything shows up right below its owner.
+fn walk(
+    children: &HashMap<String, Vec<Child>>,
+    uid: &str,
+    depth: usize,
+    seen: &mut HashSet<String>,
+    dependents: &mut Vec<Dependent>,
+) {
+    for child in children.get(uid).into_iter().flatten() {
+        if !seen.insert(child.uid.clone()) {
+            continue;
+        }
+
+        dependents.push(Dependent {
+            depth,
+            ..child.dependent.clone()
+        });
+
+        walk(children, &child.uid, depth + 1, seen, dependents);
+    }
+}
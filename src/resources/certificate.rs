@@ -0,0 +1,194 @@
+use std::fmt;
+
+use base64::prelude::*;
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use super::age::Age;
+
+static BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+static END: &str = "-----END CERTIFICATE-----";
+
+// DER tags used by the handful of fields that get looked at.
+static SEQUENCE: u8 = 0x30;
+static SET: u8 = 0x31;
+static OID: u8 = 0x06;
+static UTC_TIME: u8 = 0x17;
+static GENERALIZED_TIME: u8 = 0x18;
+static VERSION: u8 = 0xa0;
+
+// Attribute types (2.5.4.x) that show up in subjects and issuers.
+static ATTRIBUTES: &[(u8, &str)] = &[
+    (3, "CN"),
+    (6, "C"),
+    (7, "L"),
+    (8, "ST"),
+    (10, "O"),
+    (11, "OU"),
+];
+
+/// Enough of an X.509 certificate to tell what it is for and when it stops
+/// being valid. This is for display only, nothing is verified.
+pub struct Certificate {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+struct Tlv<'a> {
+    tag: u8,
+    value: &'a [u8],
+}
+
+fn read(input: &[u8]) -> Option<(Tlv, &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&len, rest) = rest.split_first()?;
+
+    let (len, rest) = if len & 0x80 == 0 {
+        (usize::from(len), rest)
+    } else {
+        let octets = usize::from(len & 0x7f);
+        if octets == 0 || octets > 4 {
+            return None;
+        }
+
+        let (len, rest) = rest.split_at_checked(octets)?;
+
+        (
+            len.iter()
+                .fold(0, |acc, byte| acc << 8 | usize::from(*byte)),
+            rest,
+        )
+    };
+
+    let (value, rest) = rest.split_at_checked(len)?;
+
+    Some((Tlv { tag, value }, rest))
+}
+
+fn expect(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (tlv, rest) = read(input)?;
+
+    (tlv.tag == tag).then_some((tlv.value, rest))
+}
+
+fn name(input: &[u8]) -> Option<String> {
+    let mut parts = Vec::new();
+    let mut rdns = input;
+
+    while !rdns.is_empty() {
+        let (mut set, rest) = expect(rdns, SET)?;
+        rdns = rest;
+
+        while !set.is_empty() {
+            let (attribute, rest) = expect(set, SEQUENCE)?;
+            set = rest;
+
+            let (oid, value) = expect(attribute, OID)?;
+            let (value, _) = read(value)?;
+
+            let label = match oid {
+                [0x55, 0x04, kind] => ATTRIBUTES
+                    .iter()
+                    .find(|(id, _)| id == kind)
+                    .map(|(_, label)| *label),
+                _ => None,
+            };
+
+            if let Some(label) = label {
+                parts.push(format!("{label}={}", String::from_utf8_lossy(value.value)));
+            }
+        }
+    }
+
+    Some(parts.join(", "))
+}
+
+fn time(input: &[u8]) -> Option<(DateTime<Utc>, &[u8])> {
+    let (tlv, rest) = read(input)?;
+    let value = std::str::from_utf8(tlv.value).ok()?;
+
+    let value = match tlv.tag {
+        // Two digit years are 1950 through 2049.
+        t if t == UTC_TIME => {
+            let century = if value.get(..2)?.parse::<u8>().ok()? < 50 {
+                "20"
+            } else {
+                "19"
+            };
+
+            format!("{century}{value}")
+        }
+        t if t == GENERALIZED_TIME => value.to_string(),
+        _ => return None,
+    };
+
+    let time = NaiveDateTime::parse_from_str(&value, "%Y%m%d%H%M%SZ").ok()?;
+
+    Some((time.and_utc(), rest))
+}
+
+impl Certificate {
+    pub fn from_der(der: &[u8]) -> Option<Self> {
+        let (cert, _) = expect(der, SEQUENCE)?;
+        let (mut tbs, _) = expect(cert, SEQUENCE)?;
+
+        if tbs.first() == Some(&VERSION) {
+            tbs = read(tbs)?.1;
+        }
+
+        // Serial number and signature algorithm.
+        let (_, tbs) = read(tbs)?;
+        let (_, tbs) = read(tbs)?;
+
+        let (issuer, tbs) = expect(tbs, SEQUENCE)?;
+        let (validity, tbs) = expect(tbs, SEQUENCE)?;
+        let (subject, _) = expect(tbs, SEQUENCE)?;
+
+        let (not_before, validity) = time(validity)?;
+        let (not_after, _) = time(validity)?;
+
+        Some(Self {
+            subject: name(subject)?,
+            issuer: name(issuer)?,
+            not_before,
+            not_after,
+        })
+    }
+
+    /// Every certificate in a PEM bundle. Anything else in there, such as
+    /// private keys, is skipped.
+    pub fn from_pem(pem: &str) -> Vec<Self> {
+        pem.split(BEGIN)
+            .skip(1)
+            .filter_map(|block| {
+                let (body, _) = block.split_once(END)?;
+                let body: String = body.split_whitespace().collect();
+
+                Self::from_der(&BASE64_STANDARD.decode(body).ok()?)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let now = Utc::now();
+
+        let expiry = if self.not_after < now {
+            format!("expired {} ago", (now - self.not_after).to_age())
+        } else if self.not_before > now {
+            format!("not valid for another {}", (self.not_before - now).to_age())
+        } else {
+            format!("expires in {}", (self.not_after - now).to_age())
+        };
+
+        write!(
+            f,
+            "certificate {} (issuer {}), valid until {}, {expiry}",
+            self.subject,
+            self.issuer,
+            self.not_after.format("%Y-%m-%d %H:%M:%S UTC"),
+        )
+    }
+}
@@ -0,0 +1,133 @@
+use std::{cmp::Ordering, collections::BTreeMap, sync::Arc};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::{api::ObjectMeta, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{age::Age, encoded, quantity, Compare, Filter};
+use crate::widget::{table, yaml::syntax};
+
+#[allow(clippy::module_name_repetitions)]
+pub trait ConfigMapExt {
+    fn entries(&self) -> Vec<Entry>;
+    fn age(&self) -> TimeDelta;
+}
+
+impl ConfigMapExt for ConfigMap {
+    /// `data` and `binaryData` together, sorted by key. Keys can't be in both.
+    fn entries(&self) -> Vec<Entry> {
+        let text = self.data.iter().flatten().map(|(key, value)| Entry {
+            key: key.clone(),
+            size: value.len(),
+            value: value.clone(),
+            binary: false,
+        });
+
+        let binary = self.binary_data.iter().flatten().map(|(key, value)| Entry {
+            key: key.clone(),
+            size: value.0.len(),
+            value: encoded::describe(&value.0),
+            binary: true,
+        });
+
+        let mut entries: Vec<_> = text.chain(binary).collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+
+        entries
+    }
+
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+}
+
+/// A single key of a `ConfigMap`. Binary values are only described, the same
+/// way they are in the YAML.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub key: String,
+    pub value: String,
+    pub size: usize,
+    pub binary: bool,
+}
+
+impl Entry {
+    pub fn format(&self) -> String {
+        if self.binary {
+            return "binary".to_string();
+        }
+
+        syntax::detect(&self.value, Some(&self.key)).name.clone()
+    }
+}
+
+impl table::Row for Entry {
+    fn columns() -> Vec<&'static str> {
+        vec!["Key", "Format", "Size"]
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.key.clone(),
+            self.format(),
+            quantity::bytes(self.size as f64),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        Some(self.key.clone())
+    }
+}
+
+impl table::Row for Arc<ConfigMap> {
+    fn columns() -> Vec<&'static str> {
+        vec!["Namespace", "Name", "Data", "Age"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.namespace().unwrap_or_default(),
+            self.name_any(),
+            (self.data.as_ref().map_or(0, BTreeMap::len)
+                + self.binary_data.as_ref().map_or(0, BTreeMap::len))
+            .to_string(),
+            self.age().to_age(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.as_ref().meta())
+    }
+}
+
+impl Filter for ConfigMap {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<ConfigMap> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.namespace()
+            .cmp(&other.namespace())
+            .then_with(|| self.name_any().cmp(&other.name_any()))
+    }
+}
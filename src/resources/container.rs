@@ -8,13 +8,10 @@ use k8s_openapi::api::core::v1::{
     ContainerStatus, Pod,
 };
 use kube::ResourceExt;
-use ratatui::{
-    layout::Constraint,
-    widgets::{Cell, Row},
-};
+use ratatui::widgets::Row;
 
-use super::{age::Age, Compare};
-use crate::widget::table;
+use super::{age::Age, extended, Compare};
+use crate::widget::{table, theme::Indicator};
 
 #[allow(clippy::module_name_repetitions)]
 pub trait ContainerExt {
@@ -48,6 +45,18 @@ impl std::fmt::Display for State {
     }
 }
 
+impl State {
+    pub fn indicator(&self) -> Indicator {
+        match self {
+            State::Running => Indicator::Healthy,
+            State::Terminated(reason) if reason == "Completed" => Indicator::Healthy,
+            State::Terminated(_) => Indicator::Unhealthy,
+            State::Waiting(_) => Indicator::Progressing,
+            State::Unknown => Indicator::Warning,
+        }
+    }
+}
+
 impl From<&ContainerStateRunning> for State {
     fn from(_: &ContainerStateRunning) -> Self {
         State::Running
@@ -197,42 +206,40 @@ impl ContainerExt for Container {
 }
 
 impl table::Row for Container {
-    fn header<'a>() -> Option<Row<'a>> {
-        Some(Row::new(vec![
-            Cell::from("Name"),
-            Cell::from("Image"),
-            Cell::from("Ready"),
-            Cell::from("State"),
-            Cell::from("Restarts"),
-            Cell::from("Age"),
-        ]))
-    }
-
-    fn constraints() -> Vec<Constraint> {
-        vec![
-            Constraint::Max(20),
-            Constraint::Min(10),
-            Constraint::Max(5),
-            Constraint::Max(10),
-            Constraint::Max(10),
-            Constraint::Max(10),
-        ]
+    fn columns() -> Vec<&'static str> {
+        vec!["Name", "Image", "Ready", "State", "Restarts", "Age"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let mut cells = vec![
+            self.name_any(),
+            self.image().to_string(),
+            self.ready(),
+            self.state().indicator().label(self.state()),
+            self.restarts(),
+        ];
+
+        // See `Items::columns` for `Arc<Pod>`, the column is only there for pods
+        // with containers that use extended resources.
+        if extended::requested(&self.pod) {
+            cells.push(extended::summary(self.spec.resources.as_ref()));
+        }
+
+        cells.push(self.age());
+
+        cells
     }
 
     fn row(&self, style: &table::RowStyle) -> Row {
-        Row::new(vec![
-            Cell::from(self.name_any()),
-            Cell::from(self.image()),
-            Cell::from(self.ready()),
-            Cell::from(self.state().to_string()),
-            Cell::from(self.restarts()),
-            Cell::from(self.age()),
-        ])
-        .style(match self.state() {
+        Row::new(self.cells()).style(match self.state() {
             State::Running | State::Waiting(_) => style.normal,
             _ => style.unhealthy,
         })
     }
+
+    fn uid(&self) -> Option<String> {
+        Some(self.name_any())
+    }
 }
 
 impl Compare for Container {
@@ -0,0 +1,117 @@
+use std::{collections::HashMap, sync::Arc};
+
+use k8s_openapi::api::core::v1::{Node, Pod};
+use kube::ResourceExt;
+
+use super::{capacity, node::NodeExt};
+
+// The average month, which is what cloud providers bill by.
+static HOURS_PER_MONTH: f64 = 730.0;
+
+/// What a node costs a month, from `costs` in the config. `None` when there's
+/// no price for its instance type and no default either.
+pub fn node(node: &Node) -> Option<f64> {
+    let cfg = &crate::config::get().costs;
+
+    node.instance_type()
+        .and_then(|kind| cfg.hourly.get(kind))
+        .copied()
+        .or(cfg.default)
+        .map(|hourly| hourly * HOURS_PER_MONTH)
+}
+
+/// The share of its node's price that a pod keeps for itself. That's whichever
+/// of CPU and memory it requests the larger fraction of, as running out of
+/// either keeps other pods off the node.
+pub fn pod(pod: &Pod, node: &Node) -> Option<f64> {
+    let price = self::node(node)?;
+
+    let requests = capacity::requests(pod);
+    let allocatable = capacity::allocatable(node);
+
+    let fraction = |used: f64, available: f64| {
+        if available > 0.0 {
+            (used / available).min(1.0)
+        } else {
+            0.0
+        }
+    };
+
+    Some(
+        price
+            * fraction(requests.cpu, allocatable.cpu)
+                .max(fraction(requests.memory, allocatable.memory)),
+    )
+}
+
+/// Pods that are done don't hold onto anything, and ones that haven't been
+/// scheduled aren't on a node to take a share of.
+fn running<'a>(pod: &Pod, nodes: &'a HashMap<String, Arc<Node>>) -> Option<&'a Arc<Node>> {
+    let phase = pod
+        .status
+        .as_ref()
+        .and_then(|status| status.phase.as_deref());
+
+    if matches!(phase, Some("Succeeded" | "Failed")) {
+        return None;
+    }
+
+    nodes.get(pod.spec.as_ref()?.node_name.as_ref()?)
+}
+
+/// Nodes by name, for looking up where pods are running.
+pub fn by_name(nodes: &[Arc<Node>]) -> HashMap<String, Arc<Node>> {
+    nodes
+        .iter()
+        .map(|node| (node.name_any(), node.clone()))
+        .collect()
+}
+
+/// Monthly estimate for a set of pods, such as the ones owned by a
+/// deployment.
+pub fn pods<'a>(
+    pods: impl IntoIterator<Item = &'a Arc<Pod>>,
+    nodes: &HashMap<String, Arc<Node>>,
+) -> f64 {
+    pods.into_iter()
+        .filter_map(|p| running(p, nodes).and_then(|node| pod(p, node)))
+        .sum()
+}
+
+/// What the whole cluster costs, split into what's been requested and what is
+/// sitting idle.
+#[derive(Default)]
+pub struct Totals {
+    pub nodes: f64,
+    pub requested: f64,
+    /// Nodes that there's no price for, they aren't in any of the totals.
+    pub unpriced: usize,
+}
+
+impl Totals {
+    pub fn new(nodes: &[Arc<Node>], pods: &[Arc<Pod>]) -> Self {
+        let lookup = by_name(nodes);
+
+        let mut totals = Self::default();
+
+        for node in nodes {
+            match self::node(node) {
+                Some(price) => totals.nodes += price,
+                None => totals.unpriced += 1,
+            }
+        }
+
+        totals.requested = self::pods(pods, &lookup);
+
+        totals
+    }
+
+    pub fn idle(&self) -> f64 {
+        (self.nodes - self.requested).max(0.0)
+    }
+}
+
+/// A monthly price, in whatever currency the config has.
+pub fn format(monthly: f64) -> String {
+    format!("{}{monthly:.2}", crate::config::get().costs.currency)
+}
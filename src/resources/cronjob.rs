@@ -0,0 +1,190 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use kube::{api::ObjectMeta, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{age::Age, store::Store, Compare, Filter};
+use crate::widget::table;
+
+// What `kubectl create job --from=cronjob/...` marks its jobs with, so that
+// they can be told apart from scheduled runs.
+static INSTANTIATE: &str = "cronjob.kubernetes.io/instantiate";
+
+// Names end up in the `job-name` label on the pods, which can't be longer than
+// this. The API server adds 5 characters to a `generateName`.
+static MAX_NAME: usize = 63 - 5;
+
+#[allow(clippy::module_name_repetitions)]
+pub trait CronJobExt {
+    fn age(&self) -> TimeDelta;
+    fn suspended(&self) -> bool;
+    fn last_schedule(&self) -> Option<TimeDelta>;
+    fn owns(&self, job: &Job) -> bool;
+    fn trigger(&self) -> Job;
+}
+
+impl CronJobExt for CronJob {
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+
+    fn suspended(&self) -> bool {
+        self.spec.as_ref().and_then(|spec| spec.suspend) == Some(true)
+    }
+
+    /// How long ago a job was last scheduled, `None` when it never has been.
+    fn last_schedule(&self) -> Option<TimeDelta> {
+        let last = self.status.as_ref()?.last_schedule_time.as_ref()?;
+
+        Some(Utc::now() - last.0)
+    }
+
+    fn owns(&self, job: &Job) -> bool {
+        let Some(uid) = self.metadata.uid.as_ref() else {
+            return false;
+        };
+
+        job.owner_references()
+            .iter()
+            .any(|owner| owner.controller == Some(true) && &owner.uid == uid)
+    }
+
+    /// A job from the template, the same as `kubectl create job --from`. It is
+    /// owned by the cronjob like a scheduled run, so it goes away along with
+    /// the rest of the history.
+    fn trigger(&self) -> Job {
+        let template = self
+            .spec
+            .as_ref()
+            .map(|spec| spec.job_template.clone())
+            .unwrap_or_default();
+        let metadata = template.metadata.unwrap_or_default();
+
+        let mut annotations = metadata.annotations.unwrap_or_default();
+        annotations.insert(INSTANTIATE.to_string(), "manual".to_string());
+
+        let suffix = "-manual-";
+        let name: String = self
+            .name_any()
+            .chars()
+            .take(MAX_NAME - suffix.len())
+            .collect();
+
+        Job {
+            metadata: ObjectMeta {
+                generate_name: Some(format!("{name}{suffix}")),
+                namespace: self.namespace(),
+                labels: metadata.labels,
+                annotations: Some(annotations),
+                owner_references: self.controller_owner_ref(&()).map(|owner| vec![owner]),
+                ..ObjectMeta::default()
+            },
+            spec: template.spec,
+            status: None,
+        }
+    }
+}
+
+impl table::Row for Arc<CronJob> {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Namespace",
+            "Name",
+            "Schedule",
+            "Suspend",
+            "Active",
+            "Last Schedule",
+            "Age",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.namespace().unwrap_or_default(),
+            self.name_any(),
+            self.spec
+                .as_ref()
+                .map(|spec| spec.schedule.clone())
+                .unwrap_or_default(),
+            self.suspended().to_string(),
+            self.status
+                .as_ref()
+                .and_then(|status| status.active.as_ref())
+                .map_or(0, Vec::len)
+                .to_string(),
+            self.last_schedule()
+                .map_or("never".to_string(), |ago| ago.to_age()),
+            self.age().to_age(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        // Nothing runs while suspended, which is easy to forget about.
+        if self.suspended() {
+            table::highlight(self, &["Suspend"], style.unhealthy)
+        } else {
+            Row::new(self.cells())
+        }
+        .style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.as_ref().meta())
+    }
+}
+
+impl Filter for CronJob {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<CronJob> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.namespace()
+            .cmp(&other.namespace())
+            .then_with(|| self.name_any().cmp(&other.name_any()))
+    }
+}
+
+/// The jobs a single cronjob has created, newest first, kept up to date as
+/// they come and go.
+pub struct Owned {
+    pub cronjob: Arc<CronJob>,
+    pub jobs: Arc<Store<Job>>,
+}
+
+impl table::Items for Arc<Owned> {
+    type Item = Arc<Job>;
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let mut jobs: Vec<_> = self
+            .jobs
+            .items(filter)
+            .into_iter()
+            .filter(|job| self.cronjob.owns(job))
+            .collect();
+
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.creation_timestamp()));
+
+        jobs
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(self.jobs.generation())
+    }
+
+    fn error(&self) -> Option<String> {
+        self.jobs.error()
+    }
+}
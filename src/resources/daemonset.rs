@@ -0,0 +1,508 @@
+use std::{cmp::Ordering, collections::HashMap, sync::Arc};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::api::{
+    apps::v1::DaemonSet,
+    core::v1::{Node, Pod, PodSpec, Taint, Toleration},
+};
+use kube::{api::ObjectMeta, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{
+    age::Age,
+    deployment::{failing, Health},
+    kstatus::{self, Status},
+    pod::PodExt,
+    selector,
+    statefulset::State,
+    store::Store,
+    Compare, Filter,
+};
+use crate::widget::table;
+
+// What the daemonset controller adds to every pod it creates, on top of the
+// tolerations in the template. `network-unavailable` is only added for pods
+// on the host network, which is close enough to always for agents.
+static DEFAULT_TOLERATIONS: &[(&str, &str)] = &[
+    ("node.kubernetes.io/not-ready", "NoExecute"),
+    ("node.kubernetes.io/unreachable", "NoExecute"),
+    ("node.kubernetes.io/disk-pressure", "NoSchedule"),
+    ("node.kubernetes.io/memory-pressure", "NoSchedule"),
+    ("node.kubernetes.io/pid-pressure", "NoSchedule"),
+    ("node.kubernetes.io/unschedulable", "NoSchedule"),
+    ("node.kubernetes.io/network-unavailable", "NoSchedule"),
+];
+
+fn tolerates(tolerations: &[Toleration], taint: &Taint) -> bool {
+    if DEFAULT_TOLERATIONS
+        .iter()
+        .any(|(key, effect)| *key == taint.key && *effect == taint.effect)
+    {
+        return true;
+    }
+
+    tolerations.iter().any(|toleration| {
+        let effect = toleration
+            .effect
+            .as_ref()
+            .map_or(true, |effect| effect.is_empty() || *effect == taint.effect);
+
+        let key = match toleration.key.as_deref() {
+            None | Some("") => toleration.operator.as_deref() == Some("Exists"),
+            Some(key) => key == taint.key,
+        };
+
+        let value = match toleration.operator.as_deref() {
+            Some("Exists") => true,
+            _ => {
+                toleration.value.as_deref().unwrap_or_default()
+                    == taint.value.as_deref().unwrap_or_default()
+            }
+        };
+
+        effect && key && value
+    })
+}
+
+/// Why the daemonset won't run a pod on `node`, `None` when it should. This
+/// is the same check the controller makes: the template's node selector and
+/// required node affinity, and taints that would keep the pod off of it.
+fn ineligible(spec: Option<&PodSpec>, node: &Node) -> Option<String> {
+    let spec = spec?;
+    let labels = node.labels();
+
+    if let Some((key, value)) = spec
+        .node_selector
+        .iter()
+        .flatten()
+        .find(|(key, value)| labels.get(*key) != Some(value))
+    {
+        return Some(format!("nodeSelector {key}={value} doesn't match"));
+    }
+
+    let required = spec
+        .affinity
+        .as_ref()
+        .and_then(|affinity| affinity.node_affinity.as_ref())
+        .and_then(|affinity| {
+            affinity
+                .required_during_scheduling_ignored_during_execution
+                .as_ref()
+        });
+
+    if let Some(required) = required {
+        if !required
+            .node_selector_terms
+            .iter()
+            .any(|term| selector::matches_term(term, &node.name_any(), labels))
+        {
+            return Some("required nodeAffinity doesn't match".to_string());
+        }
+    }
+
+    let tolerations = spec.tolerations.as_deref().unwrap_or_default();
+
+    node.spec
+        .as_ref()
+        .and_then(|spec| spec.taints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter(|taint| taint.effect == "NoSchedule" || taint.effect == "NoExecute")
+        .find(|taint| !tolerates(tolerations, taint))
+        .map(|taint| {
+            format!(
+                "taint {}{}:{} isn't tolerated",
+                taint.key,
+                taint
+                    .value
+                    .as_ref()
+                    .filter(|value| !value.is_empty())
+                    .map(|value| format!("={value}"))
+                    .unwrap_or_default(),
+                taint.effect
+            )
+        })
+}
+
+/// The node a daemonset pod is for. Pods that haven't been scheduled yet only
+/// have it in the node affinity the controller pins them with.
+fn target(pod: &Pod) -> Option<String> {
+    let spec = pod.spec.as_ref()?;
+
+    if let Some(node) = spec.node_name.as_ref() {
+        return Some(node.clone());
+    }
+
+    spec.affinity
+        .as_ref()?
+        .node_affinity
+        .as_ref()?
+        .required_during_scheduling_ignored_during_execution
+        .as_ref()?
+        .node_selector_terms
+        .iter()
+        .flat_map(|term| term.match_fields.iter().flatten())
+        .find(|field| field.key == "metadata.name")?
+        .values
+        .as_ref()?
+        .first()
+        .cloned()
+}
+
+// The scheduler leaves why it couldn't place a pod on its `PodScheduled`
+// condition.
+fn unscheduled(pod: &Pod) -> Option<String> {
+    pod.status
+        .as_ref()?
+        .conditions
+        .as_ref()?
+        .iter()
+        .find(|condition| condition.type_ == "PodScheduled" && condition.status == "False")
+        .and_then(|condition| condition.message.clone().or(condition.reason.clone()))
+}
+
+/// What is going on with the daemonset on a single node.
+pub struct Placement {
+    pub node: Arc<Node>,
+    pub pod: Option<Arc<Pod>>,
+    /// `None` when the node isn't one the daemonset runs on.
+    pub state: Option<State>,
+    pub reason: Option<String>,
+}
+
+impl Placement {
+    /// Whether the node needs a look, either its pod isn't ready or it's on a
+    /// node it shouldn't be.
+    pub fn is_problem(&self) -> bool {
+        match self.state {
+            None => false,
+            Some(State::Ready) => self.reason.is_some(),
+            Some(_) => true,
+        }
+    }
+}
+
+impl table::Row for Placement {
+    fn columns() -> Vec<&'static str> {
+        vec!["Node", "Pod", "Status", "Restarts", "Reason", "Age"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let pod = self.pod.as_ref();
+
+        vec![
+            self.node.name_any(),
+            pod.map(|pod| pod.name_any()).unwrap_or_default(),
+            self.state
+                .as_ref()
+                .map_or("Skipped".to_string(), ToString::to_string),
+            pod.map(|pod| pod.restarts()).unwrap_or_default(),
+            self.reason.clone().unwrap_or_default(),
+            pod.map(|pod| pod.age().to_age()).unwrap_or_default(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        let color = match self.state {
+            Some(State::Ready) => style.healthy,
+            None | Some(State::Pending | State::NotReady | State::Terminating) => style.normal,
+            Some(State::Missing | State::Failed(_)) => style.unhealthy,
+        };
+
+        table::highlight(self, &["Status"], color)
+    }
+
+    fn uid(&self) -> Option<String> {
+        Some(self.node.name_any())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        self.pod.as_ref().map(|pod| pod.as_ref().meta())
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait DaemonSetExt {
+    fn age(&self) -> TimeDelta;
+    fn owns(&self, pod: &Pod) -> bool;
+    fn placements(&self, pods: &[&Arc<Pod>], nodes: &[Arc<Node>]) -> Vec<Placement>;
+    fn health(&self, pods: &[&Arc<Pod>]) -> Health;
+}
+
+impl DaemonSetExt for DaemonSet {
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+
+    fn owns(&self, pod: &Pod) -> bool {
+        let Some(uid) = self.metadata.uid.as_ref() else {
+            return false;
+        };
+
+        pod.owner_references()
+            .iter()
+            .any(|owner| owner.controller == Some(true) && &owner.uid == uid)
+    }
+
+    /// Every node, along with the pod the daemonset has there or why there
+    /// isn't one. Nodes with something wrong come first.
+    fn placements(&self, pods: &[&Arc<Pod>], nodes: &[Arc<Node>]) -> Vec<Placement> {
+        let template = self
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.template.spec.as_ref());
+
+        let mut by_node: HashMap<String, Arc<Pod>> = pods
+            .iter()
+            .filter_map(|pod| Some((target(pod)?, (*pod).clone())))
+            .collect();
+
+        let mut placements: Vec<_> = nodes
+            .iter()
+            .map(|node| {
+                let pod = by_node.remove(&node.name_any());
+                let ineligible = ineligible(template, node);
+
+                let (state, reason) = match (pod.as_deref(), ineligible) {
+                    (None, Some(reason)) => (None, Some(reason)),
+                    (None, None) => (Some(State::Missing), Some("no pod yet".to_string())),
+                    (Some(pod), ineligible) => {
+                        let state = State::of(pod);
+
+                        let reason = match (&state, ineligible) {
+                            (State::Pending, _) => unscheduled(pod),
+                            (_, Some(reason)) => {
+                                Some(format!("shouldn't be on this node anymore, {reason}"))
+                            }
+                            _ => None,
+                        };
+
+                        (Some(state), reason)
+                    }
+                };
+
+                Placement {
+                    node: node.clone(),
+                    pod,
+                    state,
+                    reason,
+                }
+            })
+            .collect();
+
+        placements.sort_by_key(|placement| !placement.is_problem());
+
+        placements
+    }
+
+    fn health(&self, pods: &[&Arc<Pod>]) -> Health {
+        let status = kstatus::of("apps", "DaemonSet", self).unwrap_or(Status::Current);
+
+        if let Status::Failed(reason) = status {
+            return Health::Failed(reason);
+        }
+
+        if let Some(reason) = pods.iter().find_map(|pod| failing(pod)) {
+            return Health::Failed(reason);
+        }
+
+        let Status::InProgress(reason) = status else {
+            return Health::Healthy;
+        };
+
+        let ds = self.status.clone().unwrap_or_default();
+
+        let rolling = ds.observed_generation < self.metadata.generation
+            || ds.updated_number_scheduled.unwrap_or_default() < ds.desired_number_scheduled;
+
+        if rolling {
+            return Health::Progressing;
+        }
+
+        Health::Degraded(reason)
+    }
+}
+
+/// A daemonset along with its health, which depends on the pods it owns.
+pub struct Workload {
+    pub daemonset: Arc<DaemonSet>,
+    pub health: Health,
+}
+
+impl table::Row for Workload {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Namespace",
+            "Name",
+            "Health",
+            "Desired",
+            "Current",
+            "Ready",
+            "Up-to-date",
+            "Available",
+            "Age",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let status = self.daemonset.status.clone().unwrap_or_default();
+
+        vec![
+            self.daemonset.namespace().unwrap_or_default(),
+            self.daemonset.name_any(),
+            self.health.indicator().label(&self.health),
+            status.desired_number_scheduled.to_string(),
+            status.current_number_scheduled.to_string(),
+            status.number_ready.to_string(),
+            status
+                .updated_number_scheduled
+                .unwrap_or_default()
+                .to_string(),
+            status.number_available.unwrap_or_default().to_string(),
+            self.daemonset.age().to_age(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        let health = match self.health {
+            Health::Healthy => style.healthy,
+            Health::Progressing => style.normal,
+            Health::Degraded(_) | Health::Failed(_) => style.unhealthy,
+        };
+
+        table::highlight(self, &["Health"], health)
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.daemonset.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.daemonset.as_ref().meta())
+    }
+}
+
+impl Filter for DaemonSet {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<DaemonSet> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.namespace()
+            .cmp(&other.namespace())
+            .then_with(|| self.name_any().cmp(&other.name_any()))
+    }
+}
+
+/// Daemonsets joined with the pods they own for health, and the nodes for
+/// where each one should be running.
+pub struct DaemonSets {
+    pub daemonsets: Arc<Store<DaemonSet>>,
+    pub pods: Arc<Store<Pod>>,
+    pub nodes: Arc<Store<Node>>,
+}
+
+impl DaemonSets {
+    fn workload(daemonset: Arc<DaemonSet>, pods: &[&Arc<Pod>]) -> Workload {
+        let owned: Vec<_> = pods
+            .iter()
+            .filter(|pod| daemonset.owns(pod))
+            .copied()
+            .collect();
+
+        Workload {
+            health: daemonset.health(&owned),
+            daemonset,
+        }
+    }
+
+    /// Where the daemonset with `uid` is and isn't running right now.
+    pub fn placements(&self, uid: &str) -> Vec<Placement> {
+        let Some(daemonset) = self
+            .daemonsets
+            .snapshot()
+            .iter()
+            .find(|ds| ds.metadata.uid.as_deref() == Some(uid))
+            .cloned()
+        else {
+            return Vec::new();
+        };
+
+        let pods = self.pods.snapshot();
+        let owned: Vec<_> = pods.iter().filter(|pod| daemonset.owns(pod)).collect();
+
+        daemonset.placements(&owned, &self.nodes.snapshot())
+    }
+}
+
+impl table::Items for Arc<DaemonSets> {
+    type Item = Workload;
+
+    fn error(&self) -> Option<String> {
+        self.daemonsets.error()
+    }
+
+    fn retry(&self) {
+        self.daemonsets.retry();
+    }
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let pods = self.pods.snapshot();
+
+        let mut by_ns: HashMap<String, Vec<&Arc<Pod>>> = HashMap::new();
+        for pod in pods.iter() {
+            by_ns
+                .entry(pod.namespace().unwrap_or_default())
+                .or_default()
+                .push(pod);
+        }
+
+        self.daemonsets
+            .items(filter)
+            .map(|daemonset| {
+                let pods = by_ns
+                    .get(&daemonset.namespace().unwrap_or_default())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+
+                DaemonSets::workload(daemonset, pods)
+            })
+            .collect()
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(table::revision([
+            self.daemonsets.generation(),
+            self.pods.generation(),
+        ]))
+    }
+}
+
+/// Every node and what a single daemonset is doing on it, kept up to date as
+/// pods and nodes change.
+pub struct Placements {
+    pub daemonsets: Arc<DaemonSets>,
+    pub uid: String,
+}
+
+impl table::Items for Placements {
+    type Item = Placement;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.daemonsets.placements(&self.uid)
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(table::revision([
+            self.daemonsets.daemonsets.generation(),
+            self.daemonsets.pods.generation(),
+            self.daemonsets.nodes.generation(),
+        ]))
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::{
+    api::{
+        apps::v1::Deployment,
+        core::v1::{Node, Pod},
+    },
+    apimachinery::pkg::apis::meta::v1::LabelSelector,
+};
+use kube::{api::ObjectMeta, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{
+    age::Age,
+    cost,
+    kstatus::{self, Status, FAILING_REASONS},
+    selector,
+    store::Store,
+    Compare, Filter,
+};
+use crate::widget::{table, theme::Indicator};
+
+/// Roll-up of everything that says whether a deployment is doing what it
+/// should, from worst to best.
+#[derive(Debug, Clone)]
+pub enum Health {
+    /// The rollout has given up or pods are stuck in a way that won't fix
+    /// itself.
+    Failed(String),
+    /// Fewer pods are available than have been asked for.
+    Degraded(String),
+    /// A rollout is in progress.
+    Progressing,
+    Healthy,
+}
+
+impl std::fmt::Display for Health {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Health::Failed(reason) => write!(f, "Failed ({reason})"),
+            Health::Degraded(reason) => write!(f, "Degraded ({reason})"),
+            Health::Progressing => write!(f, "Progressing"),
+            Health::Healthy => write!(f, "Healthy"),
+        }
+    }
+}
+
+impl Health {
+    pub fn indicator(&self) -> Indicator {
+        match self {
+            Health::Failed(_) => Indicator::Unhealthy,
+            Health::Degraded(_) => Indicator::Warning,
+            Health::Progressing => Indicator::Progressing,
+            Health::Healthy => Indicator::Healthy,
+        }
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait DeploymentExt {
+    fn age(&self) -> TimeDelta;
+    fn desired(&self) -> i32;
+    fn selects(&self, pod: &Pod) -> bool;
+    fn health(&self, pods: &[&Arc<Pod>]) -> Health;
+}
+
+impl DeploymentExt for Deployment {
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+
+    fn desired(&self) -> i32 {
+        self.spec
+            .as_ref()
+            .and_then(|spec| spec.replicas)
+            .unwrap_or(1)
+    }
+
+    fn selects(&self, pod: &Pod) -> bool {
+        if self.namespace() != pod.namespace() {
+            return false;
+        }
+
+        self.spec
+            .as_ref()
+            .is_some_and(|spec| matches_selector(&spec.selector, pod.labels()))
+    }
+
+    fn health(&self, pods: &[&Arc<Pod>]) -> Health {
+        let status = kstatus::of("apps", "Deployment", self).unwrap_or(Status::Current);
+
+        if let Status::Failed(reason) = status {
+            return Health::Failed(reason);
+        }
+
+        if let Some(reason) = pods.iter().find_map(|pod| failing(pod)) {
+            return Health::Failed(reason);
+        }
+
+        let Status::InProgress(reason) = status else {
+            return Health::Healthy;
+        };
+
+        let deployment = self.status.clone().unwrap_or_default();
+        let desired = self.desired();
+
+        // Pods going away and coming back is what a rollout looks like, it is only
+        // degraded once the rollout has stopped and there still aren't enough.
+        let rolling = deployment.observed_generation < self.metadata.generation
+            || deployment.updated_replicas.unwrap_or_default() < desired
+            || deployment.replicas.unwrap_or_default() > desired;
+
+        if rolling {
+            return Health::Progressing;
+        }
+
+        Health::Degraded(reason)
+    }
+}
+
+fn matches_selector(selector: &LabelSelector, labels: &BTreeMap<String, String>) -> bool {
+    // An empty selector matches everything, which the API server doesn't allow for
+    // deployments. Treat it as matching nothing instead of claiming every pod.
+    if selector
+        .match_labels
+        .as_ref()
+        .map_or(true, BTreeMap::is_empty)
+        && selector
+            .match_expressions
+            .as_ref()
+            .map_or(true, Vec::is_empty)
+    {
+        return false;
+    }
+
+    selector::matches(selector, labels)
+}
+
+/// Why a pod is stuck in a way that won't fix itself, if it is.
+pub fn failing(pod: &Pod) -> Option<String> {
+    let status = pod.status.as_ref()?;
+
+    if status.phase.as_deref() == Some("Failed") {
+        return Some(
+            status
+                .reason
+                .clone()
+                .unwrap_or_else(|| "pod failed".to_string()),
+        );
+    }
+
+    status
+        .init_container_statuses
+        .iter()
+        .flatten()
+        .chain(status.container_statuses.iter().flatten())
+        .find_map(|c| {
+            let reason = c.state.as_ref()?.waiting.as_ref()?.reason.as_ref()?;
+
+            FAILING_REASONS
+                .contains(&reason.as_str())
+                .then(|| reason.clone())
+        })
+}
+
+/// A deployment along with its health, which depends on the pods it owns and
+/// can't be worked out from the deployment alone.
+pub struct Workload {
+    pub deployment: Arc<Deployment>,
+    pub health: Health,
+    /// Monthly estimate from what its pods request, when there are prices for
+    /// nodes in the config.
+    pub cost: Option<f64>,
+}
+
+impl table::Row for Workload {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Namespace",
+            "Name",
+            "Health",
+            "Ready",
+            "Up-to-date",
+            "Available",
+            "Age",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let status = self.deployment.status.clone().unwrap_or_default();
+
+        let mut cells = vec![
+            self.deployment.namespace().unwrap_or_default(),
+            self.deployment.name_any(),
+            self.health.indicator().label(&self.health),
+            format!(
+                "{}/{}",
+                status.ready_replicas.unwrap_or_default(),
+                self.deployment.desired()
+            ),
+            status.updated_replicas.unwrap_or_default().to_string(),
+            status.available_replicas.unwrap_or_default().to_string(),
+        ];
+
+        cells.extend(self.cost.map(cost::format));
+        cells.push(self.deployment.age().to_age());
+
+        cells
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        let health = match self.health {
+            Health::Healthy => style.healthy,
+            Health::Progressing => style.normal,
+            Health::Degraded(_) | Health::Failed(_) => style.unhealthy,
+        };
+
+        table::highlight(self, &["Health"], health)
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.deployment.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.deployment.as_ref().meta())
+    }
+}
+
+impl Filter for Deployment {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<Deployment> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = self
+            .namespace()
+            .unwrap_or_default()
+            .cmp(&other.namespace().unwrap_or_default());
+
+        if lhs != Ordering::Equal {
+            return lhs;
+        }
+
+        self.name_any().cmp(&other.name_any())
+    }
+}
+
+/// Deployments joined with the pods they select so that each row can show a
+/// health roll-up. Nodes are only watched when there are prices to estimate
+/// costs with.
+#[allow(clippy::struct_field_names)]
+pub struct Deployments {
+    pub deployments: Arc<Store<Deployment>>,
+    pub pods: Arc<Store<Pod>>,
+    pub nodes: Option<Arc<Store<Node>>>,
+}
+
+impl table::Items for Arc<Deployments> {
+    type Item = Workload;
+
+    fn error(&self) -> Option<String> {
+        self.deployments.error()
+    }
+
+    fn retry(&self) {
+        self.deployments.retry();
+    }
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let pods = self.pods.snapshot();
+        let nodes = self
+            .nodes
+            .as_ref()
+            .map(|nodes| cost::by_name(&nodes.snapshot()));
+
+        // Matching every pod against every deployment gets expensive quickly on
+        // large clusters, only look at the pods in the same namespace.
+        let mut by_ns: HashMap<String, Vec<&Arc<Pod>>> = HashMap::new();
+        for pod in pods.iter() {
+            by_ns
+                .entry(pod.namespace().unwrap_or_default())
+                .or_default()
+                .push(pod);
+        }
+
+        self.deployments
+            .items(filter)
+            .map(|deployment| {
+                let owned: Vec<_> = by_ns
+                    .get(&deployment.namespace().unwrap_or_default())
+                    .into_iter()
+                    .flatten()
+                    .filter(|pod| deployment.selects(pod))
+                    .copied()
+                    .collect();
+
+                Workload {
+                    health: deployment.health(&owned),
+                    cost: nodes
+                        .as_ref()
+                        .map(|nodes| cost::pods(owned.iter().copied(), nodes)),
+                    deployment,
+                }
+            })
+            .collect()
+    }
+
+    // The cost column goes right before the age, when there is one.
+    fn columns(&self) -> Vec<&'static str> {
+        let mut columns = <Workload as table::Row>::columns();
+
+        if self.nodes.is_some() {
+            columns.insert(columns.len() - 1, "Cost/mo");
+        }
+
+        columns
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(table::revision([
+            self.deployments.generation(),
+            self.pods.generation(),
+            self.nodes.as_ref().map_or(0, |nodes| nodes.generation()),
+        ]))
+    }
+}
+
+impl Workload {
+    /// Whether the rollout has stalled, either by going past its
+    /// `progressDeadlineSeconds` or by finishing without enough available
+    /// replicas. These don't fix themselves and are easy to miss in a long
+    /// list.
+    pub fn needs_attention(&self) -> bool {
+        matches!(self.health, Health::Failed(_) | Health::Degraded(_))
+    }
+}
+
+/// The deployments that need someone to look at them, failed ones first.
+pub struct Attention(pub Arc<Deployments>);
+
+impl table::Items for Arc<Attention> {
+    type Item = Workload;
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let mut items: Vec<_> = self
+            .0
+            .items(filter)
+            .into_iter()
+            .filter(Workload::needs_attention)
+            .collect();
+
+        items.sort_by_key(|item| !matches!(item.health, Health::Failed(_)));
+
+        items
+    }
+
+    fn columns(&self) -> Vec<&'static str> {
+        self.0.columns()
+    }
+
+    fn revision(&self) -> Option<u64> {
+        self.0.revision()
+    }
+}
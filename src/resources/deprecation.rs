@@ -0,0 +1,629 @@
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, OnceLock},
+};
+
+use eyre::Result;
+use kube::api::{Api as KubeApi, ApiResource, DynamicObject, ListParams, ObjectMeta};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use serde_json::Value;
+
+use crate::{policy::Scope, throttle::THROTTLE};
+
+lazy_static! {
+    static ref DEPRECATED_OBJECTS: IntCounterVec = register_int_counter_vec!(
+        "deprecated_api_objects_total",
+        "Number of objects found using an API version that is deprecated or removed, by API \
+         version",
+        &["api_version"]
+    )
+    .unwrap();
+}
+
+static LAST_APPLIED: &str = "kubectl.kubernetes.io/last-applied-configuration";
+
+/// An API version that is going away, or already has. Releases are the minor
+/// version of `1.x`, which is all there has ever been.
+#[derive(Debug)]
+pub struct Api {
+    /// Group and version, as in `apiVersion`.
+    pub version: &'static str,
+    pub kinds: &'static [&'static str],
+    pub deprecated: u32,
+    pub removed: u32,
+    /// What to move to, `None` when the kind is gone altogether.
+    pub replacement: Option<&'static str>,
+}
+
+// From the Kubernetes deprecated API migration guide. Kinds that are only ever
+// created by the API server itself, such as `TokenReview` or `Event`, are left
+// out as there's nothing a manifest could be updated for.
+static APIS: &[Api] = &[
+    Api {
+        version: "extensions/v1beta1",
+        kinds: &["DaemonSet", "Deployment", "ReplicaSet"],
+        deprecated: 9,
+        removed: 16,
+        replacement: Some("apps/v1"),
+    },
+    Api {
+        version: "apps/v1beta1",
+        kinds: &["Deployment", "StatefulSet"],
+        deprecated: 9,
+        removed: 16,
+        replacement: Some("apps/v1"),
+    },
+    Api {
+        version: "apps/v1beta2",
+        kinds: &["DaemonSet", "Deployment", "ReplicaSet", "StatefulSet"],
+        deprecated: 9,
+        removed: 16,
+        replacement: Some("apps/v1"),
+    },
+    Api {
+        version: "extensions/v1beta1",
+        kinds: &["NetworkPolicy"],
+        deprecated: 9,
+        removed: 16,
+        replacement: Some("networking.k8s.io/v1"),
+    },
+    Api {
+        version: "extensions/v1beta1",
+        kinds: &["Ingress"],
+        deprecated: 14,
+        removed: 22,
+        replacement: Some("networking.k8s.io/v1"),
+    },
+    Api {
+        version: "networking.k8s.io/v1beta1",
+        kinds: &["Ingress", "IngressClass"],
+        deprecated: 19,
+        removed: 22,
+        replacement: Some("networking.k8s.io/v1"),
+    },
+    Api {
+        version: "admissionregistration.k8s.io/v1beta1",
+        kinds: &[
+            "MutatingWebhookConfiguration",
+            "ValidatingWebhookConfiguration",
+        ],
+        deprecated: 16,
+        removed: 22,
+        replacement: Some("admissionregistration.k8s.io/v1"),
+    },
+    Api {
+        version: "apiextensions.k8s.io/v1beta1",
+        kinds: &["CustomResourceDefinition"],
+        deprecated: 16,
+        removed: 22,
+        replacement: Some("apiextensions.k8s.io/v1"),
+    },
+    Api {
+        version: "apiregistration.k8s.io/v1beta1",
+        kinds: &["APIService"],
+        deprecated: 19,
+        removed: 22,
+        replacement: Some("apiregistration.k8s.io/v1"),
+    },
+    Api {
+        version: "certificates.k8s.io/v1beta1",
+        kinds: &["CertificateSigningRequest"],
+        deprecated: 19,
+        removed: 22,
+        replacement: Some("certificates.k8s.io/v1"),
+    },
+    Api {
+        version: "coordination.k8s.io/v1beta1",
+        kinds: &["Lease"],
+        deprecated: 19,
+        removed: 22,
+        replacement: Some("coordination.k8s.io/v1"),
+    },
+    Api {
+        version: "rbac.authorization.k8s.io/v1beta1",
+        kinds: &["ClusterRole", "ClusterRoleBinding", "Role", "RoleBinding"],
+        deprecated: 17,
+        removed: 22,
+        replacement: Some("rbac.authorization.k8s.io/v1"),
+    },
+    Api {
+        version: "scheduling.k8s.io/v1beta1",
+        kinds: &["PriorityClass"],
+        deprecated: 14,
+        removed: 22,
+        replacement: Some("scheduling.k8s.io/v1"),
+    },
+    Api {
+        version: "storage.k8s.io/v1beta1",
+        kinds: &["CSIDriver", "CSINode", "StorageClass", "VolumeAttachment"],
+        deprecated: 19,
+        removed: 22,
+        replacement: Some("storage.k8s.io/v1"),
+    },
+    Api {
+        version: "batch/v1beta1",
+        kinds: &["CronJob"],
+        deprecated: 21,
+        removed: 25,
+        replacement: Some("batch/v1"),
+    },
+    Api {
+        version: "discovery.k8s.io/v1beta1",
+        kinds: &["EndpointSlice"],
+        deprecated: 21,
+        removed: 25,
+        replacement: Some("discovery.k8s.io/v1"),
+    },
+    Api {
+        version: "autoscaling/v2beta1",
+        kinds: &["HorizontalPodAutoscaler"],
+        deprecated: 22,
+        removed: 25,
+        replacement: Some("autoscaling/v2"),
+    },
+    Api {
+        version: "policy/v1beta1",
+        kinds: &["PodDisruptionBudget"],
+        deprecated: 21,
+        removed: 25,
+        replacement: Some("policy/v1"),
+    },
+    Api {
+        version: "policy/v1beta1",
+        kinds: &["PodSecurityPolicy"],
+        deprecated: 21,
+        removed: 25,
+        replacement: None,
+    },
+    Api {
+        version: "node.k8s.io/v1beta1",
+        kinds: &["RuntimeClass"],
+        deprecated: 20,
+        removed: 25,
+        replacement: Some("node.k8s.io/v1"),
+    },
+    Api {
+        version: "autoscaling/v2beta2",
+        kinds: &["HorizontalPodAutoscaler"],
+        deprecated: 23,
+        removed: 26,
+        replacement: Some("autoscaling/v2"),
+    },
+    Api {
+        version: "flowcontrol.apiserver.k8s.io/v1beta1",
+        kinds: &["FlowSchema", "PriorityLevelConfiguration"],
+        deprecated: 23,
+        removed: 26,
+        replacement: Some("flowcontrol.apiserver.k8s.io/v1"),
+    },
+    Api {
+        version: "storage.k8s.io/v1beta1",
+        kinds: &["CSIStorageCapacity"],
+        deprecated: 24,
+        removed: 27,
+        replacement: Some("storage.k8s.io/v1"),
+    },
+    Api {
+        version: "flowcontrol.apiserver.k8s.io/v1beta2",
+        kinds: &["FlowSchema", "PriorityLevelConfiguration"],
+        deprecated: 26,
+        removed: 29,
+        replacement: Some("flowcontrol.apiserver.k8s.io/v1"),
+    },
+    Api {
+        version: "flowcontrol.apiserver.k8s.io/v1beta3",
+        kinds: &["FlowSchema", "PriorityLevelConfiguration"],
+        deprecated: 29,
+        removed: 32,
+        replacement: Some("flowcontrol.apiserver.k8s.io/v1"),
+    },
+];
+
+fn lookup(api_version: &str, kind: &str) -> Option<&'static Api> {
+    APIS.iter()
+        .find(|api| api.version == api_version && api.kinds.contains(&kind))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Removed from the server that is running, so it can't be used anymore.
+    Removed,
+    Deprecated,
+    /// Still fine on the server that is running, but not on a later one.
+    Upcoming,
+}
+
+impl Api {
+    /// Where things stand on a server running `1.{minor}`. Until the version
+    /// is known, everything counts as deprecated.
+    pub fn severity(&self, minor: Option<u32>) -> Severity {
+        match minor {
+            Some(minor) if minor >= self.removed => Severity::Removed,
+            Some(minor) if minor < self.deprecated => Severity::Upcoming,
+            _ => Severity::Deprecated,
+        }
+    }
+}
+
+/// An object that is using an API version in `APIS`.
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub api: &'static Api,
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    /// Only how the object was last applied uses the API version, the server
+    /// serves it with another. The manifest it came from needs updating before
+    /// the upgrade.
+    pub applied: bool,
+}
+
+impl Finding {
+    pub fn id(&self) -> String {
+        self.namespace.as_ref().map_or_else(
+            || format!("{} {}", self.kind, self.name),
+            |ns| format!("{} {ns}/{}", self.kind, self.name),
+        )
+    }
+
+    pub fn message(&self, minor: Option<u32>) -> String {
+        let how = if self.applied {
+            "last applied as"
+        } else {
+            "served as"
+        };
+
+        let status = match self.api.severity(minor) {
+            Severity::Removed => format!("was removed in 1.{}", self.api.removed),
+            Severity::Deprecated => {
+                format!("is deprecated and goes away in 1.{}", self.api.removed)
+            }
+            Severity::Upcoming => format!(
+                "is deprecated in 1.{} and goes away in 1.{}",
+                self.api.deprecated, self.api.removed
+            ),
+        };
+
+        let next = self.api.replacement.map_or_else(
+            || "there's no replacement".to_string(),
+            |replacement| format!("use {replacement} instead"),
+        );
+
+        format!(
+            "{how} {} {}, which {status}: {next}",
+            self.api.version, self.kind
+        )
+    }
+}
+
+impl PartialEq for Finding {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Finding {}
+
+impl PartialOrd for Finding {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Whatever is closest to being removed goes first.
+impl Ord for Finding {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.api
+            .removed
+            .cmp(&other.api.removed)
+            .then_with(|| self.id().cmp(&other.id()))
+    }
+}
+
+/// What uses an API version that is going away, out of the version the object
+/// is served as and the one it was last applied with. Objects that come from
+/// the typed API are always served as the current version, it is how they were
+/// applied that gives anything away.
+pub fn check(api_version: &str, kind: &str, metadata: &ObjectMeta) -> Vec<Finding> {
+    let finding = |api, applied| Finding {
+        api,
+        kind: kind.to_string(),
+        namespace: metadata.namespace.clone(),
+        name: metadata.name.clone().unwrap_or_default(),
+        applied,
+    };
+
+    if let Some(api) = lookup(api_version, kind) {
+        return vec![finding(api, false)];
+    }
+
+    metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(LAST_APPLIED))
+        .and_then(|applied| serde_json::from_str::<Value>(applied).ok())
+        .and_then(|applied| {
+            lookup(
+                applied.get("apiVersion")?.as_str()?,
+                applied.get("kind")?.as_str()?,
+            )
+        })
+        .map(|api| vec![finding(api, true)])
+        .unwrap_or_default()
+}
+
+/// `check` for an object that has been serialized, such as the one a YAML view
+/// is showing. Anything found is kept for the session's report.
+pub fn inspect(object: &Value) -> Vec<Finding> {
+    let (Some(api_version), Some(kind)) = (
+        object.get("apiVersion").and_then(Value::as_str),
+        object.get("kind").and_then(Value::as_str),
+    ) else {
+        return Vec::new();
+    };
+
+    let Some(metadata) = object
+        .get("metadata")
+        .and_then(|metadata| serde_json::from_value::<ObjectMeta>(metadata.clone()).ok())
+    else {
+        return Vec::new();
+    };
+
+    let findings = check(api_version, kind, &metadata);
+
+    SESSION.with_borrow_mut(|session| {
+        for finding in &findings {
+            session.seen.insert(finding.id(), finding.clone());
+        }
+    });
+
+    count(&findings);
+
+    findings
+}
+
+/// Add anything that hasn't been counted yet this session to the metric. The
+/// same object is found on every scan and every time it is looked at, which
+/// would otherwise make the count grow without anything new being deprecated.
+fn count(findings: &[Finding]) {
+    SESSION.with_borrow_mut(|session| {
+        for finding in findings {
+            if session.counted.insert(finding.id()) {
+                DEPRECATED_OBJECTS
+                    .with_label_values(&[finding.api.version])
+                    .inc();
+            }
+        }
+    });
+}
+
+#[derive(Default)]
+struct Session {
+    // Filled in once the API server has said what it is running.
+    minor: Arc<OnceLock<u32>>,
+    seen: BTreeMap<String, Finding>,
+    // By `Finding::id`, whether found by a scan or by looking at the object.
+    counted: BTreeSet<String>,
+}
+
+thread_local! {
+    // Like `prefetch::PREFETCHED`, every session's widgets are created on its
+    // own dashboard thread, which makes this per session.
+    static SESSION: RefCell<Session> = RefCell::default();
+}
+
+/// Everything that has been found using a deprecated API version while the
+/// session has been looking at objects.
+pub fn seen() -> Vec<Finding> {
+    SESSION.with_borrow(|session| session.seen.values().cloned().collect())
+}
+
+/// The minor version of the API server, if it is known yet.
+pub fn minor() -> Option<u32> {
+    SESSION.with_borrow(|session| session.minor.get().copied())
+}
+
+/// What is known about deprecations for a session. It is kept for as long as
+/// this is around, which is held by the top level of the dashboard.
+pub struct Tracked(());
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        SESSION.with_borrow_mut(|session| *session = Session::default());
+    }
+}
+
+/// Find out which version the API server is running, so that findings can say
+/// whether they have already been removed.
+pub fn start(client: &kube::Client) -> Tracked {
+    let minor = SESSION.with_borrow(|session| session.minor.clone());
+    let client = client.clone();
+
+    tokio::spawn(async move {
+        let info = match client.apiserver_version().await {
+            Ok(info) => info,
+            Err(err) => {
+                tracing::debug!("unable to get the server version: {err}");
+
+                return;
+            }
+        };
+
+        // Managed offerings like to add a `+` to the end.
+        let Ok(version) = info
+            .minor
+            .trim_end_matches(|c: char| !c.is_ascii_digit())
+            .parse()
+        else {
+            return;
+        };
+
+        minor.set(version).ok();
+    });
+
+    Tracked(())
+}
+
+/// Findings from going through the whole cluster.
+pub struct Scan {
+    pub findings: Vec<Finding>,
+    /// Kinds that couldn't be listed, along with why.
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Look through every object of the kinds in `APIS`, as served by `kinds`
+/// (from discovery). Only metadata is listed, as how an object was last
+/// applied is in its annotations. Objects in namespaces outside of `scope` are
+/// left out, like they are everywhere else.
+pub async fn scan(client: kube::Client, scope: Scope, kinds: Vec<ApiResource>) -> Result<Scan> {
+    let resources = kinds.into_iter().filter(|resource| {
+        APIS.iter().any(|api| {
+            api.kinds.contains(&resource.kind.as_str())
+                && [Some(api.version), api.replacement]
+                    .into_iter()
+                    .flatten()
+                    .any(|version| group(version) == resource.group)
+        })
+    });
+
+    let mut findings = Vec::new();
+    let mut skipped = Vec::new();
+
+    for resource in resources {
+        THROTTLE.wait().await;
+
+        let list = match KubeApi::<DynamicObject>::all_with(client.clone(), &resource)
+            .list_metadata(&ListParams::default())
+            .await
+        {
+            Ok(list) => list,
+            Err(err) => {
+                skipped.push((resource.plural.clone(), err.to_string()));
+
+                continue;
+            }
+        };
+
+        for object in list.items {
+            if object
+                .metadata
+                .namespace
+                .as_deref()
+                .is_some_and(|namespace| !scope.allows(namespace))
+            {
+                continue;
+            }
+
+            findings.extend(check(
+                &resource.api_version,
+                &resource.kind,
+                &object.metadata,
+            ));
+        }
+    }
+
+    count(&findings);
+
+    findings.sort();
+
+    Ok(Scan { findings, skipped })
+}
+
+fn group(api_version: &str) -> &str {
+    api_version.rsplit_once('/').map_or("", |(group, _)| group)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn metadata(annotations: Option<Value>) -> ObjectMeta {
+        ObjectMeta {
+            name: Some("web".to_string()),
+            namespace: Some("default".to_string()),
+            annotations: annotations
+                .map(|applied| BTreeMap::from([(LAST_APPLIED.to_string(), applied.to_string())])),
+            ..ObjectMeta::default()
+        }
+    }
+
+    #[test]
+    fn check_served() {
+        let findings = check("extensions/v1beta1", "Deployment", &metadata(None));
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].api.version, "extensions/v1beta1");
+        assert_eq!(findings[0].api.replacement, Some("apps/v1"));
+        assert_eq!(findings[0].id(), "Deployment default/web");
+        assert!(!findings[0].applied);
+    }
+
+    #[test]
+    fn check_kind_not_in_version() {
+        // `extensions/v1beta1` had ingresses and deployments, but never cronjobs.
+        assert!(check("extensions/v1beta1", "CronJob", &metadata(None)).is_empty());
+    }
+
+    #[test]
+    fn check_current() {
+        assert!(check("apps/v1", "Deployment", &metadata(None)).is_empty());
+    }
+
+    #[test]
+    fn check_last_applied() {
+        let applied = serde_json::json!({
+            "apiVersion": "policy/v1beta1",
+            "kind": "PodDisruptionBudget",
+        });
+
+        let findings = check("policy/v1", "PodDisruptionBudget", &metadata(Some(applied)));
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].api.version, "policy/v1beta1");
+        assert!(findings[0].applied);
+    }
+
+    #[test]
+    fn check_last_applied_current() {
+        let applied = serde_json::json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+        });
+
+        assert!(check("apps/v1", "Deployment", &metadata(Some(applied))).is_empty());
+    }
+
+    #[test]
+    fn check_last_applied_invalid() {
+        let mut metadata = metadata(None);
+        metadata.annotations = Some(BTreeMap::from([(
+            LAST_APPLIED.to_string(),
+            "not json".to_string(),
+        )]));
+
+        assert!(check("policy/v1", "PodDisruptionBudget", &metadata).is_empty());
+    }
+
+    #[test]
+    fn severity() {
+        let api = Api {
+            version: "batch/v1beta1",
+            kinds: &["CronJob"],
+            deprecated: 21,
+            removed: 25,
+            replacement: Some("batch/v1"),
+        };
+
+        assert_eq!(api.severity(None), Severity::Deprecated);
+        assert_eq!(api.severity(Some(20)), Severity::Upcoming);
+        assert_eq!(api.severity(Some(21)), Severity::Deprecated);
+        assert_eq!(api.severity(Some(24)), Severity::Deprecated);
+        assert_eq!(api.severity(Some(25)), Severity::Removed);
+        assert_eq!(api.severity(Some(30)), Severity::Removed);
+    }
+}
@@ -0,0 +1,526 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::Utc;
+use eyre::{eyre, Result, WrapErr};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use kube::{
+    api::{Api, ApiResource, DynamicObject, GroupVersionKind},
+    core::discovery::Scope,
+    discovery::pinned_kind,
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{
+    feed::{flatten, Field},
+    quantity, GetGvk,
+};
+use crate::{config, diagnostics, policy};
+
+lazy_static! {
+    static ref DRIFT_CHECKS: IntCounterVec = register_int_counter_vec!(
+        "drift_objects_total",
+        "Number of declared objects compared against the cluster, by what was found",
+        &["result"]
+    )
+    .unwrap();
+}
+
+static CLONE_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Filled in by the API server or controllers, a manifest that has them was
+// exported from a cluster and they say nothing about what was meant.
+static IGNORED: &[&str] = &[
+    "status",
+    "metadata.managedFields",
+    "metadata.resourceVersion",
+    "metadata.uid",
+    "metadata.creationTimestamp",
+    "metadata.generation",
+    "metadata.selfLink",
+    "metadata.annotations.kubectl.kubernetes.io/last-applied-configuration",
+];
+
+// Files in a repository that look like manifests but are for the tools that
+// build them.
+static TOOLING: &[&str] = &["kustomize.config.k8s.io"];
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum State {
+    /// The live object has something else for a declared field.
+    Differs,
+    Missing,
+    /// The object couldn't be looked up, such as when its kind doesn't exist.
+    Unknown(String),
+    InSync,
+}
+
+/// A declared object, along with how the live one compares.
+pub struct Object {
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub file: PathBuf,
+    pub state: State,
+    /// Declared fields that the live object disagrees with. `before` is what
+    /// was declared, `after` what is live.
+    pub fields: Vec<Field>,
+}
+
+impl Object {
+    pub fn id(&self) -> String {
+        self.namespace.as_ref().map_or_else(
+            || format!("{} {}", self.kind, self.name),
+            |ns| format!("{} {ns}/{}", self.kind, self.name),
+        )
+    }
+}
+
+pub struct Report {
+    /// Everything that was declared, anything out of sync first.
+    pub objects: Vec<Object>,
+    /// Files ending in `.yaml`, `.yml` or `.json` that couldn't be read as
+    /// manifests, such as Helm templates.
+    pub skipped: Vec<PathBuf>,
+}
+
+// What the objects from an upload say they were declared in.
+static UPLOAD: &str = "<upload>";
+
+/// What to compare the cluster against.
+#[derive(Clone)]
+pub enum Source {
+    /// The git repository or directory from the server's config.
+    Configured,
+    /// Manifests the user pasted in, as one or more YAML or JSON documents.
+    Upload(Arc<String>),
+}
+
+/// Compare everything declared in `source` against what is in the cluster
+/// right now. Only the fields that the manifests set are looked at, anything
+/// defaulted or added by controllers is left alone.
+pub async fn check(client: kube::Client, scope: policy::Scope, source: Source) -> Result<Report> {
+    let cfg = &config::get().drift;
+
+    let (manifests, skipped) = match (source, cfg.git.as_ref()) {
+        (Source::Upload(txt), _) => {
+            let manifests = documents(&txt).wrap_err("unable to read the upload as manifests")?;
+
+            (
+                manifests
+                    .into_iter()
+                    .map(|value| (PathBuf::from(UPLOAD), value))
+                    .collect(),
+                Vec::new(),
+            )
+        }
+        (Source::Configured, Some(url)) => {
+            let dir = std::env::temp_dir().join(format!(
+                "kty-drift-{}",
+                Utc::now().timestamp_nanos_opt().unwrap_or_default()
+            ));
+
+            let result = async {
+                clone(url, cfg.branch.as_deref(), &dir).await?;
+
+                load(dir.join(cfg.path.clone().unwrap_or_default())).await
+            }
+            .await;
+
+            tokio::fs::remove_dir_all(&dir).await.ok();
+
+            result?
+        }
+        (Source::Configured, None) => {
+            load(
+                cfg.path
+                    .clone()
+                    .ok_or_else(|| eyre!("no drift source is configured"))?,
+            )
+            .await?
+        }
+    };
+
+    let mut kinds = HashMap::new();
+    let mut objects = Vec::new();
+
+    for (file, manifest) in manifests {
+        let Some(object) = compare(client.clone(), &scope, &mut kinds, file, manifest).await else {
+            continue;
+        };
+
+        DRIFT_CHECKS
+            .with_label_values(&[match object.state {
+                State::Differs => "differs",
+                State::Missing => "missing",
+                State::Unknown(_) => "unknown",
+                State::InSync => "in_sync",
+            }])
+            .inc();
+
+        objects.push(object);
+    }
+
+    objects.sort_by(|a, b| a.state.cmp(&b.state).then_with(|| a.id().cmp(&b.id())));
+
+    Ok(Report { objects, skipped })
+}
+
+async fn clone(url: &str, branch: Option<&str>, dir: &Path) -> Result<()> {
+    let mut cmd = tokio::process::Command::new("git");
+
+    cmd.args(["clone", "--quiet", "--depth", "1"])
+        // Nobody is around to answer a prompt for credentials.
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .kill_on_drop(true);
+
+    if let Some(branch) = branch {
+        cmd.args(["--branch", branch]);
+    }
+
+    cmd.arg(url).arg(dir);
+
+    // Errors end up on screen and in the logs, credentials in the URL don't
+    // belong in either. `git` leaves them in what it writes out as well.
+    let url = diagnostics::redact(url);
+
+    let output = tokio::time::timeout(CLONE_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| {
+            eyre!(
+                "cloning {url} took longer than {}s",
+                CLONE_TIMEOUT.as_secs()
+            )
+        })?
+        .wrap_err("unable to run git, it needs to be installed next to the server")?;
+
+    if !output.status.success() {
+        return Err(eyre!(
+            "unable to clone {url}: {}",
+            diagnostics::redact(String::from_utf8_lossy(&output.stderr).trim())
+        ));
+    }
+
+    Ok(())
+}
+
+type Manifests = (Vec<(PathBuf, Value)>, Vec<PathBuf>);
+
+// Reading a repository's worth of files would hold up everything else on the
+// runtime, so it happens on a thread of its own.
+async fn load(dir: PathBuf) -> Result<Manifests> {
+    tokio::task::spawn_blocking(move || walk(&dir)).await?
+}
+
+// Symlinks are followed as long as they stay under `dir`, which is how
+// mounted `ConfigMap`s lay their files out. Anything pointing elsewhere is
+// skipped, as is a directory that has already been walked so that a link back
+// up doesn't go around forever.
+fn walk(dir: &Path) -> Result<Manifests> {
+    let root = dir
+        .canonicalize()
+        .wrap_err_with(|| format!("unable to read {}", dir.display()))?;
+
+    let mut manifests = Vec::new();
+    let mut skipped = Vec::new();
+    let mut visited = HashSet::from([root.clone()]);
+    let mut pending = vec![root.clone()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .wrap_err_with(|| format!("unable to read {}", dir.display()))?;
+
+        for entry in entries {
+            let path = entry?.path();
+
+            // `.git` and friends.
+            if path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            {
+                continue;
+            }
+
+            if std::fs::symlink_metadata(&path)?.is_symlink() {
+                let Some(target) = path
+                    .canonicalize()
+                    .ok()
+                    .filter(|target| target.starts_with(&root))
+                else {
+                    tracing::debug!(path = %path.display(), "skipping symlink outside of the manifests");
+
+                    continue;
+                };
+
+                if target.is_dir() {
+                    if visited.insert(target.clone()) {
+                        pending.push(target);
+                    }
+
+                    continue;
+                }
+            } else if path.is_dir() {
+                if visited.insert(path.clone()) {
+                    pending.push(path);
+                }
+
+                continue;
+            }
+
+            if !path
+                .extension()
+                .is_some_and(|ext| ext == "yaml" || ext == "yml" || ext == "json")
+            {
+                continue;
+            }
+
+            match parse(&path) {
+                Ok(values) => {
+                    manifests.extend(values.into_iter().map(|value| (path.clone(), value)));
+                }
+                Err(err) => {
+                    tracing::debug!(path = %path.display(), "skipping file: {err}");
+
+                    skipped.push(path);
+                }
+            }
+        }
+    }
+
+    manifests.sort_by(|(a, _), (b, _)| a.cmp(b));
+    skipped.sort();
+
+    Ok((manifests, skipped))
+}
+
+fn parse(path: &Path) -> Result<Vec<Value>> {
+    documents(&std::fs::read_to_string(path)?)
+}
+
+// Every document in `txt`, with `List`s flattened into their items. JSON is
+// YAML too, as far as the parser is concerned.
+fn documents(txt: &str) -> Result<Vec<Value>> {
+    let mut values = Vec::new();
+
+    for doc in serde_yaml::Deserializer::from_str(txt) {
+        let value = Value::deserialize(doc)?;
+
+        match value {
+            Value::Null => {}
+            Value::Object(ref map) if map.get("kind") == Some(&"List".into()) => {
+                values.extend(
+                    map.get("items")
+                        .and_then(Value::as_array)
+                        .cloned()
+                        .unwrap_or_default(),
+                );
+            }
+            value => values.push(value),
+        }
+    }
+
+    Ok(values)
+}
+
+type Kinds = HashMap<GroupVersionKind, Result<(ApiResource, bool), String>>;
+
+// `None` for files that belong to tooling rather than the cluster.
+async fn compare(
+    client: kube::Client,
+    scope: &policy::Scope,
+    kinds: &mut Kinds,
+    file: PathBuf,
+    manifest: Value,
+) -> Option<Object> {
+    let declared = serde_json::from_value::<DynamicObject>(manifest.clone());
+
+    let mut object = Object {
+        kind: declared
+            .as_ref()
+            .ok()
+            .and_then(|obj| obj.types.as_ref())
+            .map(|types| types.kind.clone())
+            .unwrap_or_default(),
+        namespace: declared.as_ref().ok().and_then(ResourceExt::namespace),
+        name: declared
+            .as_ref()
+            .map(ResourceExt::name_any)
+            .unwrap_or_default(),
+        file,
+        state: State::InSync,
+        fields: Vec::new(),
+    };
+
+    let gvk = declared.as_ref().ok().and_then(|obj| obj.gvk().ok());
+
+    if gvk
+        .as_ref()
+        .is_some_and(|gvk| TOOLING.contains(&gvk.group.as_str()))
+    {
+        return None;
+    }
+
+    let live = async {
+        let declared = declared?;
+        let gvk = gvk.ok_or_else(|| eyre!("missing apiVersion or kind"))?;
+
+        if !kinds.contains_key(&gvk) {
+            let found = pinned_kind(&client, &gvk)
+                .await
+                .map(|(resource, caps)| (resource, caps.scope == Scope::Namespaced))
+                .map_err(|_| format!("{} isn't served by the cluster", gvk.kind));
+
+            kinds.insert(gvk.clone(), found);
+        }
+
+        let (resource, namespaced) = kinds[&gvk].clone().map_err(|err| eyre!(err))?;
+
+        let api = if namespaced {
+            let namespace = declared
+                .namespace()
+                .unwrap_or_else(|| "default".to_string());
+
+            if !scope.allows(&namespace) {
+                return Err(eyre!(
+                    "{namespace} isn't one of the namespaces you can browse"
+                ));
+            }
+
+            Api::<DynamicObject>::namespaced_with(client, &namespace, &resource)
+        } else {
+            Api::<DynamicObject>::all_with(client, &resource)
+        };
+
+        Ok::<_, eyre::Report>(api.get_opt(&declared.name_any()).await?)
+    }
+    .await;
+
+    object.state = match live {
+        Ok(None) => State::Missing,
+        Ok(Some(live)) => {
+            object.fields = fields(
+                &manifest,
+                &serde_json::to_value(live).unwrap_or_default(),
+                object.kind == "Secret",
+            );
+
+            if object.fields.is_empty() {
+                State::InSync
+            } else {
+                State::Differs
+            }
+        }
+        Err(err) => State::Unknown(err.to_string()),
+    };
+
+    Some(object)
+}
+
+// Only what was declared is compared, the live object having more is fine.
+fn fields(declared: &Value, live: &Value, hidden: bool) -> Vec<Field> {
+    let (mut lhs, mut rhs) = (BTreeMap::new(), BTreeMap::new());
+
+    flatten("", declared, &mut lhs);
+    flatten("", live, &mut rhs);
+
+    lhs.into_iter()
+        .filter(|(path, _)| {
+            !IGNORED
+                .iter()
+                .any(|ignored| path == ignored || path.starts_with(&format!("{ignored}.")))
+        })
+        // Empty maps and lists don't declare anything.
+        .filter(|(_, value)| value != "{}" && value != "[]")
+        .filter_map(|(path, value)| {
+            let live = rhs.remove(&path);
+
+            if live.as_ref().is_some_and(|live| same(&value, live)) {
+                return None;
+            }
+
+            let hide = |value: String| {
+                if hidden {
+                    "<hidden>".to_string()
+                } else {
+                    value
+                }
+            };
+
+            Some(Field {
+                path,
+                before: Some(hide(value)),
+                after: live.map(hide),
+            })
+        })
+        .collect()
+}
+
+// Quantities come back from the API server in their canonical form, `0.5` cpu
+// is `500m` once it has been applied.
+fn same(declared: &str, live: &str) -> bool {
+    if declared == live {
+        return true;
+    }
+
+    let unquoted = |value: &str| Quantity(value.trim_matches('"').to_string());
+
+    match (
+        quantity::parse(&unquoted(declared)),
+        quantity::parse(&unquoted(live)),
+    ) {
+        (Some(declared), Some(live)) => (declared - live).abs() < f64::EPSILON,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walk_follows_symlinks_inside_only() {
+        let root = std::env::temp_dir().join(format!("kty-drift-test-{}", std::process::id()));
+        let outside = root.with_extension("outside");
+
+        std::fs::create_dir_all(root.join("apps")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+
+        std::fs::write(
+            root.join("apps/cm.yaml"),
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: a\n",
+        )
+        .unwrap();
+        std::fs::write(
+            outside.join("other.yaml"),
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: b\n",
+        )
+        .unwrap();
+
+        // A loop back up, a link to a file inside and one to outside.
+        std::os::unix::fs::symlink(&root, root.join("apps/loop")).unwrap();
+        std::os::unix::fs::symlink(root.join("apps/cm.yaml"), root.join("linked.yaml")).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("outside")).unwrap();
+
+        let result = walk(&root);
+
+        std::fs::remove_dir_all(&root).ok();
+        std::fs::remove_dir_all(&outside).ok();
+
+        let (manifests, skipped) = result.unwrap();
+        let names: Vec<_> = manifests
+            .iter()
+            .map(|(_, value)| value["metadata"]["name"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(names, vec!["a", "a"]);
+        assert!(skipped.is_empty());
+    }
+}
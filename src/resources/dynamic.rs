@@ -0,0 +1,334 @@
+use std::{cmp::Ordering, sync::Arc, time::Duration};
+
+use chrono::Utc;
+use eyre::Result;
+use kube::{
+    api::{ApiResource, DynamicObject, ObjectMeta},
+    core::GroupVersion,
+    discovery::{pinned_group, verbs},
+    runtime::watcher,
+    Resource, ResourceExt,
+};
+use ratatui::widgets::Row;
+use tokio::{sync::watch, task::JoinHandle};
+
+use super::{
+    age::Age,
+    kstatus::{self, Status},
+    store::Store,
+    Compare, Filter,
+};
+use crate::{
+    config::{Column, View},
+    throttle::THROTTLE,
+    widget::table::{self, Row as _},
+};
+
+// CRDs don't get installed particularly often, this is mostly here so that
+// people don't need to reconnect after installing an operator.
+static REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps a list of every kind that can be listed and watched. Discovery is
+/// re-run periodically so that kinds added by CRDs after the session starts
+/// show up without needing to reconnect.
+pub struct Discovery {
+    task: JoinHandle<()>,
+    kinds: watch::Receiver<Vec<ApiResource>>,
+}
+
+impl Discovery {
+    pub fn new(client: kube::Client) -> Self {
+        let (tx, kinds) = watch::channel(Vec::new());
+
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                THROTTLE.wait().await;
+
+                let latest = match discover(&client).await {
+                    Ok(latest) => latest,
+                    Err(err) => {
+                        tracing::debug!("unable to run discovery: {err}");
+
+                        continue;
+                    }
+                };
+
+                tx.send_if_modified(|current| {
+                    if *current == latest {
+                        return false;
+                    }
+
+                    *current = latest;
+
+                    true
+                });
+            }
+        });
+
+        Self { task, kinds }
+    }
+
+    pub fn kinds(&self) -> watch::Receiver<Vec<ApiResource>> {
+        self.kinds.clone()
+    }
+}
+
+impl Drop for Discovery {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+// `kube::Discovery` fails completely if any single group fails, which happens
+// regularly for aggregated APIs that aren't healthy (metrics-server being the
+// usual culprit). Instead, only the preferred version of each group is fetched
+// and groups that error are skipped.
+async fn discover(client: &kube::Client) -> Result<Vec<ApiResource>> {
+    let mut versions = vec![GroupVersion::gv("", "v1")];
+
+    versions.extend(
+        client
+            .list_api_groups()
+            .await?
+            .groups
+            .into_iter()
+            .filter_map(|group| group.preferred_version)
+            .filter_map(|version| version.group_version.parse().ok()),
+    );
+
+    let mut kinds = Vec::new();
+
+    for version in versions {
+        let group = match pinned_group(client, &version).await {
+            Ok(group) => group,
+            Err(err) => {
+                tracing::debug!(group = version.api_version(), "discovery failed: {err}");
+
+                continue;
+            }
+        };
+
+        kinds.extend(
+            group
+                .recommended_resources()
+                .into_iter()
+                .filter(|(_, caps)| {
+                    caps.supports_operation(verbs::LIST) && caps.supports_operation(verbs::WATCH)
+                })
+                .map(|(ar, _)| ar),
+        );
+    }
+
+    kinds.sort_by(|a, b| a.plural.cmp(&b.plural).then(a.group.cmp(&b.group)));
+
+    Ok(kinds)
+}
+
+impl Filter for ApiResource {
+    fn matches(&self, filter: &str) -> bool {
+        self.plural.contains(filter)
+            || self.kind.to_lowercase().contains(&filter.to_lowercase())
+            || self.group.contains(filter)
+    }
+}
+
+impl table::Row for ApiResource {
+    fn columns() -> Vec<&'static str> {
+        vec!["Name", "Group", "Version", "Kind"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.plural.clone(),
+            self.group.clone(),
+            self.version.clone(),
+            self.kind.clone(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        Some(format!("{}/{}", self.api_version, self.plural))
+    }
+}
+
+/// An object along with its status, which depends on the kind and can't be
+/// worked out from a `DynamicObject` alone.
+#[allow(clippy::struct_field_names)]
+pub struct Object {
+    pub object: Arc<DynamicObject>,
+    pub status: Option<Status>,
+    /// For objects listed as part of a view, which can have columns of its own.
+    pub view: Option<&'static View>,
+}
+
+impl Object {
+    fn value(&self, column: &Column) -> String {
+        let pointer = column.pointer();
+
+        let value = if pointer.starts_with("/metadata/") {
+            serde_json::to_value(&self.object.metadata)
+                .ok()
+                .and_then(|metadata| metadata.pointer(&pointer["/metadata".len()..]).cloned())
+        } else {
+            self.object.data.pointer(&pointer).cloned()
+        };
+
+        match value {
+            None | Some(serde_json::Value::Null) => "-".to_string(),
+            Some(serde_json::Value::String(value)) => value,
+            Some(value) => value.to_string(),
+        }
+    }
+}
+
+impl table::Row for Object {
+    fn columns() -> Vec<&'static str> {
+        vec!["Namespace", "Name", "Status", "Age"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        if let Some(view) = self.view.filter(|view| !view.columns.is_empty()) {
+            let mut cells = vec![
+                self.object.namespace().unwrap_or_default(),
+                self.object.name_any(),
+            ];
+            cells.extend(view.columns.iter().map(|column| self.value(column)));
+
+            return cells;
+        }
+
+        vec![
+            self.object.namespace().unwrap_or_default(),
+            self.object.name_any(),
+            self.status
+                .as_ref()
+                .map_or("-".to_string(), |status| status.indicator().label(status)),
+            self.object
+                .creation_timestamp()
+                .map_or("-".to_string(), |ts| (Utc::now() - ts.0).to_age()),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(
+            self.status
+                .as_ref()
+                .map_or(style.normal, |status| status.style(style)),
+        )
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.object.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.object.as_ref().meta())
+    }
+}
+
+/// Every object of a kind, with their status.
+pub struct Objects {
+    pub store: Arc<Store<DynamicObject>>,
+    pub resource: ApiResource,
+    pub view: Option<&'static View>,
+    /// Objects in any other namespace are left out. Whether a kind is
+    /// namespaced isn't known here, so objects without one always stay.
+    pub namespace: Option<String>,
+}
+
+impl table::Items for Arc<Objects> {
+    type Item = Object;
+
+    fn error(&self) -> Option<String> {
+        self.store.error()
+    }
+
+    fn retry(&self) {
+        self.store.retry();
+    }
+
+    fn columns(&self) -> Vec<&'static str> {
+        self.view
+            .and_then(View::headers)
+            .unwrap_or_else(Object::columns)
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(self.store.generation())
+    }
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        self.store
+            .items(filter)
+            .filter(
+                |object| match (self.namespace.as_ref(), object.namespace()) {
+                    (Some(scope), Some(namespace)) => *scope == namespace,
+                    _ => true,
+                },
+            )
+            .map(|object| Object {
+                status: kstatus::interpret(
+                    &self.resource.group,
+                    &self.resource.kind,
+                    &object.metadata,
+                    &object.data,
+                ),
+                object,
+                view: self.view,
+            })
+            .collect()
+    }
+}
+
+impl Filter for DynamicObject {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl View {
+    /// The kind this view lists, out of everything found by discovery.
+    pub fn resource(&self, kinds: &[ApiResource]) -> Option<ApiResource> {
+        kinds
+            .iter()
+            .find(|kind| {
+                (kind.kind.eq_ignore_ascii_case(&self.kind) || kind.plural == self.kind)
+                    && self
+                        .group
+                        .as_ref()
+                        .map_or(true, |group| *group == kind.group)
+            })
+            .cloned()
+    }
+
+    /// Only what matches the view is fetched, the rest never makes it into the
+    /// store.
+    pub fn watch(&self) -> watcher::Config {
+        let mut config = watcher::Config::default();
+
+        if let Some(namespace) = self.namespace.as_ref() {
+            config = config.fields(&format!("metadata.namespace={namespace}"));
+        }
+
+        if let Some(selector) = self.selector.as_ref() {
+            config = config.labels(selector);
+        }
+
+        config
+    }
+}
+
+impl Compare for Arc<DynamicObject> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.namespace()
+            .cmp(&other.namespace())
+            .then_with(|| self.name_any().cmp(&other.name_any()))
+    }
+}
@@ -0,0 +1,301 @@
+use eyre::{eyre, Result};
+use json_patch::PatchOperation;
+use ratatui::{
+    layout::Constraint,
+    widgets::{Cell, Row},
+};
+use serde::Serialize;
+use serde_json::{from_value, json, Value};
+
+use super::encoded;
+use crate::widget::table;
+
+#[derive(Clone)]
+enum Field {
+    Replicas,
+    /// Only the tag is edited, the rest of the image stays the same.
+    Tag {
+        repository: String,
+    },
+    Image,
+    Env,
+    /// A plain string, such as a `ConfigMap`'s data.
+    Text,
+    /// Shown decoded, the input is encoded again before it is applied.
+    Base64,
+    /// Any field at all, picked by its path. Strings are taken as typed in,
+    /// everything else has to be JSON.
+    Json,
+}
+
+/// A single field that is changed often enough to warrant a shortcut, instead
+/// of having to edit the entire object.
+#[derive(Clone)]
+pub struct Edit {
+    pub label: String,
+    pub current: String,
+
+    path: String,
+    original: Value,
+    field: Field,
+}
+
+impl Edit {
+    /// Find everything that can be quickly edited in an object. Pods land in
+    /// here via their spec, workloads via their pod template.
+    pub fn find<K>(obj: &K) -> Vec<Self>
+    where
+        K: Serialize,
+    {
+        let Ok(obj) = serde_json::to_value(obj) else {
+            return Vec::new();
+        };
+
+        let mut edits = Vec::new();
+
+        if let Some(replicas) = obj.pointer("/spec/replicas") {
+            edits.push(Self {
+                label: "replicas".to_string(),
+                current: replicas.to_string(),
+                path: "/spec/replicas".to_string(),
+                original: replicas.clone(),
+                field: Field::Replicas,
+            });
+        }
+
+        if obj["kind"] == "ConfigMap" {
+            edits.extend(Self::text(&obj, "data"));
+        }
+
+        if let Some(field) = obj["kind"].as_str().and_then(encoded::field) {
+            edits.extend(Self::encoded(&obj, field));
+
+            return edits;
+        }
+
+        let (spec, templated) = if obj.pointer("/spec/template/spec").is_some() {
+            ("/spec/template/spec", true)
+        } else if obj["kind"] == "Pod" {
+            ("/spec", false)
+        } else {
+            return edits;
+        };
+
+        let containers = obj
+            .pointer(&format!("{spec}/containers"))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .enumerate();
+
+        for (i, container) in containers {
+            let name = container["name"].as_str().unwrap_or_default();
+            let path = format!("{spec}/containers/{i}");
+
+            if let Some(image) = container["image"].as_str() {
+                edits.push(Self::image(name, &format!("{path}/image"), image));
+            }
+
+            // Environment variables for a pod can't be changed once it has been
+            // created.
+            if !templated {
+                continue;
+            }
+
+            let env = container["env"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .enumerate();
+
+            for (j, var) in env {
+                // Anything coming from a secret or the downward API isn't a simple value.
+                let Some(value) = var.get("value") else {
+                    continue;
+                };
+
+                edits.push(Self {
+                    label: format!("env {} ({name})", var["name"].as_str().unwrap_or_default()),
+                    current: value.as_str().unwrap_or_default().to_string(),
+                    path: format!("{path}/env/{j}/value"),
+                    original: value.clone(),
+                    field: Field::Env,
+                });
+            }
+        }
+
+        edits
+    }
+
+    // Only values that can be typed back in are editable: text on a single line
+    // that isn't a certificate.
+    fn encoded(obj: &Value, field: &str) -> Vec<Self> {
+        obj[field]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, value)| {
+                let decoded = String::from_utf8(encoded::decode(value.as_str()?)?).ok()?;
+
+                if decoded.contains('\n') || decoded.starts_with("-----BEGIN") {
+                    return None;
+                }
+
+                Some(Self {
+                    label: format!("{field} {key}"),
+                    current: decoded,
+                    path: format!("/{field}/{}", key.replace('~', "~0").replace('/', "~1")),
+                    original: value.clone(),
+                    field: Field::Base64,
+                })
+            })
+            .collect()
+    }
+
+    fn text(obj: &Value, field: &str) -> Vec<Self> {
+        obj[field]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, value)| {
+                let current = value.as_str()?;
+
+                if current.contains('\n') || current.starts_with("-----BEGIN") {
+                    return None;
+                }
+
+                Some(Self {
+                    label: format!("{field} {key}"),
+                    current: current.to_string(),
+                    path: format!("/{field}/{}", key.replace('~', "~0").replace('/', "~1")),
+                    original: value.clone(),
+                    field: Field::Text,
+                })
+            })
+            .collect()
+    }
+
+    /// Whatever is at `pointer` in the object, so that fields without a
+    /// shortcut can still be patched.
+    pub fn at<K>(obj: &K, pointer: &str) -> Option<Self>
+    where
+        K: Serialize,
+    {
+        let value = serde_json::to_value(obj).ok()?.pointer(pointer)?.clone();
+
+        Some(Self {
+            label: pointer.to_string(),
+            current: value
+                .as_str()
+                .map_or_else(|| value.to_string(), ToString::to_string),
+            path: pointer.to_string(),
+            original: value,
+            field: Field::Json,
+        })
+    }
+
+    /// The same field, as it is in a newer version of the object. `None` when
+    /// the field can't be found anymore, such as when a container was removed.
+    pub fn rebase<K>(&self, obj: &K) -> Option<Self>
+    where
+        K: Serialize,
+    {
+        if let Field::Json = self.field {
+            return Self::at(obj, &self.path);
+        }
+
+        Self::find(obj)
+            .into_iter()
+            .find(|edit| edit.path == self.path)
+    }
+
+    fn image(container: &str, path: &str, image: &str) -> Self {
+        // Images pinned to a digest don't have a tag that can be swapped out.
+        let tag = (!image.contains('@'))
+            .then(|| image.rsplit_once(':'))
+            .flatten()
+            .filter(|(_, tag)| !tag.contains('/'));
+
+        let (label, current, field) = match tag {
+            Some((repository, tag)) => (
+                format!("image tag ({container})"),
+                tag.to_string(),
+                Field::Tag {
+                    repository: repository.to_string(),
+                },
+            ),
+            None => (
+                format!("image ({container})"),
+                image.to_string(),
+                Field::Image,
+            ),
+        };
+
+        Self {
+            label,
+            current,
+            path: path.to_string(),
+            original: Value::String(image.to_string()),
+            field,
+        }
+    }
+
+    /// Build a JSON patch that sets the field to `input`. The patch tests that
+    /// the field hasn't changed since it was read so that edits from elsewhere
+    /// aren't clobbered.
+    pub fn patch(&self, input: &str) -> Result<Vec<PatchOperation>> {
+        let input = input.trim();
+
+        let value = match &self.field {
+            Field::Replicas => json!(input
+                .parse::<u32>()
+                .map_err(|_| eyre!("replicas must be a number, got '{input}'"))?),
+            Field::Tag { repository } => {
+                if input.is_empty() {
+                    return Err(eyre!("tag cannot be empty"));
+                }
+
+                json!(format!("{repository}:{input}"))
+            }
+            Field::Image => {
+                if input.is_empty() {
+                    return Err(eyre!("image cannot be empty"));
+                }
+
+                json!(input)
+            }
+            Field::Env | Field::Text => json!(input),
+            Field::Base64 => json!(encoded::encode(input)),
+            Field::Json if self.original.is_string() => json!(input),
+            Field::Json => serde_json::from_str(input)
+                .map_err(|_| eyre!("{} must be JSON, got '{input}'", self.label))?,
+        };
+
+        Ok(vec![
+            from_value(json!({
+                "op": "test",
+                "path": self.path,
+                "value": self.original,
+            }))?,
+            from_value(json!({
+                "op": "replace",
+                "path": self.path,
+                "value": value,
+            }))?,
+        ])
+    }
+}
+
+impl table::Row for Edit {
+    fn header<'a>() -> Option<Row<'a>> {
+        Some(Row::new(vec![Cell::from("Field"), Cell::from("Current")]))
+    }
+
+    fn constraints() -> Vec<Constraint> {
+        vec![Constraint::Percentage(40), Constraint::Percentage(60)]
+    }
+
+    fn row(&self, _: &table::RowStyle) -> Row {
+        Row::new(vec![self.label.clone(), self.current.clone()])
+    }
+}
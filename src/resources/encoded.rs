@@ -0,0 +1,86 @@
+use base64::prelude::*;
+use itertools::Itertools;
+use serde_yaml::Value;
+
+use super::certificate::Certificate;
+
+// Fields that hold base64 wherever they show up, for example on webhook
+// configurations, `APIService` and CRD conversion.
+static CA_BUNDLE: &str = "caBundle";
+
+pub fn decode(value: &str) -> Option<Vec<u8>> {
+    BASE64_STANDARD.decode(value.trim()).ok()
+}
+
+pub fn encode(value: &str) -> String {
+    BASE64_STANDARD.encode(value)
+}
+
+/// Human readable version of a decoded value. Certificates are summarized
+/// instead of being shown as yet another blob, binary data is only described.
+pub fn describe(bytes: &[u8]) -> String {
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return format!("<{} bytes of binary data>", bytes.len());
+    };
+
+    let certs = Certificate::from_pem(text);
+
+    if certs.is_empty() {
+        return text.to_string();
+    }
+
+    certs.iter().map(ToString::to_string).join("\n")
+}
+
+/// Name of the field that holds nothing but base64 values for a kind.
+pub fn field(kind: &str) -> Option<&'static str> {
+    match kind {
+        "Secret" => Some("data"),
+        "ConfigMap" => Some("binaryData"),
+        _ => None,
+    }
+}
+
+fn replace(value: &mut Value) -> bool {
+    let Some(decoded) = value.as_str().and_then(decode) else {
+        return false;
+    };
+
+    *value = Value::String(describe(&decoded));
+
+    true
+}
+
+fn ca_bundles(value: &mut Value) -> bool {
+    match value {
+        Value::Mapping(map) => map.iter_mut().fold(false, |decoded, (key, value)| {
+            let replaced = if key.as_str() == Some(CA_BUNDLE) {
+                replace(value)
+            } else {
+                ca_bundles(value)
+            };
+
+            decoded | replaced
+        }),
+        Value::Sequence(items) => items
+            .iter_mut()
+            .fold(false, |decoded, item| decoded | ca_bundles(item)),
+        _ => false,
+    }
+}
+
+/// Decode every base64 value of an object in place, returning whether there
+/// was anything to decode.
+pub fn decode_all(obj: &mut Value) -> bool {
+    let field = obj.get("kind").and_then(Value::as_str).and_then(field);
+
+    let mut decoded = false;
+
+    if let Some(Value::Mapping(values)) = field.and_then(|field| obj.get_mut(field)) {
+        for value in values.values_mut() {
+            decoded |= replace(value);
+        }
+    }
+
+    decoded | ca_bundles(obj)
+}
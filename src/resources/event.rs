@@ -0,0 +1,326 @@
+use std::{
+    cmp::Ordering,
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc,
+    },
+};
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::Event;
+use kube::{api::ObjectMeta, runtime::watcher, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{age::Age, store::Store, Compare, Filter};
+use crate::widget::table;
+
+// Aggregated events only say how often something happened between two points
+// in time. Past this, there's no point in spreading them out any further.
+static MAX_OCCURRENCES: i32 = 1000;
+
+/// Only the events about the object with `uid`.
+pub fn involving(uid: &str) -> watcher::Config {
+    watcher::Config::default().fields(&format!("involvedObject.uid={uid}"))
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait EventExt {
+    fn last_seen(&self) -> Option<DateTime<Utc>>;
+    fn occurrences(&self) -> Vec<DateTime<Utc>>;
+    fn is_warning(&self) -> bool;
+    fn object(&self) -> String;
+}
+
+impl EventExt for Event {
+    fn last_seen(&self) -> Option<DateTime<Utc>> {
+        self.series
+            .as_ref()
+            .and_then(|series| series.last_observed_time.as_ref())
+            .map(|time| time.0)
+            .or_else(|| self.last_timestamp.as_ref().map(|time| time.0))
+            .or_else(|| self.event_time.as_ref().map(|time| time.0))
+            .or_else(|| self.metadata.creation_timestamp.as_ref().map(|time| time.0))
+    }
+
+    /// When each occurrence of this event happened. The API server only keeps
+    /// the first and last time for aggregated events, so anything in between
+    /// is spread out evenly.
+    fn occurrences(&self) -> Vec<DateTime<Utc>> {
+        let Some(last) = self.last_seen() else {
+            return Vec::new();
+        };
+
+        let first = self
+            .first_timestamp
+            .as_ref()
+            .map_or(last, |time| time.0)
+            .min(last);
+
+        let count = self
+            .series
+            .as_ref()
+            .and_then(|series| series.count)
+            .or(self.count)
+            .unwrap_or(1)
+            .clamp(1, MAX_OCCURRENCES);
+
+        if count == 1 {
+            return vec![last];
+        }
+
+        let step = (last - first) / (count - 1);
+
+        (0..count).map(|i| first + step * i).collect()
+    }
+
+    fn is_warning(&self) -> bool {
+        self.type_.as_deref() == Some("Warning")
+    }
+
+    /// What the event is about, the same way `kubectl get events` shows it.
+    fn object(&self) -> String {
+        let object = &self.involved_object;
+
+        format!(
+            "{}/{}",
+            object.kind.as_deref().unwrap_or_default().to_lowercase(),
+            object.name.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+impl Filter for Event {
+    fn matches(&self, filter: &str) -> bool {
+        self.type_
+            .as_ref()
+            .is_some_and(|type_| type_.eq_ignore_ascii_case(filter))
+            || self
+                .involved_object
+                .name
+                .as_ref()
+                .is_some_and(|name| name.contains(filter))
+            || self
+                .reason
+                .as_ref()
+                .is_some_and(|reason| reason.contains(filter))
+            || self
+                .message
+                .as_ref()
+                .is_some_and(|message| message.contains(filter))
+    }
+}
+
+impl Compare for Arc<Event> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.last_seen().cmp(&other.last_seen())
+    }
+}
+
+impl table::Row for Arc<Event> {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Last Seen",
+            "Namespace",
+            "Type",
+            "Reason",
+            "Object",
+            "Count",
+            "Message",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.last_seen()
+                .map_or("-".to_string(), |seen| (Utc::now() - seen).to_age()),
+            self.namespace().unwrap_or_default(),
+            self.type_.clone().unwrap_or_default(),
+            self.reason.clone().unwrap_or_default(),
+            self.object(),
+            self.series
+                .as_ref()
+                .and_then(|series| series.count)
+                .or(self.count)
+                .unwrap_or(1)
+                .to_string(),
+            self.message
+                .as_deref()
+                .unwrap_or_default()
+                .replace('\n', " "),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(if self.is_warning() {
+            style.unhealthy
+        } else {
+            style.normal
+        })
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.as_ref().meta())
+    }
+}
+
+/// Every event that the store has, the latest first like the end of
+/// `kubectl get events -w`. Normal events can be left out to only see what
+/// has gone wrong.
+pub struct Stream {
+    pub events: Arc<Store<Event>>,
+    pub warnings: AtomicBool,
+}
+
+impl Stream {
+    pub fn new(events: Arc<Store<Event>>) -> Self {
+        Self {
+            events,
+            warnings: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether only warnings are included.
+    pub fn warnings(&self) -> bool {
+        self.warnings.load(atomic::Ordering::Relaxed)
+    }
+
+    pub fn toggle(&self) {
+        self.warnings.fetch_xor(true, atomic::Ordering::Relaxed);
+    }
+}
+
+impl table::Items for Arc<Stream> {
+    type Item = Arc<Event>;
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let warnings = self.warnings();
+
+        let mut events: Vec<_> = self
+            .events
+            .items(filter)
+            .into_iter()
+            .filter(|event| !warnings || event.is_warning())
+            .collect();
+
+        events.sort_by_key(|event| std::cmp::Reverse(event.last_seen()));
+
+        events
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(table::revision([
+            self.events.generation(),
+            u64::from(self.warnings()),
+        ]))
+    }
+
+    fn error(&self) -> Option<String> {
+        self.events.error()
+    }
+
+    fn retry(&self) {
+        self.events.retry();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use k8s_openapi::{
+        api::core::v1::{EventSeries, ObjectReference},
+        apimachinery::pkg::apis::meta::v1::{MicroTime, Time},
+    };
+
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000, 0).unwrap() + Duration::seconds(secs)
+    }
+
+    fn event(first: Option<i64>, last: Option<i64>, count: Option<i32>) -> Event {
+        Event {
+            first_timestamp: first.map(|secs| Time(at(secs))),
+            last_timestamp: last.map(|secs| Time(at(secs))),
+            count,
+            ..Event::default()
+        }
+    }
+
+    #[test]
+    fn occurrences_single() {
+        assert_eq!(event(None, Some(10), None).occurrences(), vec![at(10)]);
+        assert_eq!(
+            event(Some(0), Some(10), Some(1)).occurrences(),
+            vec![at(10)]
+        );
+    }
+
+    #[test]
+    fn occurrences_spread() {
+        assert_eq!(
+            event(Some(0), Some(30), Some(4)).occurrences(),
+            vec![at(0), at(10), at(20), at(30)]
+        );
+    }
+
+    #[test]
+    fn occurrences_series() {
+        let mut event = event(Some(0), Some(10), Some(2));
+        event.series = Some(EventSeries {
+            count: Some(3),
+            last_observed_time: Some(MicroTime(at(20))),
+        });
+
+        assert_eq!(event.occurrences(), vec![at(0), at(10), at(20)]);
+    }
+
+    #[test]
+    fn occurrences_capped() {
+        let occurrences = event(Some(0), Some(10_000), Some(5_000)).occurrences();
+
+        assert_eq!(i32::try_from(occurrences.len()).ok(), Some(MAX_OCCURRENCES));
+        assert_eq!(occurrences.first(), Some(&at(0)));
+        assert!(occurrences.last().is_some_and(|last| *last <= at(10_000)));
+    }
+
+    #[test]
+    fn occurrences_first_after_last() {
+        assert_eq!(
+            event(Some(20), Some(10), Some(2)).occurrences(),
+            vec![at(10), at(10)]
+        );
+    }
+
+    #[test]
+    fn occurrences_unknown() {
+        assert!(event(None, None, Some(3)).occurrences().is_empty());
+    }
+
+    #[test]
+    fn filter() {
+        let event = Event {
+            type_: Some("Warning".to_string()),
+            reason: Some("BackOff".to_string()),
+            message: Some("Back-off restarting failed container".to_string()),
+            involved_object: ObjectReference {
+                name: Some("web-7d4b9".to_string()),
+                ..ObjectReference::default()
+            },
+            ..Event::default()
+        };
+
+        assert!(event.matches("warning"));
+        assert!(event.matches("BackOff"));
+        assert!(event.matches("web"));
+        assert!(event.matches("restarting"));
+
+        // Only the type ignores case.
+        assert!(!event.matches("backoff"));
+        assert!(!event.matches("Normal"));
+        assert!(!event.matches("api"));
+    }
+}
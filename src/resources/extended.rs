@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use k8s_openapi::{
+    api::core::v1::{Node, Pod, ResourceRequirements},
+    apimachinery::pkg::api::resource::Quantity,
+};
+use kube::ResourceExt;
+use ratatui::widgets::Row;
+
+use super::quantity;
+use crate::widget::table;
+
+// Resources that every node has, they're covered by the capacity view.
+static STANDARD: &[&str] = &["cpu", "memory", "ephemeral-storage", "pods"];
+
+/// Anything beyond CPU, memory and storage, such as `nvidia.com/gpu` from a
+/// device plugin or `hugepages-2Mi`.
+fn is_extended(name: &str) -> bool {
+    !STANDARD.contains(&name)
+}
+
+fn extended(resources: Option<&BTreeMap<String, Quantity>>) -> impl Iterator<Item = (&str, f64)> {
+    resources
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| is_extended(name))
+        .filter_map(|(name, value)| Some((name.as_str(), quantity::parse(value)?)))
+}
+
+/// Huge pages are sized in bytes, everything else is a count of devices.
+pub fn format(name: &str, value: f64) -> String {
+    if name.starts_with("hugepages-") {
+        quantity::bytes(value)
+    } else {
+        format!("{value}")
+    }
+}
+
+/// Extended resources that a container asks for, as `name request/limit`, eg.
+/// `nvidia.com/gpu 1/1`. Either side is `-` when it isn't set.
+pub fn summary(resources: Option<&ResourceRequirements>) -> String {
+    let requests: BTreeMap<_, _> = extended(resources.and_then(|r| r.requests.as_ref())).collect();
+    let limits: BTreeMap<_, _> = extended(resources.and_then(|r| r.limits.as_ref())).collect();
+
+    let show = |name: &str, value: Option<&f64>| {
+        value.map_or_else(|| "-".to_string(), |value| format(name, *value))
+    };
+
+    requests
+        .keys()
+        .chain(limits.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|name| {
+            format!(
+                "{name} {}/{}",
+                show(name, requests.get(name)),
+                show(name, limits.get(name))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether any of the pod's containers, init containers included, ask for an
+/// extended resource.
+pub fn requested(pod: &Pod) -> bool {
+    pod.spec.iter().any(|spec| {
+        spec.containers
+            .iter()
+            .chain(spec.init_containers.iter().flatten())
+            .any(|container| {
+                let resources = container.resources.as_ref();
+
+                extended(resources.and_then(|r| r.requests.as_ref()))
+                    .chain(extended(resources.and_then(|r| r.limits.as_ref())))
+                    .next()
+                    .is_some()
+            })
+    })
+}
+
+// What a pod holds onto, the same way the scheduler adds it up: every container
+// together or the largest init container, whichever is more.
+fn usage(
+    pod: &Pod,
+    field: fn(&ResourceRequirements) -> Option<&BTreeMap<String, Quantity>>,
+) -> BTreeMap<String, f64> {
+    let mut total: BTreeMap<String, f64> = BTreeMap::new();
+
+    let Some(spec) = pod.spec.as_ref() else {
+        return total;
+    };
+
+    for container in &spec.containers {
+        for (name, value) in extended(container.resources.as_ref().and_then(field)) {
+            *total.entry(name.to_string()).or_default() += value;
+        }
+    }
+
+    for container in spec.init_containers.iter().flatten() {
+        for (name, value) in extended(container.resources.as_ref().and_then(field)) {
+            let current = total.entry(name.to_string()).or_default();
+            *current = current.max(value);
+        }
+    }
+
+    total
+}
+
+/// How much of an extended resource a node has, and what the pods on it have
+/// taken.
+#[derive(Clone, Default)]
+pub struct Allocation {
+    pub name: String,
+    pub allocatable: f64,
+    pub requests: f64,
+    pub limits: f64,
+    pub pods: Vec<String>,
+}
+
+impl table::Row for Allocation {
+    fn columns() -> Vec<&'static str> {
+        vec!["Resource", "Allocatable", "Requested", "Limits", "Pods"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let requested = if self.allocatable > 0.0 {
+            format!(
+                "{} ({:.0}%)",
+                format(&self.name, self.requests),
+                self.requests / self.allocatable * 100.0
+            )
+        } else {
+            format(&self.name, self.requests)
+        };
+
+        vec![
+            self.name.clone(),
+            format(&self.name, self.allocatable),
+            requested,
+            format(&self.name, self.limits),
+            self.pods.join(", "),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(
+            if self.allocatable > 0.0 && self.requests >= self.allocatable {
+                style.unhealthy
+            } else {
+                style.normal
+            },
+        )
+    }
+}
+
+fn allocation<'a>(
+    allocations: &'a mut BTreeMap<String, Allocation>,
+    name: &str,
+) -> &'a mut Allocation {
+    allocations
+        .entry(name.to_string())
+        .or_insert_with(|| Allocation {
+            name: name.to_string(),
+            ..Default::default()
+        })
+}
+
+/// Every extended resource that the node has or that a pod on it asks for.
+/// Pods that are done don't hold onto anything and aren't counted.
+pub fn allocations(node: &Node, pods: &[Pod]) -> Vec<Allocation> {
+    let mut allocations: BTreeMap<String, Allocation> = BTreeMap::new();
+
+    for (name, value) in extended(
+        node.status
+            .as_ref()
+            .and_then(|status| status.allocatable.as_ref()),
+    ) {
+        allocation(&mut allocations, name).allocatable += value;
+    }
+
+    for pod in pods {
+        let phase = pod
+            .status
+            .as_ref()
+            .and_then(|status| status.phase.as_deref());
+        if matches!(phase, Some("Succeeded" | "Failed")) {
+            continue;
+        }
+
+        let requests = usage(pod, |resources| resources.requests.as_ref());
+        let limits = usage(pod, |resources| resources.limits.as_ref());
+
+        for name in requests
+            .keys()
+            .chain(limits.keys())
+            .collect::<BTreeSet<_>>()
+        {
+            let allocation = allocation(&mut allocations, name);
+
+            allocation.requests += requests.get(name).copied().unwrap_or_default();
+            allocation.limits += limits.get(name).copied().unwrap_or_default();
+            allocation.pods.push(format!(
+                "{}/{}",
+                pod.namespace().unwrap_or_default(),
+                pod.name_any()
+            ));
+        }
+    }
+
+    // Nodes list every size of huge pages whether they're set up or not.
+    allocations
+        .into_values()
+        .filter(|allocation| allocation.allocatable > 0.0 || !allocation.pods.is_empty())
+        .collect()
+}
@@ -0,0 +1,310 @@
+pub mod diff;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::Secret;
+use kube::{
+    api::{ApiResource, DynamicObject},
+    runtime::watcher,
+    ResourceExt,
+};
+use serde_json::Value;
+
+use crate::{policy::Scope, resources::secret::SecretExt};
+
+// Enough to scroll back through a rollout without holding onto everything a
+// busy controller has ever done. Modifications keep both versions of the
+// object around for `Change::diff`, which consecutive changes share.
+static MAX_CHANGES: usize = 1000;
+
+// Lines around each hunk in `Change::diff`, like `diff -u`.
+static CONTEXT: usize = 3;
+
+// Fields that change on every update without saying anything about what
+// changed.
+static IGNORED: &[&str] = &["metadata.resourceVersion", "metadata.managedFields"];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Added => write!(f, "ADDED"),
+            Action::Modified => write!(f, "MODIFIED"),
+            Action::Deleted => write!(f, "DELETED"),
+        }
+    }
+}
+
+pub struct Field {
+    pub path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+pub struct Change {
+    /// Unique within the feed, indexes into `changes` move as old ones are
+    /// dropped.
+    pub id: u64,
+    pub at: DateTime<Utc>,
+    pub action: Action,
+    pub namespace: Option<String>,
+    pub name: String,
+    /// Only filled in for modifications.
+    pub fields: Vec<Field>,
+    /// The object before and after, only for modifications.
+    pub versions: Option<(Arc<Value>, Arc<Value>)>,
+}
+
+impl Change {
+    /// A unified diff of the object's YAML from before the change to after,
+    /// without the fields that change every time.
+    pub fn diff(&self) -> Vec<diff::Line> {
+        let Some((before, after)) = self.versions.as_ref() else {
+            return Vec::new();
+        };
+
+        let yaml = |value: &Value| {
+            let mut value = value.clone();
+
+            if let Some(metadata) = value.get_mut("metadata").and_then(Value::as_object_mut) {
+                metadata.remove("resourceVersion");
+            }
+
+            serde_yaml::to_string(&value).unwrap_or_default()
+        };
+
+        diff::unified(&yaml(before), &yaml(after), CONTEXT)
+    }
+}
+
+/// Every leaf of `value` by its path, such as `spec.template.spec.containers[0].image`.
+pub fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, String>) {
+    if IGNORED.contains(&prefix) {
+        return;
+    }
+
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+
+                flatten(&path, value, out);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (i, value) in items.iter().enumerate() {
+                flatten(&format!("{prefix}[{i}]"), value, out);
+            }
+        }
+        _ => {
+            out.insert(prefix.to_string(), value.to_string());
+        }
+    }
+}
+
+/// Every leaf that differs between two versions of an object.
+fn diff(before: &Value, after: &Value) -> Vec<Field> {
+    let (mut lhs, mut rhs) = (BTreeMap::new(), BTreeMap::new());
+
+    flatten("", before, &mut lhs);
+    flatten("", after, &mut rhs);
+
+    let paths: HashSet<_> = lhs.keys().chain(rhs.keys()).cloned().collect();
+    let mut paths: Vec<_> = paths.into_iter().collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let (before, after) = (lhs.remove(&path), rhs.remove(&path));
+
+            (before != after).then_some(Field {
+                path,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+/// What has happened to the objects of a kind since the feed started, newest
+/// last. The objects that were already there when it started aren't changes
+/// and only show up once something happens to them.
+#[derive(Default)]
+pub struct Feed {
+    objects: HashMap<String, Arc<Value>>,
+    changes: VecDeque<Change>,
+    next: u64,
+
+    initialized: bool,
+    // Objects seen during a re-list, anything missing at the end was deleted
+    // while the watch was disconnected.
+    relisted: HashSet<String>,
+
+    // The watch is for every namespace the picker allows, anything outside of
+    // the session's scope is dropped before it is recorded.
+    scope: Scope,
+    // Objects in a list don't always say what they are, so this comes from
+    // the watch instead.
+    resource: Option<ApiResource>,
+}
+
+impl Feed {
+    pub fn new(scope: Scope, resource: ApiResource) -> Self {
+        Self {
+            scope,
+            resource: Some(resource),
+            ..Self::default()
+        }
+    }
+
+    fn is(&self, group: &str, kind: &str) -> bool {
+        self.resource
+            .as_ref()
+            .is_some_and(|resource| resource.group == group && resource.kind == kind)
+    }
+
+    fn visible(&self, obj: &DynamicObject) -> bool {
+        match obj.namespace() {
+            Some(namespace) => self.scope.allows(&namespace),
+            None if self.is("", "Namespace") => self.scope.allows(&obj.name_any()),
+            None => true,
+        }
+    }
+
+    // Managed fields are usually bigger than the rest of the object, and are
+    // never compared. Secrets are masked before anything is kept, so their
+    // values never end up in a change or a diff.
+    fn stripped(&self, obj: &DynamicObject) -> Option<Value> {
+        let mut value = serde_json::to_value(obj).ok()?;
+
+        if self.is("", "Secret") {
+            let secret = serde_json::from_value::<Secret>(value).ok()?.masked();
+
+            value = serde_json::to_value(secret).ok()?;
+        }
+
+        if let Some(metadata) = value.get_mut("metadata").and_then(Value::as_object_mut) {
+            metadata.remove("managedFields");
+        }
+
+        Some(value)
+    }
+
+    pub fn changes(&self) -> &VecDeque<Change> {
+        &self.changes
+    }
+
+    fn key(obj: &DynamicObject) -> String {
+        obj.uid().unwrap_or_else(|| {
+            format!("{}/{}", obj.namespace().unwrap_or_default(), obj.name_any())
+        })
+    }
+
+    fn record(
+        &mut self,
+        action: Action,
+        obj: &DynamicObject,
+        fields: Vec<Field>,
+        versions: Option<(Arc<Value>, Arc<Value>)>,
+    ) {
+        // Modifications that only touched ignored fields aren't interesting.
+        if action == Action::Modified && fields.is_empty() {
+            return;
+        }
+
+        self.next += 1;
+
+        self.changes.push_back(Change {
+            id: self.next,
+            at: Utc::now(),
+            action,
+            namespace: obj.namespace(),
+            name: obj.name_any(),
+            fields,
+            versions,
+        });
+
+        while self.changes.len() > MAX_CHANGES {
+            self.changes.pop_front();
+        }
+    }
+
+    fn apply(&mut self, obj: &DynamicObject) {
+        let Some(value) = self.stripped(obj).map(Arc::new) else {
+            return;
+        };
+
+        match self.objects.insert(Self::key(obj), value.clone()) {
+            Some(previous) => {
+                let fields = diff(&previous, &value);
+
+                self.record(Action::Modified, obj, fields, Some((previous, value)));
+            }
+            None => self.record(Action::Added, obj, Vec::new(), None),
+        }
+    }
+
+    fn delete(&mut self, obj: &DynamicObject) {
+        self.objects.remove(&Self::key(obj));
+        self.record(Action::Deleted, obj, Vec::new(), None);
+    }
+
+    pub fn handle(&mut self, event: watcher::Event<DynamicObject>) {
+        if let watcher::Event::InitApply(obj)
+        | watcher::Event::Apply(obj)
+        | watcher::Event::Delete(obj) = &event
+        {
+            if !self.visible(obj) {
+                return;
+            }
+        }
+
+        match event {
+            watcher::Event::Init => self.relisted.clear(),
+            watcher::Event::InitApply(obj) => {
+                self.relisted.insert(Self::key(&obj));
+
+                if self.initialized {
+                    self.apply(&obj);
+                } else if let Some(value) = self.stripped(&obj) {
+                    self.objects.insert(Self::key(&obj), Arc::new(value));
+                }
+            }
+            watcher::Event::InitDone => {
+                if self.initialized {
+                    let missing: Vec<_> = self
+                        .objects
+                        .iter()
+                        .filter(|(key, _)| !self.relisted.contains(*key))
+                        .filter_map(|(_, value)| {
+                            serde_json::from_value(value.as_ref().clone()).ok()
+                        })
+                        .collect();
+
+                    for obj in missing {
+                        self.delete(&obj);
+                    }
+                }
+
+                self.initialized = true;
+            }
+            watcher::Event::Apply(obj) => self.apply(&obj),
+            watcher::Event::Delete(obj) => self.delete(&obj),
+        }
+    }
+}
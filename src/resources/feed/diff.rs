@@ -0,0 +1,163 @@
+// Past this many cells in the table for the part that changed, finding the
+// smallest diff takes longer than it's worth and everything in it is shown as
+// removed and then added instead.
+static MAX_CELLS: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    /// The `@@ -1,7 +1,8 @@` at the start of each hunk.
+    Hunk(String),
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Same,
+    Removed,
+    Added,
+}
+
+/// Every line of `before` and `after` in order, along with whether it was
+/// kept, removed or added. Lines at the start and end that didn't change are
+/// taken off before comparing, which is usually almost all of an object.
+fn ops<'a>(before: &[&'a str], after: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let prefix = before
+        .iter()
+        .zip(after)
+        .take_while(|(lhs, rhs)| lhs == rhs)
+        .count();
+
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(lhs, rhs)| lhs == rhs)
+        .count();
+
+    let lhs = &before[prefix..before.len() - suffix];
+    let rhs = &after[prefix..after.len() - suffix];
+
+    let mut ops: Vec<_> = before[..prefix].iter().map(|l| (Op::Same, *l)).collect();
+
+    if lhs.len().saturating_mul(rhs.len()) > MAX_CELLS {
+        ops.extend(lhs.iter().map(|l| (Op::Removed, *l)));
+        ops.extend(rhs.iter().map(|l| (Op::Added, *l)));
+    } else {
+        // The longest common subsequence of what's left, from the end so that
+        // walking it forwards below comes out in order.
+        let mut lengths = vec![vec![0u32; rhs.len() + 1]; lhs.len() + 1];
+
+        for i in (0..lhs.len()).rev() {
+            for j in (0..rhs.len()).rev() {
+                lengths[i][j] = if lhs[i] == rhs[j] {
+                    lengths[i + 1][j + 1] + 1
+                } else {
+                    lengths[i + 1][j].max(lengths[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+
+        while i < lhs.len() && j < rhs.len() {
+            if lhs[i] == rhs[j] {
+                ops.push((Op::Same, lhs[i]));
+                i += 1;
+                j += 1;
+            } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+                ops.push((Op::Removed, lhs[i]));
+                i += 1;
+            } else {
+                ops.push((Op::Added, rhs[j]));
+                j += 1;
+            }
+        }
+
+        ops.extend(lhs[i..].iter().map(|l| (Op::Removed, *l)));
+        ops.extend(rhs[j..].iter().map(|l| (Op::Added, *l)));
+    }
+
+    ops.extend(
+        before[before.len() - suffix..]
+            .iter()
+            .map(|l| (Op::Same, *l)),
+    );
+
+    ops
+}
+
+// Hunks count lines from 1, except for an empty side which starts at 0.
+fn range(start: usize, len: usize) -> String {
+    if len == 0 {
+        format!("{start},0")
+    } else {
+        format!("{},{len}", start + 1)
+    }
+}
+
+/// A unified diff between `before` and `after`, with `context` lines that
+/// didn't change around each hunk like `diff -u`. Nothing at all when they're
+/// the same.
+pub fn unified(before: &str, after: &str, context: usize) -> Vec<Line> {
+    let before: Vec<_> = before.lines().collect();
+    let after: Vec<_> = after.lines().collect();
+
+    let ops = ops(&before, &after);
+
+    let changed: Vec<_> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != Op::Same)
+        .map(|(i, _)| i)
+        .collect();
+
+    // Changes close enough together that their context would overlap end up
+    // in the same hunk.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+
+    for i in changed {
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(ops.len());
+
+        match hunks.last_mut() {
+            Some((_, last)) if start <= *last => *last = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut lines = Vec::new();
+
+    for (start, end) in hunks {
+        // Where the hunk starts on each side is however many lines came
+        // before it there.
+        let lhs = ops[..start]
+            .iter()
+            .filter(|(op, _)| *op != Op::Added)
+            .count();
+        let rhs = ops[..start]
+            .iter()
+            .filter(|(op, _)| *op != Op::Removed)
+            .count();
+
+        let hunk = &ops[start..end];
+
+        lines.push(Line::Hunk(format!(
+            "@@ -{} +{} @@",
+            range(lhs, hunk.iter().filter(|(op, _)| *op != Op::Added).count()),
+            range(
+                rhs,
+                hunk.iter().filter(|(op, _)| *op != Op::Removed).count()
+            ),
+        )));
+
+        lines.extend(hunk.iter().map(|(op, line)| match op {
+            Op::Same => Line::Same((*line).to_string()),
+            Op::Removed => Line::Removed((*line).to_string()),
+            Op::Added => Line::Added((*line).to_string()),
+        }));
+    }
+
+    lines
+}
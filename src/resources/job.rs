@@ -0,0 +1,161 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::api::batch::v1::Job;
+use kube::{api::ObjectMeta, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{age::Age, Compare, Filter};
+use crate::widget::table;
+
+/// Where a job is at, from its conditions. A job that hasn't finished is
+/// running even when its pods are still being created.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Running,
+    Suspended,
+    Complete,
+    Failed(String),
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Running => write!(f, "Running"),
+            Phase::Suspended => write!(f, "Suspended"),
+            Phase::Complete => write!(f, "Complete"),
+            Phase::Failed(reason) => write!(f, "Failed ({reason})"),
+        }
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait JobExt {
+    fn age(&self) -> TimeDelta;
+    fn phase(&self) -> Phase;
+    fn completions(&self) -> String;
+    fn duration(&self) -> Option<TimeDelta>;
+}
+
+impl JobExt for Job {
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+
+    fn phase(&self) -> Phase {
+        let condition = |type_: &str| {
+            self.status
+                .as_ref()
+                .and_then(|status| status.conditions.as_ref())
+                .into_iter()
+                .flatten()
+                .find(|condition| condition.type_ == type_ && condition.status == "True")
+        };
+
+        if let Some(failed) = condition("Failed") {
+            return Phase::Failed(failed.reason.clone().unwrap_or_default());
+        }
+
+        if condition("Complete").is_some() {
+            return Phase::Complete;
+        }
+
+        if self.spec.as_ref().and_then(|spec| spec.suspend) == Some(true) {
+            return Phase::Suspended;
+        }
+
+        Phase::Running
+    }
+
+    /// Succeeded pods out of the ones needed, like `kubectl get jobs`. Work
+    /// queue jobs don't have a number of completions and only show how many
+    /// have succeeded.
+    fn completions(&self) -> String {
+        let succeeded = self
+            .status
+            .as_ref()
+            .and_then(|status| status.succeeded)
+            .unwrap_or_default();
+
+        match self.spec.as_ref().and_then(|spec| spec.completions) {
+            Some(completions) => format!("{succeeded}/{completions}"),
+            None => succeeded.to_string(),
+        }
+    }
+
+    /// From the job starting until it finished, or until now when it hasn't.
+    fn duration(&self) -> Option<TimeDelta> {
+        let status = self.status.as_ref()?;
+        let start = status.start_time.as_ref()?.0;
+
+        Some(
+            status
+                .completion_time
+                .as_ref()
+                .map_or_else(Utc::now, |time| time.0)
+                - start,
+        )
+    }
+}
+
+impl table::Row for Arc<Job> {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Namespace",
+            "Name",
+            "Status",
+            "Completions",
+            "Duration",
+            "Age",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.namespace().unwrap_or_default(),
+            self.name_any(),
+            self.phase().to_string(),
+            self.completions(),
+            self.duration()
+                .map(|duration| duration.to_age())
+                .unwrap_or_default(),
+            self.age().to_age(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        let color = match self.phase() {
+            Phase::Complete => style.healthy,
+            Phase::Running | Phase::Suspended => style.normal,
+            Phase::Failed(_) => style.unhealthy,
+        };
+
+        table::highlight(self, &["Status"], color)
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.as_ref().meta())
+    }
+}
+
+impl Filter for Job {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<Job> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.namespace()
+            .cmp(&other.namespace())
+            .then_with(|| self.name_any().cmp(&other.name_any()))
+    }
+}
@@ -0,0 +1,297 @@
+use kube::api::ObjectMeta;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::widget::{table::RowStyle, theme::Indicator};
+
+// Container waiting reasons that will not go away without someone changing
+// something.
+pub static FAILING_REASONS: &[&str] = &[
+    "CrashLoopBackOff",
+    "ImagePullBackOff",
+    "ErrImagePull",
+    "InvalidImageName",
+    "CreateContainerConfigError",
+    "CreateContainerError",
+];
+
+/// Works out the status of one kind from its body (`spec`, `status`, ...).
+/// `None` means there's nothing to go on and the generic rules should be used
+/// instead.
+type Interpreter = fn(&Value) -> Option<Status>;
+
+// Kinds that don't follow the conditions convention, or have more to say than
+// it does. Anything not in here gets the generic rules, which is what most
+// CRs need. Adding a kind is a matter of adding an entry, keyed on its group
+// and kind.
+static INTERPRETERS: &[(&str, &str, Interpreter)] = &[
+    ("", "PersistentVolumeClaim", pvc),
+    ("", "Pod", pod),
+    ("apps", "DaemonSet", daemonset),
+    ("apps", "Deployment", deployment),
+    ("apps", "StatefulSet", statefulset),
+    ("batch", "Job", job),
+];
+
+/// Normalized status for any kind, following the same ideas as kstatus. Kinds
+/// each have their own way of saying how they're doing, this is what gets
+/// used to color rows and roll health up so that it means the same thing
+/// everywhere.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// Everything asked for in the spec has happened.
+    Current,
+    /// Still working towards the spec, with what is being waited on.
+    InProgress(String),
+    /// Won't get to the spec without someone intervening.
+    Failed(String),
+    /// Being deleted.
+    Terminating,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Current => write!(f, "Current"),
+            Status::InProgress(reason) => write!(f, "InProgress ({reason})"),
+            Status::Failed(reason) => write!(f, "Failed ({reason})"),
+            Status::Terminating => write!(f, "Terminating"),
+        }
+    }
+}
+
+impl Status {
+    pub fn indicator(&self) -> Indicator {
+        match self {
+            Status::Current => Indicator::Healthy,
+            Status::InProgress(_) | Status::Terminating => Indicator::Progressing,
+            Status::Failed(_) => Indicator::Unhealthy,
+        }
+    }
+
+    pub fn style(&self, style: &RowStyle) -> ratatui::style::Style {
+        match self {
+            Status::Current => style.healthy,
+            Status::InProgress(_) | Status::Terminating => style.normal,
+            Status::Failed(_) => style.unhealthy,
+        }
+    }
+}
+
+/// The status of a typed object, see `interpret`.
+pub fn of<K>(group: &str, kind: &str, obj: &K) -> Option<Status>
+where
+    K: kube::Resource + Serialize,
+{
+    let body = serde_json::to_value(obj).ok()?;
+
+    interpret(group, kind, obj.meta(), &body)
+}
+
+/// Status of an object of `kind`, `None` when the object has no status at all
+/// (eg. a `ConfigMap`).
+pub fn interpret(group: &str, kind: &str, meta: &ObjectMeta, body: &Value) -> Option<Status> {
+    if meta.deletion_timestamp.is_some() {
+        return Some(Status::Terminating);
+    }
+
+    let status = body.get("status")?;
+
+    // The controller hasn't seen the latest spec yet, whatever the status says
+    // is about an older one.
+    let observed = status.get("observedGeneration").and_then(Value::as_i64);
+    if let (Some(observed), Some(generation)) = (observed, meta.generation) {
+        if observed < generation {
+            return Some(Status::InProgress("spec not yet observed".to_string()));
+        }
+    }
+
+    INTERPRETERS
+        .iter()
+        .find(|(g, k, _)| *g == group && *k == kind)
+        .and_then(|(_, _, interpreter)| interpreter(body))
+        .or_else(|| Some(conditions(status)))
+}
+
+fn condition<'a>(status: &'a Value, type_: &str) -> Option<(&'a str, String)> {
+    status
+        .get("conditions")?
+        .as_array()?
+        .iter()
+        .find(|c| c.get("type").and_then(Value::as_str) == Some(type_))
+        .map(|c| {
+            let reason = c
+                .get("reason")
+                .and_then(Value::as_str)
+                .unwrap_or(type_)
+                .to_string();
+
+            (
+                c.get("status").and_then(Value::as_str).unwrap_or_default(),
+                reason,
+            )
+        })
+}
+
+fn int(value: &Value, path: &str) -> i64 {
+    value
+        .pointer(path)
+        .and_then(Value::as_i64)
+        .unwrap_or_default()
+}
+
+// The conventions most controllers follow: `Stalled` when they have given up,
+// `Reconciling` while they work and `Ready` once they are done.
+fn conditions(status: &Value) -> Status {
+    if let Some(("True", reason)) = condition(status, "Stalled") {
+        return Status::Failed(reason);
+    }
+
+    if let Some(("True", reason)) = condition(status, "Reconciling") {
+        return Status::InProgress(reason);
+    }
+
+    match condition(status, "Ready") {
+        Some(("True", _)) | None => Status::Current,
+        Some((_, reason)) => Status::InProgress(reason),
+    }
+}
+
+fn deployment(body: &Value) -> Option<Status> {
+    let status = body.get("status")?;
+
+    if let Some(("False", reason)) = condition(status, "Progressing") {
+        return Some(Status::Failed(reason));
+    }
+
+    if let Some(("True", reason)) = condition(status, "ReplicaFailure") {
+        return Some(Status::Failed(reason));
+    }
+
+    let desired = body
+        .pointer("/spec/replicas")
+        .and_then(Value::as_i64)
+        .unwrap_or(1);
+
+    if int(status, "/updatedReplicas") < desired || int(status, "/replicas") > desired {
+        return Some(Status::InProgress("rolling out".to_string()));
+    }
+
+    let available = int(status, "/availableReplicas");
+    if available < desired {
+        return Some(Status::InProgress(format!(
+            "{available}/{desired} available"
+        )));
+    }
+
+    Some(Status::Current)
+}
+
+fn statefulset(body: &Value) -> Option<Status> {
+    let status = body.get("status")?;
+
+    let desired = body
+        .pointer("/spec/replicas")
+        .and_then(Value::as_i64)
+        .unwrap_or(1);
+
+    let updated = status.get("updateRevision").and_then(Value::as_str);
+    if updated.is_some() && updated != status.get("currentRevision").and_then(Value::as_str) {
+        return Some(Status::InProgress("rolling out".to_string()));
+    }
+
+    let ready = int(status, "/readyReplicas");
+    if ready < desired {
+        return Some(Status::InProgress(format!("{ready}/{desired} ready")));
+    }
+
+    Some(Status::Current)
+}
+
+fn daemonset(body: &Value) -> Option<Status> {
+    let status = body.get("status")?;
+
+    let desired = int(status, "/desiredNumberScheduled");
+
+    if int(status, "/updatedNumberScheduled") < desired {
+        return Some(Status::InProgress("rolling out".to_string()));
+    }
+
+    let available = int(status, "/numberAvailable");
+    if available < desired {
+        return Some(Status::InProgress(format!(
+            "{available}/{desired} available"
+        )));
+    }
+
+    Some(Status::Current)
+}
+
+fn job(body: &Value) -> Option<Status> {
+    let status = body.get("status")?;
+
+    if let Some(("True", reason)) = condition(status, "Failed") {
+        return Some(Status::Failed(reason));
+    }
+
+    if let Some(("True", _)) = condition(status, "Complete") {
+        return Some(Status::Current);
+    }
+
+    if body.pointer("/spec/suspend").and_then(Value::as_bool) == Some(true) {
+        return Some(Status::InProgress("suspended".to_string()));
+    }
+
+    Some(Status::InProgress(format!(
+        "{} active",
+        int(status, "/active")
+    )))
+}
+
+fn pod(body: &Value) -> Option<Status> {
+    let status = body.get("status")?;
+
+    match status.get("phase").and_then(Value::as_str) {
+        Some("Succeeded") => return Some(Status::Current),
+        Some("Failed") => {
+            return Some(Status::Failed(
+                status
+                    .get("reason")
+                    .and_then(Value::as_str)
+                    .unwrap_or("pod failed")
+                    .to_string(),
+            ))
+        }
+        _ => {}
+    }
+
+    let waiting = ["initContainerStatuses", "containerStatuses"]
+        .iter()
+        .filter_map(|key| status.get(key)?.as_array())
+        .flatten()
+        .filter_map(|c| c.pointer("/state/waiting/reason")?.as_str())
+        .find(|reason| FAILING_REASONS.contains(reason));
+
+    if let Some(reason) = waiting {
+        return Some(Status::Failed(reason.to_string()));
+    }
+
+    match condition(status, "Ready") {
+        Some(("True", _)) => Some(Status::Current),
+        _ => Some(Status::InProgress(
+            status
+                .get("phase")
+                .and_then(Value::as_str)
+                .unwrap_or("Pending")
+                .to_string(),
+        )),
+    }
+}
+
+fn pvc(body: &Value) -> Option<Status> {
+    match body.pointer("/status/phase").and_then(Value::as_str)? {
+        "Bound" => Some(Status::Current),
+        "Lost" => Some(Status::Failed("Lost".to_string())),
+        phase => Some(Status::InProgress(phase.to_string())),
+    }
+}
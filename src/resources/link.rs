@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Somewhere that can be opened in a browser, usually found in annotations or
+/// in the spec of an object like an ingress.
+#[derive(Debug, Clone)]
+pub struct Link {
+    pub name: String,
+    pub url: String,
+}
+
+fn is_url(value: &str) -> bool {
+    (value.starts_with("https://") || value.starts_with("http://"))
+        && !value.chars().any(char::is_whitespace)
+}
+
+/// Find all the links in an object. This works off the serialized object so
+/// that both typed and dynamic objects are supported.
+pub fn find<K>(obj: &K) -> Vec<Link>
+where
+    K: Serialize,
+{
+    let Ok(obj) = serde_json::to_value(obj) else {
+        return Vec::new();
+    };
+
+    let mut links = ingress(&obj);
+
+    // Things like dashboards and runbooks tend to get attached as annotations,
+    // e.g. `grafana.com/dashboard: https://...`.
+    if let Some(annotations) = obj["metadata"]["annotations"].as_object() {
+        links.extend(annotations.iter().filter_map(|(key, value)| {
+            let value = value.as_str()?;
+
+            is_url(value).then(|| Link {
+                name: key.clone(),
+                url: value.to_string(),
+            })
+        }));
+    }
+
+    links
+}
+
+fn ingress(obj: &Value) -> Vec<Link> {
+    if obj["kind"] != "Ingress" {
+        return Vec::new();
+    }
+
+    let tls: BTreeSet<&str> = obj["spec"]["tls"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|tls| tls["hosts"].as_array().into_iter().flatten())
+        .filter_map(Value::as_str)
+        .collect();
+
+    obj["spec"]["rules"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|rule| {
+            let host = rule["host"].as_str()?;
+
+            // Wildcards aren't something that can be opened.
+            if host.starts_with('*') {
+                return None;
+            }
+
+            let scheme = if tls.contains(host) { "https" } else { "http" };
+
+            let paths: Vec<_> = rule["http"]["paths"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|path| path["path"].as_str())
+                .collect();
+
+            Some(
+                if paths.is_empty() { vec!["/"] } else { paths }
+                    .into_iter()
+                    .map(move |path| Link {
+                        name: format!("ingress {host}"),
+                        url: format!("{scheme}://{host}{path}"),
+                    }),
+            )
+        })
+        .flatten()
+        .collect()
+}
@@ -0,0 +1,42 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::api::core::v1::Namespace;
+use kube::ResourceExt;
+
+use super::{Compare, Filter};
+
+#[allow(clippy::module_name_repetitions)]
+pub trait NamespaceExt {
+    fn phase(&self) -> String;
+    fn age(&self) -> TimeDelta;
+}
+
+impl NamespaceExt for Namespace {
+    fn phase(&self) -> String {
+        self.status
+            .as_ref()
+            .and_then(|status| status.phase.clone())
+            .unwrap_or_else(|| "Unknown".to_string())
+    }
+
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+}
+
+impl Filter for Namespace {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<Namespace> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name_any().cmp(&other.name_any())
+    }
+}
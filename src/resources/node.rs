@@ -0,0 +1,212 @@
+pub mod drain;
+
+use std::{cmp::Ordering, sync::Arc};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::api::core::v1::Node;
+use kube::{api::ObjectMeta, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{age::Age, capacity, quantity, Compare, Filter};
+use crate::widget::table;
+
+/// Prefix of the labels that give a node its roles, eg.
+/// `node-role.kubernetes.io/control-plane`.
+static ROLE_PREFIX: &str = "node-role.kubernetes.io/";
+
+/// Well-known label that cloud providers set on nodes to say which zone they
+/// are in.
+pub static ZONE_LABEL: &str = "topology.kubernetes.io/zone";
+
+/// Labels that say which pool a node belongs to, in order of preference.
+/// Providers each have their own, the instance type is the fallback for when
+/// none of them are set.
+pub static POOL_LABELS: &[&str] = &[
+    "cloud.google.com/gke-nodepool",
+    "eks.amazonaws.com/nodegroup",
+    "karpenter.sh/nodepool",
+    "kubernetes.azure.com/agentpool",
+    "node.kubernetes.io/instance-type",
+];
+
+/// Well-known label with the node's instance type, along with the deprecated
+/// one that older clusters still set.
+pub static INSTANCE_TYPE_LABELS: &[&str] = &[
+    "node.kubernetes.io/instance-type",
+    "beta.kubernetes.io/instance-type",
+];
+
+#[allow(clippy::module_name_repetitions)]
+pub trait NodeExt {
+    fn zone(&self) -> Option<&str>;
+    fn pool(&self) -> Option<&str>;
+    fn instance_type(&self) -> Option<&str>;
+    fn roles(&self) -> Vec<String>;
+    fn is_ready(&self) -> Option<bool>;
+    fn is_cordoned(&self) -> bool;
+    fn version(&self) -> String;
+    fn taints(&self) -> Vec<String>;
+    fn age(&self) -> TimeDelta;
+}
+
+impl NodeExt for Node {
+    fn zone(&self) -> Option<&str> {
+        self.labels().get(ZONE_LABEL).map(String::as_str)
+    }
+
+    fn pool(&self) -> Option<&str> {
+        POOL_LABELS
+            .iter()
+            .find_map(|label| self.labels().get(*label))
+            .map(String::as_str)
+    }
+
+    fn instance_type(&self) -> Option<&str> {
+        INSTANCE_TYPE_LABELS
+            .iter()
+            .find_map(|label| self.labels().get(*label))
+            .map(String::as_str)
+    }
+
+    fn roles(&self) -> Vec<String> {
+        self.labels()
+            .keys()
+            .filter_map(|label| label.strip_prefix(ROLE_PREFIX))
+            .filter(|role| !role.is_empty())
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// `None` when the kubelet hasn't reported in, which is what the `Ready`
+    /// condition being `Unknown` means.
+    fn is_ready(&self) -> Option<bool> {
+        let condition = self
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())?
+            .iter()
+            .find(|condition| condition.type_ == "Ready")?;
+
+        match condition.status.as_str() {
+            "True" => Some(true),
+            "False" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn is_cordoned(&self) -> bool {
+        self.spec
+            .as_ref()
+            .and_then(|spec| spec.unschedulable)
+            .unwrap_or_default()
+    }
+
+    fn version(&self) -> String {
+        self.status
+            .as_ref()
+            .and_then(|status| status.node_info.as_ref())
+            .map(|info| info.kubelet_version.clone())
+            .unwrap_or_default()
+    }
+
+    /// As `key=value:Effect`, the same way they're passed to `kubectl taint`.
+    fn taints(&self) -> Vec<String> {
+        self.spec
+            .as_ref()
+            .and_then(|spec| spec.taints.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|taint| match taint.value.as_deref() {
+                Some(value) if !value.is_empty() => {
+                    format!("{}={value}:{}", taint.key, taint.effect)
+                }
+                _ => format!("{}:{}", taint.key, taint.effect),
+            })
+            .collect()
+    }
+
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+}
+
+impl table::Row for Arc<Node> {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Name", "Status", "Roles", "Version", "Taints", "CPU", "Memory", "Pods", "Age",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let mut status = match self.is_ready() {
+            Some(true) => "Ready",
+            Some(false) => "NotReady",
+            None => "Unknown",
+        }
+        .to_string();
+
+        if self.is_cordoned() {
+            status.push_str(",SchedulingDisabled");
+        }
+
+        let roles = self.roles();
+        let taints = self.taints();
+        let allocatable = capacity::allocatable(self);
+
+        vec![
+            self.name_any(),
+            status,
+            if roles.is_empty() {
+                "<none>".to_string()
+            } else {
+                roles.join(",")
+            },
+            self.version(),
+            match taints.len() {
+                0 => String::new(),
+                1 => taints[0].clone(),
+                count => format!("{} (+{})", taints[0], count - 1),
+            },
+            quantity::cpu(allocatable.cpu),
+            quantity::bytes(allocatable.memory),
+            self.status
+                .as_ref()
+                .and_then(|status| status.allocatable.as_ref())
+                .and_then(|allocatable| allocatable.get("pods"))
+                .map(|pods| pods.0.clone())
+                .unwrap_or_default(),
+            self.age().to_age(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(match self.is_ready() {
+            Some(true) => style.normal,
+            _ => style.unhealthy,
+        })
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.as_ref().meta())
+    }
+}
+
+impl Filter for Node {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<Node> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name_any().cmp(&other.name_any())
+    }
+}
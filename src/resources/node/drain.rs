@@ -0,0 +1,174 @@
+use std::{collections::HashMap, sync::Arc};
+
+use k8s_openapi::api::{core::v1::Pod, policy::v1::PodDisruptionBudget};
+use kube::ResourceExt;
+use ratatui::widgets::Row;
+
+use crate::{
+    resources::selector,
+    widget::{table, theme::Indicator},
+};
+
+static MIRROR_ANNOTATION: &str = "kubernetes.io/config.mirror";
+
+/// What draining the node would do with a pod.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Evict,
+    /// Eviction would be refused because of the named disruption budget.
+    Blocked(String),
+    /// The pod isn't evicted at all, with the reason why.
+    Stays(&'static str),
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Evict => write!(f, "{}", Indicator::Progressing.label("evict")),
+            Outcome::Blocked(_) => write!(f, "{}", Indicator::Unhealthy.label("blocked")),
+            Outcome::Stays(_) => write!(f, "{}", Indicator::Healthy.label("stays")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Plan {
+    pub pod: Arc<Pod>,
+    pub outcome: Outcome,
+    /// Something worth knowing before evicting, eg. that the pod won't come
+    /// back anywhere else.
+    pub warning: Option<&'static str>,
+}
+
+impl Plan {
+    fn reason(&self) -> String {
+        match &self.outcome {
+            Outcome::Evict => self.warning.unwrap_or_default().to_string(),
+            Outcome::Blocked(pdb) => format!("disruption budget {pdb} allows no more disruptions"),
+            Outcome::Stays(reason) => (*reason).to_string(),
+        }
+    }
+}
+
+impl table::Row for Plan {
+    fn columns() -> Vec<&'static str> {
+        vec!["Namespace", "Name", "Action", "Reason"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.pod.namespace().unwrap_or_default(),
+            self.pod.name_any(),
+            self.outcome.to_string(),
+            self.reason(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(match self.outcome {
+            Outcome::Evict => style.normal,
+            Outcome::Blocked(_) => style.unhealthy,
+            Outcome::Stays(_) => style.healthy,
+        })
+    }
+
+    fn uid(&self) -> Option<String> {
+        self.pod.uid()
+    }
+}
+
+fn is_daemonset(pod: &Pod) -> bool {
+    pod.owner_references()
+        .iter()
+        .any(|owner| owner.controller == Some(true) && owner.kind == "DaemonSet")
+}
+
+fn warning(pod: &Pod) -> Option<&'static str> {
+    if pod.owner_references().is_empty() {
+        return Some("not managed by a controller, it won't be recreated");
+    }
+
+    let empty_dir = pod
+        .spec
+        .iter()
+        .flat_map(|spec| spec.volumes.iter().flatten())
+        .any(|volume| volume.empty_dir.is_some());
+
+    empty_dir.then_some("emptyDir data is lost")
+}
+
+/// Work out what draining would do with each of `pods`, the same way `kubectl
+/// drain` decides. Disruption budgets are used up in the order pods are
+/// evicted, so a budget that allows one disruption lets through the first pod
+/// it covers and blocks the rest.
+pub fn plan(pods: &[Arc<Pod>], pdbs: &[Arc<PodDisruptionBudget>]) -> Vec<Plan> {
+    let mut allowed: HashMap<(String, String), i32> = pdbs
+        .iter()
+        .map(|pdb| {
+            (
+                (pdb.namespace().unwrap_or_default(), pdb.name_any()),
+                pdb.status
+                    .as_ref()
+                    .map_or(0, |status| status.disruptions_allowed),
+            )
+        })
+        .collect();
+
+    pods.iter()
+        .map(|pod| {
+            let stays = if pod.annotations().contains_key(MIRROR_ANNOTATION) {
+                Some("static pod, managed by the kubelet")
+            } else if is_daemonset(pod) {
+                Some("daemonset pods are ignored")
+            } else {
+                None
+            };
+
+            if let Some(reason) = stays {
+                return Plan {
+                    pod: pod.clone(),
+                    outcome: Outcome::Stays(reason),
+                    warning: None,
+                };
+            }
+
+            // Pods that have already finished don't count against a budget.
+            let finished = matches!(
+                pod.status
+                    .as_ref()
+                    .and_then(|status| status.phase.as_deref()),
+                Some("Succeeded" | "Failed")
+            );
+
+            let covering = pdbs.iter().filter(|pdb| {
+                !finished
+                    && pdb.namespace() == pod.namespace()
+                    && pdb
+                        .spec
+                        .as_ref()
+                        .and_then(|spec| spec.selector.as_ref())
+                        .is_some_and(|selector| selector::matches(selector, pod.labels()))
+            });
+
+            let mut outcome = Outcome::Evict;
+
+            for pdb in covering {
+                let key = (pdb.namespace().unwrap_or_default(), pdb.name_any());
+                let remaining = allowed.entry(key).or_default();
+
+                if *remaining <= 0 {
+                    outcome = Outcome::Blocked(pdb.name_any());
+                    break;
+                }
+
+                *remaining -= 1;
+            }
+
+            Plan {
+                pod: pod.clone(),
+                warning: (outcome == Outcome::Evict).then(|| warning(pod)).flatten(),
+                outcome,
+            }
+        })
+        .collect()
+}
@@ -1,30 +1,53 @@
+pub mod command;
+pub mod debug;
+pub mod disk;
+pub mod env;
+pub mod forward;
+pub mod history;
+pub mod level;
+pub mod lifecycle;
+pub mod probe;
 pub mod proc;
-
-use std::{borrow::Borrow, cmp::Ordering, net::IpAddr, sync::Arc};
+pub mod pull;
+pub mod runtime;
+pub mod scheduling;
+pub mod security;
+pub mod spread;
+pub mod termination;
+
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
 
 use chrono::{TimeDelta, Utc};
 use k8s_openapi::api::core::v1::{
-    ContainerState, ContainerStateTerminated, ContainerStateWaiting, ContainerStatus, Pod,
+    ContainerState, ContainerStateTerminated, ContainerStateWaiting, ContainerStatus, Node, Pod,
     PodStatus,
 };
-use kube::ResourceExt;
+use kube::{api::ObjectMeta, Resource, ResourceExt};
 pub use proc::Proc;
-use ratatui::{
-    layout::Constraint,
-    widgets::{Cell, Row},
-};
+use ratatui::widgets::Row;
 
 use super::{
     age::Age,
     container::{Container, ContainerExt},
+    extended,
+    node::NodeExt,
+    store::Store,
     Compare, Filter,
 };
-use crate::widget::table;
+use crate::widget::{table, theme::Indicator};
 
 pub enum Phase {
     Pending,
     Running,
     Succeeded,
+    /// Being deleted, with how much of the grace period is left.
+    Terminating(TimeDelta),
     Unknown(String),
 }
 
@@ -48,11 +71,35 @@ impl std::fmt::Display for Phase {
             Phase::Pending => write!(f, "Pending"),
             Phase::Running => write!(f, "Running"),
             Phase::Succeeded => write!(f, "Succeeded"),
+            Phase::Terminating(remaining) if *remaining < TimeDelta::zero() => {
+                write!(f, "Terminating ({} overdue)", (-*remaining).to_age())
+            }
+            Phase::Terminating(remaining) => {
+                write!(f, "Terminating ({} left)", remaining.to_age())
+            }
             Phase::Unknown(s) => write!(f, "{s}"),
         }
     }
 }
 
+impl Phase {
+    pub fn indicator(&self) -> Indicator {
+        match self {
+            Phase::Pending | Phase::Terminating(_) => Indicator::Progressing,
+            Phase::Running | Phase::Succeeded => Indicator::Healthy,
+            Phase::Unknown(_) => Indicator::Unhealthy,
+        }
+    }
+
+    pub fn style(&self, style: &table::RowStyle) -> ratatui::style::Style {
+        match self {
+            Phase::Pending | Phase::Running | Phase::Terminating(_) => style.normal,
+            Phase::Succeeded => style.healthy,
+            Phase::Unknown(_) => style.unhealthy,
+        }
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 pub trait PodExt {
     fn age(&self) -> TimeDelta;
@@ -61,6 +108,8 @@ pub trait PodExt {
     fn status(&self) -> Phase;
     fn containers(&self, filter: Option<String>) -> Vec<Container>;
     fn ip(&self) -> Option<IpAddr>;
+    fn priority(&self) -> String;
+    fn nominated_node(&self) -> Option<&str>;
 }
 
 impl PodExt for Pod {
@@ -125,6 +174,10 @@ impl PodExt for Pod {
     }
 
     fn status(&self) -> Phase {
+        if let Some(remaining) = termination::remaining(self) {
+            return Phase::Terminating(remaining);
+        }
+
         let Some(status) = &self.status else {
             return Some(String::new()).borrow().into();
         };
@@ -222,45 +275,60 @@ impl PodExt for Pod {
 
         pod_ip.parse().ok()
     }
+
+    /// The pod's priority, along with the class it came from. Admission
+    /// resolves the class into a value, pods without one get the global default
+    /// or 0.
+    fn priority(&self) -> String {
+        let spec = self.spec.as_ref();
+        let value = spec.and_then(|spec| spec.priority).unwrap_or_default();
+
+        match spec.and_then(|spec| spec.priority_class_name.as_ref()) {
+            Some(class) => format!("{value} ({class})"),
+            None => value.to_string(),
+        }
+    }
+
+    /// The node the scheduler preempted pods on to make room for this one. It
+    /// is only set while the pod is waiting for the victims to go away.
+    fn nominated_node(&self) -> Option<&str> {
+        self.status
+            .as_ref()
+            .and_then(|status| status.nominated_node_name.as_deref())
+            .filter(|_| {
+                self.spec
+                    .as_ref()
+                    .map_or(true, |spec| spec.node_name.is_none())
+            })
+    }
 }
 
 impl table::Row for Arc<Pod> {
-    fn header<'a>() -> Option<Row<'a>> {
-        Some(Row::new(vec![
-            Cell::from("Namespace"),
-            Cell::from("Name"),
-            Cell::from("Ready"),
-            Cell::from("Status"),
-            Cell::from("Restarts"),
-            Cell::from("Age"),
-        ]))
+    fn columns() -> Vec<&'static str> {
+        vec!["Namespace", "Name", "Ready", "Status", "Restarts", "Age"]
     }
 
-    fn constraints() -> Vec<Constraint> {
+    fn cells(&self) -> Vec<String> {
         vec![
-            Constraint::Max(20),
-            Constraint::Min(10),
-            Constraint::Max(10),
-            Constraint::Max(10),
-            Constraint::Max(10),
-            Constraint::Max(10),
-        ]
-    }
-
-    fn row(&self, style: &table::RowStyle) -> Row {
-        Row::new(vec![
             self.namespace().unwrap_or_default(),
             self.name_any(),
             self.ready(),
-            self.status().to_string(),
+            self.status().indicator().label(self.status()),
             self.restarts(),
             self.age().to_age(),
-        ])
-        .style(match self.status() {
-            Phase::Pending | Phase::Running => style.normal,
-            Phase::Succeeded => style.healthy,
-            Phase::Unknown(_) => style.unhealthy,
-        })
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(self.status().style(style))
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.as_ref().meta())
     }
 }
 
@@ -291,4 +359,231 @@ impl table::Items for Arc<Pod> {
     fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
         self.containers(filter)
     }
+
+    // GPUs, huge pages and the like go right before the age, only for pods that
+    // have any.
+    fn columns(&self) -> Vec<&'static str> {
+        let mut columns = <Container as table::Row>::columns();
+
+        if extended::requested(self) {
+            columns.insert(columns.len() - 1, "Extended");
+        }
+
+        columns
+    }
+
+    // This is a copy of the pod as it was, it never changes.
+    fn revision(&self) -> Option<u64> {
+        Some(0)
+    }
+}
+
+/// How pods are grouped in a table. Grouping keeps pods with the same key next
+/// to each other, which makes it easy to spot everything landing in one place.
+#[derive(Clone, Copy, Default)]
+pub enum Grouping {
+    #[default]
+    None,
+    Zone,
+    Node,
+}
+
+impl Grouping {
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Zone,
+            Self::Zone => Self::Node,
+            Self::Node => Self::None,
+        }
+    }
+}
+
+impl std::fmt::Display for Grouping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Zone => write!(f, "zone"),
+            Self::Node => write!(f, "node"),
+        }
+    }
+}
+
+/// A pod along with where it is running. The zone comes from the node, which
+/// isn't something the pod knows about itself.
+pub struct Placed {
+    pub pod: Arc<Pod>,
+    pub node: String,
+    pub zone: String,
+    /// Sparkline for the trend column, when it is being shown.
+    pub trend: Option<String>,
+    /// Whether the priority column is being shown, see `Pods::wide`.
+    pub wide: bool,
+}
+
+impl Placed {
+    pub fn group(&self, grouping: Grouping) -> &str {
+        match grouping {
+            Grouping::None => "",
+            Grouping::Zone => self.zone.as_str(),
+            Grouping::Node => self.node.as_str(),
+        }
+    }
+}
+
+impl table::Row for Placed {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Namespace",
+            "Name",
+            "Ready",
+            "Status",
+            "Restarts",
+            "Node",
+            "Zone",
+            "Age",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let mut cells = self.pod.cells();
+        let age = cells.pop().unwrap_or_default();
+
+        // A pending pod that has preempted others shows where it's going to
+        // land, it isn't on that node until the victims have gone.
+        let node = match self.pod.nominated_node() {
+            Some(nominated) => format!("{nominated} (nominated)"),
+            None => self.node.clone(),
+        };
+
+        cells.extend([node, self.zone.clone()]);
+
+        if self.wide {
+            cells.push(self.pod.priority());
+        }
+
+        cells.extend(self.trend.clone());
+        cells.push(age);
+
+        cells
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(self.pod.status().style(style))
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.pod.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.pod.as_ref().meta())
+    }
+}
+
+/// Pods joined with the nodes they're running on.
+#[allow(clippy::struct_field_names)]
+pub struct Pods {
+    pub pods: Arc<Store<Pod>>,
+    pub nodes: Arc<Store<Node>>,
+    pub grouping: Mutex<Grouping>,
+    pub wide: Mutex<bool>,
+    pub history: Arc<history::History>,
+}
+
+impl Pods {
+    pub fn grouping(&self) -> Grouping {
+        *self.grouping.lock().unwrap()
+    }
+
+    pub fn set_grouping(&self, grouping: Grouping) {
+        *self.grouping.lock().unwrap() = grouping;
+    }
+
+    /// Whether the columns that are only occasionally useful, such as the
+    /// priority, are shown.
+    pub fn wide(&self) -> bool {
+        *self.wide.lock().unwrap()
+    }
+
+    pub fn set_wide(&self, wide: bool) {
+        *self.wide.lock().unwrap() = wide;
+    }
+}
+
+impl table::Items for Arc<Pods> {
+    type Item = Placed;
+
+    fn error(&self) -> Option<String> {
+        self.pods.error()
+    }
+
+    fn retry(&self) {
+        self.pods.retry();
+    }
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let zones: HashMap<String, String> = self
+            .nodes
+            .snapshot()
+            .iter()
+            .filter_map(|node| Some((node.name_any(), node.zone()?.to_string())))
+            .collect();
+        let wide = self.wide();
+
+        let mut items: Vec<_> = self
+            .pods
+            .items(filter)
+            .into_iter()
+            .map(|pod| {
+                let node = pod
+                    .spec
+                    .as_ref()
+                    .and_then(|spec| spec.node_name.clone())
+                    .unwrap_or_default();
+
+                Placed {
+                    zone: zones.get(&node).cloned().unwrap_or_default(),
+                    trend: self.history.sparkline(&pod),
+                    wide,
+                    node,
+                    pod,
+                }
+            })
+            .collect();
+
+        // The store has already sorted the pods, a stable sort keeps that order
+        // within each group.
+        let grouping = self.grouping();
+        items.sort_by(|a, b| a.group(grouping).cmp(b.group(grouping)));
+
+        items
+    }
+
+    // The priority and trend columns go right before the age, when they're
+    // shown.
+    fn columns(&self) -> Vec<&'static str> {
+        let mut columns = <Placed as table::Row>::columns();
+
+        if self.wide() {
+            columns.insert(columns.len() - 1, "Priority");
+        }
+
+        if let Some(trend) = self.history.trend().column() {
+            columns.insert(columns.len() - 1, trend);
+        }
+
+        columns
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(table::revision([
+            self.pods.generation(),
+            self.nodes.generation(),
+            self.grouping() as u64,
+            u64::from(self.wide()),
+            self.history.generation(),
+            self.history.trend() as u64,
+        ]))
+    }
 }
@@ -0,0 +1,108 @@
+use eyre::Result;
+use k8s_openapi::api::core::v1::{self, Pod};
+
+use super::{
+    termination::{self, Hook},
+    Proc,
+};
+use crate::resources::container::Container;
+
+/// How a container is started, as far as the pod's spec says. Anything left
+/// out comes from the image's config, which the API server never sees.
+pub struct Invocation {
+    pub container: String,
+    pub init: bool,
+    pub image: String,
+    /// Digest the kubelet resolved the image to, for looking up the image's
+    /// config with something like `crane config`.
+    pub image_id: Option<String>,
+    pub command: Option<Vec<String>>,
+    pub args: Option<Vec<String>>,
+    pub working_dir: Option<String>,
+    pub hooks: Vec<Hook>,
+    pub running: bool,
+}
+
+impl Invocation {
+    fn new(pod: &Pod, container: &v1::Container, init: bool) -> Self {
+        let status = pod.status.as_ref().and_then(|status| {
+            if init {
+                status.init_container_statuses.as_ref()
+            } else {
+                status.container_statuses.as_ref()
+            }
+            .and_then(|statuses| statuses.iter().find(|s| s.name == container.name))
+        });
+
+        Self {
+            container: container.name.clone(),
+            init,
+            image: container.image.clone().unwrap_or_default(),
+            image_id: status
+                .map(|status| status.image_id.clone())
+                .filter(|id| !id.is_empty()),
+            command: container.command.clone().filter(|cmd| !cmd.is_empty()),
+            args: container.args.clone().filter(|args| !args.is_empty()),
+            working_dir: container.working_dir.clone(),
+            hooks: termination::hooks(container),
+            running: status
+                .and_then(|status| status.state.as_ref())
+                .is_some_and(|state| state.running.is_some()),
+        }
+    }
+}
+
+/// Every container in the pod, init containers first since that is the order
+/// they start in.
+pub fn all(pod: &Pod) -> Vec<Invocation> {
+    let Some(spec) = pod.spec.as_ref() else {
+        return Vec::new();
+    };
+
+    spec.init_containers
+        .iter()
+        .flatten()
+        .map(|container| Invocation::new(pod, container, true))
+        .chain(
+            spec.containers
+                .iter()
+                .map(|container| Invocation::new(pod, container, false)),
+        )
+        .collect()
+}
+
+/// What the container's first process was actually started with, read from
+/// `/proc/1/cmdline`. This is the spec merged with the image's entrypoint and
+/// command, which is the only place the two are ever put together. It needs
+/// `cat` in the container, so distroless images won't have it.
+pub async fn effective(client: kube::Client, container: Container) -> Result<Vec<String>> {
+    let (out, _) = Proc::new(container)
+        .exec(client, vec!["cat", "/proc/1/cmdline"])
+        .await?;
+
+    Ok(out
+        .split(|b| *b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).to_string())
+        .collect())
+}
+
+/// Join arguments into something that can be pasted into a shell and run the
+/// same way.
+pub fn quote(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            let safe = !arg.is_empty()
+                && arg
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c));
+
+            if safe {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
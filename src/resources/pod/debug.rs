@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::{
+    Capabilities, Container, EphemeralContainer, Pod, PodSpec, SecurityContext,
+};
+use kube::{api::ObjectMeta, ResourceExt};
+
+pub static COPY_OF_ANNOTATION: &str = "kty.dev/copy-of";
+
+/// Ephemeral containers attached for network debugging are named with this,
+/// which is how an existing one gets found again.
+pub static NETWORK_PREFIX: &str = "kty-netshoot-";
+
+/// For pods that only ever sleep, such as copies and debug deployments.
+/// There's nothing to wait for, a grace period only makes delete slow.
+pub static GRACE_PERIOD: i64 = 0;
+
+/// A standalone copy of `pod` that can be exec'd into without touching the
+/// original. It has none of the original's labels, so that it isn't picked up
+/// by services or counted by whatever owns the original, and every container
+/// sleeps instead of running its command. Probes and lifecycle hooks are
+/// removed because they would expect the usual process to be running.
+pub fn copy(pod: &Pod) -> Pod {
+    let spec = pod.spec.clone().unwrap_or_default();
+
+    let containers = spec
+        .containers
+        .into_iter()
+        .map(|mut container| {
+            container.command = Some(vec!["sleep".to_string(), "infinity".to_string()]);
+            container.args = None;
+            container.liveness_probe = None;
+            container.readiness_probe = None;
+            container.startup_probe = None;
+            container.lifecycle = None;
+
+            container
+        })
+        .collect();
+
+    Pod {
+        metadata: ObjectMeta {
+            generate_name: Some(format!("{}-debug-", pod.name_any())),
+            namespace: pod.namespace(),
+            labels: Some(BTreeMap::from([(
+                "kty.dev/temporary".to_string(),
+                "true".to_string(),
+            )])),
+            annotations: Some(BTreeMap::from([(
+                COPY_OF_ANNOTATION.to_string(),
+                pod.name_any(),
+            )])),
+            ..Default::default()
+        },
+        spec: Some(PodSpec {
+            containers,
+            // Leaving it up to the scheduler keeps the copy from adding to the
+            // load of a node that might be the reason the original is unhappy.
+            node_name: None,
+            ephemeral_containers: None,
+            restart_policy: Some("Never".to_string()),
+            termination_grace_period_seconds: Some(GRACE_PERIOD),
+            ..spec
+        }),
+        status: None,
+    }
+}
+
+/// An ephemeral container running `image` that can be attached to `pod`.
+/// Every container in a pod shares the same network namespace, so anything run
+/// in it sees the pod's interfaces and sockets. It targets the first container
+/// so that its processes show up as well, which only works when the runtime
+/// supports it. `NET_ADMIN` and `NET_RAW` are what `tcpdump` and friends need.
+pub fn network(pod: &Pod, image: &str, name: String) -> EphemeralContainer {
+    EphemeralContainer {
+        name,
+        image: Some(image.to_string()),
+        // The shell is exec'd separately, this only keeps the container around
+        // so that it can be exec'd into again.
+        command: Some(vec!["sleep".to_string(), "infinity".to_string()]),
+        target_container_name: pod
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.containers.first())
+            .map(|container| container.name.clone()),
+        security_context: Some(SecurityContext {
+            capabilities: Some(Capabilities {
+                add: Some(vec!["NET_ADMIN".to_string(), "NET_RAW".to_string()]),
+                drop: None,
+            }),
+            ..Default::default()
+        }),
+        stdin: Some(true),
+        tty: Some(true),
+        ..Default::default()
+    }
+}
+
+/// An ephemeral container for network debugging that is already running in
+/// `pod` with `image`, there's no need to add another one.
+pub fn running_network(pod: &Pod, image: &str) -> Option<Container> {
+    let running: Vec<_> = pod
+        .status
+        .as_ref()
+        .and_then(|status| status.ephemeral_container_statuses.as_ref())
+        .into_iter()
+        .flatten()
+        .filter(|status| {
+            status
+                .state
+                .as_ref()
+                .is_some_and(|state| state.running.is_some())
+        })
+        .map(|status| status.name.as_str())
+        .collect();
+
+    pod.spec
+        .as_ref()
+        .and_then(|spec| spec.ephemeral_containers.as_ref())
+        .into_iter()
+        .flatten()
+        .find(|container| {
+            container.name.starts_with(NETWORK_PREFIX)
+                && container.image.as_deref() == Some(image)
+                && running.contains(&container.name.as_str())
+        })
+        .map(|container| Container {
+            name: container.name.clone(),
+            image: container.image.clone(),
+            ..Default::default()
+        })
+}
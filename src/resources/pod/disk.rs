@@ -0,0 +1,171 @@
+use eyre::{eyre, Result};
+use k8s_openapi::api::core::v1::Pod;
+
+use super::Proc;
+use crate::resources::container::{Container, ContainerExt};
+
+// Everything is collected with a single exec. `df` covers every mount, `du`
+// breaks down the root filesystem, which is where the writable layer lives.
+// `-x` keeps `du` from wandering into the mounts `df` already reported and
+// both `-P` and `-d` work with busybox as well as coreutils.
+static SCRIPT: &str = "df -kP; echo '---'; du -kx -d 1 / 2>/dev/null; exit 0";
+
+// Mounts that never take up any space worth looking at.
+static PSEUDO: &[&str] = &["/proc", "/sys", "/dev"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kind {
+    /// The container's root filesystem: the image plus its writable layer.
+    Root,
+    /// A mount backed by one of the pod's volumes.
+    Volume(String),
+    /// Any other mount, such as the ones for `/etc/hosts` or secrets.
+    Mount,
+    /// A top level directory on the root filesystem.
+    Directory,
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Root => write!(f, "root"),
+            Kind::Volume(name) => write!(f, "volume {name}"),
+            Kind::Mount => write!(f, "mount"),
+            Kind::Directory => write!(f, "dir"),
+        }
+    }
+}
+
+/// Space taken up at a path, in KiB. Only mounts have a size, directories
+/// share whatever the root filesystem has.
+#[derive(Clone, Debug)]
+pub struct Usage {
+    pub path: String,
+    pub kind: Kind,
+    pub used: u64,
+    pub size: Option<u64>,
+    pub available: Option<u64>,
+}
+
+impl Usage {
+    /// How full the filesystem is, as a percentage.
+    pub fn percent(&self) -> Option<u64> {
+        self.size
+            .filter(|size| *size > 0)
+            .map(|size| self.used * 100 / size)
+    }
+}
+
+/// Collect disk usage for a running container. It needs `sh`, `df` and `du`
+/// in the image, so distroless containers won't work.
+pub async fn collect(client: kube::Client, pod: &Pod, container: Container) -> Result<Vec<Usage>> {
+    let volumes = volumes(pod, &container.name_any());
+
+    let (out, err) = Proc::new(container)
+        .exec(client, vec!["sh", "-c", SCRIPT])
+        .await?;
+
+    let out = String::from_utf8_lossy(&out);
+
+    let Some((df, du)) = out.split_once("---\n") else {
+        return Err(eyre!(
+            "unexpected output: {}",
+            String::from_utf8_lossy(&err).trim()
+        ));
+    };
+
+    let mut usage = mounts(df, &volumes);
+
+    if usage.is_empty() {
+        return Err(eyre!(
+            "df didn't report anything: {}",
+            String::from_utf8_lossy(&err).trim()
+        ));
+    }
+
+    usage.extend(directories(du, &usage));
+
+    Ok(usage)
+}
+
+// Where each of the container's volumes is mounted, by path.
+fn volumes(pod: &Pod, container: &str) -> Vec<(String, String)> {
+    pod.spec
+        .iter()
+        .flat_map(|spec| {
+            spec.init_containers
+                .iter()
+                .flatten()
+                .chain(&spec.containers)
+        })
+        .filter(|spec| spec.name == container)
+        .flat_map(|spec| spec.volume_mounts.iter().flatten())
+        .map(|mount| (mount.mount_path.clone(), mount.name.clone()))
+        .collect()
+}
+
+// `df -kP` output: a header and then `filesystem blocks used available
+// capacity mountpoint`, where the mountpoint is everything that's left.
+fn mounts(df: &str, volumes: &[(String, String)]) -> Vec<Usage> {
+    df.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+
+            let _filesystem = fields.next()?;
+            let size: u64 = fields.next()?.parse().ok()?;
+            let used: u64 = fields.next()?.parse().ok()?;
+            let available: u64 = fields.next()?.parse().ok()?;
+            let _capacity = fields.next()?;
+            let path = fields.collect::<Vec<_>>().join(" ");
+
+            let pseudo = PSEUDO
+                .iter()
+                .any(|prefix| path == *prefix || path.starts_with(&format!("{prefix}/")));
+
+            if size == 0 || path.is_empty() || pseudo {
+                return None;
+            }
+
+            let kind = if path == "/" {
+                Kind::Root
+            } else {
+                volumes
+                    .iter()
+                    .find(|(mount, _)| *mount == path)
+                    .map_or(Kind::Mount, |(_, name)| Kind::Volume(name.clone()))
+            };
+
+            Some(Usage {
+                path,
+                kind,
+                used,
+                size: Some(size),
+                available: Some(available),
+            })
+        })
+        .collect()
+}
+
+// `du -k` output: `size<tab>path`. The total for `/` itself is already
+// covered by the root mount.
+fn directories(du: &str, mounts: &[Usage]) -> Vec<Usage> {
+    du.lines()
+        .filter_map(|line| {
+            let (used, path) = line.split_once(char::is_whitespace)?;
+            let path = path.trim();
+
+            if path == "/" || mounts.iter().any(|mount| mount.path == path) {
+                return None;
+            }
+
+            Some(Usage {
+                path: path.to_string(),
+                kind: Kind::Directory,
+                used: used.parse().ok()?,
+                size: None,
+                available: None,
+            })
+        })
+        .collect()
+}
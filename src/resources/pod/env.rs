@@ -0,0 +1,437 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use k8s_openapi::api::core::v1::{ConfigMap, Container, EnvVar, Pod, Secret};
+use kube::ResourceExt;
+
+/// Where the value of a variable came from.
+#[derive(Clone, Debug)]
+pub enum Source {
+    Value,
+    ConfigMap { name: String, key: String },
+    Secret { name: String, key: String },
+    Field(String),
+    Resource(String),
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Value => write!(f, "value"),
+            Source::ConfigMap { name, key } => write!(f, "configmap/{name}:{key}"),
+            Source::Secret { name, key } => write!(f, "secret/{name}:{key}"),
+            Source::Field(path) => write!(f, "field {path}"),
+            Source::Resource(resource) => write!(f, "resource {resource}"),
+        }
+    }
+}
+
+/// A variable as the container ends up seeing it. `Err` explains why there's
+/// no value, which is also what would stop the container from starting.
+#[derive(Clone, Debug)]
+pub struct Var {
+    pub name: String,
+    pub value: Result<String, String>,
+    pub source: Source,
+}
+
+impl Var {
+    pub fn is_secret(&self) -> bool {
+        matches!(self.source, Source::Secret { .. })
+    }
+}
+
+/// The objects variables are looked up in. `None` is an object that doesn't
+/// exist, `Err` one that couldn't be read (eg. RBAC doesn't allow it).
+#[derive(Default)]
+pub struct Refs {
+    pub config_maps: BTreeMap<String, Result<Option<ConfigMap>, String>>,
+    pub secrets: BTreeMap<String, Result<Option<Secret>, String>>,
+}
+
+impl Refs {
+    fn config_map(&self, name: &str, key: &str) -> Result<Option<String>, String> {
+        match self.config_maps.get(name) {
+            Some(Ok(Some(cm))) => Ok(cm
+                .data
+                .as_ref()
+                .and_then(|data| data.get(key).cloned())
+                .or_else(|| {
+                    cm.binary_data
+                        .as_ref()
+                        .and_then(|data| data.get(key))
+                        .map(|value| String::from_utf8_lossy(&value.0).to_string())
+                })),
+            Some(Ok(None)) | None => Err(format!("configmap {name} not found")),
+            Some(Err(err)) => Err(err.clone()),
+        }
+    }
+
+    fn secret(&self, name: &str, key: &str) -> Result<Option<String>, String> {
+        match self.secrets.get(name) {
+            Some(Ok(Some(secret))) => Ok(secret
+                .data
+                .as_ref()
+                .and_then(|data| data.get(key))
+                .map(|value| String::from_utf8_lossy(&value.0).to_string())),
+            Some(Ok(None)) | None => Err(format!("secret {name} not found")),
+            Some(Err(err)) => Err(err.clone()),
+        }
+    }
+}
+
+fn containers(pod: &Pod) -> impl Iterator<Item = &Container> {
+    pod.spec.iter().flat_map(|spec| {
+        spec.init_containers
+            .iter()
+            .flatten()
+            .chain(spec.containers.iter())
+    })
+}
+
+/// Every `ConfigMap` and `Secret` the pod's variables refer to, so that they
+/// can be fetched before resolving.
+pub fn references(pod: &Pod) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut config_maps = BTreeSet::new();
+    let mut secrets = BTreeSet::new();
+
+    for container in containers(pod) {
+        for from in container.env_from.iter().flatten() {
+            if let Some(name) = from.config_map_ref.as_ref().and_then(|r| r.name.clone()) {
+                config_maps.insert(name);
+            }
+
+            if let Some(name) = from.secret_ref.as_ref().and_then(|r| r.name.clone()) {
+                secrets.insert(name);
+            }
+        }
+
+        for var in container.env.iter().flatten() {
+            let Some(from) = var.value_from.as_ref() else {
+                continue;
+            };
+
+            if let Some(name) = from
+                .config_map_key_ref
+                .as_ref()
+                .and_then(|r| r.name.clone())
+            {
+                config_maps.insert(name);
+            }
+
+            if let Some(name) = from.secret_key_ref.as_ref().and_then(|r| r.name.clone()) {
+                secrets.insert(name);
+            }
+        }
+    }
+
+    (config_maps, secrets)
+}
+
+// Fields that can be used with `fieldRef`. Labels and annotations use their
+// own syntax, eg. `metadata.labels['app']`.
+fn field(pod: &Pod, path: &str) -> Result<String, String> {
+    let status = pod.status.as_ref();
+    let spec = pod.spec.as_ref();
+
+    let subscript = |prefix: &str, map: &BTreeMap<String, String>| {
+        path.strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix("']"))
+            .map(|key| map.get(key).cloned().unwrap_or_default())
+    };
+
+    if let Some(value) = subscript("metadata.labels['", pod.labels()) {
+        return Ok(value);
+    }
+
+    if let Some(value) = subscript("metadata.annotations['", pod.annotations()) {
+        return Ok(value);
+    }
+
+    let value = match path {
+        "metadata.name" => Some(pod.name_any()),
+        "metadata.namespace" => pod.namespace(),
+        "metadata.uid" => pod.uid(),
+        "spec.nodeName" => spec.and_then(|spec| spec.node_name.clone()),
+        "spec.serviceAccountName" => spec.and_then(|spec| spec.service_account_name.clone()),
+        "status.hostIP" => status.and_then(|status| status.host_ip.clone()),
+        "status.podIP" => status.and_then(|status| status.pod_ip.clone()),
+        "status.podIPs" => status.and_then(|status| {
+            status.pod_ips.as_ref().map(|ips| {
+                ips.iter()
+                    .filter_map(|ip| ip.ip.clone())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+        }),
+        _ => return Err(format!("unsupported field {path}")),
+    };
+
+    // Things like the pod IP aren't known until it has been scheduled and
+    // started, the container gets whatever is known at that point.
+    Ok(value.unwrap_or_default())
+}
+
+fn resource(container: &Container, name: &str, divisor: Option<&str>) -> Result<String, String> {
+    let resources = container.resources.as_ref();
+
+    let value = match name.split_once('.') {
+        Some(("limits", resource)) => resources
+            .and_then(|r| r.limits.as_ref())
+            .and_then(|limits| limits.get(resource)),
+        Some(("requests", resource)) => resources
+            .and_then(|r| r.requests.as_ref())
+            .and_then(|requests| requests.get(resource)),
+        _ => return Err(format!("unsupported resource {name}")),
+    };
+
+    // Without a limit, the kubelet uses what the node has allocatable, which
+    // isn't something the pod knows.
+    let Some(value) = value else {
+        return Ok(if name.starts_with("limits.") {
+            "node allocatable".to_string()
+        } else {
+            "0".to_string()
+        });
+    };
+
+    Ok(match divisor {
+        Some(divisor) if divisor != "1" => format!("{} (divided by {divisor})", value.0),
+        _ => value.0.clone(),
+    })
+}
+
+// `$(VAR)` is replaced with the value of a variable defined before it, `$$` is
+// an escaped `$`. Anything that can't be expanded is left as is, which is what
+// the kubelet does too.
+fn expand(value: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(idx) = rest.find('$') {
+        out.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        if let Some(after) = rest.strip_prefix("$$") {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+
+        let expanded = rest
+            .strip_prefix("$(")
+            .and_then(|after| after.split_once(')'))
+            .and_then(|(name, after)| vars.get(name).map(|value| (value, after)));
+
+        if let Some((value, after)) = expanded {
+            out.push_str(value);
+            rest = after;
+        } else {
+            out.push('$');
+            rest = &rest[1..];
+        }
+    }
+
+    out.push_str(rest);
+
+    out
+}
+
+fn resolve_var(pod: &Pod, container: &Container, var: &EnvVar, refs: &Refs) -> Option<Var> {
+    let name = var.name.clone();
+
+    let Some(from) = var.value_from.as_ref() else {
+        return Some(Var {
+            name,
+            value: Ok(var.value.clone().unwrap_or_default()),
+            source: Source::Value,
+        });
+    };
+
+    let missing =
+        |optional: Option<bool>, source: Source, value: Result<Option<String>, String>| {
+            match value {
+                Ok(Some(value)) => Some(Var {
+                    name: name.clone(),
+                    value: Ok(value),
+                    source,
+                }),
+                // Optional references that aren't there leave the variable unset.
+                Ok(None) | Err(_) if optional == Some(true) => None,
+                Ok(None) => Some(Var {
+                    name: name.clone(),
+                    value: Err(format!("{source} does not exist")),
+                    source,
+                }),
+                Err(err) => Some(Var {
+                    name: name.clone(),
+                    value: Err(err),
+                    source,
+                }),
+            }
+        };
+
+    if let Some(key) = from.config_map_key_ref.as_ref() {
+        let cm = key.name.clone().unwrap_or_default();
+
+        return missing(
+            key.optional,
+            Source::ConfigMap {
+                name: cm.clone(),
+                key: key.key.clone(),
+            },
+            refs.config_map(&cm, &key.key),
+        );
+    }
+
+    if let Some(key) = from.secret_key_ref.as_ref() {
+        let secret = key.name.clone().unwrap_or_default();
+
+        return missing(
+            key.optional,
+            Source::Secret {
+                name: secret.clone(),
+                key: key.key.clone(),
+            },
+            refs.secret(&secret, &key.key),
+        );
+    }
+
+    if let Some(selector) = from.field_ref.as_ref() {
+        return Some(Var {
+            name,
+            value: field(pod, &selector.field_path),
+            source: Source::Field(selector.field_path.clone()),
+        });
+    }
+
+    if let Some(selector) = from.resource_field_ref.as_ref() {
+        return Some(Var {
+            name,
+            value: resource(
+                container,
+                &selector.resource,
+                selector.divisor.as_ref().map(|d| d.0.as_str()),
+            ),
+            source: Source::Resource(selector.resource.clone()),
+        });
+    }
+
+    Some(Var {
+        name,
+        value: Err("valueFrom has no source".to_string()),
+        source: Source::Value,
+    })
+}
+
+fn set(vars: &mut Vec<Var>, var: Var) {
+    match vars.iter_mut().find(|v| v.name == var.name) {
+        Some(existing) => *existing = var,
+        None => vars.push(var),
+    }
+}
+
+/// The variables `container` starts with, in the order the kubelet builds
+/// them: everything from `envFrom` first, then `env`, with later definitions
+/// replacing earlier ones of the same name.
+pub fn resolve(pod: &Pod, container: &Container, refs: &Refs) -> Vec<Var> {
+    let mut vars: Vec<Var> = Vec::new();
+
+    for from in container.env_from.iter().flatten() {
+        let prefix = from.prefix.as_deref().unwrap_or_default();
+
+        #[allow(clippy::type_complexity)]
+        let (name, values, optional, source): (
+            String,
+            Result<BTreeMap<String, String>, String>,
+            Option<bool>,
+            fn(String, String) -> Source,
+        ) = if let Some(r) = from.config_map_ref.as_ref() {
+            let name = r.name.clone().unwrap_or_default();
+
+            let values = match refs.config_maps.get(&name) {
+                Some(Ok(Some(cm))) => Ok(cm.data.clone().unwrap_or_default()),
+                Some(Err(err)) => Err(err.clone()),
+                _ => Err(format!("configmap {name} not found")),
+            };
+
+            (name, values, r.optional, |name: String, key: String| {
+                Source::ConfigMap { name, key }
+            })
+        } else if let Some(r) = from.secret_ref.as_ref() {
+            let name = r.name.clone().unwrap_or_default();
+
+            let values = match refs.secrets.get(&name) {
+                Some(Ok(Some(secret))) => Ok(secret
+                    .data
+                    .iter()
+                    .flatten()
+                    .map(|(k, v)| (k.clone(), String::from_utf8_lossy(&v.0).to_string()))
+                    .collect()),
+                Some(Err(err)) => Err(err.clone()),
+                _ => Err(format!("secret {name} not found")),
+            };
+
+            (name, values, r.optional, |name: String, key: String| {
+                Source::Secret { name, key }
+            })
+        } else {
+            continue;
+        };
+
+        match values {
+            Ok(values) => {
+                for (key, value) in values {
+                    set(
+                        &mut vars,
+                        Var {
+                            name: format!("{prefix}{key}"),
+                            value: Ok(value),
+                            source: source(name.clone(), key),
+                        },
+                    );
+                }
+            }
+            Err(_) if optional == Some(true) => {}
+            Err(err) => set(
+                &mut vars,
+                Var {
+                    name: format!("{prefix}*"),
+                    value: Err(err),
+                    source: source(name, "*".to_string()),
+                },
+            ),
+        }
+    }
+
+    for var in container.env.iter().flatten() {
+        let Some(mut resolved) = resolve_var(pod, container, var, refs) else {
+            continue;
+        };
+
+        // Only literal values are expanded, the rest are used as is.
+        if let (Source::Value, Ok(value)) = (&resolved.source, &resolved.value) {
+            let known: HashMap<_, _> = vars
+                .iter()
+                .filter_map(|v| {
+                    v.value
+                        .as_ref()
+                        .ok()
+                        .map(|value| (v.name.clone(), value.clone()))
+                })
+                .collect();
+
+            resolved.value = Ok(expand(value, &known));
+        }
+
+        set(&mut vars, resolved);
+    }
+
+    vars
+}
+
+/// Each container, init containers first, with its variables.
+pub fn all(pod: &Pod, refs: &Refs) -> Vec<(String, Vec<Var>)> {
+    containers(pod)
+        .map(|container| (container.name.clone(), resolve(pod, container, refs)))
+        .collect()
+}
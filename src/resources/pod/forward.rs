@@ -0,0 +1,63 @@
+use std::ops::{Deref, DerefMut};
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::Pod;
+use kube::{api::Portforwarder, Api};
+use tokio::task::JoinHandle;
+
+/// A port-forward that gets torn down when it goes out of scope. Returning
+/// early with `?`, or the task using it being aborted, would otherwise leave
+/// the websocket to the kubelet open until the pod goes away.
+pub struct Forward(Portforwarder);
+
+impl Forward {
+    pub async fn new(api: &Api<Pod>, name: &str, ports: &[u16]) -> Result<Self> {
+        Ok(Self(api.portforward(name, ports).await?))
+    }
+}
+
+impl Deref for Forward {
+    type Target = Portforwarder;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Forward {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Drop for Forward {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// The task driving an HTTP connection over a forwarded port, stopped when
+/// the request is done with it, however that happens.
+pub struct Connection(JoinHandle<()>);
+
+impl Connection {
+    pub fn spawn<F, E>(conn: F) -> Self
+    where
+        F: std::future::Future<Output = Result<(), E>> + Send + 'static,
+        E: std::fmt::Display + Send + 'static,
+    {
+        // Whatever went wrong also fails the request, which is where it gets
+        // reported.
+        Self(tokio::spawn(async move {
+            if let Err(err) = conn.await {
+                tracing::debug!("forwarded connection closed: {err}");
+            }
+        }))
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
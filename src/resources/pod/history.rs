@@ -0,0 +1,261 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use eyre::{eyre, Result};
+use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::api::resource::Quantity};
+use kube::{
+    api::{Api, ApiResource, DynamicObject, GroupVersionKind, ListParams},
+    ResourceExt,
+};
+
+use crate::resources::{quantity, store::Store};
+
+static SPARKS: &[char] = &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// What the trend column of the pod table shows, if anything.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Trend {
+    #[default]
+    None,
+    Restarts,
+    Cpu,
+}
+
+impl Trend {
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Restarts,
+            Self::Restarts => Self::Cpu,
+            Self::Cpu => Self::None,
+        }
+    }
+
+    pub fn column(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Restarts => Some("Restart Trend"),
+            Self::Cpu => Some("CPU Trend"),
+        }
+    }
+}
+
+/// The last few samples of restarts and CPU usage for every pod, by
+/// `namespace/name`. Restarts are kept as totals, one more than there are
+/// samples to show, so that what gets shown is how many happened in between.
+#[derive(Default)]
+pub struct History {
+    trend: Mutex<Trend>,
+    restarts: Mutex<HashMap<String, VecDeque<f64>>>,
+    // `None` when the last attempt to get usage failed, usually because
+    // metrics-server isn't installed.
+    cpu: Mutex<Option<HashMap<String, VecDeque<f64>>>>,
+    generation: AtomicU64,
+}
+
+impl History {
+    pub fn trend(&self) -> Trend {
+        *self.trend.lock().unwrap()
+    }
+
+    pub fn set_trend(&self, trend: Trend) {
+        *self.trend.lock().unwrap() = trend;
+    }
+
+    /// Moves on every time a sample is taken.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Sparkline for the pod, for whatever `trend` currently is.
+    pub fn sparkline(&self, pod: &Pod) -> Option<String> {
+        let key = key(pod.namespace().as_deref(), &pod.name_any());
+
+        match self.trend() {
+            Trend::None => None,
+            Trend::Restarts => {
+                let restarts = self.restarts.lock().unwrap();
+
+                let deltas: Vec<_> = restarts
+                    .get(&key)
+                    .iter()
+                    .flat_map(|totals| {
+                        totals
+                            .iter()
+                            .zip(totals.iter().skip(1))
+                            .map(|(before, after)| (after - before).max(0.0))
+                    })
+                    .collect();
+
+                Some(sparkline(&deltas))
+            }
+            Trend::Cpu => match self.cpu.lock().unwrap().as_ref() {
+                Some(cpu) => Some(sparkline(
+                    &cpu.get(&key)
+                        .map(|samples| samples.iter().copied().collect::<Vec<_>>())
+                        .unwrap_or_default(),
+                )),
+                None => Some("n/a".to_string()),
+            },
+        }
+    }
+
+    fn restarts(&self, pods: &[Arc<Pod>], samples: usize) {
+        let mut restarts = self.restarts.lock().unwrap();
+
+        let current: HashMap<_, _> = pods
+            .iter()
+            .map(|pod| (key(pod.namespace().as_deref(), &pod.name_any()), total(pod)))
+            .collect();
+
+        restarts.retain(|key, _| current.contains_key(key));
+
+        for (key, total) in current {
+            push(restarts.entry(key).or_default(), total, samples + 1);
+        }
+    }
+
+    fn cpu(&self, usage: Option<HashMap<String, f64>>, samples: usize) {
+        let mut cpu = self.cpu.lock().unwrap();
+
+        let Some(usage) = usage else {
+            *cpu = None;
+
+            return;
+        };
+
+        let cpu = cpu.get_or_insert_with(HashMap::new);
+        cpu.retain(|key, _| usage.contains_key(key));
+
+        for (key, cores) in usage {
+            push(cpu.entry(key).or_default(), cores, samples);
+        }
+    }
+}
+
+fn key(namespace: Option<&str>, name: &str) -> String {
+    format!("{}/{name}", namespace.unwrap_or_default())
+}
+
+fn push(samples: &mut VecDeque<f64>, value: f64, max: usize) {
+    samples.push_back(value);
+
+    while samples.len() > max {
+        samples.pop_front();
+    }
+}
+
+// Restarts of every container in the pod, init containers included.
+fn total(pod: &Pod) -> f64 {
+    pod.status
+        .iter()
+        .flat_map(|status| {
+            status
+                .init_container_statuses
+                .iter()
+                .flatten()
+                .chain(status.container_statuses.iter().flatten())
+        })
+        .map(|status| f64::from(status.restart_count))
+        .sum()
+}
+
+/// Draw values as block characters, scaled so that the largest one is a full
+/// block. Anything at zero is the lowest block, so that an idle pod still
+/// shows up as having been sampled.
+pub fn sparkline(values: &[f64]) -> String {
+    let max = values.iter().copied().fold(0.0, f64::max);
+
+    values
+        .iter()
+        .map(|value| {
+            if max <= 0.0 {
+                return SPARKS[0];
+            }
+
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                clippy::cast_precision_loss
+            )]
+            let idx = ((value / max) * (SPARKS.len() - 1) as f64).round() as usize;
+
+            SPARKS[idx.min(SPARKS.len() - 1)]
+        })
+        .collect()
+}
+
+// CPU usage of every pod, in cores, from metrics-server. There's no type for
+// these in `k8s-openapi`, so they're fetched as dynamic objects.
+async fn usage(client: kube::Client, namespace: Option<&str>) -> Result<HashMap<String, f64>> {
+    let resource = ApiResource::from_gvk_with_plural(
+        &GroupVersionKind::gvk("metrics.k8s.io", "v1beta1", "PodMetrics"),
+        "pods",
+    );
+
+    let api: Api<DynamicObject> = match namespace {
+        Some(ns) => Api::namespaced_with(client, ns, &resource),
+        None => Api::all_with(client, &resource),
+    };
+
+    let list = api.list(&ListParams::default()).await?;
+
+    list.items
+        .iter()
+        .map(|metrics| {
+            let containers = metrics
+                .data
+                .get("containers")
+                .and_then(|containers| containers.as_array())
+                .ok_or_else(|| eyre!("metrics for {} have no containers", metrics.name_any()))?;
+
+            let cores = containers
+                .iter()
+                .filter_map(|container| container.pointer("/usage/cpu")?.as_str())
+                .filter_map(|cpu| quantity::parse(&Quantity(cpu.to_string())))
+                .sum();
+
+            Ok((
+                key(metrics.namespace().as_deref(), &metrics.name_any()),
+                cores,
+            ))
+        })
+        .collect()
+}
+
+/// Take a sample every `interval` for as long as the pod table is open.
+/// Restarts come from the pods that are already being watched, CPU usage is
+/// only asked for while it is being shown.
+pub async fn sample(
+    client: kube::Client,
+    namespace: Option<String>,
+    pods: Arc<Store<Pod>>,
+    history: Arc<History>,
+) {
+    let cfg = &crate::config::get().tables;
+
+    let mut interval = tokio::time::interval(Duration::from_secs(cfg.history_interval.max(1)));
+
+    loop {
+        interval.tick().await;
+
+        history.restarts(&pods.snapshot(), cfg.history);
+
+        if history.trend() == Trend::Cpu {
+            let usage = usage(client.clone(), namespace.as_deref())
+                .await
+                .inspect_err(|err| tracing::debug!("unable to get pod metrics: {err}"))
+                .ok();
+
+            history.cpu(usage, cfg.history);
+        }
+
+        history.generation.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,178 @@
+use std::str::FromStr;
+
+use bytes::Bytes;
+use eyre::{eyre, Result};
+use http::{header, Method, Request};
+use http_body_util::{BodyExt, Full};
+use hyper_util::rt::TokioIo;
+use k8s_openapi::api::core::v1::Pod;
+use kube::{Api, ResourceExt};
+use serde::Deserialize;
+
+use super::forward::{Connection, Forward};
+use crate::config;
+
+/// Containers declare where their log level can be changed with an annotation
+/// per container, such as `log-level.kty.dev/app: actuator:8080/actuator/loggers/ROOT`.
+pub static ANNOTATION_PREFIX: &str = "log-level.kty.dev/";
+
+pub static LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
+/// The shape of the admin endpoint, which decides how the level is sent.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, strum::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Flavor {
+    /// Spring Boot's `/actuator/loggers/{name}`, `POST`ing `configuredLevel`.
+    #[default]
+    Actuator,
+    /// zap's `AtomicLevel` handler, `PUT`ing `level`.
+    Zap,
+    /// `PUT` the level as the whole body.
+    Plain,
+}
+
+impl FromStr for Flavor {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "actuator" => Ok(Self::Actuator),
+            "zap" => Ok(Self::Zap),
+            "plain" => Ok(Self::Plain),
+            _ => Err(eyre!("{s} isn't one of actuator, zap or plain")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endpoint {
+    pub api: Flavor,
+    pub port: u16,
+    pub path: String,
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}{}", self.api, self.port, self.path)
+    }
+}
+
+// `[api:]port/path`, the api defaults to actuator.
+impl FromStr for Endpoint {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (api, rest) = match s.split_once(':') {
+            Some((api, rest)) => (api.parse()?, rest),
+            None => (Flavor::default(), s),
+        };
+
+        let (port, path) = rest.find('/').map_or((rest, "/"), |idx| rest.split_at(idx));
+
+        Ok(Self {
+            api,
+            port: port
+                .parse()
+                .map_err(|_| eyre!("{port} isn't a valid port in {s}"))?,
+            path: path.to_string(),
+        })
+    }
+}
+
+impl Endpoint {
+    fn request(&self, level: &str) -> Result<Request<Full<Bytes>>> {
+        let (method, content, body) = match self.api {
+            Flavor::Actuator => (
+                Method::POST,
+                "application/json",
+                serde_json::json!({ "configuredLevel": level.to_uppercase() }).to_string(),
+            ),
+            Flavor::Zap => (
+                Method::PUT,
+                "application/json",
+                serde_json::json!({ "level": level }).to_string(),
+            ),
+            Flavor::Plain => (Method::PUT, "text/plain", level.to_string()),
+        };
+
+        Ok(Request::builder()
+            .method(method)
+            .uri(self.path.as_str())
+            .header(header::HOST, "localhost")
+            .header(header::CONTENT_TYPE, content)
+            .body(Full::new(Bytes::from(body)))?)
+    }
+}
+
+/// Where the log level of `container` can be changed, from its annotation or
+/// otherwise the first matching entry in the config.
+pub fn endpoint(pod: &Pod, container: &str) -> Option<Endpoint> {
+    if let Some(value) = pod
+        .annotations()
+        .get(&format!("{ANNOTATION_PREFIX}{container}"))
+    {
+        return match value.parse() {
+            Ok(endpoint) => Some(endpoint),
+            Err(err) => {
+                tracing::debug!(pod = pod.name_any(), container, "invalid annotation: {err}");
+
+                None
+            }
+        };
+    }
+
+    let labels = pod.labels();
+
+    config::get()
+        .logs
+        .levels
+        .iter()
+        .find(|entry| {
+            entry
+                .container
+                .as_ref()
+                .map_or(true, |name| name == container)
+                && entry
+                    .labels
+                    .iter()
+                    .all(|(key, value)| labels.get(key) == Some(value))
+        })
+        .map(config::LogLevel::endpoint)
+}
+
+/// Change the log level through a port-forward to the pod, so that the admin
+/// endpoint doesn't need to be exposed anywhere else. Returns the response,
+/// which is usually empty.
+pub async fn set(
+    client: kube::Client,
+    pod: &Pod,
+    endpoint: &Endpoint,
+    level: &str,
+) -> Result<String> {
+    let api = Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default());
+
+    let mut forwarder = Forward::new(&api, &pod.name_any(), &[endpoint.port]).await?;
+    let stream = forwarder
+        .take_stream(endpoint.port)
+        .ok_or_else(|| eyre!("port {} is not available", endpoint.port))?;
+
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+    let conn = Connection::spawn(conn);
+
+    let resp = sender.send_request(endpoint.request(level)?).await?;
+    let status = resp.status();
+    let body = resp.into_body().collect().await?.to_bytes();
+
+    drop(sender);
+    drop(conn);
+    drop(forwarder);
+
+    let body = String::from_utf8_lossy(&body).trim().to_string();
+
+    if !status.is_success() {
+        return Err(eyre!("{} returned {status}: {body}", endpoint.path));
+    }
+
+    Ok(body)
+}
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::{Event, Pod};
+use kube::ResourceExt;
+
+use crate::resources::event::EventExt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display)]
+pub enum Kind {
+    Created,
+    Scheduled,
+    Pulling,
+    Pulled,
+    Started,
+    Ready,
+    #[strum(serialize = "Not Ready")]
+    NotReady,
+    Restarted,
+    #[strum(serialize = "Probe Failed")]
+    ProbeFailed,
+    Killed,
+    Warning,
+}
+
+/// Something that happened to a pod or one of its containers.
+pub struct Milestone {
+    pub at: DateTime<Utc>,
+    pub kind: Kind,
+    pub container: Option<String>,
+    pub detail: String,
+    /// Aggregated events happened this many times, the most recent being `at`.
+    pub count: i32,
+}
+
+impl Milestone {
+    pub fn is_warning(&self) -> bool {
+        matches!(
+            self.kind,
+            Kind::NotReady | Kind::Restarted | Kind::ProbeFailed | Kind::Killed | Kind::Warning
+        )
+    }
+}
+
+// Events about a container have `spec.containers{name}` as their field path.
+fn container(event: &Event) -> Option<String> {
+    let path = event.involved_object.field_path.as_deref()?;
+
+    path.split_once('{')
+        .and_then(|(_, rest)| rest.strip_suffix('}'))
+        .map(ToString::to_string)
+}
+
+fn from_event(event: &Event) -> Option<Milestone> {
+    let kind = match (event.reason.as_deref()?, event.type_.as_deref()) {
+        ("Scheduled", _) => Kind::Scheduled,
+        ("Pulling", _) => Kind::Pulling,
+        ("Pulled", _) => Kind::Pulled,
+        ("Started", _) => Kind::Started,
+        ("Unhealthy", _) => Kind::ProbeFailed,
+        ("Killing", _) => Kind::Killed,
+        (_, Some("Warning")) => Kind::Warning,
+        // `Created` is always immediately followed by `Started`, everything
+        // else isn't part of the pod's life.
+        _ => return None,
+    };
+
+    let detail = match kind {
+        Kind::Warning => format!(
+            "{}: {}",
+            event.reason.as_deref().unwrap_or_default(),
+            event.message.as_deref().unwrap_or_default()
+        ),
+        _ => event.message.clone().unwrap_or_default(),
+    };
+
+    Some(Milestone {
+        at: event.last_seen()?,
+        kind,
+        container: container(event),
+        detail,
+        count: event
+            .series
+            .as_ref()
+            .and_then(|series| series.count)
+            .or(event.count)
+            .unwrap_or(1),
+    })
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait LifecycleExt {
+    fn milestones(&self, events: &[Arc<Event>]) -> Vec<Milestone>;
+}
+
+impl LifecycleExt for Pod {
+    /// Everything known about the pod's life, oldest first. Events only stick
+    /// around for an hour by default, so the pod's status fills in what it can
+    /// once they're gone.
+    fn milestones(&self, events: &[Arc<Event>]) -> Vec<Milestone> {
+        let mut milestones: Vec<_> = events.iter().filter_map(|ev| from_event(ev)).collect();
+
+        let has = |milestones: &[Milestone], kind: Kind, container: Option<&str>| {
+            milestones
+                .iter()
+                .any(|m| m.kind == kind && m.container.as_deref() == container)
+        };
+
+        let status =
+            |at: DateTime<Utc>, kind: Kind, container: Option<&str>, detail: String| Milestone {
+                at,
+                kind,
+                container: container.map(ToString::to_string),
+                detail,
+                count: 1,
+            };
+
+        let mut derived = Vec::new();
+
+        if let Some(created) = self.creation_timestamp() {
+            derived.push(status(created.0, Kind::Created, None, String::new()));
+        }
+
+        let conditions = self
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref());
+
+        for condition in conditions.into_iter().flatten() {
+            let Some(at) = condition.last_transition_time.as_ref().map(|time| time.0) else {
+                continue;
+            };
+
+            let ready = condition.status == "True";
+
+            match condition.type_.as_str() {
+                "PodScheduled" if ready && !has(&milestones, Kind::Scheduled, None) => {
+                    derived.push(status(
+                        at,
+                        Kind::Scheduled,
+                        None,
+                        self.spec
+                            .as_ref()
+                            .and_then(|spec| spec.node_name.as_ref())
+                            .map(|node| format!("on {node}"))
+                            .unwrap_or_default(),
+                    ));
+                }
+                "Ready" => derived.push(status(
+                    at,
+                    if ready { Kind::Ready } else { Kind::NotReady },
+                    None,
+                    condition.message.clone().unwrap_or_default(),
+                )),
+                _ => {}
+            }
+        }
+
+        let statuses = self
+            .status
+            .as_ref()
+            .and_then(|status| status.container_statuses.as_ref());
+
+        for container in statuses.into_iter().flatten() {
+            let name = Some(container.name.as_str());
+
+            let running = container
+                .state
+                .as_ref()
+                .and_then(|state| state.running.as_ref())
+                .and_then(|running| running.started_at.as_ref());
+
+            if let Some(started) = running {
+                if !has(&milestones, Kind::Started, name) {
+                    derived.push(status(started.0, Kind::Started, name, String::new()));
+                }
+            }
+
+            let terminated = container
+                .last_state
+                .as_ref()
+                .and_then(|state| state.terminated.as_ref());
+
+            if let Some(terminated) = terminated {
+                if let Some(finished) = terminated.finished_at.as_ref() {
+                    derived.push(status(
+                        finished.0,
+                        Kind::Restarted,
+                        name,
+                        format!(
+                            "exited with {}{}, {} restarts in total",
+                            terminated.exit_code,
+                            terminated
+                                .reason
+                                .as_ref()
+                                .map(|reason| format!(" ({reason})"))
+                                .unwrap_or_default(),
+                            container.restart_count
+                        ),
+                    ));
+                }
+            }
+        }
+
+        milestones.extend(derived);
+        milestones.sort_by_key(|milestone| milestone.at);
+
+        milestones
+    }
+}
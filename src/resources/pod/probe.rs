@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::{Event, Pod};
+
+use crate::resources::event::EventExt;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Startup,
+    Readiness,
+    Liveness,
+}
+
+impl Kind {
+    // How the kubelet starts the message of an `Unhealthy` event.
+    fn prefix(self) -> &'static str {
+        match self {
+            Kind::Startup => "Startup probe",
+            Kind::Readiness => "Readiness probe",
+            Kind::Liveness => "Liveness probe",
+        }
+    }
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Startup => write!(f, "startup"),
+            Kind::Readiness => write!(f, "readiness"),
+            Kind::Liveness => write!(f, "liveness"),
+        }
+    }
+}
+
+pub struct Failure {
+    pub at: DateTime<Utc>,
+    pub message: String,
+}
+
+pub struct Probe {
+    pub kind: Kind,
+    /// Oldest first.
+    pub failures: Vec<Failure>,
+}
+
+/// What is known about the probes of a single container. Successes aren't
+/// recorded anywhere, so anything that isn't a failure while the container was
+/// around is assumed to have passed.
+pub struct Timeline {
+    pub container: String,
+    pub ready: bool,
+    pub started: Option<DateTime<Utc>>,
+    /// When the previous instance of the container stopped, only the most
+    /// recent restart is kept by the API server.
+    pub restarted: Option<DateTime<Utc>>,
+    pub restarts: i32,
+    pub probes: Vec<Probe>,
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait ProbeExt {
+    fn probes(&self, events: &[Arc<Event>]) -> Vec<Timeline>;
+}
+
+fn failures(events: &[Arc<Event>], container: &str, kind: Kind) -> Vec<Failure> {
+    let path = format!("spec.containers{{{container}}}");
+
+    let mut failures: Vec<_> = events
+        .iter()
+        .filter(|event| event.reason.as_deref() == Some("Unhealthy"))
+        .filter(|event| event.involved_object.field_path.as_deref() == Some(path.as_str()))
+        .filter_map(|event| {
+            let message = event.message.as_deref()?.strip_prefix(kind.prefix())?;
+            // Leave out the "failed:" or "errored:" that comes before the reason.
+            let message = message
+                .split_once(':')
+                .map_or(message, |(_, reason)| reason)
+                .trim();
+
+            Some(event.occurrences().into_iter().map(|at| Failure {
+                at,
+                message: message.to_string(),
+            }))
+        })
+        .flatten()
+        .collect();
+
+    failures.sort_by_key(|failure| failure.at);
+
+    failures
+}
+
+impl ProbeExt for Pod {
+    fn probes(&self, events: &[Arc<Event>]) -> Vec<Timeline> {
+        let Some(spec) = self.spec.as_ref() else {
+            return Vec::new();
+        };
+
+        let statuses = self
+            .status
+            .as_ref()
+            .and_then(|status| status.container_statuses.as_ref());
+
+        spec.containers
+            .iter()
+            .map(|container| {
+                let status = statuses
+                    .and_then(|statuses| statuses.iter().find(|s| s.name == container.name));
+
+                let probes = [
+                    (Kind::Startup, container.startup_probe.is_some()),
+                    (Kind::Readiness, container.readiness_probe.is_some()),
+                    (Kind::Liveness, container.liveness_probe.is_some()),
+                ]
+                .into_iter()
+                .filter(|(_, configured)| *configured)
+                .map(|(kind, _)| Probe {
+                    kind,
+                    failures: failures(events, &container.name, kind),
+                })
+                .collect();
+
+                Timeline {
+                    container: container.name.clone(),
+                    ready: status.is_some_and(|status| status.ready),
+                    started: status
+                        .and_then(|status| status.state.as_ref())
+                        .and_then(|state| state.running.as_ref())
+                        .and_then(|running| running.started_at.as_ref())
+                        .map(|time| time.0),
+                    restarted: status
+                        .and_then(|status| status.last_state.as_ref())
+                        .and_then(|state| state.terminated.as_ref())
+                        .and_then(|terminated| terminated.finished_at.as_ref())
+                        .map(|time| time.0),
+                    restarts: status.map_or(0, |status| status.restart_count),
+                    probes,
+                }
+            })
+            .collect()
+    }
+}
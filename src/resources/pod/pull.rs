@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::{Pod, Secret};
+use serde::Deserialize;
+
+// Waiting reasons that mean the kubelet couldn't get the image.
+static PULL_REASONS: &[&str] = &[
+    "ImagePullBackOff",
+    "ErrImagePull",
+    "InvalidImageName",
+    "ErrImageNeverPull",
+    "RegistryUnavailable",
+];
+
+// Every name that Docker Hub goes by, images without a registry come from
+// here.
+static DOCKER_HUB: &[&str] = &["docker.io", "index.docker.io", "registry-1.docker.io"];
+
+/// A container that is waiting on its image.
+pub struct Failure {
+    pub container: String,
+    pub image: String,
+    pub reason: String,
+    pub message: Option<String>,
+}
+
+impl Failure {
+    pub fn registry(&self) -> String {
+        registry(&self.image)
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait PullExt {
+    fn pull_failures(&self) -> Vec<Failure>;
+    fn pull_secrets(&self) -> Vec<String>;
+}
+
+impl PullExt for Pod {
+    fn pull_failures(&self) -> Vec<Failure> {
+        let Some(status) = self.status.as_ref() else {
+            return Vec::new();
+        };
+
+        status
+            .init_container_statuses
+            .iter()
+            .flatten()
+            .chain(status.container_statuses.iter().flatten())
+            .filter_map(|status| {
+                let waiting = status.state.as_ref()?.waiting.as_ref()?;
+                let reason = waiting.reason.as_ref()?;
+
+                PULL_REASONS.contains(&reason.as_str()).then(|| Failure {
+                    container: status.name.clone(),
+                    image: status.image.clone(),
+                    reason: reason.clone(),
+                    message: waiting.message.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// The secrets the kubelet tries when pulling. Those from the pod's
+    /// service account have already been added to the pod by admission.
+    fn pull_secrets(&self) -> Vec<String> {
+        self.spec
+            .iter()
+            .flat_map(|spec| spec.image_pull_secrets.iter().flatten())
+            .filter_map(|secret| secret.name.clone())
+            .collect()
+    }
+}
+
+/// The registry host an image is pulled from. The first part of the name is
+/// only a host when it looks like one, otherwise it's a Docker Hub image.
+pub fn registry(image: &str) -> String {
+    match image.split_once('/') {
+        Some((host, _)) if host.contains(['.', ':']) || host == "localhost" => host.to_string(),
+        _ => "docker.io".to_string(),
+    }
+}
+
+#[derive(Deserialize)]
+struct DockerConfig {
+    #[serde(default)]
+    auths: BTreeMap<String, serde_json::Value>,
+}
+
+// Keys can be anything from `quay.io` to `https://index.docker.io/v1/`,
+// only the host matters.
+fn host(key: &str) -> &str {
+    let key = key
+        .strip_prefix("https://")
+        .or_else(|| key.strip_prefix("http://"))
+        .unwrap_or(key);
+
+    key.split('/').next().unwrap_or(key)
+}
+
+fn matches(key: &str, registry: &str) -> bool {
+    let key = host(key);
+
+    if DOCKER_HUB.contains(&key) && DOCKER_HUB.contains(&registry) {
+        return true;
+    }
+
+    // The kubelet allows globs for subdomains, eg. `*.gcr.io`.
+    match key.strip_prefix("*.") {
+        Some(domain) => registry.ends_with(&format!(".{domain}")),
+        None => key == registry,
+    }
+}
+
+/// What a pull secret has to say about a registry.
+pub enum Credentials {
+    /// The secret has an entry for the registry, under this key.
+    Found(String),
+    /// The secret has credentials, but only for these hosts.
+    Missing(Vec<String>),
+    /// The secret isn't something the kubelet can use.
+    Invalid(String),
+}
+
+impl Credentials {
+    pub fn check(secret: &Secret, registry: &str) -> Self {
+        let data = secret.data.as_ref();
+
+        // `.dockercfg` is the legacy format, it is just the `auths` map.
+        let auths = if let Some(config) = data.and_then(|data| data.get(".dockerconfigjson")) {
+            serde_json::from_slice::<DockerConfig>(&config.0).map(|config| config.auths)
+        } else if let Some(config) = data.and_then(|data| data.get(".dockercfg")) {
+            serde_json::from_slice(&config.0)
+        } else {
+            return Credentials::Invalid(format!(
+                "{} secret has no .dockerconfigjson",
+                secret.type_.as_deref().unwrap_or("Opaque")
+            ));
+        };
+
+        let auths = match auths {
+            Ok(auths) => auths,
+            Err(err) => return Credentials::Invalid(format!("unable to parse: {err}")),
+        };
+
+        match auths.keys().find(|key| matches(key, registry)) {
+            Some(key) => Credentials::Found(key.clone()),
+            None => Credentials::Missing(auths.keys().map(|key| host(key).to_string()).collect()),
+        }
+    }
+}
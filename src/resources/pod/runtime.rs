@@ -0,0 +1,89 @@
+use k8s_openapi::api::core::v1::{ContainerStatus, Node, Pod};
+
+/// How the container runtime on a node knows about a container, so that it can
+/// be found with node level tooling such as `crictl`.
+pub struct Runtime {
+    pub container: String,
+    pub init: bool,
+    /// The runtime the ID belongs to, the `containerd` of
+    /// `containerd://<id>`.
+    pub engine: Option<String>,
+    pub id: Option<String>,
+    pub image: String,
+    pub image_id: Option<String>,
+}
+
+impl Runtime {
+    fn from_status(status: &ContainerStatus, init: bool) -> Self {
+        let (runtime, id) = match status
+            .container_id
+            .as_deref()
+            .map(|id| id.split_once("://"))
+        {
+            Some(Some((runtime, id))) => (Some(runtime.to_string()), Some(id.to_string())),
+            Some(None) => (None, status.container_id.clone()),
+            None => (None, None),
+        };
+
+        Self {
+            container: status.name.clone(),
+            init,
+            engine: runtime,
+            id,
+            image: status.image.clone(),
+            image_id: Some(status.image_id.clone()).filter(|id| !id.is_empty()),
+        }
+    }
+
+    /// The command to look at this container from the node it is running on.
+    pub fn inspect(&self) -> Option<String> {
+        self.id.as_ref().map(|id| format!("crictl inspect {id}"))
+    }
+}
+
+/// What is running containers on a node, as reported by its kubelet.
+pub struct Host {
+    pub name: String,
+    /// For example, `containerd://1.7.2`.
+    pub version: String,
+    pub kubelet: String,
+    pub os: String,
+}
+
+impl From<&Node> for Host {
+    fn from(node: &Node) -> Self {
+        let info = node
+            .status
+            .as_ref()
+            .and_then(|status| status.node_info.as_ref());
+
+        Self {
+            name: node.metadata.name.clone().unwrap_or_default(),
+            version: info.map_or_else(String::new, |info| info.container_runtime_version.clone()),
+            kubelet: info.map_or_else(String::new, |info| info.kubelet_version.clone()),
+            os: info.map_or_else(String::new, |info| info.os_image.clone()),
+        }
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait RuntimeExt {
+    fn runtimes(&self) -> Vec<Runtime>;
+}
+
+impl RuntimeExt for Pod {
+    /// Only containers that have been created have a status, anything still
+    /// waiting on its image won't have an ID yet.
+    fn runtimes(&self) -> Vec<Runtime> {
+        let Some(status) = self.status.as_ref() else {
+            return Vec::new();
+        };
+
+        let init = status.init_container_statuses.iter().flatten();
+        let containers = status.container_statuses.iter().flatten();
+
+        init.map(|status| Runtime::from_status(status, true))
+            .chain(containers.map(|status| Runtime::from_status(status, false)))
+            .collect()
+    }
+}
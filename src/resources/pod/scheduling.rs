@@ -0,0 +1,528 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use itertools::Itertools;
+use k8s_openapi::{
+    api::core::v1::{
+        Node, NodeSelectorTerm, Pod, PodAffinityTerm, TopologySpreadConstraint,
+        WeightedPodAffinityTerm,
+    },
+    apimachinery::pkg::apis::meta::v1::LabelSelector,
+};
+use kube::ResourceExt;
+use ratatui::{
+    layout::Constraint,
+    widgets::{Cell, Row},
+};
+
+use crate::{
+    resources::{node::NodeExt, selector},
+    widget::{table, theme::Indicator},
+};
+
+/// Number of names shown before the rest are summarized as a count.
+static MAX_NAMES: usize = 3;
+
+/// A single scheduling constraint on a pod, along with what in the cluster
+/// currently satisfies it.
+pub struct Rule {
+    kind: String,
+    expression: String,
+    satisfied: String,
+    peers: String,
+    ok: bool,
+}
+
+/// Everything the scheduler would be looking at when placing a pod.
+pub struct Cluster<'a> {
+    pub nodes: &'a [Arc<Node>],
+    pub pods: &'a [Arc<Pod>],
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait SchedulingExt {
+    fn scheduling(&self, cluster: &Cluster) -> Vec<Rule>;
+}
+
+impl SchedulingExt for Pod {
+    #[allow(clippy::too_many_lines)]
+    fn scheduling(&self, cluster: &Cluster) -> Vec<Rule> {
+        let Some(spec) = self.spec.as_ref() else {
+            return Vec::new();
+        };
+
+        let current = spec
+            .node_name
+            .as_ref()
+            .and_then(|name| cluster.nodes.iter().find(|node| &node.name_any() == name));
+
+        let mut rules = Vec::new();
+
+        if let Some(name) = spec.node_name.as_ref() {
+            rules.push(Rule {
+                kind: "scheduled".to_string(),
+                expression: String::new(),
+                satisfied: current
+                    .and_then(|node| node.zone())
+                    .map_or(name.clone(), |zone| format!("{name} ({zone})")),
+                peers: String::new(),
+                ok: true,
+            });
+        }
+
+        if let Some(node_selector) = spec.node_selector.as_ref().filter(|s| !s.is_empty()) {
+            let nodes = cluster
+                .nodes
+                .iter()
+                .filter(|node| {
+                    let labels = node.labels();
+
+                    node_selector.iter().all(|(k, v)| labels.get(k) == Some(v))
+                })
+                .collect_vec();
+
+            rules.push(Rule::nodes(
+                "nodeSelector".to_string(),
+                node_selector
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v}"))
+                    .join(", "),
+                &nodes,
+                cluster,
+                current,
+            ));
+        }
+
+        let affinity = spec.affinity.clone().unwrap_or_default();
+
+        if let Some(node_affinity) = affinity.node_affinity {
+            if let Some(required) =
+                node_affinity.required_during_scheduling_ignored_during_execution
+            {
+                // Terms are ORed together, any one of them matching is enough.
+                let nodes = cluster
+                    .nodes
+                    .iter()
+                    .filter(|node| {
+                        required.node_selector_terms.iter().any(|term| {
+                            selector::matches_term(term, &node.name_any(), node.labels())
+                        })
+                    })
+                    .collect_vec();
+
+                rules.push(Rule::nodes(
+                    "nodeAffinity (required)".to_string(),
+                    required
+                        .node_selector_terms
+                        .iter()
+                        .map(describe_term)
+                        .join(" | "),
+                    &nodes,
+                    cluster,
+                    current,
+                ));
+            }
+
+            for preferred in node_affinity
+                .preferred_during_scheduling_ignored_during_execution
+                .into_iter()
+                .flatten()
+            {
+                let nodes = cluster
+                    .nodes
+                    .iter()
+                    .filter(|node| {
+                        selector::matches_term(
+                            &preferred.preference,
+                            &node.name_any(),
+                            node.labels(),
+                        )
+                    })
+                    .collect_vec();
+
+                rules.push(Rule::nodes(
+                    format!("nodeAffinity (preferred, {})", preferred.weight),
+                    describe_term(&preferred.preference),
+                    &nodes,
+                    cluster,
+                    current,
+                ));
+            }
+        }
+
+        if let Some(pod_affinity) = affinity.pod_affinity {
+            for (kind, term) in affinity_terms(
+                "podAffinity",
+                pod_affinity.required_during_scheduling_ignored_during_execution,
+                pod_affinity.preferred_during_scheduling_ignored_during_execution,
+            ) {
+                rules.push(self.pod_affinity(kind, &term, cluster, current, false));
+            }
+        }
+
+        if let Some(pod_anti_affinity) = affinity.pod_anti_affinity {
+            for (kind, term) in affinity_terms(
+                "podAntiAffinity",
+                pod_anti_affinity.required_during_scheduling_ignored_during_execution,
+                pod_anti_affinity.preferred_during_scheduling_ignored_during_execution,
+            ) {
+                rules.push(self.pod_affinity(kind, &term, cluster, current, true));
+            }
+        }
+
+        for constraint in spec.topology_spread_constraints.iter().flatten() {
+            rules.push(self.topology_spread(constraint, cluster, current));
+        }
+
+        rules
+    }
+}
+
+trait Peers {
+    fn pod_affinity(
+        &self,
+        kind: String,
+        term: &PodAffinityTerm,
+        cluster: &Cluster,
+        current: Option<&Arc<Node>>,
+        anti: bool,
+    ) -> Rule;
+
+    fn topology_spread(
+        &self,
+        constraint: &TopologySpreadConstraint,
+        cluster: &Cluster,
+        current: Option<&Arc<Node>>,
+    ) -> Rule;
+
+    fn peers<'a>(
+        &self,
+        selector: Option<&LabelSelector>,
+        namespaces: Option<&[String]>,
+        cluster: &Cluster<'a>,
+    ) -> Vec<&'a Arc<Pod>>;
+}
+
+impl Peers for Pod {
+    fn pod_affinity(
+        &self,
+        kind: String,
+        term: &PodAffinityTerm,
+        cluster: &Cluster,
+        current: Option<&Arc<Node>>,
+        anti: bool,
+    ) -> Rule {
+        // There are no namespace labels available here, a namespace selector is
+        // treated as matching every namespace.
+        let namespaces = if term.namespace_selector.is_some() {
+            None
+        } else {
+            Some(
+                term.namespaces
+                    .clone()
+                    .filter(|ns| !ns.is_empty())
+                    .unwrap_or_else(|| vec![self.namespace().unwrap_or_default()]),
+            )
+        };
+
+        let peers = self.peers(term.label_selector.as_ref(), namespaces.as_deref(), cluster);
+        let domains = domains(&peers, cluster, &term.topology_key);
+
+        let nodes = cluster
+            .nodes
+            .iter()
+            .filter(|node| {
+                let Some(value) = node.labels().get(&term.topology_key) else {
+                    return false;
+                };
+
+                if anti {
+                    return !domains.contains(value);
+                }
+
+                // The first pod of a group that selects itself would never be
+                // schedulable, the scheduler lets it go anywhere instead.
+                domains.contains(value)
+                    || (domains.is_empty()
+                        && term
+                            .label_selector
+                            .as_ref()
+                            .is_some_and(|s| selector::matches(s, self.labels())))
+            })
+            .collect_vec();
+
+        let mut rule = Rule::nodes(
+            kind,
+            format!(
+                "{} by {}",
+                describe_selector(term.label_selector.as_ref()),
+                term.topology_key
+            ),
+            &nodes,
+            cluster,
+            current,
+        );
+        rule.peers = names(peers.iter().map(|pod| pod.name_any()));
+
+        rule
+    }
+
+    fn topology_spread(
+        &self,
+        constraint: &TopologySpreadConstraint,
+        cluster: &Cluster,
+        current: Option<&Arc<Node>>,
+    ) -> Rule {
+        let namespace = [self.namespace().unwrap_or_default()];
+        let peers = self.peers(
+            constraint.label_selector.as_ref(),
+            Some(&namespace),
+            cluster,
+        );
+
+        let mut counts: BTreeMap<&str, i32> = cluster
+            .nodes
+            .iter()
+            .filter_map(|node| node.labels().get(&constraint.topology_key))
+            .map(|value| (value.as_str(), 0))
+            .collect();
+
+        for peer in &peers {
+            let Some(value) = peer
+                .spec
+                .as_ref()
+                .and_then(|spec| spec.node_name.as_ref())
+                .and_then(|name| cluster.nodes.iter().find(|node| &node.name_any() == name))
+                .and_then(|node| node.labels().get(&constraint.topology_key))
+            else {
+                continue;
+            };
+
+            *counts.entry(value.as_str()).or_default() += 1;
+        }
+
+        let min = counts.values().min().copied().unwrap_or_default();
+
+        let nodes = cluster
+            .nodes
+            .iter()
+            .filter(|node| {
+                node.labels()
+                    .get(&constraint.topology_key)
+                    .and_then(|value| counts.get(value.as_str()))
+                    .is_some_and(|count| count + 1 - min <= constraint.max_skew)
+            })
+            .collect_vec();
+
+        let mut rule = Rule::nodes(
+            format!("topologySpread ({})", constraint.when_unsatisfiable),
+            format!(
+                "{} by {}, max skew {}",
+                describe_selector(constraint.label_selector.as_ref()),
+                constraint.topology_key,
+                constraint.max_skew
+            ),
+            &nodes,
+            cluster,
+            current,
+        );
+        rule.peers = counts.iter().map(|(k, v)| format!("{k}={v}")).join(", ");
+
+        rule
+    }
+
+    /// Scheduled pods, other than this one, that are selected by `selector`.
+    /// `None` for namespaces means every namespace.
+    fn peers<'a>(
+        &self,
+        selector: Option<&LabelSelector>,
+        namespaces: Option<&[String]>,
+        cluster: &Cluster<'a>,
+    ) -> Vec<&'a Arc<Pod>> {
+        // A missing selector matches nothing, an empty one matches everything.
+        let Some(selector) = selector else {
+            return Vec::new();
+        };
+
+        cluster
+            .pods
+            .iter()
+            .filter(|pod| pod.uid() != self.uid())
+            .filter(|pod| {
+                pod.spec
+                    .as_ref()
+                    .is_some_and(|spec| spec.node_name.is_some())
+            })
+            .filter(|pod| {
+                namespaces.map_or(true, |namespaces| {
+                    namespaces.contains(&pod.namespace().unwrap_or_default())
+                })
+            })
+            .filter(|pod| selector::matches(selector, pod.labels()))
+            .collect()
+    }
+}
+
+impl Rule {
+    /// A rule that is satisfied by `nodes`. It is ok if the pod is on one of them
+    /// or, when it hasn't been scheduled yet, if there's anywhere for it to go.
+    fn nodes(
+        kind: String,
+        expression: String,
+        nodes: &[&Arc<Node>],
+        cluster: &Cluster,
+        current: Option<&Arc<Node>>,
+    ) -> Self {
+        let ok = current.map_or(!nodes.is_empty(), |current| {
+            nodes
+                .iter()
+                .any(|node| node.name_any() == current.name_any())
+        });
+
+        let zones: BTreeSet<_> = nodes.iter().filter_map(|node| node.zone()).collect();
+
+        let mut satisfied = format!("{}/{} nodes", nodes.len(), cluster.nodes.len());
+
+        if !nodes.is_empty() {
+            satisfied.push_str(&format!(": {}", names(nodes.iter().map(|n| n.name_any()))));
+        }
+
+        if !zones.is_empty() {
+            satisfied.push_str(&format!(" (zones: {})", zones.iter().join(", ")));
+        }
+
+        Self {
+            kind,
+            expression,
+            satisfied,
+            peers: String::new(),
+            ok,
+        }
+    }
+}
+
+/// Required and preferred terms, labeled with how they apply.
+fn affinity_terms(
+    name: &str,
+    required: Option<Vec<PodAffinityTerm>>,
+    preferred: Option<Vec<WeightedPodAffinityTerm>>,
+) -> Vec<(String, PodAffinityTerm)> {
+    required
+        .into_iter()
+        .flatten()
+        .map(|term| (format!("{name} (required)"), term))
+        .chain(preferred.into_iter().flatten().map(|weighted| {
+            (
+                format!("{name} (preferred, {})", weighted.weight),
+                weighted.pod_affinity_term,
+            )
+        }))
+        .collect()
+}
+
+/// Topology values of the nodes that `pods` are running on.
+fn domains(pods: &[&Arc<Pod>], cluster: &Cluster, key: &str) -> BTreeSet<String> {
+    pods.iter()
+        .filter_map(|pod| pod.spec.as_ref()?.node_name.as_ref())
+        .filter_map(|name| cluster.nodes.iter().find(|node| &node.name_any() == name))
+        .filter_map(|node| node.labels().get(key).cloned())
+        .collect()
+}
+
+fn names(names: impl Iterator<Item = String>) -> String {
+    let names = names.collect_vec();
+
+    if names.len() <= MAX_NAMES {
+        return names.join(", ");
+    }
+
+    format!(
+        "{} +{} more",
+        names[..MAX_NAMES].join(", "),
+        names.len() - MAX_NAMES
+    )
+}
+
+fn describe_selector(selector: Option<&LabelSelector>) -> String {
+    let Some(selector) = selector else {
+        return "nothing".to_string();
+    };
+
+    let parts = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(k, v)| format!("{k}={v}"))
+        .chain(selector.match_expressions.iter().flatten().map(|expr| {
+            format!(
+                "{} {} ({})",
+                expr.key,
+                expr.operator,
+                expr.values.iter().flatten().join(", ")
+            )
+        }))
+        .collect_vec();
+
+    if parts.is_empty() {
+        return "everything".to_string();
+    }
+
+    parts.join(", ")
+}
+
+fn describe_term(term: &NodeSelectorTerm) -> String {
+    term.match_expressions
+        .iter()
+        .flatten()
+        .chain(term.match_fields.iter().flatten())
+        .map(|req| match req.operator.as_str() {
+            "Exists" | "DoesNotExist" => format!("{} {}", req.key, req.operator),
+            _ => format!(
+                "{} {} ({})",
+                req.key,
+                req.operator,
+                req.values.iter().flatten().join(", ")
+            ),
+        })
+        .join(" & ")
+}
+
+impl table::Row for Rule {
+    fn header<'a>() -> Option<Row<'a>> {
+        Some(Row::new(vec![
+            Cell::from("Constraint"),
+            Cell::from("Rule"),
+            Cell::from("Satisfied By"),
+            Cell::from("Peers"),
+        ]))
+    }
+
+    fn constraints() -> Vec<Constraint> {
+        vec![
+            Constraint::Max(32),
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(vec![
+            Cell::from(if self.ok {
+                Indicator::Healthy.label(&self.kind)
+            } else {
+                Indicator::Unhealthy.label(&self.kind)
+            }),
+            Cell::from(self.expression.as_str()),
+            Cell::from(self.satisfied.as_str()),
+            Cell::from(self.peers.as_str()),
+        ])
+        .style(if self.ok {
+            style.normal
+        } else {
+            style.unhealthy
+        })
+    }
+}
@@ -0,0 +1,254 @@
+use k8s_openapi::api::core::v1::{self, Pod, PodSecurityContext, SeccompProfile};
+use kube::ResourceExt;
+use ratatui::{
+    layout::Constraint,
+    widgets::{Cell, Row},
+};
+
+use crate::widget::{table, theme::Indicator};
+
+static APPARMOR_PREFIX: &str = "container.apparmor.security.beta.kubernetes.io/";
+
+// Capabilities that effectively grant root on the node (or close enough to it)
+// when added to a container.
+static DANGEROUS_CAPABILITIES: &[&str] = &[
+    "ALL",
+    "BPF",
+    "DAC_READ_SEARCH",
+    "NET_ADMIN",
+    "NET_RAW",
+    "PERFMON",
+    "SYS_ADMIN",
+    "SYS_MODULE",
+    "SYS_PTRACE",
+    "SYS_RAWIO",
+];
+
+pub enum Risk {
+    Hardened,
+    Neutral,
+    Risky,
+}
+
+pub struct Setting {
+    scope: String,
+    name: &'static str,
+    value: String,
+    risk: Risk,
+}
+
+impl Setting {
+    fn new(scope: &str, name: &'static str, value: impl Into<String>, risk: Risk) -> Self {
+        Self {
+            scope: scope.to_string(),
+            name,
+            value: value.into(),
+            risk,
+        }
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait SecurityExt {
+    fn security(&self) -> Vec<Setting>;
+}
+
+impl SecurityExt for Pod {
+    fn security(&self) -> Vec<Setting> {
+        let Some(spec) = self.spec.as_ref() else {
+            return Vec::new();
+        };
+
+        let mut settings = Vec::new();
+
+        for (name, enabled) in [
+            ("hostNetwork", spec.host_network),
+            ("hostPID", spec.host_pid),
+            ("hostIPC", spec.host_ipc),
+        ] {
+            if enabled.unwrap_or_default() {
+                settings.push(Setting::new("pod", name, "true", Risk::Risky));
+            }
+        }
+
+        for volume in spec.volumes.iter().flatten() {
+            if let Some(host_path) = &volume.host_path {
+                settings.push(Setting::new(
+                    "pod",
+                    "hostPath",
+                    format!("{} → {}", volume.name, host_path.path),
+                    Risk::Risky,
+                ));
+            }
+        }
+
+        let annotations = self.annotations();
+
+        for container in spec
+            .init_containers
+            .iter()
+            .flatten()
+            .chain(spec.containers.iter())
+        {
+            settings.extend(container_settings(
+                container,
+                spec.security_context.as_ref(),
+                annotations
+                    .get(format!("{APPARMOR_PREFIX}{}", container.name).as_str())
+                    .map(String::as_str),
+            ));
+        }
+
+        settings
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+fn container_settings(
+    container: &v1::Container,
+    pod: Option<&PodSecurityContext>,
+    apparmor: Option<&str>,
+) -> Vec<Setting> {
+    let scope = container.name.as_str();
+    let ctx = container.security_context.clone().unwrap_or_default();
+    let pod = pod.cloned().unwrap_or_default();
+
+    let mut settings = vec![
+        match ctx.privileged {
+            Some(true) => Setting::new(scope, "privileged", "true", Risk::Risky),
+            _ => Setting::new(scope, "privileged", "false", Risk::Hardened),
+        },
+        match ctx.allow_privilege_escalation {
+            Some(true) => Setting::new(scope, "allowPrivilegeEscalation", "true", Risk::Risky),
+            Some(false) => Setting::new(scope, "allowPrivilegeEscalation", "false", Risk::Hardened),
+            None => Setting::new(
+                scope,
+                "allowPrivilegeEscalation",
+                "unset (true)",
+                Risk::Neutral,
+            ),
+        },
+        match ctx.run_as_user.or(pod.run_as_user) {
+            Some(0) => Setting::new(scope, "runAsUser", "0", Risk::Risky),
+            Some(uid) => Setting::new(scope, "runAsUser", uid.to_string(), Risk::Hardened),
+            None => Setting::new(scope, "runAsUser", "unset (image default)", Risk::Neutral),
+        },
+        match ctx.run_as_non_root.or(pod.run_as_non_root) {
+            Some(true) => Setting::new(scope, "runAsNonRoot", "true", Risk::Hardened),
+            _ => Setting::new(scope, "runAsNonRoot", "false", Risk::Neutral),
+        },
+        match ctx.read_only_root_filesystem {
+            Some(true) => Setting::new(scope, "readOnlyRootFilesystem", "true", Risk::Hardened),
+            _ => Setting::new(scope, "readOnlyRootFilesystem", "false", Risk::Neutral),
+        },
+    ];
+
+    let capabilities = ctx.capabilities.unwrap_or_default();
+
+    let added = capabilities.add.unwrap_or_default();
+    if !added.is_empty() {
+        let risk = if added
+            .iter()
+            .any(|cap| DANGEROUS_CAPABILITIES.contains(&cap.trim_start_matches("CAP_")))
+        {
+            Risk::Risky
+        } else {
+            Risk::Neutral
+        };
+
+        settings.push(Setting::new(
+            scope,
+            "capabilities.add",
+            added.join(", "),
+            risk,
+        ));
+    }
+
+    let dropped = capabilities.drop.unwrap_or_default();
+    settings.push(if dropped.iter().any(|cap| cap == "ALL") {
+        Setting::new(
+            scope,
+            "capabilities.drop",
+            dropped.join(", "),
+            Risk::Hardened,
+        )
+    } else if dropped.is_empty() {
+        Setting::new(scope, "capabilities.drop", "none", Risk::Neutral)
+    } else {
+        Setting::new(
+            scope,
+            "capabilities.drop",
+            dropped.join(", "),
+            Risk::Neutral,
+        )
+    });
+
+    settings.push(
+        match ctx
+            .seccomp_profile
+            .as_ref()
+            .or(pod.seccomp_profile.as_ref())
+        {
+            Some(SeccompProfile { type_, .. }) if type_ == "Unconfined" => {
+                Setting::new(scope, "seccompProfile", type_.as_str(), Risk::Risky)
+            }
+            Some(SeccompProfile {
+                type_,
+                localhost_profile,
+            }) => Setting::new(
+                scope,
+                "seccompProfile",
+                localhost_profile
+                    .as_ref()
+                    .map_or(type_.clone(), |profile| format!("{type_} ({profile})")),
+                Risk::Hardened,
+            ),
+            None => Setting::new(scope, "seccompProfile", "unset", Risk::Neutral),
+        },
+    );
+
+    settings.push(match apparmor {
+        Some(profile) if profile == "unconfined" => {
+            Setting::new(scope, "appArmorProfile", profile, Risk::Risky)
+        }
+        Some(profile) => Setting::new(scope, "appArmorProfile", profile, Risk::Hardened),
+        None => Setting::new(scope, "appArmorProfile", "unset", Risk::Neutral),
+    });
+
+    settings
+}
+
+impl table::Row for Setting {
+    fn header<'a>() -> Option<Row<'a>> {
+        Some(Row::new(vec![
+            Cell::from("Container"),
+            Cell::from("Setting"),
+            Cell::from("Value"),
+        ]))
+    }
+
+    fn constraints() -> Vec<Constraint> {
+        vec![
+            Constraint::Max(20),
+            Constraint::Max(30),
+            Constraint::Min(10),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(vec![
+            Cell::from(self.scope.as_str()),
+            Cell::from(self.name),
+            Cell::from(match self.risk {
+                Risk::Hardened => Indicator::Healthy.label(&self.value),
+                Risk::Risky => Indicator::Warning.label(&self.value),
+                Risk::Neutral => self.value.clone(),
+            }),
+        ])
+        .style(match self.risk {
+            Risk::Hardened => style.healthy,
+            Risk::Neutral => style.normal,
+            Risk::Risky => style.unhealthy,
+        })
+    }
+}
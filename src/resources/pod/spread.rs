@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use k8s_openapi::api::core::v1::{Node, Pod};
+use kube::ResourceExt;
+
+use super::{Grouping, Placed};
+use crate::resources::node::NodeExt;
+
+/// The workload a pod belongs to, as `namespace/name`. Pods from a replica set
+/// count towards its deployment, which is what the spread is set on. Pods
+/// without a controller don't belong to anything.
+pub fn workload(pod: &Pod) -> Option<String> {
+    let owner = pod
+        .owner_references()
+        .iter()
+        .find(|owner| owner.controller.unwrap_or_default())?;
+
+    let name = match (owner.kind.as_str(), pod.labels().get("pod-template-hash")) {
+        ("ReplicaSet", Some(hash)) => owner
+            .name
+            .strip_suffix(&format!("-{hash}"))
+            .unwrap_or(&owner.name),
+        _ => owner.name.as_str(),
+    };
+
+    Some(format!("{}/{name}", pod.namespace().unwrap_or_default()))
+}
+
+/// How many pods of a workload are in each domain, in the same order as
+/// `Spread::domains`.
+pub struct Workload {
+    pub name: String,
+    pub counts: Vec<usize>,
+}
+
+impl Workload {
+    /// The difference between the most and the fewest pods in any one domain,
+    /// the same way `maxSkew` in a topology spread constraint is measured.
+    pub fn skew(&self) -> usize {
+        let max = self.counts.iter().max().copied().unwrap_or_default();
+        let min = self.counts.iter().min().copied().unwrap_or_default();
+
+        max - min
+    }
+}
+
+/// Scheduled pods counted by zone or node.
+pub struct Spread {
+    pub grouping: Grouping,
+    /// Every domain along with how many pods are in it, largest first.
+    pub domains: Vec<(String, usize)>,
+    /// Workloads with more than one pod, most uneven first.
+    pub workloads: Vec<Workload>,
+    /// Pods that haven't been scheduled yet and aren't counted anywhere.
+    pub pending: usize,
+}
+
+fn domain(node: &Node, grouping: Grouping) -> String {
+    match grouping {
+        Grouping::Zone => node.zone().unwrap_or_default().to_string(),
+        Grouping::Node | Grouping::None => node.name_any(),
+    }
+}
+
+/// Domains come from the nodes as well as the pods, so that one without any pods
+/// at all still counts as the emptiest. `Grouping::None` is treated as by node.
+pub fn spread(pods: &[Placed], nodes: &[Arc<Node>], grouping: Grouping) -> Spread {
+    let grouping = match grouping {
+        Grouping::None => Grouping::Node,
+        grouping => grouping,
+    };
+
+    let (scheduled, pending): (Vec<_>, Vec<_>) =
+        pods.iter().partition(|placed| !placed.node.is_empty());
+
+    let mut totals: BTreeMap<String, usize> = nodes
+        .iter()
+        .map(|node| (domain(node, grouping), 0))
+        .collect();
+
+    let mut by_workload: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+
+    for placed in &scheduled {
+        let group = placed.group(grouping).to_string();
+
+        *totals.entry(group.clone()).or_default() += 1;
+
+        if let Some(name) = workload(&placed.pod) {
+            *by_workload
+                .entry(name)
+                .or_default()
+                .entry(group)
+                .or_default() += 1;
+        }
+    }
+
+    let mut domains: Vec<_> = totals.into_iter().collect();
+    domains.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+
+    let mut workloads: Vec<_> = by_workload
+        .into_iter()
+        .filter(|(_, counts)| counts.values().sum::<usize>() > 1)
+        .map(|(name, counts)| Workload {
+            name,
+            counts: domains
+                .iter()
+                .map(|(domain, _)| counts.get(domain).copied().unwrap_or_default())
+                .collect(),
+        })
+        .collect();
+
+    workloads.sort_by(|a, b| b.skew().cmp(&a.skew()).then_with(|| a.name.cmp(&b.name)));
+
+    Spread {
+        grouping,
+        domains,
+        workloads,
+        pending: pending.len(),
+    }
+}
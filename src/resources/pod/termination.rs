@@ -0,0 +1,90 @@
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::{
+    api::core::v1::{LifecycleHandler, Pod},
+    apimachinery::pkg::util::intstr::IntOrString,
+};
+
+use super::command;
+
+// What the API server uses when the spec doesn't say.
+static DEFAULT_GRACE_PERIOD: i64 = 30;
+
+/// A container's `postStart` or `preStop` hook.
+pub struct Hook {
+    pub event: &'static str,
+    pub action: String,
+}
+
+fn port(port: &IntOrString) -> String {
+    match port {
+        IntOrString::Int(port) => port.to_string(),
+        IntOrString::String(name) => name.clone(),
+    }
+}
+
+fn describe(handler: &LifecycleHandler) -> String {
+    if let Some(exec) = handler.exec.as_ref() {
+        return command::quote(exec.command.as_deref().unwrap_or_default());
+    }
+
+    if let Some(http) = handler.http_get.as_ref() {
+        return format!(
+            "GET {}://{}:{}{}",
+            http.scheme.as_deref().unwrap_or("HTTP").to_lowercase(),
+            http.host.as_deref().unwrap_or("<pod ip>"),
+            port(&http.port),
+            http.path.as_deref().unwrap_or("/"),
+        );
+    }
+
+    if let Some(tcp) = handler.tcp_socket.as_ref() {
+        return format!(
+            "tcp {}:{}",
+            tcp.host.as_deref().unwrap_or("<pod ip>"),
+            port(&tcp.port)
+        );
+    }
+
+    "unknown handler".to_string()
+}
+
+/// The hooks a container has, `postStart` first since that's the order they
+/// run in.
+pub fn hooks(container: &k8s_openapi::api::core::v1::Container) -> Vec<Hook> {
+    let Some(lifecycle) = container.lifecycle.as_ref() else {
+        return Vec::new();
+    };
+
+    [
+        ("postStart", lifecycle.post_start.as_ref()),
+        ("preStop", lifecycle.pre_stop.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(event, handler)| {
+        handler.map(|handler| Hook {
+            event,
+            action: describe(handler),
+        })
+    })
+    .collect()
+}
+
+/// How long containers get between `SIGTERM` (after any `preStop` hook) and
+/// being killed.
+pub fn grace_period(pod: &Pod) -> i64 {
+    pod.spec
+        .as_ref()
+        .and_then(|spec| spec.termination_grace_period_seconds)
+        .unwrap_or(DEFAULT_GRACE_PERIOD)
+}
+
+/// How much of the grace period a pod that is being deleted has left. Deleting
+/// sets `deletionTimestamp` to when the grace period runs out, so this goes
+/// negative once a pod has lingered past it, usually because of a finalizer or
+/// a node that isn't responding.
+pub fn remaining(pod: &Pod) -> Option<TimeDelta> {
+    pod.metadata
+        .deletion_timestamp
+        .as_ref()
+        .map(|deadline| deadline.0 - Utc::now())
+}
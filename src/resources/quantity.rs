@@ -0,0 +1,68 @@
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+
+static BINARY: &[&str] = &["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei"];
+
+fn multiplier(suffix: &str) -> Option<f64> {
+    let value = match suffix {
+        "" => 1.0,
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        _ => {
+            if let Some(exp) = BINARY.iter().position(|unit| unit == &suffix) {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                return Some(1024_f64.powi(exp as i32));
+            }
+
+            // Scientific notation, eg. `1e3`.
+            10_f64.powi(suffix.strip_prefix(['e', 'E'])?.parse().ok()?)
+        }
+    };
+
+    Some(value)
+}
+
+/// Value of a quantity in its base unit. CPU is in cores and memory is in
+/// bytes. Quantities that aren't valid are `None`.
+pub fn parse(quantity: &Quantity) -> Option<f64> {
+    let value = quantity.0.trim();
+
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '+' | '-')))
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(split);
+
+    Some(number.parse::<f64>().ok()? * multiplier(suffix)?)
+}
+
+/// Cores, shown as millicores when there's less than one.
+pub fn cpu(cores: f64) -> String {
+    if cores < 1.0 {
+        format!("{:.0}m", cores * 1000.0)
+    } else {
+        format!("{cores:.1}")
+    }
+}
+
+/// Bytes, in the largest binary unit that keeps the value above one.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub fn bytes(bytes: f64) -> String {
+    let exp = (0..BINARY.len())
+        .rev()
+        .find(|exp| bytes >= 1024_f64.powi(*exp as i32))
+        .unwrap_or_default();
+
+    let value = bytes / 1024_f64.powi(exp as i32);
+
+    if exp == 0 || value >= 100.0 {
+        format!("{value:.0}{}", BINARY[exp])
+    } else {
+        format!("{value:.1}{}", BINARY[exp])
+    }
+}
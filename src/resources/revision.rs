@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use eyre::{eyre, Result};
+use k8s_openapi::{
+    api::core::v1::ConfigMap,
+    apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference},
+};
+use kube::{
+    api::{Api, DeleteParams, ListParams, PostParams},
+    Resource, ResourceExt,
+};
+
+use super::MANAGER;
+
+static LABEL: &str = "kty.dev/revision";
+static REVISION_OF: &str = "kty.dev/revision-of";
+
+// Names can be at most this long, see `ObjectMeta`.
+static MAX_NAME: usize = 253;
+
+fn api(client: kube::Client, namespace: &str) -> Api<ConfigMap> {
+    Api::namespaced(client, namespace)
+}
+
+fn name(cm: &ConfigMap) -> String {
+    let suffix = format!("-kty-{}", cm.resource_version().unwrap_or_default());
+    let base = cm.name_any();
+
+    format!(
+        "{}{suffix}",
+        &base[..base.len().min(MAX_NAME - suffix.len())]
+    )
+}
+
+/// Copy a `ConfigMap` into a new one before it gets changed, then prune the
+/// oldest copies so that only `configmaps.revisions` of them are left. Copies
+/// are immutable and owned by the original, so they go away along with it.
+/// Labels aren't copied, anything selecting on them would pick up the copy
+/// too.
+pub async fn snapshot(client: kube::Client, cm: &ConfigMap) -> Result<()> {
+    let keep = crate::config::get().configmaps.revisions;
+
+    if keep == 0 {
+        return Ok(());
+    }
+
+    let namespace = cm
+        .namespace()
+        .ok_or_else(|| eyre!("ConfigMaps are namespaced"))?;
+    let api = api(client.clone(), &namespace);
+
+    let owner = OwnerReference {
+        api_version: "v1".to_string(),
+        kind: "ConfigMap".to_string(),
+        name: cm.name_any(),
+        uid: cm
+            .uid()
+            .ok_or_else(|| eyre!("{} has no uid", cm.name_any()))?,
+        ..Default::default()
+    };
+
+    let copy = ConfigMap {
+        metadata: ObjectMeta {
+            name: Some(name(cm)),
+            namespace: Some(namespace),
+            labels: Some(BTreeMap::from([(LABEL.to_string(), "true".to_string())])),
+            annotations: Some(BTreeMap::from([(REVISION_OF.to_string(), cm.name_any())])),
+            owner_references: Some(vec![owner]),
+            ..Default::default()
+        },
+        data: cm.data.clone(),
+        binary_data: cm.binary_data.clone(),
+        immutable: Some(true),
+    };
+
+    match api
+        .create(
+            &PostParams {
+                field_manager: Some(MANAGER.to_string()),
+                ..Default::default()
+            },
+            &copy,
+        )
+        .await
+    {
+        // The same version has already been saved, such as when an edit is
+        // retried after a failure.
+        Ok(_) | Err(kube::Error::Api(kube::error::ErrorResponse { code: 409, .. })) => {}
+        Err(err) => return Err(err.into()),
+    }
+
+    for old in list(client, cm).await?.iter().skip(keep) {
+        api.delete(&old.name_any(), &DeleteParams::default())
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Saved copies of a `ConfigMap`, newest first.
+pub async fn list(client: kube::Client, cm: &ConfigMap) -> Result<Vec<ConfigMap>> {
+    let namespace = cm
+        .namespace()
+        .ok_or_else(|| eyre!("ConfigMaps are namespaced"))?;
+
+    let mut revisions: Vec<_> = api(client, &namespace)
+        .list(&ListParams::default().labels(&format!("{LABEL}=true")))
+        .await?
+        .items
+        .into_iter()
+        .filter(|revision| {
+            revision.annotations().get(REVISION_OF) == Some(&cm.name_any())
+                && revision
+                    .owner_references()
+                    .iter()
+                    .any(|owner| Some(&owner.uid) == cm.meta().uid.as_ref())
+        })
+        .collect();
+
+    revisions.sort_by_key(|revision| std::cmp::Reverse(revision.creation_timestamp()));
+
+    Ok(revisions)
+}
+
+/// Put a saved copy's contents back. What's there now is saved first, so that
+/// reverting can be undone the same way. The replace fails if the `ConfigMap`
+/// changes in between.
+pub async fn revert(client: kube::Client, cm: &ConfigMap, revision: &ConfigMap) -> Result<()> {
+    let namespace = cm
+        .namespace()
+        .ok_or_else(|| eyre!("ConfigMaps are namespaced"))?;
+    let api = api(client.clone(), &namespace);
+
+    let mut current = api.get(&cm.name_any()).await?;
+
+    snapshot(client, &current).await?;
+
+    current.data.clone_from(&revision.data);
+    current.binary_data.clone_from(&revision.binary_data);
+
+    api.replace(
+        &cm.name_any(),
+        &PostParams {
+            field_manager: Some(MANAGER.to_string()),
+            ..Default::default()
+        },
+        &current,
+    )
+    .await?;
+
+    Ok(())
+}
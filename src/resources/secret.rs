@@ -0,0 +1,195 @@
+use std::{cmp::Ordering, collections::BTreeMap, sync::Arc};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::api::core::v1::Secret;
+use kube::{api::ObjectMeta, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{age::Age, encoded, quantity, Compare, Filter};
+use crate::widget::table;
+
+static HIDDEN: &str = "<hidden>";
+
+// `kubectl apply` keeps the whole object it was handed here, `data` included.
+static LAST_APPLIED: &str = "kubectl.kubernetes.io/last-applied-configuration";
+
+/// A decoded value that can't be printed by accident. `Debug` and `Display`
+/// only ever write out `<hidden>`, so it stays out of logs, traces and
+/// anything else that formats what it's handed. Getting at the value takes
+/// calling `expose()`, which only the widget showing it does.
+#[derive(Clone)]
+pub struct Masked(String);
+
+impl Masked {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for Masked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{HIDDEN}")
+    }
+}
+
+impl std::fmt::Display for Masked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{HIDDEN}")
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait SecretExt {
+    fn entries(&self) -> Vec<Entry>;
+    fn masked(&self) -> Secret;
+    fn age(&self) -> TimeDelta;
+}
+
+impl SecretExt for Secret {
+    /// Every key of `data`, sorted. The API server only ever returns `data`,
+    /// `stringData` is write-only.
+    fn entries(&self) -> Vec<Entry> {
+        self.data
+            .iter()
+            .flatten()
+            .map(|(key, value)| Entry {
+                key: key.clone(),
+                size: value.0.len(),
+                value: Masked(encoded::describe(&value.0)),
+            })
+            .collect()
+    }
+
+    /// The same secret without any of its values, for showing it as YAML or
+    /// anywhere else the whole object ends up. That includes the copy of it
+    /// `kubectl apply` leaves in the annotations.
+    fn masked(&self) -> Secret {
+        let mut secret = Secret {
+            data: None,
+            string_data: None,
+            ..self.clone()
+        };
+
+        if let Some(annotations) = secret.metadata.annotations.as_mut() {
+            annotations.remove(LAST_APPLIED);
+        }
+
+        secret
+    }
+
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+}
+
+/// A single key of a `Secret`, already decoded.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub key: String,
+    pub value: Masked,
+    pub size: usize,
+}
+
+impl table::Row for Entry {
+    fn columns() -> Vec<&'static str> {
+        vec!["Key", "Size"]
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn cells(&self) -> Vec<String> {
+        vec![self.key.clone(), quantity::bytes(self.size as f64)]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        Some(self.key.clone())
+    }
+}
+
+impl table::Row for Arc<Secret> {
+    fn columns() -> Vec<&'static str> {
+        vec!["Namespace", "Name", "Type", "Data", "Age"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.namespace().unwrap_or_default(),
+            self.name_any(),
+            self.type_.clone().unwrap_or_else(|| "Opaque".to_string()),
+            self.data.as_ref().map_or(0, BTreeMap::len).to_string(),
+            self.age().to_age(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.as_ref().meta())
+    }
+}
+
+impl Filter for Secret {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<Secret> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.namespace()
+            .cmp(&other.namespace())
+            .then_with(|| self.name_any().cmp(&other.name_any()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::ByteString;
+
+    use super::*;
+
+    #[test]
+    fn masked_drops_values_and_last_applied() {
+        let secret = Secret {
+            metadata: ObjectMeta {
+                annotations: Some(BTreeMap::from([
+                    (
+                        LAST_APPLIED.to_string(),
+                        r#"{"data":{"password":"aHVudGVyMg=="}}"#.to_string(),
+                    ),
+                    ("team".to_string(), "payments".to_string()),
+                ])),
+                ..ObjectMeta::default()
+            },
+            data: Some(BTreeMap::from([(
+                "password".to_string(),
+                ByteString(b"hunter2".to_vec()),
+            )])),
+            ..Secret::default()
+        };
+
+        let masked = secret.masked();
+
+        assert!(masked.data.is_none());
+        assert_eq!(
+            masked.metadata.annotations,
+            Some(BTreeMap::from([(
+                "team".to_string(),
+                "payments".to_string()
+            )]))
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::{
+    api::core::v1::{NodeSelectorRequirement, NodeSelectorTerm},
+    apimachinery::pkg::apis::meta::v1::LabelSelector,
+};
+
+/// Whether `labels` are selected by `selector`. This follows the API server's
+/// semantics, an empty selector matches everything.
+pub fn matches(selector: &LabelSelector, labels: &BTreeMap<String, String>) -> bool {
+    let labels_match = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .all(|(k, v)| labels.get(k) == Some(v));
+
+    let expressions_match = selector.match_expressions.iter().flatten().all(|expr| {
+        let values = expr.values.as_deref().unwrap_or_default();
+        let value = labels.get(&expr.key);
+
+        match expr.operator.as_str() {
+            "In" => value.is_some_and(|v| values.contains(v)),
+            "NotIn" => value.map_or(true, |v| !values.contains(v)),
+            "Exists" => value.is_some(),
+            "DoesNotExist" => value.is_none(),
+            _ => false,
+        }
+    });
+
+    labels_match && expressions_match
+}
+
+/// `selector` the way list and watch calls (and `kubectl -l`) take it, eg.
+/// `app=web,tier in (frontend,edge),!canary`.
+pub fn format(selector: &LabelSelector) -> String {
+    let labels = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(k, v)| format!("{k}={v}"));
+
+    let expressions = selector.match_expressions.iter().flatten().map(|expr| {
+        let values = expr.values.as_deref().unwrap_or_default().join(",");
+
+        match expr.operator.as_str() {
+            "In" => format!("{} in ({values})", expr.key),
+            "NotIn" => format!("{} notin ({values})", expr.key),
+            "DoesNotExist" => format!("!{}", expr.key),
+            _ => expr.key.clone(),
+        }
+    });
+
+    labels.chain(expressions).collect::<Vec<_>>().join(",")
+}
+
+fn node_requirement(req: &NodeSelectorRequirement, value: Option<&String>) -> bool {
+    let values = req.values.as_deref().unwrap_or_default();
+
+    // `Gt` and `Lt` compare a single integer value.
+    let compare = |ordering: std::cmp::Ordering| {
+        let (Some(value), Some(target)) = (value, values.first()) else {
+            return false;
+        };
+
+        match (value.parse::<i64>(), target.parse::<i64>()) {
+            (Ok(value), Ok(target)) => value.cmp(&target) == ordering,
+            _ => false,
+        }
+    };
+
+    match req.operator.as_str() {
+        "In" => value.is_some_and(|v| values.contains(v)),
+        "NotIn" => value.map_or(true, |v| !values.contains(v)),
+        "Exists" => value.is_some(),
+        "DoesNotExist" => value.is_none(),
+        "Gt" => compare(std::cmp::Ordering::Greater),
+        "Lt" => compare(std::cmp::Ordering::Less),
+        _ => false,
+    }
+}
+
+/// Whether a node with `name` and `labels` matches a node selector term. The
+/// requirements in a term must all match, an empty term matches nothing.
+pub fn matches_term(
+    term: &NodeSelectorTerm,
+    name: &str,
+    labels: &BTreeMap<String, String>,
+) -> bool {
+    let expressions = term.match_expressions.as_deref().unwrap_or_default();
+    let fields = term.match_fields.as_deref().unwrap_or_default();
+
+    if expressions.is_empty() && fields.is_empty() {
+        return false;
+    }
+
+    let name = name.to_string();
+
+    expressions
+        .iter()
+        .all(|req| node_requirement(req, labels.get(&req.key)))
+        && fields.iter().all(|req| {
+            // `metadata.name` is the only supported field.
+            req.key == "metadata.name" && node_requirement(req, Some(&name))
+        })
+}
@@ -0,0 +1,168 @@
+pub mod check;
+pub mod churn;
+
+use std::{cmp::Ordering, sync::Arc};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::{
+    api::core::v1::Service,
+    apimachinery::pkg::{apis::meta::v1::LabelSelector, util::intstr::IntOrString},
+};
+use kube::{api::ObjectMeta, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{age::Age, Compare, Filter};
+use crate::widget::table;
+
+#[allow(clippy::module_name_repetitions)]
+pub trait ServiceExt {
+    fn dns_name(&self) -> String;
+    fn cluster_ips(&self) -> Vec<String>;
+    fn is_headless(&self) -> bool;
+    fn type_(&self) -> String;
+    fn ports(&self) -> String;
+    fn selector(&self) -> Option<LabelSelector>;
+    fn age(&self) -> TimeDelta;
+}
+
+impl ServiceExt for Service {
+    /// Leaves the cluster domain off so that it is picked up from the search
+    /// path of whichever pod does the lookup, not every cluster uses
+    /// `cluster.local`.
+    fn dns_name(&self) -> String {
+        format!(
+            "{}.{}.svc",
+            self.name_any(),
+            self.namespace().unwrap_or_default()
+        )
+    }
+
+    fn cluster_ips(&self) -> Vec<String> {
+        let Some(spec) = self.spec.as_ref() else {
+            return Vec::new();
+        };
+
+        spec.cluster_ips
+            .clone()
+            .or_else(|| spec.cluster_ip.clone().map(|ip| vec![ip]))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|ip| ip != "None" && !ip.is_empty())
+            .collect()
+    }
+
+    fn is_headless(&self) -> bool {
+        self.spec
+            .as_ref()
+            .and_then(|spec| spec.cluster_ip.as_deref())
+            == Some("None")
+    }
+
+    fn type_(&self) -> String {
+        self.spec
+            .as_ref()
+            .and_then(|spec| spec.type_.clone())
+            .unwrap_or_else(|| "ClusterIP".to_string())
+    }
+
+    /// The same way `kubectl get services` shows them, `port[:nodePort]/protocol`
+    /// with the target port added when it's different, eg. `80→8080/TCP`.
+    fn ports(&self) -> String {
+        let Some(ports) = self.spec.as_ref().and_then(|spec| spec.ports.as_ref()) else {
+            return String::new();
+        };
+
+        ports
+            .iter()
+            .map(|port| {
+                let mut out = port.port.to_string();
+
+                if let Some(node_port) = port.node_port {
+                    out.push_str(&format!(":{node_port}"));
+                }
+
+                if let Some(target) = port.target_port.as_ref() {
+                    let target = match target {
+                        IntOrString::Int(target) => target.to_string(),
+                        IntOrString::String(name) => name.clone(),
+                    };
+
+                    if target != port.port.to_string() {
+                        out.push_str(&format!("→{target}"));
+                    }
+                }
+
+                format!("{out}/{}", port.protocol.as_deref().unwrap_or("TCP"))
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Services without a selector have their endpoints managed by something
+    /// else, there are no pods to go looking for.
+    fn selector(&self) -> Option<LabelSelector> {
+        self.spec
+            .as_ref()
+            .and_then(|spec| spec.selector.clone())
+            .filter(|selector| !selector.is_empty())
+            .map(|selector| LabelSelector {
+                match_labels: Some(selector),
+                ..Default::default()
+            })
+    }
+
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+}
+
+impl table::Row for Arc<Service> {
+    fn columns() -> Vec<&'static str> {
+        vec!["Namespace", "Name", "Type", "Cluster-IP", "Ports", "Age"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.namespace().unwrap_or_default(),
+            self.name_any(),
+            self.type_(),
+            if self.is_headless() {
+                "None".to_string()
+            } else {
+                self.cluster_ips().join(",")
+            },
+            self.ports(),
+            self.age().to_age(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.as_ref().meta())
+    }
+}
+
+impl Filter for Service {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<Service> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.namespace()
+            .cmp(&other.namespace())
+            .then_with(|| self.name_any().cmp(&other.name_any()))
+    }
+}
@@ -0,0 +1,331 @@
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use eyre::{eyre, Result};
+use http::{header, Request};
+use http_body_util::{BodyExt, Full};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use k8s_openapi::{
+    api::core::v1::{Pod, Service, ServicePort},
+    apimachinery::pkg::util::intstr::IntOrString,
+};
+use kube::{
+    api::{Api, ListParams},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use tokio::io::AsyncRead;
+
+use super::ServiceExt;
+use crate::resources::{
+    pod::forward::{Connection, Forward},
+    selector,
+};
+
+lazy_static! {
+    static ref PORT_CHECKS: IntCounterVec = register_int_counter_vec!(
+        "service_port_checks_total",
+        "Number of service ports checked, by protocol and whether they were reachable",
+        &["protocol", "result"]
+    )
+    .unwrap();
+}
+
+// The kubelet reports a port that nothing is listening on right away, a port
+// that hasn't said anything wrong by now is taken as accepting connections.
+static CONNECT_WAIT: Duration = Duration::from_secs(1);
+static CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+// `grpc.health.v1.HealthCheckResponse.ServingStatus`.
+static SERVING: u8 = 1;
+
+/// How far a check goes past opening a connection, picked from the port's
+/// `appProtocol` or name the same way service meshes do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Protocol {
+    Tcp,
+    /// `GET /`, anything other than a 5xx counts.
+    Http,
+    /// `grpc.health.v1.Health/Check` for the whole server.
+    Grpc,
+}
+
+impl Protocol {
+    fn of(port: &ServicePort) -> Self {
+        let name = port.name.as_deref().unwrap_or_default();
+
+        match port.app_protocol.as_deref() {
+            Some("grpc") => Self::Grpc,
+            Some("http" | "kubernetes.io/h2c") => Self::Http,
+            None if name == "grpc" || name.starts_with("grpc-") => Self::Grpc,
+            None if name == "http" || name.starts_with("http-") => Self::Http,
+            _ => Self::Tcp,
+        }
+    }
+}
+
+/// How one of the service's ports did.
+pub struct Outcome {
+    pub port: String,
+    pub protocol: Protocol,
+    pub result: Result<String, String>,
+    pub took: Duration,
+}
+
+/// Every `TCP` port the service has, checked through a port-forward to one of
+/// the ready pods behind it. That skips kube-proxy and the cluster IP, which
+/// is the part that's hard to get to from outside the cluster, and leaves
+/// whether the pods are listening where the service says they are.
+pub async fn all(client: kube::Client, service: &Service) -> Result<(String, Vec<Outcome>)> {
+    let pod = backend(client.clone(), service).await?;
+
+    let ports = service
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.ports.clone())
+        .unwrap_or_default();
+
+    let mut outcomes = Vec::new();
+
+    for port in ports {
+        let protocol = Protocol::of(&port);
+        let label = format!(
+            "{}{}",
+            port.port,
+            port.name
+                .as_ref()
+                .map(|name| format!(" ({name})"))
+                .unwrap_or_default()
+        );
+
+        let started = Instant::now();
+
+        let result = if port.protocol.as_deref().unwrap_or("TCP") == "TCP" {
+            match target(&pod, &port) {
+                Some(target) => check(client.clone(), &pod, service, target, protocol).await,
+                None => Err(eyre!("no container in the pod has the target port")),
+            }
+        } else {
+            Err(eyre!("only TCP ports can be checked"))
+        }
+        .map_err(|err| err.to_string());
+
+        PORT_CHECKS
+            .with_label_values(&[
+                &protocol.to_string(),
+                if result.is_ok() { "success" } else { "failure" },
+            ])
+            .inc();
+
+        outcomes.push(Outcome {
+            port: label,
+            protocol,
+            result,
+            took: started.elapsed(),
+        });
+    }
+
+    Ok((pod.name_any(), outcomes))
+}
+
+async fn backend(client: kube::Client, service: &Service) -> Result<Pod> {
+    let selector = service
+        .selector()
+        .ok_or_else(|| eyre!("the service has no selector, there are no pods to check"))?;
+
+    let pods = Api::<Pod>::namespaced(client, &service.namespace().unwrap_or_default())
+        .list(&ListParams::default().labels(&selector::format(&selector)))
+        .await?;
+
+    if pods.items.is_empty() {
+        return Err(eyre!("no pods match the selector"));
+    }
+
+    pods.items
+        .into_iter()
+        .find(|pod| {
+            pod.status
+                .as_ref()
+                .and_then(|status| status.conditions.as_ref())
+                .into_iter()
+                .flatten()
+                .any(|condition| condition.type_ == "Ready" && condition.status == "True")
+        })
+        .ok_or_else(|| eyre!("pods match the selector, but none of them are ready"))
+}
+
+// Named target ports are looked up in the pod's containers, the same way the
+// endpoints controller does it.
+fn target(pod: &Pod, port: &ServicePort) -> Option<u16> {
+    let number = match port.target_port.as_ref() {
+        None => port.port,
+        Some(IntOrString::Int(number)) => *number,
+        Some(IntOrString::String(name)) => {
+            pod.spec
+                .as_ref()?
+                .containers
+                .iter()
+                .flat_map(|container| container.ports.iter().flatten())
+                .find(|container_port| container_port.name.as_deref() == Some(name))?
+                .container_port
+        }
+    };
+
+    u16::try_from(number).ok()
+}
+
+// What the kubelet sends back is a sentence per address family, everything
+// that matters is that nothing was listening.
+fn reason(err: &str) -> String {
+    if err.contains("connection refused") {
+        "nothing is listening (connection refused)".to_string()
+    } else {
+        err.trim().to_string()
+    }
+}
+
+async fn check(
+    client: kube::Client,
+    pod: &Pod,
+    service: &Service,
+    port: u16,
+    protocol: Protocol,
+) -> Result<String> {
+    let api = Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default());
+
+    let mut forwarder = Forward::new(&api, &pod.name_any(), &[port]).await?;
+    let stream = forwarder
+        .take_stream(port)
+        .ok_or_else(|| eyre!("port {port} is not available"))?;
+    let error = forwarder
+        .take_error(port)
+        .ok_or_else(|| eyre!("port {port} is not available"))?;
+
+    let authority = format!("{}:{port}", service.dns_name());
+
+    let probe = async move {
+        match protocol {
+            Protocol::Tcp => {
+                tokio::time::sleep(CONNECT_WAIT).await;
+                drop(stream);
+
+                Ok(format!("port {port} is accepting connections"))
+            }
+            Protocol::Http => http(stream, &authority).await,
+            Protocol::Grpc => grpc(stream, &authority).await,
+        }
+    };
+
+    let result = tokio::time::timeout(CHECK_TIMEOUT, async move {
+        tokio::select! {
+            result = probe => result,
+            Some(err) = error => Err(eyre!(reason(&err))),
+        }
+    })
+    .await
+    .unwrap_or_else(|_| {
+        Err(eyre!(
+            "no answer after {}s on port {port}",
+            CHECK_TIMEOUT.as_secs()
+        ))
+    });
+
+    drop(forwarder);
+
+    result
+}
+
+async fn http<S>(stream: S, authority: &str) -> Result<String>
+where
+    S: AsyncRead + tokio::io::AsyncWrite + Send + Unpin + 'static,
+{
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+    let conn = Connection::spawn(conn);
+
+    let resp = sender
+        .send_request(
+            Request::get("/")
+                .header(header::HOST, authority)
+                .body(Full::new(Bytes::new()))?,
+        )
+        .await;
+
+    drop(conn);
+
+    let status = resp?.status();
+
+    if status.is_server_error() {
+        return Err(eyre!("GET / returned {status}"));
+    }
+
+    Ok(format!("GET / returned {status}"))
+}
+
+async fn grpc<S>(stream: S, authority: &str) -> Result<String>
+where
+    S: AsyncRead + tokio::io::AsyncWrite + Send + Unpin + 'static,
+{
+    let (mut sender, conn) =
+        hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(stream)).await?;
+    let conn = Connection::spawn(conn);
+
+    // An empty `HealthCheckRequest` asks about the server as a whole. The body
+    // is the message prefixed with whether it is compressed and its length.
+    let resp = sender
+        .send_request(
+            Request::post(format!("http://{authority}/grpc.health.v1.Health/Check"))
+                .header(header::CONTENT_TYPE, "application/grpc")
+                .header(header::TE, "trailers")
+                .body(Full::new(Bytes::from_static(&[0, 0, 0, 0, 0])))?,
+        )
+        .await;
+
+    let result = async {
+        let resp = resp?;
+        let headers = resp.headers().clone();
+        let body = resp.into_body().collect().await?;
+
+        // Errors without a body come back as headers only, without trailers.
+        let status = body
+            .trailers()
+            .and_then(|trailers| trailers.get("grpc-status"))
+            .or_else(|| headers.get("grpc-status"))
+            .and_then(|status| status.to_str().ok())
+            .unwrap_or("0")
+            .to_string();
+
+        match status.as_str() {
+            "0" => {}
+            "12" => return Err(eyre!("the server doesn't implement grpc.health.v1")),
+            status => return Err(eyre!("health check failed with grpc-status {status}")),
+        }
+
+        // The response is `status` as field 1, left out entirely when it is
+        // `UNKNOWN`.
+        let body = body.to_bytes();
+        let serving = match body.get(5..) {
+            Some([0x08, status, ..]) => *status,
+            _ => 0,
+        };
+
+        if serving == SERVING {
+            Ok("health check says SERVING".to_string())
+        } else {
+            Err(eyre!(
+                "health check says {}",
+                match serving {
+                    2 => "NOT_SERVING",
+                    3 => "SERVICE_UNKNOWN",
+                    _ => "UNKNOWN",
+                }
+            ))
+        }
+    }
+    .await;
+
+    drop(conn);
+
+    result
+}
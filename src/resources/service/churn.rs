@@ -0,0 +1,230 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use k8s_openapi::api::discovery::v1::EndpointSlice;
+use kube::{runtime::watcher, ResourceExt};
+
+// Plenty to see a backend flapping, without holding onto a day of history for
+// a service that's been left open.
+static MAX_CHANGES: usize = 500;
+
+/// Backends that changed at least this often within `WINDOW` are flapping.
+static FLAPPING: usize = 3;
+pub static WINDOW: TimeDelta = TimeDelta::minutes(5);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum Kind {
+    Added,
+    Removed,
+    Ready,
+    NotReady,
+}
+
+/// A single address behind the service, across every slice.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backend {
+    pub pod: Option<String>,
+    pub ready: bool,
+}
+
+pub struct Change {
+    pub at: DateTime<Utc>,
+    pub kind: Kind,
+    pub address: String,
+    pub pod: Option<String>,
+}
+
+/// Every time an address was added to, removed from or changed readiness in
+/// the `EndpointSlice`s of a service since the watch started, newest last.
+/// Slices are merged before comparing, the endpoint controller moving an
+/// address from one slice to another isn't churn.
+#[derive(Default)]
+pub struct Churn {
+    slices: HashMap<String, BTreeMap<String, Backend>>,
+    changes: VecDeque<Change>,
+
+    initialized: bool,
+    // Slices seen during a re-list, the rest were deleted while the watch was
+    // disconnected.
+    relisted: HashMap<String, BTreeMap<String, Backend>>,
+}
+
+impl Churn {
+    pub fn changes(&self) -> &VecDeque<Change> {
+        &self.changes
+    }
+
+    /// Forget the changes so far, the backends are kept to compare against.
+    pub fn clear(&mut self) {
+        self.changes.clear();
+    }
+
+    /// Every address currently behind the service.
+    pub fn backends(&self) -> BTreeMap<String, Backend> {
+        self.slices
+            .values()
+            .flatten()
+            .fold(BTreeMap::new(), |mut backends, (address, backend)| {
+                // An address can briefly be in two slices while it moves, it is
+                // ready if either says so.
+                backends
+                    .entry(address.clone())
+                    .and_modify(|existing: &mut Backend| existing.ready |= backend.ready)
+                    .or_insert_with(|| backend.clone());
+
+                backends
+            })
+    }
+
+    /// How many changes happened in each of the last `buckets` minutes, oldest
+    /// first.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn per_minute(&self, buckets: usize) -> Vec<u64> {
+        let now = Utc::now();
+        let mut counts = vec![0; buckets];
+
+        for change in &self.changes {
+            let ago = (now - change.at).num_minutes().max(0) as usize;
+
+            if let Some(count) = ago
+                .checked_add(1)
+                .and_then(|ago| buckets.checked_sub(ago))
+                .and_then(|idx| counts.get_mut(idx))
+            {
+                *count += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Addresses that changed at least `FLAPPING` times within `WINDOW`, most
+    /// changes first.
+    pub fn flapping(&self) -> Vec<(String, Option<String>, usize)> {
+        let since = Utc::now() - WINDOW;
+
+        let mut counts: HashMap<&str, (Option<String>, usize)> = HashMap::new();
+
+        for change in self.changes.iter().filter(|change| change.at >= since) {
+            let entry = counts
+                .entry(change.address.as_str())
+                .or_insert_with(|| (change.pod.clone(), 0));
+            entry.1 += 1;
+        }
+
+        let mut flapping: Vec<_> = counts
+            .into_iter()
+            .filter(|(_, (_, count))| *count >= FLAPPING)
+            .map(|(address, (pod, count))| (address.to_string(), pod, count))
+            .collect();
+        flapping.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+        flapping
+    }
+
+    fn record(&mut self, before: &BTreeMap<String, Backend>) {
+        let after = self.backends();
+        let at = Utc::now();
+
+        let mut push = |kind, address: &str, backend: &Backend| {
+            self.changes.push_back(Change {
+                at,
+                kind,
+                address: address.to_string(),
+                pod: backend.pod.clone(),
+            });
+        };
+
+        for (address, backend) in &after {
+            match before.get(address) {
+                None => push(Kind::Added, address, backend),
+                Some(previous) if previous.ready != backend.ready => push(
+                    if backend.ready {
+                        Kind::Ready
+                    } else {
+                        Kind::NotReady
+                    },
+                    address,
+                    backend,
+                ),
+                Some(_) => {}
+            }
+        }
+
+        for (address, backend) in before {
+            if !after.contains_key(address) {
+                push(Kind::Removed, address, backend);
+            }
+        }
+
+        while self.changes.len() > MAX_CHANGES {
+            self.changes.pop_front();
+        }
+    }
+
+    fn update(&mut self, slices: HashMap<String, BTreeMap<String, Backend>>) {
+        let before = self.backends();
+        self.slices = slices;
+        self.record(&before);
+    }
+
+    pub fn handle(&mut self, event: watcher::Event<EndpointSlice>) {
+        match event {
+            watcher::Event::Init => self.relisted.clear(),
+            watcher::Event::InitApply(slice) => {
+                self.relisted.insert(slice.name_any(), backends(&slice));
+            }
+            watcher::Event::InitDone => {
+                let slices = std::mem::take(&mut self.relisted);
+
+                if self.initialized {
+                    self.update(slices);
+                } else {
+                    self.slices = slices;
+                }
+
+                self.initialized = true;
+            }
+            watcher::Event::Apply(slice) => {
+                let mut slices = self.slices.clone();
+                slices.insert(slice.name_any(), backends(&slice));
+
+                self.update(slices);
+            }
+            watcher::Event::Delete(slice) => {
+                let mut slices = self.slices.clone();
+                slices.remove(&slice.name_any());
+
+                self.update(slices);
+            }
+        }
+    }
+}
+
+// A missing `ready` condition means ready, see `EndpointConditions`.
+fn backends(slice: &EndpointSlice) -> BTreeMap<String, Backend> {
+    slice
+        .endpoints
+        .iter()
+        .flat_map(|endpoint| {
+            let backend = Backend {
+                pod: endpoint
+                    .target_ref
+                    .as_ref()
+                    .filter(|target| target.kind.as_deref() == Some("Pod"))
+                    .and_then(|target| target.name.clone()),
+                ready: endpoint
+                    .conditions
+                    .as_ref()
+                    .and_then(|conditions| conditions.ready)
+                    .unwrap_or(true),
+            };
+
+            endpoint
+                .addresses
+                .iter()
+                .map(move |address| (address.clone(), backend.clone()))
+        })
+        .collect()
+}
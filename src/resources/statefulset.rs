@@ -0,0 +1,487 @@
+use std::{cmp::Ordering, collections::HashMap, sync::Arc};
+
+use chrono::{TimeDelta, Utc};
+use k8s_openapi::api::{apps::v1::StatefulSet, core::v1::Pod};
+use kube::{api::ObjectMeta, Resource, ResourceExt};
+use ratatui::widgets::Row;
+
+use super::{
+    age::Age,
+    deployment::{failing, Health},
+    kstatus::{self, Status},
+    pod::PodExt,
+    store::Store,
+    Compare, Filter,
+};
+use crate::widget::table;
+
+// Past this many ordinals the column only shows a count of the rest, it would
+// otherwise push everything after it off the screen.
+static MAX_ORDINALS: usize = 20;
+
+// Label the controller puts on every pod with the revision it was created
+// from.
+static REVISION_LABEL: &str = "controller-revision-hash";
+
+/// How the pod for a single ordinal is doing. Unlike a deployment, every
+/// ordinal is a specific pod that has to come back, so one that's missing or
+/// stuck holds up everything after it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum State {
+    Missing,
+    Pending,
+    NotReady,
+    Ready,
+    Terminating,
+    Failed(String),
+}
+
+impl State {
+    pub fn of(pod: &Pod) -> Self {
+        if pod.metadata.deletion_timestamp.is_some() {
+            return State::Terminating;
+        }
+
+        if let Some(reason) = failing(pod) {
+            return State::Failed(reason);
+        }
+
+        let status = pod.status.as_ref();
+
+        if status.and_then(|status| status.phase.as_deref()) == Some("Pending") {
+            return State::Pending;
+        }
+
+        let ready = status
+            .and_then(|status| status.conditions.as_ref())
+            .into_iter()
+            .flatten()
+            .any(|condition| condition.type_ == "Ready" && condition.status == "True");
+
+        if ready {
+            State::Ready
+        } else {
+            State::NotReady
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            State::Ready => '●',
+            State::Pending | State::NotReady | State::Terminating => '◐',
+            State::Missing => '○',
+            State::Failed(_) => '✗',
+        }
+    }
+}
+
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            State::Missing => write!(f, "Missing"),
+            State::Pending => write!(f, "Pending"),
+            State::NotReady => write!(f, "NotReady"),
+            State::Ready => write!(f, "Ready"),
+            State::Terminating => write!(f, "Terminating"),
+            State::Failed(reason) => write!(f, "Failed ({reason})"),
+        }
+    }
+}
+
+/// The pod at one ordinal, `None` when it doesn't exist right now.
+pub struct Ordinal {
+    pub index: i32,
+    pub pod: Option<Arc<Pod>>,
+    pub state: State,
+    /// Whether the pod is on the revision being rolled out.
+    pub updated: bool,
+}
+
+impl table::Row for Ordinal {
+    fn columns() -> Vec<&'static str> {
+        vec!["Ordinal", "Pod", "Status", "Restarts", "Revision", "Age"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let pod = self.pod.as_ref();
+
+        vec![
+            self.index.to_string(),
+            pod.map(|pod| pod.name_any()).unwrap_or_default(),
+            self.state.to_string(),
+            pod.map(|pod| pod.restarts()).unwrap_or_default(),
+            pod.and_then(|pod| pod.labels().get(REVISION_LABEL).cloned())
+                .map(|revision| {
+                    if self.updated {
+                        revision
+                    } else {
+                        format!("{revision} (outdated)")
+                    }
+                })
+                .unwrap_or_default(),
+            pod.map(|pod| pod.age().to_age()).unwrap_or_default(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        let color = match self.state {
+            State::Ready => style.healthy,
+            State::Pending | State::NotReady | State::Terminating => style.normal,
+            State::Missing | State::Failed(_) => style.unhealthy,
+        };
+
+        table::highlight(self, &["Status"], color)
+    }
+
+    fn uid(&self) -> Option<String> {
+        Some(self.index.to_string())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        self.pod.as_ref().map(|pod| pod.as_ref().meta())
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub trait StatefulSetExt {
+    fn age(&self) -> TimeDelta;
+    fn desired(&self) -> i32;
+    fn owns(&self, pod: &Pod) -> bool;
+    fn ordinals(&self, pods: &[&Arc<Pod>]) -> Vec<Ordinal>;
+    fn health(&self, ordinals: &[Ordinal]) -> Health;
+    fn revision(&self) -> String;
+}
+
+impl StatefulSetExt for StatefulSet {
+    fn age(&self) -> TimeDelta {
+        let Some(creation) = self.creation_timestamp() else {
+            return TimeDelta::zero();
+        };
+
+        Utc::now() - creation.0
+    }
+
+    fn desired(&self) -> i32 {
+        self.spec
+            .as_ref()
+            .and_then(|spec| spec.replicas)
+            .unwrap_or(1)
+    }
+
+    // Pods are matched on their owner instead of the selector, that way pods
+    // from an old statefulset of the same name that are still going away don't
+    // count.
+    fn owns(&self, pod: &Pod) -> bool {
+        let Some(uid) = self.metadata.uid.as_ref() else {
+            return false;
+        };
+
+        pod.owner_references()
+            .iter()
+            .any(|owner| owner.controller == Some(true) && &owner.uid == uid)
+    }
+
+    /// Every ordinal from 0 up to the number of replicas, along with any pods
+    /// past that which haven't been scaled down yet.
+    fn ordinals(&self, pods: &[&Arc<Pod>]) -> Vec<Ordinal> {
+        let prefix = format!("{}-", self.name_any());
+        let update = self
+            .status
+            .as_ref()
+            .and_then(|status| status.update_revision.clone());
+
+        let mut by_ordinal: HashMap<i32, Arc<Pod>> = pods
+            .iter()
+            .filter_map(|pod| {
+                let index = pod.name_any().strip_prefix(&prefix)?.parse().ok()?;
+
+                Some((index, (*pod).clone()))
+            })
+            .collect();
+
+        let last = by_ordinal
+            .keys()
+            .copied()
+            .max()
+            .unwrap_or_default()
+            .max(self.desired() - 1);
+
+        (0..=last)
+            .filter_map(|index| {
+                let pod = by_ordinal.remove(&index);
+
+                // Ordinals past the replicas that are already gone are just
+                // what scaling down looks like.
+                if pod.is_none() && index >= self.desired() {
+                    return None;
+                }
+
+                Some(Ordinal {
+                    index,
+                    state: pod.as_deref().map_or(State::Missing, State::of),
+                    updated: pod.as_ref().is_some_and(|pod| {
+                        update.is_none() || pod.labels().get(REVISION_LABEL) == update.as_ref()
+                    }),
+                    pod,
+                })
+            })
+            .collect()
+    }
+
+    fn health(&self, ordinals: &[Ordinal]) -> Health {
+        let status = kstatus::of("apps", "StatefulSet", self).unwrap_or(Status::Current);
+
+        if let Status::Failed(reason) = status {
+            return Health::Failed(reason);
+        }
+
+        if let Some((index, reason)) = ordinals.iter().find_map(|ordinal| match &ordinal.state {
+            State::Failed(reason) => Some((ordinal.index, reason)),
+            _ => None,
+        }) {
+            return Health::Failed(format!("{reason} on ordinal {index}"));
+        }
+
+        let Status::InProgress(reason) = status else {
+            return Health::Healthy;
+        };
+
+        let sts = self.status.clone().unwrap_or_default();
+
+        // Pods are replaced one ordinal at a time, from the highest down, so a
+        // rollout spends most of its time with a pod missing or not ready.
+        let rolling = sts.observed_generation < self.metadata.generation
+            || sts.current_revision != sts.update_revision
+            || sts.updated_replicas.unwrap_or_default() < self.desired();
+
+        if rolling {
+            return Health::Progressing;
+        }
+
+        Health::Degraded(reason)
+    }
+
+    /// The revision pods are on, followed by the one being rolled out when
+    /// there's a rollout in progress.
+    fn revision(&self) -> String {
+        let Some(status) = self.status.as_ref() else {
+            return String::new();
+        };
+
+        let prefix = format!("{}-", self.name_any());
+        let short = |revision: &String| {
+            revision
+                .strip_prefix(&prefix)
+                .unwrap_or(revision)
+                .to_string()
+        };
+
+        match (
+            status.current_revision.as_ref(),
+            status.update_revision.as_ref(),
+        ) {
+            (Some(current), Some(update)) if current != update => {
+                format!("{} → {}", short(current), short(update))
+            }
+            (Some(revision), _) | (None, Some(revision)) => short(revision),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// A statefulset along with the pods at each of its ordinals.
+pub struct Workload {
+    pub statefulset: Arc<StatefulSet>,
+    pub ordinals: Vec<Ordinal>,
+    pub health: Health,
+}
+
+impl Workload {
+    fn ordinals(&self) -> String {
+        let mut symbols: String = self
+            .ordinals
+            .iter()
+            .take(MAX_ORDINALS)
+            .map(|ordinal| ordinal.state.symbol())
+            .collect();
+
+        if self.ordinals.len() > MAX_ORDINALS {
+            symbols.push_str(&format!(" +{}", self.ordinals.len() - MAX_ORDINALS));
+        }
+
+        symbols
+    }
+}
+
+impl table::Row for Workload {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Namespace",
+            "Name",
+            "Health",
+            "Ready",
+            "Updated",
+            "Revision",
+            "Ordinals",
+            "Age",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let status = self.statefulset.status.clone().unwrap_or_default();
+
+        vec![
+            self.statefulset.namespace().unwrap_or_default(),
+            self.statefulset.name_any(),
+            self.health.indicator().label(&self.health),
+            format!(
+                "{}/{}",
+                status.ready_replicas.unwrap_or_default(),
+                self.statefulset.desired()
+            ),
+            status.updated_replicas.unwrap_or_default().to_string(),
+            self.statefulset.revision(),
+            self.ordinals(),
+            self.statefulset.age().to_age(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        let health = match self.health {
+            Health::Healthy => style.healthy,
+            Health::Progressing => style.normal,
+            Health::Degraded(_) | Health::Failed(_) => style.unhealthy,
+        };
+
+        table::highlight(self, &["Health"], health)
+    }
+
+    fn uid(&self) -> Option<String> {
+        ResourceExt::uid(self.statefulset.as_ref())
+    }
+
+    fn meta(&self) -> Option<&ObjectMeta> {
+        Some(self.statefulset.as_ref().meta())
+    }
+}
+
+impl Filter for StatefulSet {
+    fn matches(&self, filter: &str) -> bool {
+        self.name_any().contains(filter)
+    }
+}
+
+impl Compare for Arc<StatefulSet> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.namespace()
+            .cmp(&other.namespace())
+            .then_with(|| self.name_any().cmp(&other.name_any()))
+    }
+}
+
+/// Statefulsets joined with the pods they own, for the health and ordinals of
+/// each one.
+pub struct StatefulSets {
+    pub statefulsets: Arc<Store<StatefulSet>>,
+    pub pods: Arc<Store<Pod>>,
+}
+
+impl StatefulSets {
+    fn workload(statefulset: Arc<StatefulSet>, pods: &[&Arc<Pod>]) -> Workload {
+        let owned: Vec<_> = pods
+            .iter()
+            .filter(|pod| statefulset.owns(pod))
+            .copied()
+            .collect();
+
+        let ordinals = statefulset.ordinals(&owned);
+
+        Workload {
+            health: statefulset.health(&ordinals),
+            ordinals,
+            statefulset,
+        }
+    }
+
+    /// The latest version of a single statefulset, by uid.
+    pub fn get(&self, uid: &str) -> Option<Workload> {
+        let statefulset = self
+            .statefulsets
+            .snapshot()
+            .iter()
+            .find(|sts| sts.metadata.uid.as_deref() == Some(uid))?
+            .clone();
+
+        let pods = self.pods.snapshot();
+        let pods: Vec<_> = pods
+            .iter()
+            .filter(|pod| pod.namespace() == statefulset.namespace())
+            .collect();
+
+        Some(Self::workload(statefulset, &pods))
+    }
+}
+
+impl table::Items for Arc<StatefulSets> {
+    type Item = Workload;
+
+    fn error(&self) -> Option<String> {
+        self.statefulsets.error()
+    }
+
+    fn retry(&self) {
+        self.statefulsets.retry();
+    }
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let pods = self.pods.snapshot();
+
+        let mut by_ns: HashMap<String, Vec<&Arc<Pod>>> = HashMap::new();
+        for pod in pods.iter() {
+            by_ns
+                .entry(pod.namespace().unwrap_or_default())
+                .or_default()
+                .push(pod);
+        }
+
+        self.statefulsets
+            .items(filter)
+            .map(|statefulset| {
+                let pods = by_ns
+                    .get(&statefulset.namespace().unwrap_or_default())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+
+                StatefulSets::workload(statefulset, pods)
+            })
+            .collect()
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(table::revision([
+            self.statefulsets.generation(),
+            self.pods.generation(),
+        ]))
+    }
+}
+
+/// The ordinals of a single statefulset, kept up to date as its pods change.
+pub struct Ordinals {
+    pub statefulsets: Arc<StatefulSets>,
+    pub uid: String,
+}
+
+impl table::Items for Ordinals {
+    type Item = Ordinal;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.statefulsets
+            .get(&self.uid)
+            .map(|workload| workload.ordinals)
+            .unwrap_or_default()
+    }
+
+    fn revision(&self) -> Option<u64> {
+        table::Items::revision(&self.statefulsets)
+    }
+}
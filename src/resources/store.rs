@@ -1,138 +1,431 @@
-use std::{future::ready, iter::Iterator, sync::Arc};
+pub mod coalesce;
+pub mod memory;
+pub mod poll;
+pub mod prefetch;
+pub mod watch;
 
-use eyre::{eyre, Result};
-use futures::StreamExt;
-use kube::{
-    runtime::{self, reflector, watcher::Config, WatchStreamExt},
-    Api, ResourceExt,
+use std::{
+    hash::Hash,
+    io,
+    iter::Iterator,
+    sync::{Arc, LazyLock, Mutex, Weak},
+    time::Duration,
 };
-use serde::de::DeserializeOwned;
-use tokio::{sync::oneshot, task::JoinSet};
 
+use kube::{api::ListParams, core::ErrorResponse, runtime::watcher::Config, ResourceExt};
+use regex::Regex;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{sync::oneshot, time::MissedTickBehavior};
+
+use self::{
+    memory::{Footprint, Memory, Trim},
+    poll::Poll,
+    watch::Watch,
+};
 use super::{Compare, Filter};
-use crate::widget::table;
+use crate::{
+    policy::{self, Scope},
+    usage,
+    widget::{startup::Startup, table},
+};
 
-async fn is_ready<K>(reader: reflector::Store<K>, tx: oneshot::Sender<()>) -> Result<()>
-where
-    K: kube::Resource<DynamicType = ()>
-        + Clone
-        + std::fmt::Debug
-        + Send
-        + Sync
-        + DeserializeOwned
-        + 'static,
-{
-    reader.wait_until_ready().await?;
+/// Where the objects in a store come from. Tables only ever see the `Store`,
+/// so how it is kept up to date (a watch, polling a list, something that isn't
+/// the API server at all) can change without any widget noticing.
+#[allow(clippy::module_name_repetitions)]
+pub trait StoreBackend<K>: Send + Sync {
+    /// Everything held right now, in no particular order.
+    fn state(&self) -> Vec<Arc<K>>;
+
+    /// Moves on whenever `state` has changed. Snapshots are only rebuilt when
+    /// this is different from the generation they were built at.
+    fn generation(&self) -> u64;
+
+    /// Why the store can't be filled right now, such as the user not being
+    /// allowed to list the kind. Cleared once it works again.
+    fn error(&self) -> Option<String> {
+        None
+    }
 
-    tx.send(()).map_err(|()| eyre!("receiver dropped"))?;
+    /// Try again right away instead of waiting on the backoff.
+    fn retry(&self) {}
 
-    Ok(())
+    /// Let go of everything, to be fetched again the next time `state` is
+    /// called. Backends that can't do this return `false`.
+    fn trim(&self) -> bool {
+        false
+    }
 }
 
-pub struct Store<K>
-where
-    K: kube::Resource<DynamicType = ()>
-        + Clone
-        + std::fmt::Debug
-        + Send
-        + Sync
-        + DeserializeOwned
-        + 'static,
-{
-    tasks: JoinSet<Result<()>>,
-    reader: reflector::Store<K>,
+// What the API server says when RBAC turns a request down, eg. `pods is
+// forbidden: User "me" cannot list resource "pods" in API group "" at the
+// cluster scope`.
+static FORBIDDEN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"cannot (\S+) resource "([^"]+)"(?: in API group "[^"]*")?(?: in the namespace "([^"]+)"| (at the cluster scope))?"#,
+    )
+    .expect("valid regex")
+});
+
+/// A short version of an error from the API server to show in place of the
+/// table, which would otherwise look like there's nothing there.
+fn describe(resp: &ErrorResponse) -> String {
+    if resp.code != 403 {
+        return resp.message.clone();
+    }
+
+    let Some(captures) = FORBIDDEN.captures(&resp.message) else {
+        return format!("forbidden: {}", resp.message);
+    };
+
+    let scope = match (captures.get(3), captures.get(4)) {
+        (Some(namespace), _) => format!(" in namespace {}", namespace.as_str()),
+        (None, Some(_)) => " at the cluster scope".to_string(),
+        (None, None) => String::new(),
+    };
+
+    format!(
+        "forbidden: missing {} {}{scope}",
+        &captures[1], &captures[2]
+    )
+}
+
+// Serializing everything in a store isn't free, stores are only checked for
+// changes to measure this often.
+static MEASURE_INTERVAL: Duration = Duration::from_secs(10);
+
+// Counts what would be written, without keeping any of it.
+struct Counter(u64);
+
+impl io::Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sorted contents of a store at some point in time, shared until it changes.
+pub type Snapshot<K> = Arc<Vec<Arc<K>>>;
+
+pub struct Store<K> {
+    backend: Box<dyn StoreBackend<K>>,
+    snapshot: Mutex<Option<(u64, Snapshot<K>)>>,
+    // See `memory::Memory`.
+    memory: Arc<Memory>,
+    id: u64,
+    // The generation the store was last measured at.
+    measured: Mutex<Option<u64>>,
+    // Namespaces the session may see, see `policy::Scope`. For stores of
+    // namespaces themselves, it applies to the objects' names instead.
+    scope: Scope,
+    namespaces: bool,
 }
 
 impl<K> Store<K>
 where
     K: Filter
-        + kube::Resource<DynamicType = ()>
+        + kube::Resource
         + Clone
         + std::fmt::Debug
         + Send
         + Sync
         + DeserializeOwned
+        + Serialize
         + 'static,
+    K::DynamicType: Clone + Eq + Hash + Send + Sync,
     Arc<K>: Compare,
 {
     // TODO: need to have a way to filter stuff out (with some defaults) to keep
     // from memory going nuts.
-    pub fn new(client: kube::Client) -> (Arc<Self>, oneshot::Receiver<()>) {
-        let (reader, writer) = reflector::store();
-        let stream = runtime::watcher(Api::<K>::all(client), Config::default())
-            .default_backoff()
-            .modify(|obj| {
-                ResourceExt::managed_fields_mut(obj).clear();
-            })
-            .reflect(writer)
-            .applied_objects()
-            .boxed();
-
-        let mut tasks = JoinSet::new();
-
-        tasks.spawn(async move {
-            stream.for_each(|_| ready(())).await;
-
-            Ok(())
+    /// Everything of a kind across the cluster. This is the store started along
+    /// with the session when the kind is prefetched, see `prefetch`.
+    pub fn new(client: kube::Client) -> (Arc<Self>, oneshot::Receiver<()>)
+    where
+        K::DynamicType: Default,
+    {
+        prefetch::get().unwrap_or_else(|| Self::new_with(client, &K::DynamicType::default()))
+    }
+
+    /// Everything in `namespace`, or the whole cluster like `new` without
+    /// one.
+    pub fn namespaced(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Arc<Self>, oneshot::Receiver<()>)
+    where
+        K::DynamicType: Default,
+    {
+        match namespace {
+            Some(_) => Self::with_config(client, Startup::watch(namespace)),
+            None => Self::new(client),
+        }
+    }
+
+    /// Create a store for resources that are only known at runtime, such as
+    /// `DynamicObject` with an `ApiResource` from discovery.
+    pub fn new_with(
+        client: kube::Client,
+        dyntype: &K::DynamicType,
+    ) -> (Arc<Self>, oneshot::Receiver<()>) {
+        Self::watch(client, dyntype, Config::default())
+    }
+
+    /// Like `new_with`, but only holding what matches `config`.
+    pub fn new_with_config(
+        client: kube::Client,
+        dyntype: &K::DynamicType,
+        config: Config,
+    ) -> (Arc<Self>, oneshot::Receiver<()>) {
+        Self::watch(client, dyntype, config)
+    }
+
+    /// Create a store that only holds what matches `config`, such as the events
+    /// for a single object via a field selector.
+    pub fn with_config(client: kube::Client, config: Config) -> (Arc<Self>, oneshot::Receiver<()>)
+    where
+        K::DynamicType: Default,
+    {
+        Self::watch(client, &K::DynamicType::default(), config)
+    }
+
+    /// Create a store that lists what matches `params` every `interval`
+    /// instead of watching it, see `Poll`.
+    pub fn polled(
+        client: kube::Client,
+        dyntype: &K::DynamicType,
+        params: ListParams,
+        interval: Duration,
+    ) -> (Arc<Self>, oneshot::Receiver<()>)
+    where
+        K::DynamicType: 'static,
+    {
+        let (backend, rx) = Poll::new(client, dyntype, params, interval);
+
+        (Self::with_backend(backend, dyntype), rx)
+    }
+
+    /// Create a store with objects from anywhere at all.
+    pub fn with_backend(
+        backend: impl StoreBackend<K> + 'static,
+        dyntype: &K::DynamicType,
+    ) -> Arc<Self> {
+        let memory = memory::current();
+        let resource = usage::resource(&K::plural(dyntype), &K::group(dyntype));
+
+        let store = Arc::new_cyclic(|store: &Weak<Self>| Self {
+            backend: Box::new(backend),
+            snapshot: Mutex::default(),
+            id: memory.register(resource, store.clone()),
+            memory,
+            measured: Mutex::default(),
+            scope: policy::scope(),
+            namespaces: K::kind(dyntype) == "Namespace",
         });
 
-        let (tx, rx) = oneshot::channel();
-        tasks.spawn(is_ready(reader.clone(), tx));
+        tokio::spawn(Self::measure(Arc::downgrade(&store)));
+
+        store
+    }
+
+    // Stores are measured as they change rather than when they're read,
+    // prefetched ones are often never looked at and would otherwise never count
+    // towards the limit.
+    async fn measure(store: Weak<Self>) {
+        let mut interval = tokio::time::interval(MEASURE_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let Some(store) = store.upgrade() else {
+                return;
+            };
+
+            tokio::task::spawn_blocking(move || store.footprint())
+                .await
+                .ok();
+        }
+    }
+
+    fn watch(
+        client: kube::Client,
+        dyntype: &K::DynamicType,
+        config: Config,
+    ) -> (Arc<Self>, oneshot::Receiver<()>) {
+        let (backend, rx) = Watch::new(client, dyntype, config);
+
+        (Self::with_backend(backend, dyntype), rx)
+    }
+
+    /// See `StoreBackend::generation`.
+    pub fn generation(&self) -> u64 {
+        self.backend.generation()
+    }
+
+    /// See `StoreBackend::error`.
+    pub fn error(&self) -> Option<String> {
+        self.backend.error()
+    }
 
-        (Arc::new(Self { tasks, reader }), rx)
+    /// See `StoreBackend::retry`.
+    pub fn retry(&self) {
+        self.backend.retry();
     }
 
-    pub fn items(&self, filter: Option<String>) -> Vec<Arc<K>> {
-        let mut items = filter
-            .map(|filter| {
-                self.reader
-                    .state()
-                    .into_iter()
-                    .filter(|obj| obj.matches(filter.as_str()))
-                    .collect()
-            })
-            .unwrap_or(self.reader.state());
+    /// Everything in the store, sorted. This is shared between callers until
+    /// the store changes, so reading it every frame doesn't copy or sort
+    /// anything.
+    pub fn snapshot(&self) -> Snapshot<K> {
+        self.memory.viewed(self.id);
+
+        let generation = self.backend.generation();
+        let mut snapshot = self.snapshot.lock().unwrap();
+
+        if let Some((built, items)) = snapshot.as_ref() {
+            if *built == generation {
+                return items.clone();
+            }
+        }
+
+        let mut items = self.backend.state();
+
+        if self.scope.is_restricted() {
+            items.retain(|obj| self.visible(obj));
+        }
 
         items.sort_by(Compare::cmp);
 
+        let items = Arc::new(items);
+        *snapshot = Some((generation, items.clone()));
+
         items
     }
 
+    fn footprint(&self) {
+        if !self.memory.needed() {
+            return;
+        }
+
+        let generation = self.backend.generation();
+
+        if self.measured.lock().unwrap().replace(generation) == Some(generation) {
+            return;
+        }
+
+        let items = self.backend.state();
+        let mut counter = Counter(0);
+
+        for obj in &items {
+            serde_json::to_writer(&mut counter, obj.as_ref()).ok();
+        }
+
+        self.memory.update(
+            self.id,
+            Footprint {
+                objects: items.len(),
+                bytes: counter.0,
+            },
+        );
+    }
+
+    fn visible(&self, obj: &K) -> bool {
+        match obj.meta().namespace.as_deref() {
+            Some(namespace) => self.scope.allows(namespace),
+            None if self.namespaces => self.scope.allows(&obj.name_any()),
+            None => true,
+        }
+    }
+
+    /// Everything that matches `filter`, straight out of the snapshot. Nothing
+    /// is copied up front, so going through it every frame is cheap.
+    pub fn items(&self, filter: Option<String>) -> impl Iterator<Item = Arc<K>> {
+        let snapshot = self.snapshot();
+
+        (0..snapshot.len())
+            .map(move |idx| snapshot[idx].clone())
+            .filter(move |obj| filter.as_ref().map_or(true, |filter| obj.matches(filter)))
+    }
+
     pub fn get(&self, idx: usize, filter: Option<String>) -> Option<Arc<K>> {
-        self.items(filter).get(idx).cloned()
+        let snapshot = self.snapshot();
+
+        match filter {
+            Some(filter) => snapshot
+                .iter()
+                .filter(|obj| obj.matches(filter.as_str()))
+                .nth(idx)
+                .cloned(),
+            None => snapshot.get(idx).cloned(),
+        }
     }
 }
 
-impl<K> Drop for Store<K>
+// Whatever is in the snapshot goes too, otherwise trimming wouldn't let go of
+// anything that had already been looked at.
+impl<K> Trim for Store<K>
 where
-    K: kube::Resource<DynamicType = ()>
-        + Clone
-        + std::fmt::Debug
-        + Send
-        + Sync
-        + DeserializeOwned
-        + 'static,
+    K: Send + Sync,
 {
+    fn trim(&self) -> bool {
+        if !self.backend.trim() {
+            return false;
+        }
+
+        // Measuring it again would only start it back up.
+        self.snapshot.lock().unwrap().take();
+        *self.measured.lock().unwrap() = Some(self.backend.generation());
+
+        true
+    }
+}
+
+impl<K> Drop for Store<K> {
     fn drop(&mut self) {
-        self.tasks.abort_all();
+        self.memory.remove(self.id);
     }
 }
 
 impl<K> table::Items for Arc<Store<K>>
 where
     K: Filter
-        + kube::Resource<DynamicType = ()>
+        + kube::Resource
         + Clone
         + std::fmt::Debug
         + Send
         + Sync
         + DeserializeOwned
+        + Serialize
         + 'static,
+    K::DynamicType: Clone + Eq + Hash + Send + Sync,
     Arc<K>: table::Row + Compare,
 {
     type Item = Arc<K>;
 
     fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
-        Store::items(self, filter)
+        Store::items(self, filter).collect()
+    }
+
+    fn shared(&self, filter: Option<String>) -> Arc<Vec<Self::Item>> {
+        match filter {
+            Some(_) => Arc::new(Store::items(self, filter).collect()),
+            None => Store::snapshot(self),
+        }
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(self.generation())
+    }
+
+    fn error(&self) -> Option<String> {
+        Store::error(self)
+    }
+
+    fn retry(&self) {
+        Store::retry(self);
     }
 }
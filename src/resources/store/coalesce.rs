@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use eyre::Result;
+use kube::{runtime::watcher, Resource, ResourceExt};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, IntCounter};
+
+lazy_static! {
+    static ref COALESCED: IntCounter = register_int_counter!(
+        "store_events_coalesced_total",
+        "Number of watch events for objects that had changed moments before, which were held back \
+         and shown along with whatever came next"
+    )
+    .unwrap();
+}
+
+/// Decides when a store's generation gets bumped, which is what has widgets
+/// rebuild what they show. Controllers that write status as a heartbeat can
+/// modify the same object many times a second, there's no point in rebuilding
+/// for each of those. After an object has changed, anything else that happens
+/// to it within `window` is held back until the window is up.
+pub struct Coalesce {
+    generation: Arc<AtomicU64>,
+    window: Duration,
+
+    notified: Mutex<HashMap<String, Instant>>,
+    pending: AtomicBool,
+}
+
+impl Coalesce {
+    pub fn new(generation: Arc<AtomicU64>, window: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            generation,
+            window,
+            notified: Mutex::default(),
+            pending: AtomicBool::new(false),
+        })
+    }
+
+    pub fn event<K: Resource>(&self, event: &watcher::Event<K>) {
+        match event {
+            watcher::Event::Apply(obj) => self.applied(obj),
+            watcher::Event::Delete(obj) => {
+                self.notified.lock().unwrap().remove(&key(obj));
+                self.bump();
+            }
+            _ => self.bump(),
+        }
+    }
+
+    pub fn bump(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn applied<K: Resource>(&self, obj: &K) {
+        let now = Instant::now();
+        let mut notified = self.notified.lock().unwrap();
+
+        if let Some(at) = notified.get(&key(obj)) {
+            if now.duration_since(*at) < self.window {
+                self.pending.store(true, Ordering::Relaxed);
+                COALESCED.inc();
+
+                return;
+            }
+        }
+
+        notified.insert(key(obj), now);
+        self.bump();
+    }
+
+    /// Let through whatever was held back, once per window. This runs for as
+    /// long as the store does.
+    pub async fn flush(self: Arc<Self>) -> Result<()> {
+        let mut interval = tokio::time::interval(self.window);
+
+        loop {
+            interval.tick().await;
+
+            self.tick(Instant::now());
+        }
+    }
+
+    // Objects that haven't changed for a whole window don't hold anything back
+    // anymore, so there's no need to remember them. That includes every object
+    // that went away without a `Delete`, such as during a relist.
+    fn tick(&self, now: Instant) {
+        self.notified
+            .lock()
+            .unwrap()
+            .retain(|_, at| now.duration_since(*at) < self.window);
+
+        if self.pending.swap(false, Ordering::Relaxed) {
+            self.bump();
+        }
+    }
+}
+
+fn key<K: Resource>(obj: &K) -> String {
+    obj.uid()
+        .unwrap_or_else(|| format!("{}/{}", obj.namespace().unwrap_or_default(), obj.name_any()))
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::ObjectMeta;
+
+    use super::*;
+
+    static WINDOW: Duration = Duration::from_secs(60);
+
+    fn object(uid: &str) -> ConfigMap {
+        ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(uid.to_string()),
+                uid: Some(uid.to_string()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        }
+    }
+
+    fn coalesce() -> (Arc<AtomicU64>, Arc<Coalesce>) {
+        let generation = Arc::new(AtomicU64::new(0));
+
+        (generation.clone(), Coalesce::new(generation, WINDOW))
+    }
+
+    #[test]
+    fn first_change_bumps() {
+        let (generation, coalesce) = coalesce();
+
+        coalesce.event(&watcher::Event::Apply(object("a")));
+        coalesce.event(&watcher::Event::Apply(object("b")));
+
+        assert_eq!(generation.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn changes_within_window_are_held() {
+        let (generation, coalesce) = coalesce();
+
+        for _ in 0..5 {
+            coalesce.event(&watcher::Event::Apply(object("a")));
+        }
+
+        assert_eq!(generation.load(Ordering::Relaxed), 1);
+
+        coalesce.tick(Instant::now());
+
+        assert_eq!(generation.load(Ordering::Relaxed), 2);
+
+        // Nothing was held back since.
+        coalesce.tick(Instant::now());
+
+        assert_eq!(generation.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn delete_forgets() {
+        let (generation, coalesce) = coalesce();
+
+        coalesce.event(&watcher::Event::Apply(object("a")));
+        coalesce.event(&watcher::Event::Delete(object("a")));
+        coalesce.event(&watcher::Event::Apply(object("a")));
+
+        assert_eq!(generation.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn other_events_bump() {
+        let (generation, coalesce) = coalesce();
+
+        coalesce.event(&watcher::Event::<ConfigMap>::Init);
+        coalesce.event(&watcher::Event::<ConfigMap>::InitDone);
+
+        assert_eq!(generation.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn tick_prunes() {
+        let (generation, coalesce) = coalesce();
+
+        coalesce.event(&watcher::Event::Apply(object("a")));
+        coalesce.event(&watcher::Event::Apply(object("b")));
+
+        coalesce.tick(Instant::now());
+
+        assert_eq!(coalesce.notified.lock().unwrap().len(), 2);
+
+        coalesce.tick(Instant::now() + WINDOW);
+
+        assert!(coalesce.notified.lock().unwrap().is_empty());
+
+        // Once forgotten, the next change is let through right away.
+        coalesce.event(&watcher::Event::Apply(object("a")));
+
+        assert_eq!(generation.load(Ordering::Relaxed), 3);
+    }
+}
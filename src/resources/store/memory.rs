@@ -0,0 +1,263 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, register_int_gauge_vec, IntCounterVec, IntGaugeVec};
+
+use crate::{config, resources::quantity};
+
+lazy_static! {
+    static ref STORE_BYTES: IntGaugeVec = register_int_gauge_vec!(
+        "store_bytes",
+        "Approximate size of the objects held by stores, summed over every session, by resource",
+        &["resource"]
+    )
+    .unwrap();
+    static ref STORE_TRIMS: IntCounterVec = register_int_counter_vec!(
+        "store_trims_total",
+        "Number of times a store was emptied to stay under the memory limit, by resource",
+        &["resource"]
+    )
+    .unwrap();
+}
+
+// Anything read this recently is on screen, or close enough to it that
+// trimming would only have it listed again right away.
+static IN_USE: Duration = Duration::from_secs(30);
+
+thread_local! {
+    // Like `crate::usage`, every session's stores are created on its own
+    // dashboard thread, which makes this per session.
+    static CURRENT: Arc<Memory> = Arc::default();
+}
+
+pub fn current() -> Arc<Memory> {
+    CURRENT.with(Clone::clone)
+}
+
+/// Something that can give up everything it holds and get it again later.
+pub trait Trim: Send + Sync {
+    /// Whether anything was let go of.
+    fn trim(&self) -> bool;
+}
+
+/// What a store holds, as of the last time it was measured. `bytes` is how
+/// big the objects are serialized, which is about what they take up once
+/// deserialized.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Footprint {
+    pub objects: usize,
+    pub bytes: u64,
+}
+
+struct Entry {
+    resource: String,
+    footprint: Footprint,
+    viewed: Instant,
+    trimmed: bool,
+    store: Weak<dyn Trim>,
+}
+
+/// Every store a session has open, how big each one is and when it was last
+/// looked at. Past `tables.memory_limit`, the stores that have gone the
+/// longest without being looked at are trimmed until it fits again.
+#[derive(Default)]
+pub struct Memory {
+    stores: Mutex<BTreeMap<u64, Entry>>,
+    next: AtomicU64,
+    // Debug views that are showing this, see `Memory::needed`.
+    watchers: AtomicUsize,
+}
+
+pub struct Watching(Arc<Memory>);
+
+impl std::ops::Deref for Watching {
+    type Target = Memory;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for Watching {
+    fn drop(&mut self) {
+        self.0.watchers.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A single store, as shown in the debug view.
+pub struct Resident {
+    pub resource: String,
+    pub footprint: Footprint,
+    pub trimmed: bool,
+}
+
+impl Memory {
+    pub fn register(&self, resource: String, store: Weak<dyn Trim>) -> u64 {
+        let id = self.next.fetch_add(1, Ordering::Relaxed);
+
+        self.stores.lock().unwrap().insert(
+            id,
+            Entry {
+                resource,
+                footprint: Footprint::default(),
+                viewed: Instant::now(),
+                trimmed: false,
+                store,
+            },
+        );
+
+        id
+    }
+
+    /// Whether stores should be measured at all. Nothing looks at what they
+    /// hold unless there's a limit to enforce or the debug view is open.
+    pub fn needed(&self) -> bool {
+        limit().is_some() || self.watchers.load(Ordering::Relaxed) > 0
+    }
+
+    /// Keep stores being measured for as long as the returned value is around.
+    pub fn watch(self: &Arc<Self>) -> Watching {
+        self.watchers.fetch_add(1, Ordering::Relaxed);
+
+        Watching(self.clone())
+    }
+
+    pub fn remove(&self, id: u64) {
+        if let Some(entry) = self.stores.lock().unwrap().remove(&id) {
+            sub(&entry);
+        }
+    }
+
+    pub fn viewed(&self, id: u64) {
+        if let Some(entry) = self.stores.lock().unwrap().get_mut(&id) {
+            entry.viewed = Instant::now();
+            entry.trimmed = false;
+        }
+    }
+
+    /// Record what a store holds now, then trim others if that puts the session
+    /// over the limit.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn update(&self, id: u64, footprint: Footprint) {
+        {
+            let mut stores = self.stores.lock().unwrap();
+
+            let Some(entry) = stores.get_mut(&id) else {
+                return;
+            };
+
+            sub(entry);
+            entry.footprint = footprint;
+            STORE_BYTES
+                .with_label_values(&[&entry.resource])
+                .add(footprint.bytes as i64);
+        }
+
+        if let Some(limit) = limit() {
+            self.enforce(id, limit);
+        }
+    }
+
+    // The lock isn't held while trimming, stores lock their own state to do it
+    // and might be in the middle of reporting in.
+    fn enforce(&self, current: u64, limit: u64) {
+        let (mut total, candidates) = {
+            let stores = self.stores.lock().unwrap();
+
+            let total: u64 = stores.values().map(|entry| entry.footprint.bytes).sum();
+
+            let mut candidates: Vec<_> = stores
+                .iter()
+                .filter(|(id, entry)| {
+                    **id != current
+                        && !entry.trimmed
+                        && entry.footprint.bytes > 0
+                        && entry.viewed.elapsed() > IN_USE
+                })
+                .map(|(id, entry)| (*id, entry.viewed, entry.footprint.bytes))
+                .collect();
+
+            candidates.sort_by_key(|(_, viewed, _)| *viewed);
+
+            (total, candidates)
+        };
+
+        for (id, _, bytes) in candidates {
+            if total <= limit {
+                break;
+            }
+
+            let Some(store) = self
+                .stores
+                .lock()
+                .unwrap()
+                .get(&id)
+                .and_then(|entry| entry.store.upgrade())
+            else {
+                continue;
+            };
+
+            if !store.trim() {
+                continue;
+            }
+
+            total = total.saturating_sub(bytes);
+
+            if let Some(entry) = self.stores.lock().unwrap().get_mut(&id) {
+                STORE_TRIMS.with_label_values(&[&entry.resource]).inc();
+
+                sub(entry);
+                entry.footprint = Footprint::default();
+                entry.trimmed = true;
+            }
+        }
+    }
+
+    pub fn total(&self) -> Footprint {
+        self.stores
+            .lock()
+            .unwrap()
+            .values()
+            .fold(Footprint::default(), |total, entry| Footprint {
+                objects: total.objects + entry.footprint.objects,
+                bytes: total.bytes + entry.footprint.bytes,
+            })
+    }
+
+    pub fn stores(&self) -> Vec<Resident> {
+        self.stores
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| Resident {
+                resource: entry.resource.clone(),
+                footprint: entry.footprint,
+                trimmed: entry.trimmed,
+            })
+            .collect()
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn sub(entry: &Entry) {
+    STORE_BYTES
+        .with_label_values(&[&entry.resource])
+        .sub(entry.footprint.bytes as i64);
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn limit() -> Option<u64> {
+    config::get()
+        .tables
+        .memory_limit
+        .as_ref()
+        .and_then(quantity::parse)
+        .map(|bytes| bytes as u64)
+}
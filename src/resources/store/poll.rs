@@ -0,0 +1,199 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use eyre::Result;
+use kube::{api::ListParams, Api, ResourceExt};
+use serde::de::DeserializeOwned;
+use tokio::{
+    sync::{oneshot, Notify},
+    task::JoinHandle,
+};
+
+use super::{describe, StoreBackend};
+use crate::{throttle::THROTTLE, usage};
+
+// Big enough that most kinds fit in a single request, small enough that the
+// API server doesn't have to build a huge response in one go.
+static PAGE_SIZE: u32 = 500;
+
+/// Lists everything that matches `params` every `interval`, one page at a
+/// time, instead of keeping a watch open. Kinds with a huge number of objects
+/// that change constantly, such as events on a busy cluster, are cheaper to
+/// look at this way. What is shown can be up to `interval` out of date.
+pub struct Poll<K> {
+    task: JoinHandle<()>,
+    state: Arc<Mutex<Vec<Arc<K>>>>,
+    generation: Arc<AtomicU64>,
+    error: Arc<Mutex<Option<String>>>,
+    retry: Arc<Notify>,
+}
+
+impl<K> Poll<K>
+where
+    K: kube::Resource + Clone + std::fmt::Debug + Send + Sync + DeserializeOwned + 'static,
+    K::DynamicType: Clone + Send + Sync + 'static,
+{
+    pub fn new(
+        client: kube::Client,
+        dyntype: &K::DynamicType,
+        params: ListParams,
+        interval: Duration,
+    ) -> (Self, oneshot::Receiver<()>) {
+        let api = Api::<K>::all_with(client, dyntype);
+
+        let usage = usage::current().unwrap_or_default();
+        let resource = usage::resource(&K::plural(dyntype), &K::group(dyntype));
+
+        let state = Arc::new(Mutex::new(Vec::new()));
+        let generation = Arc::new(AtomicU64::new(0));
+        let error = Arc::new(Mutex::new(None));
+        let retry = Arc::new(Notify::new());
+
+        let (tx, rx) = oneshot::channel();
+
+        let task = tokio::spawn({
+            let state = state.clone();
+            let generation = generation.clone();
+            let error = error.clone();
+            let retry = retry.clone();
+
+            async move {
+                let mut tx = Some(tx);
+                let mut interval = tokio::time::interval(interval);
+
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => {}
+                        () = retry.notified() => interval.reset(),
+                    }
+
+                    let latest = match list(&api, &params).await {
+                        Ok(latest) => latest,
+                        Err(err) => {
+                            tracing::debug!(resource, "unable to list: {err}");
+
+                            let msg = match err.downcast_ref::<kube::Error>() {
+                                Some(kube::Error::Api(resp)) => describe(resp),
+                                _ => err.to_string(),
+                            };
+
+                            if error.lock().unwrap().replace(msg.clone()) != Some(msg) {
+                                generation.fetch_add(1, Ordering::Relaxed);
+                            }
+
+                            // There's nothing coming, no point in anyone waiting for it.
+                            if let Some(tx) = tx.take() {
+                                let _ = tx.send(());
+                            }
+
+                            continue;
+                        }
+                    };
+
+                    if error.lock().unwrap().take().is_some() {
+                        generation.fetch_add(1, Ordering::Relaxed);
+                    }
+
+                    usage.event(&resource);
+
+                    let changed = {
+                        let mut state = state.lock().unwrap();
+                        let changed = !same(&state, &latest);
+
+                        if changed {
+                            *state = latest;
+                        }
+
+                        changed
+                    };
+
+                    if changed {
+                        generation.fetch_add(1, Ordering::Relaxed);
+                    }
+
+                    if let Some(tx) = tx.take() {
+                        let _ = tx.send(());
+                    }
+                }
+            }
+        });
+
+        (
+            Self {
+                task,
+                state,
+                generation,
+                error,
+                retry,
+            },
+            rx,
+        )
+    }
+}
+
+async fn list<K>(api: &Api<K>, params: &ListParams) -> Result<Vec<Arc<K>>>
+where
+    K: kube::Resource + Clone + std::fmt::Debug + DeserializeOwned,
+{
+    let mut items = Vec::new();
+    let mut params = params.clone().limit(PAGE_SIZE);
+
+    loop {
+        THROTTLE.wait().await;
+
+        let page = api.list(&params).await?;
+
+        items.extend(page.items.into_iter().map(|mut obj| {
+            obj.managed_fields_mut().clear();
+
+            Arc::new(obj)
+        }));
+
+        match page.metadata.continue_.filter(|token| !token.is_empty()) {
+            Some(token) => params = params.continue_token(&token),
+            None => return Ok(items),
+        }
+    }
+}
+
+// Resource versions change with every write, so this is enough to tell
+// whether anything needs to be rebuilt.
+fn same<K: kube::Resource>(current: &[Arc<K>], latest: &[Arc<K>]) -> bool {
+    current.len() == latest.len()
+        && current
+            .iter()
+            .zip(latest)
+            .all(|(a, b)| a.resource_version() == b.resource_version())
+}
+
+impl<K> StoreBackend<K> for Poll<K>
+where
+    K: Send + Sync,
+{
+    fn state(&self) -> Vec<Arc<K>> {
+        self.state.lock().unwrap().clone()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+
+    fn retry(&self) {
+        self.retry.notify_one();
+    }
+}
+
+impl<K> Drop for Poll<K> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
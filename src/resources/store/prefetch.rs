@@ -0,0 +1,157 @@
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    hash::Hash,
+    sync::Arc,
+};
+
+use futures::{future::Shared, FutureExt};
+use k8s_openapi::api::{
+    apps::v1::{DaemonSet, Deployment, StatefulSet},
+    core::v1::{ConfigMap, Namespace, Node, Pod, Secret, Service},
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::oneshot;
+
+use super::Store;
+use crate::{
+    config,
+    resources::{Compare, Filter},
+    usage,
+};
+
+lazy_static! {
+    static ref PREFETCH_HITS: IntCounterVec = register_int_counter_vec!(
+        "store_prefetch_hits_total",
+        "Number of views that used a store started along with their session instead of their \
+         own, by resource",
+        &["resource"]
+    )
+    .unwrap();
+}
+
+/// Kinds, by plural, that can be started along with the session. These are
+/// the ones with a dedicated view, everything else is listed from discovery
+/// and never shares a store.
+pub static KINDS: &[&str] = &[
+    "configmaps",
+    "daemonsets",
+    "deployments",
+    "namespaces",
+    "nodes",
+    "pods",
+    "secrets",
+    "services",
+    "statefulsets",
+];
+
+type Ready = Shared<oneshot::Receiver<()>>;
+
+struct Entry<K> {
+    store: Arc<Store<K>>,
+    ready: Ready,
+}
+
+thread_local! {
+    // Like `memory::CURRENT`, every session's stores are created on its own
+    // dashboard thread, which makes this per session.
+    static PREFETCHED: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::default();
+}
+
+/// The stores started along with a session. They're kept for as long as this
+/// is around, which is held by the top level of the dashboard.
+pub struct Prefetched(());
+
+impl Drop for Prefetched {
+    fn drop(&mut self) {
+        PREFETCHED.with_borrow_mut(HashMap::clear);
+    }
+}
+
+fn spawn<K>(client: &kube::Client)
+where
+    K: Filter
+        + kube::Resource
+        + Clone
+        + std::fmt::Debug
+        + Send
+        + Sync
+        + DeserializeOwned
+        + Serialize
+        + 'static,
+    K::DynamicType: Clone + Default + Eq + Hash + Send + Sync,
+    Arc<K>: Compare,
+{
+    let (store, ready) = Store::<K>::new_with(client.clone(), &K::DynamicType::default());
+
+    PREFETCHED.with_borrow_mut(|prefetched| {
+        prefetched.insert(
+            TypeId::of::<K>(),
+            Box::new(Entry {
+                store,
+                ready: ready.shared(),
+            }),
+        );
+    });
+}
+
+/// Start watching everything in `tables.prefetch` across the cluster, so that
+/// the first view of each kind doesn't have to wait on a list. This trades a
+/// slower start, with every kind being listed at once, for views that are
+/// ready right away.
+pub fn start(client: &kube::Client) -> Prefetched {
+    let prefetch = &config::get().tables.prefetch;
+
+    for kind in KINDS.iter().filter(|kind| prefetch.includes(kind)) {
+        match *kind {
+            "configmaps" => spawn::<ConfigMap>(client),
+            "daemonsets" => spawn::<DaemonSet>(client),
+            "deployments" => spawn::<Deployment>(client),
+            "namespaces" => spawn::<Namespace>(client),
+            "nodes" => spawn::<Node>(client),
+            "pods" => spawn::<Pod>(client),
+            "secrets" => spawn::<Secret>(client),
+            "services" => spawn::<Service>(client),
+            "statefulsets" => spawn::<StatefulSet>(client),
+            _ => {}
+        }
+    }
+
+    Prefetched(())
+}
+
+/// The prefetched store for `K`, when there is one. Whoever gets it hears
+/// that it is ready like they would for a store of their own, right away if
+/// the initial list is already done.
+pub fn get<K>() -> Option<(Arc<Store<K>>, oneshot::Receiver<()>)>
+where
+    K: kube::Resource + 'static,
+    K::DynamicType: Default,
+{
+    let (store, ready) = PREFETCHED.with_borrow(|prefetched| {
+        let entry = prefetched
+            .get(&TypeId::of::<K>())?
+            .downcast_ref::<Entry<K>>()?;
+
+        Some((entry.store.clone(), entry.ready.clone()))
+    })?;
+
+    PREFETCH_HITS
+        .with_label_values(&[&usage::resource(
+            &K::plural(&K::DynamicType::default()),
+            &K::group(&K::DynamicType::default()),
+        )])
+        .inc();
+
+    let (tx, rx) = oneshot::channel();
+
+    tokio::spawn(async move {
+        let _ = ready.await;
+        let _ = tx.send(());
+    });
+
+    Some((store, rx))
+}
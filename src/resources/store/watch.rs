@@ -0,0 +1,249 @@
+use std::{
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use eyre::{eyre, Result};
+use futures::{StreamExt, TryStreamExt};
+use kube::{
+    runtime::{
+        self, reflector,
+        watcher::{self, Config},
+        WatchStreamExt,
+    },
+    Api, ResourceExt,
+};
+use serde::de::DeserializeOwned;
+use tokio::{
+    sync::{oneshot, Notify},
+    task::JoinSet,
+};
+
+use super::{coalesce::Coalesce, describe, StoreBackend};
+use crate::{throttle::THROTTLE, usage};
+
+// Changes to the same object that come faster than this are shown together.
+static COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
+// Whoever is waiting on the store hears about it once, either when it is
+// ready or when it fails, so that they aren't left waiting on a store that
+// never will be.
+type Ready = Arc<Mutex<Option<oneshot::Sender<()>>>>;
+
+fn done(ready: &Ready) {
+    if let Some(tx) = ready.lock().unwrap().take() {
+        let _ = tx.send(());
+    }
+}
+
+async fn is_ready<K>(reader: reflector::Store<K>, ready: Ready) -> Result<()>
+where
+    K: kube::Resource + Clone + std::fmt::Debug + Send + Sync + DeserializeOwned + 'static,
+    K::DynamicType: Clone + Eq + Hash + Send + Sync,
+{
+    reader.wait_until_ready().await.map_err(|err| eyre!(err))?;
+
+    done(&ready);
+
+    Ok(())
+}
+
+fn message(err: &watcher::Error) -> String {
+    match err {
+        watcher::Error::InitialListFailed(kube::Error::Api(resp))
+        | watcher::Error::WatchStartFailed(kube::Error::Api(resp))
+        | watcher::Error::WatchFailed(kube::Error::Api(resp))
+        | watcher::Error::WatchError(resp) => describe(resp),
+        err => err.to_string(),
+    }
+}
+
+// Everything that goes away when the store is trimmed, and comes back the next
+// time it is read.
+struct Running<K>
+where
+    K: kube::Resource + Clone + std::fmt::Debug + Send + Sync + DeserializeOwned + 'static,
+    K::DynamicType: Clone + Eq + Hash + Send + Sync,
+{
+    tasks: JoinSet<Result<()>>,
+    reader: reflector::Store<K>,
+}
+
+impl<K> Drop for Running<K>
+where
+    K: kube::Resource + Clone + std::fmt::Debug + Send + Sync + DeserializeOwned + 'static,
+    K::DynamicType: Clone + Eq + Hash + Send + Sync,
+{
+    fn drop(&mut self) {
+        self.tasks.abort_all();
+    }
+}
+
+/// Keeps everything that matches `config` up to date with a list and then a
+/// watch, which is how almost every store is backed.
+pub struct Watch<K>
+where
+    K: kube::Resource + Clone + std::fmt::Debug + Send + Sync + DeserializeOwned + 'static,
+    K::DynamicType: Clone + Eq + Hash + Send + Sync,
+{
+    api: Api<K>,
+    dyntype: K::DynamicType,
+    config: Config,
+    running: Mutex<Option<Running<K>>>,
+
+    // Bumped on changes to the reader, see `Coalesce` for when.
+    generation: Arc<AtomicU64>,
+    error: Arc<Mutex<Option<String>>>,
+    retry: Arc<Notify>,
+}
+
+impl<K> Watch<K>
+where
+    K: kube::Resource + Clone + std::fmt::Debug + Send + Sync + DeserializeOwned + 'static,
+    K::DynamicType: Clone + Eq + Hash + Send + Sync,
+{
+    pub fn new(
+        client: kube::Client,
+        dyntype: &K::DynamicType,
+        config: Config,
+    ) -> (Self, oneshot::Receiver<()>) {
+        let watch = Self {
+            api: Api::<K>::all_with(client, dyntype),
+            dyntype: dyntype.clone(),
+            config,
+            running: Mutex::default(),
+            generation: Arc::new(AtomicU64::new(0)),
+            error: Arc::new(Mutex::new(None)),
+            retry: Arc::new(Notify::new()),
+        };
+
+        let (running, rx) = watch.start();
+        *watch.running.lock().unwrap() = Some(running);
+
+        (watch, rx)
+    }
+
+    fn start(&self) -> (Running<K>, oneshot::Receiver<()>) {
+        let mut writer = reflector::store::Writer::new(self.dyntype.clone());
+        let reader = writer.as_reader();
+
+        let usage = usage::current().unwrap_or_default();
+        let resource = usage::resource(&K::plural(&self.dyntype), &K::group(&self.dyntype));
+
+        let coalesce = Coalesce::new(self.generation.clone(), COALESCE_WINDOW);
+        let notify = coalesce.clone();
+
+        let (tx, rx) = oneshot::channel();
+        let ready: Ready = Arc::new(Mutex::new(Some(tx)));
+
+        let api = self.api.clone();
+        let config = self.config.clone();
+
+        let mut tasks = JoinSet::new();
+
+        tasks.spawn(coalesce.flush());
+
+        tasks.spawn({
+            let error = self.error.clone();
+            let retry = self.retry.clone();
+            let ready = ready.clone();
+            async move {
+                // Retrying starts a new watcher, which lists everything again
+                // before the writer swaps it in.
+                loop {
+                    let usage = usage.clone();
+                    let resource = resource.clone();
+
+                    let mut stream = runtime::watcher(api.clone(), config.clone())
+                        .default_backoff()
+                        .inspect_ok(move |_| usage.event(&resource))
+                        // Pulling the next event is what triggers the watcher to make another
+                        // request, so waiting here keeps the store from hammering the API
+                        // server.
+                        .then(|ev| async move {
+                            THROTTLE.wait().await;
+
+                            ev
+                        })
+                        .modify(|obj| {
+                            ResourceExt::managed_fields_mut(obj).clear();
+                        })
+                        .boxed();
+
+                    loop {
+                        let ev = tokio::select! {
+                            ev = stream.next() => ev,
+                            () = retry.notified() => break,
+                        };
+
+                        let Some(ev) = ev else {
+                            return Ok(());
+                        };
+
+                        match ev {
+                            Ok(ev) => {
+                                writer.apply_watcher_event(&ev);
+
+                                // `Init` comes before every attempt at listing, whether or
+                                // not it works out.
+                                if !matches!(ev, watcher::Event::Init) {
+                                    error.lock().unwrap().take();
+                                }
+
+                                notify.event(&ev);
+                            }
+                            Err(err) => {
+                                *error.lock().unwrap() = Some(message(&err));
+                                done(&ready);
+                                notify.bump();
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        tasks.spawn(is_ready(reader.clone(), ready));
+
+        (Running { tasks, reader }, rx)
+    }
+}
+
+impl<K> StoreBackend<K> for Watch<K>
+where
+    K: kube::Resource + Clone + std::fmt::Debug + Send + Sync + DeserializeOwned + 'static,
+    K::DynamicType: Clone + Eq + Hash + Send + Sync,
+{
+    fn state(&self) -> Vec<Arc<K>> {
+        self.running
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| self.start().0)
+            .reader
+            .state()
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+
+    fn retry(&self) {
+        self.retry.notify_one();
+    }
+
+    // Nobody is waiting on the store being ready when it starts up again, what
+    // is there shows up as it is listed.
+    fn trim(&self) -> bool {
+        self.error.lock().unwrap().take();
+
+        self.running.lock().unwrap().take().is_some()
+    }
+}
@@ -0,0 +1,160 @@
+use eyre::{eyre, Result};
+use kube::api::DynamicObject;
+use ratatui::{
+    layout::Constraint,
+    widgets::{Cell, Row},
+};
+use serde_json::json;
+
+use super::pod::debug::GRACE_PERIOD;
+use crate::widget::table;
+
+pub struct Field {
+    pub label: &'static str,
+    pub default: &'static str,
+}
+
+/// Objects that can be created from scratch by filling out a couple of fields.
+/// Each one knows what it needs to ask for and how to turn the answers into a
+/// manifest.
+#[derive(Clone, Copy, strum::VariantArray)]
+pub enum Template {
+    Namespace,
+    ConfigMap,
+    Debug,
+}
+
+impl Template {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Namespace => "Namespace",
+            Self::ConfigMap => "ConfigMap",
+            Self::Debug => "Debug Deployment",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Namespace => "Empty namespace",
+            Self::ConfigMap => "ConfigMap from key=value literals",
+            Self::Debug => "Deployment that sleeps forever, for exec'ing into",
+        }
+    }
+
+    pub fn fields(self) -> Vec<Field> {
+        let name = |default| Field {
+            label: "Name",
+            default,
+        };
+        let namespace = Field {
+            label: "Namespace",
+            default: "default",
+        };
+
+        match self {
+            Self::Namespace => vec![name("")],
+            Self::ConfigMap => vec![
+                name(""),
+                namespace,
+                Field {
+                    label: "Data (key=value, comma separated)",
+                    default: "",
+                },
+            ],
+            Self::Debug => vec![
+                name("debug"),
+                namespace,
+                Field {
+                    label: "Image",
+                    default: "busybox:stable",
+                },
+            ],
+        }
+    }
+
+    /// Build the manifest from values in the same order as `fields()`.
+    pub fn manifest(self, values: &[String]) -> Result<DynamicObject> {
+        let value = |idx: usize| -> Result<&str> {
+            let field = &self.fields()[idx];
+
+            values
+                .get(idx)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| eyre!("{} is required", field.label))
+        };
+
+        let manifest = match self {
+            Self::Namespace => json!({
+                "apiVersion": "v1",
+                "kind": "Namespace",
+                "metadata": { "name": value(0)? },
+            }),
+            Self::ConfigMap => json!({
+                "apiVersion": "v1",
+                "kind": "ConfigMap",
+                "metadata": { "name": value(0)?, "namespace": value(1)? },
+                "data": literals(values.get(2).map_or("", String::as_str))?,
+            }),
+            Self::Debug => json!({
+                "apiVersion": "apps/v1",
+                "kind": "Deployment",
+                "metadata": {
+                    "name": value(0)?,
+                    "namespace": value(1)?,
+                    "labels": { "kty.dev/temporary": "true" },
+                },
+                "spec": {
+                    "replicas": 1,
+                    "selector": { "matchLabels": { "app": value(0)? } },
+                    "template": {
+                        "metadata": { "labels": { "app": value(0)? } },
+                        "spec": {
+                            "terminationGracePeriodSeconds": GRACE_PERIOD,
+                            "containers": [{
+                                "name": "debug",
+                                "image": value(2)?,
+                                "command": ["sleep", "infinity"],
+                            }],
+                        },
+                    },
+                },
+            }),
+        };
+
+        Ok(serde_json::from_value(manifest)?)
+    }
+}
+
+// Same format as `kubectl create configmap --from-literal`, except all in one
+// field.
+fn literals(data: &str) -> Result<serde_json::Map<String, serde_json::Value>> {
+    data.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| eyre!("expected key=value, got {pair}"))?;
+
+            Ok((key.trim().to_string(), value.into()))
+        })
+        .collect()
+}
+
+impl table::Row for Template {
+    fn header<'a>() -> Option<Row<'a>> {
+        Some(Row::new(vec![
+            Cell::from("Template"),
+            Cell::from("Description"),
+        ]))
+    }
+
+    fn constraints() -> Vec<Constraint> {
+        vec![Constraint::Max(20), Constraint::Min(10)]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(vec![self.name(), self.description()]).style(style.normal)
+    }
+}
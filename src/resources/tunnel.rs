@@ -1,3 +1,4 @@
+mod apiserver;
 mod egress;
 mod ingress;
 
@@ -17,7 +18,7 @@ use prometheus_static_metric::make_static_metric;
 use ratatui::{layout::Constraint, widgets::Row};
 use tokio::io::{AsyncRead, AsyncWrite};
 
-use crate::widget::table;
+use crate::widget::{table, theme::Indicator};
 
 make_static_metric! {
     pub struct ResourceVec: IntCounter {
@@ -166,7 +167,12 @@ impl table::Row for Tunnel {
         Row::new(vec![
             self.kind.to_string().to_lowercase(),
             format!("{}:{}", self.host, self.port),
-            self.lifecycle.to_string(),
+            match self.lifecycle {
+                Lifecycle::Active => Indicator::Healthy.label(&self.lifecycle),
+                Lifecycle::Listening => Indicator::Progressing.label(&self.lifecycle),
+                Lifecycle::Error => Indicator::Unhealthy.label(&self.lifecycle),
+                Lifecycle::Inactive => self.lifecycle.to_string(),
+            },
         ])
         .style(match self.lifecycle {
             Lifecycle::Active => style.healthy,
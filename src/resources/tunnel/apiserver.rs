@@ -0,0 +1,106 @@
+use std::convert::Infallible;
+
+use eyre::Result;
+use http::{header, Request, Response, StatusCode};
+use http_body_util::BodyExt;
+use hyper::{body::Incoming, server::conn::http1, service::service_fn};
+use hyper_util::rt::TokioIo;
+use kube::client::Body;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use russh::server;
+
+lazy_static! {
+    static ref API_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "tunnel_api_requests_total",
+        "Number of requests proxied to the API server over ingress tunnels, by method and status \
+         code",
+        &["method", "code"]
+    )
+    .unwrap();
+}
+
+// Anything that says who the request is from has to come from the client kty
+// builds for the user. Passing these along would let someone act as whoever
+// they like, or at least end up with a confusing mix of identities.
+fn forbidden(name: &header::HeaderName) -> bool {
+    name == header::AUTHORIZATION || name.as_str().starts_with("impersonate-")
+}
+
+/// Serve HTTP over `channel`, sending every request on to the API server as
+/// the user `client` belongs to. This is what lets `kubectl` and friends use
+/// the kty connection when they can't reach the API server themselves.
+pub async fn serve(client: kube::Client, channel: russh::Channel<server::Msg>) -> Result<()> {
+    http1::Builder::new()
+        .serve_connection(
+            TokioIo::new(channel.into_stream()),
+            service_fn(move |req| forward(client.clone(), req)),
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn forward(
+    client: kube::Client,
+    req: Request<Incoming>,
+) -> Result<Response<Body>, Infallible> {
+    let method = req.method().to_string();
+
+    let resp = match send(client, req).await {
+        Ok(resp) => resp,
+        Err(err) => error(StatusCode::BAD_GATEWAY, &format!("{err:?}")),
+    };
+
+    API_REQUESTS
+        .with_label_values(&[method.as_str(), resp.status().as_str()])
+        .inc();
+
+    Ok(resp)
+}
+
+async fn send(client: kube::Client, req: Request<Incoming>) -> Result<Response<Body>> {
+    // Exec, attach and port-forward all switch the connection over to another
+    // protocol, which isn't something a plain request can be turned into.
+    if req.headers().contains_key(header::UPGRADE) {
+        return Ok(error(
+            StatusCode::NOT_IMPLEMENTED,
+            "upgrading connections (exec, attach, port-forward) is not supported through kty",
+        ));
+    }
+
+    let (mut parts, body) = req.into_parts();
+
+    let names: Vec<_> = parts
+        .headers
+        .keys()
+        .filter(|name| forbidden(name) || *name == header::HOST)
+        .cloned()
+        .collect();
+
+    for name in names {
+        parts.headers.remove(name);
+    }
+
+    // The client adds the API server's address, only the path is needed.
+    parts.uri = parts
+        .uri
+        .path_and_query()
+        .map_or("/", http::uri::PathAndQuery::as_str)
+        .parse()?;
+
+    // Request bodies are manifests and patches, there's no harm in having them
+    // in memory. Responses are streamed so that watches work.
+    let body = body.collect().await?.to_bytes();
+
+    Ok(client
+        .send(Request::from_parts(parts, Body::from(body)))
+        .await?)
+}
+
+fn error(code: StatusCode, msg: &str) -> Response<Body> {
+    let mut resp = Response::new(Body::from(format!("{msg}\n").into_bytes()));
+    *resp.status_mut() = code;
+
+    resp
+}
@@ -9,7 +9,8 @@ use kube::{api::PostParams, core::ErrorResponse, Api, Resource};
 use russh::server::{self};
 use tokio::net::TcpStream;
 
-use super::{stream, StreamMetrics};
+use super::{apiserver, stream, StreamMetrics};
+use crate::policy::Scope;
 
 static CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
 
@@ -36,6 +37,21 @@ impl Ingress {
         self.host.to_string()
     }
 
+    /// Tunnels are held to the same namespaces as the dashboard, otherwise
+    /// they'd be a way around them. The API server can reach every namespace,
+    /// so it isn't available at all to anyone who is restricted.
+    pub fn allowed(&self, scope: &Scope) -> Result<()> {
+        match (self.host.resource(), self.host.segments.get(1)) {
+            ("kube-api", _) if scope.is_restricted() => Err(eyre!(
+                "kube-api isn't available when restricted to some namespaces"
+            )),
+            ("pods" | "services", Some(ns)) if !scope.allows(ns) => {
+                Err(eyre!("{ns} isn't one of the namespaces you can browse"))
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub async fn run(
         &self,
         client: kube::Client,
@@ -48,6 +64,12 @@ impl Ingress {
             "connection",
         );
 
+        // There's nothing to connect to, the API server is reached through the
+        // client so that requests are made as the user.
+        if self.host.resource() == "kube-api" {
+            return apiserver::serve(client, channel).await;
+        }
+
         let addr = self.host.addr(client.clone()).await?;
 
         let remote = tokio::time::timeout(
@@ -112,6 +134,7 @@ impl Host {
                 "pods" | "pod" | "po" => "pods".to_string(),
                 "services" | "service" | "svc" => "services".to_string(),
                 "nodes" | "node" | "no" => "nodes".to_string(),
+                "kube-api" | "kubernetes" => "kube-api".to_string(),
                 _ => return Err(eyre!("resource not supported")),
             },
             segments,
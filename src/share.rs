@@ -0,0 +1,199 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, LazyLock, Mutex,
+    },
+};
+
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use k8s_openapi::api::authorization::v1::{
+    ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+};
+use kube::api::{Api, PostParams};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, register_int_gauge, IntCounterVec, IntGauge};
+use tokio::sync::broadcast;
+use tokio_util::bytes::Bytes;
+
+// Enough output to give someone joining part way through an idea of what is
+// going on, without holding onto everything forever.
+static MAX_HISTORY: usize = 64 * 1024;
+
+// Viewers that fall this far behind skip ahead instead of slowing down the
+// session they're watching.
+static CHANNEL_SIZE: usize = 1024;
+
+lazy_static! {
+    static ref SHARED_SESSIONS: IntGauge = register_int_gauge!(
+        "shared_sessions",
+        "Number of exec sessions that are currently being shared"
+    )
+    .unwrap();
+    static ref SHARE_VIEWS: IntCounterVec = register_int_counter_vec!(
+        "shared_session_views_total",
+        "Number of times a shared session was watched, by result",
+        &["result"]
+    )
+    .unwrap();
+}
+
+/// An exec session that other users connected to this server can watch. It is
+/// only ever written to by its owner, everyone else gets a copy of the output.
+pub struct Share {
+    pub id: String,
+    pub namespace: String,
+    pub pod: String,
+    pub container: String,
+    pub started: DateTime<Utc>,
+
+    // Taken when sharing stops so that viewers see the channel close, even
+    // though they're still holding onto the share itself.
+    tx: Mutex<Option<broadcast::Sender<Bytes>>>,
+    history: Mutex<Vec<u8>>,
+}
+
+impl Share {
+    pub fn send(&self, data: &[u8]) {
+        let mut history = self.history.lock().unwrap();
+
+        history.extend_from_slice(data);
+
+        if history.len() > MAX_HISTORY + MAX_HISTORY / 10 {
+            let excess = history.len() - MAX_HISTORY;
+            history.drain(..excess);
+        }
+
+        if let Some(tx) = self.tx.lock().unwrap().as_ref() {
+            // Nobody watching isn't an error.
+            tx.send(Bytes::copy_from_slice(data)).ok();
+        }
+    }
+
+    /// Recent output along with a receiver for everything that comes after it.
+    /// Both are taken together so that nothing gets lost or repeated in between.
+    /// Once sharing has stopped, there's nothing to subscribe to.
+    pub fn subscribe(&self) -> Option<(Vec<u8>, broadcast::Receiver<Bytes>)> {
+        let history = self.history.lock().unwrap();
+        let rx = self.tx.lock().unwrap().as_ref()?.subscribe();
+
+        Some((history.clone(), rx))
+    }
+
+    pub fn viewers(&self) -> usize {
+        self.tx
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or(0, broadcast::Sender::receiver_count)
+    }
+
+    fn close(&self) {
+        self.tx.lock().unwrap().take();
+    }
+
+    /// Whether the user behind `client` can watch this session. Anyone who
+    /// could exec into the container themselves is allowed to.
+    pub async fn authorize(&self, client: kube::Client) -> Result<bool> {
+        let allowed = self.can_watch(client).await?;
+
+        SHARE_VIEWS
+            .with_label_values(&[if allowed { "allowed" } else { "denied" }])
+            .inc();
+
+        Ok(allowed)
+    }
+
+    /// The same check as `authorize`, for deciding whether to list the session
+    /// at all. Listing isn't watching, so it doesn't count towards the views.
+    pub async fn can_watch(&self, client: kube::Client) -> Result<bool> {
+        let access = Api::<SelfSubjectAccessReview>::all(client)
+            .create(
+                &PostParams::default(),
+                &SelfSubjectAccessReview {
+                    spec: SelfSubjectAccessReviewSpec {
+                        resource_attributes: Some(ResourceAttributes {
+                            namespace: Some(self.namespace.clone()),
+                            name: Some(self.pod.clone()),
+                            resource: Some("pods".to_string()),
+                            subresource: Some("exec".to_string()),
+                            verb: Some("create".to_string()),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        Ok(access.status.is_some_and(|status| status.allowed))
+    }
+}
+
+/// Every session currently being shared on this server. This lives in the
+/// replica's memory and isn't in the cluster, with more than one replica only
+/// the users that landed on the same one can see a share. The service routes
+/// clients by affinity to make that likely, see the "High Availability" docs.
+#[derive(Default)]
+pub struct Shares {
+    next: AtomicU64,
+    sessions: Mutex<BTreeMap<String, Arc<Share>>>,
+}
+
+pub static SHARES: LazyLock<Shares> = LazyLock::new(Shares::default);
+
+impl Shares {
+    /// Start sharing a session. It stays shared until the returned `Sharing` is
+    /// dropped.
+    pub fn start(&self, namespace: &str, pod: &str, container: &str) -> Sharing {
+        let (tx, _) = broadcast::channel(CHANNEL_SIZE);
+
+        let share = Arc::new(Share {
+            id: format!("s{}", self.next.fetch_add(1, Ordering::Relaxed) + 1),
+            namespace: namespace.to_string(),
+            pod: pod.to_string(),
+            container: container.to_string(),
+            started: Utc::now(),
+            tx: Mutex::new(Some(tx)),
+            history: Mutex::default(),
+        });
+
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(share.id.clone(), share.clone());
+
+        SHARED_SESSIONS.inc();
+
+        Sharing(share)
+    }
+
+    pub fn list(&self) -> Vec<Arc<Share>> {
+        self.sessions.lock().unwrap().values().cloned().collect()
+    }
+
+    fn stop(&self, id: &str) {
+        if self.sessions.lock().unwrap().remove(id).is_some() {
+            SHARED_SESSIONS.dec();
+        }
+    }
+}
+
+/// Handle held by the owner of a shared session. Dropping it stops sharing and
+/// disconnects anyone that is watching.
+pub struct Sharing(Arc<Share>);
+
+impl Sharing {
+    pub fn share(&self) -> &Arc<Share> {
+        &self.0
+    }
+}
+
+impl Drop for Sharing {
+    fn drop(&mut self) {
+        self.0.close();
+        SHARES.stop(&self.0.id);
+    }
+}
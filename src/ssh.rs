@@ -1,3 +1,4 @@
+pub(crate) mod listen;
 pub(crate) mod session;
 
 use std::{
@@ -19,7 +20,7 @@ use russh::server::{Config, Handler, Server};
 use session::{Session, SessionBuilder};
 use tracing::error;
 
-use crate::{identity::Identity, openid};
+use crate::{client, identity::Identity, openid, usage::Usage};
 
 lazy_static! {
     static ref CLIENT_COUNTER: IntCounter = register_int_counter!(
@@ -83,19 +84,28 @@ pub struct Controller {
 
 impl Controller {
     pub fn client(&self) -> Result<kube::Client, kube::Error> {
-        kube::Client::try_from(self.config.clone())
+        client::new(self.config.clone())
     }
 
     pub fn impersonate(
         &self,
         user: String,
         groups: Vec<String>,
+    ) -> Result<kube::Client, kube::Error> {
+        self.impersonate_with(user, groups, Arc::default())
+    }
+
+    pub fn impersonate_with(
+        &self,
+        user: String,
+        groups: Vec<String>,
+        usage: Arc<Usage>,
     ) -> Result<kube::Client, kube::Error> {
         let mut cfg = self.config.clone();
         cfg.auth_info.impersonate = Some(user);
         cfg.auth_info.impersonate_groups = (!groups.is_empty()).then_some(groups);
 
-        kube::Client::try_from(cfg)
+        client::metered(cfg, usage)
     }
 
     #[allow(dead_code)]
@@ -120,6 +130,7 @@ pub enum Features {
     IngressTunnel,
     EgressTunnel,
     Sftp,
+    Proxy,
 }
 
 #[derive(Clone, Builder)]
@@ -130,8 +141,27 @@ pub struct UIServer {
 }
 
 impl UIServer {
-    pub async fn run(&mut self, cfg: Config, addr: (String, u16)) -> Result<()> {
-        self.run_on_address(Arc::new(cfg), addr).await?;
+    pub async fn run(
+        &mut self,
+        cfg: Config,
+        mode: &listen::Mode,
+        addr: (String, u16),
+    ) -> Result<()> {
+        let cfg = Arc::new(cfg);
+
+        match mode {
+            listen::Mode::Address => self.run_on_address(cfg, addr).await?,
+            listen::Mode::Systemd => self.run_on_socket(cfg, &listen::systemd()?).await?,
+            listen::Mode::Inetd => {
+                let handler = self.new_client(None);
+
+                // The process is only around for this one connection, it is done
+                // once the session is.
+                russh::server::run_stream(cfg, listen::inetd(), handler)
+                    .await?
+                    .await?;
+            }
+        }
 
         Ok(())
     }
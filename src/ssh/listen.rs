@@ -0,0 +1,96 @@
+use std::{
+    os::fd::{FromRawFd, RawFd},
+    sync::OnceLock,
+};
+
+use clap::ValueEnum;
+use eyre::{eyre, Result};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+};
+
+// Sockets passed by systemd start right after stdin, stdout and stderr. See
+// `sd_listen_fds(3)`.
+static LISTEN_FDS_START: RawFd = 3;
+
+static VARS: [&str; 3] = ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"];
+
+// `LISTEN_PID` and `LISTEN_FDS`, as they were when the process started.
+static ACTIVATION: OnceLock<(Option<String>, Option<String>)> = OnceLock::new();
+
+/// Where connections come from.
+#[derive(Clone, Debug, Default, PartialEq, ValueEnum)]
+pub enum Mode {
+    /// Listen on `--address` and `--ssh-port`.
+    #[default]
+    Address,
+    /// Accept connections on a socket passed in by systemd socket activation
+    /// (`Accept=no`).
+    Systemd,
+    /// Serve a single connection over stdin and stdout, the way inetd and
+    /// systemd with `Accept=yes` start a process per connection.
+    Inetd,
+}
+
+/// Take the socket activation variables out of the environment, like
+/// `sd_listen_fds` with `unset_environment`, so that nothing started from here
+/// on thinks it was handed the socket too. This needs to happen before anything
+/// else is running, changing the environment isn't safe while another thread
+/// could be reading it.
+pub fn init() {
+    ACTIVATION.get_or_init(|| {
+        let activation = (
+            std::env::var("LISTEN_PID").ok(),
+            std::env::var("LISTEN_FDS").ok(),
+        );
+
+        for var in VARS {
+            // SAFETY: called first thing in `main`, before any tasks have been
+            // spawned that could be reading the environment.
+            unsafe { std::env::remove_var(var) };
+        }
+
+        activation
+    });
+}
+
+/// Take the listening socket systemd passed in. Only the first one is used,
+/// a `.socket` unit with more than one `ListenStream` isn't supported.
+pub fn systemd() -> Result<TcpListener> {
+    let (pid, fds) = ACTIVATION.get_or_init(Default::default);
+
+    let pid: Option<u32> = pid.as_ref().and_then(|pid| pid.parse().ok());
+
+    // The variables are inherited by children that aren't the ones they were
+    // meant for, which would end up sharing a socket they don't own.
+    if pid != Some(std::process::id()) {
+        return Err(eyre!(
+            "LISTEN_PID is not set to this process, is kty being started by a systemd .socket \
+             unit?"
+        ));
+    }
+
+    let fds: RawFd = fds
+        .as_ref()
+        .and_then(|fds| fds.parse().ok())
+        .unwrap_or_default();
+
+    if fds < 1 {
+        return Err(eyre!("no sockets were passed in with LISTEN_FDS"));
+    }
+
+    // SAFETY: systemd hands over ownership of the descriptors listed in
+    // `LISTEN_FDS`, nothing else in the process has opened or uses this one.
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(LISTEN_FDS_START) };
+    listener.set_nonblocking(true)?;
+
+    Ok(TcpListener::from_std(listener)?)
+}
+
+/// The connection inetd started this process for, stdin to read from it and
+/// stdout to write to it. Logs need to go somewhere else for this to work,
+/// which they do by default.
+pub fn inetd() -> impl AsyncRead + AsyncWrite + Unpin + Send + 'static {
+    tokio::io::join(tokio::io::stdin(), tokio::io::stdout())
+}
@@ -1,4 +1,6 @@
 mod metrics;
+mod proxy;
+mod resume;
 mod sftp;
 mod state;
 
@@ -16,7 +18,7 @@ use ratatui::{backend::WindowSize, layout::Size};
 use russh::{
     keys::key::PublicKey,
     server::{self, Auth, Response},
-    ChannelId, Disconnect, MethodSet,
+    ChannelId, CryptoVec, Disconnect, MethodSet,
 };
 use state::State;
 use tokio::task::JoinSet;
@@ -26,12 +28,18 @@ use super::Features;
 use crate::{
     broadcast::Broadcast,
     dashboard::Dashboard,
+    diagnostics,
     events::Event,
-    identity::Key,
-    io::Channel,
+    identity::{preferences::Storage, Key},
+    io::{Channel, Handoff},
+    lease::ELECTION,
     openid,
+    policy::Scope,
     resources::tunnel::{self, EgressBuilder, Ingress, Tunnel, TunnelBuilder},
     ssh::{Authenticate, Controller},
+    usage::Usage,
+    version::RELEASES,
+    widget::startup::Startup,
 };
 
 fn token_response(error: Report) -> Result<Auth> {
@@ -91,6 +99,12 @@ pub struct Session {
     #[builder(default)]
     broadcast: Broadcast,
 
+    // Dashboards running on this connection, by the channel they were started on.
+    // When the connection goes away they're parked instead of being shut down,
+    // see `resume`.
+    #[builder(default)]
+    dashboards: HashMap<ChannelId, resume::Attached>,
+
     // This is a somewhat special state. With my OpenSSH client, the
     // `tcpip_forward` connection comes in before the `pty` request. This makes
     // it difficult to show that there's an open, listening egress tunnel via.
@@ -106,6 +120,51 @@ impl Session {
         self.features.contains(feature)
     }
 
+    async fn diagnostics(&self) -> Result<Vec<u8>> {
+        let State::Authenticated(identity) = &self.state else {
+            return Err(eyre!("not authenticated"));
+        };
+
+        let client = identity.client(&self.controller)?;
+
+        if !diagnostics::authorize(client, &self.controller.server()).await? {
+            return Err(eyre!(
+                "{identity} is not allowed to get the logs of the kty server"
+            ));
+        }
+
+        diagnostics::bundle()
+    }
+
+    // Preferences are stored with the server's client, the same as they are for
+    // the dashboard.
+    async fn startup(&self, screen: Option<&str>) -> Result<Vec<u8>> {
+        let State::Authenticated(identity) = &self.state else {
+            return Err(eyre!("not authenticated"));
+        };
+
+        let storage = Storage::new(self.controller.client()?, identity);
+        let mut preferences = storage.load().await?;
+
+        if let Some(screen) = screen {
+            preferences.spec.startup = match screen {
+                "default" => None,
+                screen => Some(screen.parse()?),
+            };
+
+            storage.save(&preferences).await?;
+        }
+
+        Ok(preferences
+            .spec
+            .startup
+            .map_or_else(
+                || format!("{} (server default)\n", Startup::configured()),
+                |startup| format!("{startup}\n"),
+            )
+            .into_bytes())
+    }
+
     #[tracing::instrument(skip(self))]
     async fn send_code(&mut self) -> Result<Auth> {
         CODE_GENERATED.inc();
@@ -131,6 +190,8 @@ impl Session {
         let instructions =
             format!("{preface}\nLogin or scan the QRCode below to validate your identity:\n");
 
+        // This stays plain text instead of being an OSC 8 link, ssh clients strip
+        // control characters out of keyboard-interactive prompts.
         let prompt = format!("\n{login_url}\n\n{uri}\n\nPress Enter to continue");
 
         AUTH_RESULTS.interactive.partial.inc();
@@ -289,7 +350,11 @@ impl server::Handler for Session {
         CHANNELS.close.inc();
         tracing::debug!("channel-close");
 
-        if let Some(writer) = self.broadcast.remove(&id).await {
+        let writer = self.broadcast.remove(&id).await;
+
+        if let Some(dashboard) = self.dashboards.remove(&id) {
+            dashboard.park();
+        } else if let Some(writer) = writer {
             writer.send(Event::Shutdown)?;
         }
 
@@ -362,6 +427,19 @@ impl server::Handler for Session {
         #[allow(clippy::cast_possible_truncation)]
         let ingress = Ingress::new(host_to_connect, port_to_connect as u16)?;
 
+        if let Err(err) = ingress.allowed(&Scope::new(identity)) {
+            self.broadcast
+                .all(Event::Tunnel(Err(tunnel::Error::new(
+                    &err.wrap_err("unable to forward connection"),
+                    meta.into_error(),
+                ))))
+                .await?;
+
+            session.channel_failure(id);
+
+            return Ok(false);
+        }
+
         self.tasks.spawn(async move {
             let meta = meta.into_inactive();
 
@@ -470,13 +548,29 @@ impl server::Handler for Session {
             return Err(eyre!("channel {id} already consumed"));
         };
 
-        let writer = Dashboard::builder()
-            .client(identity.client(&self.controller)?)
-            .build()
-            .start(
-                channel.into_stream(),
-                Channel::new(id, session.handle().clone()),
-            )?;
+        let output = Channel::new(id, session.handle().clone());
+
+        let dashboard = if let Some(waiting) = resume::take(identity) {
+            debug!("resuming dashboard");
+
+            waiting.attach(channel.into_stream(), output)
+        } else {
+            let usage = Arc::new(Usage::default());
+            let output = Handoff::new(output);
+
+            let tx = Dashboard::builder()
+                .client(identity.metered_client(&self.controller, usage.clone())?)
+                .preferences(Storage::new(self.controller.client()?, identity))
+                .usage(usage)
+                .scope(Scope::new(identity))
+                .build()
+                .start(channel.into_stream(), output.clone())?;
+
+            resume::Attached::new(identity, tx, output)
+        };
+
+        let writer = dashboard.tx().clone();
+        self.dashboards.insert(id, dashboard);
 
         #[allow(clippy::cast_possible_truncation)]
         writer.send(Event::Resize(WindowSize {
@@ -500,7 +594,7 @@ impl server::Handler for Session {
         Ok(())
     }
 
-    #[tracing::instrument(skip(self, session), fields(activity = "sftp"))]
+    #[tracing::instrument(skip(self, session), fields(activity = "subsystem"))]
     async fn subsystem_request(
         &mut self,
         id: ChannelId,
@@ -516,21 +610,31 @@ impl server::Handler for Session {
             return Err(eyre!("Unexpected state: {:?}", self.state));
         };
 
-        if name != "sftp" {
-            session.channel_failure(id);
+        let feature = match name {
+            "sftp" => {
+                REQUESTS.sftp.inc();
 
-            session.disconnect(
-                Disconnect::ByApplication,
-                format!("unsupported subsystem: {name}").as_str(),
-                "",
-            );
+                Features::Sftp
+            }
+            "kty-proxy" => {
+                REQUESTS.proxy.inc();
 
-            return Ok(());
-        }
+                Features::Proxy
+            }
+            _ => {
+                session.channel_failure(id);
+
+                session.disconnect(
+                    Disconnect::ByApplication,
+                    format!("unsupported subsystem: {name}").as_str(),
+                    "",
+                );
 
-        REQUESTS.sftp.inc();
+                return Ok(());
+            }
+        };
 
-        if !self.enabled(&Features::Sftp) {
+        if !self.enabled(&feature) {
             session.channel_failure(id);
 
             return Ok(());
@@ -545,7 +649,24 @@ impl server::Handler for Session {
 
         self.channels.insert(id, None);
 
-        let handler = sftp::Handler::new(identity.client(&self.controller)?);
+        let client = identity.client(&self.controller)?;
+        let scope = Scope::new(identity);
+
+        if feature == Features::Proxy {
+            session.channel_success(id);
+
+            // This runs for as long as the channel is open, it can't hold up the
+            // rest of the session.
+            tokio::spawn(async move {
+                if let Err(err) = proxy::serve(client, scope, channel.into_stream()).await {
+                    tracing::debug!("proxy session ended: {err}");
+                }
+            });
+
+            return Ok(());
+        }
+
+        let handler = sftp::Handler::new(client, scope);
         russh_sftp::server::run(channel.into_stream(), handler).await;
 
         session.channel_success(id);
@@ -553,6 +674,55 @@ impl server::Handler for Session {
         Ok(())
     }
 
+    // There's no shell to run commands in, this only supports a small set of
+    // commands that are built into kty itself.
+    #[tracing::instrument(skip(self, data, session))]
+    async fn exec_request(
+        &mut self,
+        id: ChannelId,
+        data: &[u8],
+        session: &mut server::Session,
+    ) -> Result<(), Self::Error> {
+        REQUESTS.exec.inc();
+
+        let command = String::from_utf8_lossy(data);
+        tracing::debug!("exec: {command}");
+
+        let output = match command.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["kty", "version"] => {
+                Ok(format!("{}, {}\n", RELEASES.summary(), ELECTION.summary()).into_bytes())
+            }
+            ["kty", "diagnostics"] => self.diagnostics().await,
+            ["kty", "startup"] => self.startup(None).await,
+            ["kty", "startup", screen] => self.startup(Some(screen)).await,
+            _ => {
+                session.channel_failure(id);
+
+                return Ok(());
+            }
+        };
+
+        session.channel_success(id);
+
+        match output {
+            Ok(output) => {
+                session.data(id, CryptoVec::from(output));
+                session.exit_status_request(id, 0);
+            }
+            Err(err) => {
+                session.extended_data(id, 1, CryptoVec::from(format!("{err}\n")));
+                session.exit_status_request(id, 1);
+            }
+        }
+
+        session.eof(id);
+        session.close(id);
+
+        self.channels.remove(&id);
+
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self, session))]
     async fn tcpip_forward(
         &mut self,
@@ -594,6 +764,21 @@ impl server::Handler for Session {
             return Ok(false);
         }
 
+        if let Some((ns, _)) = address
+            .split_once('/')
+            .filter(|(ns, _)| !Scope::new(identity).allows(ns))
+        {
+            self.broadcast
+                .all(Event::Tunnel(Err(tunnel::Error::new(
+                    &eyre!("{ns} isn't one of the namespaces you can browse")
+                        .wrap_err("unable to listen"),
+                    meta.clone(),
+                ))))
+                .await?;
+
+            return Ok(false);
+        }
+
         let handle = session.handle();
         let broadcast = self.broadcast.clone();
         #[allow(clippy::cast_possible_truncation)]
@@ -643,5 +828,11 @@ impl Drop for Session {
         );
 
         self.tasks.abort_all();
+
+        // Connections that drop without closing their channels first, such as
+        // when the network goes away, end up here instead of `channel_close`.
+        for (_, dashboard) in self.dashboards.drain() {
+            dashboard.park();
+        }
     }
 }
@@ -31,8 +31,10 @@ make_static_metric! {
     }
     pub struct RequestVec: IntCounter {
         "method" => {
+            exec,
             pty,
             sftp,
+            proxy,
             window_resize,
             tcpip_forward,
         }
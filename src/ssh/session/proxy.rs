@@ -0,0 +1,691 @@
+use std::collections::HashMap;
+
+use eyre::Result;
+use futures::{AsyncBufReadExt, TryStreamExt};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    api::{Api, DynamicObject, GroupVersionKind, ListParams, LogParams},
+    core::discovery::Scope,
+    discovery::pinned_kind,
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    io::{
+        AsyncBufRead, AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, AsyncWrite,
+        AsyncWriteExt, BufReader,
+    },
+    sync::mpsc::{self, Sender},
+    task::AbortHandle,
+};
+
+use crate::{
+    policy,
+    resources::{pod::PodExt, GetGvk, Yaml},
+};
+
+lazy_static! {
+    static ref PROXY_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "proxy_requests_total",
+        "Number of requests made over the kty-proxy subsystem, by method and whether it succeeded",
+        &["method", "result"]
+    )
+    .unwrap();
+}
+
+// Codes from the JSON-RPC spec, along with the one LSP uses for cancellation.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+const CANCELLED: i64 = -32800;
+
+// Responses and log lines waiting to be written. Once it is full, requests
+// wait for the client to read instead of piling up in memory.
+const BUFFER: usize = 128;
+
+// Longest request that is read, anything past this is dropped without being
+// kept around. None of the methods take anything close to it.
+const MAX_REQUEST: usize = 1024 * 1024;
+
+#[derive(Deserialize)]
+struct Request {
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize)]
+struct Error {
+    code: i64,
+    message: String,
+}
+
+impl Error {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<eyre::Report> for Error {
+    fn from(err: eyre::Report) -> Self {
+        if let Some(kube::Error::Api(resp)) = err.downcast_ref::<kube::Error>() {
+            return Self::new(SERVER_ERROR, resp.message.clone());
+        }
+
+        Self::new(SERVER_ERROR, err.to_string())
+    }
+}
+
+enum Line {
+    Request(String),
+    TooLong,
+}
+
+// The next line of input, `None` once it has been closed. Lines longer than
+// `MAX_REQUEST` are skipped over a chunk at a time.
+async fn read_line<R>(reader: &mut R) -> Result<Option<Line>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut buf = Vec::new();
+
+    let read = (&mut *reader)
+        .take(MAX_REQUEST as u64 + 1)
+        .read_until(b'\n', &mut buf)
+        .await?;
+
+    if read == 0 {
+        return Ok(None);
+    }
+
+    if buf.last() == Some(&b'\n') || buf.len() <= MAX_REQUEST {
+        return Ok(Some(Line::Request(
+            String::from_utf8_lossy(&buf).into_owned(),
+        )));
+    }
+
+    loop {
+        let chunk = reader.fill_buf().await?;
+
+        if chunk.is_empty() {
+            break;
+        }
+
+        if let Some(end) = chunk.iter().position(|b| *b == b'\n') {
+            reader.consume(end + 1);
+
+            break;
+        }
+
+        let len = chunk.len();
+        reader.consume(len);
+    }
+
+    Ok(Some(Line::TooLong))
+}
+
+fn response(id: &Value, result: Result<Value, Error>) -> String {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => json!({ "jsonrpc": "2.0", "id": id, "error": err }),
+    }
+    .to_string()
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, Error> {
+    // Methods without anything required can be called without any params.
+    let params = if params.is_null() { json!({}) } else { params };
+
+    serde_json::from_value(params).map_err(|err| Error::new(INVALID_PARAMS, err.to_string()))
+}
+
+/// Newline delimited JSON-RPC 2.0 for editor plugins and scripts, eg.
+/// `ssh -s me@cluster kty-proxy`. Everything happens as the user that
+/// connected, so it can only read what they can, and is held to the same
+/// namespaces as their dashboard. Requests run concurrently, responses come
+/// back in whatever order they finish. Notifications (requests without an id)
+/// run the same way, but never get a response.
+pub async fn serve<S>(client: kube::Client, scope: policy::Scope, stream: S) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let (tx, mut rx) = mpsc::channel::<String>(BUFFER);
+
+    let output = tokio::spawn(async move {
+        while let Some(mut msg) = rx.recv().await {
+            msg.push('\n');
+
+            writer.write_all(msg.as_bytes()).await?;
+            writer.flush().await?;
+        }
+
+        writer.shutdown().await?;
+
+        Ok::<(), eyre::Report>(())
+    });
+
+    let mut running: HashMap<String, AbortHandle> = HashMap::new();
+    let mut reader = BufReader::new(reader);
+
+    while let Some(line) = read_line(&mut reader).await? {
+        let line = match line {
+            Line::Request(line) => line,
+            Line::TooLong => {
+                tx.send(response(
+                    &Value::Null,
+                    Err(Error::new(
+                        INVALID_REQUEST,
+                        format!("requests can be at most {MAX_REQUEST} bytes"),
+                    )),
+                ))
+                .await?;
+
+                continue;
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        running.retain(|_, task| !task.is_finished());
+
+        let request: Request = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(err) => {
+                tx.send(response(
+                    &Value::Null,
+                    Err(Error::new(PARSE_ERROR, err.to_string())),
+                ))
+                .await?;
+
+                continue;
+            }
+        };
+
+        let id = request.id;
+
+        if request.method == "cancel" {
+            let result = match params::<Cancel>(request.params) {
+                Ok(cancel) => match running.remove(&cancel.id.to_string()) {
+                    Some(task) => {
+                        task.abort();
+                        tx.send(response(
+                            &cancel.id,
+                            Err(Error::new(CANCELLED, "request cancelled")),
+                        ))
+                        .await?;
+
+                        Ok(json!(true))
+                    }
+                    None => Ok(json!(false)),
+                },
+                Err(err) => Err(err),
+            };
+
+            if let Some(id) = id {
+                tx.send(response(&id, result)).await?;
+            }
+
+            continue;
+        }
+
+        // Cancelling goes by id, so there can only be one of each running.
+        if let Some(id) = id
+            .as_ref()
+            .filter(|id| running.contains_key(&id.to_string()))
+        {
+            tx.send(response(
+                id,
+                Err(Error::new(
+                    INVALID_REQUEST,
+                    format!("a request with id {id} is already running"),
+                )),
+            ))
+            .await?;
+
+            continue;
+        }
+
+        let task = tokio::spawn(handle(
+            client.clone(),
+            scope.clone(),
+            tx.clone(),
+            id.clone(),
+            request.method,
+            request.params,
+        ));
+
+        if let Some(id) = id {
+            running.insert(id.to_string(), task.abort_handle());
+        }
+    }
+
+    for task in running.values() {
+        task.abort();
+    }
+
+    drop(tx);
+
+    output.await?
+}
+
+#[derive(Deserialize)]
+struct Cancel {
+    id: Value,
+}
+
+async fn handle(
+    client: kube::Client,
+    scope: policy::Scope,
+    tx: Sender<String>,
+    id: Option<Value>,
+    method: String,
+    params: Value,
+) {
+    // Anything the client makes up goes under one label, so that it can't
+    // blow up the number of series.
+    let (label, result) = match method.as_str() {
+        "pods.list" => ("pods.list", list_pods(client, &scope, params).await),
+        "resources.get" => ("resources.get", get(client, &scope, params).await),
+        "logs.stream" => (
+            "logs.stream",
+            logs(client, &scope, &tx, id.as_ref(), params).await,
+        ),
+        _ => (
+            "unknown",
+            Err(Error::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method: {method}"),
+            )),
+        ),
+    };
+
+    PROXY_REQUESTS
+        .with_label_values(&[label, if result.is_ok() { "success" } else { "failure" }])
+        .inc();
+
+    if let Some(id) = id {
+        tx.send(response(&id, result)).await.ok();
+    }
+}
+
+fn allowed(scope: &policy::Scope, namespace: &str) -> Result<(), Error> {
+    if scope.allows(namespace) {
+        return Ok(());
+    }
+
+    Err(Error::new(
+        INVALID_PARAMS,
+        format!("{namespace} isn't one of the namespaces you can browse"),
+    ))
+}
+
+#[derive(Deserialize)]
+struct ListPods {
+    namespace: Option<String>,
+    selector: Option<String>,
+}
+
+async fn list_pods(
+    client: kube::Client,
+    scope: &policy::Scope,
+    params: Value,
+) -> Result<Value, Error> {
+    let ListPods {
+        namespace,
+        selector,
+    } = self::params(params)?;
+
+    let api = match namespace {
+        Some(ns) => {
+            allowed(scope, &ns)?;
+
+            Api::<Pod>::namespaced(client, &ns)
+        }
+        None => Api::<Pod>::all(client),
+    };
+
+    let mut lp = ListParams::default();
+    if let Some(selector) = selector {
+        lp = lp.labels(&selector);
+    }
+
+    let pods = api.list(&lp).await.map_err(eyre::Report::from)?;
+
+    Ok(pods
+        .items
+        .iter()
+        .filter(|pod| scope.allows(&pod.namespace().unwrap_or_default()))
+        .map(|pod| {
+            json!({
+                "namespace": pod.namespace(),
+                "name": pod.name_any(),
+                "status": pod.status().to_string(),
+                "ready": pod.ready(),
+                "restarts": pod
+                    .status
+                    .iter()
+                    .flat_map(|status| status.container_statuses.iter().flatten())
+                    .map(|status| status.restart_count)
+                    .sum::<i32>(),
+                "node": pod.spec.as_ref().and_then(|spec| spec.node_name.clone()),
+                "ip": pod.ip(),
+            })
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Get {
+    api_version: String,
+    kind: String,
+    namespace: Option<String>,
+    name: String,
+}
+
+async fn get(client: kube::Client, scope: &policy::Scope, params: Value) -> Result<Value, Error> {
+    let Get {
+        api_version,
+        kind,
+        namespace,
+        name,
+    } = self::params(params)?;
+
+    let gvk = DynamicObject {
+        types: Some(kube::api::TypeMeta { api_version, kind }),
+        metadata: kube::api::ObjectMeta::default(),
+        data: Value::Null,
+    }
+    .gvk()?;
+
+    let object = fetch(client, scope, &gvk, namespace.as_deref(), &name).await?;
+
+    Ok(json!({ "yaml": object.to_yaml()? }))
+}
+
+async fn fetch(
+    client: kube::Client,
+    scope: &policy::Scope,
+    gvk: &GroupVersionKind,
+    namespace: Option<&str>,
+    name: &str,
+) -> Result<DynamicObject, Error> {
+    let (ar, caps) = pinned_kind(&client, gvk)
+        .await
+        .map_err(eyre::Report::from)?;
+
+    let api = match (caps.scope, namespace) {
+        (Scope::Namespaced, Some(ns)) => {
+            allowed(scope, ns)?;
+
+            Api::<DynamicObject>::namespaced_with(client, ns, &ar)
+        }
+        (Scope::Namespaced, None) => {
+            return Err(Error::new(
+                INVALID_PARAMS,
+                format!("{} is namespaced", gvk.kind),
+            ))
+        }
+        (Scope::Cluster, _) => {
+            // Namespaces are held to the scope like everything in them.
+            if ar.group.is_empty() && ar.kind == "Namespace" {
+                allowed(scope, name)?;
+            }
+
+            Api::<DynamicObject>::all_with(client, &ar)
+        }
+    };
+
+    Ok(api.get(name).await.map_err(eyre::Report::from)?)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Logs {
+    namespace: String,
+    name: String,
+    container: Option<String>,
+    #[serde(default)]
+    follow: bool,
+    tail_lines: Option<i64>,
+    #[serde(default)]
+    previous: bool,
+}
+
+// Each line goes out as a `logs.line` notification with the id of the request
+// it belongs to. The request itself finishes once the logs do, which is never
+// when following unless it is cancelled.
+async fn logs(
+    client: kube::Client,
+    scope: &policy::Scope,
+    tx: &Sender<String>,
+    id: Option<&Value>,
+    params: Value,
+) -> Result<Value, Error> {
+    let Logs {
+        namespace,
+        name,
+        container,
+        follow,
+        tail_lines,
+        previous,
+    } = self::params(params)?;
+
+    allowed(scope, &namespace)?;
+
+    let mut stream = Api::<Pod>::namespaced(client, &namespace)
+        .log_stream(
+            &name,
+            &LogParams {
+                container,
+                follow,
+                previous,
+                tail_lines,
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(eyre::Report::from)?
+        .lines();
+
+    let mut count = 0;
+
+    while let Some(line) = stream.try_next().await.map_err(eyre::Report::from)? {
+        count += 1;
+
+        tx.send(
+            json!({
+                "jsonrpc": "2.0",
+                "method": "logs.line",
+                "params": { "id": id, "line": line },
+            })
+            .to_string(),
+        )
+        .await
+        .map_err(|err| Error::new(SERVER_ERROR, err.to_string()))?;
+    }
+
+    Ok(json!({ "lines": count }))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{duplex, AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    use super::*;
+
+    // Anything that gets as far as the API server fails, which is all that's
+    // needed for what happens before that.
+    fn client() -> kube::Client {
+        kube::Client::new(
+            tower::service_fn(|_: http::Request<kube::client::Body>| async {
+                Err::<http::Response<kube::client::Body>, _>(std::io::Error::other("no cluster"))
+            }),
+            "default",
+        )
+    }
+
+    #[test]
+    fn params_missing() {
+        let list: ListPods = params(Value::Null).unwrap_or_else(|_| panic!("params"));
+
+        assert!(list.namespace.is_none());
+        assert!(list.selector.is_none());
+    }
+
+    #[test]
+    fn params_camel_case() {
+        let logs: Logs = params(json!({
+            "namespace": "default",
+            "name": "web",
+            "tailLines": 10,
+        }))
+        .unwrap_or_else(|_| panic!("params"));
+
+        assert_eq!(logs.tail_lines, Some(10));
+        assert!(!logs.follow);
+        assert!(!logs.previous);
+    }
+
+    #[test]
+    fn params_invalid() {
+        let Err(err) = params::<Get>(json!({ "kind": "Pod" })) else {
+            panic!("apiVersion and name are required");
+        };
+
+        assert_eq!(err.code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn request_without_id_or_params() {
+        let request: Request = serde_json::from_str(r#"{"method":"pods.list"}"#).unwrap();
+
+        assert!(request.id.is_none());
+        assert!(request.params.is_null());
+        assert_eq!(request.method, "pods.list");
+    }
+
+    #[test]
+    fn responses() {
+        let ok: Value = serde_json::from_str(&response(&json!(1), Ok(json!([])))).unwrap();
+
+        assert_eq!(ok, json!({ "jsonrpc": "2.0", "id": 1, "result": [] }));
+
+        let err: Value = serde_json::from_str(&response(
+            &json!("a"),
+            Err(Error::new(METHOD_NOT_FOUND, "unknown method: nope")),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            err,
+            json!({
+                "jsonrpc": "2.0",
+                "id": "a",
+                "error": { "code": METHOD_NOT_FOUND, "message": "unknown method: nope" },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn serve_requests() {
+        let (local, remote) = duplex(4096);
+        let server = tokio::spawn(serve(client(), policy::Scope::default(), remote));
+
+        let (reader, mut writer) = tokio::io::split(local);
+
+        writer
+            .write_all(
+                concat!(
+                    "not json\n",
+                    "\n",
+                    r#"{"id":1,"method":"nope"}"#,
+                    "\n",
+                    r#"{"id":2,"method":"cancel","params":{"id":99}}"#,
+                    "\n",
+                    r#"{"id":3,"method":"resources.get","params":{}}"#,
+                    "\n",
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        let mut lines = BufReader::new(reader).lines();
+        let mut responses = HashMap::new();
+
+        // Anything still running is dropped once the input closes, so it stays
+        // open until every request has been answered.
+        while responses.len() < 4 {
+            let line = lines.next_line().await.unwrap().unwrap();
+            let response: Value = serde_json::from_str(&line).unwrap();
+
+            responses.insert(response["id"].to_string(), response);
+        }
+
+        writer.shutdown().await.unwrap();
+
+        assert!(lines.next_line().await.unwrap().is_none());
+
+        server.await.unwrap().unwrap();
+
+        assert_eq!(responses["null"]["error"]["code"], json!(PARSE_ERROR));
+        assert_eq!(responses["1"]["error"]["code"], json!(METHOD_NOT_FOUND));
+        assert_eq!(responses["2"]["result"], json!(false));
+        assert_eq!(responses["3"]["error"]["code"], json!(INVALID_PARAMS));
+    }
+
+    #[tokio::test]
+    async fn serve_notifications_and_long_requests() {
+        let (local, remote) = duplex(4096);
+        let server = tokio::spawn(serve(client(), policy::Scope::default(), remote));
+
+        let (reader, mut writer) = tokio::io::split(local);
+
+        let mut input = r#"{"method":"nope"}"#.to_string();
+        input.push('\n');
+        input.push_str(&"a".repeat(MAX_REQUEST + 10));
+        input.push('\n');
+        input.push_str(r#"{"id":5,"method":"cancel","params":{"id":1}}"#);
+        input.push('\n');
+
+        let input = tokio::spawn(async move {
+            writer.write_all(input.as_bytes()).await.unwrap();
+
+            writer
+        });
+
+        let mut lines = BufReader::new(reader).lines();
+        let mut responses = Vec::new();
+
+        while responses.len() < 2 {
+            let line = lines.next_line().await.unwrap().unwrap();
+
+            responses.push(serde_json::from_str::<Value>(&line).unwrap());
+        }
+
+        input.await.unwrap().shutdown().await.unwrap();
+
+        assert!(lines.next_line().await.unwrap().is_none());
+
+        server.await.unwrap().unwrap();
+
+        // Nothing for the notification, which would have been first.
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[0]["error"]["code"], json!(INVALID_REQUEST));
+        assert_eq!(responses[1]["id"], json!(5));
+        assert_eq!(responses[1]["result"], json!(false));
+    }
+}
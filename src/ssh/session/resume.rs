@@ -0,0 +1,171 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        LazyLock, Mutex,
+    },
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, register_int_gauge, IntCounterVec, IntGauge};
+use tokio::{io::AsyncRead, sync::mpsc::UnboundedSender};
+
+use crate::{
+    dashboard,
+    events::Event,
+    identity::Identity,
+    io::{Channel, Handoff},
+};
+
+lazy_static! {
+    static ref PARKED_DASHBOARDS: IntGauge = register_int_gauge!(
+        "parked_dashboards",
+        "Number of dashboards waiting for their user to reconnect"
+    )
+    .unwrap();
+    static ref PARKED_RESULTS: IntCounterVec = register_int_counter_vec!(
+        "parked_dashboards_total",
+        "Number of dashboards kept around after their connection went away, by whether they \
+         were resumed",
+        &["result"]
+    )
+    .unwrap();
+}
+
+static NEXT: AtomicU64 = AtomicU64::new(0);
+
+// Dashboards are running on this replica, so this can't be anywhere else.
+// Only reconnects that land here again find theirs, which the service's
+// affinity takes care of most of the time.
+static PARKED: LazyLock<Mutex<HashMap<String, Parked>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+struct Parked {
+    id: u64,
+    tx: UnboundedSender<Event>,
+    output: Handoff,
+}
+
+/// A dashboard that is running on a connection, along with what it takes to
+/// move it over to another connection later.
+pub struct Attached {
+    key: String,
+    tx: UnboundedSender<Event>,
+    output: Handoff,
+}
+
+impl Attached {
+    pub fn new(identity: &Identity, tx: UnboundedSender<Event>, output: Handoff) -> Self {
+        Self {
+            key: identity.to_string(),
+            tx,
+            output,
+        }
+    }
+
+    pub fn tx(&self) -> &UnboundedSender<Event> {
+        &self.tx
+    }
+
+    /// The connection has gone away. Keep the dashboard running for
+    /// `reconnect.window` so that the same user can pick it back up. Only the
+    /// most recent one is kept for each user, anything that was already waiting
+    /// is shut down. Dashboards that have finished on their own aren't kept.
+    pub fn park(self) {
+        let window = crate::config::get().reconnect.window;
+
+        if window == 0 || self.tx.is_closed() {
+            self.tx.send(Event::Shutdown).ok();
+
+            return;
+        }
+
+        self.output.detach();
+
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+        let key = self.key.clone();
+
+        let previous = PARKED.lock().unwrap().insert(
+            self.key,
+            Parked {
+                id,
+                tx: self.tx,
+                output: self.output,
+            },
+        );
+
+        PARKED_DASHBOARDS.inc();
+
+        if let Some(previous) = previous {
+            PARKED_DASHBOARDS.dec();
+            PARKED_RESULTS.with_label_values(&["replaced"]).inc();
+
+            previous.tx.send(Event::Shutdown).ok();
+        }
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(window)).await;
+
+            expire(&key, id);
+        });
+    }
+}
+
+fn expire(key: &str, id: u64) {
+    let mut parked = PARKED.lock().unwrap();
+
+    if !parked.get(key).is_some_and(|dashboard| dashboard.id == id) {
+        return;
+    }
+
+    if let Some(dashboard) = parked.remove(key) {
+        PARKED_DASHBOARDS.dec();
+        PARKED_RESULTS.with_label_values(&["expired"]).inc();
+
+        dashboard.tx.send(Event::Shutdown).ok();
+    }
+}
+
+/// A dashboard waiting for its user to come back, if there is one. It is no
+/// longer waiting once this returns, dropping it without attaching ends it.
+pub fn take(identity: &Identity) -> Option<Waiting> {
+    let key = identity.to_string();
+
+    let dashboard = PARKED.lock().unwrap().remove(&key)?;
+
+    PARKED_DASHBOARDS.dec();
+
+    if dashboard.tx.is_closed() {
+        return None;
+    }
+
+    Some(Waiting { key, dashboard })
+}
+
+pub struct Waiting {
+    key: String,
+    dashboard: Parked,
+}
+
+impl Waiting {
+    /// Pick up where the user left off. Output moves over to `channel` and
+    /// input comes from `stdin`.
+    pub fn attach<R>(self, stdin: R, channel: Channel) -> Attached
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        PARKED_RESULTS.with_label_values(&["resumed"]).inc();
+
+        let Parked { tx, output, .. } = self.dashboard;
+
+        output.attach(channel);
+        dashboard::forward(stdin, tx.clone());
+
+        Attached {
+            key: self.key,
+            tx,
+            output,
+        }
+    }
+}
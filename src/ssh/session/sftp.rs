@@ -12,7 +12,7 @@ use russh_sftp::{
     server,
 };
 
-use crate::resources::File;
+use crate::{policy::Scope, resources::File};
 
 make_static_metric! {
     pub struct DirectionVec: IntCounter {
@@ -66,19 +66,30 @@ impl Default for State {
 
 pub struct Handler {
     client: kube::Client,
+    scope: Scope,
     state: State,
 }
 
 // TODO: would it be better to add a `Store<Pod>` to this?
 impl Handler {
-    pub fn new(client: kube::Client) -> Self {
+    pub fn new(client: kube::Client, scope: Scope) -> Self {
         SFTP_ACTIVE.inc();
 
         Self {
             client,
+            scope,
             state: State::default(),
         }
     }
+
+    // Namespaces outside of the session's scope don't exist as far as sftp is
+    // concerned, the same as they don't in the dashboard.
+    fn allowed(&self, file: &File) -> Result<(), StatusCode> {
+        match &file.namespace {
+            Some(ns) if !self.scope.allows(ns) => Err(StatusCode::NoSuchFile),
+            _ => Ok(()),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -122,7 +133,11 @@ impl server::Handler for Handler {
 
         tracing::debug!("read");
 
-        let result = File::new(Path::new(handle.as_str()))
+        let file = File::new(Path::new(handle.as_str()));
+
+        self.allowed(&file)?;
+
+        let result = file
             .read(self.client.clone())
             .await
             .map(|data| Data { id, data })
@@ -174,12 +189,23 @@ impl server::Handler for Handler {
 
         self.state = State::DirComplete;
 
-        let path = Path::new(handle.as_str());
+        let file = File::new(Path::new(handle.as_str()));
 
-        File::new(path)
-            .list(self.client.clone())
+        self.allowed(&file)?;
+
+        file.list(self.client.clone())
             .await
-            .map(|files| Name { id, files })
+            .map(|files| Name {
+                id,
+                files: if file.namespace.is_none() {
+                    files
+                        .into_iter()
+                        .filter(|ns| self.scope.allows(&ns.filename))
+                        .collect()
+                } else {
+                    files
+                },
+            })
             .map_err(|e| {
                 tracing::debug!("readdir: {:?}", e);
                 StatusCode::NoSuchFile
@@ -202,8 +228,11 @@ impl server::Handler for Handler {
         SFTP_STAT.inc();
         tracing::debug!("stat");
 
-        File::new(Path::new(path.as_str()))
-            .stat(self.client.clone())
+        let file = File::new(Path::new(path.as_str()));
+
+        self.allowed(&file)?;
+
+        file.stat(self.client.clone())
             .await
             .map(|attrs| Attrs { id, attrs })
             .map_err(|e| {
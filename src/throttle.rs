@@ -0,0 +1,81 @@
+use std::{
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::{header::RETRY_AFTER, Response, StatusCode};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, IntCounter};
+
+lazy_static! {
+    static ref THROTTLED: IntCounter = register_int_counter!(
+        "api_throttled_total",
+        "Number of times the API server responded with 429 (too many requests)"
+    )
+    .unwrap();
+}
+
+// The API server's priority and fairness implementation sets `Retry-After` on
+// every 429, this is only used if that header is missing or unparseable.
+static DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Shared state for when the API server has asked us to slow down.
+///
+/// Every session goes through the same upstream API server, so being throttled
+/// in one store is a good indication that the rest will be throttled as well.
+/// Stores consult this before pulling more events and the UI uses it to tell
+/// the user why things have stopped updating.
+#[derive(Default)]
+pub struct Throttle {
+    until: Mutex<Option<Instant>>,
+}
+
+pub static THROTTLE: LazyLock<Throttle> = LazyLock::new(Throttle::default);
+
+impl Throttle {
+    pub fn throttle(&self, retry_after: Duration) {
+        THROTTLED.inc();
+
+        let until = Instant::now() + retry_after;
+        let mut current = self.until.lock().unwrap();
+
+        if current.map_or(true, |current| current < until) {
+            *current = Some(until);
+        }
+    }
+
+    /// How long until requests should be attempted again, `None` if there is
+    /// no active throttle.
+    pub fn remaining(&self) -> Option<Duration> {
+        let until = (*self.until.lock().unwrap())?;
+
+        until
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    pub async fn wait(&self) {
+        while let Some(remaining) = self.remaining() {
+            tokio::time::sleep(remaining).await;
+        }
+    }
+}
+
+pub fn observe<B>(resp: Response<B>) -> Response<B> {
+    if resp.status() != StatusCode::TOO_MANY_REQUESTS {
+        return resp;
+    }
+
+    let retry_after = resp
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map_or(DEFAULT_RETRY_AFTER, Duration::from_secs);
+
+    tracing::debug!(?retry_after, "throttled by API server");
+
+    THROTTLE.throttle(retry_after);
+
+    resp
+}
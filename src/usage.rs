@@ -0,0 +1,253 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::{future::BoxFuture, FutureExt};
+use http::{Request, Response};
+use http_body::{Body, Frame, SizeHint};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use tower::{Layer, Service};
+
+lazy_static! {
+    static ref API_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "api_requests_total",
+        "Number of requests made to the API server, by resource",
+        &["resource"]
+    )
+    .unwrap();
+    static ref API_BYTES: IntCounterVec = register_int_counter_vec!(
+        "api_response_bytes_total",
+        "Number of bytes received from the API server, by resource",
+        &["resource"]
+    )
+    .unwrap();
+    static ref WATCH_EVENTS: IntCounterVec = register_int_counter_vec!(
+        "watch_events_total",
+        "Number of watch events processed by stores, by resource",
+        &["resource"]
+    )
+    .unwrap();
+}
+
+thread_local! {
+    // Stores are created by widgets, which all run on the dashboard's thread.
+    // Keeping the session's usage here saves passing it to every one of them.
+    static CURRENT: RefCell<Option<Arc<Usage>>> = const { RefCell::new(None) };
+}
+
+/// Make `usage` the one that stores created on this thread report to.
+pub fn set(usage: Arc<Usage>) {
+    CURRENT.set(Some(usage));
+}
+
+pub fn current() -> Option<Arc<Usage>> {
+    CURRENT.with_borrow(Clone::clone)
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Counts {
+    pub requests: u64,
+    pub events: u64,
+    pub bytes: u64,
+}
+
+/// How much a single session has been asking of the API server, broken down
+/// by resource. Everything is also exported as metrics, without the session,
+/// so that operators can tell which views are expensive on their cluster.
+#[derive(Default)]
+pub struct Usage {
+    resources: Mutex<BTreeMap<String, Counts>>,
+}
+
+impl Usage {
+    fn update(&self, resource: &str, f: impl FnOnce(&mut Counts)) {
+        f(self
+            .resources
+            .lock()
+            .unwrap()
+            .entry(resource.to_string())
+            .or_default());
+    }
+
+    fn request(&self, resource: &str) {
+        API_REQUESTS.with_label_values(&[resource]).inc();
+
+        self.update(resource, |counts| counts.requests += 1);
+    }
+
+    fn received(&self, resource: &str, len: usize) {
+        API_BYTES.with_label_values(&[resource]).inc_by(len as u64);
+
+        self.update(resource, |counts| counts.bytes += len as u64);
+    }
+
+    pub fn event(&self, resource: &str) {
+        WATCH_EVENTS.with_label_values(&[resource]).inc();
+
+        self.update(resource, |counts| counts.events += 1);
+    }
+
+    pub fn total(&self) -> Counts {
+        self.resources
+            .lock()
+            .unwrap()
+            .values()
+            .fold(Counts::default(), |total, counts| Counts {
+                requests: total.requests + counts.requests,
+                events: total.events + counts.events,
+                bytes: total.bytes + counts.bytes,
+            })
+    }
+
+    pub fn resources(&self) -> Vec<(String, Counts)> {
+        self.resources
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(resource, counts)| (resource.clone(), *counts))
+            .collect()
+    }
+}
+
+/// The name resources are reported under, the plural and group the way
+/// `kubectl` takes them (eg. `deployments.apps`).
+pub fn resource(plural: &str, group: &str) -> String {
+    if group.is_empty() {
+        plural.to_string()
+    } else {
+        format!("{plural}.{group}")
+    }
+}
+
+// Pull the resource out of a request's path, eg.
+// `/apis/apps/v1/namespaces/default/deployments/foo` is `deployments.apps`.
+// Subresources are kept separate, `pods/log` is a very different cost than
+// `pods`.
+fn from_path(path: &str) -> String {
+    let segments: Vec<_> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let (group, rest) = match segments.as_slice() {
+        ["api", _, rest @ ..] => ("", rest),
+        ["apis", group, _, rest @ ..] => (*group, rest),
+        _ => return "other".to_string(),
+    };
+
+    let rest = match rest {
+        ["namespaces", _, rest @ ..] if !rest.is_empty() => rest,
+        rest => rest,
+    };
+
+    match rest {
+        [] => "discovery".to_string(),
+        [plural] | [plural, _] => resource(plural, group),
+        [plural, _, subresource, ..] => format!("{}/{subresource}", resource(plural, group)),
+    }
+}
+
+#[derive(Clone)]
+pub struct MeterLayer {
+    usage: Arc<Usage>,
+}
+
+impl MeterLayer {
+    pub fn new(usage: Arc<Usage>) -> Self {
+        Self { usage }
+    }
+}
+
+impl<S> Layer<S> for MeterLayer {
+    type Service = Meter<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Meter {
+            inner,
+            usage: self.usage.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Meter<S> {
+    inner: S,
+    usage: Arc<Usage>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for Meter<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Metered<ResBody>>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let resource = from_path(req.uri().path());
+        let usage = self.usage.clone();
+
+        usage.request(&resource);
+
+        self.inner
+            .call(req)
+            .map(move |result| {
+                result.map(|resp| {
+                    resp.map(|inner| Metered {
+                        inner,
+                        usage,
+                        resource,
+                    })
+                })
+            })
+            .boxed()
+    }
+}
+
+/// A response body that counts the bytes going through it. Watches and logs
+/// stream for as long as they are open, so this is counted as data arrives
+/// instead of once the response has been read.
+pub struct Metered<B> {
+    inner: B,
+    usage: Arc<Usage>,
+    resource: String,
+}
+
+impl<B> Body for Metered<B>
+where
+    B: Body<Data = Bytes> + Unpin,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let result = Pin::new(&mut self.inner).poll_frame(cx);
+
+        if let Poll::Ready(Some(Ok(frame))) = &result {
+            if let Some(data) = frame.data_ref() {
+                self.usage.received(&self.resource, data.len());
+            }
+        }
+
+        result
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use eyre::Result;
+use lazy_static::lazy_static;
+use prometheus::{register_int_gauge_vec, IntGaugeVec};
+use serde::Deserialize;
+
+use crate::lease::ELECTION;
+
+pub static VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub static RELEASES_URL: &str = "https://api.github.com/repos/grampelberg/kty/releases/latest";
+
+static CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+lazy_static! {
+    static ref BUILD_INFO: IntGaugeVec = register_int_gauge_vec!(
+        "build_info",
+        "Always 1, labeled with the running version and the latest release if known",
+        &["version", "latest"]
+    )
+    .unwrap();
+}
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+/// What is known about releases. The latest release is only ever filled in
+/// when the upstream check has been enabled.
+#[derive(Default)]
+pub struct Releases {
+    latest: Mutex<Option<semver::Version>>,
+}
+
+pub static RELEASES: LazyLock<Releases> = LazyLock::new(Releases::default);
+
+impl Releases {
+    pub(crate) fn set(&self, latest: semver::Version) {
+        BUILD_INFO.reset();
+        BUILD_INFO
+            .with_label_values(&[VERSION, latest.to_string().as_str()])
+            .set(1);
+
+        *self.latest.lock().unwrap() = Some(latest);
+    }
+
+    /// The latest release, regardless of what is running.
+    pub fn latest(&self) -> Option<semver::Version> {
+        self.latest.lock().unwrap().clone()
+    }
+
+    /// The latest release, if it is newer than what is running.
+    pub fn update(&self) -> Option<semver::Version> {
+        let current = semver::Version::parse(VERSION).ok()?;
+
+        self.latest().filter(|latest| latest > &current)
+    }
+
+    /// Single line description of the running version, suitable for showing
+    /// to users.
+    pub fn summary(&self) -> String {
+        self.update().map_or_else(
+            || format!("kty {VERSION}"),
+            |latest| format!("kty {VERSION} (update available: {latest})"),
+        )
+    }
+}
+
+/// Record the running version as a metric so that it can be tracked across
+/// clusters.
+pub fn register() {
+    BUILD_INFO.with_label_values(&[VERSION, ""]).set(1);
+}
+
+async fn latest(url: &str) -> Result<semver::Version> {
+    let release: Release = reqwest::Client::new()
+        .get(url)
+        // GitHub rejects requests without a user agent.
+        .header(reqwest::header::USER_AGENT, format!("kty/{VERSION}"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(semver::Version::parse(
+        release.tag_name.trim_start_matches('v'),
+    )?)
+}
+
+/// Periodically check `url` for the latest release. Failures are logged and
+/// retried on the next interval, there's no reason for this to take the server
+/// down. With multiple replicas, only the leader checks and the rest pick the
+/// result up from the lease.
+pub async fn watch(url: String) -> Result<()> {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if !ELECTION.is_leader() {
+            continue;
+        }
+
+        match latest(&url).await {
+            Ok(version) => RELEASES.set(version),
+            Err(err) => tracing::warn!("unable to check for the latest release: {err:?}"),
+        }
+    }
+}
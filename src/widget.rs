@@ -1,13 +1,52 @@
 pub mod apex;
+pub mod capacity;
+pub mod configmap;
+pub mod confirm;
+pub mod copy;
+pub mod cordon;
+pub mod create;
+pub mod cronjob;
+pub mod daemonset;
 pub mod debug;
+pub mod delete;
+pub mod deployment;
+pub mod deprecation;
+pub mod drain;
+pub mod drift;
+pub mod dynamic;
+pub mod edit;
 pub mod error;
+pub mod event;
+pub mod extended;
+pub mod feed;
 pub mod input;
+pub mod job;
+pub mod link;
 pub mod loading;
 pub mod log;
+pub mod metadata;
+pub mod namespace;
 pub mod nav;
+pub mod node;
+pub mod object;
+pub mod palette;
 pub mod pod;
+pub mod preflight;
+pub mod quit;
+pub mod recent;
+pub mod revert;
+pub mod scroll;
+pub mod scrollback;
+pub mod secret;
+pub mod service;
+pub mod share;
+pub mod startup;
+pub mod statefulset;
+pub mod status;
+pub mod switcher;
 pub mod table;
 pub mod tabs;
+pub mod theme;
 pub mod tunnel;
 pub mod view;
 pub mod viewport;
@@ -22,7 +61,7 @@ use prometheus::{opts, register_int_counter_vec, IntCounterVec};
 use prometheus_static_metric::make_static_metric;
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Rect},
+    layout::{Constraint, Layout, Rect},
     Frame,
 };
 use tokio::{io::AsyncWrite, sync::mpsc::UnboundedReceiver};
@@ -32,15 +71,39 @@ use crate::events::{Broadcast, Event};
 make_static_metric! {
     pub struct WidgetVec: IntCounter {
         "resource" => {
+            configmap,
             container,
+            cronjob,
+            daemonset,
+            deployment,
+            event,
+            job,
+            node,
             pod,
+            secret,
+            service,
+            statefulset,
         },
         "type" => {
+            capacity,
+            churn,
             cmd,
+            command,
+            data,
             detail,
+            env,
             exec,
+            jobs,
+            lifecycle,
             list,
             log,
+            nodes,
+            ordinals,
+            probes,
+            runtime,
+            scheduling,
+            security,
+            trigger,
             yaml,
         },
     }
@@ -72,6 +135,36 @@ impl Default for Placement {
     }
 }
 
+/// Where a popup goes, in the middle of `area` and taking up most of it.
+pub fn centered(area: Rect) -> Rect {
+    centered_with(area, Constraint::Percentage(60), Constraint::Percentage(60))
+}
+
+/// A popup of a particular size in the middle of `area`, such as a short
+/// confirmation that only needs a few lines.
+pub fn centered_with(area: Rect, width: Constraint, height: Constraint) -> Rect {
+    let [_, area, _] =
+        Layout::horizontal([Constraint::Fill(1), width, Constraint::Fill(1)]).areas(area);
+    let [_, area, _] =
+        Layout::vertical([Constraint::Fill(1), height, Constraint::Fill(1)]).areas(area);
+
+    area
+}
+
+/// Something a widget does when a key is pressed. These are what the command
+/// palette (`ctrl-k`) lists, picking one presses the key.
+#[derive(Clone, Debug)]
+pub struct Action {
+    pub key: char,
+    pub description: &'static str,
+}
+
+impl Action {
+    pub fn new(key: char, description: &'static str) -> Self {
+        Self { key, description }
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 pub type BoxWidget = Box<dyn Widget>;
 
@@ -94,6 +187,12 @@ pub trait Widget {
         0
     }
 
+    /// What can be done from here right now. Widgets that wrap others should
+    /// include those of whatever is currently receiving input.
+    fn actions(&self) -> Vec<Action> {
+        Vec::new()
+    }
+
     fn boxed(self) -> BoxWidget
     where
         Self: Sized + 'static,
@@ -1,30 +1,102 @@
 use eyre::Result;
-use ratatui::{buffer::Buffer, layout::Rect, Frame};
+use k8s_openapi::api::{apps::v1::Deployment, core::v1::Pod};
+use kube::api::ApiResource;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    Frame,
+};
 use tachyonfx::{fx, EffectTimer, Interpolation};
 use tracing::{metadata::LevelFilter, Level};
 
-use super::{debug::Debug, error::Error, pod, tunnel::Tunnel, view::View, Widget};
+use super::{
+    capacity::Capacity,
+    configmap,
+    copy::Copy,
+    create::Create,
+    cronjob, daemonset,
+    debug::Debug,
+    deployment,
+    deprecation::Deprecations,
+    drift::Drift,
+    dynamic,
+    error::Error,
+    event,
+    feed::Feed,
+    job,
+    loading::Loading,
+    namespace::{self, Picker},
+    node,
+    palette::{Command, Palette},
+    pod,
+    preflight::Preflight,
+    quit::Quit,
+    recent::{Jump, Recent},
+    secret, service,
+    share::Sessions,
+    startup::Startup,
+    statefulset,
+    status::Status,
+    switcher::{Choice, Switcher},
+    table::density,
+    theme,
+    tunnel::Tunnel,
+    view::View,
+    Action, BoxWidget, Widget,
+};
 use crate::{
-    events::{Broadcast, Event},
+    config,
+    events::{Broadcast, Event, Keypress},
     fx::Animated,
+    identity::preferences::{Storage, Visit},
+    policy,
+    resources::{
+        deprecation::{self, Tracked},
+        dynamic::Discovery,
+        store::prefetch::{self, Prefetched},
+    },
 };
 
 pub struct Apex {
+    client: kube::Client,
+    discovery: Discovery,
+
     view: View,
+    // The kind being listed, for views that apply to whatever is on screen.
+    current: ApiResource,
+    switcher: Option<Switcher>,
+    namespaces: Option<Picker>,
+    // What the lists are scoped to, `None` for everything.
+    namespace: Option<String>,
+    palette: Option<Palette>,
+    copy: Option<Copy>,
+    // Waiting to find out whether the top level screen exiting really means
+    // the session should end.
+    quit: Option<Quit>,
+    // Covers everything else while the cluster can't be used.
+    preflight: Preflight,
+    recent: Recent,
+    // Users can pick their own startup screen, which isn't known until their
+    // preferences have loaded. That only replaces what's on screen if they
+    // haven't started doing something else in the meantime.
+    pending: bool,
+    // Stores the views share for the rest of the session, see `prefetch`.
+    _prefetched: Prefetched,
+    // Which API versions objects use that are going away, for the session.
+    _deprecations: Tracked,
 }
 
 impl Apex {
-    pub fn new(client: kube::Client) -> Self {
+    pub fn new(client: kube::Client, preferences: Option<Storage>) -> Self {
+        // This comes first so that the startup screen uses the prefetched
+        // stores instead of starting its own.
+        let prefetched = prefetch::start(&client);
+        let deprecations = deprecation::start(&client);
+
         let mut widgets = vec![
-            Animated::builder()
-                .widget(pod::List::new(client).boxed())
-                .effect(fx::coalesce(EffectTimer::from_ms(
-                    500,
-                    Interpolation::CubicOut,
-                )))
-                .build()
-                .boxed(),
+            Self::animate(pod::List::new(client.clone(), None).boxed()),
             Tunnel::default().boxed(),
+            Status::default().boxed(),
         ];
 
         // TODO: This dependency on the crate is unfortunate, it should probably be
@@ -34,9 +106,336 @@ impl Apex {
             widgets.push(Debug::default().boxed());
         }
 
-        Self {
+        let mut apex = Self {
+            discovery: Discovery::new(client.clone()),
+            preflight: Preflight::new(client.clone()),
+            client,
             view: View::builder().widgets(widgets).show_all(true).build(),
+            current: ApiResource::erase::<Pod>(&()),
+            switcher: None,
+            namespaces: None,
+            namespace: None,
+            palette: None,
+            copy: None,
+            quit: None,
+            pending: preferences.is_some(),
+            recent: Recent::new(preferences),
+            _prefetched: prefetched,
+            _deprecations: deprecations,
+        };
+
+        let startup = Startup::configured();
+
+        if startup != Startup::default() {
+            apex.open(&startup);
         }
+
+        apex
+    }
+
+    fn open(&mut self, startup: &Startup) {
+        let (resource, list) = match startup {
+            Startup::Pods(ns) => (
+                ApiResource::erase::<Pod>(&()),
+                pod::List::new(self.client.clone(), ns.as_deref()).boxed(),
+            ),
+            Startup::Deployments(ns) => (
+                ApiResource::erase::<Deployment>(&()),
+                deployment::List::new(self.client.clone(), ns.as_deref()).boxed(),
+            ),
+            Startup::Capacity => {
+                self.view.push(Capacity::new(&self.client).boxed());
+
+                return;
+            }
+            Startup::Last => {
+                // Nothing has been visited yet for new users, or before the
+                // preferences have loaded, which leaves them on the pods.
+                if let Some(visit) = self.recent.last() {
+                    self.revisit(&visit);
+                }
+
+                return;
+            }
+        };
+
+        // See `show` for why the current list goes away first.
+        self.view.replace(0, Loading.boxed());
+        self.view.replace(0, Self::animate(list));
+        self.current = resource;
+        self.namespace = match startup {
+            Startup::Pods(ns) | Startup::Deployments(ns) => ns.clone(),
+            _ => None,
+        };
+    }
+
+    fn startup(&mut self) {
+        if !self.pending || !self.recent.is_loaded() {
+            return;
+        }
+
+        self.pending = false;
+
+        let configured = Startup::configured();
+        let startup = self.recent.startup().unwrap_or_else(|| configured.clone());
+
+        // The server's startup screen is already open, except for `last` which
+        // needs the preferences to know what that was.
+        if startup != configured || startup == Startup::Last {
+            self.open(&startup);
+        }
+    }
+
+    fn animate(widget: BoxWidget) -> BoxWidget {
+        Animated::builder()
+            .widget(widget)
+            .effect(fx::coalesce(EffectTimer::from_ms(
+                500,
+                Interpolation::CubicOut,
+            )))
+            .build()
+            .boxed()
+    }
+
+    // Kinds that have a dedicated view get that, everything else falls back to the
+    // generic YAML based one.
+    fn list(&self, resource: ApiResource) -> BoxWidget {
+        let namespace = self.namespace.as_deref();
+
+        match (resource.group.as_str(), resource.kind.as_str()) {
+            ("", "Pod") => pod::List::new(self.client.clone(), namespace).boxed(),
+            ("apps", "Deployment") => deployment::List::new(self.client.clone(), namespace).boxed(),
+            ("", "Node") => node::List::new(self.client.clone(), None).boxed(),
+            ("", "Service") => service::List::new(self.client.clone(), namespace).boxed(),
+            ("", "ConfigMap") => configmap::List::new(self.client.clone(), namespace).boxed(),
+            ("", "Secret") => secret::List::new(self.client.clone(), namespace).boxed(),
+            ("apps", "StatefulSet") => {
+                statefulset::List::new(self.client.clone(), namespace).boxed()
+            }
+            ("apps", "DaemonSet") => daemonset::List::new(self.client.clone(), namespace).boxed(),
+            ("batch", "Job") => job::List::new(self.client.clone(), namespace).boxed(),
+            ("batch", "CronJob") => cronjob::List::new(self.client.clone(), namespace).boxed(),
+            ("", "Event") => event::List::new(self.client.clone(), namespace).boxed(),
+            _ => dynamic::List::new(self.client.clone(), resource, namespace).boxed(),
+        }
+    }
+
+    fn show(&mut self, resource: &ApiResource) {
+        self.replace(resource);
+        self.recent.visit(resource.into());
+    }
+
+    fn replace(&mut self, resource: &ApiResource) {
+        // The current list remembers how its table was sorted and filtered as it
+        // goes away. That needs to happen before the new one looks it up, which
+        // might be for the same kind, and before it gets saved by `visit`.
+        self.view.replace(0, Loading.boxed());
+
+        self.current = resource.clone();
+
+        let list = Self::animate(self.list(resource.clone()));
+
+        self.view.replace(0, list);
+    }
+
+    // Objects go on top of the list of their kind, so that closing them ends up
+    // somewhere sensible. Opening the object is what records the visit.
+    fn revisit(&mut self, visit: &Visit) {
+        let resource = ApiResource::from(visit);
+
+        if visit.name.is_none() {
+            self.show(&resource);
+
+            return;
+        }
+
+        self.replace(&resource);
+        self.view
+            .push(Jump::new(self.client.clone(), visit).boxed());
+    }
+
+    // Favorite kinds are listed in the namespace they were pinned in, as long
+    // as it can still be browsed.
+    fn jump(&mut self, visit: &Visit, favorite: bool) {
+        if favorite && visit.name.is_none() {
+            if let Some(ns) = visit
+                .namespace
+                .as_deref()
+                .filter(|ns| !policy::scope().allows(ns))
+            {
+                self.view.push(
+                    Error::from(format!("{ns} isn't one of the namespaces you can browse")).boxed(),
+                );
+
+                return;
+            }
+
+            self.namespace.clone_from(&visit.namespace);
+        }
+
+        self.revisit(visit);
+    }
+
+    fn dispatch_switcher(
+        &mut self,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<Broadcast> {
+        let Some(switcher) = self.switcher.as_mut() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        // The switcher is modal, input shouldn't leak through to whatever is
+        // underneath it.
+        if event.key().is_none() && !matches!(event, Event::Paste(_)) {
+            return Ok(Broadcast::Ignored);
+        }
+
+        match switcher.dispatch(event, buffer, area)? {
+            Broadcast::Selected(idx) => {
+                match switcher.get(idx) {
+                    Some(Choice::Kind(resource)) => self.show(&resource),
+                    Some(Choice::View(view, resource)) => {
+                        // Views aren't kept in the recent strip, it only has
+                        // room for kinds and objects.
+                        self.view.replace(0, Loading.boxed());
+                        self.view.replace(
+                            0,
+                            Self::animate(
+                                dynamic::List::view(self.client.clone(), resource.clone(), view)
+                                    .boxed(),
+                            ),
+                        );
+                        self.current = resource;
+                    }
+                    None => {}
+                }
+
+                self.switcher = None;
+            }
+            Broadcast::Exited => {
+                self.switcher = None;
+            }
+            _ => {}
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn dispatch_namespaces(
+        &mut self,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<Broadcast> {
+        let Some(picker) = self.namespaces.as_mut() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        // Modal, the same as the switcher.
+        if event.key().is_none() && !matches!(event, Event::Paste(_)) {
+            return Ok(Broadcast::Ignored);
+        }
+
+        match picker.dispatch(event, buffer, area)? {
+            Broadcast::Selected(idx) => {
+                if let Some(choice) = picker.get(idx) {
+                    self.scope(&choice);
+                }
+
+                self.namespaces = None;
+            }
+            Broadcast::Exited => {
+                self.namespaces = None;
+            }
+            _ => {}
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    // Everything is listed again from scratch, the stores behind the current
+    // list are watching the namespace that was picked before.
+    fn scope(&mut self, choice: &namespace::Choice) {
+        self.namespace = choice.name();
+
+        self.view.replace(0, Loading.boxed());
+
+        let list = Self::animate(self.list(self.current.clone()));
+
+        self.view.replace(0, list);
+    }
+
+    fn dispatch_palette(
+        &mut self,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<Broadcast> {
+        let Some(palette) = self.palette.as_mut() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        // Modal, the same as the switcher.
+        if event.key().is_none() && !matches!(event, Event::Paste(_)) {
+            return Ok(Broadcast::Ignored);
+        }
+
+        match palette.dispatch(event, buffer, area)? {
+            Broadcast::Selected(idx) => {
+                let command = palette.get(idx);
+
+                self.palette = None;
+
+                match command {
+                    // Running an action is the same as pressing its key, which
+                    // might be handled anywhere from the apex down.
+                    Some(Command::Action(action)) => {
+                        return self.dispatch(
+                            &Event::from(action.key.to_string().as_bytes()),
+                            buffer,
+                            area,
+                        );
+                    }
+                    Some(Command::Kind(resource)) => self.show(&resource),
+                    None => {}
+                }
+            }
+            Broadcast::Exited => {
+                self.palette = None;
+            }
+            _ => {}
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    // Screens that can be opened from anywhere, on top of whatever is showing.
+    fn screen(&self, key: &Keypress) -> Option<BoxWidget> {
+        let screen = match key {
+            Keypress::Printable('c') => Capacity::new(&self.client).boxed(),
+            Keypress::Printable('F') => Feed::new(
+                self.client.clone(),
+                self.current.clone(),
+                self.namespace.as_deref(),
+            )
+            .boxed(),
+            Keypress::Printable('E') => {
+                event::List::new(self.client.clone(), self.namespace.as_deref()).boxed()
+            }
+            Keypress::Printable('M') if config::get().drift.enabled() => {
+                Drift::new(self.client.clone()).boxed()
+            }
+            Keypress::Printable('W') => {
+                Deprecations::new(self.client.clone(), self.discovery.kinds()).boxed()
+            }
+            Keypress::Printable('n') => Create::new(self.client.clone()).boxed(),
+            Keypress::Printable('w') => Sessions::new(self.client.clone()).boxed(),
+            _ => return None,
+        };
+
+        Some(screen)
     }
 }
 
@@ -46,10 +445,173 @@ impl Widget for Apex {
             self.view.push(Error::from(err.message()).boxed());
         }
 
-        self.view.dispatch(event, buffer, area)
+        if self.preflight.is_blocking() {
+            return self.preflight.dispatch(event, buffer, area);
+        }
+
+        if event.key().is_some() {
+            self.pending = false;
+        }
+
+        if let Some(quit) = self.quit.as_mut() {
+            match quit.dispatch(event, buffer, area)? {
+                Broadcast::Selected(_) => return Ok(Broadcast::Exited),
+                Broadcast::Exited => {
+                    self.quit = None;
+
+                    return Ok(Broadcast::Consumed);
+                }
+                Broadcast::Ignored if event.key().is_some() => self.quit = None,
+                Broadcast::Ignored => {}
+                _ => return Ok(Broadcast::Consumed),
+            }
+        }
+
+        if let Broadcast::Consumed = self.dispatch_switcher(event, buffer, area)? {
+            return Ok(Broadcast::Consumed);
+        }
+
+        if self.namespaces.is_some() {
+            return self.dispatch_namespaces(event, buffer, area);
+        }
+
+        if self.palette.is_some() {
+            return self.dispatch_palette(event, buffer, area);
+        }
+
+        if let Some(copy) = self.copy.as_mut() {
+            if event.key().is_none() {
+                return Ok(Broadcast::Ignored);
+            }
+
+            if let Broadcast::Exited = copy.dispatch(event, buffer, area)? {
+                self.copy = None;
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        let result = self.view.dispatch(event, buffer, area)?;
+
+        if let Broadcast::Exited = result {
+            self.quit = Quit::new(config::get().quit.confirm);
+
+            if self.quit.is_some() {
+                return Ok(Broadcast::Consumed);
+            }
+        }
+
+        if !matches!(result, Broadcast::Ignored) {
+            return Ok(result);
+        }
+
+        if let Some(screen) = event.key().and_then(|key| self.screen(key)) {
+            self.view.push(screen);
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        match event.key() {
+            Some(Keypress::Printable(':')) => {
+                self.switcher = Some(Switcher::new(self.discovery.kinds()));
+            }
+            Some(Keypress::Printable('N')) => {
+                self.namespaces = Some(Picker::new(self.client.clone()));
+            }
+            Some(Keypress::Control('k')) => {
+                self.palette = Some(Palette::new(self.actions(), self.discovery.kinds()));
+            }
+            Some(Keypress::Printable('v')) => {
+                self.copy = Some(Copy::new(buffer));
+            }
+            Some(Keypress::Printable('T')) => {
+                self.recent.set_theme(theme::current().next());
+            }
+            Some(Keypress::Printable('Z')) => {
+                self.recent.set_density(density::current().next());
+            }
+            Some(Keypress::Printable('*')) => {
+                let mut visit = Visit::from(&self.current);
+                visit.namespace.clone_from(&self.namespace);
+
+                self.recent.favorite(visit);
+            }
+            Some(Keypress::Printable(key @ '1'..='9')) => {
+                let idx = key.to_digit(10).unwrap_or_default() as usize;
+
+                let Some((visit, favorite)) = self.recent.get(idx - 1) else {
+                    return Ok(result);
+                };
+
+                self.jump(&visit, favorite);
+            }
+            _ => return Ok(result),
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    // Whatever is on screen gets first dibs on a key, so its actions go ahead
+    // of the ones that work from anywhere.
+    fn actions(&self) -> Vec<Action> {
+        let mut actions = self.view.actions();
+        actions.extend([
+            Action::new(':', "Switch resources"),
+            Action::new('c', "Show cluster capacity"),
+            Action::new('E', "Show every event as it happens"),
+            Action::new('F', "Show the change feed"),
+            Action::new('n', "Create a resource from a template"),
+            Action::new('W', "Show deprecated API versions in use"),
+            Action::new('N', "Pick the namespace to list"),
+            Action::new('v', "Copy from the screen"),
+            Action::new('T', "Switch to the next theme"),
+            Action::new('Z', "Switch to the next table density"),
+            Action::new('*', "Pin or unpin the list as a favorite"),
+            Action::new('w', "Watch a shared session"),
+        ]);
+
+        if config::get().drift.enabled() {
+            actions.push(Action::new('M', "Compare against the declared manifests"));
+        }
+
+        actions
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        self.view.draw(frame, area)
+        self.startup();
+
+        let [main, recent] =
+            Layout::vertical([Constraint::Fill(0), self.recent.placement().vertical]).areas(area);
+
+        self.view.draw(frame, main)?;
+        self.recent.draw(frame, recent)?;
+
+        if let Some(switcher) = self.switcher.as_mut() {
+            switcher.draw(frame, area)?;
+        }
+
+        if let Some(picker) = self.namespaces.as_mut() {
+            picker.draw(frame, area)?;
+        }
+
+        if let Some(palette) = self.palette.as_mut() {
+            palette.draw(frame, area)?;
+        }
+
+        if let Some(copy) = self.copy.as_mut() {
+            copy.draw(frame, area)?;
+        }
+
+        if self.quit.as_ref().is_some_and(Quit::expired) {
+            self.quit = None;
+        }
+
+        if let Some(quit) = self.quit.as_mut() {
+            quit.draw(frame, area)?;
+        }
+
+        self.preflight.draw(frame, area)?;
+
+        Ok(())
     }
 }
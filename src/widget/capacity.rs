@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::{Node, Pod};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::{centered_with, nav::exit_keys, table, Widget, WIDGET_VIEWS};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        capacity::{self, Pool},
+        cost::{self, Totals},
+        store::Store,
+    },
+};
+
+struct Pools {
+    nodes: Arc<Store<Node>>,
+    pods: Arc<Store<Pod>>,
+}
+
+/// Requests and limits across every pod, grouped by node pool and compared
+/// against what the nodes in that pool have allocatable. Answers whether
+/// there's room for something new without adding it all up by hand. When
+/// there are node prices in the config, the monthly totals go underneath.
+pub struct Capacity {
+    nodes: Arc<Store<Node>>,
+    pods: Arc<Store<Pod>>,
+
+    table: table::Table<Pools>,
+}
+
+impl Capacity {
+    pub fn new(client: &kube::Client) -> Self {
+        WIDGET_VIEWS.node.capacity.inc();
+
+        let (nodes, _) = Store::new(client.clone());
+        let (pods, _) = Store::new(client.clone());
+
+        Self {
+            nodes: nodes.clone(),
+            pods: pods.clone(),
+            table: table::Table::builder()
+                .items(Pools { nodes, pods })
+                .border(false)
+                .build(),
+        }
+    }
+
+    fn costs(&self) -> Vec<Line<'static>> {
+        let totals = Totals::new(&self.nodes.snapshot(), &self.pods.snapshot());
+
+        let pct = if totals.nodes > 0.0 {
+            totals.requested / totals.nodes * 100.0
+        } else {
+            0.0
+        };
+
+        let mut lines = vec![Line::from(format!(
+            "Nodes: {}/mo, Requested: {}/mo ({pct:.0}%), Idle: {}/mo",
+            cost::format(totals.nodes),
+            cost::format(totals.requested),
+            cost::format(totals.idle()),
+        ))];
+
+        if totals.unpriced > 0 {
+            lines.push(
+                Line::from(format!(
+                    "{} nodes have no price and aren't included",
+                    totals.unpriced
+                ))
+                .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        }
+
+        lines
+    }
+}
+
+impl table::Items for Pools {
+    type Item = Pool;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        capacity::pools(&self.nodes.snapshot(), &self.pods.snapshot())
+    }
+}
+
+impl Widget for Capacity {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(key, exit_keys!()) {
+            return Ok(Broadcast::Exited);
+        }
+
+        // There's nothing to do with a pool once it is selected.
+        self.table.dispatch(event, buffer, area)?;
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = centered_with(area, Constraint::Percentage(90), Constraint::Percentage(80));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Capacity")
+            .title_bottom(
+                Line::from("requests and limits are a percentage of allocatable, esc: close")
+                    .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        if !crate::config::get().costs.enabled() {
+            return self.table.draw(frame, inner);
+        }
+
+        let [table, _, totals] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(2),
+        ])
+        .areas(inner);
+
+        frame.render_widget(Paragraph::new(self.costs()), totals);
+
+        self.table.draw(frame, table)
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
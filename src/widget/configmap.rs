@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::ConfigMap;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use tokio::sync::oneshot;
+
+use super::{
+    object::{self, Object},
+    propagate,
+    revert::Revert,
+    table,
+    tabs::Tab,
+    yaml::Yaml,
+    Action, BoxWidget, Widget, WIDGET_VIEWS,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        configmap::{ConfigMapExt, Entry},
+        store::Store,
+    },
+};
+
+pub type List = object::List<ConfigMap>;
+
+/// A `ConfigMap` one key at a time, so that files in it are read as the files
+/// they are instead of as strings inside of YAML.
+impl Object for ConfigMap {
+    type Items = Arc<Store<ConfigMap>>;
+
+    fn items(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Self::Items, oneshot::Receiver<()>) {
+        Store::namespaced(client, namespace)
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.get(idx, filter)
+    }
+
+    fn tabs(client: &kube::Client, _: &Self::Items, cm: &Arc<Self>) -> Vec<Tab> {
+        let data = {
+            let cm = cm.clone();
+
+            Tab::builder()
+                .name("Data".to_string())
+                .constructor(Box::new(move || Keys::new(cm.entries()).boxed()))
+                .build()
+        };
+
+        vec![
+            data,
+            Yaml::tab("Overview".to_string(), client.clone(), cm.clone()),
+        ]
+    }
+
+    fn popup(
+        client: &kube::Client,
+        _: &Self::Items,
+        cm: &Arc<Self>,
+        key: &Keypress,
+    ) -> Option<BoxWidget> {
+        match key {
+            Keypress::Printable('R') => Some(Revert::config_map(client.clone(), cm).boxed()),
+            _ => None,
+        }
+    }
+
+    fn actions(_: &Self::Items, _: &Self) -> Vec<Action> {
+        vec![Action::new('R', "Revert to a saved copy")]
+    }
+}
+
+#[derive(Clone)]
+struct Entries(Vec<Entry>);
+
+impl table::Items for Entries {
+    type Item = Entry;
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        self.0
+            .iter()
+            .filter(|entry| {
+                filter
+                    .as_ref()
+                    .map_or(true, |f| entry.key.contains(f.as_str()))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Every key on the left, with the selected one's value on the right. `enter`
+/// moves over to the value to scroll and search through it, `esc` comes back.
+struct Keys {
+    entries: Entries,
+    table: table::Table<Entries>,
+
+    // The value being shown, along with which entry it is for.
+    value: Option<(usize, Yaml)>,
+    focused: bool,
+}
+
+impl Keys {
+    fn new(entries: Vec<Entry>) -> Self {
+        WIDGET_VIEWS.configmap.data.inc();
+
+        let entries = Entries(entries);
+
+        Self {
+            table: table::Table::builder().items(entries.clone()).build(),
+            entries,
+            value: None,
+            focused: false,
+        }
+    }
+
+    fn selected(&self) -> Option<(usize, &Entry)> {
+        let idx = self.table.selected()?;
+
+        self.entries.0.get(idx).map(|entry| (idx, entry))
+    }
+
+    // Only rebuilt when the selection moves, highlighting is done on every draw
+    // as it is.
+    fn update(&mut self) {
+        let Some((idx, entry)) = self.selected() else {
+            self.value = None;
+
+            return;
+        };
+
+        if self.value.as_ref().is_some_and(|(shown, _)| *shown == idx) {
+            return;
+        }
+
+        let yaml = Yaml::value(&entry.key, entry.value.clone());
+
+        self.value = Some((idx, yaml));
+    }
+}
+
+impl Widget for Keys {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if self.focused {
+            if let Some((_, value)) = self.value.as_mut() {
+                propagate!(value.dispatch(event, buffer, area));
+            }
+
+            if matches!(event.key(), Some(Keypress::Escape)) {
+                self.focused = false;
+
+                return Ok(Broadcast::Consumed);
+            }
+
+            return Ok(Broadcast::Ignored);
+        }
+
+        match self.table.dispatch(event, buffer, area)? {
+            Broadcast::Selected(_) => {
+                self.focused = self.value.is_some();
+
+                Ok(Broadcast::Consumed)
+            }
+            Broadcast::Exited => Ok(Broadcast::Ignored),
+            result => Ok(result),
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        match self.value.as_ref() {
+            Some((_, value)) if self.focused => value.actions(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.update();
+
+        let [keys, value] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Fill(1)]).areas(area);
+
+        self.table.draw(frame, keys)?;
+
+        let title = self
+            .selected()
+            .map(|(_, entry)| entry.key.clone())
+            .unwrap_or_default();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_bottom(
+                Line::from(if self.focused {
+                    "esc: back to the keys"
+                } else {
+                    "enter: scroll and search the value"
+                })
+                .style(Style::default().fg(tailwind::GRAY.c400)),
+            )
+            .border_style(if self.focused {
+                Style::default().fg(tailwind::INDIGO.c300)
+            } else {
+                Style::default()
+            });
+        let inner = block.inner(value);
+
+        frame.render_widget(block, value);
+
+        match self.value.as_mut() {
+            Some((_, yaml)) => yaml.draw(frame, inner)?,
+            None => frame.render_widget(
+                Paragraph::new("no keys").style(Style::default().fg(tailwind::GRAY.c400)),
+                inner,
+            ),
+        }
+
+        Ok(())
+    }
+}
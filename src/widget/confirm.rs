@@ -0,0 +1,132 @@
+use eyre::Result;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use super::{
+    input::{Content, Text},
+    nav::exit_keys,
+    theme, Widget,
+};
+use crate::events::{Broadcast, Event, Keypress};
+
+enum Mode {
+    Quick,
+    Typed(String, Text),
+}
+
+/// Asks before doing something that can't be undone. Quick confirmation takes
+/// a single `y`, typed confirmation only goes through once `name` has been
+/// typed out exactly, for when a slip of the finger would be expensive.
+/// Confirming, the same as selecting the only option there is, is
+/// `Broadcast::Selected(0)`. Backing out is `Broadcast::Exited`.
+pub struct Confirm {
+    message: String,
+    mode: Mode,
+}
+
+impl Confirm {
+    pub fn quick(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            mode: Mode::Quick,
+        }
+    }
+
+    pub fn typed(message: impl Into<String>, name: impl Into<String>) -> Self {
+        let name = name.into();
+        let content = Content::default();
+
+        let expected = name.clone();
+        let typed = content.clone();
+
+        let input = Text::builder()
+            .title("Type the name to confirm")
+            .content(content)
+            .feedback(Box::new(move || {
+                let typed = typed.try_borrow().ok()?;
+                let typed = typed.as_deref()?.trim();
+
+                (!typed.is_empty()).then(|| {
+                    if typed == expected {
+                        ("matches".to_string(), true)
+                    } else {
+                        ("doesn't match".to_string(), false)
+                    }
+                })
+            }))
+            .build();
+
+        Self {
+            message: message.into(),
+            mode: Mode::Typed(name, input),
+        }
+    }
+
+    pub fn help(&self) -> &'static str {
+        match self.mode {
+            Mode::Quick => "y: confirm, esc: cancel",
+            Mode::Typed(..) => "enter: confirm, esc: cancel",
+        }
+    }
+}
+
+impl Widget for Confirm {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        match &mut self.mode {
+            Mode::Quick => match event.key() {
+                Some(Keypress::Printable('y')) => Ok(Broadcast::Selected(0)),
+                Some(exit_keys!()) => Ok(Broadcast::Exited),
+                _ => Ok(Broadcast::Consumed),
+            },
+            Mode::Typed(name, input) => match event.key() {
+                // Anything other than the exact name does nothing, there's no
+                // shortcut past typing it.
+                Some(Keypress::Enter) => {
+                    let content = input.content();
+                    let typed = content.try_borrow()?;
+
+                    if typed.as_deref().map(str::trim) == Some(name.as_str()) {
+                        Ok(Broadcast::Selected(0))
+                    } else {
+                        Ok(Broadcast::Consumed)
+                    }
+                }
+                Some(exit_keys!()) => Ok(Broadcast::Exited),
+                _ => {
+                    input.dispatch(event, buffer, area)?;
+
+                    Ok(Broadcast::Consumed)
+                }
+            },
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [warning, input_area] = Layout::vertical([
+            Constraint::Fill(0),
+            Constraint::Length(match self.mode {
+                Mode::Quick => 0,
+                Mode::Typed(..) => 3,
+            }),
+        ])
+        .areas(area);
+
+        frame.render_widget(
+            Paragraph::new(self.message.as_str())
+                .style(Style::default().fg(theme::unhealthy()))
+                .wrap(Wrap { trim: false }),
+            warning,
+        );
+
+        if let Mode::Typed(_, input) = &mut self.mode {
+            input.draw(frame, input_area)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use std::cell::RefCell;
+
+use base64::prelude::*;
+use eyre::Result;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, IntCounter};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::Line,
+    Frame,
+};
+
+use super::{
+    nav::{exit_keys, move_cursor, Movement},
+    Widget,
+};
+use crate::events::{Broadcast, Event, Keypress};
+
+lazy_static! {
+    static ref COPIES: IntCounter = register_int_counter!(
+        "copy_selections_total",
+        "Number of selections copied to the clipboard with copy mode"
+    )
+    .unwrap();
+}
+
+// Like links, copying is something the dashboard has to write out to the
+// terminal itself. Widgets leave what they want copied here and it gets picked
+// up once the frame has been drawn.
+thread_local! {
+    static PENDING: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Ask for `text` to be put on the client's clipboard.
+pub fn set(text: String) {
+    PENDING.with_borrow_mut(|pending| *pending = Some(text));
+}
+
+/// Take whatever is waiting to be copied as an OSC 52 escape. Terminals that
+/// don't support it, or have it turned off, ignore the escape.
+pub fn take() -> Option<String> {
+    PENDING
+        .with_borrow_mut(Option::take)
+        .map(|text| format!("\x1b]52;c;{}\x07", BASE64_STANDARD.encode(text)))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Lines,
+    Block,
+}
+
+/// Select part of the screen with the keyboard and copy it. The screen is
+/// frozen as it was when copy mode started so that the selection doesn't move
+/// out from under the cursor as things update. This is for terminals where
+/// mouse selection doesn't work because of mouse reporting.
+pub struct Copy {
+    screen: Buffer,
+
+    cursor: Position,
+    anchor: Option<(Position, Shape)>,
+}
+
+impl Copy {
+    pub fn new(screen: &Buffer) -> Self {
+        Self {
+            screen: screen.clone(),
+            cursor: screen.area.as_position(),
+            anchor: None,
+        }
+    }
+
+    fn select(&mut self, shape: Shape) {
+        self.anchor = match self.anchor {
+            Some((_, current)) if current == shape => None,
+            Some((anchor, _)) => Some((anchor, shape)),
+            None => Some((self.cursor, shape)),
+        };
+    }
+
+    // The rows, and columns within each, that are selected. Without an
+    // anchor, only the line under the cursor is.
+    fn selection(&self) -> (u16, u16, impl Fn(u16) -> (u16, u16) + '_) {
+        let area = self.screen.area;
+        let (anchor, shape) = self.anchor.unwrap_or((self.cursor, Shape::Lines));
+
+        let (top, bottom) = if anchor.y <= self.cursor.y {
+            (anchor, self.cursor)
+        } else {
+            (self.cursor, anchor)
+        };
+
+        let columns = move |y: u16| match shape {
+            Shape::Block => (anchor.x.min(self.cursor.x), anchor.x.max(self.cursor.x) + 1),
+            Shape::Lines if top.y == bottom.y && self.anchor.is_some() => {
+                (top.x.min(bottom.x), top.x.max(bottom.x) + 1)
+            }
+            Shape::Lines if self.anchor.is_none() => (area.left(), area.right()),
+            Shape::Lines if y == top.y => (top.x, area.right()),
+            Shape::Lines if y == bottom.y => (area.left(), bottom.x + 1),
+            Shape::Lines => (area.left(), area.right()),
+        };
+
+        (top.y, bottom.y, columns)
+    }
+
+    fn text(&self) -> String {
+        let (top, bottom, columns) = self.selection();
+
+        (top..=bottom)
+            .map(|y| {
+                let (left, right) = columns(y);
+
+                (left..right)
+                    .filter_map(|x| self.screen.cell((x, y)))
+                    .map(ratatui::buffer::Cell::symbol)
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn help(&self) -> &'static str {
+        match self.anchor {
+            None => " copy: v: select lines, V: select block, y: copy line, esc: cancel ",
+            Some(_) => " copy: v/V: change selection, y: copy, esc: cancel ",
+        }
+    }
+}
+
+impl Widget for Copy {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, _: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        let area = self.screen.area;
+
+        match key {
+            exit_keys!() => return Ok(Broadcast::Exited),
+            Keypress::Printable('v') => self.select(Shape::Lines),
+            Keypress::Printable('V') => self.select(Shape::Block),
+            Keypress::Printable('y') | Keypress::Enter => {
+                set(self.text());
+                COPIES.inc();
+
+                return Ok(Broadcast::Exited);
+            }
+            key => match move_cursor(key, area) {
+                Some(Movement::X(x)) => {
+                    self.cursor.x = self
+                        .cursor
+                        .x
+                        .saturating_add_signed(x)
+                        .clamp(area.left(), area.right().saturating_sub(1));
+                }
+                Some(Movement::Y(y)) => {
+                    self.cursor.y = self
+                        .cursor
+                        .y
+                        .saturating_add_signed(y)
+                        .clamp(area.top(), area.bottom().saturating_sub(1));
+                }
+                None => {}
+            },
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let buffer = frame.buffer_mut();
+
+        // The screen may have been resized since copy mode started, anything
+        // outside of it is gone.
+        let area = area.intersection(self.screen.area);
+        self.cursor.x = self.cursor.x.min(area.right().saturating_sub(1));
+        self.cursor.y = self.cursor.y.min(area.bottom().saturating_sub(1));
+
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if let (Some(cell), Some(saved)) =
+                    (buffer.cell_mut((x, y)), self.screen.cell((x, y)))
+                {
+                    *cell = saved.clone();
+                }
+            }
+        }
+
+        let (top, bottom, columns) = self.selection();
+
+        for y in top..=bottom.min(area.bottom().saturating_sub(1)) {
+            let (left, right) = columns(y);
+
+            buffer.set_style(
+                Rect::new(left, y, right.saturating_sub(left), 1).intersection(area),
+                Style::default().add_modifier(Modifier::REVERSED),
+            );
+        }
+
+        if let Some(cell) = buffer.cell_mut(self.cursor) {
+            cell.set_style(Style::default().bg(tailwind::INDIGO.c500));
+        }
+
+        let help = Line::from(self.help()).style(
+            Style::default()
+                .fg(tailwind::GRAY.c100)
+                .bg(tailwind::INDIGO.c800),
+        );
+        let width = u16::try_from(help.width())
+            .unwrap_or(u16::MAX)
+            .min(area.width);
+
+        frame.render_widget(
+            help,
+            Rect::new(
+                area.right().saturating_sub(width),
+                area.bottom().saturating_sub(1),
+                width,
+                1,
+            ),
+        );
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
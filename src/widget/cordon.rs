@@ -0,0 +1,174 @@
+use eyre::Result;
+use k8s_openapi::api::core::v1::Node;
+use kube::api::Api;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{centered_with, confirm::Confirm, theme, Widget};
+use crate::events::{Broadcast, Event};
+
+enum Stage {
+    Confirm(Confirm),
+    Running(JoinHandle<Result<(), kube::Error>>),
+    Done(Result<String, String>),
+}
+
+/// Marks a node as unschedulable, or schedulable again when it already is,
+/// after asking first. Pods already on the node stay where they are, `Drain`
+/// is for moving them off.
+pub struct Cordon {
+    client: kube::Client,
+    node: String,
+    unschedulable: bool,
+
+    stage: Stage,
+}
+
+impl Cordon {
+    /// `cordoned` is whether the node is cordoned now, which decides which way
+    /// this goes.
+    pub fn new(client: kube::Client, node: String, cordoned: bool) -> Self {
+        let message = if cordoned {
+            format!("Uncordon {node}? New pods can be scheduled on it again.")
+        } else {
+            format!("Cordon {node}? New pods won't be scheduled on it, running ones stay.")
+        };
+
+        Self {
+            client,
+            node,
+            unschedulable: !cordoned,
+            stage: Stage::Confirm(Confirm::quick(message)),
+        }
+    }
+
+    fn run(&self) -> Stage {
+        let api = Api::<Node>::all(self.client.clone());
+        let node = self.node.clone();
+        let unschedulable = self.unschedulable;
+
+        Stage::Running(tokio::spawn(async move {
+            if unschedulable {
+                api.cordon(&node).await?;
+            } else {
+                api.uncordon(&node).await?;
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn verb(&self) -> &'static str {
+        if self.unschedulable {
+            "cordon"
+        } else {
+            "uncordon"
+        }
+    }
+
+    fn poll(&mut self) {
+        let Stage::Running(task) = &mut self.stage else {
+            return;
+        };
+
+        if !task.is_finished() {
+            return;
+        }
+
+        let result = match futures::executor::block_on(task) {
+            Ok(Ok(())) => Ok(format!("{}ed {}", self.verb(), self.node)),
+            Ok(Err(kube::Error::Api(resp))) => Err(resp.message),
+            Ok(Err(err)) => Err(err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+
+        self.stage = Stage::Done(result);
+    }
+}
+
+impl Widget for Cordon {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if event.key().is_none() {
+            return Ok(Broadcast::Ignored);
+        }
+
+        match &mut self.stage {
+            Stage::Confirm(confirm) => match confirm.dispatch(event, buffer, area)? {
+                Broadcast::Selected(_) => self.stage = self.run(),
+                Broadcast::Exited => return Ok(Broadcast::Exited),
+                _ => {}
+            },
+            Stage::Running(_) => {}
+            Stage::Done(_) => return Ok(Broadcast::Exited),
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        let area = centered_with(area, Constraint::Length(60), Constraint::Length(5));
+
+        frame.render_widget(Clear, area);
+
+        let help = match &self.stage {
+            Stage::Confirm(confirm) => confirm.help(),
+            Stage::Running(_) => "",
+            Stage::Done(_) => "press any key to close",
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(if self.unschedulable {
+                "Cordon"
+            } else {
+                "Uncordon"
+            })
+            .title_bottom(Line::from(help).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Confirm(confirm) => confirm.draw(frame, inner)?,
+            Stage::Running(_) => {
+                frame.render_widget(Paragraph::new(format!("{}ing...", self.verb())), inner);
+            }
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Cordon {
+    fn drop(&mut self) {
+        if let Stage::Running(task) = &self.stage {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use eyre::Result;
+use kube::{
+    api::{DynamicObject, PostParams},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use strum::VariantArray;
+use tokio::task::JoinHandle;
+
+use super::{centered, input::form::Form, nav::exit_keys, table, theme, yaml::Yaml, Widget};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    policy::{self, Action},
+    resources::{template::Template, DynamicClient},
+};
+
+lazy_static! {
+    static ref CREATED: IntCounterVec = register_int_counter_vec!(
+        "resources_created_total",
+        "Number of resources created from a template",
+        &["kind"]
+    )
+    .unwrap();
+}
+
+struct Templates;
+
+impl table::Items for Templates {
+    type Item = Template;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        Template::VARIANTS.to_vec()
+    }
+}
+
+enum Stage {
+    Pick(table::Table<Templates>),
+    Form(Template, Form),
+    Preview {
+        template: Template,
+        form: Form,
+        manifest: DynamicObject,
+        yaml: Yaml,
+    },
+    Applying(JoinHandle<Result<DynamicObject>>),
+    Done(Result<String, String>),
+}
+
+/// Walks through creating an object from scratch: pick a template, fill out
+/// its form, check the generated manifest and then create it.
+pub struct Create {
+    client: kube::Client,
+    stage: Stage,
+}
+
+impl Create {
+    pub fn new(client: kube::Client) -> Self {
+        Self {
+            client,
+            stage: Stage::Pick(table::Table::builder().items(Templates).build()),
+        }
+    }
+
+    fn form(template: Template) -> Form {
+        Form::new(
+            template
+                .fields()
+                .iter()
+                .map(|field| (field.label, field.default)),
+        )
+    }
+
+    // Checked before the preview so that there's no getting someone's hopes up.
+    fn allowed(manifest: DynamicObject) -> Result<DynamicObject> {
+        let kind = manifest
+            .types
+            .as_ref()
+            .map_or("", |types| types.kind.as_str());
+
+        policy::check(Action::Create, kind, &manifest.name_any())?;
+
+        Ok(manifest)
+    }
+
+    fn apply(&self, manifest: DynamicObject) -> JoinHandle<Result<DynamicObject>> {
+        let client = self.client.clone();
+
+        tokio::spawn(async move {
+            let api = manifest.dynamic(client).await?;
+
+            Ok(api.create(&PostParams::default(), &manifest).await?)
+        })
+    }
+
+    fn advance(
+        &mut self,
+        stage: Stage,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<(Stage, Broadcast)> {
+        let key = event.key();
+
+        let stage = match stage {
+            Stage::Pick(mut table) => match table.dispatch(event, buffer, area)? {
+                Broadcast::Selected(idx) => {
+                    let template = Template::VARIANTS[idx];
+
+                    Stage::Form(template, Self::form(template))
+                }
+                _ if matches!(key, Some(exit_keys!())) => {
+                    return Ok((Stage::Pick(table), Broadcast::Exited))
+                }
+                _ => Stage::Pick(table),
+            },
+            Stage::Form(template, mut form) => match form.dispatch(event, buffer, area)? {
+                Broadcast::Selected(_) => {
+                    match template.manifest(&form.values()).and_then(Self::allowed) {
+                        Ok(manifest) => Stage::Preview {
+                            yaml: Yaml::with_kind(
+                                manifest
+                                    .types
+                                    .as_ref()
+                                    .map_or("", |types| types.kind.as_str()),
+                                &manifest,
+                            ),
+                            template,
+                            form,
+                            manifest,
+                        },
+                        Err(err) => Stage::Done(Err(err.to_string())),
+                    }
+                }
+                Broadcast::Exited => return Ok((Stage::Form(template, form), Broadcast::Exited)),
+                _ => Stage::Form(template, form),
+            },
+            Stage::Preview {
+                template,
+                form,
+                manifest,
+                mut yaml,
+            } => match key {
+                Some(Keypress::Enter) => Stage::Applying(self.apply(manifest)),
+                Some(exit_keys!()) => Stage::Form(template, form),
+                _ => {
+                    yaml.dispatch(event, buffer, area)?;
+
+                    Stage::Preview {
+                        template,
+                        form,
+                        manifest,
+                        yaml,
+                    }
+                }
+            },
+            Stage::Applying(task) => Stage::Applying(task),
+            Stage::Done(result) => {
+                if key.is_some() {
+                    return Ok((Stage::Done(result), Broadcast::Exited));
+                }
+
+                Stage::Done(result)
+            }
+        };
+
+        Ok((stage, Broadcast::Consumed))
+    }
+
+    fn finished(task: &mut JoinHandle<Result<DynamicObject>>) -> Stage {
+        let result = futures::executor::block_on(async move { task.await? });
+
+        Stage::Done(match result {
+            Ok(obj) => {
+                let kind = obj.types.as_ref().map_or("", |types| types.kind.as_str());
+
+                CREATED.with_label_values(&[kind]).inc();
+
+                Ok(format!("created {} {}", kind, obj.name_any()))
+            }
+            Err(err) => Err(match err.downcast_ref::<kube::Error>() {
+                Some(kube::Error::Api(resp)) => resp.message.clone(),
+                _ => err.to_string(),
+            }),
+        })
+    }
+
+    fn title(&self) -> String {
+        match &self.stage {
+            Stage::Form(template, _) | Stage::Preview { template, .. } => {
+                format!("Create {}", template.name())
+            }
+            Stage::Pick(_) | Stage::Applying(_) | Stage::Done(_) => "Create".to_string(),
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self.stage {
+            Stage::Pick(_) => "enter: select, esc: cancel",
+            Stage::Form(..) => "tab: next field, enter on the last field: preview, esc: cancel",
+            Stage::Preview { .. } => "enter: create, esc: edit",
+            Stage::Applying(_) => "",
+            Stage::Done(_) => "press any key to close",
+        }
+    }
+}
+
+impl Widget for Create {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        // This is modal, nothing else should be getting key presses while it is
+        // open.
+        if event.key().is_none() && !matches!(event, Event::Paste(_)) {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        match self.advance(stage, event, buffer, area) {
+            Ok((stage, result)) => {
+                self.stage = stage;
+
+                Ok(result)
+            }
+            Err(err) => {
+                self.stage = Stage::Done(Err(err.to_string()));
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Stage::Applying(task) = &mut self.stage {
+            if task.is_finished() {
+                self.stage = Self::finished(task);
+            }
+        }
+
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title())
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Pick(table) => table.draw(frame, inner)?,
+            Stage::Form(_, form) => form.draw(frame, inner)?,
+            Stage::Preview { yaml, .. } => yaml.draw(frame, inner)?,
+            Stage::Applying(_) => frame.render_widget(Paragraph::new("creating..."), inner),
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Create {
+    fn drop(&mut self) {
+        if let Stage::Applying(task) = &self.stage {
+            task.abort();
+        }
+    }
+}
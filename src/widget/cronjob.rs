@@ -0,0 +1,105 @@
+pub mod trigger;
+
+use std::sync::Arc;
+
+use eyre::eyre;
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use kube::ResourceExt;
+use tokio::sync::oneshot;
+
+use self::trigger::Trigger;
+use super::{
+    object::{self, Object},
+    table::{self, Items},
+    tabs::Tab,
+    yaml::Yaml,
+    Action, BoxWidget, Widget, WIDGET_VIEWS,
+};
+use crate::{
+    events::Keypress,
+    resources::{
+        cronjob::{CronJobExt, Owned},
+        store::Store,
+    },
+};
+
+pub type List = object::List<CronJob>;
+
+/// A cronjob with the jobs it has created, newest first. `t` runs it right
+/// away instead of waiting for the next time it is scheduled.
+impl Object for CronJob {
+    type Items = Arc<Store<CronJob>>;
+
+    fn items(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Self::Items, oneshot::Receiver<()>) {
+        Store::namespaced(client, namespace)
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.get(idx, filter)
+    }
+
+    fn tabs(client: &kube::Client, _: &Self::Items, cronjob: &Arc<Self>) -> Vec<Tab> {
+        let jobs = {
+            let client = client.clone();
+            let cronjob = cronjob.clone();
+
+            Tab::builder()
+                .name("Jobs".to_string())
+                .constructor(Box::new(move || {
+                    WIDGET_VIEWS.cronjob.jobs.inc();
+
+                    let (jobs, _) =
+                        Store::namespaced(client.clone(), cronjob.namespace().as_deref());
+                    let owned = Arc::new(Owned {
+                        cronjob: cronjob.clone(),
+                        jobs,
+                    });
+
+                    let client = client.clone();
+
+                    table::Filtered::builder()
+                        .table(table::Table::builder().items(owned.clone()).build())
+                        .constructor(Box::new(move |idx, filter| {
+                            let job = owned
+                                .items(filter)
+                                .get(idx)
+                                .cloned()
+                                .ok_or_else(|| eyre!("job not found"))?;
+
+                            Ok(object::detail::<Job>(&client, owned.jobs.clone(), job))
+                        }))
+                        .build()
+                        .boxed()
+                }))
+                .build()
+        };
+
+        vec![
+            Yaml::tab("Overview".to_string(), client.clone(), cronjob.clone()),
+            jobs,
+        ]
+    }
+
+    fn status(_: &Self::Items, cronjob: &Self) -> Option<String> {
+        cronjob.suspended().then(|| "suspended".to_string())
+    }
+
+    fn popup(
+        client: &kube::Client,
+        _: &Self::Items,
+        cronjob: &Arc<Self>,
+        key: &Keypress,
+    ) -> Option<BoxWidget> {
+        match key {
+            Keypress::Printable('t') => Some(Trigger::new(client.clone(), cronjob.clone()).boxed()),
+            _ => None,
+        }
+    }
+
+    fn actions(_: &Self::Items, _: &Self) -> Vec<Action> {
+        vec![Action::new('t', "Trigger a run now")]
+    }
+}
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use kube::{
+    api::{Api, PostParams},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    events::{Broadcast, Event},
+    resources::cronjob::CronJobExt,
+    widget::{centered_with, confirm::Confirm, theme, Widget, WIDGET_VIEWS},
+};
+
+lazy_static! {
+    static ref CRONJOB_TRIGGERS: IntCounterVec = register_int_counter_vec!(
+        "cronjob_triggers_total",
+        "Number of jobs created from a cronjob by hand, by whether it succeeded",
+        &["result"]
+    )
+    .unwrap();
+}
+
+enum Stage {
+    Confirm(Confirm),
+    Running(JoinHandle<Result<Job, kube::Error>>),
+    Done(Result<String, String>),
+}
+
+/// Creates a job from a cronjob's template right away, after asking first.
+/// This works the same whether or not the cronjob is suspended, which is a
+/// common way to only ever run one by hand.
+pub struct Trigger {
+    client: kube::Client,
+    cronjob: Arc<CronJob>,
+
+    stage: Stage,
+}
+
+impl Trigger {
+    pub fn new(client: kube::Client, cronjob: Arc<CronJob>) -> Self {
+        WIDGET_VIEWS.cronjob.trigger.inc();
+
+        let message = format!(
+            "Run {} now? This creates a job from its template, the same as when it is scheduled.",
+            cronjob.name_any()
+        );
+
+        Self {
+            client,
+            cronjob,
+            stage: Stage::Confirm(Confirm::quick(message)),
+        }
+    }
+
+    fn run(&self) -> Stage {
+        let api = Api::<Job>::namespaced(
+            self.client.clone(),
+            &self.cronjob.namespace().unwrap_or_default(),
+        );
+        let job = self.cronjob.trigger();
+
+        Stage::Running(tokio::spawn(async move {
+            api.create(&PostParams::default(), &job).await
+        }))
+    }
+
+    fn poll(&mut self) {
+        let Stage::Running(task) = &mut self.stage else {
+            return;
+        };
+
+        if !task.is_finished() {
+            return;
+        }
+
+        let result = match futures::executor::block_on(task) {
+            Ok(Ok(job)) => Ok(format!("created job {}", job.name_any())),
+            Ok(Err(kube::Error::Api(resp))) => Err(resp.message),
+            Ok(Err(err)) => Err(err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+
+        CRONJOB_TRIGGERS
+            .with_label_values(&[if result.is_ok() { "ok" } else { "error" }])
+            .inc();
+
+        self.stage = Stage::Done(result);
+    }
+}
+
+impl Widget for Trigger {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if event.key().is_none() {
+            return Ok(Broadcast::Ignored);
+        }
+
+        match &mut self.stage {
+            Stage::Confirm(confirm) => match confirm.dispatch(event, buffer, area)? {
+                Broadcast::Selected(_) => self.stage = self.run(),
+                Broadcast::Exited => return Ok(Broadcast::Exited),
+                _ => {}
+            },
+            Stage::Running(_) => {}
+            Stage::Done(_) => return Ok(Broadcast::Exited),
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        let area = centered_with(area, Constraint::Length(60), Constraint::Length(6));
+
+        frame.render_widget(Clear, area);
+
+        let help = match &self.stage {
+            Stage::Confirm(confirm) => confirm.help(),
+            Stage::Running(_) => "",
+            Stage::Done(_) => "press any key to close",
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Trigger")
+            .title_bottom(Line::from(help).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Confirm(confirm) => confirm.draw(frame, inner)?,
+            Stage::Running(_) => {
+                frame.render_widget(Paragraph::new("creating job..."), inner);
+            }
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Trigger {
+    fn drop(&mut self) {
+        if let Stage::Running(task) = &self.stage {
+            task.abort();
+        }
+    }
+}
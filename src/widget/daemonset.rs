@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::apps::v1::DaemonSet;
+use kube::ResourceExt;
+use tokio::sync::oneshot;
+
+use super::{
+    object::{self, Object},
+    table,
+    tabs::Tab,
+    yaml::Yaml,
+    Widget, WIDGET_VIEWS,
+};
+use crate::resources::{
+    daemonset::{DaemonSets, Placements},
+    store::Store,
+};
+
+pub type List = object::List<DaemonSet>;
+
+/// A daemonset with every node in the cluster. The "Nodes" tab shows which pod
+/// is running on each one, or why there isn't one: the node being excluded
+/// by the template's selector, affinity or a taint it doesn't tolerate, or the
+/// scheduler not having placed the pod yet.
+impl Object for DaemonSet {
+    type Items = Arc<DaemonSets>;
+
+    fn items(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Self::Items, oneshot::Receiver<()>) {
+        // Pods and nodes are only needed for health and the "Nodes" tab, they
+        // don't hold up showing the list.
+        let (daemonsets, is_ready) = Store::namespaced(client.clone(), namespace);
+        let (pods, _) = Store::new(client.clone());
+        let (nodes, _) = Store::new(client);
+
+        (
+            Arc::new(DaemonSets {
+                daemonsets,
+                pods,
+                nodes,
+            }),
+            is_ready,
+        )
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.daemonsets.get(idx, filter)
+    }
+
+    fn tabs(client: &kube::Client, items: &Self::Items, daemonset: &Arc<Self>) -> Vec<Tab> {
+        let nodes = {
+            let items = items.clone();
+            let uid = daemonset.uid().unwrap_or_default();
+
+            Tab::builder()
+                .name("Nodes".to_string())
+                .constructor(Box::new(move || {
+                    WIDGET_VIEWS.daemonset.nodes.inc();
+
+                    table::Table::builder()
+                        .items(Placements {
+                            daemonsets: items.clone(),
+                            uid: uid.clone(),
+                        })
+                        .build()
+                        .boxed()
+                }))
+                .build()
+        };
+
+        let mut tabs = vec![
+            Yaml::tab("Overview".to_string(), client.clone(), daemonset.clone()),
+            nodes,
+        ];
+
+        tabs.extend(object::pods(
+            client,
+            daemonset.namespace().unwrap_or_default(),
+            daemonset.spec.as_ref().map(|spec| spec.selector.clone()),
+        ));
+
+        tabs
+    }
+}
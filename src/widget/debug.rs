@@ -1,5 +1,6 @@
 use std::{
     iter::once,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -13,9 +14,16 @@ use ratatui::{
 use ringbuffer::{AllocRingBuffer, RingBuffer};
 
 use super::{Placement, Widget};
+use crate::{
+    resources::{quantity, store::memory},
+    usage::{self, Usage},
+};
 
 static RANGE: usize = 30;
 
+// Only the most expensive resources are shown, the rest are in the metrics.
+static TOP: usize = 5;
+
 pub struct Fps {
     last: Instant,
     period: AllocRingBuffer<Duration>,
@@ -55,8 +63,112 @@ impl Widget for Fps {
 
     fn placement(&self) -> Placement {
         Placement {
+            horizontal: Constraint::Length(10),
             vertical: Constraint::Length(2),
-            ..Default::default()
+        }
+    }
+}
+
+/// What this session has been asking of the API server, to find out which
+/// views are the expensive ones.
+pub struct Requests {
+    usage: Arc<Usage>,
+}
+
+impl Widget for Requests {
+    #[allow(clippy::cast_precision_loss)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let total = self.usage.total();
+
+        let lines = once(format!(
+            "API: {} req, {} ev, {}",
+            total.requests,
+            total.events,
+            quantity::bytes(total.bytes as f64)
+        ))
+        .chain(
+            self.usage
+                .resources()
+                .into_iter()
+                .sorted_by_key(|(_, counts)| std::cmp::Reverse(counts.bytes))
+                .take(TOP)
+                .map(|(resource, counts)| {
+                    format!(
+                        "{resource}: {}/{}/{}",
+                        counts.requests,
+                        counts.events,
+                        quantity::bytes(counts.bytes as f64)
+                    )
+                }),
+        )
+        .join("\n");
+
+        frame.render_widget(Paragraph::new(lines), area);
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn placement(&self) -> Placement {
+        Placement {
+            horizontal: Constraint::Length(40),
+            vertical: Constraint::Length(TOP as u16 + 1),
+        }
+    }
+}
+
+/// How much the session's stores are holding on to, biggest first. Trimmed
+/// stores are listed again as soon as something looks at them.
+pub struct Stores {
+    memory: memory::Watching,
+}
+
+impl Widget for Stores {
+    #[allow(clippy::cast_precision_loss)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let total = self.memory.total();
+        let stores = self.memory.stores();
+
+        let lines = once(format!(
+            "Stores: {}, {} obj, {}{}",
+            stores.len(),
+            total.objects,
+            quantity::bytes(total.bytes as f64),
+            memory::limit().map_or(String::new(), |limit| format!(
+                " of {}",
+                quantity::bytes(limit as f64)
+            )),
+        ))
+        .chain(
+            stores
+                .into_iter()
+                .sorted_by_key(|store| std::cmp::Reverse(store.footprint.bytes))
+                .take(TOP)
+                .map(|store| {
+                    if store.trimmed {
+                        format!("{}: trimmed", store.resource)
+                    } else {
+                        format!(
+                            "{}: {}/{}",
+                            store.resource,
+                            store.footprint.objects,
+                            quantity::bytes(store.footprint.bytes as f64)
+                        )
+                    }
+                }),
+        )
+        .join("\n");
+
+        frame.render_widget(Paragraph::new(lines), area);
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn placement(&self) -> Placement {
+        Placement {
+            horizontal: Constraint::Length(40),
+            vertical: Constraint::Length(TOP as u16 + 1),
         }
     }
 }
@@ -67,18 +179,36 @@ pub struct Debug {
 
 impl Default for Debug {
     fn default() -> Self {
-        Self {
-            widgets: vec![Box::new(Fps::default())],
+        let mut widgets: Vec<Box<dyn Widget>> = vec![Box::new(Fps::default())];
+
+        if let Some(usage) = usage::current() {
+            widgets.push(Box::new(Requests { usage }));
         }
+
+        widgets.push(Box::new(Stores {
+            memory: memory::current().watch(),
+        }));
+
+        Self { widgets }
     }
 }
 
 impl Widget for Debug {
     #[allow(clippy::cast_possible_truncation)]
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let width = self
+            .widgets
+            .iter()
+            .filter_map(|widget| match widget.placement().horizontal {
+                Constraint::Length(width) => Some(width),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(10);
+
         let [_, area, _] = Layout::horizontal([
             Constraint::Fill(0),
-            Constraint::Length(10),
+            Constraint::Length(width),
             Constraint::Length(3),
         ])
         .areas(area);
@@ -0,0 +1,410 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use kube::{
+    api::{Api, ApiResource, DynamicObject},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{confirm::Confirm, nav::exit_keys, table, theme, Widget};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    policy::{self, Action},
+    resources::cascade::{self, Cascade, Dependent, Propagation},
+};
+
+lazy_static! {
+    static ref DELETED: IntCounterVec = register_int_counter_vec!(
+        "resources_deleted_total",
+        "Number of resources deleted, by kind and whether it succeeded",
+        &["kind", "result"]
+    )
+    .unwrap();
+}
+
+// Namespaced kinds that take more with them than it looks like they do.
+static HIGH_BLAST_RADIUS: &[&str] = &["PersistentVolumeClaim"];
+
+struct Dependents(Vec<Dependent>);
+
+impl table::Items for Dependents {
+    type Item = Dependent;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.clone()
+    }
+}
+
+struct Preview {
+    unchecked: Vec<String>,
+    table: Option<table::Table<Dependents>>,
+}
+
+enum Stage {
+    Loading(JoinHandle<Cascade>, Confirm),
+    Confirm(Confirm),
+    Deleting(JoinHandle<Result<()>>),
+    Done(Result<String, String>),
+}
+
+/// Delete an object after asking first. Cluster-scoped objects, such as nodes,
+/// namespaces and CRDs, take everything in or on them along, so those need to
+/// have their name typed out. Anything that `policy` doesn't allow never gets
+/// as far as asking. Namespaced objects get a preview of what the garbage
+/// collector would take along with them first, and a choice of how.
+pub struct Delete {
+    client: kube::Client,
+    object: Arc<DynamicObject>,
+    resource: ApiResource,
+
+    propagation: Propagation,
+    preview: Option<Preview>,
+    stage: Stage,
+}
+
+impl Delete {
+    pub fn new(client: kube::Client, object: Arc<DynamicObject>, resource: ApiResource) -> Self {
+        let name = object.name_any();
+
+        let stage = match policy::check(Action::Delete, &resource.kind, &name) {
+            Ok(())
+                if object.namespace().is_none()
+                    || HIGH_BLAST_RADIUS.contains(&resource.kind.as_str()) =>
+            {
+                Stage::Confirm(Confirm::typed(
+                    format!(
+                        "Deleting {} {name} can't be undone, type its name below to confirm.",
+                        resource.kind
+                    ),
+                    name,
+                ))
+            }
+            Ok(()) => Stage::Confirm(Confirm::quick(format!(
+                "Deleting {} {name} can't be undone, press y to go ahead.",
+                resource.kind
+            ))),
+            Err(err) => Stage::Done(Err(err.to_string())),
+        };
+
+        // Cluster-scoped objects don't own anything that this knows how to look
+        // for, namespaces take everything in them anyway.
+        let stage = match (stage, object.namespace(), object.uid()) {
+            (Stage::Confirm(confirm), Some(ns), Some(uid)) => {
+                let client = client.clone();
+
+                Stage::Loading(
+                    tokio::spawn(async move { cascade::dependents(client, &ns, &uid).await }),
+                    confirm,
+                )
+            }
+            (stage, ..) => stage,
+        };
+
+        Self {
+            client,
+            object,
+            resource,
+            propagation: Propagation::default(),
+            preview: None,
+            stage,
+        }
+    }
+
+    fn has_dependents(&self) -> bool {
+        self.preview
+            .as_ref()
+            .is_some_and(|preview| preview.table.is_some())
+    }
+
+    fn delete(&self) -> Stage {
+        let api: Api<DynamicObject> = match self.object.namespace() {
+            Some(ns) => Api::namespaced_with(self.client.clone(), &ns, &self.resource),
+            None => Api::all_with(self.client.clone(), &self.resource),
+        };
+        let name = self.object.name_any();
+        let kind = self.resource.kind.clone();
+        let params = self.propagation.params();
+
+        Stage::Deleting(tokio::spawn(async move {
+            let result = api.delete(&name, &params).await;
+
+            DELETED
+                .with_label_values(&[
+                    kind.as_str(),
+                    if result.is_ok() { "success" } else { "failure" },
+                ])
+                .inc();
+
+            result?;
+
+            Ok(())
+        }))
+    }
+
+    fn advance(
+        &mut self,
+        stage: Stage,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<(Stage, Broadcast)> {
+        let stage = match stage {
+            Stage::Loading(task, confirm) if matches!(event.key(), Some(exit_keys!())) => {
+                return Ok((Stage::Loading(task, confirm), Broadcast::Exited))
+            }
+            Stage::Loading(task, confirm) => Stage::Loading(task, confirm),
+            Stage::Confirm(confirm)
+                if self.has_dependents()
+                    && matches!(event.key(), Some(Keypress::HorizontalTab)) =>
+            {
+                self.propagation = self.propagation.next();
+
+                Stage::Confirm(confirm)
+            }
+            Stage::Confirm(confirm)
+                if matches!(event.key(), Some(Keypress::CursorUp | Keypress::CursorDown)) =>
+            {
+                if let Some(table) = self
+                    .preview
+                    .as_mut()
+                    .and_then(|preview| preview.table.as_mut())
+                {
+                    table.dispatch(event, buffer, area)?;
+                }
+
+                Stage::Confirm(confirm)
+            }
+            Stage::Confirm(mut confirm) => match confirm.dispatch(event, buffer, area)? {
+                Broadcast::Selected(_) => self.delete(),
+                Broadcast::Exited => return Ok((Stage::Confirm(confirm), Broadcast::Exited)),
+                _ => Stage::Confirm(confirm),
+            },
+            Stage::Deleting(task) => Stage::Deleting(task),
+            Stage::Done(result) => {
+                if event.key().is_some() {
+                    return Ok((Stage::Done(result), Broadcast::Exited));
+                }
+
+                Stage::Done(result)
+            }
+        };
+
+        Ok((stage, Broadcast::Consumed))
+    }
+
+    fn finished(&self, task: &mut JoinHandle<Result<()>>) -> Stage {
+        let result = futures::executor::block_on(async move { task.await? });
+
+        Stage::Done(match result {
+            Ok(()) => Ok(format!(
+                "deleted {} {}, it may take a while to go away",
+                self.resource.kind,
+                self.object.name_any()
+            )),
+            Err(err) => Err(match err.downcast_ref::<kube::Error>() {
+                Some(kube::Error::Api(resp)) => resp.message.clone(),
+                _ => err.to_string(),
+            }),
+        })
+    }
+
+    fn loaded(&mut self, task: JoinHandle<Cascade>, confirm: Confirm) -> Stage {
+        match futures::executor::block_on(task) {
+            Ok(cascade) => {
+                self.preview = Some(Preview {
+                    unchecked: cascade.unchecked,
+                    table: (!cascade.dependents.is_empty()).then(|| {
+                        table::Table::builder()
+                            .items(Dependents(cascade.dependents))
+                            .border(false)
+                            .build()
+                    }),
+                });
+
+                Stage::Confirm(confirm)
+            }
+            Err(err) => Stage::Done(Err(err.to_string())),
+        }
+    }
+
+    fn summary(&self, preview: &Preview) -> String {
+        let mut lines = Vec::new();
+
+        if preview.table.is_some() {
+            lines.push(format!(
+                "Propagation: {}, tab to change ({})",
+                self.propagation,
+                self.propagation.description()
+            ));
+        } else {
+            lines.push("Nothing is owned by it.".to_string());
+        }
+
+        if !preview.unchecked.is_empty() {
+            lines.push(format!(
+                "Couldn't check {}, there may be more.",
+                preview.unchecked.join(", ")
+            ));
+        }
+
+        lines.join("\n")
+    }
+
+    fn help(&self) -> &'static str {
+        match &self.stage {
+            Stage::Loading(..) => "esc: cancel",
+            Stage::Confirm(confirm) => confirm.help(),
+            Stage::Deleting(_) => "",
+            Stage::Done(_) => "press any key to close",
+        }
+    }
+}
+
+impl Widget for Delete {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        // This is modal, nothing else should be getting key presses while it is
+        // open.
+        if event.key().is_none() && !matches!(event, Event::Paste(_)) {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        match self.advance(stage, event, buffer, area) {
+            Ok((stage, result)) => {
+                self.stage = stage;
+
+                Ok(result)
+            }
+            Err(err) => {
+                self.stage = Stage::Done(Err(err.to_string()));
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        self.stage = match stage {
+            Stage::Loading(task, confirm) if task.is_finished() => self.loaded(task, confirm),
+            Stage::Deleting(mut task) if task.is_finished() => self.finished(&mut task),
+            stage => stage,
+        };
+
+        // There's a list to make room for when the object owns anything.
+        let margin = if self.has_dependents() { 10 } else { 20 };
+
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Percentage(margin),
+            Constraint::Percentage(100 - 2 * margin),
+            Constraint::Percentage(margin),
+        ])
+        .areas(area);
+
+        let [_, area, _] = Layout::vertical([
+            Constraint::Percentage(margin),
+            Constraint::Percentage(100 - 2 * margin),
+            Constraint::Percentage(margin),
+        ])
+        .areas(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "Delete {} {}",
+                self.resource.kind,
+                self.object.name_any()
+            ))
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        let summary = self.preview.as_ref().map(|preview| self.summary(preview));
+
+        match &mut self.stage {
+            Stage::Loading(..) => {
+                frame.render_widget(Paragraph::new("looking for what it owns..."), inner);
+            }
+            Stage::Confirm(confirm) => {
+                let table = self
+                    .preview
+                    .as_mut()
+                    .and_then(|preview| preview.table.as_mut());
+
+                let [warning, summary_area, list] = Layout::vertical(if table.is_some() {
+                    [
+                        Constraint::Length(6),
+                        Constraint::Length(3),
+                        Constraint::Fill(0),
+                    ]
+                } else {
+                    [
+                        Constraint::Fill(0),
+                        Constraint::Length(3),
+                        Constraint::Length(0),
+                    ]
+                })
+                .areas(inner);
+
+                confirm.draw(frame, warning)?;
+
+                if let Some(summary) = summary {
+                    frame.render_widget(
+                        Paragraph::new(summary).wrap(Wrap { trim: false }),
+                        summary_area,
+                    );
+                }
+
+                if let Some(table) = table {
+                    table.draw(frame, list)?;
+                }
+            }
+            Stage::Deleting(_) => frame.render_widget(Paragraph::new("deleting..."), inner),
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Delete {
+    fn drop(&mut self) {
+        match &self.stage {
+            Stage::Loading(task, _) => task.abort(),
+            Stage::Deleting(task) => task.abort(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use eyre::{eyre, Result};
+use k8s_openapi::api::apps::v1::Deployment;
+use kube::ResourceExt;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use tokio::sync::oneshot;
+
+use super::{
+    loading::Loading,
+    object::{self, Object},
+    propagate,
+    table::{self, Items},
+    tabs::Tab,
+    view::View,
+    yaml::Yaml,
+    Action, Placement, Widget, WIDGET_VIEWS,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        deployment::{Attention, Deployments, Health},
+        store::Store,
+    },
+};
+
+// How many deployments needing attention are shown above the list, the rest
+// are a `!` away.
+static ATTENTION_ROWS: usize = 3;
+
+pub struct List {
+    client: kube::Client,
+    stuck: Arc<Attention>,
+
+    view: View,
+    attention: Option<table::Filtered>,
+    is_ready: oneshot::Receiver<()>,
+}
+
+impl List {
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(skip(client), fields(activity = "deployment.list"))]
+    pub fn new(client: kube::Client, namespace: Option<&str>) -> Self {
+        WIDGET_VIEWS.deployment.list.inc();
+
+        let (items, is_ready) = Deployment::items(client.clone(), namespace);
+
+        let table = table::Filtered::builder()
+            .table(
+                table::Table::builder()
+                    .title(namespace.map_or_else(
+                        || "Deployments".to_string(),
+                        |ns| format!("Deployments ({ns})"),
+                    ))
+                    .items(items.clone())
+                    .remember("deployments.apps")
+                    .build(),
+            )
+            .constructor({
+                let client = client.clone();
+                let items = items.clone();
+
+                Box::new(move |idx, filter| {
+                    let deployment = Deployment::get(&items, idx, filter)
+                        .ok_or_else(|| eyre!("deployment not found"))?;
+
+                    Ok(object::detail(&client, items.clone(), deployment))
+                })
+            })
+            .build();
+
+        Self {
+            client,
+            stuck: Arc::new(Attention(items)),
+            view: View::builder()
+                .widgets(vec![table.boxed(), Loading.boxed()])
+                .build(),
+            attention: None,
+            is_ready,
+        }
+    }
+
+    fn attention(&self) -> table::Filtered {
+        table::Filtered::builder()
+            .table(
+                table::Table::builder()
+                    .title("Attention")
+                    .items(self.stuck.clone())
+                    .build(),
+            )
+            .constructor({
+                let client = self.client.clone();
+                let stuck = self.stuck.clone();
+
+                Box::new(move |idx, filter| {
+                    let deployment = stuck
+                        .items(filter)
+                        .into_iter()
+                        .nth(idx)
+                        .ok_or_else(|| eyre!("deployment not found"))?
+                        .deployment;
+
+                    Ok(object::detail(&client, stuck.0.clone(), deployment))
+                })
+            })
+            .build()
+    }
+
+    fn draw_attention(&self, frame: &mut Frame, area: Rect) -> Rect {
+        let stuck = self.stuck.items(None);
+
+        if stuck.is_empty() {
+            return area;
+        }
+
+        let shown = stuck.len().min(ATTENTION_ROWS);
+
+        let [top, area] = Layout::vertical([
+            Constraint::Length(u16::try_from(shown).unwrap_or_default() + 2),
+            Constraint::Fill(0),
+        ])
+        .areas(area);
+
+        let style = table::RowStyle::default();
+
+        let lines: Vec<_> = stuck
+            .iter()
+            .take(shown)
+            .map(|item| {
+                let color = match item.health {
+                    Health::Failed(_) => style.unhealthy,
+                    _ => style.normal,
+                };
+
+                Line::from(vec![
+                    Span::from(item.health.indicator().label(&item.health)).style(color),
+                    Span::from(format!(
+                        "  {}/{}",
+                        item.deployment.namespace().unwrap_or_default(),
+                        item.deployment.name_any()
+                    )),
+                ])
+            })
+            .collect();
+
+        let more = stuck.len() - shown;
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Attention ({})", stuck.len()))
+            .title_bottom(
+                Line::from(if more > 0 {
+                    format!("{more} more, !: review all")
+                } else {
+                    "!: review".to_string()
+                })
+                .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+
+        frame.render_widget(Paragraph::new(lines).block(block), top);
+
+        area
+    }
+}
+
+impl Widget for List {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(attention) = self.attention.as_mut() {
+            match attention.dispatch(event, buffer, area)? {
+                Broadcast::Exited => self.attention = None,
+                Broadcast::Ignored if matches!(event.key(), Some(Keypress::Escape)) => {
+                    self.attention = None;
+                }
+                _ => {}
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        propagate!(self.view.dispatch(event, buffer, area));
+
+        match event.key() {
+            Some(Keypress::Escape) => Ok(Broadcast::Exited),
+            Some(Keypress::Printable('!')) => {
+                if !self.stuck.items(None).is_empty() {
+                    self.attention = Some(self.attention());
+                }
+
+                Ok(Broadcast::Consumed)
+            }
+            _ => Ok(Broadcast::Ignored),
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        if let Some(attention) = self.attention.as_ref() {
+            return attention.actions();
+        }
+
+        let mut actions = self.view.actions();
+
+        if !self.stuck.items(None).is_empty() {
+            actions.push(Action::new('!', "Show deployments that need attention"));
+        }
+
+        actions
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Ok(()) = self.is_ready.try_recv() {
+            self.view.pop();
+        }
+
+        if let Some(attention) = self.attention.as_mut() {
+            return attention.draw(frame, area);
+        }
+
+        let area = self.draw_attention(frame, area);
+
+        self.view.draw(frame, area)
+    }
+
+    fn placement(&self) -> Placement {
+        Placement {
+            horizontal: Constraint::Fill(0),
+            vertical: Constraint::Fill(0),
+        }
+    }
+}
+
+impl Object for Deployment {
+    type Items = Arc<Deployments>;
+
+    fn items(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Self::Items, oneshot::Receiver<()>) {
+        // The pods are only needed for health, there's no reason to wait for them
+        // before showing the deployments.
+        let (deployments, is_ready) = Store::namespaced(client.clone(), namespace);
+        let (pods, _) = Store::new(client.clone());
+        let nodes = crate::config::get()
+            .costs
+            .enabled()
+            .then(|| Store::new(client).0);
+
+        (
+            Arc::new(Deployments {
+                deployments,
+                pods,
+                nodes,
+            }),
+            is_ready,
+        )
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.deployments.get(idx, filter)
+    }
+
+    fn tabs(client: &kube::Client, _: &Self::Items, deployment: &Arc<Self>) -> Vec<Tab> {
+        let mut tabs = vec![Yaml::tab(
+            "Overview".to_string(),
+            client.clone(),
+            deployment.clone(),
+        )];
+
+        tabs.extend(object::pods(
+            client,
+            deployment.namespace().unwrap_or_default(),
+            deployment.spec.as_ref().map(|spec| spec.selector.clone()),
+        ));
+
+        tabs
+    }
+}
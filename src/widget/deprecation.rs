@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use eyre::Result;
+use kube::api::ApiResource;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use tokio::{sync::watch, task::JoinHandle};
+
+use super::{
+    centered_with,
+    nav::{exit_keys, move_cursor, Movement},
+    theme::{self, Indicator},
+    Widget, WIDGET_VIEWS_VEC,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    policy,
+    resources::deprecation::{self, Finding, Scan, Severity},
+};
+
+/// Objects using API versions that are deprecated, or already removed, as of
+/// the version the API server is running. Everything the session has looked at
+/// is included, `s` goes through the rest of the cluster as well. Meant for
+/// getting ready for an upgrade, so whatever goes away soonest is first.
+pub struct Deprecations {
+    client: kube::Client,
+    kinds: watch::Receiver<Vec<ApiResource>>,
+
+    task: Option<JoinHandle<Result<Scan>>>,
+    scan: Option<Result<Scan, String>>,
+    scroll: u16,
+}
+
+impl Deprecations {
+    pub fn new(client: kube::Client, kinds: watch::Receiver<Vec<ApiResource>>) -> Self {
+        WIDGET_VIEWS_VEC
+            .with_label_values(&["deprecation", "report"])
+            .inc();
+
+        Self {
+            client,
+            kinds,
+            task: None,
+            scan: None,
+            scroll: 0,
+        }
+    }
+
+    fn run(&mut self) {
+        let client = self.client.clone();
+        let scope = policy::scope();
+        let kinds = self.kinds.borrow().clone();
+
+        self.scan = None;
+        self.task = Some(tokio::spawn(async move {
+            deprecation::scan(client, scope, kinds).await
+        }));
+    }
+
+    fn poll(&mut self) {
+        if !self.task.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+
+        let Some(task) = self.task.take() else {
+            return;
+        };
+
+        self.scan = Some(
+            futures::executor::block_on(async move { task.await? }).map_err(|err| err.to_string()),
+        );
+    }
+
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings: BTreeMap<_, _> = deprecation::seen()
+            .into_iter()
+            .map(|finding| (finding.id(), finding))
+            .collect();
+
+        if let Some(Ok(scan)) = self.scan.as_ref() {
+            findings.extend(
+                scan.findings
+                    .iter()
+                    .map(|finding| (finding.id(), finding.clone())),
+            );
+        }
+
+        let mut findings: Vec<_> = findings.into_values().collect();
+        findings.sort();
+
+        findings
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let detail = Style::default().fg(tailwind::GRAY.c400);
+        let minor = deprecation::minor();
+
+        let mut lines = vec![Line::from(Span::styled(
+            minor.map_or_else(
+                || "the API server's version isn't known yet".to_string(),
+                |minor| format!("the API server is running 1.{minor}"),
+            ),
+            detail,
+        ))];
+
+        let scanned = match self.scan.as_ref() {
+            _ if self.task.is_some() => "going through the cluster...".to_string(),
+            None => "only objects that have been looked at, s: go through the cluster".to_string(),
+            Some(Ok(scan)) if scan.skipped.is_empty() => "the whole cluster".to_string(),
+            Some(Ok(scan)) => format!(
+                "the whole cluster, except for {} kinds that couldn't be listed: {}",
+                scan.skipped.len(),
+                scan.skipped
+                    .iter()
+                    .map(|(kind, _)| kind.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Some(Err(err)) => format!("unable to go through the cluster: {err}"),
+        };
+
+        lines.push(Line::from(Span::styled(scanned, detail)));
+        lines.push(Line::default());
+
+        let findings = self.findings();
+
+        if findings.is_empty() {
+            lines.push(Line::from(Span::styled(
+                Indicator::Healthy.label("nothing is using an API version that is going away"),
+                Style::default().fg(theme::healthy()),
+            )));
+
+            return lines;
+        }
+
+        lines.extend(findings.iter().map(|finding| {
+            let (indicator, style) = match finding.api.severity(minor) {
+                Severity::Removed => (
+                    Indicator::Unhealthy,
+                    Style::default().fg(theme::unhealthy()),
+                ),
+                Severity::Deprecated => {
+                    (Indicator::Warning, Style::default().fg(theme::unhealthy()))
+                }
+                Severity::Upcoming => (Indicator::Warning, Style::default()),
+            };
+
+            Line::from(vec![
+                Span::styled(format!("{} ", indicator.glyph()), style),
+                Span::raw(format!("{}  ", finding.id())),
+                Span::styled(finding.message(minor), style),
+            ])
+        }));
+
+        lines
+    }
+}
+
+impl Widget for Deprecations {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match key {
+            exit_keys!() => return Ok(Broadcast::Exited),
+            Keypress::Printable('s') if self.task.is_none() => self.run(),
+            key => {
+                if let Some(Movement::Y(y)) = move_cursor(key, area) {
+                    self.scroll = self.scroll.saturating_add_signed(y);
+                }
+            }
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        let area = centered_with(area, Constraint::Percentage(90), Constraint::Percentage(80));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Deprecated API versions")
+            .title_bottom(
+                Line::from(if self.task.is_some() {
+                    "esc: close"
+                } else {
+                    "s: go through the cluster, esc: close"
+                })
+                .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        frame.render_widget(Paragraph::new(self.lines()).scroll((self.scroll, 0)), inner);
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Deprecations {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use futures::future::join_all;
+use itertools::Itertools;
+use k8s_openapi::api::{
+    core::v1::{Node, Pod},
+    policy::v1::PodDisruptionBudget,
+};
+use kube::{
+    api::{Api, EvictParams, ListParams},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{centered_with, nav::exit_keys, table, theme, Widget};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::node::drain::{self, Outcome, Plan},
+};
+
+lazy_static! {
+    static ref EVICTIONS: IntCounterVec = register_int_counter_vec!(
+        "drain_evictions_total",
+        "Number of pods evicted while draining a node, by whether it succeeded",
+        &["result"]
+    )
+    .unwrap();
+}
+
+struct Plans(Vec<Plan>);
+
+impl table::Items for Plans {
+    type Item = Plan;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.clone()
+    }
+}
+
+enum Stage {
+    Loading(JoinHandle<Result<Vec<Plan>>>),
+    Preview(Vec<Plan>, table::Table<Plans>),
+    Draining(JoinHandle<Result<String>>),
+    Done(Result<String, String>),
+}
+
+/// Shows what draining a node would do before doing any of it: which pods get
+/// evicted, which are held up by a disruption budget and which stay put
+/// because they belong to a daemonset or the kubelet. Approving cordons the
+/// node and evicts everything that can be.
+pub struct Drain {
+    client: kube::Client,
+    node: String,
+
+    stage: Stage,
+}
+
+impl Drain {
+    pub fn new(client: kube::Client, node: String) -> Self {
+        let stage = Stage::Loading(tokio::spawn(preview(client.clone(), node.clone())));
+
+        Self {
+            client,
+            node,
+            stage,
+        }
+    }
+
+    fn drain(&self, plans: Vec<Plan>) -> Stage {
+        let client = self.client.clone();
+        let node = self.node.clone();
+
+        Stage::Draining(tokio::spawn(async move {
+            Api::<Node>::all(client.clone()).cordon(&node).await?;
+
+            let pods = plans
+                .into_iter()
+                .filter(|plan| plan.outcome == Outcome::Evict)
+                .map(|plan| plan.pod);
+
+            let results = join_all(pods.map(|pod| {
+                let client = client.clone();
+
+                async move {
+                    let result =
+                        Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default())
+                            .evict(&pod.name_any(), &EvictParams::default())
+                            .await;
+
+                    EVICTIONS
+                        .with_label_values(&[if result.is_ok() { "success" } else { "failure" }])
+                        .inc();
+
+                    result.map_err(|err| match err {
+                        kube::Error::Api(resp) => format!("{}: {}", pod.name_any(), resp.message),
+                        err => format!("{}: {err}", pod.name_any()),
+                    })
+                }
+            }))
+            .await;
+
+            let (evicted, failed): (Vec<_>, Vec<_>) = results.into_iter().partition_result();
+
+            let mut msg = format!("cordoned {node} and evicted {} pods", evicted.len());
+
+            if !failed.is_empty() {
+                msg.push_str(&format!(
+                    ", {} could not be evicted:\n{}",
+                    failed.len(),
+                    failed.join("\n")
+                ));
+            }
+
+            Ok(msg)
+        }))
+    }
+
+    fn advance(
+        &mut self,
+        stage: Stage,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<(Stage, Broadcast)> {
+        let key = event.key();
+
+        let stage = match stage {
+            Stage::Loading(task) if matches!(key, Some(exit_keys!())) => {
+                return Ok((Stage::Loading(task), Broadcast::Exited))
+            }
+            Stage::Loading(task) => Stage::Loading(task),
+            Stage::Preview(plans, mut table) => match key {
+                Some(Keypress::Printable('y')) => self.drain(plans),
+                Some(exit_keys!()) => return Ok((Stage::Preview(plans, table), Broadcast::Exited)),
+                _ => {
+                    table.dispatch(event, buffer, area)?;
+
+                    Stage::Preview(plans, table)
+                }
+            },
+            Stage::Draining(task) => Stage::Draining(task),
+            Stage::Done(result) => {
+                if key.is_some() {
+                    return Ok((Stage::Done(result), Broadcast::Exited));
+                }
+
+                Stage::Done(result)
+            }
+        };
+
+        Ok((stage, Broadcast::Consumed))
+    }
+
+    fn summary(plans: &[Plan]) -> String {
+        let count = |f: fn(&Outcome) -> bool| plans.iter().filter(|plan| f(&plan.outcome)).count();
+
+        format!(
+            "{} would be evicted, {} are blocked by a disruption budget and {} stay",
+            count(|outcome| *outcome == Outcome::Evict),
+            count(|outcome| matches!(outcome, Outcome::Blocked(_))),
+            count(|outcome| matches!(outcome, Outcome::Stays(_))),
+        )
+    }
+
+    fn help(&self) -> &'static str {
+        match self.stage {
+            Stage::Loading(_) => "esc: cancel",
+            Stage::Preview(..) => "y: cordon and drain, esc: abort",
+            Stage::Draining(_) => "",
+            Stage::Done(_) => "press any key to close",
+        }
+    }
+
+    fn error(err: &eyre::Report) -> String {
+        match err.downcast_ref::<kube::Error>() {
+            Some(kube::Error::Api(resp)) => resp.message.clone(),
+            _ => err.to_string(),
+        }
+    }
+
+    fn poll(&mut self) {
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        self.stage = match stage {
+            Stage::Loading(task) if task.is_finished() => {
+                match futures::executor::block_on(async move { task.await? }) {
+                    Ok(plans) => {
+                        let table = table::Table::builder()
+                            .items(Plans(plans.clone()))
+                            .border(false)
+                            .build();
+
+                        Stage::Preview(plans, table)
+                    }
+                    Err(err) => Stage::Done(Err(Self::error(&err))),
+                }
+            }
+            Stage::Draining(task) if task.is_finished() => Stage::Done(
+                futures::executor::block_on(async move { task.await? })
+                    .map_err(|err| Self::error(&err)),
+            ),
+            stage => stage,
+        };
+    }
+}
+
+async fn preview(client: kube::Client, node: String) -> Result<Vec<Plan>> {
+    let pods = Api::<Pod>::all(client.clone())
+        .list(&ListParams::default().fields(&format!("spec.nodeName={node}")))
+        .await?;
+    let pdbs = Api::<PodDisruptionBudget>::all(client)
+        .list(&ListParams::default())
+        .await?;
+
+    let pods = pods
+        .items
+        .into_iter()
+        .sorted_by_key(|pod| (pod.namespace(), pod.name_any()))
+        .map(Arc::new)
+        .collect_vec();
+    let pdbs = pdbs.items.into_iter().map(Arc::new).collect_vec();
+
+    Ok(drain::plan(&pods, &pdbs))
+}
+
+impl Widget for Drain {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if event.key().is_none() {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        match self.advance(stage, event, buffer, area) {
+            Ok((stage, result)) => {
+                self.stage = stage;
+
+                Ok(result)
+            }
+            Err(err) => {
+                self.stage = Stage::Done(Err(err.to_string()));
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        let area = centered_with(area, Constraint::Percentage(80), Constraint::Percentage(80));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Drain {}", self.node))
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Loading(_) => {
+                frame.render_widget(Paragraph::new("finding pods on the node..."), inner);
+            }
+            Stage::Preview(plans, table) => {
+                let [summary, rest] =
+                    Layout::vertical([Constraint::Length(2), Constraint::Fill(0)]).areas(inner);
+
+                frame.render_widget(Paragraph::new(Self::summary(plans)), summary);
+
+                table.draw(frame, rest)?;
+            }
+            Stage::Draining(_) => frame.render_widget(Paragraph::new("draining..."), inner),
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Drain {
+    fn drop(&mut self) {
+        match &self.stage {
+            Stage::Loading(task) => task.abort(),
+            Stage::Draining(task) => task.abort(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{
+    centered_with,
+    nav::{exit_keys, move_cursor, Movement},
+    theme, Widget,
+};
+use crate::{
+    config,
+    events::{Broadcast, Event, Keypress},
+    policy,
+    resources::drift::{self, Object, Report, Source, State},
+};
+
+// See `feed::MAX_FIELDS`.
+static MAX_FIELDS: usize = 10;
+
+struct DriftStyle {
+    differs: Style,
+    in_sync: Style,
+    path: Style,
+    declared: Style,
+    live: Style,
+    detail: Style,
+}
+
+impl Default for DriftStyle {
+    fn default() -> Self {
+        Self {
+            differs: Style::default()
+                .fg(theme::unhealthy())
+                .add_modifier(Modifier::BOLD),
+            in_sync: Style::default().fg(theme::healthy()),
+            path: Style::default().fg(tailwind::BLUE.c300),
+            declared: Style::default().fg(theme::healthy()),
+            live: Style::default().fg(theme::unhealthy()),
+            detail: Style::default().fg(tailwind::GRAY.c400),
+        }
+    }
+}
+
+/// Every object declared in the configured git repository or directory of
+/// manifests, compared against what's live. Meant for checking that nothing
+/// has been changed by hand during a change freeze, so anything that doesn't
+/// match goes first along with the fields that differ. `r` compares again.
+/// When the server allows it, pasting manifests in compares against those
+/// instead for as long as the view is open.
+pub struct Drift {
+    client: kube::Client,
+    upload: Option<Arc<String>>,
+
+    task: Option<JoinHandle<Result<Report>>>,
+    report: Option<Result<Report, String>>,
+    scroll: u16,
+}
+
+impl Drift {
+    pub fn new(client: kube::Client) -> Self {
+        let mut drift = Self {
+            client,
+            upload: None,
+            task: None,
+            report: None,
+            scroll: 0,
+        };
+
+        if config::get().drift.configured() {
+            drift.run();
+        }
+
+        drift
+    }
+
+    fn run(&mut self) {
+        let client = self.client.clone();
+        let scope = policy::scope();
+        let source = self
+            .upload
+            .clone()
+            .map_or(Source::Configured, Source::Upload);
+
+        self.report = None;
+        self.scroll = 0;
+        self.task = Some(tokio::spawn(async move {
+            drift::check(client, scope, source).await
+        }));
+    }
+
+    fn poll(&mut self) {
+        if !self.task.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+
+        let Some(task) = self.task.take() else {
+            return;
+        };
+
+        self.report = Some(
+            futures::executor::block_on(async move { task.await? }).map_err(|err| err.to_string()),
+        );
+    }
+
+    fn object(object: &Object, style: &DriftStyle) -> Vec<Line<'static>> {
+        let (mark, summary, color) = match &object.state {
+            State::InSync => return Vec::new(),
+            State::Differs => (
+                "✗",
+                format!("{} fields differ", object.fields.len()),
+                style.differs,
+            ),
+            State::Missing => ("✗", "missing from the cluster".to_string(), style.differs),
+            State::Unknown(err) => ("!", err.clone(), style.detail),
+        };
+
+        let mut lines = vec![Line::from(vec![
+            Span::styled(format!("{mark} "), color),
+            Span::raw(format!("{}  ", object.id())),
+            Span::styled(summary, color),
+            Span::styled(format!("  {}", object.file.display()), style.detail),
+        ])];
+
+        let none = || "<none>".to_string();
+
+        lines.extend(object.fields.iter().take(MAX_FIELDS).map(|field| {
+            Line::from(vec![
+                Span::styled(format!("    {}: ", field.path), style.path),
+                Span::styled(field.before.clone().unwrap_or_else(none), style.declared),
+                Span::styled(" → ", style.detail),
+                Span::styled(field.after.clone().unwrap_or_else(none), style.live),
+            ])
+        }));
+
+        if object.fields.len() > MAX_FIELDS {
+            lines.push(Line::from(Span::styled(
+                format!("    and {} more", object.fields.len() - MAX_FIELDS),
+                style.detail,
+            )));
+        }
+
+        lines
+    }
+
+    fn lines(report: &Report) -> Vec<Line<'static>> {
+        let style = DriftStyle::default();
+
+        let in_sync = report
+            .objects
+            .iter()
+            .filter(|object| object.state == State::InSync)
+            .count();
+
+        let mut lines = vec![Line::from(Span::styled(
+            format!(
+                "✓ {in_sync} of {} declared objects match",
+                report.objects.len()
+            ),
+            style.in_sync,
+        ))];
+
+        if !report.skipped.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{} files couldn't be read as manifests and were skipped",
+                    report.skipped.len()
+                ),
+                style.detail,
+            )));
+        }
+
+        lines.push(Line::default());
+
+        lines.extend(
+            report
+                .objects
+                .iter()
+                .flat_map(|object| Self::object(object, &style)),
+        );
+
+        lines
+    }
+}
+
+impl Widget for Drift {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Event::Paste(txt) = event {
+            if !config::get().drift.uploads {
+                return Ok(Broadcast::Ignored);
+            }
+
+            if let Some(task) = self.task.take() {
+                task.abort();
+            }
+
+            self.upload = Some(Arc::new(txt.clone()));
+            self.run();
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match key {
+            exit_keys!() => return Ok(Broadcast::Exited),
+            Keypress::Printable('r')
+                if self.task.is_none()
+                    && (self.upload.is_some() || config::get().drift.configured()) =>
+            {
+                self.run();
+            }
+            key => {
+                if let Some(Movement::Y(y)) = move_cursor(key, area) {
+                    self.scroll = self.scroll.saturating_add_signed(y);
+                }
+            }
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        let area = centered_with(area, Constraint::Percentage(90), Constraint::Percentage(80));
+
+        frame.render_widget(Clear, area);
+
+        let mut keys = Vec::new();
+
+        if config::get().drift.uploads {
+            keys.push("paste: compare against other manifests");
+        }
+
+        if self.task.is_none() && (self.upload.is_some() || config::get().drift.configured()) {
+            keys.push("r: compare again");
+        }
+
+        keys.push("esc: close");
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(if self.upload.is_some() {
+                "Drift from the uploaded manifests"
+            } else {
+                "Drift from the declared manifests"
+            })
+            .title_bottom(
+                Line::from(keys.join(", ")).style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        let pg = match &self.report {
+            None if self.task.is_none() => Paragraph::new(
+                "Paste in manifests, such as the output of `kustomize build`, to compare them \
+                 against the cluster.",
+            ),
+            None => Paragraph::new("comparing the manifests against the cluster..."),
+            Some(Ok(report)) => Paragraph::new(Self::lines(report)),
+            Some(Err(err)) => {
+                Paragraph::new(err.clone()).style(Style::default().fg(theme::unhealthy()))
+            }
+        };
+
+        frame.render_widget(pg.scroll((self.scroll, 0)), inner);
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Drift {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
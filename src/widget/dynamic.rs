@@ -0,0 +1,332 @@
+use std::{sync::Arc, time::Duration};
+
+use eyre::{eyre, Result};
+use kube::{
+    api::{ApiResource, DynamicObject, ListParams},
+    ResourceExt,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders},
+    Frame,
+};
+use tokio::sync::oneshot;
+
+use super::{
+    delete::Delete, drain::Drain, edit::QuickEdit, extended::Extended, link::Links,
+    loading::Loading, log::attempts::Attempts, propagate, recent, revert::Revert, table,
+    view::View, yaml::Yaml, Action, BoxWidget, Placement, Widget, WIDGET_VIEWS_VEC,
+};
+use crate::{
+    config,
+    events::{Broadcast, Event, Keypress},
+    resources::{dynamic::Objects, store::Store},
+    usage,
+};
+
+/// Generic list for any kind found via discovery. There's no knowledge of what
+/// is interesting about the kind, so this sticks to metadata and the YAML.
+pub struct List {
+    view: View,
+    is_ready: oneshot::Receiver<()>,
+}
+
+impl List {
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(skip(client), fields(activity = "dynamic.list"))]
+    pub fn new(client: kube::Client, resource: ApiResource, namespace: Option<&str>) -> Self {
+        WIDGET_VIEWS_VEC
+            .with_label_values(&[resource.kind.as_str(), "list"])
+            .inc();
+
+        let tables = &config::get().tables;
+
+        let (objects, is_ready) = if tables.poll.contains(&resource.plural) {
+            Store::<DynamicObject>::polled(
+                client.clone(),
+                &resource,
+                ListParams::default(),
+                Duration::from_secs(tables.poll_interval),
+            )
+        } else {
+            Store::<DynamicObject>::new_with(client.clone(), &resource)
+        };
+
+        let table = table::Table::builder()
+            .title(namespace.map_or_else(
+                || resource.plural.clone(),
+                |ns| format!("{} ({ns})", resource.plural),
+            ))
+            .items(Arc::new(Objects {
+                store: objects.clone(),
+                resource: resource.clone(),
+                view: None,
+                namespace: namespace.map(ToString::to_string),
+            }))
+            .remember(usage::resource(&resource.plural, &resource.group))
+            .build();
+
+        Self::with_table(client, table, objects, resource, is_ready)
+    }
+
+    /// A view from the config, `resource` being the kind it lists.
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(skip(client, view), fields(activity = "dynamic.view"))]
+    pub fn view(client: kube::Client, resource: ApiResource, view: &'static config::View) -> Self {
+        WIDGET_VIEWS_VEC
+            .with_label_values(&[resource.kind.as_str(), "view"])
+            .inc();
+
+        let (objects, is_ready) =
+            Store::<DynamicObject>::new_with_config(client.clone(), &resource, view.watch());
+
+        let table = table::Table::builder()
+            .title(view.name.as_str())
+            .items(Arc::new(Objects {
+                store: objects.clone(),
+                resource: resource.clone(),
+                view: Some(view),
+                namespace: None,
+            }))
+            .remember(format!("view.{}", view.name))
+            .maybe_sort(view.sort.as_deref().map(|sort| table::state::Sort {
+                column: sort.trim_start_matches('-').to_string(),
+                descending: sort.starts_with('-'),
+            }))
+            .build();
+
+        Self::with_table(client, table, objects, resource, is_ready)
+    }
+
+    fn with_table(
+        client: kube::Client,
+        table: table::Table<Arc<Objects>>,
+        objects: Arc<Store<DynamicObject>>,
+        resource: ApiResource,
+        is_ready: oneshot::Receiver<()>,
+    ) -> Self {
+        let table = table::Filtered::builder()
+            .table(table)
+            .constructor(Detail::from_store(client, objects, resource))
+            .build();
+
+        Self {
+            view: View::builder()
+                .widgets(vec![table.boxed(), Loading.boxed()])
+                .build(),
+            is_ready,
+        }
+    }
+}
+
+impl Widget for List {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        propagate!(self.view.dispatch(event, buffer, area));
+
+        if matches!(event.key(), Some(Keypress::Escape)) {
+            return Ok(Broadcast::Exited);
+        }
+
+        Ok(Broadcast::Ignored)
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        self.view.actions()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Ok(()) = self.is_ready.try_recv() {
+            self.view.pop();
+        }
+
+        self.view.draw(frame, area)
+    }
+
+    fn placement(&self) -> Placement {
+        Placement {
+            horizontal: Constraint::Fill(0),
+            vertical: Constraint::Fill(0),
+        }
+    }
+}
+
+struct Detail {
+    object: Arc<DynamicObject>,
+    resource: ApiResource,
+
+    yaml: Yaml,
+    client: kube::Client,
+    popup: Option<BoxWidget>,
+}
+
+impl Detail {
+    fn new(client: &kube::Client, object: Arc<DynamicObject>, resource: &ApiResource) -> Self {
+        WIDGET_VIEWS_VEC
+            .with_label_values(&[resource.kind.as_str(), "detail"])
+            .inc();
+
+        recent::opened(object.as_ref(), resource);
+
+        Self {
+            yaml: Yaml::with_kind(resource.kind.as_str(), object.as_ref())
+                .editable(client.clone(), object.as_ref()),
+            object,
+            resource: resource.clone(),
+            client: client.clone(),
+            popup: None,
+        }
+    }
+
+    fn from_store(
+        client: kube::Client,
+        objects: Arc<Store<DynamicObject>>,
+        resource: ApiResource,
+    ) -> table::DetailFn {
+        Box::new(move |idx, filter| {
+            let object = objects
+                .get(idx, filter)
+                .ok_or_else(|| eyre!("{} not found", resource.kind))?;
+
+            Ok(Detail::new(&client, object, &resource).boxed())
+        })
+    }
+
+    fn breadcrumb(&self) -> Vec<Span> {
+        let mut crumb: Vec<Span> = Vec::new();
+
+        if let Some(ns) = self.object.namespace() {
+            crumb.push(ns.into());
+            crumb.push(Span::from(" → ").style(Style::default().add_modifier(Modifier::BOLD)));
+        }
+
+        crumb.push(self.object.name_any().into());
+
+        crumb
+    }
+}
+
+/// The generic detail of `object`, for when there's no list it was picked
+/// from, see `recent::Jump`.
+pub fn detail(
+    client: &kube::Client,
+    object: Arc<DynamicObject>,
+    resource: &ApiResource,
+) -> BoxWidget {
+    Detail::new(client, object, resource).boxed()
+}
+
+impl Widget for Detail {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(popup) = self.popup.as_mut() {
+            if let Broadcast::Exited = popup.dispatch(event, buffer, area)? {
+                self.popup = None;
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        propagate!(self.yaml.dispatch(event, buffer, area));
+
+        match event.key() {
+            Some(Keypress::Escape) => Ok(Broadcast::Exited),
+            Some(Keypress::Printable('o')) => {
+                self.popup = Some(Links::new(self.object.as_ref()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('e')) => {
+                self.popup =
+                    Some(QuickEdit::new(self.client.clone(), self.object.as_ref()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('X')) => {
+                self.popup = Some(
+                    Delete::new(
+                        self.client.clone(),
+                        self.object.clone(),
+                        self.resource.clone(),
+                    )
+                    .boxed(),
+                );
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('D')) if self.resource.kind == "Node" => {
+                self.popup = Some(Drain::new(self.client.clone(), self.object.name_any()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('G')) if self.resource.kind == "Node" => {
+                self.popup =
+                    Some(Extended::new(self.client.clone(), self.object.name_any()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('A')) if self.resource.kind == "Job" => {
+                self.popup = Some(Attempts::new(self.client.clone(), self.object.as_ref()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('R')) if self.resource.kind == "ConfigMap" => {
+                self.popup = Some(Revert::new(self.client.clone(), self.object.as_ref()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            _ => Ok(Broadcast::Ignored),
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        if self.popup.is_some() {
+            return Vec::new();
+        }
+
+        let mut actions = self.yaml.actions();
+        actions.extend([
+            Action::new('o', "Open links"),
+            Action::new('e', "Quick edit a field"),
+            Action::new('X', "Delete"),
+        ]);
+
+        if self.resource.kind == "Node" {
+            actions.push(Action::new('D', "Drain the node"));
+            actions.push(Action::new('G', "Show GPUs and other extended resources"));
+        }
+
+        if self.resource.kind == "Job" {
+            actions.push(Action::new('A', "Logs from every attempt"));
+        }
+
+        if self.resource.kind == "ConfigMap" {
+            actions.push(Action::new('R', "Revert to a saved copy"));
+        }
+
+        actions
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(self.breadcrumb()));
+
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        self.yaml.draw(frame, inner)?;
+
+        if let Some(popup) = self.popup.as_mut() {
+            popup.draw(frame, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
@@ -0,0 +1,409 @@
+use eyre::{eyre, Result, WrapErr};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::{
+    api::{Api, DynamicObject, Patch, PatchParams},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+use super::{centered, input::form::Form, nav::exit_keys, table, theme, Widget};
+use crate::{
+    config,
+    events::{Broadcast, Event, Keypress},
+    resources::{edit::Edit, revision, DynamicClient, MANAGER},
+};
+
+lazy_static! {
+    static ref QUICK_EDITS: IntCounterVec = register_int_counter_vec!(
+        "quick_edits_total",
+        "Number of quick edits applied, by whether they succeeded",
+        &["result"]
+    )
+    .unwrap();
+}
+
+fn is_configmap(object: &DynamicObject) -> bool {
+    object
+        .types
+        .as_ref()
+        .is_some_and(|types| types.kind == "ConfigMap")
+}
+
+struct Edits(Vec<Edit>);
+
+impl table::Items for Edits {
+    type Item = Edit;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.clone()
+    }
+}
+
+enum Outcome {
+    Applied,
+    Conflict(DynamicObject),
+}
+
+/// An edit that lost out to a change made on the server in the meantime.
+struct Conflict {
+    // What the field was when the edit started, what it is on the server now and
+    // what was typed in.
+    edit: Edit,
+    theirs: Option<Edit>,
+    input: String,
+    latest: DynamicObject,
+}
+
+enum Stage {
+    Pick(table::Table<Edits>),
+    Prompt(Edit, Form),
+    Applying(Edit, String, JoinHandle<Result<Outcome>>),
+    Conflict(Conflict),
+    Done(Result<String, String>),
+}
+
+/// Change a single field of an object by picking it and typing in a new
+/// value. This issues a JSON patch for just that field instead of replacing
+/// the entire object.
+#[allow(clippy::module_name_repetitions)]
+pub struct QuickEdit {
+    client: kube::Client,
+    object: Option<DynamicObject>,
+    edits: Vec<Edit>,
+
+    stage: Stage,
+}
+
+impl QuickEdit {
+    pub fn new<K>(client: kube::Client, obj: &K) -> Self
+    where
+        K: Serialize,
+    {
+        let edits = Edit::find(obj);
+
+        Self {
+            client,
+            object: serde_json::to_value(obj)
+                .and_then(serde_json::from_value)
+                .ok(),
+            stage: Stage::Pick(table::Table::builder().items(Edits(edits.clone())).build()),
+            edits,
+        }
+    }
+
+    /// Skip straight to typing in a value for the field at `pointer`.
+    pub fn at<K>(client: kube::Client, obj: &K, pointer: &str) -> Self
+    where
+        K: Serialize,
+    {
+        let stage = match Edit::at(obj, pointer) {
+            Some(edit) => Stage::Prompt(
+                edit.clone(),
+                Form::new([(edit.label.as_str(), edit.current.as_str())]),
+            ),
+            None => Stage::Done(Err(format!("{pointer} can't be found"))),
+        };
+
+        Self {
+            client,
+            object: serde_json::to_value(obj)
+                .and_then(serde_json::from_value)
+                .ok(),
+            edits: Vec::new(),
+            stage,
+        }
+    }
+
+    fn apply(&self, edit: &Edit, input: &str) -> Result<JoinHandle<Result<Outcome>>> {
+        let patch = json_patch::Patch(edit.patch(input)?);
+        let client = self.client.clone();
+        let object = self
+            .object
+            .clone()
+            .ok_or_else(|| eyre!("object cannot be edited"))?;
+
+        Ok(tokio::spawn(async move {
+            if is_configmap(&object) {
+                let cm = Api::<ConfigMap>::namespaced(
+                    client.clone(),
+                    &object.namespace().unwrap_or_default(),
+                )
+                .get(&object.name_any())
+                .await?;
+
+                revision::snapshot(client.clone(), &cm)
+                    .await
+                    .wrap_err("unable to save a copy before editing")?;
+            }
+
+            let api = object.dynamic(client).await?;
+
+            let result = api
+                .patch(
+                    &object.name_any(),
+                    &PatchParams {
+                        field_manager: Some(MANAGER.to_string()),
+                        ..Default::default()
+                    },
+                    &Patch::Json::<()>(patch),
+                )
+                .await;
+
+            match result {
+                Ok(_) => Ok(Outcome::Applied),
+                // A failed `test` means that someone else got there first. Fetch
+                // what they did so the edit can be retried on top of it.
+                Err(kube::Error::Api(resp)) if resp.code == 409 || resp.code == 422 => {
+                    Ok(Outcome::Conflict(api.get(&object.name_any()).await?))
+                }
+                Err(err) => Err(err.into()),
+            }
+        }))
+    }
+
+    fn advance(
+        &mut self,
+        stage: Stage,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<(Stage, Broadcast)> {
+        let stage = match stage {
+            Stage::Pick(mut table) => match table.dispatch(event, buffer, area)? {
+                Broadcast::Selected(idx) => match self.edits.get(idx) {
+                    Some(edit) => Stage::Prompt(
+                        edit.clone(),
+                        Form::new([(edit.label.as_str(), edit.current.as_str())]),
+                    ),
+                    None => Stage::Pick(table),
+                },
+                _ if matches!(event.key(), Some(exit_keys!())) => {
+                    return Ok((Stage::Pick(table), Broadcast::Exited))
+                }
+                _ => Stage::Pick(table),
+            },
+            Stage::Prompt(edit, mut form) => match form.dispatch(event, buffer, area)? {
+                Broadcast::Selected(_) => {
+                    let input = form.values().pop().unwrap_or_default();
+                    let task = self.apply(&edit, &input)?;
+
+                    Stage::Applying(edit, input, task)
+                }
+                // There's nothing to go back to when the field was picked elsewhere.
+                Broadcast::Exited if self.edits.is_empty() => {
+                    return Ok((Stage::Prompt(edit, form), Broadcast::Exited))
+                }
+                Broadcast::Exited => Stage::Pick(
+                    table::Table::builder()
+                        .items(Edits(self.edits.clone()))
+                        .build(),
+                ),
+                _ => Stage::Prompt(edit, form),
+            },
+            Stage::Applying(edit, input, task) => Stage::Applying(edit, input, task),
+            Stage::Conflict(conflict) => match (event.key(), conflict.theirs) {
+                (Some(Keypress::Printable('r')), Some(theirs)) => {
+                    self.object = Some(conflict.latest);
+
+                    let task = self.apply(&theirs, &conflict.input)?;
+
+                    Stage::Applying(theirs, conflict.input, task)
+                }
+                (Some(exit_keys!()), _) => Stage::Done(Err("edit discarded".to_string())),
+                (_, theirs) => Stage::Conflict(Conflict { theirs, ..conflict }),
+            },
+            Stage::Done(result) => {
+                if event.key().is_some() {
+                    return Ok((Stage::Done(result), Broadcast::Exited));
+                }
+
+                Stage::Done(result)
+            }
+        };
+
+        Ok((stage, Broadcast::Consumed))
+    }
+
+    fn finished(&self, edit: Edit, input: String, task: &mut JoinHandle<Result<Outcome>>) -> Stage {
+        let result = futures::executor::block_on(async move { task.await? });
+
+        let label = match result {
+            Ok(Outcome::Applied) => "success",
+            Ok(Outcome::Conflict(_)) => "conflict",
+            Err(_) => "failure",
+        };
+        QUICK_EDITS.with_label_values(&[label]).inc();
+
+        match result {
+            Ok(Outcome::Applied)
+                if self.object.as_ref().is_some_and(is_configmap)
+                    && config::get().configmaps.revisions > 0 =>
+            {
+                Stage::Done(Ok(
+                    "updated, the previous version was saved and can be put back with R"
+                        .to_string(),
+                ))
+            }
+            Ok(Outcome::Applied) => Stage::Done(Ok("updated".to_string())),
+            Ok(Outcome::Conflict(latest)) => Stage::Conflict(Conflict {
+                theirs: edit.rebase(&latest),
+                edit,
+                input,
+                latest,
+            }),
+            Err(err) => Stage::Done(Err(match err.downcast_ref::<kube::Error>() {
+                Some(kube::Error::Api(resp)) => resp.message.clone(),
+                _ => err.to_string(),
+            })),
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self.stage {
+            Stage::Pick(_) => "enter: edit, esc: cancel",
+            Stage::Prompt(..) if self.edits.is_empty() => "enter: apply, esc: cancel",
+            Stage::Prompt(..) => "enter: apply, esc: back",
+            Stage::Applying(..) => "",
+            Stage::Conflict(Conflict {
+                theirs: Some(_), ..
+            }) => "r: apply your value on top of the server's, esc: discard",
+            Stage::Conflict(_) => "esc: discard",
+            Stage::Done(_) => "press any key to close",
+        }
+    }
+}
+
+impl Conflict {
+    fn lines(&self) -> Vec<Line<'static>> {
+        let label = Style::default().fg(tailwind::GRAY.c400);
+
+        let mut lines = vec![
+            Line::from(format!(
+                "{} was changed on the server while it was being edited.",
+                self.edit.label
+            ))
+            .style(Style::default().fg(tailwind::YELLOW.c300)),
+            Line::default(),
+            Line::from(vec![
+                Span::styled("was:    ", label),
+                Span::raw(self.edit.current.clone()),
+            ]),
+        ];
+
+        lines.push(match self.theirs.as_ref() {
+            Some(theirs) => Line::from(vec![
+                Span::styled("server: ", label),
+                Span::styled(
+                    theirs.current.clone(),
+                    Style::default().fg(theme::unhealthy()),
+                ),
+            ]),
+            None => Line::from(vec![
+                Span::styled("server: ", label),
+                Span::styled(
+                    "the field no longer exists",
+                    Style::default().fg(theme::unhealthy()),
+                ),
+            ]),
+        });
+
+        lines.push(Line::from(vec![
+            Span::styled("yours:  ", label),
+            Span::styled(self.input.clone(), Style::default().fg(theme::healthy())),
+        ]));
+
+        lines
+    }
+}
+
+impl Widget for QuickEdit {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if event.key().is_none() && !matches!(event, Event::Paste(_)) {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        match self.advance(stage, event, buffer, area) {
+            Ok((stage, result)) => {
+                self.stage = stage;
+
+                Ok(result)
+            }
+            Err(err) => {
+                self.stage = Stage::Done(Err(err.to_string()));
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if matches!(&self.stage, Stage::Applying(.., task) if task.is_finished()) {
+            if let Stage::Applying(edit, input, mut task) =
+                std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())))
+            {
+                self.stage = self.finished(edit, input, &mut task);
+            }
+        }
+
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Quick Edit")
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Pick(_) if self.edits.is_empty() => {
+                frame.render_widget(Paragraph::new("nothing to edit"), inner);
+            }
+            Stage::Pick(table) => table.draw(frame, inner)?,
+            Stage::Prompt(_, form) => form.draw(frame, inner)?,
+            Stage::Applying(..) => frame.render_widget(Paragraph::new("updating..."), inner),
+            Stage::Conflict(conflict) => frame.render_widget(
+                Paragraph::new(conflict.lines()).wrap(Wrap { trim: false }),
+                inner,
+            ),
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for QuickEdit {
+    fn drop(&mut self) {
+        if let Stage::Applying(.., task) = &self.stage {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::core::v1::Event as KubeEvent;
+use tokio::sync::oneshot;
+
+use super::{
+    object::{self, Object},
+    table::Items,
+    tabs::Tab,
+    yaml::Yaml,
+    Action, Widget,
+};
+use crate::{
+    events::Keypress,
+    resources::{
+        event::{EventExt, Stream},
+        store::Store,
+    },
+};
+
+/// Every event in the cluster as it comes in, the latest at the top. This is
+/// the place to look for what just broke, `w` leaves only the warnings and the
+/// filter matches a type, reason, object name or message.
+pub type List = object::List<KubeEvent>;
+
+/// A single event, with everything the table has to cut short. Events come
+/// and go too quickly to be worth remembering or editing.
+impl Object for KubeEvent {
+    type Items = Arc<Stream>;
+
+    const REMEMBER: bool = false;
+    const EDITABLE: bool = false;
+    const RECENT: bool = false;
+
+    fn items(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Self::Items, oneshot::Receiver<()>) {
+        let (events, is_ready) = Store::namespaced(client, namespace);
+
+        (Arc::new(Stream::new(events)), is_ready)
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.items(filter).get(idx).cloned()
+    }
+
+    fn tabs(_: &kube::Client, _: &Self::Items, event: &Arc<Self>) -> Vec<Tab> {
+        let event = event.clone();
+
+        vec![Tab::builder()
+            .name("Overview".to_string())
+            .constructor(Box::new(move || Yaml::new(&event).boxed()))
+            .build()]
+    }
+
+    fn title(event: &Self) -> String {
+        event.object()
+    }
+
+    fn status(_: &Self::Items, event: &Self) -> Option<String> {
+        event.reason.clone()
+    }
+
+    fn list_key(stream: &Self::Items, key: &Keypress) -> bool {
+        if !matches!(key, Keypress::Printable('w')) {
+            return false;
+        }
+
+        stream.toggle();
+
+        true
+    }
+
+    fn list_actions(stream: &Self::Items) -> Vec<Action> {
+        vec![Action::new(
+            'w',
+            if stream.warnings() {
+                "Show every event"
+            } else {
+                "Only show warnings"
+            },
+        )]
+    }
+}
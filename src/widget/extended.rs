@@ -0,0 +1,165 @@
+use eyre::Result;
+use k8s_openapi::api::core::v1::{Node, Pod};
+use kube::api::{Api, ListParams};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{centered_with, nav::exit_keys, table, theme, Widget};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::extended::{self, Allocation},
+};
+
+struct Allocations(Vec<Allocation>);
+
+impl table::Items for Allocations {
+    type Item = Allocation;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.clone()
+    }
+}
+
+enum Stage {
+    Loading(JoinHandle<Result<Vec<Allocation>>>),
+    Table(table::Table<Allocations>),
+    Done(Result<String, String>),
+}
+
+/// How much of each extended resource, GPUs and huge pages for the most part,
+/// a node has and which pods have asked for it.
+pub struct Extended {
+    node: String,
+
+    stage: Stage,
+}
+
+impl Extended {
+    pub fn new(client: kube::Client, node: String) -> Self {
+        let stage = Stage::Loading(tokio::spawn(load(client, node.clone())));
+
+        Self { node, stage }
+    }
+
+    fn help(&self) -> &'static str {
+        match self.stage {
+            Stage::Loading(_) | Stage::Table(_) => "esc: close",
+            Stage::Done(_) => "press any key to close",
+        }
+    }
+
+    fn error(err: &eyre::Report) -> String {
+        match err.downcast_ref::<kube::Error>() {
+            Some(kube::Error::Api(resp)) => resp.message.clone(),
+            _ => err.to_string(),
+        }
+    }
+
+    fn poll(&mut self) {
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        self.stage = match stage {
+            Stage::Loading(task) if task.is_finished() => {
+                match futures::executor::block_on(async move { task.await? }) {
+                    Ok(allocations) if allocations.is_empty() => Stage::Done(Ok(format!(
+                        "{} has no extended resources and no pods on it ask for any",
+                        self.node
+                    ))),
+                    Ok(allocations) => Stage::Table(
+                        table::Table::builder()
+                            .items(Allocations(allocations))
+                            .border(false)
+                            .build(),
+                    ),
+                    Err(err) => Stage::Done(Err(Self::error(&err))),
+                }
+            }
+            stage => stage,
+        };
+    }
+}
+
+async fn load(client: kube::Client, node: String) -> Result<Vec<Allocation>> {
+    let pods = Api::<Pod>::all(client.clone())
+        .list(&ListParams::default().fields(&format!("spec.nodeName={node}")))
+        .await?;
+    let node = Api::<Node>::all(client).get(&node).await?;
+
+    Ok(extended::allocations(&node, &pods.items))
+}
+
+impl Widget for Extended {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match &mut self.stage {
+            Stage::Done(_) => Ok(Broadcast::Exited),
+            _ if matches!(key, exit_keys!()) => Ok(Broadcast::Exited),
+            Stage::Table(table) => {
+                table.dispatch(event, buffer, area)?;
+
+                Ok(Broadcast::Consumed)
+            }
+            Stage::Loading(_) => Ok(Broadcast::Consumed),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        let area = centered_with(area, Constraint::Percentage(80), Constraint::Percentage(60));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Extended resources on {}", self.node))
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Loading(_) => {
+                frame.render_widget(Paragraph::new("finding pods on the node..."), inner);
+            }
+            Stage::Table(table) => table.draw(frame, inner)?,
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Extended {
+    fn drop(&mut self) {
+        if let Stage::Loading(task) = &self.stage {
+            task.abort();
+        }
+    }
+}
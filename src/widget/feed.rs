@@ -0,0 +1,359 @@
+use std::sync::{Arc, Mutex};
+
+use eyre::Result;
+use futures::StreamExt;
+use kube::{
+    api::{ApiResource, DynamicObject},
+    runtime::{self, WatchStreamExt},
+    Api,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+pub mod diff;
+
+use super::{
+    centered_with,
+    nav::{exit_keys, move_cursor, Movement},
+    theme, BoxWidget, Widget, WIDGET_VIEWS_VEC,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    policy,
+    resources::feed::{Action, Change, Feed as Changes},
+    throttle::THROTTLE,
+    widget::startup::Startup,
+};
+
+use self::diff::Diff;
+
+// A single change can touch a lot of fields (a whole status block being filled
+// in), past this they're summarized so that other changes stay visible.
+static MAX_FIELDS: usize = 10;
+
+struct FeedStyle {
+    time: Style,
+    added: Style,
+    modified: Style,
+    deleted: Style,
+    path: Style,
+    before: Style,
+    after: Style,
+    detail: Style,
+    cursor: Style,
+}
+
+impl Default for FeedStyle {
+    fn default() -> Self {
+        Self {
+            time: Style::default().fg(tailwind::GRAY.c500),
+            added: Style::default()
+                .fg(theme::healthy())
+                .add_modifier(Modifier::BOLD),
+            modified: Style::default()
+                .fg(tailwind::YELLOW.c300)
+                .add_modifier(Modifier::BOLD),
+            deleted: Style::default()
+                .fg(theme::unhealthy())
+                .add_modifier(Modifier::BOLD),
+            path: Style::default().fg(tailwind::BLUE.c300),
+            before: Style::default().fg(theme::unhealthy()),
+            after: Style::default().fg(theme::healthy()),
+            detail: Style::default().fg(tailwind::GRAY.c400),
+            cursor: Style::default().bg(tailwind::GRAY.c800),
+        }
+    }
+}
+
+/// Like `kubectl get --watch`, every change to objects of a kind as it happens
+/// along with the fields that changed. Handy for watching a rollout or what a
+/// controller is doing instead of trying to spot the difference between
+/// snapshots. `enter` on a modification shows the whole object before and
+/// after as a diff. Like the lists, only the picked namespace is watched and
+/// nothing outside of the session's scope is shown.
+pub struct Feed {
+    resource: ApiResource,
+    namespace: Option<String>,
+    changes: Arc<Mutex<Changes>>,
+    task: JoinHandle<()>,
+
+    // The `Change::id` picked with the cursor, `None` follows new changes as
+    // they come in.
+    selected: Option<u64>,
+    scroll: u16,
+    popup: Option<BoxWidget>,
+}
+
+impl Feed {
+    pub fn new(client: kube::Client, resource: ApiResource, namespace: Option<&str>) -> Self {
+        WIDGET_VIEWS_VEC
+            .with_label_values(&[resource.kind.as_str(), "feed"])
+            .inc();
+
+        let changes = Arc::new(Mutex::new(Changes::new(policy::scope(), resource.clone())));
+
+        let task = tokio::spawn({
+            let changes = changes.clone();
+            let api = Api::<DynamicObject>::all_with(client, &resource);
+            let config = Startup::watch(namespace);
+
+            async move {
+                let mut stream = runtime::watcher(api, config).default_backoff().boxed();
+
+                loop {
+                    // See `Store` for why this waits before pulling the next event.
+                    THROTTLE.wait().await;
+
+                    let Some(event) = stream.next().await else {
+                        break;
+                    };
+
+                    match event {
+                        Ok(event) => {
+                            if let Ok(mut changes) = changes.lock() {
+                                changes.handle(event);
+                            }
+                        }
+                        Err(err) => tracing::debug!("feed watch failed: {err}"),
+                    }
+                }
+            }
+        });
+
+        Self {
+            resource,
+            namespace: namespace.map(ToString::to_string),
+            changes,
+            task,
+            selected: None,
+            scroll: 0,
+            popup: None,
+        }
+    }
+
+    // The cursor moves a change at a time, going past the newest one starts
+    // following again.
+    fn select(&mut self, y: i16) {
+        let Ok(changes) = self.changes.lock() else {
+            return;
+        };
+
+        let changes = changes.changes();
+
+        let current = self
+            .selected
+            .and_then(|id| changes.iter().position(|change| change.id == id))
+            .unwrap_or(changes.len());
+
+        let next = current
+            .saturating_add_signed(isize::from(y))
+            .min(changes.len());
+
+        self.selected = changes.get(next).map(|change| change.id);
+    }
+
+    // The picked change, or the newest one while following.
+    fn open(&mut self) {
+        let Ok(changes) = self.changes.lock() else {
+            return;
+        };
+
+        let change = match self.selected {
+            Some(id) => changes.changes().iter().find(|change| change.id == id),
+            None => changes.changes().back(),
+        };
+
+        if let Some(change) = change.filter(|change| change.action == Action::Modified) {
+            WIDGET_VIEWS_VEC
+                .with_label_values(&[self.resource.kind.as_str(), "diff"])
+                .inc();
+
+            self.popup = Some(Diff::new(change).boxed());
+        }
+    }
+
+    fn change(change: &Change, style: &FeedStyle, selected: bool) -> Vec<Line<'static>> {
+        let name = change
+            .namespace
+            .as_ref()
+            .map_or(change.name.clone(), |ns| format!("{ns}/{}", change.name));
+
+        let action = match change.action {
+            Action::Added => style.added,
+            Action::Modified => style.modified,
+            Action::Deleted => style.deleted,
+        };
+
+        let mut header = Line::from(vec![
+            Span::styled(change.at.format("%H:%M:%S ").to_string(), style.time),
+            Span::styled(format!("{:<9}", change.action.to_string()), action),
+            Span::raw(name),
+        ]);
+
+        if selected {
+            header = header.style(style.cursor);
+        }
+
+        let mut lines = vec![header];
+
+        let none = || "<none>".to_string();
+
+        lines.extend(change.fields.iter().take(MAX_FIELDS).map(|field| {
+            Line::from(vec![
+                Span::styled(format!("    {}: ", field.path), style.path),
+                Span::styled(field.before.clone().unwrap_or_else(none), style.before),
+                Span::styled(" → ", style.detail),
+                Span::styled(field.after.clone().unwrap_or_else(none), style.after),
+            ])
+        }));
+
+        if change.fields.len() > MAX_FIELDS {
+            lines.push(Line::from(Span::styled(
+                format!("    and {} more", change.fields.len() - MAX_FIELDS),
+                style.detail,
+            )));
+        }
+
+        lines
+    }
+
+    // Along with the line the picked change starts on. A change that's since
+    // been dropped moves the cursor to the oldest one left.
+    fn lines(&mut self) -> (Vec<Line<'static>>, Option<usize>) {
+        let style = FeedStyle::default();
+
+        let Ok(changes) = self.changes.lock() else {
+            return (Vec::new(), None);
+        };
+
+        let changes = changes.changes();
+
+        if let Some(id) = self.selected {
+            if !changes.iter().any(|change| change.id == id) {
+                self.selected = changes.front().map(|change| change.id);
+            }
+        }
+
+        let mut lines = Vec::new();
+        let mut cursor = None;
+
+        for change in changes {
+            let selected = self.selected == Some(change.id);
+
+            if selected {
+                cursor = Some(lines.len());
+            }
+
+            lines.extend(Self::change(change, &style, selected));
+        }
+
+        (lines, cursor)
+    }
+}
+
+impl Widget for Feed {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(popup) = self.popup.as_mut() {
+            if let Broadcast::Exited = popup.dispatch(event, buffer, area)? {
+                self.popup = None;
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(key, exit_keys!()) {
+            return Ok(Broadcast::Exited);
+        }
+
+        if matches!(key, Keypress::Enter) {
+            self.open();
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        if let Some(Movement::Y(y)) = move_cursor(key, area) {
+            self.select(y);
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = centered_with(area, Constraint::Percentage(90), Constraint::Percentage(80));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.namespace.as_ref().map_or_else(
+                || format!("Changes to {}", self.resource.plural),
+                |ns| format!("Changes to {} ({ns})", self.resource.plural),
+            ))
+            .title_bottom(
+                Line::from(if self.selected.is_some() {
+                    "paused, enter: diff, move past the end to follow, esc: close"
+                } else {
+                    "following, enter: diff the newest, move up to pause, esc: close"
+                })
+                .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        let (lines, cursor) = self.lines();
+
+        if lines.is_empty() {
+            frame.render_widget(
+                Line::from("waiting for changes").style(FeedStyle::default().detail),
+                inner,
+            );
+
+            return Ok(());
+        }
+
+        let end = u16::try_from(lines.len())
+            .unwrap_or(u16::MAX)
+            .saturating_sub(inner.height);
+
+        // Only scroll as far as it takes to keep the cursor on screen.
+        self.scroll = match cursor.map(|cursor| u16::try_from(cursor).unwrap_or(u16::MAX)) {
+            Some(cursor) if cursor < self.scroll => cursor,
+            Some(cursor) if cursor >= self.scroll.saturating_add(inner.height) => {
+                cursor.saturating_sub(inner.height.saturating_sub(1))
+            }
+            Some(_) => self.scroll,
+            None => end,
+        }
+        .min(end);
+
+        frame.render_widget(Paragraph::new(lines).scroll((self.scroll, 0)), inner);
+
+        if let Some(popup) = self.popup.as_mut() {
+            popup.draw(frame, inner)?;
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Feed {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,117 @@
+use eyre::Result;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::feed::{diff, Change},
+    widget::{
+        nav::{exit_keys, move_cursor, Movement},
+        theme, Widget,
+    },
+};
+
+/// The object's YAML from before a modification to after, as a unified diff.
+/// Controllers fighting over a field show up as the same lines flipping back
+/// and forth between consecutive changes.
+pub struct Diff {
+    title: String,
+    lines: Vec<Line<'static>>,
+
+    scroll: u16,
+}
+
+impl Diff {
+    pub fn new(change: &Change) -> Self {
+        let name = change
+            .namespace
+            .as_ref()
+            .map_or(change.name.clone(), |ns| format!("{ns}/{}", change.name));
+
+        let lines: Vec<_> = change
+            .diff()
+            .into_iter()
+            .map(|line| match line {
+                diff::Line::Hunk(hunk) => {
+                    Line::styled(hunk, Style::default().fg(tailwind::BLUE.c300))
+                }
+                diff::Line::Same(line) => Line::raw(format!(" {line}")),
+                diff::Line::Removed(line) => {
+                    Line::styled(format!("-{line}"), Style::default().fg(theme::unhealthy()))
+                }
+                diff::Line::Added(line) => {
+                    Line::styled(format!("+{line}"), Style::default().fg(theme::healthy()))
+                }
+            })
+            .collect();
+
+        Self {
+            title: format!("{name} at {}", change.at.format("%H:%M:%S")),
+            lines,
+            scroll: 0,
+        }
+    }
+}
+
+impl Widget for Diff {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(key, exit_keys!()) {
+            return Ok(Broadcast::Exited);
+        }
+
+        if let Some(Movement::Y(y)) = move_cursor(key, area) {
+            self.scroll = self.scroll.saturating_add_signed(y);
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title.as_str())
+            .title_bottom(Line::from("esc: back").style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        if self.lines.is_empty() {
+            frame.render_widget(
+                Line::from("only fields that change every time changed")
+                    .style(Style::default().fg(tailwind::GRAY.c400)),
+                inner,
+            );
+
+            return Ok(());
+        }
+
+        self.scroll = self.scroll.min(
+            u16::try_from(self.lines.len())
+                .unwrap_or(u16::MAX)
+                .saturating_sub(inner.height),
+        );
+
+        frame.render_widget(
+            Paragraph::new(self.lines.clone()).scroll((self.scroll, 0)),
+            inner,
+        );
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
@@ -1,16 +1,20 @@
+pub mod form;
+
 use std::{cell::RefCell, rc::Rc};
 
 use eyre::{eyre, Result};
 use ratatui::{
     buffer::Buffer,
     layout::{Position, Rect},
+    style::Style,
+    text::Line,
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
 use super::{
     nav::{exit_keys, move_cursor, Movement},
-    Widget,
+    theme, Widget,
 };
 use crate::events::{Broadcast, Event, Keypress};
 
@@ -24,22 +28,46 @@ pub trait ContentExt {
 
 impl ContentExt for Content {}
 
+/// Shown on the right of the border while typing, such as how many rows a
+/// filter matches. `false` marks the content as not being any good, which
+/// turns the border red.
+pub type Feedback = Box<dyn Fn() -> Option<(String, bool)>>;
+
 pub struct Text {
     title: String,
     content: Content,
+    feedback: Option<Feedback>,
+    // In characters, not bytes. Anything pasted in can be outside of ASCII.
     pos: u16,
 }
 
+// Where the character at `pos` starts in `content`, the end if it's past the
+// last one.
+fn offset(content: &str, pos: u16) -> usize {
+    content
+        .char_indices()
+        .nth(pos as usize)
+        .map_or(content.len(), |(idx, _)| idx)
+}
+
 #[bon::bon]
 impl Text {
     #[builder]
-    pub fn new(#[builder(into)] title: String, #[builder(default)] content: Content) -> Self {
+    pub fn new(
+        #[builder(into)] title: String,
+        #[builder(default)] content: Content,
+        feedback: Option<Feedback>,
+    ) -> Self {
         #[allow(clippy::cast_possible_truncation)]
-        let pos = content.borrow().as_ref().map_or(0, String::len) as u16;
+        let pos = content
+            .borrow()
+            .as_ref()
+            .map_or(0, |content| content.chars().count()) as u16;
 
         Self {
             title,
             content,
+            feedback,
             pos,
         }
     }
@@ -52,6 +80,24 @@ impl Text {
 impl Widget for Text {
     // TODO: implement ctrl + a, ctrl + e, ctrl + k, ctrl + u
     fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Event::Paste(data) = event {
+            // This is a single line input, newlines and the like would only end up
+            // mangling the content.
+            let data: String = data.chars().filter(|c| !c.is_control()).collect();
+
+            let mut content = self.content.try_borrow_mut()?;
+            let content = content.get_or_insert_with(String::new);
+
+            content.insert_str(offset(content, self.pos), &data);
+
+            #[allow(clippy::cast_possible_truncation)]
+            {
+                self.pos = self.pos.saturating_add(data.chars().count() as u16);
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
         let Some(key) = event.key() else {
             return Ok(Broadcast::Ignored);
         };
@@ -63,10 +109,10 @@ impl Widget for Text {
                 return Ok(Broadcast::Exited);
             }
             Keypress::Printable(x) => {
-                self.content
-                    .try_borrow_mut()?
-                    .get_or_insert_with(String::new)
-                    .insert(self.pos as usize, *x);
+                let mut content = self.content.try_borrow_mut()?;
+                let content = content.get_or_insert_with(String::new);
+
+                content.insert(offset(content, self.pos), *x);
                 self.pos = self.pos.saturating_add(1);
 
                 return Ok(Broadcast::Consumed);
@@ -76,11 +122,14 @@ impl Widget for Text {
                     break 'outer;
                 }
 
-                self.content
-                    .try_borrow_mut()?
-                    .as_mut()
-                    .ok_or(eyre!("no content"))?
-                    .remove(self.pos as usize - 1);
+                let mut content = self.content.try_borrow_mut()?;
+                let content = content.as_mut().ok_or(eyre!("no content"))?;
+
+                let at = offset(content, self.pos - 1);
+
+                if at < content.len() {
+                    content.remove(at);
+                }
                 self.pos = self.pos.saturating_sub(1);
 
                 return Ok(Broadcast::Consumed);
@@ -93,6 +142,8 @@ impl Widget for Text {
                 *content = String::new();
 
                 self.pos = 0;
+
+                return Ok(Broadcast::Consumed);
             }
             _ => {}
         };
@@ -115,6 +166,14 @@ impl Widget for Text {
             block = block.title(self.title.as_ref());
         }
 
+        if let Some((msg, ok)) = self.feedback.as_ref().and_then(|feedback| feedback()) {
+            block = block.title(Line::from(msg).right_aligned());
+
+            if !ok {
+                block = block.border_style(Style::default().fg(theme::unhealthy()));
+            }
+        }
+
         let cmd_pos = block.inner(area);
         let content = self
             .content
@@ -122,7 +181,7 @@ impl Widget for Text {
             .as_ref()
             .map_or(String::new(), String::clone);
 
-        self.pos = self.pos.clamp(0, content.len() as u16);
+        self.pos = self.pos.clamp(0, content.chars().count() as u16);
 
         let pg = Paragraph::new(content).block(block);
 
@@ -140,3 +199,29 @@ impl Widget for Text {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dispatch(text: &mut Text, event: &Event) {
+        let area = Rect::new(0, 0, 40, 3);
+
+        text.dispatch(event, &Buffer::empty(area), area)
+            .expect("dispatch");
+    }
+
+    #[test]
+    fn edit_after_non_ascii_paste() {
+        let mut text = Text::builder().title("Filter").build();
+
+        dispatch(&mut text, &Event::Paste("héllo".to_string()));
+        dispatch(&mut text, &Event::from(b"!".as_slice()));
+        assert_eq!(text.content().borrow().as_deref(), Some("héllo!"));
+
+        for _ in 0..5 {
+            dispatch(&mut text, &Event::from(b"\x08".as_slice()));
+        }
+        assert_eq!(text.content().borrow().as_deref(), Some("h"));
+    }
+}
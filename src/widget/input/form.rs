@@ -0,0 +1,113 @@
+use eyre::Result;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    Frame,
+};
+
+use super::{Content, ContentExt, Text};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    widget::{nav::exit_keys, Placement, Widget},
+};
+
+/// A stack of text inputs. Tab and the cursor keys move between fields, enter
+/// moves to the next field and submits from the last one. Submitting is
+/// broadcast as `Selected` with the index of the last field.
+pub struct Form {
+    fields: Vec<Text>,
+    focus: usize,
+}
+
+impl Form {
+    /// Create a form from (label, default) pairs.
+    pub fn new<'a>(fields: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        Self {
+            fields: fields
+                .into_iter()
+                .map(|(label, default)| {
+                    Text::builder()
+                        .title(label)
+                        .content(Content::from_string(default))
+                        .build()
+                })
+                .collect(),
+            focus: 0,
+        }
+    }
+
+    pub fn values(&self) -> Vec<String> {
+        self.fields
+            .iter()
+            .map(|field| field.content().borrow().clone().unwrap_or_default())
+            .collect()
+    }
+
+    fn focus(&mut self, offset: isize) {
+        self.focus = self
+            .focus
+            .saturating_add_signed(offset)
+            .min(self.fields.len().saturating_sub(1));
+    }
+}
+
+impl Widget for Form {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let last = self.focus + 1 == self.fields.len();
+
+        // The fields clear their content on exit, which isn't what should happen
+        // when the form as a whole is being closed.
+        match event.key() {
+            Some(exit_keys!()) => return Ok(Broadcast::Exited),
+            Some(Keypress::Enter) if last => {
+                return Ok(Broadcast::Selected(self.focus));
+            }
+            Some(Keypress::HorizontalTab | Keypress::CursorDown | Keypress::Enter) => self.focus(1),
+            Some(Keypress::CursorUp) => self.focus(-1),
+            _ => {
+                let Some(field) = self.fields.get_mut(self.focus) else {
+                    return Ok(Broadcast::Ignored);
+                };
+
+                return field.dispatch(event, buffer, area);
+            }
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let areas = Layout::vertical(self.fields.iter().map(|field| field.placement().vertical))
+            .split(area);
+
+        // Each field sets the cursor position, draw the focused one last so that
+        // its cursor is the one that shows up.
+        for (i, (field, area)) in self.fields.iter_mut().zip(areas.iter()).enumerate() {
+            if i != self.focus {
+                field.draw(frame, *area)?;
+            }
+        }
+
+        if let (Some(field), Some(area)) = (self.fields.get_mut(self.focus), areas.get(self.focus))
+        {
+            field.draw(frame, *area)?;
+        }
+
+        Ok(())
+    }
+
+    fn placement(&self) -> Placement {
+        Placement {
+            vertical: Constraint::Length(
+                self.fields
+                    .iter()
+                    .map(|field| match field.placement().vertical {
+                        Constraint::Length(len) => len,
+                        _ => 0,
+                    })
+                    .sum(),
+            ),
+            ..Default::default()
+        }
+    }
+}
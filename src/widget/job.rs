@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::batch::v1::Job;
+use kube::ResourceExt;
+use tokio::sync::oneshot;
+
+use super::{
+    object::{self, Object},
+    tabs::Tab,
+    yaml::Yaml,
+};
+use crate::resources::{job::JobExt, store::Store};
+
+pub type List = object::List<Job>;
+
+/// A job and the pods it ran. Finished jobs keep their pods around until
+/// they're cleaned up, so the "Pods" tab is where to look for the logs of a run
+/// that failed.
+impl Object for Job {
+    type Items = Arc<Store<Job>>;
+
+    fn items(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Self::Items, oneshot::Receiver<()>) {
+        Store::namespaced(client, namespace)
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.get(idx, filter)
+    }
+
+    fn tabs(client: &kube::Client, _: &Self::Items, job: &Arc<Self>) -> Vec<Tab> {
+        let mut tabs = vec![Yaml::tab(
+            "Overview".to_string(),
+            client.clone(),
+            job.clone(),
+        )];
+
+        tabs.extend(object::pods(
+            client,
+            job.namespace().unwrap_or_default(),
+            job.spec.as_ref().and_then(|spec| spec.selector.clone()),
+        ));
+
+        tabs
+    }
+
+    fn status(_: &Self::Items, job: &Self) -> Option<String> {
+        Some(job.phase().to_string())
+    }
+}
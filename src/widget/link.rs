@@ -0,0 +1,152 @@
+use std::cell::RefCell;
+
+use eyre::Result;
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use serde::Serialize;
+
+use super::{centered_with, nav::exit_keys, Widget};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::link::{self, Link},
+};
+
+// Ratatui works out how wide a cell is from its symbol, so an escape sequence
+// can't be stuck in the buffer without throwing off the rest of the line.
+// Instead, widgets mark where their links are while drawing and the dashboard
+// writes the links out separately once the frame has been drawn. Drawing is
+// synchronous, so a thread local is enough to get from one to the other.
+thread_local! {
+    static LINKS: RefCell<Vec<(Rect, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Mark `area` as a link to `url`. This should be called from `draw`, after
+/// the text has been rendered.
+pub fn register(area: Rect, url: &str) {
+    LINKS.with_borrow_mut(|links| links.push((area, url.to_string())));
+}
+
+/// Take everything registered while drawing and turn it into cells that are
+/// wrapped in OSC 8 hyperlinks. Terminals that don't support OSC 8 ignore the
+/// escapes and show the text as-is.
+pub fn cells(buffer: &Buffer) -> Vec<(u16, u16, Cell)> {
+    let links = LINKS.with_borrow_mut(std::mem::take);
+
+    let mut cells = Vec::new();
+
+    for (area, url) in links {
+        let area = area.intersection(buffer.area);
+
+        for y in area.top()..area.bottom() {
+            let mut row: Vec<_> = (area.left()..area.right())
+                .filter_map(|x| buffer.cell((x, y)).map(|cell| (x, y, cell.clone())))
+                .collect();
+
+            if let Some((_, _, first)) = row.first_mut() {
+                first.set_symbol(&format!("\x1b]8;;{url}\x1b\\{}", first.symbol()));
+            }
+
+            if let Some((_, _, last)) = row.last_mut() {
+                last.set_symbol(&format!("{}\x1b]8;;\x1b\\", last.symbol()));
+            }
+
+            cells.extend(row);
+        }
+    }
+
+    cells
+}
+
+/// Popup listing every link found in an object. The links are clickable in
+/// terminals that support hyperlinks, which is how they get opened in the
+/// browser on the client's side of the connection.
+pub struct Links {
+    links: Vec<Link>,
+}
+
+impl Links {
+    pub fn new<K>(obj: &K) -> Self
+    where
+        K: Serialize,
+    {
+        Self {
+            links: link::find(obj),
+        }
+    }
+}
+
+impl Widget for Links {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, _: Rect) -> Result<Broadcast> {
+        match event.key() {
+            Some(exit_keys!()) => Ok(Broadcast::Exited),
+            Some(_) => Ok(Broadcast::Consumed),
+            None => Ok(Broadcast::Ignored),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        #[allow(clippy::cast_possible_truncation)]
+        let height = (self.links.len() as u16).saturating_mul(2).max(1) + 2;
+
+        let area = centered_with(area, Constraint::Percentage(60), Constraint::Length(height));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Links")
+            .title_bottom(
+                Line::from("click to open, esc to close")
+                    .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        if self.links.is_empty() {
+            frame.render_widget(Paragraph::new("no links found"), inner);
+
+            return Ok(());
+        }
+
+        let rows = Layout::vertical(
+            self.links
+                .iter()
+                .flat_map(|_| [Constraint::Length(1), Constraint::Length(1)]),
+        )
+        .split(inner);
+
+        for (link, rows) in self.links.iter().zip(rows.chunks(2)) {
+            let [name, url] = rows else {
+                continue;
+            };
+
+            frame.render_widget(
+                Line::from(link.name.as_str()).style(Style::default().add_modifier(Modifier::BOLD)),
+                *name,
+            );
+
+            frame.render_widget(
+                Line::from(link.url.as_str()).style(
+                    Style::default()
+                        .fg(tailwind::BLUE.c300)
+                        .add_modifier(Modifier::UNDERLINED),
+                ),
+                *url,
+            );
+
+            #[allow(clippy::cast_possible_truncation)]
+            let width = (link.url.chars().count() as u16).min(url.width);
+
+            register(Rect { width, ..*url }, &link.url);
+        }
+
+        Ok(())
+    }
+}
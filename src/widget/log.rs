@@ -1,5 +1,13 @@
+pub mod attempts;
+pub mod highlight;
+pub mod level;
+pub mod pipe;
+pub mod rate;
+pub mod timestamp;
+
 use std::sync::Arc;
 
+use chrono::{DateTime, TimeDelta, Utc};
 use color_eyre::{Section, SectionExt};
 use eyre::{eyre, Report, Result};
 use futures::{
@@ -11,7 +19,10 @@ use k8s_openapi::api::core::v1::Pod;
 use kube::{api::LogParams, Api, ResourceExt};
 use ratatui::{
     buffer::Buffer,
-    layout::{Position, Rect},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear},
     Frame,
 };
 use tokio::{
@@ -19,14 +30,28 @@ use tokio::{
     task::JoinHandle,
 };
 
+use self::{
+    highlight::HIGHLIGHTER,
+    level::SetLevel,
+    pipe::{Output, Pipe},
+    rate::{Rate, Sampler},
+    timestamp::Timeline,
+};
 use super::{
+    centered,
+    input::{Content, Text},
     nav::{move_cursor, Movement},
+    pod::picker::{Answer, Choice, Picker},
+    propagate,
+    scroll::Scroll,
+    scrollback::MAX_LINES,
     tabs::Tab,
+    theme,
     viewport::Viewport,
-    Widget, WIDGET_VIEWS,
+    Action, BoxWidget, Widget, WIDGET_VIEWS,
 };
 use crate::{
-    events::{Broadcast, Event},
+    events::{Broadcast, Event, Keypress},
     resources::{
         container::{Container, ContainerExt},
         pod::PodExt,
@@ -34,10 +59,27 @@ use crate::{
 };
 
 pub struct Log {
+    client: kube::Client,
+    pod: Arc<Pod>,
     task: JoinHandle<Result<()>>,
 
     rx: mpsc::UnboundedReceiver<String>,
     buffer: Vec<String>,
+    times: Vec<Option<DateTime<Utc>>>,
+    // Set while timestamps are being shown.
+    timeline: Option<Timeline>,
+    gaps: bool,
+    rate: Rate,
+    sampler: Sampler,
+
+    // Which container to pipe through, asked for first when there's more than
+    // one.
+    picker: Option<Picker<Arc<Pod>>>,
+    container: Option<Container>,
+    prompt: Option<Text>,
+    command: Content,
+    pipe: Option<Pipe>,
+    popup: Option<BoxWidget>,
 
     position: Position,
 }
@@ -47,7 +89,6 @@ pub struct Log {
 //   stateful).
 // - Allow for searching within the logs. Feels like it should be ala fzf and
 //   jump to the text + highlight it.
-// - Keep the buffer bounded to a certain size.
 // - Only fetch the most recent X lines, on scroll-back, fetch more.
 // - Convert into something more general, this is fundamentally the same thing
 //   as the yaml widget - but without the syntax highlighting. There should
@@ -62,21 +103,36 @@ impl Log {
 
         // TODO: this should be a function call.
         let task = tokio::spawn(log_stream(
-            client,
-            pod,
+            client.clone(),
+            pod.clone(),
             tx,
             LogParams {
                 follow: true,
                 pretty: true,
                 previous: true,
+                timestamps: true,
                 ..Default::default()
             },
         ));
 
         Self {
+            client,
+            pod,
             task,
             rx,
             buffer: Vec::new(),
+            times: Vec::new(),
+            timeline: None,
+            gaps: true,
+            rate: Rate::default(),
+            sampler: Sampler::new(crate::config::get().logs.max_rate),
+
+            picker: None,
+            container: None,
+            prompt: None,
+            command: Content::default(),
+            pipe: None,
+            popup: None,
 
             position: Position::default(),
         }
@@ -94,23 +150,221 @@ impl Log {
     }
 
     fn update(&mut self) -> u16 {
-        let mut i = 0;
+        let (mut lines, mut bytes, mut kept) = (0, 0, 0_usize);
 
         while let Ok(line) = self.rx.try_recv() {
-            self.buffer.push(line);
-            i += 1;
+            lines += 1;
+            bytes += line.len();
+
+            if self.sampler.keep() {
+                let (time, line) = timestamp::split(line);
+
+                if let Some(timeline) = self.timeline.as_mut() {
+                    timeline.push(time, &line);
+                }
+
+                self.times.push(time);
+                self.buffer.push(line);
+                kept += 1;
+            }
+        }
+
+        self.rate.record(lines, bytes);
+        self.sampler.update(self.rate.lines());
+        self.trim();
+
+        u16::try_from(kept).unwrap_or(u16::MAX)
+    }
+
+    // Like `ScrollBack`, the buffer goes over a little before the oldest lines
+    // are dropped so that it isn't shifted for every new one.
+    #[allow(clippy::cast_possible_truncation)]
+    fn trim(&mut self) {
+        let mut dropped = 0;
+
+        if self.buffer.len() > MAX_LINES + MAX_LINES / 10 {
+            dropped = self.buffer.len() - MAX_LINES;
+
+            self.buffer.drain(..dropped);
+            self.times.drain(..dropped);
+        }
+
+        if let Some(timeline) = self.timeline.as_mut() {
+            dropped = 0;
+
+            if timeline.lines.len() > MAX_LINES + MAX_LINES / 10 {
+                dropped = timeline.lines.len() - MAX_LINES;
+
+                timeline.lines.drain(..dropped);
+            }
+        }
+
+        // Whatever was being looked at stays on screen, piped output is a copy
+        // and doesn't move.
+        if self.pipe.is_none() && self.position.y != u16::MAX {
+            self.position.y = self.position.y.saturating_sub(dropped as u16);
+        }
+    }
+
+    fn show_timestamps(&mut self, show: bool) {
+        if !show {
+            self.timeline = None;
+
+            return;
+        }
+
+        let min_gap = crate::config::get().logs.min_gap;
+
+        let mut timeline = Timeline::new(
+            (self.gaps && min_gap > 0).then(|| TimeDelta::seconds(i64::from(min_gap))),
+        );
+
+        for (time, line) in self.times.iter().zip(&self.buffer) {
+            timeline.push(*time, line);
+        }
+
+        self.timeline = Some(timeline);
+    }
+
+    fn ask(&mut self, container: Container) {
+        self.prompt = Some(
+            Text::builder()
+                .title(format!(
+                    "Pipe through (runs in {}, empty to clear)",
+                    container.name_any()
+                ))
+                .content(self.command.clone())
+                .build(),
+        );
+        self.container = Some(container);
+    }
+
+    fn dispatch_picker(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(picker) = self.picker.as_mut() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match picker.dispatch(event, buffer, area)? {
+            Answer::Picked(container) => {
+                self.picker = None;
+                self.ask(container);
+            }
+            Answer::Cancelled => self.picker = None,
+            Answer::Waiting => {}
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn dispatch_prompt(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match prompt.dispatch(event, buffer, area)? {
+            Broadcast::Exited => {
+                self.prompt = None;
+            }
+            _ if matches!(event.key(), Some(Keypress::Enter)) => {
+                self.prompt = None;
+
+                let command = self
+                    .command
+                    .try_borrow()?
+                    .clone()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+
+                // An empty command goes back to the logs themselves.
+                self.pipe = match self.container.as_ref() {
+                    Some(container) if !command.is_empty() => Some(Pipe::new(
+                        self.client.clone(),
+                        self.pod.clone(),
+                        container.name_any(),
+                        command,
+                        &self.buffer,
+                    )),
+                    _ => None,
+                };
+                self.position = Position::default();
+            }
+            _ => {}
         }
 
-        i
+        Ok(Broadcast::Consumed)
+    }
+
+    fn status(&mut self) -> Line<'static> {
+        let style = Style::default().fg(tailwind::GRAY.c400);
+
+        let Some(pipe) = self.pipe.as_mut() else {
+            let help = if self.timeline.is_some() {
+                "|: pipe through a command, t: hide timestamps, g: toggle gap markers, L: log level"
+            } else {
+                "|: pipe through a command, t: show timestamps, L: log level"
+            };
+
+            return Line::from(help).style(style);
+        };
+
+        let command = format!("| {}", pipe.command);
+
+        match pipe.output() {
+            Output::Running(_) => Line::from(format!("{command} (running...)")).style(style),
+            Output::Done(_) => Line::from(command).style(style),
+            Output::Failed(err) => Line::from(format!("{command}: {err}"))
+                .style(Style::default().fg(theme::unhealthy())),
+        }
     }
 }
 
 impl Widget for Log {
-    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(popup) = self.popup.as_mut() {
+            if matches!(popup.dispatch(event, buffer, area)?, Broadcast::Exited) {
+                self.popup = None;
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        propagate!(self.dispatch_picker(event, buffer, area));
+        propagate!(self.dispatch_prompt(event, buffer, area));
+
         let Some(key) = event.key() else {
             return Ok(Broadcast::Ignored);
         };
 
+        if matches!(key, Keypress::Printable('|')) {
+            match Picker::new(self.pod.clone()) {
+                Choice::Only(container) => self.ask(container),
+                Choice::Ask(picker) => self.picker = Some(picker),
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        match key {
+            Keypress::Printable('t') => {
+                self.show_timestamps(self.timeline.is_none());
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable('L') => {
+                self.popup = Some(SetLevel::new(self.client.clone(), self.pod.clone()).boxed());
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable('g') if self.timeline.is_some() => {
+                self.gaps = !self.gaps;
+                self.show_timestamps(true);
+
+                return Ok(Broadcast::Consumed);
+            }
+            _ => {}
+        }
+
         if let Some(Movement::Y(y)) = move_cursor(key, area) {
             self.position.y = self.position.y.saturating_add_signed(y);
 
@@ -120,20 +374,80 @@ impl Widget for Log {
         Ok(Broadcast::Ignored)
     }
 
+    fn actions(&self) -> Vec<Action> {
+        if self.popup.is_some() || self.picker.is_some() || self.prompt.is_some() {
+            return Vec::new();
+        }
+
+        let mut actions = vec![
+            Action::new('|', "Pipe the logs through a command"),
+            Action::new('L', "Change the log level"),
+        ];
+
+        if self.timeline.is_some() {
+            actions.push(Action::new('t', "Hide timestamps"));
+            actions.push(Action::new('g', "Toggle gap markers"));
+        } else {
+            actions.push(Action::new('t', "Show timestamps"));
+        }
+
+        actions
+    }
+
     #[allow(clippy::cast_possible_truncation)]
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        let lines = self.update();
+        let mut lines = self.update();
+
+        let [title, area, input] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(0),
+            Constraint::Length(if self.prompt.is_some() { 3 } else { 0 }),
+        ])
+        .areas(area);
+
+        frame.render_widget(self.status(), title);
+
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.draw(frame, input)?;
+        }
+
+        // Piped output is a snapshot, it doesn't grow.
+        if self.pipe.is_some() {
+            lines = 0;
+        }
+
+        let empty = Vec::new();
+        let shown = match self.pipe.as_mut().map(Pipe::output) {
+            Some(Output::Done(output)) => output,
+            Some(_) => &empty,
+            None => self
+                .timeline
+                .as_ref()
+                .map_or(&self.buffer, |timeline| &timeline.lines),
+        };
 
         if self
             .position
             .y
             .saturating_add(lines)
             .saturating_add(area.height)
-            >= self.buffer.len() as u16
+            >= shown.len() as u16
         {
             self.position.y = u16::MAX;
         }
 
+        let height = area.height as usize;
+
+        if let Some(line) = (Scroll {
+            offset: (self.position.y as usize).min(shown.len().saturating_sub(height)),
+            height,
+            total: shown.len(),
+        })
+        .title(Some(&self.sampler.summary(&self.rate)))
+        {
+            frame.render_widget(line, title);
+        }
+
         if self.task.is_finished() {
             let task = &mut self.task;
 
@@ -152,10 +466,35 @@ impl Widget for Log {
         }
 
         Viewport::builder()
-            .buffer(&self.buffer)
+            .buffer(shown)
             .view(self.position)
+            .highlight(&HIGHLIGHTER)
             .build()
-            .draw(frame, area)
+            .draw(frame, area)?;
+
+        if let Some(picker) = self.picker.as_mut() {
+            let area = centered(area);
+
+            frame.render_widget(Clear, area);
+
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title("Pipe Through")
+                .title_bottom(
+                    Line::from("enter: select container, esc: cancel")
+                        .style(Style::default().fg(tailwind::GRAY.c400)),
+                );
+            let inner = block.inner(area);
+
+            frame.render_widget(block, area);
+            picker.draw(frame, inner)?;
+        }
+
+        if let Some(popup) = self.popup.as_mut() {
+            popup.draw(frame, area)?;
+        }
+
+        Ok(())
     }
 }
 
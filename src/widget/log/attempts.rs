@@ -0,0 +1,296 @@
+use eyre::{eyre, Result};
+use itertools::Itertools;
+use k8s_openapi::api::core::v1::{ContainerStatus, Pod};
+use kube::{
+    api::{DynamicObject, ListParams, LogParams},
+    Api, ResourceExt,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::highlight::HIGHLIGHTER;
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    widget::{
+        nav::{exit_keys, move_cursor, Movement},
+        scroll::Scroll,
+        theme,
+        viewport::Viewport,
+        Widget,
+    },
+};
+
+static COMPLETION_INDEX: &str = "batch.kubernetes.io/job-completion-index";
+
+/// Logs from every pod that a job has created, oldest first, with a header in
+/// front of each attempt. A failed job leaves its evidence spread over pods
+/// that are all dead, this puts it in one place. The logs are fetched once
+/// rather than followed.
+pub struct Attempts {
+    job: String,
+    task: JoinHandle<Result<Vec<String>>>,
+
+    lines: Option<Result<Vec<String>, String>>,
+    position: Position,
+}
+
+impl Attempts {
+    pub fn new(client: kube::Client, job: &DynamicObject) -> Self {
+        let task = tokio::spawn(collect(
+            client,
+            job.namespace().unwrap_or_default(),
+            selector(job),
+        ));
+
+        Self {
+            job: job.name_any(),
+            task,
+            lines: None,
+            position: Position::default(),
+        }
+    }
+
+    fn error(err: &eyre::Report) -> String {
+        match err.downcast_ref::<kube::Error>() {
+            Some(kube::Error::Api(resp)) => resp.message.clone(),
+            _ => err.to_string(),
+        }
+    }
+
+    fn poll(&mut self) {
+        if self.lines.is_some() || !self.task.is_finished() {
+            return;
+        }
+
+        let task = &mut self.task;
+
+        self.lines = Some(
+            futures::executor::block_on(async move { task.await? })
+                .map_err(|err| Self::error(&err)),
+        );
+    }
+}
+
+// Jobs pick their pods with a label that has their uid in it, which keeps pods
+// from a previous job with the same name out. The name is a fallback for jobs
+// that don't have a selector yet.
+fn selector(job: &DynamicObject) -> String {
+    job.data
+        .pointer("/spec/selector/matchLabels")
+        .and_then(|labels| labels.as_object())
+        .filter(|labels| !labels.is_empty())
+        .map_or_else(
+            || format!("job-name={}", job.name_any()),
+            |labels| {
+                labels
+                    .iter()
+                    .map(|(key, value)| format!("{key}={}", value.as_str().unwrap_or_default()))
+                    .join(",")
+            },
+        )
+}
+
+async fn collect(client: kube::Client, namespace: String, selector: String) -> Result<Vec<String>> {
+    let api = Api::<Pod>::namespaced(client, &namespace);
+
+    let pods = api
+        .list(&ListParams::default().labels(&selector))
+        .await?
+        .items
+        .into_iter()
+        .sorted_by_key(|pod| pod.metadata.creation_timestamp.as_ref().map(|time| time.0))
+        .collect_vec();
+
+    if pods.is_empty() {
+        return Err(eyre!(
+            "the job has no pods, they may have been cleaned up by its ttlSecondsAfterFinished"
+        ));
+    }
+
+    let total = pods.len();
+    let mut lines = Vec::new();
+
+    for (i, pod) in pods.iter().enumerate() {
+        if i > 0 {
+            lines.push(String::new());
+        }
+
+        lines.push(header(i + 1, total, pod));
+
+        let statuses = pod
+            .status
+            .as_ref()
+            .and_then(|status| status.container_statuses.as_ref());
+
+        let containers = pod
+            .spec
+            .iter()
+            .flat_map(|spec| &spec.containers)
+            .map(|container| container.name.as_str())
+            .collect_vec();
+
+        for name in &containers {
+            let restarts = statuses
+                .and_then(|statuses| statuses.iter().find(|status| status.name == *name))
+                .map_or(0, |status| status.restart_count);
+
+            // With `restartPolicy: OnFailure` the retries happen inside of the
+            // same pod. Only the run before the current one is kept around.
+            if restarts > 0 {
+                lines.push(format!("--- {name}, previous run ---"));
+                lines.extend(logs(&api, pod, name, true).await);
+            }
+
+            if containers.len() > 1 || restarts > 0 {
+                lines.push(format!("--- {name} ---"));
+            }
+
+            lines.extend(logs(&api, pod, name, false).await);
+        }
+    }
+
+    Ok(lines)
+}
+
+async fn logs(api: &Api<Pod>, pod: &Pod, container: &str, previous: bool) -> Vec<String> {
+    let params = LogParams {
+        container: Some(container.to_string()),
+        previous,
+        ..Default::default()
+    };
+
+    match api.logs(&pod.name_any(), &params).await {
+        Ok(logs) if logs.is_empty() => vec!["(no output)".to_string()],
+        Ok(logs) => logs.lines().map(str::to_string).collect(),
+        Err(kube::Error::Api(resp)) => vec![format!("(logs unavailable: {})", resp.message)],
+        Err(err) => vec![format!("(logs unavailable: {err})")],
+    }
+}
+
+fn header(attempt: usize, total: usize, pod: &Pod) -> String {
+    let mut details = vec![pod
+        .status
+        .as_ref()
+        .and_then(|status| status.phase.clone())
+        .unwrap_or_else(|| "Unknown".to_string())];
+
+    if let Some(index) = pod.labels().get(COMPLETION_INDEX) {
+        details.push(format!("index {index}"));
+    }
+
+    details.extend(
+        pod.status
+            .iter()
+            .flat_map(|status| status.container_statuses.iter().flatten())
+            .filter_map(exit),
+    );
+
+    format!(
+        "=== attempt {attempt}/{total}: {} ({}) ===",
+        pod.name_any(),
+        details.join(", ")
+    )
+}
+
+fn exit(status: &ContainerStatus) -> Option<String> {
+    let terminated = status.state.as_ref()?.terminated.as_ref()?;
+
+    Some(match &terminated.reason {
+        Some(reason) => format!("{} exited {} ({reason})", status.name, terminated.exit_code),
+        None => format!("{} exited {}", status.name, terminated.exit_code),
+    })
+}
+
+impl Widget for Attempts {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(key, exit_keys!()) {
+            return Ok(Broadcast::Exited);
+        }
+
+        if let Some(Movement::Y(y)) = move_cursor(key, area) {
+            self.position.y = self.position.y.saturating_add_signed(y);
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        frame.render_widget(Clear, area);
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Every attempt of {}", self.job))
+            .title_bottom(Line::from("esc: close").style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        match &self.lines {
+            None => {
+                frame.render_widget(block, area);
+                frame.render_widget(
+                    Paragraph::new("fetching logs from the job's pods..."),
+                    inner,
+                );
+            }
+            Some(Err(err)) => {
+                frame.render_widget(block, area);
+                frame.render_widget(
+                    Paragraph::new(err.as_str())
+                        .style(Style::default().fg(theme::unhealthy()))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+            Some(Ok(lines)) => {
+                let height = inner.height as usize;
+                let offset = (self.position.y as usize).min(lines.len().saturating_sub(height));
+
+                self.position.y = offset as u16;
+
+                if let Some(indicator) = (Scroll {
+                    offset,
+                    height,
+                    total: lines.len(),
+                })
+                .title(None)
+                {
+                    block = block.title(indicator);
+                }
+
+                frame.render_widget(block, area);
+
+                Viewport::builder()
+                    .buffer(lines)
+                    .view(self.position)
+                    .highlight(&HIGHLIGHTER)
+                    .build()
+                    .draw(frame, inner)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Attempts {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::LazyLock;
+
+use ansi_to_tui::IntoText;
+use eyre::Result;
+use ratatui::{
+    style::Style,
+    text::{Line, Span, Text},
+};
+use regex::{Regex, RegexSet};
+
+use crate::config::{self, Highlight, Scope};
+
+pub static HIGHLIGHTER: LazyLock<Highlighter> = LazyLock::new(|| {
+    Highlighter::new(&config::get().logs.highlight).expect("patterns validated on config load")
+});
+
+struct Rule {
+    regex: Regex,
+    scope: Scope,
+    style: Style,
+}
+
+/// Applies the configured highlight rules to log lines. Every line goes
+/// through the `RegexSet` first so that lines without any matches (the vast
+/// majority) only pay for a single pass. The individual regexes are only run
+/// for rules that are known to match.
+pub struct Highlighter {
+    set: RegexSet,
+    rules: Vec<Rule>,
+}
+
+impl Highlighter {
+    pub fn new(rules: &[Highlight]) -> Result<Self> {
+        Ok(Self {
+            set: RegexSet::new(rules.iter().map(|rule| rule.pattern.as_str()))?,
+            rules: rules
+                .iter()
+                .map(|rule| {
+                    Ok(Rule {
+                        regex: Regex::new(&rule.pattern)?,
+                        scope: rule.scope,
+                        style: (&rule.style).into(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    pub fn highlight<'a>(&self, line: &'a str) -> Result<Text<'a>> {
+        let matched = self.set.matches(line);
+
+        if !matched.matched_any() {
+            return Ok(line.into_text()?);
+        }
+
+        let rules = matched.iter().map(|i| &self.rules[i]);
+
+        let line_style = rules
+            .clone()
+            .filter(|rule| matches!(rule.scope, Scope::Line))
+            .fold(Style::default(), |style, rule| style.patch(rule.style));
+
+        // Lines that already have color are left alone other than the line level
+        // styling, splitting ANSI styled spans on match boundaries isn't worth the
+        // complexity.
+        if line.contains('\x1b') {
+            return Ok(line.into_text()?.patch_style(line_style));
+        }
+
+        let mut ranges: Vec<_> = rules
+            .filter(|rule| matches!(rule.scope, Scope::Match))
+            .flat_map(|rule| {
+                rule.regex
+                    .find_iter(line)
+                    .map(|m| (m.start(), m.end(), rule.style))
+            })
+            .collect();
+
+        // When matches overlap, the one that starts first wins.
+        ranges.sort_by_key(|(start, ..)| *start);
+
+        let mut spans = Vec::new();
+        let mut pos = 0;
+
+        for (start, end, style) in ranges {
+            if start < pos || start == end {
+                continue;
+            }
+
+            if start > pos {
+                spans.push(Span::raw(&line[pos..start]));
+            }
+
+            spans.push(Span::styled(&line[start..end], style));
+            pos = end;
+        }
+
+        if pos < line.len() {
+            spans.push(Span::raw(&line[pos..]));
+        }
+
+        Ok(Line::from(spans).style(line_style).into())
+    }
+}
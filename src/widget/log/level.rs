@@ -0,0 +1,314 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::Pod;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Row, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        container::ContainerExt,
+        pod::{
+            level::{self, Endpoint, LEVELS},
+            PodExt,
+        },
+    },
+    widget::{
+        centered,
+        nav::exit_keys,
+        pod::picker::{Answer, Choice, Picker},
+        table, theme, Widget,
+    },
+};
+
+lazy_static! {
+    static ref LEVEL_CHANGES: IntCounterVec = register_int_counter_vec!(
+        "log_level_changes_total",
+        "Number of times the log level of a container was changed, by endpoint api and whether \
+         it succeeded",
+        &["api", "result"]
+    )
+    .unwrap();
+}
+
+#[derive(Clone)]
+struct Target {
+    container: String,
+    endpoint: Endpoint,
+}
+
+impl table::Row for Target {
+    fn columns() -> Vec<&'static str> {
+        vec!["Container", "Endpoint"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.container.clone(), self.endpoint.to_string()]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+}
+
+#[derive(Clone)]
+struct Targets(Vec<Target>);
+
+impl table::Items for Targets {
+    type Item = Target;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.clone()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Level(&'static str);
+
+impl table::Row for Level {
+    fn columns() -> Vec<&'static str> {
+        vec!["Level"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.0.to_string()]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+}
+
+struct Levels;
+
+impl table::Items for Levels {
+    type Item = Level;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        LEVELS.iter().copied().map(Level).collect()
+    }
+}
+
+enum Stage {
+    Container(Picker<Targets>),
+    Pick(Target, table::Table<Levels>),
+    Sending(JoinHandle<Result<String>>),
+    Done(Result<String, String>),
+}
+
+/// Change the log level of a container at runtime through its admin endpoint,
+/// such as Spring Boot's actuator. Endpoints come from the pod's annotations
+/// or the server's config, containers without one aren't offered.
+#[allow(clippy::module_name_repetitions)]
+pub struct SetLevel {
+    client: kube::Client,
+    pod: Arc<Pod>,
+
+    stage: Stage,
+}
+
+impl SetLevel {
+    pub fn new(client: kube::Client, pod: Arc<Pod>) -> Self {
+        let targets: Vec<_> = pod
+            .containers(None)
+            .iter()
+            .filter_map(|container| {
+                let container = container.name_any();
+
+                Some(Target {
+                    endpoint: level::endpoint(&pod, &container)?,
+                    container,
+                })
+            })
+            .collect();
+
+        let stage = if targets.is_empty() {
+            Stage::Done(Err(format!(
+                "none of the containers have a log level endpoint, add a `{}<container>` \
+                 annotation to the pod or configure one on the server",
+                level::ANNOTATION_PREFIX
+            )))
+        } else {
+            match Picker::new(Targets(targets)) {
+                Choice::Only(target) => Self::pick(target),
+                Choice::Ask(picker) => Stage::Container(picker),
+            }
+        };
+
+        Self { client, pod, stage }
+    }
+
+    fn pick(target: Target) -> Stage {
+        Stage::Pick(
+            target,
+            table::Table::builder().items(Levels).border(false).build(),
+        )
+    }
+
+    fn send(&self, target: Target, level: &'static str) -> Stage {
+        let client = self.client.clone();
+        let pod = self.pod.clone();
+
+        Stage::Sending(tokio::spawn(async move {
+            let result = level::set(client, &pod, &target.endpoint, level).await;
+
+            LEVEL_CHANGES
+                .with_label_values(&[
+                    target.endpoint.api.to_string().as_str(),
+                    if result.is_ok() { "success" } else { "failure" },
+                ])
+                .inc();
+
+            result.map(|_| format!("{} now logs at {level}", target.container))
+        }))
+    }
+
+    fn advance(
+        &mut self,
+        stage: Stage,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<(Stage, Broadcast)> {
+        let stage = match stage {
+            Stage::Container(mut picker) => match picker.dispatch(event, buffer, area)? {
+                Answer::Picked(target) => Self::pick(target),
+                Answer::Cancelled => return Ok((Stage::Container(picker), Broadcast::Exited)),
+                Answer::Waiting => Stage::Container(picker),
+            },
+            Stage::Pick(target, mut table) => match table.dispatch(event, buffer, area)? {
+                Broadcast::Selected(idx) => match LEVELS.get(idx) {
+                    Some(level) => self.send(target, level),
+                    None => Stage::Pick(target, table),
+                },
+                _ if matches!(event.key(), Some(exit_keys!())) => {
+                    return Ok((Stage::Pick(target, table), Broadcast::Exited))
+                }
+                _ => Stage::Pick(target, table),
+            },
+            Stage::Sending(task) => Stage::Sending(task),
+            Stage::Done(result) => {
+                if event.key().is_some() {
+                    return Ok((Stage::Done(result), Broadcast::Exited));
+                }
+
+                Stage::Done(result)
+            }
+        };
+
+        Ok((stage, Broadcast::Consumed))
+    }
+
+    fn finished(task: &mut JoinHandle<Result<String>>) -> Stage {
+        let result = futures::executor::block_on(async move { task.await? });
+
+        Stage::Done(
+            result.map_err(|err| match err.downcast_ref::<kube::Error>() {
+                Some(kube::Error::Api(resp)) => resp.message.clone(),
+                _ => err.to_string(),
+            }),
+        )
+    }
+
+    fn title(&self) -> String {
+        match &self.stage {
+            Stage::Pick(target, _) => format!("Log Level of {}", target.container),
+            _ => "Log Level".to_string(),
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self.stage {
+            Stage::Container(_) => "enter: select container, esc: cancel",
+            Stage::Pick(..) => "enter: set level, esc: cancel",
+            Stage::Sending(_) => "",
+            Stage::Done(_) => "press any key to go back to the logs",
+        }
+    }
+}
+
+impl Widget for SetLevel {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if event.key().is_none() {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        match self.advance(stage, event, buffer, area) {
+            Ok((stage, result)) => {
+                self.stage = stage;
+
+                Ok(result)
+            }
+            Err(err) => {
+                self.stage = Stage::Done(Err(err.to_string()));
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Stage::Sending(task) = &mut self.stage {
+            if task.is_finished() {
+                self.stage = Self::finished(task);
+            }
+        }
+
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title())
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Container(picker) => picker.draw(frame, inner)?,
+            Stage::Pick(_, table) => table.draw(frame, inner)?,
+            Stage::Sending(_) => frame.render_widget(Paragraph::new("sending..."), inner),
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for SetLevel {
+    fn drop(&mut self) {
+        if let Stage::Sending(task) = &self.stage {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use eyre::{eyre, Result};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    api::{Api, AttachParams},
+    ResourceExt,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    task::JoinHandle,
+};
+
+use crate::resources::status::StatusExt;
+
+pub enum Output {
+    Running(JoinHandle<Result<Vec<String>>>),
+    Done(Vec<String>),
+    Failed(String),
+}
+
+/// The log buffer as it was when the command was run, passed through a shell
+/// pipeline in one of the pod's containers. Running it in the container keeps
+/// the command behind the same `pods/exec` permission as a shell does,
+/// instead of running anything on the server itself.
+pub struct Pipe {
+    pub command: String,
+    output: Output,
+}
+
+impl Pipe {
+    pub fn new(
+        client: kube::Client,
+        pod: Arc<Pod>,
+        container: String,
+        command: String,
+        input: &[String],
+    ) -> Self {
+        let mut input = input.join("\n");
+        input.push('\n');
+
+        let task = tokio::spawn({
+            let command = command.clone();
+
+            async move { run(client, &pod, container, &command, input).await }
+        });
+
+        Self {
+            command,
+            output: Output::Running(task),
+        }
+    }
+
+    pub fn output(&mut self) -> &Output {
+        if let Output::Running(task) = &mut self.output {
+            if task.is_finished() {
+                let result = futures::executor::block_on(async move { task.await? });
+
+                self.output = match result {
+                    Ok(lines) => Output::Done(lines),
+                    Err(err) => Output::Failed(match err.downcast_ref::<kube::Error>() {
+                        Some(kube::Error::Api(resp)) => resp.message.clone(),
+                        _ => err.to_string(),
+                    }),
+                };
+            }
+        }
+
+        &self.output
+    }
+}
+
+impl Drop for Pipe {
+    fn drop(&mut self) {
+        if let Output::Running(task) = &self.output {
+            task.abort();
+        }
+    }
+}
+
+async fn run(
+    client: kube::Client,
+    pod: &Pod,
+    container: String,
+    command: &str,
+    input: String,
+) -> Result<Vec<String>> {
+    // Closing stdin closes the whole connection, output included. Instead, the
+    // pipeline is only given as many bytes as there are in the buffer so that
+    // it sees the end of its input while stdin stays open.
+    let script = format!("head -c \"$0\" | {command}");
+
+    let mut proc = Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default())
+        .exec(
+            &pod.name_any(),
+            vec![
+                "sh",
+                "-c",
+                script.as_str(),
+                input.len().to_string().as_str(),
+            ],
+            &AttachParams {
+                container: Some(container),
+                stdin: true,
+                stdout: true,
+                stderr: true,
+                tty: false,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    let status = proc
+        .take_status()
+        .ok_or_else(|| eyre!("status not available"))?;
+
+    let mut stdin = proc.stdin().ok_or_else(|| eyre!("stdin not available"))?;
+    let mut stdout = proc.stdout().ok_or_else(|| eyre!("stdout not available"))?;
+    let mut stderr = proc.stderr().ok_or_else(|| eyre!("stderr not available"))?;
+
+    let (mut out, mut err) = (String::new(), String::new());
+
+    // Everything happens at once, a pipeline that writes as it reads would
+    // otherwise fill up its output and stop reading.
+    tokio::try_join!(
+        stdin.write_all(input.as_bytes()),
+        stdout.read_to_string(&mut out),
+        stderr.read_to_string(&mut err),
+    )?;
+
+    let status = status.await.ok_or_else(|| eyre!("status not available"))?;
+
+    drop(stdin);
+    proc.join().await?;
+
+    // `grep` exits with 1 when nothing matched, which isn't worth an error.
+    if !status.is_success() && !err.trim().is_empty() {
+        return Err(eyre!("{}", err.trim()));
+    }
+
+    Ok(out.lines().map(ToString::to_string).collect())
+}
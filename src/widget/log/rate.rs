@@ -0,0 +1,110 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::resources::quantity;
+
+// Short enough that a burst, such as the initial history of a container,
+// doesn't keep the stream sampled for long after it is over.
+static WINDOW: Duration = Duration::from_secs(1);
+
+/// Incoming volume of a log stream over the last second.
+#[derive(Default)]
+pub struct Rate {
+    samples: VecDeque<(Instant, usize, usize)>,
+}
+
+impl Rate {
+    pub fn record(&mut self, lines: usize, bytes: usize) {
+        let now = Instant::now();
+
+        self.samples.push_back((now, lines, bytes));
+
+        while self
+            .samples
+            .front()
+            .is_some_and(|(at, ..)| now.duration_since(*at) > WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn per_second(&self, value: impl Fn(&(Instant, usize, usize)) -> usize) -> f64 {
+        self.samples.iter().map(value).sum::<usize>() as f64 / WINDOW.as_secs_f64()
+    }
+
+    pub fn lines(&self) -> f64 {
+        self.per_second(|(_, lines, _)| *lines)
+    }
+
+    pub fn bytes(&self) -> f64 {
+        self.per_second(|(.., bytes)| *bytes)
+    }
+}
+
+/// Keeps every nth line once a stream is faster than `max` lines per second,
+/// so that a noisy container can't grow the buffer faster than it can be
+/// rendered. A `max` of 0 keeps everything.
+pub struct Sampler {
+    max: u32,
+    every: usize,
+    seen: usize,
+    skipped: usize,
+}
+
+impl Sampler {
+    pub fn new(max: u32) -> Self {
+        Self {
+            max,
+            every: 1,
+            seen: 0,
+            skipped: 0,
+        }
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn update(&mut self, lines: f64) {
+        self.every = if self.max == 0 {
+            1
+        } else {
+            (lines / f64::from(self.max)).ceil().max(1.0) as usize
+        };
+    }
+
+    pub fn keep(&mut self) -> bool {
+        self.seen = self.seen.wrapping_add(1);
+
+        let keep = self.seen % self.every == 0;
+        if !keep {
+            self.skipped += 1;
+        }
+
+        keep
+    }
+
+    /// Description of the incoming rate, along with how much is being skipped
+    /// when sampling.
+    pub fn summary(&self, rate: &Rate) -> String {
+        let mut summary = format!(
+            "{:.0} lines/s, {}B/s",
+            rate.lines(),
+            quantity::bytes(rate.bytes())
+        );
+
+        if self.every > 1 {
+            summary.push_str(&format!(" (sampling 1/{})", self.every));
+        }
+
+        if self.skipped > 0 {
+            summary.push_str(&format!(", {} skipped", self.skipped));
+        }
+
+        summary
+    }
+}
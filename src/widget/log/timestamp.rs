@@ -0,0 +1,62 @@
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::resources::age::Age;
+
+static FORMAT: &str = "%m-%d %H:%M:%S%.3f";
+// Width of `FORMAT`, so that lines without a timestamp still line up.
+static WIDTH: usize = 18;
+
+/// Split off the timestamp that the API server puts at the start of each line
+/// when `timestamps` is set. Lines without one are left as they are.
+pub fn split(line: String) -> (Option<DateTime<Utc>>, String) {
+    let (ts, rest) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+
+    match DateTime::parse_from_rfc3339(ts) {
+        Ok(ts) => (Some(ts.to_utc()), rest.to_string()),
+        Err(_) => (None, line),
+    }
+}
+
+/// Lines as they're shown with timestamps. When there's a `min_gap`, a marker
+/// goes between any two lines that are further apart than it so that hangs
+/// and stalls stand out instead of having to be spotted in the timestamps.
+pub struct Timeline {
+    min_gap: Option<TimeDelta>,
+    last: Option<DateTime<Utc>>,
+
+    pub lines: Vec<String>,
+}
+
+impl Timeline {
+    pub fn new(min_gap: Option<TimeDelta>) -> Self {
+        Self {
+            min_gap,
+            last: None,
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, time: Option<DateTime<Utc>>, line: &str) {
+        if let (Some(min_gap), Some(last), Some(time)) = (self.min_gap, self.last, time) {
+            let gap = time - last;
+
+            if gap > min_gap {
+                self.lines.push(format!(
+                    "{} {} gap {}",
+                    "─".repeat(WIDTH),
+                    gap.to_age(),
+                    "─".repeat(8)
+                ));
+            }
+        }
+
+        // Lines from each container are interleaved, so they're not quite in
+        // order. Going by the latest one keeps a container that is behind from
+        // looking like a gap.
+        self.last = self.last.max(time);
+
+        let stamp = time.map_or_else(|| " ".repeat(WIDTH), |time| time.format(FORMAT).to_string());
+
+        self.lines.push(format!("{stamp} {line}"));
+    }
+}
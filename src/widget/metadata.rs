@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use eyre::Result;
+use kube::api::ObjectMeta;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Row},
+    Frame,
+};
+
+use super::{centered_with, copy, nav::exit_keys, table, Widget};
+use crate::events::{Broadcast, Event, Keypress};
+
+#[derive(Clone)]
+enum Entry {
+    Heading(&'static str, usize),
+    Pair(String, String),
+}
+
+impl Entry {
+    fn section(heading: &'static str, pairs: Option<&BTreeMap<String, String>>) -> Vec<Self> {
+        let pairs = pairs.cloned().unwrap_or_default();
+
+        let mut entries = vec![Entry::Heading(heading, pairs.len())];
+        entries.extend(pairs.into_iter().map(|(k, v)| Entry::Pair(k, v)));
+
+        entries
+    }
+}
+
+impl table::Row for Entry {
+    fn constraints() -> Vec<Constraint> {
+        vec![Constraint::Percentage(40), Constraint::Fill(1)]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        match self {
+            Entry::Heading(heading, count) => Row::new(vec![format!("{heading} ({count})")])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+            // Values such as `last-applied-configuration` go on for a while and
+            // have newlines in them, only as much as fits on one line is shown.
+            Entry::Pair(key, value) => Row::new(vec![
+                format!("  {key}"),
+                value.lines().collect::<Vec<_>>().join(" "),
+            ])
+            .style(style.normal),
+        }
+    }
+}
+
+struct Entries(Vec<Entry>);
+
+impl table::Items for Entries {
+    type Item = Entry;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.clone()
+    }
+}
+
+/// A quick look at an object's labels and annotations, for when opening the
+/// whole manifest would be a lot of scrolling for one value. Picking one copies
+/// its value to the clipboard.
+pub struct Metadata {
+    name: String,
+    entries: Vec<Entry>,
+    table: table::Table<Entries>,
+}
+
+impl Metadata {
+    pub fn new(meta: &ObjectMeta) -> Self {
+        let mut entries = Entry::section("Labels", meta.labels.as_ref());
+        entries.extend(Entry::section("Annotations", meta.annotations.as_ref()));
+
+        Self {
+            name: meta.name.clone().unwrap_or_default(),
+            entries: entries.clone(),
+            table: table::Table::builder()
+                .items(Entries(entries))
+                .border(false)
+                .build(),
+        }
+    }
+}
+
+impl Widget for Metadata {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        match self.table.dispatch(event, buffer, area)? {
+            Broadcast::Selected(idx) => {
+                let Some(Entry::Pair(_, value)) = self.entries.get(idx) else {
+                    return Ok(Broadcast::Consumed);
+                };
+
+                copy::set(value.clone());
+
+                Ok(Broadcast::Exited)
+            }
+            _ if matches!(event.key(), Some(exit_keys!())) => Ok(Broadcast::Exited),
+            _ => Ok(Broadcast::Consumed),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = centered_with(area, Constraint::Percentage(80), Constraint::Percentage(80));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Metadata of {}", self.name))
+            .title_bottom(
+                Line::from("enter: copy value, esc: close")
+                    .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        self.table.draw(frame, inner)
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
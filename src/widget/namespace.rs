@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::Namespace;
+use kube::ResourceExt;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Clear, Row},
+    Frame,
+};
+
+use super::{
+    centered,
+    input::{self, Content},
+    propagate, table, Widget,
+};
+use crate::{
+    events::{Broadcast, Event},
+    resources::{age::Age, namespace::NamespaceExt, store::Store, Filter},
+};
+
+/// What the lists get scoped to, `All` being no scope at all.
+#[derive(Clone)]
+pub enum Choice {
+    All,
+    Namespace(Arc<Namespace>),
+}
+
+impl Choice {
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Choice::All => None,
+            Choice::Namespace(ns) => Some(ns.name_any()),
+        }
+    }
+}
+
+impl Filter for Choice {
+    fn matches(&self, filter: &str) -> bool {
+        match self {
+            Choice::All => "all namespaces".contains(filter),
+            Choice::Namespace(ns) => ns.matches(filter),
+        }
+    }
+}
+
+impl table::Row for Choice {
+    fn columns() -> Vec<&'static str> {
+        vec!["Name", "Status", "Age"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        match self {
+            Choice::All => vec!["all namespaces".to_string(), String::new(), String::new()],
+            Choice::Namespace(ns) => vec![ns.name_any(), ns.phase(), ns.age().to_age()],
+        }
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        match self {
+            Choice::All => Some("all".to_string()),
+            Choice::Namespace(ns) => ns.uid(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Namespaces(Arc<Store<Namespace>>);
+
+impl table::Items for Namespaces {
+    type Item = Choice;
+
+    // Going back to everything is always at the top, whatever the filter.
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        std::iter::once(Choice::All)
+            .chain(self.0.items(None).map(Choice::Namespace))
+            .filter(|choice| filter.as_ref().map_or(true, |f| choice.matches(f)))
+            .collect()
+    }
+
+    fn revision(&self) -> Option<u64> {
+        Some(self.0.generation())
+    }
+
+    fn error(&self) -> Option<String> {
+        self.0.error()
+    }
+
+    fn retry(&self) {
+        self.0.retry();
+    }
+}
+
+/// Popup to pick the namespace that lists are scoped to. Works like the
+/// `Switcher`: typing filters, the cursor keys move and enter picks.
+pub struct Picker {
+    namespaces: Namespaces,
+    filter: Content,
+
+    input: input::Text,
+    table: table::Table<Namespaces>,
+}
+
+impl Picker {
+    pub fn new(client: kube::Client) -> Self {
+        let (store, _) = Store::new(client);
+        let namespaces = Namespaces(store);
+        let filter = Content::default();
+
+        Self {
+            input: input::Text::builder()
+                .title("Namespaces")
+                .content(filter.clone())
+                .build(),
+            table: table::Table::builder()
+                .items(namespaces.clone())
+                .filter(filter.clone())
+                .build(),
+            namespaces,
+            filter,
+        }
+    }
+
+    pub fn get(&self, idx: usize) -> Option<Choice> {
+        table::Items::items(&self.namespaces, self.filter.borrow().clone())
+            .get(idx)
+            .cloned()
+    }
+}
+
+impl Widget for Picker {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        propagate!(self.input.dispatch(event, buffer, area));
+
+        self.table.dispatch(event, buffer, area)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let [input, list] =
+            Layout::vertical([self.input.placement().vertical, Constraint::Fill(0)]).areas(area);
+
+        self.table.draw(frame, list)?;
+        self.input.draw(frame, input)
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::core::v1::Node;
+use kube::ResourceExt;
+use tokio::sync::oneshot;
+
+use super::{
+    cordon::Cordon,
+    drain::Drain,
+    extended::Extended,
+    object::{self, Object},
+    pod,
+    tabs::Tab,
+    yaml::Yaml,
+    Action, BoxWidget, Widget,
+};
+use crate::{
+    events::Keypress,
+    resources::{node::NodeExt, store::Store},
+};
+
+pub type List = object::List<Node>;
+
+/// The node as it is now. The detail only looks the node up once when it is
+/// opened, cordoning it from there needs to know what it is like since.
+fn current(nodes: &Store<Node>, node: &Node) -> Arc<Node> {
+    let name = node.name_any();
+
+    nodes
+        .snapshot()
+        .iter()
+        .find(|node| node.name_any() == name)
+        .cloned()
+        .unwrap_or_else(|| Arc::new(node.clone()))
+}
+
+/// A node along with every pod scheduled on it, and the on-call basics of
+/// cordoning and draining it.
+impl Object for Node {
+    type Items = Arc<Store<Node>>;
+
+    fn items(client: kube::Client, _: Option<&str>) -> (Self::Items, oneshot::Receiver<()>) {
+        Store::new(client)
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.get(idx, filter)
+    }
+
+    fn tabs(client: &kube::Client, _: &Self::Items, node: &Arc<Self>) -> Vec<Tab> {
+        let name = node.name_any();
+        let pods = {
+            let client = client.clone();
+
+            Tab::builder()
+                .name("Pods".to_string())
+                .constructor(Box::new(move || {
+                    pod::List::on_node(client.clone(), &name).boxed()
+                }))
+                .build()
+        };
+
+        vec![
+            Yaml::tab("Overview".to_string(), client.clone(), node.clone()),
+            pods,
+        ]
+    }
+
+    fn status(nodes: &Self::Items, node: &Self) -> Option<String> {
+        current(nodes, node)
+            .is_cordoned()
+            .then(|| "cordoned".to_string())
+    }
+
+    fn popup(
+        client: &kube::Client,
+        nodes: &Self::Items,
+        node: &Arc<Self>,
+        key: &Keypress,
+    ) -> Option<BoxWidget> {
+        let popup = match key {
+            Keypress::Printable('c') => Cordon::new(
+                client.clone(),
+                node.name_any(),
+                current(nodes, node).is_cordoned(),
+            )
+            .boxed(),
+            Keypress::Printable('D') => Drain::new(client.clone(), node.name_any()).boxed(),
+            Keypress::Printable('G') => Extended::new(client.clone(), node.name_any()).boxed(),
+            _ => return None,
+        };
+
+        Some(popup)
+    }
+
+    fn actions(nodes: &Self::Items, node: &Self) -> Vec<Action> {
+        vec![
+            Action::new(
+                'c',
+                if current(nodes, node).is_cordoned() {
+                    "Uncordon the node"
+                } else {
+                    "Cordon the node"
+                },
+            ),
+            Action::new('D', "Drain the node"),
+            Action::new('G', "Show GPUs and other extended resources"),
+        ]
+    }
+}
@@ -0,0 +1,333 @@
+use std::sync::Arc;
+
+use eyre::{eyre, Result};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use kube::{Resource, ResourceExt};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders},
+    Frame,
+};
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use super::{
+    edit::QuickEdit,
+    link::Links,
+    loading::Loading,
+    pod, propagate, recent, table,
+    tabs::{Tab, TabbedView},
+    view::View,
+    Action, BoxWidget, Placement, Widget, WIDGET_VIEWS_VEC,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::selector,
+};
+
+/// What sets the list and detail of one kind apart from the rest. Loading,
+/// `esc`, the breadcrumb, links and quick edit work the same for all of them.
+pub trait Object: Resource<DynamicType = ()> + Serialize + Send + Sync + Sized + 'static {
+    /// What the list shows, usually the store itself. Kinds whose rows need
+    /// other stores, such as pods for health, wrap them up together.
+    type Items: table::Items + Clone + 'static;
+
+    /// Whether the list's sort and filter are remembered, see `table::state`.
+    const REMEMBER: bool = true;
+    /// Whether `e` opens quick edit on the detail.
+    const EDITABLE: bool = true;
+    /// Whether opening the detail counts as a visit, see `recent`.
+    const RECENT: bool = true;
+
+    /// Start watching whatever the list needs. The receiver fires once the
+    /// objects themselves have synced, anything else can catch up afterwards.
+    fn items(client: kube::Client, namespace: Option<&str>)
+        -> (Self::Items, oneshot::Receiver<()>);
+
+    /// The object behind row `idx` of the list.
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>>;
+
+    fn tabs(client: &kube::Client, items: &Self::Items, object: &Arc<Self>) -> Vec<Tab>;
+
+    /// What the breadcrumb calls the object.
+    fn title(object: &Self) -> String {
+        object.name_any()
+    }
+
+    /// Goes in parentheses after the title, such as a job's phase.
+    fn status(_items: &Self::Items, _object: &Self) -> Option<String> {
+        None
+    }
+
+    /// Popups for keys on the detail beyond `o` and `e`.
+    fn popup(
+        _client: &kube::Client,
+        _items: &Self::Items,
+        _object: &Arc<Self>,
+        _key: &Keypress,
+    ) -> Option<BoxWidget> {
+        None
+    }
+
+    /// The actions for the keys handled by `popup`.
+    fn actions(_items: &Self::Items, _object: &Self) -> Vec<Action> {
+        Vec::new()
+    }
+
+    /// Keys on the list that the table doesn't handle, such as changing what
+    /// it shows. Returns whether the key was used.
+    fn list_key(_items: &Self::Items, _key: &Keypress) -> bool {
+        false
+    }
+
+    /// The actions for the keys handled by `list_key`.
+    fn list_actions(_items: &Self::Items) -> Vec<Action> {
+        Vec::new()
+    }
+}
+
+fn kind<K: Object>() -> String {
+    K::kind(&()).to_lowercase()
+}
+
+/// A "Pods" tab for whatever `selector` picks. An empty selector would be every
+/// pod in the namespace, which isn't what the object owns, so there's no tab
+/// for one.
+pub fn pods(
+    client: &kube::Client,
+    namespace: String,
+    selector: Option<LabelSelector>,
+) -> Option<Tab> {
+    let selector = selector.filter(|selector| !selector::format(selector).is_empty())?;
+    let client = client.clone();
+
+    Some(
+        Tab::builder()
+            .name("Pods".to_string())
+            .constructor(Box::new(move || {
+                pod::List::selected(client.clone(), &namespace, &selector).boxed()
+            }))
+            .build(),
+    )
+}
+
+/// Every object of a kind, in `namespace` or the whole cluster. `enter` opens
+/// the detail for the selected one.
+pub struct List<K: Object> {
+    items: K::Items,
+
+    view: View,
+    is_ready: oneshot::Receiver<()>,
+}
+
+impl<K: Object> List<K> {
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(skip(client), fields(activity = %format!("{}.list", kind::<K>())))]
+    pub fn new(client: kube::Client, namespace: Option<&str>) -> Self {
+        WIDGET_VIEWS_VEC
+            .with_label_values(&[&kind::<K>(), "list"])
+            .inc();
+
+        let (items, is_ready) = K::items(client.clone(), namespace);
+
+        let title = format!("{}s", K::kind(&()));
+        let remember = K::REMEMBER.then(|| match K::group(&()).as_ref() {
+            "" => K::plural(&()).to_string(),
+            group => format!("{}.{group}", K::plural(&())),
+        });
+
+        let constructor: table::DetailFn = {
+            let items = items.clone();
+
+            Box::new(move |idx, filter| {
+                let object = K::get(&items, idx, filter)
+                    .ok_or_else(|| eyre!("{} not found", kind::<K>()))?;
+
+                Ok(detail(&client, items.clone(), object))
+            })
+        };
+
+        let table = table::Filtered::builder()
+            .table(
+                table::Table::builder()
+                    .title(namespace.map_or_else(|| title.clone(), |ns| format!("{title} ({ns})")))
+                    .items(items.clone())
+                    .maybe_remember(remember)
+                    .build(),
+            )
+            .constructor(constructor)
+            .build();
+
+        Self {
+            items,
+            view: View::builder()
+                .widgets(vec![table.boxed(), Loading.boxed()])
+                .build(),
+            is_ready,
+        }
+    }
+}
+
+impl<K: Object> Widget for List<K> {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        propagate!(self.view.dispatch(event, buffer, area));
+
+        match event.key() {
+            Some(Keypress::Escape) => Ok(Broadcast::Exited),
+            Some(key) if K::list_key(&self.items, key) => Ok(Broadcast::Consumed),
+            _ => Ok(Broadcast::Ignored),
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        let mut actions = self.view.actions();
+        actions.extend(K::list_actions(&self.items));
+
+        actions
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Ok(()) = self.is_ready.try_recv() {
+            self.view.pop();
+        }
+
+        self.view.draw(frame, area)
+    }
+
+    fn placement(&self) -> Placement {
+        Placement {
+            horizontal: Constraint::Fill(0),
+            vertical: Constraint::Fill(0),
+        }
+    }
+}
+
+/// The detail view for a single object, for lists other than its own such as
+/// a cronjob's jobs.
+pub fn detail<K: Object>(client: &kube::Client, items: K::Items, object: Arc<K>) -> BoxWidget {
+    Detail::new(client, items, object).boxed()
+}
+
+struct Detail<K: Object> {
+    items: K::Items,
+    object: Arc<K>,
+
+    view: TabbedView,
+    client: kube::Client,
+    popup: Option<BoxWidget>,
+}
+
+impl<K: Object> Detail<K> {
+    fn new(client: &kube::Client, items: K::Items, object: Arc<K>) -> Self {
+        WIDGET_VIEWS_VEC
+            .with_label_values(&[&kind::<K>(), "detail"])
+            .inc();
+
+        if K::RECENT {
+            recent::opened(object.as_ref(), &());
+        }
+
+        Self {
+            view: TabbedView::builder()
+                .tabs(K::tabs(client, &items, &object))
+                .build(),
+            items,
+            object,
+            client: client.clone(),
+            popup: None,
+        }
+    }
+
+    fn breadcrumb(&self) -> Vec<Span> {
+        let mut crumb: Vec<Span> = Vec::new();
+
+        if let Some(ns) = self.object.namespace() {
+            crumb.push(ns.into());
+            crumb.push(Span::from(" → ").style(Style::default().add_modifier(Modifier::BOLD)));
+        }
+
+        crumb.push(K::title(&self.object).into());
+
+        if let Some(status) = K::status(&self.items, &self.object) {
+            crumb.push(Span::from(format!(" ({status})")));
+        }
+
+        crumb
+    }
+}
+
+impl<K: Object> Widget for Detail<K> {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(popup) = self.popup.as_mut() {
+            if let Broadcast::Exited = popup.dispatch(event, buffer, area)? {
+                self.popup = None;
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        propagate!(self.view.dispatch(event, buffer, area));
+
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        let popup = match key {
+            Keypress::Escape => return Ok(Broadcast::Exited),
+            Keypress::Printable('o') => Links::new(self.object.as_ref()).boxed(),
+            Keypress::Printable('e') if K::EDITABLE => {
+                QuickEdit::new(self.client.clone(), self.object.as_ref()).boxed()
+            }
+            key => match K::popup(&self.client, &self.items, &self.object, key) {
+                Some(popup) => popup,
+                None => return Ok(Broadcast::Ignored),
+            },
+        };
+
+        self.popup = Some(popup);
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        if self.popup.is_some() {
+            return Vec::new();
+        }
+
+        let mut actions = self.view.actions();
+        actions.push(Action::new('o', "Open links"));
+
+        if K::EDITABLE {
+            actions.push(Action::new('e', "Quick edit a field"));
+        }
+
+        actions.extend(K::actions(&self.items, &self.object));
+
+        actions
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(self.breadcrumb()));
+
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        self.view.draw(frame, inner)?;
+
+        if let Some(popup) = self.popup.as_mut() {
+            popup.draw(frame, area)?;
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+
+use eyre::Result;
+use kube::api::ApiResource;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Clear, Row},
+    Frame,
+};
+use tokio::sync::watch;
+
+use super::{
+    centered,
+    input::{self, Content},
+    propagate, table, Action, Widget,
+};
+use crate::events::{Broadcast, Event};
+
+/// How well `query` matches `text`, if at all. Every character of the query
+/// has to show up in the text in the same order, but not necessarily next to
+/// each other. Runs of characters and ones at the start of a word score
+/// higher, so `dp` ranks "Debug a copy of the pod" above "Delete".
+fn score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last: Option<usize> = None;
+
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let idx = pos + text[pos..].iter().position(|c| *c == wanted)?;
+
+        score += 1;
+
+        if last.is_some_and(|last| last + 1 == idx) {
+            score += 4;
+        }
+
+        if idx == 0 || !text[idx - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        last = Some(idx);
+        pos = idx + 1;
+    }
+
+    // Shorter entries are closer to what was typed.
+    #[allow(clippy::cast_possible_wrap)]
+    Some(score * 100 - text.len() as i64)
+}
+
+/// Something that can be picked from the palette.
+#[derive(Clone)]
+pub enum Command {
+    Action(Action),
+    Kind(ApiResource),
+}
+
+impl Command {
+    fn description(&self) -> String {
+        match self {
+            Command::Action(action) => action.description.to_string(),
+            Command::Kind(kind) => format!("Go to {}", kind.plural),
+        }
+    }
+}
+
+impl table::Row for Command {
+    fn columns() -> Vec<&'static str> {
+        vec!["Command", "Key"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        match self {
+            Command::Action(action) => vec![action.description.to_string(), action.key.to_string()],
+            Command::Kind(kind) => vec![self.description(), format!(": {}", kind.kind)],
+        }
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        match self {
+            Command::Action(action) => Some(action.key.to_string()),
+            Command::Kind(kind) => Some(format!("{}/{}", kind.api_version, kind.plural)),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Commands {
+    actions: Vec<Action>,
+    kinds: watch::Receiver<Vec<ApiResource>>,
+}
+
+impl table::Items for Commands {
+    type Item = Command;
+
+    // Actions go first, they're what can be done right here. Without a query
+    // everything stays in that order, otherwise the best matches are on top.
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let kinds = self.kinds.borrow();
+
+        let commands = self
+            .actions
+            .iter()
+            .cloned()
+            .map(Command::Action)
+            .chain(kinds.iter().cloned().map(Command::Kind));
+
+        let Some(query) = filter.filter(|query| !query.trim().is_empty()) else {
+            return commands.collect();
+        };
+
+        let mut scored: Vec<_> = commands
+            .filter_map(|command| Some((score(&query, &command.description())?, command)))
+            .collect();
+
+        scored.sort_by_key(|(score, _)| -score);
+
+        scored.into_iter().map(|(_, command)| command).collect()
+    }
+}
+
+/// Popup listing everything that can be done from the current screen, along
+/// with every kind that can be browsed. Typing fuzzy matches against the
+/// descriptions, enter runs the selection. It is an alternative to
+/// remembering which key does what.
+pub struct Palette {
+    commands: Commands,
+    filter: Content,
+
+    input: input::Text,
+    table: table::Table<Commands>,
+}
+
+impl Palette {
+    pub fn new(actions: Vec<Action>, kinds: watch::Receiver<Vec<ApiResource>>) -> Self {
+        // The same key can be offered by more than one widget, the first of them
+        // is the one that gets it.
+        let mut seen = HashSet::new();
+        let actions = actions
+            .into_iter()
+            .filter(|action| seen.insert(action.key))
+            .collect();
+
+        let commands = Commands { actions, kinds };
+        let filter = Content::default();
+
+        Self {
+            input: input::Text::builder()
+                .title("Commands")
+                .content(filter.clone())
+                .build(),
+            table: table::Table::builder()
+                .items(commands.clone())
+                .filter(filter.clone())
+                .build(),
+            commands,
+            filter,
+        }
+    }
+
+    pub fn get(&self, idx: usize) -> Option<Command> {
+        table::Items::items(&self.commands, self.filter.borrow().clone())
+            .get(idx)
+            .cloned()
+    }
+}
+
+impl Widget for Palette {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        propagate!(self.input.dispatch(event, buffer, area));
+
+        self.table.dispatch(event, buffer, area)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let [input, list] =
+            Layout::vertical([self.input.placement().vertical, Constraint::Fill(0)]).areas(area);
+
+        self.table.draw(frame, list)?;
+        self.input.draw(frame, input)
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
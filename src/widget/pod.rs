@@ -1,69 +1,258 @@
+pub mod command;
+pub mod debug;
+pub mod disk;
+pub mod dns;
+pub mod env;
+pub mod lifecycle;
+pub mod network;
+pub mod picker;
+pub mod probes;
+pub mod pull;
+pub mod runtime;
+pub mod scheduling;
+pub mod security;
 pub mod shell;
+pub mod signal;
+pub mod spread;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use eyre::{eyre, Result};
-use k8s_openapi::api::core::v1::Pod;
-use kube::ResourceExt;
+use itertools::Itertools;
+use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::apis::meta::v1::LabelSelector};
+use kube::{runtime::watcher, ResourceExt};
 use ratatui::{
     layout::Rect,
     prelude::*,
-    style::{Modifier, Style},
+    style::{palette::tailwind, Modifier, Style},
     text::Line,
     widgets::{Block, Borders},
 };
-use tokio::sync::oneshot;
+use tokio::{sync::oneshot, task::JoinHandle};
 
 use super::{
-    loading::Loading, log::Log, propagate, table, tabs::TabbedView, view::View, Placement, Widget,
-    WIDGET_VIEWS,
+    edit::QuickEdit, link::Links, loading::Loading, log::Log, propagate, recent, table,
+    tabs::TabbedView, view::View, Action, BoxWidget, Placement, Widget, WIDGET_VIEWS,
 };
 use crate::{
     events::{Broadcast, Event, Keypress},
-    resources::store::Store,
-    widget::{pod::shell::Shell, yaml::Yaml},
+    resources::{
+        pod::{
+            history::{self, History},
+            Grouping, PodExt, Pods,
+        },
+        selector,
+        store::Store,
+    },
+    widget::{
+        pod::{
+            command::Command, debug::DebugCopy, disk::DiskUsage, dns::Resolve, env::Env,
+            lifecycle::Lifecycle, network::NetworkDebug, probes::Probes, pull::ImagePull,
+            runtime::RuntimeInfo, scheduling::Scheduling, security::Security, shell::Shell,
+            signal::SendSignal, spread::Topology,
+        },
+        startup::Startup,
+        yaml::Yaml,
+    },
 };
 
 pub struct List {
     view: View,
+    items: Arc<Pods>,
     is_ready: oneshot::Receiver<()>,
+    sampler: JoinHandle<()>,
+    popup: Option<BoxWidget>,
 }
 
 impl List {
     #[allow(clippy::blocks_in_conditions)]
     #[tracing::instrument(skip(client), fields(activity = "pod.list"))]
-    pub fn new(client: kube::Client) -> Self {
+    pub fn new(client: kube::Client, namespace: Option<&str>) -> Self {
+        let store = Store::namespaced(client.clone(), namespace);
+
+        Self::build(client, namespace, store, Some("pods".to_string()))
+    }
+
+    /// Only the pods in `namespace` that `selector` picks, such as the ones
+    /// that belong to a deployment. How the table is sorted isn't remembered,
+    /// that's left to the full list.
+    pub fn selected(client: kube::Client, namespace: &str, selector: &LabelSelector) -> Self {
+        let store = Store::with_config(
+            client.clone(),
+            Startup::watch(Some(namespace)).labels(&selector::format(selector)),
+        );
+
+        Self::build(client, Some(namespace), store, None)
+    }
+
+    /// The pods scheduled on `node`, from every namespace.
+    pub fn on_node(client: kube::Client, node: &str) -> Self {
+        let store = Store::with_config(
+            client.clone(),
+            watcher::Config::default().fields(&format!("spec.nodeName={node}")),
+        );
+
+        Self::build(client, None, store, None)
+    }
+
+    fn build(
+        client: kube::Client,
+        namespace: Option<&str>,
+        (pods, is_ready): (Arc<Store<Pod>>, oneshot::Receiver<()>),
+        remember: Option<String>,
+    ) -> Self {
         WIDGET_VIEWS.pod.list.inc();
 
-        let (pods, is_ready) = Store::new(client.clone());
+        // Nodes are only needed for the zone, there's no reason to wait for them
+        // before showing the pods.
+        let (nodes, _) = Store::new(client.clone());
+
+        let history = Arc::new(History::default());
+        let sampler = tokio::spawn(history::sample(
+            client.clone(),
+            namespace.map(String::from),
+            pods.clone(),
+            history.clone(),
+        ));
+
+        let items = Arc::new(Pods {
+            pods,
+            nodes,
+            grouping: Mutex::default(),
+            wide: Mutex::default(),
+            history,
+        });
+
         let table = table::Filtered::builder()
             .table(
                 table::Table::builder()
-                    .title("Pods")
-                    .items(pods.clone())
+                    .title(
+                        namespace.map_or_else(|| "Pods".to_string(), |ns| format!("Pods ({ns})")),
+                    )
+                    .items(items.clone())
+                    .maybe_remember(remember)
                     .build(),
             )
-            .constructor(Detail::from_store(client, pods))
+            .constructor(Detail::from_items(
+                client,
+                items.clone(),
+                shell::History::default(),
+            ))
             .build();
 
         let widgets = vec![table.boxed(), Loading.boxed()];
 
         Self {
             view: View::builder().widgets(widgets).build(),
+            items,
             is_ready,
+            sampler,
+            popup: None,
         }
     }
+
+    /// Number of pods in each group, largest first, so that concentration in a
+    /// single zone or node stands out.
+    fn summary(&self) -> Option<Line<'static>> {
+        let grouping = self.items.grouping();
+
+        if matches!(grouping, Grouping::None) {
+            return None;
+        }
+
+        let counts = table::Items::items(&self.items, None)
+            .iter()
+            .map(|item| item.group(grouping).to_string())
+            .counts();
+
+        let total: usize = counts.values().sum();
+
+        let mut spans =
+            vec![Span::from(format!("by {grouping}:"))
+                .style(Style::default().fg(tailwind::GRAY.c400))];
+
+        for (group, count) in counts
+            .into_iter()
+            .sorted_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)))
+        {
+            let group = if group.is_empty() {
+                "unknown".to_string()
+            } else {
+                group
+            };
+
+            spans.push(Span::from(format!(" {group} ")));
+            spans.push(
+                Span::from(format!("{count} ({}%)", count * 100 / total.max(1)))
+                    .style(Style::default().fg(tailwind::INDIGO.c300)),
+            );
+        }
+
+        Some(Line::from(spans))
+    }
 }
 
 impl Widget for List {
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(popup) = self.popup.as_mut() {
+            if let Broadcast::Exited = popup.dispatch(event, buffer, area)? {
+                self.popup = None;
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
         propagate!(self.view.dispatch(event, buffer, area));
 
-        if matches!(event.key(), Some(Keypress::Escape)) {
-            return Ok(Broadcast::Exited);
+        match event.key() {
+            Some(Keypress::Escape) => Ok(Broadcast::Exited),
+            Some(Keypress::Printable('T')) => {
+                self.popup = Some(Topology::new(self.items.clone()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('g')) => {
+                self.items.set_grouping(self.items.grouping().next());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('t')) => {
+                let history = &self.items.history;
+                history.set_trend(history.trend().next());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('x')) => {
+                self.items.set_wide(!self.items.wide());
+
+                Ok(Broadcast::Consumed)
+            }
+            _ => Ok(Broadcast::Ignored),
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        if self.popup.is_some() {
+            return Vec::new();
         }
 
-        Ok(Broadcast::Ignored)
+        let mut actions = self.view.actions();
+        actions.push(Action::new('g', "Group pods by something else"));
+        actions.push(Action::new('t', "Show restart or CPU trends"));
+        actions.push(Action::new(
+            'x',
+            if self.items.wide() {
+                "Hide the priority column"
+            } else {
+                "Show the priority column"
+            },
+        ));
+        actions.push(Action::new(
+            'T',
+            "Show how pods are spread over zones and nodes",
+        ));
+
+        actions
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
@@ -72,7 +261,25 @@ impl Widget for List {
             self.view.pop();
         }
 
-        self.view.draw(frame, area)
+        let summary = self.summary();
+
+        let [area, footer] = Layout::vertical([
+            Constraint::Fill(0),
+            Constraint::Length(u16::from(summary.is_some())),
+        ])
+        .areas(area);
+
+        self.view.draw(frame, area)?;
+
+        if let Some(summary) = summary {
+            frame.render_widget(summary, footer);
+        }
+
+        if let Some(popup) = self.popup.as_mut() {
+            popup.draw(frame, area)?;
+        }
+
+        Ok(())
     }
 
     fn placement(&self) -> Placement {
@@ -83,6 +290,12 @@ impl Widget for List {
     }
 }
 
+impl Drop for List {
+    fn drop(&mut self) {
+        self.sampler.abort();
+    }
+}
+
 struct DetailStyle {
     breadcrumb: Style,
 }
@@ -99,32 +312,58 @@ struct Detail {
     pod: Arc<Pod>,
 
     view: TabbedView,
+    client: kube::Client,
+    popup: Option<BoxWidget>,
 }
 
 #[bon::bon]
 impl Detail {
     #[builder]
-    fn new(client: &kube::Client, pod: Arc<Pod>) -> Self {
+    fn new(client: &kube::Client, pod: Arc<Pod>, history: shell::History) -> Self {
         WIDGET_VIEWS.pod.detail.inc();
+        recent::opened(pod.as_ref(), &());
 
         let view = TabbedView::builder()
             .tabs(vec![
-                Yaml::tab("Overview".to_string(), pod.clone()),
+                Yaml::tab("Overview".to_string(), client.clone(), pod.clone()),
                 Log::tab("Logs".to_string(), client.clone(), pod.clone()),
-                Shell::tab("Shell".to_string(), client.clone(), pod.clone()),
+                Shell::tab("Shell".to_string(), client.clone(), pod.clone(), history),
+                Security::tab("Security".to_string(), pod.clone()),
+                Scheduling::tab("Scheduling".to_string(), client.clone(), pod.clone()),
+                Lifecycle::tab("Timeline".to_string(), client.clone(), pod.clone()),
+                Probes::tab("Probes".to_string(), client.clone(), pod.clone()),
+                RuntimeInfo::tab("Runtime".to_string(), client.clone(), pod.clone()),
+                Env::tab("Env".to_string(), client.clone(), pod.clone()),
+                Command::tab("Command".to_string(), client.clone(), pod.clone()),
             ])
             .build();
 
-        Self { pod, view }
+        Self {
+            pod,
+            view,
+            client: client.clone(),
+            popup: None,
+        }
     }
 
-    pub fn from_store(client: kube::Client, pods: Arc<Store<Pod>>) -> table::DetailFn {
+    pub fn from_items(
+        client: kube::Client,
+        items: Arc<Pods>,
+        history: shell::History,
+    ) -> table::DetailFn {
         Box::new(move |idx, filter| {
-            let pod = pods
-                .get(idx, filter)
-                .ok_or_else(|| eyre!("pod not found"))?;
-
-            Ok(Detail::builder().client(&client).pod(pod).build().boxed())
+            let pod = table::Items::items(&items, filter)
+                .into_iter()
+                .nth(idx)
+                .ok_or_else(|| eyre!("pod not found"))?
+                .pod;
+
+            Ok(Detail::builder()
+                .client(&client)
+                .pod(pod)
+                .history(history.clone())
+                .build()
+                .boxed())
         })
     }
 
@@ -140,19 +379,95 @@ impl Detail {
 
         crumb.push(self.pod.name_any().into());
 
+        let status = self.pod.status();
+        crumb.push(
+            Span::from(format!(" {}", status.indicator().label(&status)))
+                .style(status.style(&table::RowStyle::default())),
+        );
+
         crumb
     }
 }
 
 impl Widget for Detail {
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(popup) = self.popup.as_mut() {
+            match popup.dispatch(event, buffer, area)? {
+                Broadcast::Exited => self.popup = None,
+                // Popups can exec into something too, such as a debug copy.
+                Broadcast::Raw(raw) => return Ok(Broadcast::Raw(raw)),
+                _ => {}
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
         propagate!(self.view.dispatch(event, buffer, area));
 
-        if matches!(event.key(), Some(Keypress::Escape)) {
-            return Ok(Broadcast::Exited);
+        match event.key() {
+            Some(Keypress::Escape) => Ok(Broadcast::Exited),
+            Some(Keypress::Printable('o')) => {
+                self.popup = Some(Links::new(self.pod.as_ref()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('C')) => {
+                self.popup = Some(DebugCopy::new(self.client.clone(), self.pod.clone()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('D')) => {
+                self.popup = Some(Resolve::new(self.client.clone(), self.pod.clone()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('I')) => {
+                self.popup = Some(ImagePull::new(self.client.clone(), self.pod.clone()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('N')) => {
+                self.popup = Some(NetworkDebug::new(self.client.clone(), self.pod.clone()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('e')) => {
+                self.popup = Some(QuickEdit::new(self.client.clone(), self.pod.as_ref()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('K')) => {
+                self.popup = Some(SendSignal::new(self.client.clone(), self.pod.clone()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::Printable('U')) => {
+                self.popup = Some(DiskUsage::new(self.client.clone(), self.pod.clone()).boxed());
+
+                Ok(Broadcast::Consumed)
+            }
+            _ => Ok(Broadcast::Ignored),
         }
+    }
 
-        Ok(Broadcast::Ignored)
+    fn actions(&self) -> Vec<Action> {
+        if self.popup.is_some() {
+            return Vec::new();
+        }
+
+        let mut actions = self.view.actions();
+        actions.extend([
+            Action::new('o', "Open links"),
+            Action::new('e', "Quick edit a field"),
+            Action::new('C', "Debug a copy of the pod"),
+            Action::new('D', "Look up a service from inside the pod"),
+            Action::new('I', "Explain image pull errors"),
+            Action::new('N', "Debug the network with netshoot"),
+            Action::new('K', "Send a signal to a container"),
+            Action::new('U', "Show disk usage of a container"),
+        ]);
+
+        actions
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
@@ -164,7 +479,13 @@ impl Widget for Detail {
 
         frame.render_widget(block, area);
 
-        self.view.draw(frame, inner)
+        self.view.draw(frame, inner)?;
+
+        if let Some(popup) = self.popup.as_mut() {
+            popup.draw(frame, area)?;
+        }
+
+        Ok(())
     }
 
     fn zindex(&self) -> u16 {
@@ -0,0 +1,286 @@
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc, sync::Arc};
+
+use chrono::TimeDelta;
+use eyre::Result;
+use k8s_openapi::api::core::v1::Pod;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::Line,
+    widgets::Row,
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    events::{Broadcast, Event},
+    resources::{
+        age::Age,
+        container::{ContainerExt, State},
+        pod::{
+            command::{self, Invocation},
+            termination, PodExt,
+        },
+    },
+    widget::{copy, table, tabs::Tab, Widget, WIDGET_VIEWS},
+};
+
+// What each running container's first process was started with, or why that
+// couldn't be found out.
+type Effective = BTreeMap<String, Result<Vec<String>, String>>;
+
+#[derive(Clone)]
+enum Entry {
+    Heading(String),
+    Field {
+        label: &'static str,
+        value: String,
+        // What gets copied, when there's something worth copying.
+        copy: Option<String>,
+    },
+}
+
+impl Entry {
+    fn field(label: &'static str, value: Option<String>, fallback: &str) -> Self {
+        Entry::Field {
+            label,
+            value: value.clone().unwrap_or_else(|| fallback.to_string()),
+            copy: value,
+        }
+    }
+}
+
+impl table::Row for Entry {
+    fn constraints() -> Vec<Constraint> {
+        vec![Constraint::Length(14), Constraint::Fill(1)]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        match self {
+            Entry::Heading(heading) => {
+                Row::new(vec![heading.clone()]).style(Style::default().add_modifier(Modifier::BOLD))
+            }
+            Entry::Field { label, value, copy } => {
+                Row::new(vec![format!("  {label}"), value.clone()]).style(if copy.is_some() {
+                    style.normal
+                } else {
+                    Style::default().fg(tailwind::GRAY.c400)
+                })
+            }
+        }
+    }
+}
+
+// The grace period is what decides how long a pod sticks around once it's
+// deleted, which is worth having above any one container.
+fn termination(pod: &Pod) -> Vec<Entry> {
+    let mut entries = vec![
+        Entry::Heading("termination".to_string()),
+        Entry::field(
+            "grace period",
+            Some(format!("{}s", termination::grace_period(pod))),
+            "",
+        ),
+    ];
+
+    if let Some(remaining) = termination::remaining(pod) {
+        entries.push(Entry::field(
+            "deleting",
+            None,
+            &if remaining < TimeDelta::zero() {
+                format!(
+                    "{} past the grace period, a finalizer or the node is holding it up",
+                    (-remaining).to_age()
+                )
+            } else {
+                format!("containers are killed in {}", remaining.to_age())
+            },
+        ));
+    }
+
+    entries
+}
+
+fn entries(pod: &Pod, invocations: &[Invocation], effective: Option<&Effective>) -> Vec<Entry> {
+    let mut entries = termination(pod);
+
+    for invocation in invocations {
+        let heading = if invocation.init {
+            format!("{} (init)", invocation.container)
+        } else {
+            invocation.container.clone()
+        };
+
+        entries.push(Entry::Heading(heading));
+        entries.push(Entry::field("image", Some(invocation.image.clone()), ""));
+        entries.push(Entry::field(
+            "command",
+            invocation.command.as_deref().map(command::quote),
+            "from the image's entrypoint",
+        ));
+        entries.push(Entry::field(
+            "args",
+            invocation.args.as_deref().map(command::quote),
+            "from the image's cmd",
+        ));
+        entries.push(Entry::field(
+            "workingDir",
+            invocation.working_dir.clone(),
+            "from the image",
+        ));
+
+        for hook in &invocation.hooks {
+            entries.push(Entry::field(hook.event, Some(hook.action.clone()), ""));
+        }
+
+        if let Some(id) = invocation.image_id.as_ref() {
+            entries.push(Entry::field("image id", Some(id.clone()), ""));
+        }
+
+        let running = match effective.map(|effective| effective.get(&invocation.container)) {
+            _ if !invocation.running => Entry::field("running", None, "not running"),
+            None => Entry::field("running", None, "looking up..."),
+            Some(None) => Entry::field("running", None, "unavailable"),
+            Some(Some(Ok(args))) => Entry::field("running", Some(command::quote(args)), ""),
+            Some(Some(Err(err))) => Entry::field("running", None, &format!("unavailable: {err}")),
+        };
+
+        entries.push(running);
+    }
+
+    entries
+}
+
+#[derive(Clone)]
+struct Entries(Rc<RefCell<Vec<Entry>>>);
+
+impl table::Items for Entries {
+    type Item = Entry;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.borrow().clone()
+    }
+}
+
+// Containers that aren't running have no process to look at, everything else
+// gets asked.
+async fn fetch(client: kube::Client, pod: Arc<Pod>) -> Effective {
+    let mut effective = Effective::new();
+
+    for container in pod.containers(None) {
+        if !matches!(container.state(), State::Running) {
+            continue;
+        }
+
+        let result = command::effective(client.clone(), container.clone())
+            .await
+            .map_err(|err| err.to_string());
+
+        effective.insert(container.name_any(), result);
+    }
+
+    effective
+}
+
+/// How each container gets started: the command, args and working directory
+/// from the spec, with whatever is left to the image called out. For running
+/// containers, the command line their first process actually has is looked
+/// up too, since that's the spec and the image put together. Each container's
+/// `postStart` and `preStop` hooks are listed with it, and the pod's grace
+/// period goes first, counting down once the pod is being deleted. Picking a
+/// row copies it, for reproducing a container somewhere else.
+#[allow(clippy::module_name_repetitions)]
+pub struct Command {
+    pod: Arc<Pod>,
+    invocations: Vec<Invocation>,
+    entries: Entries,
+
+    task: Option<JoinHandle<Effective>>,
+    effective: Option<Effective>,
+    table: table::Table<Entries>,
+}
+
+impl Command {
+    pub fn new(client: &kube::Client, pod: Arc<Pod>) -> Self {
+        WIDGET_VIEWS.pod.command.inc();
+
+        let invocations = command::all(&pod);
+        let entries = Entries(Rc::new(RefCell::new(self::entries(
+            &pod,
+            &invocations,
+            None,
+        ))));
+
+        Self {
+            pod: pod.clone(),
+            invocations,
+            table: table::Table::builder()
+                .items(entries.clone())
+                .border(false)
+                .build(),
+            entries,
+            task: Some(tokio::spawn(fetch(client.clone(), pod))),
+            effective: None,
+        }
+    }
+
+    pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || Self::new(&client, pod.clone()).boxed()))
+            .build()
+    }
+}
+
+impl Widget for Command {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        match self.table.dispatch(event, buffer, area)? {
+            Broadcast::Selected(idx) => {
+                if let Some(Entry::Field {
+                    copy: Some(value), ..
+                }) = self.entries.0.borrow().get(idx)
+                {
+                    copy::set(value.clone());
+                }
+
+                Ok(Broadcast::Consumed)
+            }
+            result => Ok(result),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Some(task) = self.task.as_mut() {
+            if task.is_finished() {
+                self.effective = Some(futures::executor::block_on(task).unwrap_or_default());
+                self.task = None;
+            }
+        }
+
+        // Rebuilt every time for the countdown while the pod is being deleted.
+        *self.entries.0.borrow_mut() =
+            entries(&self.pod, &self.invocations, self.effective.as_ref());
+
+        let [area, footer] =
+            Layout::vertical([Constraint::Fill(0), Constraint::Length(1)]).areas(area);
+
+        self.table.draw(frame, area)?;
+
+        frame.render_widget(
+            Line::from("enter: copy the selected value, running is read from /proc/1/cmdline")
+                .style(Style::default().fg(tailwind::GRAY.c400)),
+            footer,
+        );
+
+        Ok(())
+    }
+}
+
+impl Drop for Command {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use std::{sync::Arc, time::Duration};
+
+use eyre::{eyre, Result};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    api::{Api, DeleteParams, PostParams},
+    runtime::wait::{await_condition, conditions},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{
+    picker::{Answer, Choice, Picker},
+    shell::{Command, History},
+};
+use crate::{
+    events::{Broadcast, Event},
+    resources::{
+        container::{Container, ContainerExt},
+        pod::{debug, PodExt},
+    },
+    widget::{centered, theme, Widget},
+};
+
+// Pulling the image again on another node can take a while, but anything
+// longer than this is most likely never going to start.
+static START_TIMEOUT: Duration = Duration::from_secs(120);
+
+lazy_static! {
+    static ref DEBUG_COPIES: IntCounterVec = register_int_counter_vec!(
+        "pod_debug_copies_total",
+        "Number of debug copies of pods created, by whether they started",
+        &["result"]
+    )
+    .unwrap();
+}
+
+enum Stage {
+    Container(Picker<Arc<Pod>>),
+    Creating(String, JoinHandle<Result<Arc<Pod>>>),
+    Shell(Command),
+    Deleting(Option<String>, JoinHandle<Result<()>>),
+    Done(Result<String, String>),
+}
+
+/// Exec into a copy of a pod instead of the pod itself. The copy runs the same
+/// containers with `sleep` as their command and without the labels that would
+/// have it receive traffic, so poking around in it can't break a production
+/// replica. It is deleted once the shell exits.
+#[allow(clippy::module_name_repetitions)]
+pub struct DebugCopy {
+    client: kube::Client,
+    pod: Arc<Pod>,
+
+    copy: Option<Arc<Pod>>,
+    stage: Stage,
+}
+
+impl DebugCopy {
+    pub fn new(client: kube::Client, pod: Arc<Pod>) -> Self {
+        let mut this = Self {
+            client,
+            pod: pod.clone(),
+            copy: None,
+            stage: Stage::Done(Ok(String::new())),
+        };
+
+        this.stage = match Picker::new(pod) {
+            Choice::Only(container) => this.create(&container),
+            Choice::Ask(picker) => Stage::Container(picker),
+        };
+
+        this
+    }
+
+    fn create(&self, container: &Container) -> Stage {
+        let client = self.client.clone();
+        let pod = self.pod.clone();
+
+        Stage::Creating(
+            container.name_any(),
+            tokio::spawn(async move {
+                let result = create(client, &pod).await;
+
+                DEBUG_COPIES
+                    .with_label_values(&[if result.is_ok() { "success" } else { "failure" }])
+                    .inc();
+
+                result
+            }),
+        )
+    }
+
+    fn delete(&mut self, err: Option<String>) -> Stage {
+        let Some(copy) = self.copy.take() else {
+            return Stage::Done(err.map_or_else(|| Ok(String::new()), Err));
+        };
+
+        Stage::Deleting(err, tokio::spawn(delete(self.client.clone(), copy)))
+    }
+
+    fn started(&mut self, container: &str, task: JoinHandle<Result<Arc<Pod>>>) -> Stage {
+        match futures::executor::block_on(async move { task.await? }) {
+            Ok(copy) => {
+                let Some(container) = copy
+                    .containers(None)
+                    .into_iter()
+                    .find(|c| c.name_any() == container)
+                else {
+                    self.copy = Some(copy);
+
+                    return self.delete(Some(format!("{container} is missing from the copy")));
+                };
+
+                self.copy = Some(copy.clone());
+
+                Stage::Shell(Command::new(
+                    self.client.clone(),
+                    copy,
+                    container,
+                    History::default(),
+                ))
+            }
+            Err(err) => Stage::Done(Err(message(&err))),
+        }
+    }
+
+    fn deleted(err: Option<String>, task: JoinHandle<Result<()>>) -> Stage {
+        let result = futures::executor::block_on(async move { task.await? });
+
+        Stage::Done(match (err, result) {
+            (_, Err(del)) => Err(format!("unable to delete the copy: {}", message(&del))),
+            (Some(err), Ok(())) => Err(err),
+            (None, Ok(())) => Ok("the copy has been deleted".to_string()),
+        })
+    }
+
+    fn advance(
+        &mut self,
+        stage: Stage,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<(Stage, Broadcast)> {
+        let stage = match stage {
+            Stage::Container(mut picker) => match picker.dispatch(event, buffer, area)? {
+                Answer::Picked(container) => self.create(&container),
+                Answer::Cancelled => return Ok((Stage::Container(picker), Broadcast::Exited)),
+                Answer::Waiting => Stage::Container(picker),
+            },
+            Stage::Shell(mut command) => match command.dispatch(event, buffer, area) {
+                Ok(Broadcast::Exited) => self.delete(None),
+                Ok(Broadcast::Raw(raw)) => return Ok((Stage::Shell(command), Broadcast::Raw(raw))),
+                Ok(_) => Stage::Shell(command),
+                Err(err) => self.delete(Some(err.to_string())),
+            },
+            Stage::Done(result) => {
+                if event.key().is_some() {
+                    return Ok((Stage::Done(result), Broadcast::Exited));
+                }
+
+                Stage::Done(result)
+            }
+            stage => stage,
+        };
+
+        Ok((stage, Broadcast::Consumed))
+    }
+
+    fn title(&self) -> String {
+        format!("Debug Copy of {}", self.pod.name_any())
+    }
+
+    fn help(&self) -> &'static str {
+        match self.stage {
+            Stage::Container(_) => "enter: select container to exec into, esc: cancel",
+            Stage::Done(_) => "press any key to close",
+            _ => "",
+        }
+    }
+}
+
+async fn create(client: kube::Client, pod: &Pod) -> Result<Arc<Pod>> {
+    let api = Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default());
+
+    let copy = api
+        .create(&PostParams::default(), &debug::copy(pod))
+        .await?;
+    let name = copy.name_any();
+
+    let running = tokio::time::timeout(
+        START_TIMEOUT,
+        await_condition(api.clone(), &name, conditions::is_pod_running()),
+    )
+    .await;
+
+    let err = match running {
+        Ok(Ok(Some(copy))) => return Ok(Arc::new(copy)),
+        Ok(Ok(None)) => eyre!("{name} was deleted before it started"),
+        Ok(Err(err)) => err.into(),
+        Err(_) => eyre!(
+            "{name} didn't start within {}s, check its events for why",
+            START_TIMEOUT.as_secs()
+        ),
+    };
+
+    // Nobody is going to use a copy that never started.
+    api.delete(&name, &DeleteParams::default()).await?;
+
+    Err(err)
+}
+
+async fn delete(client: kube::Client, copy: Arc<Pod>) -> Result<()> {
+    Api::<Pod>::namespaced(client, &copy.namespace().unwrap_or_default())
+        .delete(&copy.name_any(), &DeleteParams::default())
+        .await?;
+
+    Ok(())
+}
+
+fn message(err: &eyre::Report) -> String {
+    match err.downcast_ref::<kube::Error>() {
+        Some(kube::Error::Api(resp)) => resp.message.clone(),
+        _ => err.to_string(),
+    }
+}
+
+impl Widget for DebugCopy {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        // The shell needs to hear about its process finishing, everything else
+        // only cares about keys.
+        if event.key().is_none() && !matches!(self.stage, Stage::Shell(_)) {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        match self.advance(stage, event, buffer, area) {
+            Ok((stage, result)) => {
+                self.stage = stage;
+
+                Ok(result)
+            }
+            Err(err) => {
+                self.stage = self.delete(Some(err.to_string()));
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        self.stage = match stage {
+            Stage::Creating(container, task) if task.is_finished() => {
+                self.started(&container, task)
+            }
+            Stage::Deleting(err, task) if task.is_finished() => Self::deleted(err, task),
+            stage => stage,
+        };
+
+        if let Stage::Shell(command) = &mut self.stage {
+            return command.draw(frame, area);
+        }
+
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title())
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Container(picker) => picker.draw(frame, inner)?,
+            Stage::Creating(..) => frame.render_widget(
+                Paragraph::new("creating the copy and waiting for it to start...")
+                    .wrap(Wrap { trim: false }),
+                inner,
+            ),
+            Stage::Deleting(..) => {
+                frame.render_widget(Paragraph::new("deleting the copy..."), inner);
+            }
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+            Stage::Shell(_) => {}
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+// Closing the popup, or the whole session going away, shouldn't leave the copy
+// behind. Anything still in flight is left to finish so that there is
+// something to clean up.
+impl Drop for DebugCopy {
+    fn drop(&mut self) {
+        let client = self.client.clone();
+
+        let copy = self.copy.take();
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        let creating = match stage {
+            Stage::Creating(_, task) => Some(task),
+            _ => None,
+        };
+
+        if copy.is_none() && creating.is_none() {
+            return;
+        }
+
+        tokio::spawn(async move {
+            let copy = match (copy, creating) {
+                (Some(copy), _) => copy,
+                (None, Some(task)) => match task.await {
+                    Ok(Ok(copy)) => copy,
+                    _ => return,
+                },
+                (None, None) => return,
+            };
+
+            if let Err(err) = delete(client, copy.clone()).await {
+                tracing::warn!("unable to delete {}: {err:?}", copy.name_any());
+            }
+        });
+    }
+}
@@ -0,0 +1,241 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::Pod;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Row, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::picker::{Answer, Choice, Picker};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        container::{Container, ContainerExt},
+        pod::disk::{self, Kind, Usage},
+    },
+    widget::{centered_with, nav::exit_keys, table, theme, Widget},
+};
+
+// Sizes are shown in MiB so that sorting, which compares numbers, gets them
+// in the right order.
+#[allow(clippy::cast_precision_loss)]
+fn mib(kib: u64) -> String {
+    format!("{:.1}", kib as f64 / 1024.0)
+}
+
+impl table::Row for Usage {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Path",
+            "Kind",
+            "Used (MiB)",
+            "Size (MiB)",
+            "Free (MiB)",
+            "Use%",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.path.clone(),
+            self.kind.to_string(),
+            mib(self.used),
+            self.size.map(mib).unwrap_or_default(),
+            self.available.map(mib).unwrap_or_default(),
+            self.percent()
+                .map(|percent| percent.to_string())
+                .unwrap_or_default(),
+        ]
+    }
+
+    // Anything that is nearly out of space is the reason to be here.
+    fn row(&self, style: &table::RowStyle) -> Row {
+        let style = match self.percent() {
+            Some(percent) if percent >= 90 => style.unhealthy,
+            _ if self.kind == Kind::Directory => Style::default().fg(tailwind::GRAY.c400),
+            _ => style.normal,
+        };
+
+        Row::new(self.cells()).style(style)
+    }
+
+    fn uid(&self) -> Option<String> {
+        Some(self.path.clone())
+    }
+}
+
+struct Report(Vec<Usage>);
+
+impl table::Items for Report {
+    type Item = Usage;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.clone()
+    }
+}
+
+enum Stage {
+    Container(Picker<Arc<Pod>>),
+    Collecting(String, JoinHandle<Result<Vec<Usage>>>),
+    Done(String, table::Table<Report>),
+    Failed(String),
+}
+
+/// Show what is taking up space in a container: every mount, with the
+/// volume it comes from, and the top level directories of the root
+/// filesystem, which is where anything written outside of a volume ends up.
+/// This is what to look at when something fails with "no space left on
+/// device".
+#[allow(clippy::module_name_repetitions)]
+pub struct DiskUsage {
+    client: kube::Client,
+    pod: Arc<Pod>,
+
+    stage: Stage,
+}
+
+impl DiskUsage {
+    pub fn new(client: kube::Client, pod: Arc<Pod>) -> Self {
+        let mut widget = Self {
+            client,
+            pod: pod.clone(),
+            stage: Stage::Failed(String::new()),
+        };
+
+        widget.stage = match Picker::new(pod) {
+            Choice::Only(container) => widget.collect(container),
+            Choice::Ask(picker) => Stage::Container(picker),
+        };
+
+        widget
+    }
+
+    fn collect(&self, container: Container) -> Stage {
+        let client = self.client.clone();
+        let pod = self.pod.clone();
+        let name = container.name_any();
+
+        Stage::Collecting(
+            name,
+            tokio::spawn(async move { disk::collect(client, &pod, container).await }),
+        )
+    }
+
+    fn finished(container: String, task: &mut JoinHandle<Result<Vec<Usage>>>) -> Stage {
+        match futures::executor::block_on(async move { task.await? }) {
+            Ok(usage) => Stage::Done(
+                container,
+                table::Table::builder()
+                    .items(Report(usage))
+                    .border(false)
+                    .remember("pods.disk")
+                    .sort(table::state::Sort {
+                        column: "Used (MiB)".to_string(),
+                        descending: true,
+                    })
+                    .build(),
+            ),
+            Err(err) => Stage::Failed(match err.downcast_ref::<kube::Error>() {
+                Some(kube::Error::Api(resp)) => resp.message.clone(),
+                _ => err.to_string(),
+            }),
+        }
+    }
+
+    fn title(&self) -> String {
+        match &self.stage {
+            Stage::Collecting(container, _) | Stage::Done(container, _) => {
+                format!("Disk Usage of {container}")
+            }
+            _ => "Disk Usage".to_string(),
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self.stage {
+            Stage::Container(_) => "enter: select container, esc: cancel",
+            Stage::Collecting(..) => "esc: cancel",
+            Stage::Done(..) => "s/S: sort, esc: close",
+            Stage::Failed(_) => "press any key to close",
+        }
+    }
+}
+
+impl Widget for DiskUsage {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match &mut self.stage {
+            Stage::Container(picker) => match picker.dispatch(event, buffer, area)? {
+                Answer::Picked(container) => self.stage = self.collect(container),
+                Answer::Cancelled => return Ok(Broadcast::Exited),
+                Answer::Waiting => {}
+            },
+            Stage::Collecting(..) => {
+                if matches!(key, exit_keys!()) {
+                    return Ok(Broadcast::Exited);
+                }
+            }
+            Stage::Done(_, table) => match table.dispatch(event, buffer, area)? {
+                Broadcast::Ignored if matches!(key, exit_keys!()) => return Ok(Broadcast::Exited),
+                _ => {}
+            },
+            Stage::Failed(_) => return Ok(Broadcast::Exited),
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Stage::Collecting(container, task) = &mut self.stage {
+            if task.is_finished() {
+                self.stage = Self::finished(container.clone(), task);
+            }
+        }
+
+        let area = centered_with(area, Constraint::Percentage(70), Constraint::Percentage(70));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title())
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Container(picker) => picker.draw(frame, inner)?,
+            Stage::Collecting(..) => frame.render_widget(
+                Paragraph::new("running df and du, large filesystems take a while..."),
+                inner,
+            ),
+            Stage::Done(_, table) => table.draw(frame, inner)?,
+            Stage::Failed(msg) => frame.render_widget(
+                Paragraph::new(msg.as_str())
+                    .style(Style::default().fg(theme::unhealthy()))
+                    .wrap(Wrap { trim: false }),
+                inner,
+            ),
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for DiskUsage {
+    fn drop(&mut self) {
+        if let Stage::Collecting(_, task) = &self.stage {
+            task.abort();
+        }
+    }
+}
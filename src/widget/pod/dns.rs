@@ -0,0 +1,425 @@
+use std::{net::IpAddr, sync::Arc};
+
+use eyre::{eyre, Result};
+use k8s_openapi::api::core::v1::{Pod, Service};
+use kube::{
+    api::{Api, AttachParams},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::{io::AsyncReadExt, task::JoinHandle};
+
+use super::picker::{Answer, Choice, Picker};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        container::{Container, ContainerExt},
+        service::ServiceExt,
+        status::StatusExt,
+        store::Store,
+    },
+    widget::{centered, nav::exit_keys, table, theme, Widget},
+};
+
+lazy_static! {
+    static ref DNS_LOOKUPS: IntCounterVec = register_int_counter_vec!(
+        "dns_lookups_total",
+        "Number of service lookups run from inside a pod, by the resolver used and the result",
+        &["resolver", "result"]
+    )
+    .unwrap();
+}
+
+struct Resolver {
+    name: &'static str,
+    command: &'static [&'static str],
+    /// `nslookup` starts with the address of the name server, which isn't
+    /// part of the answer.
+    header: bool,
+}
+
+// Tried in order until one exists in the image. Minimal images rarely have
+// all of them, `getent` goes through libc the same way most applications do.
+static RESOLVERS: &[Resolver] = &[
+    Resolver {
+        name: "getent",
+        command: &["getent", "hosts"],
+        header: false,
+    },
+    Resolver {
+        name: "nslookup",
+        command: &["nslookup"],
+        header: true,
+    },
+    Resolver {
+        name: "host",
+        command: &["host"],
+        header: false,
+    },
+];
+
+// What the runtime says when the command isn't in the image.
+static MISSING: &[&str] = &["executable file not found", "no such file or directory"];
+
+struct Lookup {
+    resolver: &'static str,
+    success: bool,
+    output: String,
+    addresses: Vec<IpAddr>,
+}
+
+impl Lookup {
+    fn new(resolver: &Resolver, success: bool, output: String) -> Self {
+        let answer = if resolver.header {
+            output.split_once("\n\n").map_or("", |(_, answer)| answer)
+        } else {
+            output.as_str()
+        };
+
+        let mut addresses: Vec<IpAddr> = answer
+            .split_whitespace()
+            .filter_map(|token| token.parse().ok())
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+
+        Self {
+            resolver: resolver.name,
+            success: success && !addresses.is_empty(),
+            output,
+            addresses,
+        }
+    }
+}
+
+enum Stage {
+    Container(Picker<Arc<Pod>>),
+    Service(Container, table::Table<Arc<Store<Service>>>),
+    Resolving(Arc<Service>, JoinHandle<Result<Lookup>>),
+    Done(Result<Vec<Line<'static>>, String>),
+}
+
+/// Look up a service's DNS name from inside a container, the same way the
+/// application in it would. The answer is compared against the service's
+/// cluster IP, since DNS is usually the first suspect when one thing can't
+/// talk to another.
+pub struct Resolve {
+    client: kube::Client,
+    pod: Arc<Pod>,
+    services: Arc<Store<Service>>,
+
+    stage: Stage,
+}
+
+impl Resolve {
+    pub fn new(client: kube::Client, pod: Arc<Pod>) -> Self {
+        let (services, _) = Store::new(client.clone());
+        let mut resolve = Self {
+            client,
+            pod: pod.clone(),
+            services,
+            stage: Stage::Done(Ok(Vec::new())),
+        };
+
+        resolve.stage = match Picker::new(pod) {
+            Choice::Only(container) => resolve.pick(container),
+            Choice::Ask(picker) => Stage::Container(picker),
+        };
+
+        resolve
+    }
+
+    fn pick(&self, container: Container) -> Stage {
+        Stage::Service(
+            container,
+            table::Table::builder()
+                .items(self.services.clone())
+                .border(false)
+                .build(),
+        )
+    }
+
+    fn resolve(&self, container: &Container, service: Arc<Service>) -> Stage {
+        let client = self.client.clone();
+        let pod = self.pod.clone();
+        let container = container.name_any();
+        let name = service.dns_name();
+
+        Stage::Resolving(
+            service,
+            tokio::spawn(async move {
+                let lookup = lookup(client, &pod, &container, &name).await;
+
+                if let Ok(lookup) = &lookup {
+                    DNS_LOOKUPS
+                        .with_label_values(&[
+                            lookup.resolver,
+                            if lookup.success { "success" } else { "failure" },
+                        ])
+                        .inc();
+                }
+
+                lookup
+            }),
+        )
+    }
+
+    fn advance(
+        &mut self,
+        stage: Stage,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<(Stage, Broadcast)> {
+        let stage = match stage {
+            Stage::Container(mut picker) => match picker.dispatch(event, buffer, area)? {
+                Answer::Picked(container) => self.pick(container),
+                Answer::Cancelled => return Ok((Stage::Container(picker), Broadcast::Exited)),
+                Answer::Waiting => Stage::Container(picker),
+            },
+            Stage::Service(container, mut table) => match table.dispatch(event, buffer, area)? {
+                Broadcast::Selected(idx) => match self.services.get(idx, None) {
+                    Some(service) => self.resolve(&container, service),
+                    None => Stage::Service(container, table),
+                },
+                _ if matches!(event.key(), Some(exit_keys!())) => {
+                    return Ok((Stage::Service(container, table), Broadcast::Exited))
+                }
+                _ => Stage::Service(container, table),
+            },
+            Stage::Resolving(service, task) => Stage::Resolving(service, task),
+            Stage::Done(result) => {
+                if event.key().is_some() {
+                    return Ok((Stage::Done(result), Broadcast::Exited));
+                }
+
+                Stage::Done(result)
+            }
+        };
+
+        Ok((stage, Broadcast::Consumed))
+    }
+
+    fn finished(service: &Service, task: &mut JoinHandle<Result<Lookup>>) -> Stage {
+        let result = futures::executor::block_on(async move { task.await? });
+
+        Stage::Done(match result {
+            Ok(lookup) => Ok(report(service, &lookup)),
+            Err(err) => Err(match err.downcast_ref::<kube::Error>() {
+                Some(kube::Error::Api(resp)) => resp.message.clone(),
+                _ => err.to_string(),
+            }),
+        })
+    }
+
+    fn title(&self) -> String {
+        match &self.stage {
+            Stage::Service(container, _) => format!("Resolve from {}", container.name_any()),
+            Stage::Resolving(service, _) => format!("Resolving {}", service.dns_name()),
+            _ => "Resolve Service".to_string(),
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self.stage {
+            Stage::Container(_) => "enter: select container, esc: cancel",
+            Stage::Service(..) => "enter: resolve service, esc: cancel",
+            Stage::Resolving(..) => "",
+            Stage::Done(_) => "press any key to close",
+        }
+    }
+}
+
+fn report(service: &Service, lookup: &Lookup) -> Vec<Line<'static>> {
+    let ok = Style::default().fg(theme::healthy());
+    let bad = Style::default().fg(theme::unhealthy());
+    let detail = Style::default().fg(tailwind::GRAY.c400);
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled(format!("{}: ", lookup.resolver), detail),
+        Span::raw(service.dns_name()),
+    ])];
+
+    if lookup.success {
+        let found = lookup
+            .addresses
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        lines.push(Line::styled(
+            format!("resolved to {}", found.join(", ")),
+            ok,
+        ));
+
+        let expected = service.cluster_ips();
+
+        if service.is_headless() {
+            lines.push(Line::styled(
+                "headless, these should be the addresses of the ready pods",
+                detail,
+            ));
+        } else if expected.iter().any(|ip| found.contains(ip)) {
+            lines.push(Line::styled("matches the cluster IP", ok));
+        } else {
+            lines.push(Line::styled(
+                format!("expected the cluster IP {}", expected.join(", ")),
+                bad,
+            ));
+        }
+    } else {
+        lines.push(Line::styled("did not resolve", bad));
+    }
+
+    lines.push(Line::default());
+    lines.extend(
+        lookup
+            .output
+            .lines()
+            .map(|line| Line::styled(line.to_string(), detail)),
+    );
+
+    lines
+}
+
+async fn lookup(client: kube::Client, pod: &Pod, container: &str, name: &str) -> Result<Lookup> {
+    let api = Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default());
+
+    for resolver in RESOLVERS {
+        let mut command = resolver.command.to_vec();
+        command.push(name);
+
+        let mut proc = api
+            .exec(
+                &pod.name_any(),
+                command,
+                &AttachParams {
+                    container: Some(container.to_string()),
+                    stdin: false,
+                    stdout: true,
+                    stderr: true,
+                    tty: false,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let status = proc
+            .take_status()
+            .ok_or_else(|| eyre!("status not available"))?;
+
+        let (mut stdout, mut stderr) = (String::new(), String::new());
+        proc.stdout()
+            .ok_or_else(|| eyre!("stdout not available"))?
+            .read_to_string(&mut stdout)
+            .await?;
+        proc.stderr()
+            .ok_or_else(|| eyre!("stderr not available"))?
+            .read_to_string(&mut stderr)
+            .await?;
+
+        let status = status.await.ok_or_else(|| eyre!("status not available"))?;
+
+        proc.join().await?;
+
+        let message = status.message.clone().unwrap_or_default();
+        if MISSING.iter().any(|missing| message.contains(missing)) {
+            continue;
+        }
+
+        // A resolver that ran but that couldn't find anything is still an answer,
+        // there's no point in asking the next one.
+        return Ok(Lookup::new(
+            resolver,
+            status.is_success(),
+            format!("{stdout}{stderr}").trim_end().to_string(),
+        ));
+    }
+
+    Err(eyre!(
+        "none of {} are available in this container",
+        RESOLVERS
+            .iter()
+            .map(|resolver| resolver.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+impl Widget for Resolve {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if event.key().is_none() {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(Vec::new())));
+
+        match self.advance(stage, event, buffer, area) {
+            Ok((stage, result)) => {
+                self.stage = stage;
+
+                Ok(result)
+            }
+            Err(err) => {
+                self.stage = Stage::Done(Err(err.to_string()));
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Stage::Resolving(service, task) = &mut self.stage {
+            if task.is_finished() {
+                self.stage = Self::finished(&service.clone(), task);
+            }
+        }
+
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title())
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Container(picker) => picker.draw(frame, inner)?,
+            Stage::Service(_, table) => table.draw(frame, inner)?,
+            Stage::Resolving(..) => frame.render_widget(Paragraph::new("resolving..."), inner),
+            Stage::Done(Ok(lines)) => frame.render_widget(
+                Paragraph::new(lines.clone()).wrap(Wrap { trim: false }),
+                inner,
+            ),
+            Stage::Done(Err(msg)) => frame.render_widget(
+                Paragraph::new(msg.as_str())
+                    .style(Style::default().fg(theme::unhealthy()))
+                    .wrap(Wrap { trim: false }),
+                inner,
+            ),
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Resolve {
+    fn drop(&mut self) {
+        if let Stage::Resolving(_, task) = &self.stage {
+            task.abort();
+        }
+    }
+}
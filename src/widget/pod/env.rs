@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::{ConfigMap, Pod, Secret};
+use kube::{api::Api, ResourceExt};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::pod::env::{self, Refs, Var},
+    widget::{
+        nav::{move_cursor, Movement},
+        tabs::Tab,
+        theme, Action, Widget, WIDGET_VIEWS,
+    },
+};
+
+static MASK: &str = "********";
+
+struct EnvStyle {
+    heading: Style,
+    name: Style,
+    value: Style,
+    source: Style,
+    secret: Style,
+}
+
+impl Default for EnvStyle {
+    fn default() -> Self {
+        Self {
+            heading: Style::default().add_modifier(Modifier::BOLD),
+            name: Style::default().fg(tailwind::BLUE.c300),
+            value: Style::default(),
+            source: Style::default().fg(tailwind::GRAY.c400),
+            secret: Style::default().fg(tailwind::AMBER.c300),
+        }
+    }
+}
+
+/// Every container's environment the way it ends up inside the container,
+/// with `envFrom` and `valueFrom` references looked up. Secret values stay
+/// masked until they're revealed.
+pub struct Env {
+    pod: Arc<Pod>,
+
+    task: Option<JoinHandle<Refs>>,
+    refs: Refs,
+
+    reveal: bool,
+    scroll: u16,
+}
+
+impl Env {
+    pub fn new(client: &kube::Client, pod: Arc<Pod>) -> Self {
+        WIDGET_VIEWS.pod.env.inc();
+
+        Self {
+            task: Some(tokio::spawn(fetch(client.clone(), pod.clone()))),
+            pod,
+            refs: Refs::default(),
+            reveal: false,
+            scroll: 0,
+        }
+    }
+
+    pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || Self::new(&client, pod.clone()).boxed()))
+            .build()
+    }
+
+    fn var(&self, var: &Var, width: usize, style: &EnvStyle) -> Line<'static> {
+        let value = match &var.value {
+            Ok(_) if var.is_secret() && !self.reveal => Span::styled(MASK, style.secret),
+            Ok(value) if var.is_secret() => Span::styled(value.clone(), style.secret),
+            Ok(value) => Span::styled(value.clone(), style.value),
+            Err(err) => Span::styled(err.clone(), Style::default().fg(theme::unhealthy())),
+        };
+
+        Line::from(vec![
+            Span::styled(format!("  {:<width$}", var.name), style.name),
+            Span::styled(" = ", style.source),
+            value,
+            Span::styled(format!("  ({})", var.source), style.source),
+        ])
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let style = EnvStyle::default();
+
+        if self.task.is_some() {
+            return vec![Line::from(Span::styled(
+                "looking up references...",
+                style.source,
+            ))];
+        }
+
+        let mut lines = Vec::new();
+
+        for (container, vars) in env::all(&self.pod, &self.refs) {
+            lines.push(Line::from(Span::styled(container, style.heading)));
+
+            if vars.is_empty() {
+                lines.push(Line::from(Span::styled("  no variables", style.source)));
+            }
+
+            let width = vars.iter().map(|var| var.name.len()).max().unwrap_or(0);
+
+            for var in &vars {
+                lines.push(self.var(var, width, &style));
+            }
+
+            lines.push(Line::default());
+        }
+
+        lines
+    }
+}
+
+// Lookups that fail are kept as errors so that a missing `get` on secrets only
+// affects the variables that come from them.
+async fn fetch(client: kube::Client, pod: Arc<Pod>) -> Refs {
+    let namespace = pod.namespace().unwrap_or_default();
+    let (config_maps, secrets) = env::references(&pod);
+
+    let error = |err: kube::Error| match err {
+        kube::Error::Api(resp) => resp.message,
+        err => err.to_string(),
+    };
+
+    let mut refs = Refs::default();
+
+    let api = Api::<ConfigMap>::namespaced(client.clone(), &namespace);
+    for name in config_maps {
+        let cm = api.get_opt(&name).await.map_err(error);
+
+        refs.config_maps.insert(name, cm);
+    }
+
+    let api = Api::<Secret>::namespaced(client, &namespace);
+    for name in secrets {
+        let secret = api.get_opt(&name).await.map_err(error);
+
+        refs.secrets.insert(name, secret);
+    }
+
+    refs
+}
+
+impl Widget for Env {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(key, Keypress::Printable('r')) {
+            self.reveal = !self.reveal;
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        if let Some(Movement::Y(y)) = move_cursor(key, area) {
+            self.scroll = self.scroll.saturating_add_signed(y);
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        Ok(Broadcast::Ignored)
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action::new(
+            'r',
+            if self.reveal {
+                "Hide secrets"
+            } else {
+                "Reveal secrets"
+            },
+        )]
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Some(task) = self.task.as_mut() {
+            if task.is_finished() {
+                if let Ok(refs) = futures::executor::block_on(task) {
+                    self.refs = refs;
+                }
+
+                self.task = None;
+            }
+        }
+
+        let [area, footer] =
+            Layout::vertical([Constraint::Fill(0), Constraint::Length(1)]).areas(area);
+
+        let lines = self.lines();
+
+        self.scroll = self.scroll.min(
+            u16::try_from(lines.len())
+                .unwrap_or(u16::MAX)
+                .saturating_sub(area.height),
+        );
+
+        frame.render_widget(Paragraph::new(lines).scroll((self.scroll, 0)), area);
+
+        let help = if self.reveal {
+            "r: hide secrets"
+        } else {
+            "r: reveal secrets"
+        };
+
+        frame.render_widget(
+            Line::from(help).style(Style::default().fg(tailwind::GRAY.c400)),
+            footer,
+        );
+
+        Ok(())
+    }
+}
+
+impl Drop for Env {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use eyre::Result;
+use k8s_openapi::api::core::v1::{Event, Pod};
+use kube::ResourceExt;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{
+    events::{Broadcast, Event as UiEvent},
+    resources::{
+        age::Age,
+        event,
+        pod::lifecycle::{LifecycleExt, Milestone},
+        store::Store,
+    },
+    widget::{
+        nav::{move_cursor, Movement},
+        tabs::Tab,
+        theme, Widget, WIDGET_VIEWS,
+    },
+};
+
+static AGO_WIDTH: usize = 9;
+static KIND_WIDTH: usize = 13;
+
+struct LifecycleStyle {
+    ago: Style,
+    normal: Style,
+    warning: Style,
+    container: Style,
+    detail: Style,
+}
+
+impl Default for LifecycleStyle {
+    fn default() -> Self {
+        Self {
+            ago: Style::default().fg(tailwind::GRAY.c400),
+            normal: Style::default().fg(theme::healthy()),
+            warning: Style::default().fg(theme::unhealthy()),
+            container: Style::default().add_modifier(Modifier::BOLD),
+            detail: Style::default().fg(tailwind::GRAY.c400),
+        }
+    }
+}
+
+/// The pod's life as a list of milestones, such as when it was scheduled,
+/// pulled its images, started and became ready, along with any restarts and
+/// probe failures since. Read top to bottom, it is a quick narrative of what
+/// has happened without digging through events and status.
+pub struct Lifecycle {
+    pod: Arc<Pod>,
+    events: Arc<Store<Event>>,
+
+    scroll: u16,
+}
+
+impl Lifecycle {
+    pub fn new(client: &kube::Client, pod: Arc<Pod>) -> Self {
+        WIDGET_VIEWS.pod.lifecycle.inc();
+
+        let (events, _) = Store::with_config(
+            client.clone(),
+            event::involving(&pod.uid().unwrap_or_default()),
+        );
+
+        Self {
+            pod,
+            events,
+            scroll: 0,
+        }
+    }
+
+    pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || Self::new(&client, pod.clone()).boxed()))
+            .build()
+    }
+
+    fn line(milestone: &Milestone, style: &LifecycleStyle) -> Line<'static> {
+        let ago = format!("{} ago", (Utc::now() - milestone.at).to_age());
+
+        let mut spans = vec![
+            Span::styled(format!("{ago:>AGO_WIDTH$}  "), style.ago),
+            Span::styled(
+                format!("{:<KIND_WIDTH$}", milestone.kind.to_string()),
+                if milestone.is_warning() {
+                    style.warning
+                } else {
+                    style.normal
+                },
+            ),
+        ];
+
+        if let Some(container) = milestone.container.as_ref() {
+            spans.push(Span::styled(format!("{container} "), style.container));
+        }
+
+        spans.push(Span::styled(milestone.detail.clone(), style.detail));
+
+        if milestone.count > 1 {
+            spans.push(Span::styled(
+                format!(" (x{})", milestone.count),
+                style.detail,
+            ));
+        }
+
+        Line::from(spans)
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let style = LifecycleStyle::default();
+
+        self.pod
+            .milestones(&self.events.snapshot())
+            .iter()
+            .map(|milestone| Self::line(milestone, &style))
+            .collect()
+    }
+}
+
+impl Widget for Lifecycle {
+    fn dispatch(&mut self, event: &UiEvent, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if let Some(Movement::Y(y)) = move_cursor(key, area) {
+            self.scroll = self.scroll.saturating_add_signed(y);
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        Ok(Broadcast::Ignored)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [body, help] =
+            Layout::vertical([Constraint::Fill(0), Constraint::Length(1)]).areas(area);
+
+        let lines = self.lines();
+        self.scroll = self
+            .scroll
+            .min((lines.len() as u16).saturating_sub(body.height));
+
+        frame.render_widget(Paragraph::new(lines).scroll((self.scroll, 0)), body);
+        frame.render_widget(
+            Line::from(
+                "oldest first, events are only kept for a while so older entries come from the \
+                 pod's status",
+            )
+            .style(LifecycleStyle::default().detail),
+            help,
+        );
+
+        Ok(())
+    }
+}
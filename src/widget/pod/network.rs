@@ -0,0 +1,231 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use eyre::{eyre, Result};
+use k8s_openapi::api::core::v1::{self, Pod};
+use kube::{
+    api::{Api, Patch, PatchParams},
+    runtime::wait::await_condition,
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use serde_json::json;
+use tokio::task::JoinHandle;
+
+use super::shell::{Command, History};
+use crate::{
+    events::{Broadcast, Event},
+    policy,
+    resources::{container::Container, pod::debug},
+    widget::{centered, theme, Widget},
+};
+
+// Ephemeral containers only pull their image once they've been added, which
+// is most of the wait.
+static START_TIMEOUT: Duration = Duration::from_secs(120);
+
+lazy_static! {
+    static ref NETWORK_DEBUG: IntCounterVec = register_int_counter_vec!(
+        "pod_network_debug_total",
+        "Number of network debugging containers attached to pods, by whether they started",
+        &["result"]
+    )
+    .unwrap();
+}
+
+enum Stage {
+    Attaching(JoinHandle<Result<(Arc<Pod>, v1::Container)>>),
+    Shell(Command),
+    Failed(String),
+}
+
+/// Open a shell in an ephemeral container with networking tools, such as
+/// `tcpdump` and `ss`, that shares the pod's network namespace. The container
+/// can't be removed once it has been added, so one that is still running is
+/// reused instead of adding another.
+#[allow(clippy::module_name_repetitions)]
+pub struct NetworkDebug {
+    client: kube::Client,
+    pod: Arc<Pod>,
+
+    stage: Stage,
+}
+
+impl NetworkDebug {
+    pub fn new(client: kube::Client, pod: Arc<Pod>) -> Self {
+        let stage = match policy::network_debug() {
+            Ok(image) => {
+                let client = client.clone();
+                let pod = pod.clone();
+
+                Stage::Attaching(tokio::spawn(async move {
+                    let result = attach(client, pod, image).await;
+
+                    NETWORK_DEBUG
+                        .with_label_values(&[if result.is_ok() { "success" } else { "failure" }])
+                        .inc();
+
+                    result
+                }))
+            }
+            Err(err) => Stage::Failed(err.to_string()),
+        };
+
+        Self { client, pod, stage }
+    }
+
+    fn attached(&self, task: JoinHandle<Result<(Arc<Pod>, v1::Container)>>) -> Stage {
+        match futures::executor::block_on(async move { task.await? }) {
+            Ok((pod, container)) => Stage::Shell(Command::new(
+                self.client.clone(),
+                pod.clone(),
+                Container::new(pod.as_ref().clone(), container),
+                History::default(),
+            )),
+            Err(err) => Stage::Failed(message(&err)),
+        }
+    }
+}
+
+async fn attach(
+    client: kube::Client,
+    pod: Arc<Pod>,
+    image: &str,
+) -> Result<(Arc<Pod>, v1::Container)> {
+    if let Some(container) = debug::running_network(&pod, image) {
+        return Ok((pod, container));
+    }
+
+    let api = Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default());
+    let name = pod.name_any();
+
+    let container = debug::network(
+        &pod,
+        image,
+        format!("{}{}", debug::NETWORK_PREFIX, Utc::now().timestamp()),
+    );
+
+    api.patch_ephemeral_containers(
+        &name,
+        &PatchParams::default(),
+        &Patch::Strategic(json!({
+            "spec": {
+                "ephemeralContainers": [container],
+            },
+        })),
+    )
+    .await?;
+
+    let running = tokio::time::timeout(
+        START_TIMEOUT,
+        await_condition(api, &name, |pod: Option<&Pod>| {
+            pod.is_some_and(|pod| debug::running_network(pod, image).is_some())
+        }),
+    )
+    .await;
+
+    match running {
+        Ok(Ok(Some(pod))) => debug::running_network(&pod, image)
+            .map(|container| (Arc::new(pod), container))
+            .ok_or_else(|| eyre!("{} isn't running", container.name)),
+        Ok(Ok(None)) => Err(eyre!("{name} was deleted before the container started")),
+        Ok(Err(err)) => Err(err.into()),
+        Err(_) => Err(eyre!(
+            "{} didn't start within {}s, check the events of {name} for why",
+            container.name,
+            START_TIMEOUT.as_secs()
+        )),
+    }
+}
+
+fn message(err: &eyre::Report) -> String {
+    match err.downcast_ref::<kube::Error>() {
+        Some(kube::Error::Api(resp)) => resp.message.clone(),
+        _ => err.to_string(),
+    }
+}
+
+impl Widget for NetworkDebug {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        match &mut self.stage {
+            Stage::Shell(command) => match command.dispatch(event, buffer, area) {
+                Ok(Broadcast::Exited) => Ok(Broadcast::Exited),
+                Ok(Broadcast::Raw(raw)) => Ok(Broadcast::Raw(raw)),
+                Ok(_) => Ok(Broadcast::Consumed),
+                Err(err) => {
+                    self.stage = Stage::Failed(err.to_string());
+
+                    Ok(Broadcast::Consumed)
+                }
+            },
+            // There's nothing to cancel, the container gets added either way. All
+            // closing does is stop waiting for it to start.
+            _ if event.key().is_some() => Ok(Broadcast::Exited),
+            _ => Ok(Broadcast::Ignored),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let stage = std::mem::replace(&mut self.stage, Stage::Failed(String::new()));
+
+        self.stage = match stage {
+            Stage::Attaching(task) if task.is_finished() => self.attached(task),
+            stage => stage,
+        };
+
+        if let Stage::Shell(command) = &mut self.stage {
+            return command.draw(frame, area);
+        }
+
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Network Debug of {}", self.pod.name_any()))
+            .title_bottom(
+                Line::from("press any key to close")
+                    .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        let (msg, style) = match &self.stage {
+            Stage::Failed(err) => (err.as_str(), Style::default().fg(theme::unhealthy())),
+            _ => (
+                "attaching the debug container and waiting for it to start...",
+                Style::default(),
+            ),
+        };
+
+        frame.render_widget(
+            Paragraph::new(msg).style(style).wrap(Wrap { trim: false }),
+            inner,
+        );
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for NetworkDebug {
+    fn drop(&mut self) {
+        if let Stage::Attaching(task) = &self.stage {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use eyre::Result;
+use ratatui::{buffer::Buffer, layout::Rect, Frame};
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    widget::{nav::exit_keys, table, Widget},
+};
+
+/// Where things start off for popups that do something in a single container,
+/// such as sending it a signal. There's no reason to ask which container when
+/// there's only one.
+pub enum Choice<S>
+where
+    S: table::Items,
+{
+    Only(S::Item),
+    Ask(Picker<S>),
+}
+
+pub enum Answer<T> {
+    Picked(T),
+    Cancelled,
+    Waiting,
+}
+
+/// Table of the containers to pick from, usually every one in the pod. Popups
+/// draw it inside their own block, with their own help for what happens to
+/// the container that is picked.
+pub struct Picker<S>
+where
+    S: table::Items,
+{
+    items: S,
+    table: table::Table<S>,
+}
+
+impl<S> Picker<S>
+where
+    S: table::Items + Clone,
+    S::Item: table::Row,
+{
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(items: S) -> Choice<S> {
+        let mut containers = items.items(None);
+
+        if containers.len() == 1 {
+            return Choice::Only(containers.remove(0));
+        }
+
+        Choice::Ask(Self {
+            table: table::Table::builder()
+                .items(items.clone())
+                .border(false)
+                .build(),
+            items,
+        })
+    }
+
+    pub fn dispatch(
+        &mut self,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<Answer<S::Item>> {
+        match self.table.dispatch(event, buffer, area)? {
+            Broadcast::Selected(idx) => {
+                let mut containers = self.items.items(None);
+
+                Ok(if idx < containers.len() {
+                    Answer::Picked(containers.swap_remove(idx))
+                } else {
+                    Answer::Waiting
+                })
+            }
+            _ if matches!(event.key(), Some(exit_keys!())) => Ok(Answer::Cancelled),
+            _ => Ok(Answer::Waiting),
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.table.draw(frame, area)
+    }
+}
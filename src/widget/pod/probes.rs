@@ -0,0 +1,279 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use eyre::Result;
+use k8s_openapi::api::core::v1::{Event, Pod};
+use kube::ResourceExt;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{
+    events::{Broadcast, Event as UiEvent, Keypress},
+    resources::{
+        age::Age,
+        event,
+        pod::probe::{Probe, ProbeExt, Timeline},
+        store::Store,
+    },
+    widget::{
+        nav::{move_cursor, Movement},
+        tabs::Tab,
+        theme, Action, Widget, WIDGET_VIEWS,
+    },
+};
+
+// How far back the timeline goes, `z` cycles through these.
+static WINDOWS: &[TimeDelta] = &[
+    TimeDelta::minutes(15),
+    TimeDelta::hours(1),
+    TimeDelta::hours(6),
+    TimeDelta::days(1),
+];
+
+static LABEL_WIDTH: u16 = 12;
+
+struct ProbeStyle {
+    container: Style,
+    passed: Style,
+    failed: Style,
+    restarted: Style,
+    absent: Style,
+    detail: Style,
+}
+
+impl Default for ProbeStyle {
+    fn default() -> Self {
+        Self {
+            container: Style::default().add_modifier(Modifier::BOLD),
+            passed: Style::default().fg(theme::healthy()),
+            failed: Style::default().fg(theme::unhealthy()),
+            restarted: Style::default().fg(tailwind::YELLOW.c300),
+            absent: Style::default().fg(tailwind::GRAY.c700),
+            detail: Style::default().fg(tailwind::GRAY.c400),
+        }
+    }
+}
+
+/// Recent probe results for each container, one column per slice of time, so
+/// that a readiness probe that keeps flapping shows up as a pattern. Results
+/// are pieced together from `Unhealthy` events and the container's status.
+pub struct Probes {
+    pod: Arc<Pod>,
+    events: Arc<Store<Event>>,
+
+    window: usize,
+    scroll: u16,
+}
+
+impl Probes {
+    pub fn new(client: &kube::Client, pod: Arc<Pod>) -> Self {
+        WIDGET_VIEWS.pod.probes.inc();
+
+        let (events, _) = Store::with_config(
+            client.clone(),
+            event::involving(&pod.uid().unwrap_or_default()),
+        );
+
+        Self {
+            pod,
+            events,
+            window: 1,
+            scroll: 0,
+        }
+    }
+
+    pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || Self::new(&client, pod.clone()).boxed()))
+            .build()
+    }
+
+    fn window(&self) -> TimeDelta {
+        WINDOWS[self.window % WINDOWS.len()]
+    }
+
+    fn header(timeline: &Timeline, style: &ProbeStyle) -> Line<'static> {
+        let now = Utc::now();
+
+        let mut details = vec![if timeline.ready { "ready" } else { "not ready" }.to_string()];
+
+        if let Some(started) = timeline.started {
+            details.push(format!("started {} ago", (now - started).to_age()));
+        }
+
+        if timeline.restarts > 0 {
+            details.push(format!("{} restarts", timeline.restarts));
+        }
+
+        Line::from(vec![
+            Span::styled(timeline.container.clone(), style.container),
+            Span::styled(format!("  {}", details.join(", ")), style.detail),
+        ])
+    }
+
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn bar(
+        &self,
+        probe: &Probe,
+        timeline: &Timeline,
+        width: u16,
+        style: &ProbeStyle,
+    ) -> Line<'static> {
+        let now = Utc::now();
+        let start = now - self.window();
+        let step = self.window() / i32::from(width.max(1));
+
+        let created: Option<DateTime<Utc>> = self
+            .pod
+            .status
+            .as_ref()
+            .and_then(|status| status.start_time.as_ref())
+            .map(|time| time.0);
+
+        let mut spans = vec![Span::raw(format!(
+            "  {:<width$}",
+            probe.kind.to_string(),
+            width = LABEL_WIDTH as usize - 2
+        ))];
+
+        spans.extend((0..i32::from(width)).map(|i| {
+            let (from, to) = (start + step * i, start + step * (i + 1));
+            let within = |at: &DateTime<Utc>| *at >= from && *at < to;
+
+            if timeline.restarted.as_ref().is_some_and(within) {
+                Span::styled("↻", style.restarted)
+            } else if probe.failures.iter().any(|failure| within(&failure.at)) {
+                Span::styled("█", style.failed)
+            } else if created.is_some_and(|created| to < created) {
+                Span::styled("·", style.absent)
+            } else {
+                Span::styled("▁", style.passed)
+            }
+        }));
+
+        Line::from(spans)
+    }
+
+    fn summary(&self, probe: &Probe, style: &ProbeStyle) -> Line<'static> {
+        let start = Utc::now() - self.window();
+        let recent: Vec<_> = probe
+            .failures
+            .iter()
+            .filter(|failure| failure.at >= start)
+            .collect();
+
+        let text = match recent.last() {
+            None => "no failures".to_string(),
+            Some(last) => format!(
+                "{} failures, last {} ago: {}",
+                recent.len(),
+                (Utc::now() - last.at).to_age(),
+                last.message
+            ),
+        };
+
+        Line::from(Span::styled(
+            format!("{:width$}{text}", "", width = LABEL_WIDTH as usize),
+            style.detail,
+        ))
+    }
+
+    fn lines(&self, width: u16) -> Vec<Line<'static>> {
+        let style = ProbeStyle::default();
+        let width = width.saturating_sub(LABEL_WIDTH);
+
+        let mut lines = Vec::new();
+
+        for timeline in self.pod.probes(&self.events.snapshot()) {
+            lines.push(Self::header(&timeline, &style));
+
+            if timeline.probes.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "  no probes configured",
+                    style.detail,
+                )));
+            }
+
+            for probe in &timeline.probes {
+                lines.push(self.bar(probe, &timeline, width, &style));
+                lines.push(self.summary(probe, &style));
+            }
+
+            lines.push(Line::default());
+        }
+
+        lines
+    }
+
+    fn axis(&self, width: u16) -> Line<'static> {
+        let since = format!("{} ago", self.window().to_age());
+        let padding = usize::from(width.saturating_sub(LABEL_WIDTH))
+            .saturating_sub(since.len() + "now".len());
+
+        Line::from(format!(
+            "{:offset$}{since}{:padding$}now",
+            "",
+            "",
+            offset = LABEL_WIDTH as usize,
+        ))
+        .style(ProbeStyle::default().detail)
+    }
+}
+
+impl Widget for Probes {
+    fn dispatch(&mut self, event: &UiEvent, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(key, Keypress::Printable('z')) {
+            self.window = (self.window + 1) % WINDOWS.len();
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        if let Some(Movement::Y(y)) = move_cursor(key, area) {
+            self.scroll = self.scroll.saturating_add_signed(y);
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        Ok(Broadcast::Ignored)
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action::new('z', "Change the time window")]
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [body, axis, help] = Layout::vertical([
+            Constraint::Fill(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(area);
+
+        let lines = self.lines(area.width);
+        self.scroll = self
+            .scroll
+            .min((lines.len() as u16).saturating_sub(body.height));
+
+        frame.render_widget(Paragraph::new(lines).scroll((self.scroll, 0)), body);
+        frame.render_widget(self.axis(area.width), axis);
+        frame.render_widget(
+            Line::from("█ failed, ▁ no failures, ↻ restarted, z: change window")
+                .style(ProbeStyle::default().detail),
+            help,
+        );
+
+        Ok(())
+    }
+}
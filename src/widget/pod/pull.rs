@@ -0,0 +1,284 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use itertools::Itertools;
+use k8s_openapi::api::core::v1::{Event as KubeEvent, Pod, Secret};
+use kube::{
+    api::{Api, ListParams},
+    ResourceExt,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        event::EventExt,
+        pod::pull::{Credentials, Failure, PullExt},
+    },
+    widget::{
+        centered_with,
+        nav::{exit_keys, move_cursor, Movement},
+        theme::{self, Indicator},
+        Widget,
+    },
+};
+
+static LABEL_WIDTH: usize = 10;
+
+// What looking up a pull secret turned up, `Err` is why it couldn't be read.
+type Lookup = (String, Result<Option<Secret>, String>);
+
+struct Report {
+    failures: Vec<(Failure, Option<String>)>,
+    secrets: Vec<Lookup>,
+}
+
+/// Explains why a pod can't pull its image. For every container stuck waiting
+/// on one, this shows the image, the error the kubelet reported and whether
+/// any of the pod's pull secrets have credentials for the registry.
+#[allow(clippy::module_name_repetitions)]
+pub struct ImagePull {
+    task: Option<JoinHandle<Result<Report>>>,
+    report: Option<Result<Report, String>>,
+
+    scroll: u16,
+}
+
+impl ImagePull {
+    pub fn new(client: kube::Client, pod: Arc<Pod>) -> Self {
+        Self {
+            task: Some(tokio::spawn(report(client, pod))),
+            report: None,
+            scroll: 0,
+        }
+    }
+
+    fn field(label: &str, value: impl Into<String>, style: Style) -> Line<'static> {
+        Line::from(vec![
+            Span::styled(
+                format!("  {label:<LABEL_WIDTH$}"),
+                Style::default().fg(tailwind::GRAY.c400),
+            ),
+            Span::styled(value.into(), style),
+        ])
+    }
+
+    fn secret(lookup: &Lookup, registry: &str) -> Line<'static> {
+        let (name, secret) = lookup;
+
+        let (ok, msg) = match secret {
+            Err(err) => (false, format!("unable to read: {err}")),
+            Ok(None) => (false, "does not exist".to_string()),
+            Ok(Some(secret)) => match Credentials::check(secret, registry) {
+                Credentials::Found(key) => (true, format!("has credentials for {key}")),
+                Credentials::Missing(hosts) if hosts.is_empty() => {
+                    (false, "has no credentials at all".to_string())
+                }
+                Credentials::Missing(hosts) => (false, format!("only has {}", hosts.join(", "))),
+                Credentials::Invalid(reason) => (false, reason),
+            },
+        };
+
+        let (indicator, color) = if ok {
+            (Indicator::Healthy, theme::healthy())
+        } else {
+            (Indicator::Unhealthy, theme::unhealthy())
+        };
+
+        Self::field(&indicator.label(name), msg, Style::default().fg(color))
+    }
+
+    fn lines(report: &Report) -> Vec<Line<'static>> {
+        let heading = Style::default().add_modifier(Modifier::BOLD);
+
+        if report.failures.is_empty() {
+            return vec![Line::from(format!(
+                "{} every container has its image",
+                Indicator::Healthy.glyph()
+            ))];
+        }
+
+        let mut lines = Vec::new();
+
+        for (failure, event) in &report.failures {
+            let registry = failure.registry();
+
+            lines.push(Line::from(vec![
+                Span::styled(failure.container.clone(), heading),
+                Span::styled(
+                    format!(" {}", Indicator::Unhealthy.label(&failure.reason)),
+                    Style::default().fg(theme::unhealthy()),
+                ),
+            ]));
+            lines.push(Self::field(
+                "image",
+                failure.image.clone(),
+                Style::default(),
+            ));
+            lines.push(Self::field("registry", registry.clone(), Style::default()));
+            lines.push(Self::field(
+                "error",
+                event
+                    .as_ref()
+                    .or(failure.message.as_ref())
+                    .map_or("-", String::as_str),
+                Style::default(),
+            ));
+
+            lines.push(Line::from(Span::styled("  pull secrets", heading)));
+
+            if report.secrets.is_empty() {
+                lines.push(Self::field(
+                    "none",
+                    "the pod and its service account have no imagePullSecrets",
+                    Style::default().fg(tailwind::GRAY.c400),
+                ));
+            }
+
+            for lookup in &report.secrets {
+                lines.push(Self::secret(lookup, &registry));
+            }
+
+            lines.push(Line::default());
+        }
+
+        lines
+    }
+}
+
+async fn report(client: kube::Client, pod: Arc<Pod>) -> Result<Report> {
+    let namespace = pod.namespace().unwrap_or_default();
+    let failures = pod.pull_failures();
+
+    if failures.is_empty() {
+        return Ok(Report {
+            failures: Vec::new(),
+            secrets: Vec::new(),
+        });
+    }
+
+    let events = Api::<KubeEvent>::namespaced(client.clone(), &namespace)
+        .list(&ListParams::default().fields(&format!(
+            "involvedObject.uid={}",
+            pod.uid().unwrap_or_default()
+        )))
+        .await?;
+
+    // The waiting message is often only `Back-off pulling image`, the event is
+    // where the registry's actual answer ends up.
+    let failures = failures
+        .into_iter()
+        .map(|failure| {
+            let event = events
+                .items
+                .iter()
+                .filter(|event| event.reason.as_deref() == Some("Failed"))
+                .filter(|event| {
+                    event
+                        .message
+                        .as_ref()
+                        .is_some_and(|message| message.contains(&failure.image))
+                })
+                .max_by_key(|event| event.last_seen())
+                .and_then(|event| event.message.clone());
+
+            (failure, event)
+        })
+        .collect();
+
+    let api = Api::<Secret>::namespaced(client, &namespace);
+    let mut secrets = Vec::new();
+
+    for name in pod.pull_secrets().into_iter().unique() {
+        let secret = api.get_opt(&name).await.map_err(|err| match err {
+            kube::Error::Api(resp) => resp.message,
+            err => err.to_string(),
+        });
+
+        secrets.push((name, secret));
+    }
+
+    Ok(Report { failures, secrets })
+}
+
+impl Widget for ImagePull {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(key, exit_keys!()) {
+            return Ok(Broadcast::Exited);
+        }
+
+        if let Some(Movement::Y(y)) = move_cursor(key, area) {
+            self.scroll = self.scroll.saturating_add_signed(y);
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Some(task) = self.task.as_mut() {
+            if task.is_finished() {
+                let result = futures::executor::block_on(async move { task.await? });
+
+                self.report = Some(result.map_err(|err| err.to_string()));
+                self.task = None;
+            }
+        }
+
+        let area = centered_with(area, Constraint::Percentage(80), Constraint::Percentage(80));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Image Pull")
+            .title_bottom(Line::from("esc: close").style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        let lines = match &self.report {
+            None => vec![Line::from("checking...")],
+            Some(Ok(report)) => Self::lines(report),
+            Some(Err(err)) => vec![Line::from(err.clone()).style(theme::unhealthy())],
+        };
+
+        self.scroll = self.scroll.min(
+            u16::try_from(lines.len())
+                .unwrap_or(u16::MAX)
+                .saturating_sub(inner.height),
+        );
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            inner,
+        );
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for ImagePull {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
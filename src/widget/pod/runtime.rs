@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::{Node, Pod};
+use kube::runtime::watcher;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{
+    events::{Broadcast, Event},
+    resources::{
+        pod::runtime::{Host, Runtime, RuntimeExt},
+        store::Store,
+    },
+    widget::{
+        nav::{move_cursor, Movement},
+        tabs::Tab,
+        Widget, WIDGET_VIEWS,
+    },
+};
+
+static LABEL_WIDTH: usize = 12;
+
+struct RuntimeStyle {
+    heading: Style,
+    label: Style,
+    value: Style,
+    command: Style,
+}
+
+impl Default for RuntimeStyle {
+    fn default() -> Self {
+        Self {
+            heading: Style::default().add_modifier(Modifier::BOLD),
+            label: Style::default().fg(tailwind::GRAY.c400),
+            value: Style::default(),
+            command: Style::default().fg(tailwind::BLUE.c300),
+        }
+    }
+}
+
+/// The IDs the container runtime uses for each container and image, next to
+/// what the node is running. Everything needed to go find a container with
+/// `crictl` on the node itself.
+#[allow(clippy::module_name_repetitions)]
+pub struct RuntimeInfo {
+    pod: Arc<Pod>,
+    node: Option<Arc<Store<Node>>>,
+
+    scroll: u16,
+}
+
+impl RuntimeInfo {
+    pub fn new(client: &kube::Client, pod: Arc<Pod>) -> Self {
+        WIDGET_VIEWS.pod.runtime.inc();
+
+        // Only the node the pod is on is interesting, there's no reason to watch
+        // all of them.
+        let node = pod
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.node_name.as_ref())
+            .map(|name| {
+                Store::with_config(
+                    client.clone(),
+                    watcher::Config::default().fields(&format!("metadata.name={name}")),
+                )
+                .0
+            });
+
+        Self {
+            pod,
+            node,
+            scroll: 0,
+        }
+    }
+
+    pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || Self::new(&client, pod.clone()).boxed()))
+            .build()
+    }
+
+    fn field(label: &str, value: Option<&str>, style: Style) -> Line<'static> {
+        let base = RuntimeStyle::default();
+
+        Line::from(vec![
+            Span::styled(format!("  {label:<LABEL_WIDTH$}"), base.label),
+            Span::styled(value.unwrap_or("-").to_string(), style),
+        ])
+    }
+
+    fn node(&self, style: &RuntimeStyle) -> Vec<Line<'static>> {
+        let Some(node) = self
+            .node
+            .as_ref()
+            .and_then(|node| node.snapshot().first().cloned())
+        else {
+            let status = if self.node.is_some() {
+                "unable to find node"
+            } else {
+                "not scheduled"
+            };
+
+            return vec![
+                Line::from(Span::styled("Node", style.heading)),
+                Self::field("status", Some(status), style.value),
+                Line::default(),
+            ];
+        };
+
+        let runtime = Host::from(node.as_ref());
+
+        vec![
+            Line::from(Span::styled(
+                format!("Node {}", runtime.name),
+                style.heading,
+            )),
+            Self::field("runtime", Some(&runtime.version), style.value),
+            Self::field("kubelet", Some(&runtime.kubelet), style.value),
+            Self::field("os", Some(&runtime.os), style.value),
+            Line::default(),
+        ]
+    }
+
+    fn container(runtime: &Runtime, style: &RuntimeStyle) -> Vec<Line<'static>> {
+        let name = if runtime.init {
+            format!("{} (init)", runtime.container)
+        } else {
+            runtime.container.clone()
+        };
+
+        vec![
+            Line::from(Span::styled(name, style.heading)),
+            Self::field("runtime", runtime.engine.as_deref(), style.value),
+            Self::field("id", runtime.id.as_deref(), style.value),
+            Self::field("image", Some(&runtime.image), style.value),
+            Self::field("image id", runtime.image_id.as_deref(), style.value),
+            Self::field("inspect", runtime.inspect().as_deref(), style.command),
+            Line::default(),
+        ]
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let style = RuntimeStyle::default();
+
+        let mut lines = self.node(&style);
+
+        let runtimes = self.pod.runtimes();
+
+        if runtimes.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "no containers have been created yet",
+                style.label,
+            )));
+        }
+
+        for runtime in &runtimes {
+            lines.extend(Self::container(runtime, &style));
+        }
+
+        lines
+    }
+}
+
+impl Widget for RuntimeInfo {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if let Some(Movement::Y(y)) = move_cursor(key, area) {
+            self.scroll = self.scroll.saturating_add_signed(y);
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        Ok(Broadcast::Ignored)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines = self.lines();
+
+        self.scroll = self.scroll.min(
+            u16::try_from(lines.len())
+                .unwrap_or(u16::MAX)
+                .saturating_sub(area.height),
+        );
+
+        frame.render_widget(Paragraph::new(lines).scroll((self.scroll, 0)), area);
+
+        Ok(())
+    }
+}
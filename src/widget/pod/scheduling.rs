@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::{Node, Pod};
+use ratatui::{buffer::Buffer, layout::Rect, Frame};
+
+use crate::{
+    events::{Broadcast, Event},
+    resources::{
+        pod::scheduling::{Cluster, Rule, SchedulingExt},
+        store::Store,
+    },
+    widget::{table, tabs::Tab, Widget, WIDGET_VIEWS},
+};
+
+struct Rules {
+    pod: Arc<Pod>,
+    nodes: Arc<Store<Node>>,
+    pods: Arc<Store<Pod>>,
+}
+
+impl table::Items for Rules {
+    type Item = Rule;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.pod.scheduling(&Cluster {
+            nodes: &self.nodes.snapshot(),
+            pods: &self.pods.snapshot(),
+        })
+    }
+}
+
+/// Explains where a pod is allowed to go. Each affinity, anti-affinity and
+/// topology spread constraint is shown with the nodes that currently satisfy
+/// it and the peer pods that it is being evaluated against.
+pub struct Scheduling {
+    table: table::Table<Rules>,
+}
+
+impl Scheduling {
+    pub fn new(client: &kube::Client, pod: Arc<Pod>) -> Self {
+        WIDGET_VIEWS.pod.scheduling.inc();
+
+        let (nodes, _) = Store::new(client.clone());
+        let (pods, _) = Store::new(client.clone());
+
+        Self {
+            table: table::Table::builder()
+                .items(Rules { pod, nodes, pods })
+                .border(false)
+                .build(),
+        }
+    }
+
+    pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || Self::new(&client, pod.clone()).boxed()))
+            .build()
+    }
+}
+
+impl Widget for Scheduling {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        // There's nothing to select, make sure that the tabs don't interpret the
+        // selection as a request to change tabs.
+        match self.table.dispatch(event, buffer, area)? {
+            Broadcast::Selected(_) => Ok(Broadcast::Consumed),
+            broadcast => Ok(broadcast),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.table.draw(frame, area)
+    }
+}
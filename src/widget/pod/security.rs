@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::Pod;
+use ratatui::{buffer::Buffer, layout::Rect, Frame};
+
+use crate::{
+    events::{Broadcast, Event},
+    resources::pod::security::{SecurityExt, Setting},
+    widget::{table, tabs::Tab, Widget, WIDGET_VIEWS},
+};
+
+struct Settings(Arc<Pod>);
+
+impl table::Items for Settings {
+    type Item = Setting;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.security()
+    }
+}
+
+/// Summary of the security relevant settings for a pod and its containers.
+/// Risky settings are highlighted so that a review doesn't require reading
+/// through the full spec.
+pub struct Security {
+    table: table::Table<Settings>,
+}
+
+impl Security {
+    pub fn new(pod: Arc<Pod>) -> Self {
+        WIDGET_VIEWS.pod.security.inc();
+
+        Self {
+            table: table::Table::builder()
+                .items(Settings(pod))
+                .border(false)
+                .build(),
+        }
+    }
+
+    pub fn tab(name: String, pod: Arc<Pod>) -> Tab {
+        Tab::builder()
+            .name(name)
+            .constructor(Box::new(move || Self::new(pod.clone()).boxed()))
+            .build()
+    }
+}
+
+impl Widget for Security {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        // There's nothing to select, make sure that the tabs don't interpret the
+        // selection as a request to change tabs.
+        match self.table.dispatch(event, buffer, area)? {
+            Broadcast::Selected(_) => Ok(Broadcast::Consumed),
+            broadcast => Ok(broadcast),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.table.draw(frame, area)
+    }
+}
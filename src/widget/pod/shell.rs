@@ -1,12 +1,17 @@
+mod launcher;
+
 use std::{pin::Pin, sync::Arc, vec};
 
 use chrono::{DateTime, Utc};
 use derive_builder::Builder;
 use eyre::{eyre, Result};
-use futures::StreamExt;
-use k8s_openapi::api::core::v1::Pod;
+use futures::{
+    future::{self, BoxFuture},
+    FutureExt, StreamExt,
+};
+use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::apis::meta::v1::Status};
 use kube::{
-    api::{Api, AttachParams},
+    api::{Api, AttachParams, AttachedProcess},
     ResourceExt,
 };
 use lazy_static::lazy_static;
@@ -17,19 +22,31 @@ use ratatui::{
     Frame,
 };
 use tokio::{
-    io::{AsyncWrite, AsyncWriteExt},
-    sync::mpsc::UnboundedReceiver,
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+    sync::{mpsc::UnboundedReceiver, Mutex},
 };
 use tokio_util::io::ReaderStream;
 
+pub use self::launcher::History;
+use self::launcher::{Launcher, Recall};
+
 use crate::{
-    events::{Broadcast, Event, Keypress},
+    events::{Broadcast, Event, Keypress, StringError},
     resources::{
         container::{Container, ContainerExt},
         pod::PodExt,
         status::StatusExt,
     },
-    widget::{input, input::ContentExt, propagate, table, tabs::Tab, Raw, Widget, WIDGET_VIEWS},
+    share::{Sharing, SHARES},
+    widget::{
+        input,
+        input::ContentExt,
+        propagate,
+        scrollback::{CopyMode, ScrollBack},
+        table,
+        tabs::Tab,
+        Raw, Widget, WIDGET_VIEWS,
+    },
 };
 
 lazy_static! {
@@ -48,7 +65,7 @@ pub struct Shell {
 #[bon::bon]
 impl Shell {
     #[builder]
-    pub fn new(client: kube::Client, pod: Arc<Pod>) -> Self {
+    pub fn new(client: kube::Client, pod: Arc<Pod>, history: History) -> Self {
         WIDGET_VIEWS.container.list.inc();
 
         let len = pod.as_ref().containers(None).len();
@@ -60,7 +77,7 @@ impl Shell {
                     .border(false)
                     .build(),
             )
-            .constructor(Command::from_pod(client, pod))
+            .constructor(Command::from_pod(client, pod, history))
             .build();
 
         if len == 1 {
@@ -70,13 +87,14 @@ impl Shell {
         Self { view }
     }
 
-    pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>) -> Tab {
+    pub fn tab(name: String, client: kube::Client, pod: Arc<Pod>, history: History) -> Tab {
         Tab::builder()
             .name(name)
             .constructor(Box::new(move || {
                 Self::builder()
                     .client(client.clone())
                     .pod(pod.clone())
+                    .history(history.clone())
                     .build()
                     .boxed()
             }))
@@ -96,41 +114,69 @@ impl Widget for Shell {
 
 enum CommandState {
     Input(input::Text),
+    Launcher(Launcher),
     Attached,
+    Scroll(CopyMode),
 }
 
 static COMMAND: &str = "/bin/bash";
 
-struct Command {
+pub struct Command {
     client: kube::Client,
     pod: Arc<Pod>,
     container: Container,
+    history: History,
+    recall: Recall,
 
     state: CommandState,
+    session: Arc<Mutex<Session>>,
 }
 
 impl Command {
-    pub fn new(client: kube::Client, pod: Arc<Pod>, container: Container) -> Self {
+    pub fn new(
+        client: kube::Client,
+        pod: Arc<Pod>,
+        container: Container,
+        history: History,
+    ) -> Self {
         WIDGET_VIEWS.container.cmd.inc();
 
-        let state = CommandState::Input(Command::input(&container));
+        let state = CommandState::Input(Command::input(&container, COMMAND));
 
         Self {
             client,
             pod,
             container,
+            history,
+            recall: Recall::default(),
             state,
+            session: Arc::default(),
         }
     }
 
-    fn input(container: &Container) -> input::Text {
+    fn input(container: &Container, cmd: &str) -> input::Text {
+        let content = input::Content::from_string(cmd);
+
+        let feedback: input::Feedback = {
+            let content = content.clone();
+
+            Box::new(move || {
+                let content = content.borrow();
+
+                launcher::argv(content.as_deref().unwrap_or_default())
+                    .err()
+                    .map(|err| (err.to_string(), false))
+            })
+        };
+
         input::Text::builder()
-            .title(container.name_any())
-            .content(input::Content::from_string(COMMAND))
+            .title(format!("{} (ctrl-e: run once)", container.name_any()))
+            .content(content)
+            .feedback(feedback)
             .build()
     }
 
-    pub fn from_pod(client: kube::Client, pod: Arc<Pod>) -> table::DetailFn {
+    pub fn from_pod(client: kube::Client, pod: Arc<Pod>, history: History) -> table::DetailFn {
         Box::new(move |idx, filter| {
             let containers = pod.containers(filter);
 
@@ -138,11 +184,36 @@ impl Command {
                 client.clone(),
                 pod.clone(),
                 containers.get(idx).unwrap().clone(),
+                history.clone(),
             )
             .boxed())
         })
     }
 
+    fn launcher(&self, cmd: &str) -> CommandState {
+        CommandState::Launcher(Launcher::new(
+            self.client.clone(),
+            self.pod.clone(),
+            self.container.clone(),
+            self.history.clone(),
+            cmd,
+        ))
+    }
+
+    // Entries from the launcher can span multiple lines, which the prompt can't
+    // show. Those open in the launcher instead.
+    fn recalled(&mut self, cmd: Option<String>) -> Broadcast {
+        if let Some(cmd) = cmd {
+            self.state = if cmd.contains('\n') {
+                self.launcher(&cmd)
+            } else {
+                CommandState::Input(Command::input(&self.container, &cmd))
+            };
+        }
+
+        Broadcast::Consumed
+    }
+
     fn dispatch_input(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
         let CommandState::Input(ref mut txt) = self.state else {
             return Ok(Broadcast::Ignored);
@@ -158,23 +229,122 @@ impl Command {
 
         match event.key() {
             Some(Keypress::Enter) => {
+                let Ok(argv) = launcher::argv(&cmd) else {
+                    return Ok(Broadcast::Consumed);
+                };
+
+                self.history.push(&cmd);
                 self.state = CommandState::Attached;
 
-                Ok(Broadcast::Raw(Box::new(
-                    ExecBuilder::default()
-                        .start(Utc::now())
-                        .client(self.client.clone())
-                        .pod(self.pod.clone())
-                        .container(self.container.clone())
-                        .cmd(cmd)
-                        .build()?,
-                )))
+                self.exec(argv)
+            }
+            Some(Keypress::Control('e')) => {
+                self.state = self.launcher(&cmd);
+
+                Ok(Broadcast::Consumed)
+            }
+            Some(Keypress::CursorUp) => {
+                let cmd = self.recall.older(&self.history, &cmd);
+
+                Ok(self.recalled(cmd))
+            }
+            Some(Keypress::CursorDown) => {
+                let cmd = self.recall.newer(&self.history);
+
+                Ok(self.recalled(cmd))
             }
             Some(Keypress::Escape) => Ok(Broadcast::Exited),
             _ => Ok(Broadcast::Ignored),
         }
     }
 
+    fn dispatch_launcher(
+        &mut self,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<Broadcast> {
+        let CommandState::Launcher(ref mut launcher) = self.state else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if let Broadcast::Exited = launcher.dispatch(event, buffer, area)? {
+            // Multiple lines don't fit in the prompt, start over with the default.
+            let cmd = launcher.command();
+
+            self.recall = Recall::default();
+            self.state = CommandState::Input(Command::input(
+                &self.container,
+                if cmd.contains('\n') { COMMAND } else { &cmd },
+            ));
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn exec(&self, cmd: Vec<String>) -> Result<Broadcast> {
+        Ok(Broadcast::Raw(Box::new(
+            ExecBuilder::default()
+                .client(self.client.clone())
+                .pod(self.pod.clone())
+                .container(self.container.clone())
+                .cmd(cmd)
+                .session(self.session.clone())
+                .build()?,
+        )))
+    }
+
+    fn dispatch_scroll(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let CommandState::Scroll(ref mut scroll) = self.state else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(event.key(), Some(Keypress::Printable('s'))) {
+            let mut session = self.session.try_lock()?;
+
+            session.sharing = match session.sharing.take() {
+                Some(_) => None,
+                None => Some(SHARES.start(
+                    &self.pod.namespace().unwrap_or_default(),
+                    &self.pod.name_any(),
+                    &self.container.name_any(),
+                )),
+            };
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        match scroll.dispatch(event, buffer, area)? {
+            Broadcast::Exited => {
+                self.state = CommandState::Attached;
+
+                // The command is only used to start the process, which is still
+                // running.
+                self.exec(Vec::new())
+            }
+            result => Ok(result),
+        }
+    }
+
+    // Exec returns when the process has exited or when the user has asked to look
+    // at the scroll-back.
+    fn finished(&mut self, result: &Result<(), StringError>) -> Result<Broadcast> {
+        result.clone()?;
+
+        let session = self.session.try_lock()?;
+
+        if !session.detached {
+            return Ok(Broadcast::Exited);
+        }
+
+        let lines = session.scrollback.lines();
+        drop(session);
+
+        self.state = CommandState::Scroll(CopyMode::new(lines));
+
+        Ok(Broadcast::Consumed)
+    }
+
     fn draw_input(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         let CommandState::Input(ref mut txt) = self.state else {
             return Ok(());
@@ -201,12 +371,11 @@ impl Command {
 impl Widget for Command {
     fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
         propagate!(self.dispatch_input(event, buffer, area));
+        propagate!(self.dispatch_launcher(event, buffer, area));
+        propagate!(self.dispatch_scroll(event, buffer, area));
 
         match event {
-            Event::Finished(result) => Ok(result
-                .as_ref()
-                .map(|()| Broadcast::Exited)
-                .map_err(std::clone::Clone::clone)?),
+            Event::Finished(result) => self.finished(result),
             _ => Ok(Broadcast::Ignored),
         }
     }
@@ -214,6 +383,23 @@ impl Widget for Command {
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         match self.state {
             CommandState::Input(_) => self.draw_input(frame, area)?,
+            CommandState::Launcher(ref mut launcher) => launcher.draw(frame, area)?,
+            CommandState::Scroll(ref mut scroll) => {
+                if let Ok(session) = self.session.try_lock() {
+                    scroll.status(match session.sharing.as_ref() {
+                        Some(sharing) => format!(
+                            "scroll-back: q to return to the shell, s to stop sharing ({}, {} \
+                             watching)",
+                            sharing.share().id,
+                            sharing.share().viewers()
+                        ),
+                        None => "scroll-back: q to return to the shell, s to share read-only"
+                            .to_string(),
+                    });
+                }
+
+                scroll.draw(frame, area)?;
+            }
             CommandState::Attached => {}
         }
 
@@ -225,13 +411,114 @@ impl Widget for Command {
     }
 }
 
+// `AttachedProcess` runs the websocket in a background task that only stops
+// once the remote process has exited. When the session goes away first, that
+// task (and its connection) would stick around until someone happened to exit
+// the shell on the other side.
+struct Attached(Option<AttachedProcess>);
+
+impl Attached {
+    fn get(&mut self) -> Result<&mut AttachedProcess> {
+        self.0.as_mut().ok_or(eyre!("process already joined"))
+    }
+
+    async fn join(mut self) -> Result<()> {
+        if let Some(proc) = self.0.take() {
+            proc.join().await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Attached {
+    fn drop(&mut self) {
+        if let Some(proc) = self.0.as_ref() {
+            proc.abort();
+        }
+    }
+}
+
+struct Connection {
+    start: DateTime<Utc>,
+
+    proc: Attached,
+    status: BoxFuture<'static, Option<Status>>,
+    output: ReaderStream<Box<dyn AsyncRead + Send + Unpin>>,
+    input: Box<dyn AsyncWrite + Send + Unpin>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        EXEC_DURATION.observe(
+            (Utc::now() - self.start)
+                .to_std()
+                .expect("duration in range")
+                .as_secs_f64()
+                / 60.0,
+        );
+    }
+}
+
+// Shared between `Command` and every `Exec` it creates. Entering copy mode
+// leaves raw mode, which drops the `Exec`, but the connection needs to stick
+// around so that leaving copy mode drops the user back into the same process.
+#[derive(Default)]
+struct Session {
+    connection: Option<Connection>,
+    scrollback: ScrollBack,
+    detached: bool,
+    sharing: Option<Sharing>,
+}
+
 #[derive(Builder)]
 struct Exec {
-    start: DateTime<Utc>,
     client: kube::Client,
     pod: Arc<Pod>,
     container: Container,
-    cmd: String,
+    cmd: Vec<String>,
+    session: Arc<Mutex<Session>>,
+}
+
+impl Exec {
+    async fn connect(&self) -> Result<Connection> {
+        WIDGET_VIEWS.container.exec.inc();
+
+        let mut proc = Attached(Some(
+            Api::<Pod>::namespaced(self.client.clone(), &self.pod.namespace().unwrap())
+                .exec(
+                    &self.pod.name_any(),
+                    self.cmd.clone(),
+                    &AttachParams {
+                        container: Some(self.container.name_any().to_string()),
+                        stdin: true,
+                        stdout: true,
+                        stderr: false,
+                        tty: true,
+                        ..Default::default()
+                    },
+                )
+                .await?,
+        ));
+
+        let process = proc.get()?;
+
+        let status = process
+            .take_status()
+            .ok_or(eyre!("status not available"))?
+            .boxed();
+        let output: Box<dyn AsyncRead + Send + Unpin> =
+            Box::new(process.stdout().ok_or(eyre!("stdout not available"))?);
+        let input = Box::new(process.stdin().ok_or(eyre!("stdin not available"))?);
+
+        Ok(Connection {
+            start: Utc::now(),
+            proc,
+            status,
+            output: ReaderStream::new(output),
+            input,
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -243,59 +530,87 @@ impl Raw for Exec {
         stdin: &mut UnboundedReceiver<Event>,
         mut stdout: Pin<Box<dyn AsyncWrite + Send + Unpin>>,
     ) -> Result<()> {
-        WIDGET_VIEWS.container.exec.inc();
-
-        let mut proc = Api::<Pod>::namespaced(self.client.clone(), &self.pod.namespace().unwrap())
-            .exec(
-                &self.pod.name_any(),
-                vec![self.cmd.as_str()],
-                &AttachParams {
-                    container: Some(self.container.name_any().to_string()),
-                    stdin: true,
-                    stdout: true,
-                    stderr: false,
-                    tty: true,
-                    ..Default::default()
-                },
-            )
-            .await?;
-
-        let status = proc.take_status().ok_or(eyre!("status not available"))?;
-
-        let mut output = ReaderStream::new(proc.stdout().ok_or(eyre!("stdout not available"))?);
-        let mut input = proc.stdin().ok_or(eyre!("stdin not available"))?;
+        let session = self.session.clone();
+        let mut session = session.lock().await;
+        let Session {
+            connection,
+            scrollback,
+            detached,
+            sharing,
+        } = &mut *session;
+
+        *detached = false;
+
+        let conn = match connection {
+            Some(conn) => {
+                // The screen has been cleared since the last time this was attached.
+                // Putting the current line back gets the prompt back for shells,
+                // anything full screen will need to be redrawn (usually ctrl-l).
+                stdout.write_all(scrollback.partial()).await?;
+                stdout.flush().await?;
+
+                conn
+            }
+            None => connection.insert(self.connect().await?),
+        };
 
         // TODO: handle resize events.
         loop {
             tokio::select! {
                 msg = stdin.recv() => {
+                    // The session is gone, there's nobody left to wait on the process
+                    // for.
                     let Some(msg) = msg else {
-                        break;
+                        return Ok(());
                     };
 
-                    let Event::Input(incoming) = &msg else {
-                        continue;
-                    };
+                    match &msg {
+                        Event::Input(incoming) if matches!(incoming.key, Keypress::GS) => {
+                            *detached = true;
+
+                            return Ok(());
+                        }
+                        Event::Input(incoming) => conn.input.write_all(incoming.into()).await?,
+                        Event::Paste(data) => conn.input.write_all(data.as_bytes()).await?,
+                        Event::Shutdown => return Ok(()),
+                        _ => continue,
+                    }
 
-                    input.write_all(incoming.into()).await?;
-                    input.flush().await?;
+                    conn.input.flush().await?;
 
                     if matches!(msg.key(), Some(Keypress::Control('b'))) {
                         break;
                     }
                 }
-                msg = output.next() => {
+                msg = conn.output.next() => {
                     let Some(msg) = msg else {
                         break;
                     };
 
-                    stdout.write_all(&msg?).await?;
+                    let msg = msg?;
+
+                    scrollback.push(&msg);
+
+                    if let Some(sharing) = sharing {
+                        sharing.share().send(&msg);
+                    }
+
+                    stdout.write_all(&msg).await?;
                     stdout.flush().await?;
                 }
             }
         }
 
-        status
+        // The process is gone, there isn't anything left to watch.
+        sharing.take();
+
+        let Some(mut conn) = connection.take() else {
+            return Ok(());
+        };
+
+        let proc = std::mem::replace(&mut conn.proc, Attached(None));
+
+        std::mem::replace(&mut conn.status, future::ready(None).boxed())
             .await
             .map(|status| {
                 if status.is_success() {
@@ -311,15 +626,3 @@ impl Raw for Exec {
         Ok(())
     }
 }
-
-impl Drop for Exec {
-    fn drop(&mut self) {
-        EXEC_DURATION.observe(
-            (Utc::now() - self.start)
-                .to_std()
-                .expect("duration in range")
-                .as_secs_f64()
-                / 60.0,
-        );
-    }
-}
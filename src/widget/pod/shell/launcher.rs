@@ -0,0 +1,615 @@
+use std::sync::{Arc, Mutex};
+
+use eyre::{eyre, Result};
+use k8s_openapi::{api::core::v1::Pod, apimachinery::pkg::apis::meta::v1::Status};
+use kube::{
+    api::{Api, AttachParams, AttachedProcess},
+    ResourceExt,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    task::JoinHandle,
+};
+
+use super::Attached;
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        container::{Container, ContainerExt},
+        status::StatusExt,
+    },
+    widget::{
+        scrollback::{CopyMode, ScrollBack},
+        theme, Widget,
+    },
+};
+
+static MAX_HISTORY: usize = 100;
+
+// Everything is held in memory until the command finishes, so something like
+// `yes` needs to be cut off. Past this, for either stream, the command is
+// stopped and whatever it printed so far is shown.
+static MAX_OUTPUT: usize = 1024 * 1024;
+
+/// Commands that have been run, newest last. This is shared between every
+/// container in the pods list so that something run in one pod can be run
+/// again in the next.
+#[derive(Clone, Default)]
+pub struct History(Arc<Mutex<Vec<String>>>);
+
+impl History {
+    pub fn push(&self, cmd: &str) {
+        let cmd = cmd.trim();
+
+        if cmd.is_empty() {
+            return;
+        }
+
+        let Ok(mut history) = self.0.lock() else {
+            return;
+        };
+
+        history.retain(|entry| entry != cmd);
+        history.push(cmd.to_string());
+
+        if history.len() > MAX_HISTORY {
+            history.remove(0);
+        }
+    }
+
+    /// `back` entries from the end, 1 is the most recent.
+    fn get(&self, back: usize) -> Option<String> {
+        let history = self.0.lock().ok()?;
+
+        history
+            .len()
+            .checked_sub(back)
+            .and_then(|idx| history.get(idx))
+            .cloned()
+    }
+}
+
+/// How far back in the history a prompt is, along with what had been typed
+/// before going through it.
+#[derive(Default)]
+pub struct Recall {
+    back: usize,
+    draft: Option<String>,
+}
+
+impl Recall {
+    pub fn older(&mut self, history: &History, current: &str) -> Option<String> {
+        let entry = history.get(self.back + 1)?;
+
+        if self.back == 0 {
+            self.draft = Some(current.to_string());
+        }
+
+        self.back += 1;
+
+        Some(entry)
+    }
+
+    pub fn newer(&mut self, history: &History) -> Option<String> {
+        match self.back {
+            0 => None,
+            1 => {
+                self.back = 0;
+
+                self.draft.take()
+            }
+            _ => {
+                self.back -= 1;
+
+                history.get(self.back)
+            }
+        }
+    }
+}
+
+/// Split a command into what gets exec'd. A single line is split the way a
+/// shell would, without running one, so that it works in containers that
+/// don't have a shell. Anything longer is a script and is handed to `sh`.
+pub fn argv(cmd: &str) -> Result<Vec<String>> {
+    let cmd = cmd.trim();
+
+    if cmd.contains('\n') {
+        return Ok(vec!["/bin/sh".into(), "-c".into(), cmd.into()]);
+    }
+
+    let argv = shlex::split(cmd).ok_or(eyre!("unbalanced quotes"))?;
+
+    if argv.is_empty() {
+        return Err(eyre!("no command"));
+    }
+
+    Ok(argv)
+}
+
+struct Ran {
+    lines: Vec<String>,
+    exit: String,
+}
+
+enum Stage {
+    Editing,
+    Running(JoinHandle<Result<Ran>>),
+    Output(CopyMode),
+}
+
+/// A multi-line editor for commands that are run once, without a terminal,
+/// with everything that they print shown in a scrollable pane afterwards.
+/// Leaving the pane comes back to the editor with the command still in it.
+pub struct Launcher {
+    client: kube::Client,
+    pod: Arc<Pod>,
+    container: Container,
+    history: History,
+
+    lines: Vec<String>,
+    cursor: Position,
+    recall: Recall,
+    error: Option<String>,
+
+    stage: Stage,
+}
+
+impl Launcher {
+    pub fn new(
+        client: kube::Client,
+        pod: Arc<Pod>,
+        container: Container,
+        history: History,
+        cmd: &str,
+    ) -> Self {
+        let mut launcher = Self {
+            client,
+            pod,
+            container,
+            history,
+            lines: Vec::new(),
+            cursor: Position::default(),
+            recall: Recall::default(),
+            error: None,
+            stage: Stage::Editing,
+        };
+
+        launcher.set(cmd);
+
+        launcher
+    }
+
+    pub fn command(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn set(&mut self, cmd: &str) {
+        self.lines = cmd.split('\n').map(str::to_string).collect();
+
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            self.cursor.y = self.lines.len().saturating_sub(1) as u16;
+            self.cursor.x = self.lines.last().map_or(0, |line| line.chars().count()) as u16;
+        }
+    }
+
+    fn line(&mut self) -> &mut String {
+        &mut self.lines[self.cursor.y as usize]
+    }
+
+    fn offset(&self) -> usize {
+        let line = &self.lines[self.cursor.y as usize];
+
+        line.char_indices()
+            .nth(self.cursor.x as usize)
+            .map_or(line.len(), |(offset, _)| offset)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn width(&self, row: u16) -> u16 {
+        self.lines[row as usize].chars().count() as u16
+    }
+
+    fn insert(&mut self, c: char) {
+        if c == '\n' {
+            let offset = self.offset();
+            let rest = self.line().split_off(offset);
+
+            self.cursor.y += 1;
+            self.cursor.x = 0;
+            self.lines.insert(self.cursor.y as usize, rest);
+
+            return;
+        }
+
+        let offset = self.offset();
+        self.line().insert(offset, c);
+        self.cursor.x += 1;
+    }
+
+    fn remove(&mut self) {
+        if self.cursor.x > 0 {
+            self.cursor.x -= 1;
+
+            let offset = self.offset();
+            self.line().remove(offset);
+        } else if self.cursor.y > 0 {
+            let line = self.lines.remove(self.cursor.y as usize);
+
+            self.cursor.y -= 1;
+            self.cursor.x = self.width(self.cursor.y);
+            self.line().push_str(&line);
+        }
+    }
+
+    fn run(&mut self) {
+        let cmd = self.command();
+
+        let argv = match argv(&cmd) {
+            Ok(argv) => argv,
+            Err(err) => {
+                self.error = Some(err.to_string());
+
+                return;
+            }
+        };
+
+        self.history.push(&cmd);
+        self.recall = Recall::default();
+        self.error = None;
+
+        self.stage = Stage::Running(tokio::spawn(run(
+            self.client.clone(),
+            self.pod.clone(),
+            self.container.name_any(),
+            argv,
+        )));
+    }
+
+    fn poll(&mut self) {
+        let Stage::Running(task) = &mut self.stage else {
+            return;
+        };
+
+        if !task.is_finished() {
+            return;
+        }
+
+        match futures::executor::block_on(async move { task.await? }) {
+            Ok(ran) => {
+                let mut output = CopyMode::new(ran.lines);
+                output.status(format!("{}: q to go back to the command", ran.exit));
+
+                self.stage = Stage::Output(output);
+            }
+            Err(err) => {
+                self.error = Some(match err.downcast_ref::<kube::Error>() {
+                    Some(kube::Error::Api(resp)) => resp.message.clone(),
+                    _ => err.to_string(),
+                });
+
+                self.stage = Stage::Editing;
+            }
+        }
+    }
+
+    fn dispatch_editor(&mut self, event: &Event) -> Broadcast {
+        if let Event::Paste(data) = event {
+            for c in data.chars().filter(|c| *c == '\n' || !c.is_control()) {
+                self.insert(c);
+            }
+
+            return Broadcast::Consumed;
+        }
+
+        let Some(key) = event.key() else {
+            return Broadcast::Ignored;
+        };
+
+        match key {
+            Keypress::Escape => return Broadcast::Exited,
+            Keypress::Control('d') => self.run(),
+            Keypress::Printable(c) => self.insert(*c),
+            Keypress::Enter => self.insert('\n'),
+            Keypress::Backspace | Keypress::Delete => self.remove(),
+            Keypress::Control('a') => self.cursor.x = 0,
+            Keypress::Control('e') => self.cursor.x = self.width(self.cursor.y),
+            Keypress::Control('k') => self.set(""),
+            Keypress::CursorLeft => {
+                if self.cursor.x > 0 {
+                    self.cursor.x -= 1;
+                } else if self.cursor.y > 0 {
+                    self.cursor.y -= 1;
+                    self.cursor.x = self.width(self.cursor.y);
+                }
+            }
+            Keypress::CursorRight => {
+                if self.cursor.x < self.width(self.cursor.y) {
+                    self.cursor.x += 1;
+                } else if (self.cursor.y as usize) + 1 < self.lines.len() {
+                    self.cursor.y += 1;
+                    self.cursor.x = 0;
+                }
+            }
+            Keypress::CursorUp => {
+                self.cursor.y = self.cursor.y.saturating_sub(1);
+                self.cursor.x = self.cursor.x.min(self.width(self.cursor.y));
+            }
+            Keypress::CursorDown => {
+                if (self.cursor.y as usize) + 1 < self.lines.len() {
+                    self.cursor.y += 1;
+                }
+
+                self.cursor.x = self.cursor.x.min(self.width(self.cursor.y));
+            }
+            // ctrl-p
+            Keypress::DLE => {
+                let current = self.command();
+
+                if let Some(cmd) = self.recall.older(&self.history, &current) {
+                    self.set(&cmd);
+                }
+            }
+            // ctrl-n
+            Keypress::ShiftOut => {
+                if let Some(cmd) = self.recall.newer(&self.history) {
+                    self.set(&cmd);
+                }
+            }
+            _ => return Broadcast::Ignored,
+        }
+
+        Broadcast::Consumed
+    }
+
+    fn draw_editor(&self, frame: &mut Frame, area: Rect, running: bool) {
+        #[allow(clippy::cast_possible_truncation)]
+        let height = (self.lines.len() as u16 + 2).clamp(6, area.height);
+
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(0),
+            Constraint::Length(height),
+            Constraint::Fill(0),
+        ])
+        .areas(area);
+
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Max(10),
+            Constraint::Fill(0),
+            Constraint::Max(10),
+        ])
+        .areas(area);
+
+        frame.render_widget(Clear, area);
+
+        let help = match (&self.error, running) {
+            (_, true) => Line::from("running..."),
+            (Some(err), false) => {
+                Line::from(err.as_str()).style(Style::default().fg(theme::unhealthy()))
+            }
+            (None, false) => {
+                Line::from("ctrl-d: run, ctrl-p/ctrl-n: history, esc: back to the prompt")
+                    .style(Style::default().fg(tailwind::GRAY.c400))
+            }
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Run once in {}", self.container.name_any()))
+            .title_bottom(help);
+        let inner = block.inner(area);
+
+        // Keep the cursor visible when there are more lines than fit.
+        let scroll = self.cursor.y.saturating_sub(inner.height.saturating_sub(1));
+
+        frame.render_widget(
+            Paragraph::new(
+                self.lines
+                    .iter()
+                    .map(String::as_str)
+                    .map(Line::from)
+                    .collect::<Vec<_>>(),
+            )
+            .block(block)
+            .scroll((scroll, 0)),
+            area,
+        );
+
+        if !running {
+            frame.set_cursor_position(Position::new(
+                inner.x + self.cursor.x,
+                inner.y + self.cursor.y - scroll,
+            ));
+        }
+    }
+}
+
+impl Widget for Launcher {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        match &mut self.stage {
+            Stage::Editing => Ok(self.dispatch_editor(event)),
+            // Nothing is sent to the process, the only thing to do is give up on it.
+            Stage::Running(task) => {
+                if matches!(event.key(), Some(Keypress::Escape)) {
+                    task.abort();
+                    self.stage = Stage::Editing;
+                }
+
+                Ok(Broadcast::Consumed)
+            }
+            Stage::Output(output) => {
+                if let Broadcast::Exited = output.dispatch(event, buffer, area)? {
+                    self.stage = Stage::Editing;
+                }
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        match &mut self.stage {
+            Stage::Editing => self.draw_editor(frame, area, false),
+            Stage::Running(_) => self.draw_editor(frame, area, true),
+            Stage::Output(output) => {
+                frame.render_widget(Clear, area);
+
+                output.draw(frame, area)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Launcher {
+    fn drop(&mut self) {
+        if let Stage::Running(task) = &self.stage {
+            task.abort();
+        }
+    }
+}
+
+async fn run(
+    client: kube::Client,
+    pod: Arc<Pod>,
+    container: String,
+    argv: Vec<String>,
+) -> Result<Ran> {
+    let mut proc = Attached(Some(
+        Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default())
+            .exec(
+                &pod.name_any(),
+                argv,
+                &AttachParams {
+                    container: Some(container),
+                    stdin: false,
+                    stdout: true,
+                    stderr: true,
+                    tty: false,
+                    ..Default::default()
+                },
+            )
+            .await?,
+    ));
+
+    let process = proc.get()?;
+
+    let status = process.take_status().ok_or(eyre!("status not available"))?;
+    let stdout = process.stdout().ok_or(eyre!("stdout not available"))?;
+    let stderr = process.stderr().ok_or(eyre!("stderr not available"))?;
+
+    let (mut out, mut err) = (Vec::new(), Vec::new());
+
+    let (out_result, err_result) = tokio::join!(
+        read_limited(process, stdout, &mut out),
+        read_limited(process, stderr, &mut err)
+    );
+
+    // Stopping the command cuts off the other stream too, that isn't worth
+    // reporting as an error.
+    let truncated = matches!(out_result, Ok(true)) || matches!(err_result, Ok(true));
+
+    let status = if truncated {
+        None
+    } else {
+        out_result?;
+        err_result?;
+
+        let status = status.await;
+
+        proc.join().await?;
+
+        status
+    };
+
+    // Both streams are read in full before either is shown, which loses how
+    // they were interleaved. Keeping them apart at least makes it obvious
+    // which one something came from.
+    let mut scrollback = ScrollBack::default();
+    scrollback.push(&out);
+
+    if !err.is_empty() {
+        if !out.is_empty() && !out.ends_with(b"\n") {
+            scrollback.push(b"\n");
+        }
+
+        scrollback.push(b"--- stderr ---\n");
+        scrollback.push(&err);
+    }
+
+    if truncated {
+        scrollback.push(b"\n--- output cut off, the command was stopped ---\n");
+    }
+
+    Ok(Ran {
+        lines: scrollback.lines(),
+        exit: if truncated {
+            format!("stopped after {}KiB of output", MAX_OUTPUT / 1024)
+        } else {
+            exit(status)
+        },
+    })
+}
+
+// Read up to `MAX_OUTPUT` of `stream` into `buf`, `true` when there was more
+// than that. Once a stream is full there's nothing reading it, which would
+// otherwise leave the command blocked writing to it forever, so it is stopped.
+async fn read_limited<R>(
+    process: &AttachedProcess,
+    stream: R,
+    buf: &mut Vec<u8>,
+) -> std::io::Result<bool>
+where
+    R: AsyncRead + Unpin,
+{
+    stream.take(MAX_OUTPUT as u64 + 1).read_to_end(buf).await?;
+
+    let truncated = buf.len() > MAX_OUTPUT;
+    buf.truncate(MAX_OUTPUT);
+
+    if truncated {
+        process.abort();
+    }
+
+    Ok(truncated)
+}
+
+fn exit(status: Option<Status>) -> String {
+    let Some(status) = status else {
+        return "exit status unknown".to_string();
+    };
+
+    if status.is_success() {
+        return "exit 0".to_string();
+    }
+
+    status
+        .details
+        .as_ref()
+        .and_then(|details| details.causes.as_ref())
+        .and_then(|causes| {
+            causes
+                .iter()
+                .find(|cause| cause.reason.as_deref() == Some("ExitCode"))
+        })
+        .and_then(|cause| cause.message.clone())
+        .map_or_else(
+            || status.into_report().to_string(),
+            |code| format!("exit {code}"),
+        )
+}
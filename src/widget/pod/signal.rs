@@ -0,0 +1,333 @@
+use std::sync::Arc;
+
+use eyre::{eyre, Result};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{
+    api::{Api, AttachParams},
+    ResourceExt,
+};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Row, Wrap},
+    Frame,
+};
+use tokio::{io::AsyncReadExt, task::JoinHandle};
+
+use super::picker::{Answer, Choice, Picker};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        container::{Container, ContainerExt},
+        status::StatusExt,
+    },
+    widget::{centered, nav::exit_keys, table, theme, Widget},
+};
+
+lazy_static! {
+    static ref SIGNALS_SENT: IntCounterVec = register_int_counter_vec!(
+        "signals_sent_total",
+        "Number of signals sent to containers, by signal and whether it succeeded",
+        &["signal", "result"]
+    )
+    .unwrap();
+}
+
+#[derive(Clone, Copy)]
+struct Signal {
+    name: &'static str,
+    description: &'static str,
+}
+
+static SIGNALS: &[Signal] = &[
+    Signal {
+        name: "HUP",
+        description: "reload configuration, for most daemons",
+    },
+    Signal {
+        name: "USR1",
+        description: "application defined, often reopens logs or dumps state",
+    },
+    Signal {
+        name: "USR2",
+        description: "application defined",
+    },
+    Signal {
+        name: "QUIT",
+        description: "quit, Go and Java print stack traces to the log first",
+    },
+    Signal {
+        name: "INT",
+        description: "interrupt, the same as ctrl-c",
+    },
+    Signal {
+        name: "TERM",
+        description: "ask the process to shut down",
+    },
+    Signal {
+        name: "KILL",
+        description: "stop the process immediately",
+    },
+];
+
+impl table::Row for Signal {
+    fn columns() -> Vec<&'static str> {
+        vec!["Signal", "Description"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![format!("SIG{}", self.name), self.description.to_string()]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+}
+
+struct Signals;
+
+impl table::Items for Signals {
+    type Item = Signal;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        SIGNALS.to_vec()
+    }
+}
+
+enum Stage {
+    Container(Picker<Arc<Pod>>),
+    Pick(Container, table::Table<Signals>),
+    Sending(JoinHandle<Result<()>>),
+    Done(Result<String, String>),
+}
+
+/// Send a signal to PID 1 of a container. This execs `kill` in the container,
+/// so the image needs to have one. Anything the process writes in response,
+/// such as a stack dump, shows up in the logs.
+#[allow(clippy::module_name_repetitions)]
+pub struct SendSignal {
+    client: kube::Client,
+    pod: Arc<Pod>,
+
+    stage: Stage,
+}
+
+impl SendSignal {
+    pub fn new(client: kube::Client, pod: Arc<Pod>) -> Self {
+        let stage = match Picker::new(pod.clone()) {
+            Choice::Only(container) => Self::pick(container),
+            Choice::Ask(picker) => Stage::Container(picker),
+        };
+
+        Self { client, pod, stage }
+    }
+
+    fn pick(container: Container) -> Stage {
+        Stage::Pick(
+            container,
+            table::Table::builder().items(Signals).border(false).build(),
+        )
+    }
+
+    fn send(&self, container: &Container, signal: Signal) -> Stage {
+        let client = self.client.clone();
+        let pod = self.pod.clone();
+        let container = container.name_any();
+
+        Stage::Sending(tokio::spawn(async move {
+            let result = kill(client, &pod, &container, signal.name).await;
+
+            SIGNALS_SENT
+                .with_label_values(&[
+                    signal.name,
+                    if result.is_ok() { "success" } else { "failure" },
+                ])
+                .inc();
+
+            result
+        }))
+    }
+
+    fn advance(
+        &mut self,
+        stage: Stage,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<(Stage, Broadcast)> {
+        let stage = match stage {
+            Stage::Container(mut picker) => match picker.dispatch(event, buffer, area)? {
+                Answer::Picked(container) => Self::pick(container),
+                Answer::Cancelled => return Ok((Stage::Container(picker), Broadcast::Exited)),
+                Answer::Waiting => Stage::Container(picker),
+            },
+            Stage::Pick(container, mut table) => match table.dispatch(event, buffer, area)? {
+                Broadcast::Selected(idx) => match SIGNALS.get(idx) {
+                    Some(signal) => self.send(&container, *signal),
+                    None => Stage::Pick(container, table),
+                },
+                _ if matches!(event.key(), Some(exit_keys!())) => {
+                    return Ok((Stage::Pick(container, table), Broadcast::Exited))
+                }
+                _ => Stage::Pick(container, table),
+            },
+            Stage::Sending(task) => Stage::Sending(task),
+            Stage::Done(result) => {
+                if event.key().is_some() {
+                    return Ok((Stage::Done(result), Broadcast::Exited));
+                }
+
+                Stage::Done(result)
+            }
+        };
+
+        Ok((stage, Broadcast::Consumed))
+    }
+
+    fn finished(task: &mut JoinHandle<Result<()>>) -> Stage {
+        let result = futures::executor::block_on(async move { task.await? });
+
+        Stage::Done(match result {
+            Ok(()) => Ok("sent, any output from the process is in the logs".to_string()),
+            Err(err) => Err(match err.downcast_ref::<kube::Error>() {
+                Some(kube::Error::Api(resp)) => resp.message.clone(),
+                _ => err.to_string(),
+            }),
+        })
+    }
+
+    fn title(&self) -> String {
+        match &self.stage {
+            Stage::Pick(container, _) => format!("Send Signal to {}", container.name_any()),
+            _ => "Send Signal".to_string(),
+        }
+    }
+
+    fn help(&self) -> &'static str {
+        match self.stage {
+            Stage::Container(_) => "enter: select container, esc: cancel",
+            Stage::Pick(..) => "enter: send to PID 1, esc: cancel",
+            Stage::Sending(_) => "",
+            Stage::Done(_) => "press any key to close",
+        }
+    }
+}
+
+async fn kill(client: kube::Client, pod: &Pod, container: &str, signal: &str) -> Result<()> {
+    let mut proc = Api::<Pod>::namespaced(client, &pod.namespace().unwrap_or_default())
+        .exec(
+            &pod.name_any(),
+            vec!["kill", format!("-{signal}").as_str(), "1"],
+            &AttachParams {
+                container: Some(container.to_string()),
+                stdin: false,
+                stdout: false,
+                stderr: true,
+                tty: false,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    let status = proc
+        .take_status()
+        .ok_or_else(|| eyre!("status not available"))?;
+
+    // `kill` explains itself on stderr, which is more useful than the exit code
+    // when it fails (eg. there's no such process or it isn't allowed).
+    let mut stderr = String::new();
+    proc.stderr()
+        .ok_or_else(|| eyre!("stderr not available"))?
+        .read_to_string(&mut stderr)
+        .await?;
+
+    let status = status.await.ok_or_else(|| eyre!("status not available"))?;
+
+    proc.join().await?;
+
+    if status.is_success() {
+        return Ok(());
+    }
+
+    if stderr.trim().is_empty() {
+        Err(status.into_report())
+    } else {
+        Err(eyre!("{}", stderr.trim()))
+    }
+}
+
+impl Widget for SendSignal {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if event.key().is_none() {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        match self.advance(stage, event, buffer, area) {
+            Ok((stage, result)) => {
+                self.stage = stage;
+
+                Ok(result)
+            }
+            Err(err) => {
+                self.stage = Stage::Done(Err(err.to_string()));
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Stage::Sending(task) = &mut self.stage {
+            if task.is_finished() {
+                self.stage = Self::finished(task);
+            }
+        }
+
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title())
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Container(picker) => picker.draw(frame, inner)?,
+            Stage::Pick(_, table) => table.draw(frame, inner)?,
+            Stage::Sending(_) => frame.render_widget(Paragraph::new("sending..."), inner),
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SendSignal {
+    fn drop(&mut self) {
+        if let Stage::Sending(task) = &self.stage {
+            task.abort();
+        }
+    }
+}
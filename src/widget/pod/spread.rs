@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use itertools::Itertools;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::pod::{
+        spread::{self, Spread},
+        Grouping, Pods,
+    },
+    widget::{
+        centered_with,
+        nav::{exit_keys, move_cursor, Movement},
+        scroll::Scroll,
+        table::Items,
+        theme, Widget,
+    },
+};
+
+static BAR: char = '█';
+
+/// How the pods in the list are spread over zones or nodes, so that one domain
+/// holding most of them, or a deployment that's ended up lopsided, stands out.
+/// This follows the list as it changes.
+pub struct Topology {
+    pods: Arc<Pods>,
+    grouping: Grouping,
+    offset: u16,
+}
+
+impl Topology {
+    pub fn new(pods: Arc<Pods>) -> Self {
+        let grouping = match pods.grouping() {
+            Grouping::None => Grouping::Zone,
+            grouping => grouping,
+        };
+
+        Self {
+            pods,
+            grouping,
+            offset: 0,
+        }
+    }
+
+    fn lines(spread: &Spread, width: usize) -> Vec<Line<'static>> {
+        let gray = Style::default().fg(tailwind::GRAY.c400);
+
+        let name = |domain: &str| {
+            if domain.is_empty() {
+                "unknown".to_string()
+            } else {
+                domain.to_string()
+            }
+        };
+
+        let label_width = spread
+            .domains
+            .iter()
+            .map(|(domain, _)| name(domain).len())
+            .max()
+            .unwrap_or_default();
+        let total: usize = spread.domains.iter().map(|(_, count)| count).sum();
+        let most = spread
+            .domains
+            .iter()
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or_default()
+            .max(1);
+
+        // Room for the label, the count and the percentage.
+        let room = width.saturating_sub(label_width + 12).max(1);
+
+        let mut lines = vec![Line::from(format!(
+            "{total} pods by {}{}",
+            spread.grouping,
+            if spread.pending > 0 {
+                format!(", {} not scheduled yet", spread.pending)
+            } else {
+                String::new()
+            }
+        ))
+        .style(gray)];
+
+        for (domain, count) in &spread.domains {
+            lines.push(Line::from(vec![
+                Span::from(format!("{:label_width$} ", name(domain))),
+                Span::from(BAR.to_string().repeat(count * room / most))
+                    .style(Style::default().fg(tailwind::INDIGO.c300)),
+                Span::from(format!(" {count} ({}%)", count * 100 / total.max(1))),
+            ]));
+        }
+
+        if spread.workloads.is_empty() {
+            return lines;
+        }
+
+        lines.push(Line::default());
+        lines.push(
+            Line::from(format!(
+                "workloads, by skew (most pods in a {} minus fewest)",
+                spread.grouping
+            ))
+            .style(gray),
+        );
+
+        for workload in &spread.workloads {
+            let skew = workload.skew();
+
+            let counts = spread
+                .domains
+                .iter()
+                .zip(&workload.counts)
+                .filter(|(_, count)| **count > 0)
+                .map(|((domain, _), count)| format!("{} {count}", name(domain)))
+                .join(", ");
+
+            lines.push(Line::from(vec![
+                Span::from(format!("skew {skew:<3}")).style(Style::default().fg(if skew > 1 {
+                    theme::unhealthy()
+                } else {
+                    theme::healthy()
+                })),
+                Span::from(format!("{} ", workload.name)),
+                Span::from(counts).style(gray),
+            ]));
+        }
+
+        lines
+    }
+}
+
+impl Widget for Topology {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match key {
+            exit_keys!() => return Ok(Broadcast::Exited),
+            Keypress::Printable('g') => {
+                self.grouping = match self.grouping {
+                    Grouping::Zone => Grouping::Node,
+                    _ => Grouping::Zone,
+                };
+                self.offset = 0;
+            }
+            key => {
+                if let Some(Movement::Y(y)) = move_cursor(key, area) {
+                    self.offset = self.offset.saturating_add_signed(y);
+                }
+            }
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = centered_with(area, Constraint::Percentage(80), Constraint::Percentage(80));
+
+        frame.render_widget(Clear, area);
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title("Spread")
+            .title_bottom(
+                Line::from(format!(
+                    "g: by {}, esc: close",
+                    match self.grouping {
+                        Grouping::Zone => Grouping::Node,
+                        _ => Grouping::Zone,
+                    }
+                ))
+                .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        let spread = spread::spread(
+            &self.pods.items(None),
+            &self.pods.nodes.snapshot(),
+            self.grouping,
+        );
+        let lines = Self::lines(&spread, inner.width as usize);
+
+        let height = inner.height as usize;
+        let offset = (self.offset as usize).min(lines.len().saturating_sub(height));
+        self.offset = offset as u16;
+
+        if let Some(indicator) = (Scroll {
+            offset,
+            height,
+            total: lines.len(),
+        })
+        .title(None)
+        {
+            block = block.title(indicator);
+        }
+
+        frame.render_widget(block, area);
+        frame.render_widget(Paragraph::new(lines).scroll((self.offset, 0)), inner);
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
@@ -0,0 +1,160 @@
+use eyre::Result;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{nav::exit_keys, theme, Widget};
+use crate::{
+    client::preflight::{self, Problem},
+    events::{Broadcast, Event, Keypress},
+};
+
+/// Checks that the API server can be used as soon as a session starts. When
+/// it can't, this takes over the whole screen explaining why and what to do
+/// about it, instead of every list sitting there empty. `r` checks again, esc
+/// carries on regardless.
+pub struct Preflight {
+    client: kube::Client,
+    task: Option<JoinHandle<Result<(), Problem>>>,
+    problem: Option<Problem>,
+}
+
+impl Preflight {
+    pub fn new(client: kube::Client) -> Self {
+        let mut preflight = Self {
+            client,
+            task: None,
+            problem: None,
+        };
+
+        preflight.check();
+
+        preflight
+    }
+
+    fn check(&mut self) {
+        let client = self.client.clone();
+
+        self.task = Some(tokio::spawn(async move {
+            preflight::check(&client, None).await
+        }));
+    }
+
+    fn poll(&mut self) {
+        if !self.task.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+
+        let Some(task) = self.task.take() else {
+            return;
+        };
+
+        self.problem = match futures::executor::block_on(task) {
+            Ok(result) => result.err(),
+            Err(err) => {
+                tracing::warn!("preflight check failed to run: {err}");
+
+                None
+            }
+        };
+    }
+
+    pub fn is_blocking(&self) -> bool {
+        self.problem.is_some()
+    }
+}
+
+impl Widget for Preflight {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, _: Rect) -> Result<Broadcast> {
+        if self.problem.is_none() {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Consumed);
+        };
+
+        match key {
+            exit_keys!() => self.problem = None,
+            Keypress::Printable('r') if self.task.is_none() => self.check(),
+            _ => {}
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        let Some(problem) = self.problem.as_ref() else {
+            return Ok(());
+        };
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme::unhealthy()))
+            .title("Unable to use the cluster")
+            .title_bottom(
+                Line::from(if self.task.is_some() {
+                    "checking again..."
+                } else {
+                    "r: check again, esc: continue anyway"
+                })
+                .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        let [_, inner, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Max(80),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        let lines = vec![
+            Line::from(problem.summary()).style(Style::default().add_modifier(Modifier::BOLD)),
+            Line::default(),
+            Line::from(problem.fix()),
+            Line::default(),
+            Line::from(problem.detail.clone()).style(Style::default().fg(tailwind::GRAY.c400)),
+        ];
+
+        let pg = Paragraph::new(lines).wrap(Wrap { trim: false });
+
+        #[allow(clippy::cast_possible_truncation)]
+        let height = pg.line_count(inner.width) as u16;
+
+        let [_, inner, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        frame.render_widget(pg, inner);
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        2
+    }
+}
+
+impl Drop for Preflight {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+use eyre::Result;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear},
+    Frame,
+};
+
+use super::{centered_with, confirm::Confirm, nav::exit_keys, Widget};
+use crate::{
+    config::Confirmation,
+    events::{Broadcast, Event, Keypress},
+};
+
+// How long the second escape has to come after the first.
+static DOUBLE_WINDOW: Duration = Duration::from_secs(1);
+
+enum Mode {
+    Double(Instant),
+    Prompt(Confirm),
+}
+
+/// Stands between the top level screen exiting and the session ending. Either
+/// another exit key has to follow quickly or `y` has to be pressed, depending
+/// on `quit.confirm` in the config. Going ahead is `Broadcast::Selected(0)`,
+/// backing out is `Broadcast::Exited`. Any other key backs out of the double
+/// press by being `Broadcast::Ignored`, so that it still does what it would
+/// have.
+pub struct Quit {
+    mode: Mode,
+}
+
+impl Quit {
+    /// `None` when quitting doesn't need to be confirmed at all.
+    pub fn new(confirm: Confirmation) -> Option<Self> {
+        let mode = match confirm {
+            Confirmation::None => return None,
+            Confirmation::Double => Mode::Double(Instant::now()),
+            Confirmation::Prompt => Mode::Prompt(Confirm::quick("Quit kty?")),
+        };
+
+        Some(Self { mode })
+    }
+
+    /// Whether the window for the second press has passed.
+    pub fn expired(&self) -> bool {
+        match &self.mode {
+            Mode::Double(started) => started.elapsed() > DOUBLE_WINDOW,
+            Mode::Prompt(_) => false,
+        }
+    }
+}
+
+impl Widget for Quit {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        match &mut self.mode {
+            Mode::Double(_) => match event.key() {
+                Some(exit_keys!()) if !self.expired() => Ok(Broadcast::Selected(0)),
+                _ => Ok(Broadcast::Ignored),
+            },
+            Mode::Prompt(confirm) => confirm.dispatch(event, buffer, area),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        match &mut self.mode {
+            Mode::Double(_) => {
+                let [_, footer] =
+                    Layout::vertical([Constraint::Fill(0), Constraint::Length(1)]).areas(area);
+
+                frame.render_widget(Clear, footer);
+                frame.render_widget(
+                    Line::from("press esc again to quit")
+                        .alignment(Alignment::Center)
+                        .style(
+                            Style::default()
+                                .fg(tailwind::GRAY.c900)
+                                .bg(tailwind::YELLOW.c300),
+                        ),
+                    footer,
+                );
+            }
+            Mode::Prompt(confirm) => {
+                let area = centered_with(area, Constraint::Length(30), Constraint::Length(3));
+
+                frame.render_widget(Clear, area);
+
+                let block = Block::default()
+                    .borders(Borders::ALL)
+                    .title("Quit")
+                    .title_bottom(
+                        Line::from(confirm.help()).style(Style::default().fg(tailwind::GRAY.c400)),
+                    );
+                let inner = block.inner(area);
+
+                frame.render_widget(block, area);
+
+                confirm.draw(frame, inner)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
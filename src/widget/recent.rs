@@ -0,0 +1,402 @@
+use std::{cell::RefCell, collections::BTreeMap, sync::Arc};
+
+use eyre::{eyre, Result};
+use kube::{
+    api::{Api, ApiResource, DynamicObject},
+    Resource, ResourceExt,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+    widgets::Clear,
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{
+    dynamic,
+    error::Error,
+    loading::Loading,
+    startup::Startup,
+    table::{
+        density::{self, Density},
+        state,
+    },
+    theme::{self, Theme},
+    Action, BoxWidget, Placement, Widget,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    identity::preferences::{Preferences, PreferencesSpec, Storage, Visit},
+    policy,
+};
+
+// Details are opened from deep inside whichever list they were picked from,
+// which has no way to reach the apex. They're all constructed on the dashboard
+// thread, so they leave the visit here for `Recent` to pick up while drawing.
+thread_local! {
+    static OPENED: RefCell<Option<Visit>> = const { RefCell::new(None) };
+}
+
+/// Record that the detail of `object` has been opened, so that it shows up in
+/// the recent strip.
+pub fn opened<K>(object: &K, dt: &K::DynamicType)
+where
+    K: Resource,
+{
+    let visit = Visit::object(
+        &ApiResource::erase::<K>(dt),
+        object.namespace(),
+        object.name_any(),
+    );
+
+    OPENED.with_borrow_mut(|opened| *opened = Some(visit));
+}
+
+/// Strip of favorite and recently visited kinds and objects, each one a single
+/// key press away. This is persisted in the user's `Preferences` when there's
+/// somewhere to store them.
+pub struct Recent {
+    storage: Option<Storage>,
+    preferences: Preferences,
+
+    loading: Option<JoinHandle<Result<Preferences>>>,
+    saving: Option<JoinHandle<()>>,
+}
+
+impl Recent {
+    pub fn new(storage: Option<Storage>) -> Self {
+        let loading = storage
+            .clone()
+            .map(|storage| tokio::spawn(async move { storage.load().await }));
+
+        Self {
+            storage,
+            preferences: Preferences::new(
+                "",
+                PreferencesSpec {
+                    user: String::new(),
+                    recent: Vec::new(),
+                    favorites: Vec::new(),
+                    theme: Theme::default(),
+                    density: None,
+                    tables: BTreeMap::new(),
+                    startup: None,
+                },
+            ),
+            loading,
+            saving: None,
+        }
+    }
+
+    /// What the number key `idx + 1` jumps to, and whether it's a favorite.
+    pub fn get(&self, idx: usize) -> Option<(Visit, bool)> {
+        self.preferences
+            .strip()
+            .nth(idx)
+            .map(|(favorite, visit)| (visit.clone(), favorite))
+    }
+
+    /// The most recent visit, whether or not it is a favorite as well.
+    pub fn last(&self) -> Option<Visit> {
+        self.preferences.spec.recent.first().cloned()
+    }
+
+    /// Whether the stored preferences have shown up, or there were none to
+    /// wait for.
+    pub fn is_loaded(&self) -> bool {
+        self.loading.is_none()
+    }
+
+    pub fn startup(&self) -> Option<Startup> {
+        self.preferences.spec.startup.clone()
+    }
+
+    pub fn visit(&mut self, visit: Visit) {
+        self.preferences.visit(visit);
+
+        // Until the stored preferences have loaded there's nothing to save on top
+        // of, the visit gets merged in once they show up.
+        if self.loading.is_some() {
+            return;
+        }
+
+        self.save();
+    }
+
+    pub fn favorite(&mut self, visit: Visit) {
+        self.preferences.favorite(visit);
+
+        if self.loading.is_some() {
+            return;
+        }
+
+        self.save();
+    }
+
+    /// The theme is saved alongside the recent kinds, they're both part of the
+    /// same `Preferences`.
+    pub fn set_theme(&mut self, theme: Theme) {
+        theme::set(theme);
+        self.preferences.spec.theme = theme;
+
+        if self.loading.is_some() {
+            return;
+        }
+
+        self.save();
+    }
+
+    pub fn set_density(&mut self, density: Density) {
+        density::set(density);
+        self.preferences.spec.density = Some(density);
+
+        if self.loading.is_some() {
+            return;
+        }
+
+        self.save();
+    }
+
+    fn save(&mut self) {
+        let Some(storage) = self.storage.clone() else {
+            return;
+        };
+
+        // Only the latest state matters, there's no reason to let an older save
+        // race with it.
+        if let Some(saving) = self.saving.take() {
+            saving.abort();
+        }
+
+        self.preferences.spec.tables = state::all();
+
+        let preferences = self.preferences.clone();
+
+        self.saving = Some(tokio::spawn(async move {
+            if let Err(err) = storage.save(&preferences).await {
+                tracing::warn!("unable to save preferences: {err:?}");
+            }
+        }));
+    }
+
+    fn loaded(&mut self) {
+        let Some(task) = self.loading.take() else {
+            return;
+        };
+
+        let mut preferences = match futures::executor::block_on(async move { task.await? }) {
+            Ok(preferences) => preferences,
+            Err(err) => {
+                tracing::warn!("unable to load preferences: {err:?}");
+
+                return;
+            }
+        };
+
+        // Changing the theme before the stored one has loaded wins out over
+        // whatever was stored.
+        if self.preferences.spec.theme == Theme::default() {
+            theme::set(preferences.spec.theme);
+        } else {
+            preferences.spec.theme = self.preferences.spec.theme;
+        }
+
+        match self.preferences.spec.density {
+            Some(density) => preferences.spec.density = Some(density),
+            None => {
+                if let Some(density) = preferences.spec.density {
+                    density::set(density);
+                }
+            }
+        }
+
+        state::restore(preferences.spec.tables.clone());
+
+        for visit in &self.preferences.spec.favorites {
+            if !preferences.spec.favorites.contains(visit) {
+                preferences.favorite(visit.clone());
+            }
+        }
+
+        if self.preferences.spec.recent.is_empty() && self.preferences.spec.favorites.is_empty() {
+            self.preferences = preferences;
+
+            return;
+        }
+
+        for visit in self.preferences.spec.recent.iter().rev() {
+            preferences.visit(visit.clone());
+        }
+
+        self.preferences = preferences;
+        self.save();
+    }
+}
+
+impl Widget for Recent {
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if self.loading.as_ref().is_some_and(JoinHandle::is_finished) {
+            self.loaded();
+        }
+
+        if let Some(visit) = OPENED.with_borrow_mut(Option::take) {
+            self.visit(visit);
+        }
+
+        let key = Style::default()
+            .fg(tailwind::INDIGO.c300)
+            .add_modifier(Modifier::BOLD);
+
+        let label = Style::default().fg(tailwind::GRAY.c400);
+
+        let mut spans = Vec::new();
+        let mut section = None;
+
+        for (i, (favorite, visit)) in self.preferences.strip().enumerate() {
+            if section != Some(favorite) {
+                spans
+                    .push(Span::from(if favorite { "Favorites:" } else { "Recent:" }).style(label));
+                section = Some(favorite);
+            }
+
+            spans.push(Span::from(format!(" {}", i + 1)).style(key));
+            spans.push(Span::from(match (&visit.name, &visit.namespace) {
+                (Some(name), _) => format!(" {}/{name} ", visit.plural),
+                (None, Some(ns)) if favorite => format!(" {} ({ns}) ", visit.plural),
+                (None, _) => format!(" {} ", visit.plural),
+            }));
+        }
+
+        frame.render_widget(Line::from(spans), area);
+
+        Ok(())
+    }
+
+    fn placement(&self) -> Placement {
+        Placement {
+            vertical: Constraint::Length(u16::from(self.preferences.strip().next().is_some())),
+            ..Default::default()
+        }
+    }
+}
+
+impl Drop for Recent {
+    fn drop(&mut self) {
+        if let Some(loading) = &self.loading {
+            loading.abort();
+        }
+    }
+}
+
+/// Opens an object from the recent strip. It gets fetched again, it might have
+/// changed or be gone by now. Dedicated details need the stores their list
+/// keeps, so this is the generic one with the YAML.
+pub struct Jump {
+    task: Option<JoinHandle<Result<DynamicObject>>>,
+    client: kube::Client,
+    resource: ApiResource,
+
+    detail: Option<BoxWidget>,
+}
+
+impl Jump {
+    pub fn new(client: kube::Client, visit: &Visit) -> Self {
+        let resource = ApiResource::from(visit);
+        let namespace = visit.namespace.clone();
+        let name = visit.name.clone().unwrap_or_default();
+        let scope = policy::scope();
+
+        let task = tokio::spawn({
+            let client = client.clone();
+            let resource = resource.clone();
+
+            async move {
+                // Visits are kept across sessions, the namespaces someone may
+                // browse could have changed since.
+                if let Some(ns) = namespace.as_deref().filter(|ns| !scope.allows(ns)) {
+                    return Err(eyre!("{ns} isn't one of the namespaces you can browse"));
+                }
+
+                let api = match namespace.as_deref() {
+                    Some(ns) => Api::<DynamicObject>::namespaced_with(client, ns, &resource),
+                    None => Api::<DynamicObject>::all_with(client, &resource),
+                };
+
+                api.get_opt(&name)
+                    .await?
+                    .ok_or_else(|| eyre!("{} {name} no longer exists", resource.kind))
+            }
+        });
+
+        Self {
+            task: Some(task),
+            client,
+            resource,
+            detail: None,
+        }
+    }
+
+    fn poll(&mut self) {
+        if !self.task.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+
+        let Some(task) = self.task.take() else {
+            return;
+        };
+
+        self.detail = Some(
+            match futures::executor::block_on(async move { task.await? }) {
+                Ok(object) => dynamic::detail(&self.client, Arc::new(object), &self.resource),
+                Err(err) => Error::from(err).boxed(),
+            },
+        );
+    }
+}
+
+impl Widget for Jump {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(detail) = self.detail.as_mut() {
+            return detail.dispatch(event, buffer, area);
+        }
+
+        match event.key() {
+            Some(Keypress::Escape) => Ok(Broadcast::Exited),
+            _ => Ok(Broadcast::Ignored),
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        self.detail
+            .as_ref()
+            .map(|detail| detail.actions())
+            .unwrap_or_default()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        // The list of the kind is underneath, it'd show through otherwise.
+        frame.render_widget(Clear, area);
+
+        match self.detail.as_mut() {
+            Some(detail) => detail.draw(frame, area),
+            None => Loading.draw(frame, area),
+        }
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Jump {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
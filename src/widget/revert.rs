@@ -0,0 +1,354 @@
+use std::collections::BTreeSet;
+
+use chrono::Utc;
+use eyre::{eyre, Result};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::{api::DynamicObject, ResourceExt};
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Row, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use super::{centered, confirm::Confirm, nav::exit_keys, table, theme, Widget};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{age::Age, revision},
+};
+
+lazy_static! {
+    static ref REVERTS: IntCounterVec = register_int_counter_vec!(
+        "configmap_reverts_total",
+        "Number of ConfigMaps put back to a saved copy, by whether it succeeded",
+        &["result"]
+    )
+    .unwrap();
+}
+
+// Keys whose values aren't the same in both, including ones only one of them
+// has.
+fn changed(a: &ConfigMap, b: &ConfigMap) -> usize {
+    let keys: BTreeSet<_> = a
+        .data
+        .iter()
+        .flatten()
+        .map(|(key, _)| key)
+        .chain(b.data.iter().flatten().map(|(key, _)| key))
+        .chain(a.binary_data.iter().flatten().map(|(key, _)| key))
+        .chain(b.binary_data.iter().flatten().map(|(key, _)| key))
+        .collect();
+
+    keys.into_iter()
+        .filter(|key| {
+            let data = |cm: &ConfigMap| cm.data.as_ref().and_then(|data| data.get(*key)).cloned();
+            let binary = |cm: &ConfigMap| {
+                cm.binary_data
+                    .as_ref()
+                    .and_then(|data| data.get(*key))
+                    .cloned()
+            };
+
+            data(a) != data(b) || binary(a) != binary(b)
+        })
+        .count()
+}
+
+#[derive(Clone)]
+struct Revision {
+    cm: ConfigMap,
+    changed: usize,
+}
+
+impl table::Row for Revision {
+    fn columns() -> Vec<&'static str> {
+        vec!["Copy", "Saved", "Differs"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let age = self
+            .cm
+            .creation_timestamp()
+            .map(|ts| (Utc::now() - ts.0).to_age())
+            .unwrap_or_default();
+
+        vec![
+            self.cm.name_any(),
+            format!("{age} ago"),
+            match self.changed {
+                0 => "same as now".to_string(),
+                1 => "1 key".to_string(),
+                n => format!("{n} keys"),
+            },
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+}
+
+struct Revisions(Vec<Revision>);
+
+impl table::Items for Revisions {
+    type Item = Revision;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        self.0.clone()
+    }
+}
+
+enum Stage {
+    Loading(JoinHandle<Result<Vec<ConfigMap>>>),
+    Pick(Vec<Revision>, table::Table<Revisions>),
+    Confirm(ConfigMap, Confirm),
+    Reverting(JoinHandle<Result<()>>),
+    Done(Result<String, String>),
+}
+
+/// Put a `ConfigMap` back to one of the copies saved each time it was edited
+/// from the dashboard, newest first. The newest one is what it was right
+/// before the last edit, so undoing that is `R` followed by `enter` and `y`.
+pub struct Revert {
+    client: kube::Client,
+    cm: Option<ConfigMap>,
+
+    stage: Stage,
+}
+
+impl Revert {
+    pub fn new(client: kube::Client, object: &DynamicObject) -> Self {
+        let cm: Option<ConfigMap> = serde_json::to_value(object)
+            .and_then(serde_json::from_value)
+            .ok();
+
+        Self::with_config_map(client, cm)
+    }
+
+    pub fn config_map(client: kube::Client, cm: &ConfigMap) -> Self {
+        Self::with_config_map(client, Some(cm.clone()))
+    }
+
+    fn with_config_map(client: kube::Client, cm: Option<ConfigMap>) -> Self {
+        let stage = match cm.clone() {
+            Some(cm) => {
+                let client = client.clone();
+
+                Stage::Loading(tokio::spawn(
+                    async move { revision::list(client, &cm).await },
+                ))
+            }
+            None => Stage::Done(Err("not a ConfigMap".to_string())),
+        };
+
+        Self { client, cm, stage }
+    }
+
+    fn confirm(&self, revision: &ConfigMap) -> Stage {
+        let name = self
+            .cm
+            .as_ref()
+            .map(ResourceExt::name_any)
+            .unwrap_or_default();
+
+        Stage::Confirm(
+            revision.clone(),
+            Confirm::quick(format!(
+                "Replace the contents of {name} with {}? What's there now is saved first. Press y \
+                 to go ahead.",
+                revision.name_any(),
+            )),
+        )
+    }
+
+    fn revert(&self, revision: ConfigMap) -> Result<Stage> {
+        let client = self.client.clone();
+        let cm = self.cm.clone().ok_or_else(|| eyre!("not a ConfigMap"))?;
+
+        Ok(Stage::Reverting(tokio::spawn(async move {
+            let result = revision::revert(client, &cm, &revision).await;
+
+            REVERTS
+                .with_label_values(&[if result.is_ok() { "success" } else { "failure" }])
+                .inc();
+
+            result
+        })))
+    }
+
+    fn advance(
+        &mut self,
+        stage: Stage,
+        event: &Event,
+        buffer: &Buffer,
+        area: Rect,
+    ) -> Result<(Stage, Broadcast)> {
+        let stage = match stage {
+            Stage::Loading(_) if matches!(event.key(), Some(exit_keys!())) => {
+                return Ok((Stage::Done(Ok(String::new())), Broadcast::Exited))
+            }
+            Stage::Loading(task) => Stage::Loading(task),
+            Stage::Pick(revisions, mut table) => match table.dispatch(event, buffer, area)? {
+                Broadcast::Selected(idx) => match revisions.get(idx) {
+                    Some(revision) => self.confirm(&revision.cm),
+                    None => Stage::Pick(revisions, table),
+                },
+                _ if matches!(event.key(), Some(exit_keys!())) => {
+                    return Ok((Stage::Pick(revisions, table), Broadcast::Exited))
+                }
+                _ => Stage::Pick(revisions, table),
+            },
+            Stage::Confirm(revision, mut confirm) => match confirm.dispatch(event, buffer, area)? {
+                Broadcast::Selected(_) => self.revert(revision)?,
+                Broadcast::Exited => {
+                    return Ok((Stage::Confirm(revision, confirm), Broadcast::Exited))
+                }
+                _ => Stage::Confirm(revision, confirm),
+            },
+            Stage::Reverting(task) => Stage::Reverting(task),
+            Stage::Done(result) => {
+                if event.key().is_some() {
+                    return Ok((Stage::Done(result), Broadcast::Exited));
+                }
+
+                Stage::Done(result)
+            }
+        };
+
+        Ok((stage, Broadcast::Consumed))
+    }
+
+    fn loaded(&self, task: &mut JoinHandle<Result<Vec<ConfigMap>>>) -> Stage {
+        match futures::executor::block_on(async move { task.await? }) {
+            Ok(revisions) if revisions.is_empty() => Stage::Done(Err(
+                "there are no saved copies, they're made when the ConfigMap is edited from kty"
+                    .to_string(),
+            )),
+            Ok(revisions) => {
+                let revisions: Vec<_> = revisions
+                    .into_iter()
+                    .map(|cm| Revision {
+                        changed: self.cm.as_ref().map_or(0, |current| changed(current, &cm)),
+                        cm,
+                    })
+                    .collect();
+
+                let table = table::Table::builder()
+                    .items(Revisions(revisions.clone()))
+                    .border(false)
+                    .build();
+
+                Stage::Pick(revisions, table)
+            }
+            Err(err) => Stage::Done(Err(message(&err))),
+        }
+    }
+
+    fn finished(task: &mut JoinHandle<Result<()>>) -> Stage {
+        Stage::Done(
+            match futures::executor::block_on(async move { task.await? }) {
+                Ok(()) => Ok("reverted".to_string()),
+                Err(err) => Err(message(&err)),
+            },
+        )
+    }
+
+    fn help(&self) -> &'static str {
+        match &self.stage {
+            Stage::Loading(_) => "esc: cancel",
+            Stage::Pick(..) => "enter: revert to this copy, esc: cancel",
+            Stage::Confirm(_, confirm) => confirm.help(),
+            Stage::Reverting(_) => "",
+            Stage::Done(_) => "press any key to close",
+        }
+    }
+}
+
+fn message(err: &eyre::Report) -> String {
+    match err.downcast_ref::<kube::Error>() {
+        Some(kube::Error::Api(resp)) => resp.message.clone(),
+        _ => err.to_string(),
+    }
+}
+
+impl Widget for Revert {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if event.key().is_none() {
+            return Ok(Broadcast::Ignored);
+        }
+
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        match self.advance(stage, event, buffer, area) {
+            Ok((stage, result)) => {
+                self.stage = stage;
+
+                Ok(result)
+            }
+            Err(err) => {
+                self.stage = Stage::Done(Err(err.to_string()));
+
+                Ok(Broadcast::Consumed)
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let stage = std::mem::replace(&mut self.stage, Stage::Done(Ok(String::new())));
+
+        self.stage = match stage {
+            Stage::Loading(mut task) if task.is_finished() => self.loaded(&mut task),
+            Stage::Reverting(mut task) if task.is_finished() => Self::finished(&mut task),
+            stage => stage,
+        };
+
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Revert ConfigMap")
+            .title_bottom(Line::from(self.help()).style(Style::default().fg(tailwind::GRAY.c400)));
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        match &mut self.stage {
+            Stage::Loading(_) => frame.render_widget(Paragraph::new("loading..."), inner),
+            Stage::Pick(_, table) => table.draw(frame, inner)?,
+            Stage::Confirm(_, confirm) => confirm.draw(frame, inner)?,
+            Stage::Reverting(_) => frame.render_widget(Paragraph::new("reverting..."), inner),
+            Stage::Done(result) => {
+                let (msg, color) = match result {
+                    Ok(msg) => (msg.as_str(), theme::healthy()),
+                    Err(msg) => (msg.as_str(), theme::unhealthy()),
+                };
+
+                frame.render_widget(
+                    Paragraph::new(msg)
+                        .style(Style::default().fg(color))
+                        .wrap(Wrap { trim: false }),
+                    inner,
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Revert {
+    fn drop(&mut self) {
+        match &self.stage {
+            Stage::Loading(task) => task.abort(),
+            Stage::Reverting(task) => task.abort(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use ratatui::{
+    style::{palette::tailwind, Style},
+    text::Line,
+};
+
+/// Which part of some long content is on screen, shown as `[123-160/4512]`
+/// in the title of whatever is displaying it.
+pub struct Scroll {
+    /// Index of the first line that's visible.
+    pub offset: usize,
+    /// How many lines there is room for.
+    pub height: usize,
+    pub total: usize,
+}
+
+impl Scroll {
+    /// Nothing is shown when everything fits, there's nowhere else to be.
+    pub fn indicator(&self) -> Option<String> {
+        if self.total <= self.height && self.offset == 0 {
+            return None;
+        }
+
+        let start = self.offset.min(self.total.saturating_sub(1));
+        let end = self.offset.saturating_add(self.height).min(self.total);
+
+        Some(format!("[{}-{end}/{}]", start + 1, self.total))
+    }
+
+    /// For the right hand side of the top line, where block titles go. `extra`
+    /// is anything else that lives there, it goes before the indicator.
+    pub fn title(&self, extra: Option<&str>) -> Option<Line<'static>> {
+        let title = match (extra.filter(|extra| !extra.is_empty()), self.indicator()) {
+            (Some(extra), Some(indicator)) => format!("{extra} {indicator}"),
+            (Some(extra), None) => extra.to_string(),
+            (None, Some(indicator)) => indicator,
+            (None, None) => return None,
+        };
+
+        Some(
+            Line::from(title)
+                .style(Style::default().fg(tailwind::GRAY.c400))
+                .right_aligned(),
+        )
+    }
+}
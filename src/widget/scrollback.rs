@@ -0,0 +1,277 @@
+use std::sync::LazyLock;
+
+use eyre::Result;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    Frame,
+};
+use regex::Regex;
+
+use super::{
+    nav::{move_cursor, Movement},
+    viewport::Viewport,
+    Widget,
+};
+use crate::events::{Broadcast, Event, Keypress};
+
+/// How many lines of history are kept, logs are held to the same.
+pub static MAX_LINES: usize = 10_000;
+
+// Output without any newlines, such as a progress bar redrawn with carriage
+// returns or a binary being `cat`ed, would otherwise keep growing the line
+// being written to forever. Past this it is cut off into a line of its own.
+static MAX_LINE: usize = 64 * 1024;
+
+// Everything other than SGR (colors and the like) is about moving the cursor
+// around the screen. Without a full terminal emulator there's no way to make
+// sense of those, so they're stripped out.
+static ESCAPES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\x1b\[[0-9;?]*[@-ln-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[()][0-9A-Za-z]|\x1b[=>78]",
+    )
+    .expect("valid regex")
+});
+
+/// Line based history of everything a raw session has written to the
+/// terminal. This is an approximation of what was on screen: carriage returns
+/// overwrite the line and cursor movement is ignored. That works well for
+/// shells and command output, not so much for full screen programs.
+#[derive(Default)]
+pub struct ScrollBack {
+    lines: Vec<String>,
+    partial: Vec<u8>,
+}
+
+impl ScrollBack {
+    pub fn push(&mut self, data: &[u8]) {
+        let mut data = data;
+
+        while let Some(end) = data.iter().position(|b| *b == b'\n') {
+            self.extend(&data[..end]);
+            self.lines.push(clean(&self.partial));
+            self.partial.clear();
+
+            data = &data[end + 1..];
+        }
+
+        self.extend(data);
+
+        // Draining one line at a time means shifting the entire buffer for every
+        // line, so let it go over a little bit before trimming.
+        if self.lines.len() > MAX_LINES + MAX_LINES / 10 {
+            self.lines.drain(..self.lines.len() - MAX_LINES);
+        }
+    }
+
+    fn extend(&mut self, data: &[u8]) {
+        self.partial.extend_from_slice(data);
+
+        while self.partial.len() > MAX_LINE {
+            let rest = self.partial.split_off(MAX_LINE);
+
+            self.lines.push(clean(&self.partial));
+            self.partial = rest;
+        }
+    }
+
+    /// The line that is currently being written to, usually a prompt.
+    pub fn partial(&self) -> &[u8] {
+        &self.partial
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = self.lines.clone();
+
+        if !self.partial.is_empty() {
+            lines.push(clean(&self.partial));
+        }
+
+        lines
+    }
+}
+
+fn clean(line: &[u8]) -> String {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim_end_matches('\r');
+    let line = line.rsplit('\r').next().unwrap_or_default();
+
+    let mut result = String::with_capacity(line.len());
+
+    for c in ESCAPES.replace_all(line, "").chars() {
+        match c {
+            '\x08' => {
+                result.pop();
+            }
+            '\x1b' | '\t' => result.push(c),
+            c if c.is_control() => {}
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+static STATUS: &str = "scroll-back: q to return to the shell";
+
+/// Read-only view of a `ScrollBack`, starts at the bottom. Exits on `q`, esc or
+/// enter.
+pub struct CopyMode {
+    lines: Vec<String>,
+    position: Position,
+    status: String,
+}
+
+impl CopyMode {
+    pub fn new(lines: Vec<String>) -> Self {
+        Self {
+            lines,
+            position: Position::new(0, u16::MAX),
+            status: STATUS.to_string(),
+        }
+    }
+
+    /// Replace the text in the status bar at the top of the view.
+    pub fn status(&mut self, status: impl Into<String>) {
+        self.status = status.into();
+    }
+}
+
+impl Widget for CopyMode {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(
+            key,
+            Keypress::Printable('q') | Keypress::Escape | Keypress::Enter
+        ) {
+            return Ok(Broadcast::Exited);
+        }
+
+        if let Some(Movement::Y(y)) = move_cursor(key, area) {
+            // The viewport clamps this while drawing, make sure that scrolling up
+            // starts from where the view actually is.
+            #[allow(clippy::cast_possible_truncation)]
+            let max = (self.lines.len() as u16).saturating_sub(area.height.saturating_sub(1));
+
+            self.position.y = self.position.y.min(max).saturating_add_signed(y);
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let [status, area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(0)]).areas(area);
+
+        frame.render_widget(
+            Line::from(self.status.as_str()).style(
+                Style::default()
+                    .fg(tailwind::GRAY.c900)
+                    .bg(tailwind::YELLOW.c300),
+            ),
+            status,
+        );
+
+        Viewport::builder()
+            .buffer(&self.lines)
+            .view(self.position)
+            .build()
+            .draw(frame, area)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrollback(chunks: &[&[u8]]) -> ScrollBack {
+        let mut scrollback = ScrollBack::default();
+
+        for chunk in chunks {
+            scrollback.push(chunk);
+        }
+
+        scrollback
+    }
+
+    #[test]
+    fn lines() {
+        let scrollback = scrollback(&[b"one\ntwo\n$ "]);
+
+        assert_eq!(scrollback.lines(), vec!["one", "two", "$ "]);
+        assert_eq!(scrollback.partial(), b"$ ");
+    }
+
+    #[test]
+    fn across_pushes() {
+        let scrollback = scrollback(&[b"hel", b"lo\nwor", b"ld\n"]);
+
+        assert_eq!(scrollback.lines(), vec!["hello", "world"]);
+        assert!(scrollback.partial().is_empty());
+    }
+
+    #[test]
+    fn empty_lines() {
+        assert_eq!(scrollback(&[b"\n\na\n"]).lines(), vec!["", "", "a"]);
+    }
+
+    #[test]
+    fn carriage_return() {
+        let scrollback = scrollback(&[b"10%\r50%\r100%\r\ndone\r\n"]);
+
+        assert_eq!(scrollback.lines(), vec!["100%", "done"]);
+    }
+
+    #[test]
+    fn escapes() {
+        let scrollback = scrollback(&[b"\x1b[2K\x1b[1;1H\x1b[31mred\x1b[0m\n", b"ab\x08c\x07\n"]);
+
+        assert_eq!(scrollback.lines(), vec!["\x1b[31mred\x1b[0m", "ac"]);
+    }
+
+    #[test]
+    fn max_line() {
+        let long = vec![b'a'; MAX_LINE * 2 + 10];
+        let scrollback = scrollback(&[&long]);
+
+        let lines = scrollback.lines();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].len(), MAX_LINE);
+        assert_eq!(lines[1].len(), MAX_LINE);
+        assert_eq!(lines[2].len(), 10);
+        assert_eq!(scrollback.partial().len(), 10);
+    }
+
+    #[test]
+    fn max_line_exact() {
+        let mut line = vec![b'a'; MAX_LINE];
+        line.push(b'\n');
+
+        assert_eq!(scrollback(&[&line]).lines(), vec!["a".repeat(MAX_LINE)]);
+    }
+
+    #[test]
+    fn max_line_across_pushes() {
+        let half = vec![b'a'; MAX_LINE / 2 + 1];
+        let scrollback = scrollback(&[&half, &half, b"\n"]);
+
+        let lines = scrollback.lines();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), MAX_LINE);
+        assert_eq!(lines[1].len(), 2);
+    }
+
+    #[test]
+    fn max_lines() {
+        let output = "line\n".repeat(MAX_LINES + MAX_LINES / 10 + 1);
+
+        assert_eq!(scrollback(&[output.as_bytes()]).lines().len(), MAX_LINES);
+    }
+}
@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::Secret;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter, IntCounter};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use tokio::sync::oneshot;
+
+use super::{
+    object::{self, Object},
+    table,
+    tabs::Tab,
+    yaml::Yaml,
+    Action, Widget, WIDGET_VIEWS,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        secret::{Entry, SecretExt},
+        store::Store,
+    },
+};
+
+lazy_static! {
+    static ref SECRET_REVEALS: IntCounter = register_int_counter!(
+        "secret_reveals_total",
+        "Number of secret values revealed (v on a Secret's data)"
+    )
+    .unwrap();
+}
+
+pub type List = object::List<Secret>;
+
+/// A `Secret` with every value hidden until it's asked for, one key at a time.
+/// The "Overview" tab leaves `data` out entirely instead of showing it as
+/// base64, which is only a keypress away from being read. Quick edit would
+/// have to show the values to edit them, so there isn't any.
+impl Object for Secret {
+    type Items = Arc<Store<Secret>>;
+
+    const EDITABLE: bool = false;
+
+    fn items(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Self::Items, oneshot::Receiver<()>) {
+        Store::namespaced(client, namespace)
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.get(idx, filter)
+    }
+
+    fn tabs(client: &kube::Client, _: &Self::Items, secret: &Arc<Self>) -> Vec<Tab> {
+        let data = {
+            let secret = secret.clone();
+
+            Tab::builder()
+                .name("Data".to_string())
+                .constructor(Box::new(move || Values::new(secret.entries()).boxed()))
+                .build()
+        };
+
+        vec![
+            data,
+            Yaml::tab(
+                "Overview".to_string(),
+                client.clone(),
+                Arc::new(secret.masked()),
+            ),
+        ]
+    }
+}
+
+#[derive(Clone)]
+struct Entries(Vec<Entry>);
+
+impl table::Items for Entries {
+    type Item = Entry;
+
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        self.0
+            .iter()
+            .filter(|entry| {
+                filter
+                    .as_ref()
+                    .map_or(true, |f| entry.key.contains(f.as_str()))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Every key on the left, with `v` showing the selected one's value on the
+/// right. Moving to another key hides it again, so at most one value is ever
+/// on screen.
+struct Values {
+    entries: Entries,
+    table: table::Table<Entries>,
+
+    // The key whose value is showing.
+    revealed: Option<usize>,
+}
+
+impl Values {
+    fn new(entries: Vec<Entry>) -> Self {
+        WIDGET_VIEWS.secret.data.inc();
+
+        let entries = Entries(entries);
+
+        Self {
+            table: table::Table::builder().items(entries.clone()).build(),
+            entries,
+            revealed: None,
+        }
+    }
+
+    fn selected(&self) -> Option<(usize, &Entry)> {
+        let idx = self.table.selected()?;
+
+        self.entries.0.get(idx).map(|entry| (idx, entry))
+    }
+}
+
+impl Widget for Values {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        match event.key() {
+            Some(Keypress::Printable('v')) => {
+                let selected = self.selected().map(|(idx, _)| idx);
+
+                self.revealed = if self.revealed == selected {
+                    None
+                } else {
+                    SECRET_REVEALS.inc();
+
+                    selected
+                };
+
+                return Ok(Broadcast::Consumed);
+            }
+            Some(Keypress::Escape) if self.revealed.is_some() => {
+                self.revealed = None;
+
+                return Ok(Broadcast::Consumed);
+            }
+            _ => {}
+        }
+
+        match self.table.dispatch(event, buffer, area)? {
+            Broadcast::Exited => Ok(Broadcast::Ignored),
+            result => Ok(result),
+        }
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action::new(
+            'v',
+            if self.revealed.is_some() {
+                "Hide the value"
+            } else {
+                "Reveal the selected value"
+            },
+        )]
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let selected = self.selected().map(|(idx, _)| idx);
+
+        if self.revealed != selected {
+            self.revealed = None;
+        }
+
+        let [keys, value] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Fill(1)]).areas(area);
+
+        self.table.draw(frame, keys)?;
+
+        let gray = Style::default().fg(tailwind::GRAY.c400);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(
+                self.selected()
+                    .map(|(_, entry)| entry.key.clone())
+                    .unwrap_or_default(),
+            )
+            .title_bottom(
+                Line::from(if self.revealed.is_some() {
+                    "v: hide"
+                } else {
+                    "v: reveal"
+                })
+                .style(gray),
+            );
+        let inner = block.inner(value);
+
+        frame.render_widget(block, value);
+
+        let pg = match self.selected() {
+            None => Paragraph::new("no keys").style(gray),
+            Some((idx, entry)) if self.revealed == Some(idx) => {
+                Paragraph::new(entry.value.expose())
+            }
+            Some(_) => Paragraph::new("••••••••").style(gray),
+        };
+
+        frame.render_widget(pg.wrap(Wrap { trim: false }), inner);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+pub mod check;
+pub mod churn;
+
+use std::sync::Arc;
+
+use k8s_openapi::api::core::v1::Service;
+use kube::ResourceExt;
+use tokio::sync::oneshot;
+
+use self::{check::Ports, churn::Churn};
+use super::{
+    object::{self, Object},
+    tabs::Tab,
+    yaml::Yaml,
+    Action, BoxWidget, Widget,
+};
+use crate::{
+    events::Keypress,
+    resources::{service::ServiceExt, store::Store},
+};
+
+pub type List = object::List<Service>;
+
+/// A service along with the pods its selector picks, which are the ones it
+/// routes to once they're ready. A service that isn't routing anywhere usually
+/// has an empty "Pods" tab, or one where nothing is ready.
+impl Object for Service {
+    type Items = Arc<Store<Service>>;
+
+    fn items(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Self::Items, oneshot::Receiver<()>) {
+        Store::namespaced(client, namespace)
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.get(idx, filter)
+    }
+
+    fn tabs(client: &kube::Client, _: &Self::Items, service: &Arc<Self>) -> Vec<Tab> {
+        let mut tabs = vec![Yaml::tab(
+            "Overview".to_string(),
+            client.clone(),
+            service.clone(),
+        )];
+
+        tabs.extend(object::pods(
+            client,
+            service.namespace().unwrap_or_default(),
+            service.selector(),
+        ));
+
+        tabs
+    }
+
+    fn status(_: &Self::Items, service: &Self) -> Option<String> {
+        service
+            .selector()
+            .is_none()
+            .then(|| "no selector, endpoints are managed elsewhere".to_string())
+    }
+
+    fn popup(
+        client: &kube::Client,
+        _: &Self::Items,
+        service: &Arc<Self>,
+        key: &Keypress,
+    ) -> Option<BoxWidget> {
+        match key {
+            Keypress::Printable('c') => Some(Churn::new(client.clone(), service.clone()).boxed()),
+            Keypress::Printable('t') => Some(Ports::new(client.clone(), service.clone()).boxed()),
+            _ => None,
+        }
+    }
+
+    fn actions(_: &Self::Items, _: &Self) -> Vec<Action> {
+        vec![
+            Action::new('t', "Test connecting to each port"),
+            Action::new('c', "Watch endpoints come and go"),
+        ]
+    }
+}
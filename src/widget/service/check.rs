@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use eyre::Result;
+use k8s_openapi::api::core::v1::Service;
+use kube::ResourceExt;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::service::check::{self, Outcome},
+    widget::{centered_with, nav::exit_keys, theme, Widget},
+};
+
+type Checked = Result<(String, Vec<Outcome>)>;
+
+/// Smoke test for a service after a deploy: every port gets connected to
+/// through a port-forward to one of the ready pods behind it. Ports that look
+/// like HTTP get a `GET /` and ones that look like gRPC get a health check, on
+/// top of the connection. `r` runs it again.
+pub struct Ports {
+    client: kube::Client,
+    service: Arc<Service>,
+
+    task: Option<JoinHandle<Checked>>,
+    result: Option<Result<(String, Vec<Outcome>), String>>,
+}
+
+impl Ports {
+    pub fn new(client: kube::Client, service: Arc<Service>) -> Self {
+        let mut check = Self {
+            client,
+            service,
+            task: None,
+            result: None,
+        };
+
+        check.run();
+
+        check
+    }
+
+    fn run(&mut self) {
+        let client = self.client.clone();
+        let service = self.service.clone();
+
+        self.result = None;
+        self.task = Some(tokio::spawn(
+            async move { check::all(client, &service).await },
+        ));
+    }
+
+    fn poll(&mut self) {
+        if !self.task.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+
+        let Some(task) = self.task.take() else {
+            return;
+        };
+
+        self.result = Some(
+            futures::executor::block_on(async move { task.await? }).map_err(|err| match err
+                .downcast_ref::<kube::Error>(
+            ) {
+                Some(kube::Error::Api(resp)) => resp.message.clone(),
+                _ => err.to_string(),
+            }),
+        );
+    }
+
+    fn lines(pod: &str, outcomes: &[Outcome]) -> Vec<Line<'static>> {
+        let gray = Style::default().fg(tailwind::GRAY.c400);
+
+        let width = outcomes
+            .iter()
+            .map(|outcome| outcome.port.len())
+            .max()
+            .unwrap_or_default();
+
+        let mut lines = vec![Line::from(format!("through {pod}")).style(gray)];
+
+        for outcome in outcomes {
+            let (mark, msg, color) = match &outcome.result {
+                Ok(msg) => ("✓", msg.as_str(), theme::healthy()),
+                Err(msg) => ("✗", msg.as_str(), theme::unhealthy()),
+            };
+
+            lines.push(Line::from(vec![
+                Span::from(format!("{mark} ")).style(Style::default().fg(color)),
+                Span::from(format!("{:width$}  {:<5}", outcome.port, outcome.protocol)),
+                Span::from(msg.to_string()).style(Style::default().fg(color)),
+                Span::from(format!(" ({}ms)", outcome.took.as_millis())).style(gray),
+            ]));
+        }
+
+        lines
+    }
+}
+
+impl Widget for Ports {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, _: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match key {
+            exit_keys!() => return Ok(Broadcast::Exited),
+            Keypress::Printable('r') if self.task.is_none() => self.run(),
+            _ => {}
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.poll();
+
+        let area = centered_with(area, Constraint::Percentage(80), Constraint::Percentage(60));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Ports of {}", self.service.name_any()))
+            .title_bottom(
+                Line::from(if self.task.is_some() {
+                    "esc: close"
+                } else {
+                    "r: check again, esc: close"
+                })
+                .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        let pg = match &self.result {
+            None => Paragraph::new("checking each port..."),
+            Some(Ok((pod, outcomes))) => Paragraph::new(Self::lines(pod, outcomes)),
+            Some(Err(err)) => {
+                Paragraph::new(err.clone()).style(Style::default().fg(theme::unhealthy()))
+            }
+        };
+
+        frame.render_widget(pg.wrap(Wrap { trim: false }), inner);
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Ports {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
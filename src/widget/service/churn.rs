@@ -0,0 +1,267 @@
+use std::sync::{Arc, Mutex};
+
+use eyre::Result;
+use futures::StreamExt;
+use k8s_openapi::api::{core::v1::Service, discovery::v1::EndpointSlice};
+use kube::{
+    runtime::{self, watcher, WatchStreamExt},
+    Api, ResourceExt,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{palette::tailwind, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Sparkline},
+    Frame,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    resources::service::churn::{self, Churn as Changes, Kind},
+    throttle::THROTTLE,
+    widget::{
+        centered_with,
+        nav::{exit_keys, move_cursor, Movement},
+        theme, Widget, WIDGET_VIEWS,
+    },
+};
+
+// Half an hour of history in the sparkline, a minute per bar.
+static MINUTES: usize = 30;
+
+/// Addresses being added to and removed from a service's `EndpointSlice`s as
+/// it happens. Backends that keep flipping between ready and not ready are
+/// the usual reason for a service returning the odd 502, they're summarized
+/// at the top.
+pub struct Churn {
+    service: Arc<Service>,
+    changes: Arc<Mutex<Changes>>,
+    task: JoinHandle<()>,
+
+    scroll: u16,
+}
+
+impl Churn {
+    pub fn new(client: kube::Client, service: Arc<Service>) -> Self {
+        WIDGET_VIEWS.service.churn.inc();
+
+        let changes = Arc::new(Mutex::new(Changes::default()));
+
+        let task = tokio::spawn({
+            let changes = changes.clone();
+            let api =
+                Api::<EndpointSlice>::namespaced(client, &service.namespace().unwrap_or_default());
+            let config = watcher::Config::default().labels(&format!(
+                "kubernetes.io/service-name={}",
+                service.name_any()
+            ));
+
+            async move {
+                let mut stream = runtime::watcher(api, config).default_backoff().boxed();
+
+                loop {
+                    // See `Store` for why this waits before pulling the next event.
+                    THROTTLE.wait().await;
+
+                    let Some(event) = stream.next().await else {
+                        break;
+                    };
+
+                    match event {
+                        Ok(event) => {
+                            if let Ok(mut changes) = changes.lock() {
+                                changes.handle(event);
+                            }
+                        }
+                        Err(err) => tracing::debug!("endpoint slice watch failed: {err}"),
+                    }
+                }
+            }
+        });
+
+        Self {
+            service,
+            changes,
+            task,
+            scroll: 0,
+        }
+    }
+
+    fn summary(changes: &Changes) -> Vec<Line<'static>> {
+        let gray = Style::default().fg(tailwind::GRAY.c400);
+
+        let backends = changes.backends();
+        let ready = backends.values().filter(|backend| backend.ready).count();
+
+        let mut lines = vec![Line::from(vec![
+            Span::styled("backends: ", gray),
+            Span::styled(
+                format!("{ready}/{} ready", backends.len()),
+                Style::default().fg(if ready == backends.len() {
+                    theme::healthy()
+                } else {
+                    theme::unhealthy()
+                }),
+            ),
+        ])];
+
+        let flapping = changes.flapping();
+
+        if flapping.is_empty() {
+            lines.push(Line::styled(
+                format!(
+                    "nothing flapping in the last {}m",
+                    churn::WINDOW.num_minutes()
+                ),
+                gray,
+            ));
+        }
+
+        for (address, pod, count) in flapping {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "flapping ",
+                    Style::default()
+                        .fg(tailwind::YELLOW.c300)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(pod.map_or(address.clone(), |pod| format!("{pod} ({address})"))),
+                Span::styled(
+                    format!(
+                        ", {count} changes in the last {}m",
+                        churn::WINDOW.num_minutes()
+                    ),
+                    gray,
+                ),
+            ]));
+        }
+
+        lines
+    }
+
+    fn lines(changes: &Changes) -> Vec<Line<'static>> {
+        let time = Style::default().fg(tailwind::GRAY.c500);
+
+        if changes.changes().is_empty() {
+            return vec![Line::styled(
+                "no changes yet, watching for endpoints coming and going...",
+                Style::default().fg(tailwind::GRAY.c400),
+            )];
+        }
+
+        changes
+            .changes()
+            .iter()
+            .rev()
+            .map(|change| {
+                let color = match change.kind {
+                    Kind::Added | Kind::Ready => theme::healthy(),
+                    Kind::Removed | Kind::NotReady => theme::unhealthy(),
+                };
+
+                Line::from(vec![
+                    Span::styled(change.at.format("%H:%M:%S ").to_string(), time),
+                    Span::styled(
+                        format!("{:<10}", change.kind.to_string()),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(change.pod.as_ref().map_or(change.address.clone(), |pod| {
+                        format!("{pod} ({})", change.address)
+                    })),
+                ])
+            })
+            .collect()
+    }
+}
+
+impl Widget for Churn {
+    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(key, exit_keys!()) {
+            return Ok(Broadcast::Exited);
+        }
+
+        match key {
+            Keypress::Printable('c') => {
+                if let Ok(mut changes) = self.changes.lock() {
+                    changes.clear();
+                }
+            }
+            key => {
+                if let Some(Movement::Y(y)) = move_cursor(key, area) {
+                    self.scroll = self.scroll.saturating_add_signed(y);
+                }
+            }
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = centered_with(area, Constraint::Percentage(80), Constraint::Percentage(80));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Endpoint churn for {}", self.service.name_any()))
+            .title_bottom(
+                Line::from("c: clear, esc: close").style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        let Ok(changes) = self.changes.lock() else {
+            return Ok(());
+        };
+
+        let summary = Self::summary(&changes);
+
+        let [summary_area, spark, _, list] = Layout::vertical([
+            Constraint::Length(summary.len() as u16),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        frame.render_widget(Paragraph::new(summary), summary_area);
+
+        frame.render_widget(
+            Sparkline::default()
+                .block(
+                    Block::default().title(
+                        Line::from(format!("changes per minute, last {MINUTES}m"))
+                            .style(Style::default().fg(tailwind::GRAY.c400)),
+                    ),
+                )
+                .data(&changes.per_minute(MINUTES))
+                .style(Style::default().fg(tailwind::YELLOW.c300)),
+            spark,
+        );
+
+        let lines = Self::lines(&changes);
+        self.scroll = self.scroll.min(lines.len().saturating_sub(1) as u16);
+
+        frame.render_widget(Paragraph::new(lines).scroll((self.scroll, 0)), list);
+
+        Ok(())
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+impl Drop for Churn {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
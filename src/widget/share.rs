@@ -0,0 +1,295 @@
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use chrono::Utc;
+use eyre::{eyre, Result};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Row, Wrap},
+    Frame,
+};
+use tokio::{
+    io::{AsyncWrite, AsyncWriteExt},
+    sync::{broadcast::error::RecvError, mpsc::UnboundedReceiver},
+};
+
+use super::{
+    centered_with,
+    nav::exit_keys,
+    table::{self, Items},
+    theme, Raw, Widget,
+};
+use crate::{
+    events::{Broadcast, Event, Keypress},
+    lease::ELECTION,
+    policy,
+    resources::age::Age,
+    share::{Share, SHARES},
+};
+
+impl table::Row for Arc<Share> {
+    fn columns() -> Vec<&'static str> {
+        vec!["ID", "Namespace", "Pod", "Container", "Watching", "Age"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.namespace.clone(),
+            self.pod.clone(),
+            self.container.clone(),
+            self.viewers().to_string(),
+            (Utc::now() - self.started).to_age(),
+        ]
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        Some(self.id.clone())
+    }
+}
+
+/// The shares that the user could watch. Each one is checked once, in the
+/// background, and stays hidden until it has been. The namespace scope is
+/// applied on top, the same as it is for every list.
+struct Shared {
+    client: kube::Client,
+    scope: policy::Scope,
+    // Whether each share can be watched, `None` while that is being checked.
+    allowed: Arc<Mutex<HashMap<String, Option<bool>>>>,
+}
+
+impl Shared {
+    fn new(client: kube::Client) -> Self {
+        Self {
+            client,
+            scope: policy::scope(),
+            allowed: Arc::default(),
+        }
+    }
+
+    fn check(&self, share: Arc<Share>) {
+        let client = self.client.clone();
+        let allowed = self.allowed.clone();
+
+        tokio::spawn(async move {
+            let result = share.can_watch(client).await.unwrap_or_else(|err| {
+                tracing::debug!("unable to check access to {}: {err}", share.id);
+
+                false
+            });
+
+            allowed
+                .lock()
+                .unwrap()
+                .insert(share.id.clone(), Some(result));
+        });
+    }
+}
+
+impl table::Items for Arc<Shared> {
+    type Item = Arc<Share>;
+
+    fn items(&self, _: Option<String>) -> Vec<Self::Item> {
+        let shares: Vec<_> = SHARES
+            .list()
+            .into_iter()
+            .filter(|share| self.scope.allows(&share.namespace))
+            .collect();
+
+        let mut allowed = self.allowed.lock().unwrap();
+
+        // Shares that have stopped don't need to be remembered.
+        allowed.retain(|id, _| shares.iter().any(|share| &share.id == id));
+
+        shares
+            .into_iter()
+            .filter(|share| {
+                if let Some(allowed) = allowed.get(&share.id) {
+                    return allowed.unwrap_or_default();
+                }
+
+                allowed.insert(share.id.clone(), None);
+                self.check(share.clone());
+
+                false
+            })
+            .collect()
+    }
+}
+
+/// Exec sessions that other users on this server are sharing. Picking one
+/// watches it, read-only, for as long as it is shared.
+pub struct Sessions {
+    client: kube::Client,
+    shared: Arc<Shared>,
+    table: table::Table<Arc<Shared>>,
+    error: Option<String>,
+}
+
+impl Sessions {
+    pub fn new(client: kube::Client) -> Self {
+        let shared = Arc::new(Shared::new(client.clone()));
+
+        Self {
+            client,
+            table: table::Table::builder()
+                .items(shared.clone())
+                .border(false)
+                .build(),
+            shared,
+            error: None,
+        }
+    }
+}
+
+impl Widget for Sessions {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Event::Finished(result) = event {
+            self.error = result.as_ref().err().map(ToString::to_string);
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        let Some(key) = event.key() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        if matches!(key, exit_keys!()) {
+            return Ok(Broadcast::Exited);
+        }
+
+        self.error = None;
+
+        if let Broadcast::Selected(idx) = self.table.dispatch(event, buffer, area)? {
+            let Some(share) = self.shared.items(None).get(idx).cloned() else {
+                return Ok(Broadcast::Consumed);
+            };
+
+            return Ok(Broadcast::Raw(Box::new(Viewer {
+                client: self.client.clone(),
+                share,
+            })));
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = centered_with(area, Constraint::Percentage(70), Constraint::Percentage(60));
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Shared Sessions on {}", ELECTION.identity()))
+            .title_bottom(
+                Line::from("enter: watch, esc: close")
+                    .style(Style::default().fg(tailwind::GRAY.c400)),
+            );
+        let inner = block.inner(area);
+
+        frame.render_widget(block, area);
+
+        if let Some(error) = self.error.as_ref() {
+            frame.render_widget(
+                Paragraph::new(error.as_str())
+                    .style(Style::default().fg(theme::unhealthy()))
+                    .wrap(Wrap { trim: false }),
+                inner,
+            );
+
+            return Ok(());
+        }
+
+        if self.shared.items(None).is_empty() {
+            frame.render_widget(
+                Paragraph::new(
+                    "nothing you can watch is being shared, press `s` from the scroll-back of a shell \
+                     to share it",
+                )
+                .wrap(Wrap { trim: false }),
+                inner,
+            );
+
+            return Ok(());
+        }
+
+        self.table.draw(frame, inner)
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
+
+/// Read-only copy of someone else's exec session. Input is ignored other than
+/// `q` or `ctrl-]` to stop watching.
+struct Viewer {
+    client: kube::Client,
+    share: Arc<Share>,
+}
+
+#[async_trait::async_trait]
+impl Raw for Viewer {
+    #[allow(clippy::blocks_in_conditions)]
+    #[tracing::instrument(skip_all, fields(activity = "share.watch", id = self.share.id))]
+    async fn start(
+        &mut self,
+        stdin: &mut UnboundedReceiver<Event>,
+        mut stdout: Pin<Box<dyn AsyncWrite + Send + Unpin>>,
+    ) -> Result<()> {
+        if !self.share.authorize(self.client.clone()).await? {
+            return Err(eyre!(
+                "watching {} requires being able to exec into {}/{}",
+                self.share.id,
+                self.share.namespace,
+                self.share.pod
+            ));
+        }
+
+        let (history, mut rx) = self
+            .share
+            .subscribe()
+            .ok_or_else(|| eyre!("{} is no longer being shared", self.share.id))?;
+
+        stdout.write_all(&history).await?;
+        stdout.flush().await?;
+
+        loop {
+            tokio::select! {
+                msg = stdin.recv() => {
+                    match msg {
+                        None | Some(Event::Shutdown) => return Ok(()),
+                        Some(msg) if matches!(
+                            msg.key(),
+                            Some(Keypress::GS | Keypress::Printable('q'))
+                        ) => return Ok(()),
+                        _ => {}
+                    }
+                }
+                data = rx.recv() => {
+                    match data {
+                        Ok(data) => {
+                            stdout.write_all(&data).await?;
+                            stdout.flush().await?;
+                        }
+                        // Falling behind only means missing some output, which is
+                        // better than holding up the session being watched.
+                        Err(RecvError::Lagged(_)) => {}
+                        Err(RecvError::Closed) => return Ok(()),
+                    }
+                }
+            }
+        }
+    }
+}
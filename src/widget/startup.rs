@@ -0,0 +1,98 @@
+use std::{str::FromStr, sync::OnceLock};
+
+use eyre::{eyre, Report, Result};
+use kube::runtime::watcher;
+use serde::{Deserialize, Serialize};
+
+// Like `crate::config`, this is set once from the command line and read by
+// every session.
+static DEFAULT: OnceLock<Startup> = OnceLock::new();
+
+/// The screen the dashboard opens into. This is written as `pods`,
+/// `deployments`, `capacity` or `last`, with an optional namespace for the
+/// lists, eg. `deployments/my-app`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Startup {
+    Pods(Option<String>),
+    Deployments(Option<String>),
+    Capacity,
+    /// Whatever kind or object was visited most recently.
+    Last,
+}
+
+impl Default for Startup {
+    fn default() -> Self {
+        Self::Pods(None)
+    }
+}
+
+impl Startup {
+    /// Where sessions start when the user hasn't picked somewhere themselves.
+    pub fn configured() -> Self {
+        DEFAULT.get().cloned().unwrap_or_default()
+    }
+
+    pub fn set_configured(startup: Self) {
+        DEFAULT.set(startup).ok();
+    }
+
+    /// Config for watching only what is in `namespace`, when there is one.
+    pub fn watch(namespace: Option<&str>) -> watcher::Config {
+        namespace.map_or_else(watcher::Config::default, |ns| {
+            watcher::Config::default().fields(&format!("metadata.namespace={ns}"))
+        })
+    }
+}
+
+impl std::fmt::Display for Startup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pods(None) => write!(f, "pods"),
+            Self::Pods(Some(ns)) => write!(f, "pods/{ns}"),
+            Self::Deployments(None) => write!(f, "deployments"),
+            Self::Deployments(Some(ns)) => write!(f, "deployments/{ns}"),
+            Self::Capacity => write!(f, "capacity"),
+            Self::Last => write!(f, "last"),
+        }
+    }
+}
+
+impl FromStr for Startup {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (screen, namespace) = match s.split_once('/') {
+            Some((screen, ns)) if !ns.is_empty() => (screen, Some(ns.to_string())),
+            Some((screen, _)) => (screen, None),
+            None => (s, None),
+        };
+
+        match (screen, namespace) {
+            ("pods", ns) => Ok(Self::Pods(ns)),
+            ("deployments", ns) => Ok(Self::Deployments(ns)),
+            ("capacity", None) => Ok(Self::Capacity),
+            ("last", None) => Ok(Self::Last),
+            ("capacity" | "last", Some(_)) => {
+                Err(eyre!("{screen} can't be limited to a namespace"))
+            }
+            _ => Err(eyre!(
+                "unknown screen {s}, expected one of pods, deployments, capacity or last"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Startup {
+    type Error = Report;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Startup> for String {
+    fn from(startup: Startup) -> Self {
+        startup.to_string()
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use k8s_openapi::api::apps::v1::StatefulSet;
+use kube::ResourceExt;
+use tokio::sync::oneshot;
+
+use super::{
+    object::{self, Object},
+    table,
+    tabs::Tab,
+    yaml::Yaml,
+    Widget, WIDGET_VIEWS,
+};
+use crate::resources::{
+    statefulset::{Ordinals, StatefulSetExt, StatefulSets},
+    store::Store,
+};
+
+pub type List = object::List<StatefulSet>;
+
+/// A statefulset with the pod at each of its ordinals. Rollouts replace pods
+/// one ordinal at a time from the highest down, so the "Ordinals" tab shows
+/// how far along one is and which pod it is stuck on.
+impl Object for StatefulSet {
+    type Items = Arc<StatefulSets>;
+
+    fn items(
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> (Self::Items, oneshot::Receiver<()>) {
+        // Like deployments, the pods are only needed for health and don't hold
+        // up showing the list.
+        let (statefulsets, is_ready) = Store::namespaced(client.clone(), namespace);
+        let (pods, _) = Store::new(client);
+
+        (Arc::new(StatefulSets { statefulsets, pods }), is_ready)
+    }
+
+    fn get(items: &Self::Items, idx: usize, filter: Option<String>) -> Option<Arc<Self>> {
+        items.statefulsets.get(idx, filter)
+    }
+
+    fn tabs(client: &kube::Client, items: &Self::Items, statefulset: &Arc<Self>) -> Vec<Tab> {
+        let ordinals = {
+            let items = items.clone();
+            let uid = statefulset.uid().unwrap_or_default();
+
+            Tab::builder()
+                .name("Ordinals".to_string())
+                .constructor(Box::new(move || {
+                    WIDGET_VIEWS.statefulset.ordinals.inc();
+
+                    table::Table::builder()
+                        .items(Ordinals {
+                            statefulsets: items.clone(),
+                            uid: uid.clone(),
+                        })
+                        .build()
+                        .boxed()
+                }))
+                .build()
+        };
+
+        let mut tabs = vec![
+            Yaml::tab("Overview".to_string(), client.clone(), statefulset.clone()),
+            ordinals,
+        ];
+
+        tabs.extend(object::pods(
+            client,
+            statefulset.namespace().unwrap_or_default(),
+            statefulset.spec.as_ref().map(|spec| spec.selector.clone()),
+        ));
+
+        tabs
+    }
+
+    fn status(_: &Self::Items, statefulset: &Self) -> Option<String> {
+        Some(statefulset.revision()).filter(|revision| !revision.is_empty())
+    }
+}
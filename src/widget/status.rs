@@ -0,0 +1,79 @@
+use eyre::Result;
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    style::{palette::tailwind, Style},
+    text::Line,
+    Frame,
+};
+
+use super::{Placement, Widget};
+use crate::{credentials::CREDENTIALS, throttle::THROTTLE, version::RELEASES};
+
+/// Single line banner that is only visible while something is keeping requests
+/// to the API server from succeeding. Without this, tables stop updating and
+/// it looks like everything is hung. When nothing is wrong, it is also used to
+/// let users know that a newer release of kty is available.
+pub struct Status {
+    info: Style,
+    warning: Style,
+    error: Style,
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self {
+            info: Style::default()
+                .fg(tailwind::GRAY.c100)
+                .bg(tailwind::INDIGO.c800),
+            warning: Style::default()
+                .fg(tailwind::GRAY.c900)
+                .bg(tailwind::YELLOW.c300),
+            error: Style::default()
+                .fg(tailwind::GRAY.c100)
+                .bg(tailwind::RED.c700),
+        }
+    }
+}
+
+impl Status {
+    // Credential failures come first, there's no point in waiting out a
+    // throttle when none of the requests are going to succeed anyways.
+    fn message(&self) -> Option<Line<'static>> {
+        if let Some(failure) = CREDENTIALS.failure() {
+            return Some(Line::from(failure).style(self.error));
+        }
+
+        if let Some(remaining) = THROTTLE.remaining() {
+            return Some(
+                Line::from(format!(
+                    "throttled by API server, retrying in {}s",
+                    remaining.as_secs().saturating_add(1)
+                ))
+                .style(self.warning),
+            );
+        }
+
+        RELEASES
+            .update()
+            .map(|_| Line::from(RELEASES.summary()).style(self.info))
+    }
+}
+
+impl Widget for Status {
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some(message) = self.message() else {
+            return Ok(());
+        };
+
+        frame.render_widget(message.alignment(Alignment::Center), area);
+
+        Ok(())
+    }
+
+    fn placement(&self) -> Placement {
+        Placement {
+            vertical: Constraint::Length(u16::from(self.message().is_some())),
+            ..Default::default()
+        }
+    }
+}
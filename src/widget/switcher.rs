@@ -0,0 +1,148 @@
+use eyre::Result;
+use kube::api::ApiResource;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Clear, Row},
+    Frame,
+};
+use tokio::sync::watch;
+
+use super::{
+    centered,
+    input::{self, Content},
+    propagate, table, Widget,
+};
+use crate::{
+    config,
+    events::{Broadcast, Event},
+    resources::Filter,
+};
+
+/// What can be picked, either a kind or one of the views from the config.
+#[derive(Clone)]
+pub enum Choice {
+    Kind(ApiResource),
+    View(&'static config::View, ApiResource),
+}
+
+impl Filter for Choice {
+    fn matches(&self, filter: &str) -> bool {
+        match self {
+            Choice::Kind(kind) => kind.matches(filter),
+            Choice::View(view, kind) => {
+                view.name.to_lowercase().contains(&filter.to_lowercase()) || kind.matches(filter)
+            }
+        }
+    }
+}
+
+impl table::Row for Choice {
+    fn columns() -> Vec<&'static str> {
+        ApiResource::columns()
+    }
+
+    fn cells(&self) -> Vec<String> {
+        match self {
+            Choice::Kind(kind) => kind.cells(),
+            Choice::View(view, kind) => vec![
+                view.name.clone(),
+                kind.group.clone(),
+                kind.version.clone(),
+                format!("{} (view)", kind.kind),
+            ],
+        }
+    }
+
+    fn row(&self, style: &table::RowStyle) -> Row {
+        Row::new(self.cells()).style(style.normal)
+    }
+
+    fn uid(&self) -> Option<String> {
+        match self {
+            Choice::Kind(kind) => kind.uid(),
+            Choice::View(view, _) => Some(format!("view/{}", view.name)),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Kinds(watch::Receiver<Vec<ApiResource>>);
+
+impl table::Items for Kinds {
+    type Item = Choice;
+
+    // Views go first, they're there because they're what people look at most.
+    // Any whose kind doesn't exist are left out.
+    fn items(&self, filter: Option<String>) -> Vec<Self::Item> {
+        let kinds = self.0.borrow();
+
+        config::get()
+            .views
+            .iter()
+            .filter_map(|view| Some(Choice::View(view, view.resource(&kinds)?)))
+            .chain(kinds.iter().cloned().map(Choice::Kind))
+            .filter(|choice| filter.as_ref().map_or(true, |f| choice.matches(f)))
+            .collect()
+    }
+}
+
+/// Popup to pick which kind of resource is being browsed. Typing filters the
+/// list, the cursor keys move the selection and enter picks it.
+pub struct Switcher {
+    kinds: Kinds,
+    filter: Content,
+
+    input: input::Text,
+    table: table::Table<Kinds>,
+}
+
+impl Switcher {
+    pub fn new(kinds: watch::Receiver<Vec<ApiResource>>) -> Self {
+        let kinds = Kinds(kinds);
+        let filter = Content::default();
+
+        Self {
+            input: input::Text::builder()
+                .title("Resources")
+                .content(filter.clone())
+                .build(),
+            table: table::Table::builder()
+                .items(kinds.clone())
+                .filter(filter.clone())
+                .build(),
+            kinds,
+            filter,
+        }
+    }
+
+    pub fn get(&self, idx: usize) -> Option<Choice> {
+        table::Items::items(&self.kinds, self.filter.borrow().clone())
+            .get(idx)
+            .cloned()
+    }
+}
+
+impl Widget for Switcher {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        propagate!(self.input.dispatch(event, buffer, area));
+
+        self.table.dispatch(event, buffer, area)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = centered(area);
+
+        frame.render_widget(Clear, area);
+
+        let [input, list] =
+            Layout::vertical([self.input.placement().vertical, Constraint::Fill(0)]).areas(area);
+
+        self.table.draw(frame, list)?;
+        self.input.draw(frame, input)
+    }
+
+    fn zindex(&self) -> u16 {
+        1
+    }
+}
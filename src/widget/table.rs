@@ -1,6 +1,18 @@
-use std::{cell::RefCell, rc::Rc};
+pub mod cache;
+pub mod density;
+pub mod state;
+
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+    hash::{DefaultHasher, Hash, Hasher},
+    rc::Rc,
+    sync::Arc,
+};
 
 use eyre::Result;
+use itertools::Itertools;
+use kube::api::ObjectMeta;
 use lazy_static::lazy_static;
 use prometheus::{register_int_counter, IntCounter};
 use ratatui::{
@@ -8,19 +20,25 @@ use ratatui::{
     layout::{Constraint, Rect},
     style,
     style::{palette::tailwind, Modifier, Stylize},
-    widgets::{self, Block, Borders, TableState},
+    text::Line,
+    widgets::{self, Block, Borders, Paragraph, TableState, Wrap},
     Frame,
 };
 use tachyonfx::{fx, EffectTimer, Interpolation};
 
+use self::density::Density;
 use super::{
     error::Error,
     input::Text,
+    metadata::Metadata,
     nav::{move_cursor, Movement},
+    scroll::Scroll,
+    theme,
     view::View,
-    BoxWidget, Widget,
+    Action, BoxWidget, Widget,
 };
 use crate::{
+    config,
     events::{Broadcast, Event, Keypress},
     fx::Animated,
 };
@@ -34,13 +52,62 @@ lazy_static! {
 }
 
 pub trait Row {
-    fn constraints() -> Vec<Constraint>;
+    fn constraints() -> Vec<Constraint> {
+        Self::columns()
+            .iter()
+            .map(|_| Constraint::Fill(1))
+            .collect()
+    }
 
     fn header<'a>() -> Option<widgets::Row<'a>> {
-        None
+        let columns = Self::columns();
+
+        (!columns.is_empty()).then(|| widgets::Row::new(columns))
     }
 
     fn row(&self, style: &RowStyle) -> widgets::Row;
+
+    /// Names of the columns. Rows that provide these, along with `cells`, have
+    /// their columns sized to fit the content instead of using `constraints`.
+    fn columns() -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Text of each cell, in the same order as `columns`.
+    fn cells(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Stable identifier for the item, used to keep the selection on the same
+    /// item when the table is re-sorted or has rows added and removed.
+    /// Without one, the selection stays at the same index.
+    fn uid(&self) -> Option<String> {
+        None
+    }
+
+    /// Metadata of the object behind the row, for rows that are one. This is
+    /// what the labels and annotations quick view (`a`) shows.
+    fn meta(&self) -> Option<&ObjectMeta> {
+        None
+    }
+}
+
+/// A row of `row`'s cells where only those under `columns` get `style`, for
+/// when coloring the whole row would make the one thing that matters, such as
+/// health, harder to pick out. Columns are found by name in `Row::columns`.
+pub fn highlight<R: Row>(row: &R, columns: &[&str], style: style::Style) -> widgets::Row<'static> {
+    let highlighted: Vec<_> = R::columns()
+        .iter()
+        .positions(|column| columns.contains(column))
+        .collect();
+
+    widgets::Row::new(row.cells().into_iter().enumerate().map(|(i, cell)| {
+        if highlighted.contains(&i) {
+            widgets::Cell::from(cell).style(style)
+        } else {
+            widgets::Cell::from(cell)
+        }
+    }))
 }
 
 pub struct RowStyle {
@@ -52,8 +119,8 @@ pub struct RowStyle {
 impl Default for RowStyle {
     fn default() -> Self {
         Self {
-            healthy: style::Style::default().fg(tailwind::GREEN.c300),
-            unhealthy: style::Style::default().fg(tailwind::RED.c300),
+            healthy: style::Style::default().fg(theme::healthy()),
+            unhealthy: style::Style::default().fg(theme::unhealthy()),
             normal: style::Style::default().fg(tailwind::INDIGO.c300),
         }
     }
@@ -64,6 +131,7 @@ pub struct Style {
     pub header: style::Style,
     pub selected: style::Style,
     pub row: RowStyle,
+    pub density: Density,
 }
 
 impl Default for Style {
@@ -75,8 +143,58 @@ impl Default for Style {
                 .add_modifier(Modifier::REVERSED)
                 .bg(tailwind::GRAY.c700),
             row: RowStyle::default(),
+            density: density::current(),
+        }
+    }
+}
+
+/// Size columns to fit their content. When there isn't room for everything,
+/// the widest columns are shrunk first, but never below their configured
+/// minimum, so that one long value doesn't push everything else off the
+/// screen.
+fn fit(columns: &[&str], wanted: &[u16], available: u16) -> Vec<Constraint> {
+    let cfg = &config::get().tables;
+
+    let floors: Vec<_> = columns
+        .iter()
+        .zip(wanted)
+        .map(|(name, wanted)| cfg.minimum(name).min(*wanted))
+        .collect();
+
+    let width = |cap: u16| -> Vec<u16> {
+        wanted
+            .iter()
+            .zip(&floors)
+            .map(|(wanted, floor)| (*wanted).min(cap).max(*floor))
+            .collect()
+    };
+    let total = |cap: u16| -> u32 { width(cap).iter().map(|w| u32::from(*w)).sum() };
+
+    let max = wanted.iter().copied().max().unwrap_or_default();
+
+    // Find the widest that columns can be while still fitting. When even the
+    // minimums don't fit, they are used as is and the table truncates.
+    let (mut low, mut high) = (0, max);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+
+        if total(mid) <= u32::from(available) {
+            low = mid;
+        } else {
+            high = mid - 1;
         }
     }
+
+    width(low).into_iter().map(Constraint::Length).collect()
+}
+
+// Numbers are compared as numbers so that `10` comes after `9`, everything
+// else as text.
+fn compare(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
 }
 
 pub trait Items
@@ -86,6 +204,46 @@ where
     type Item;
 
     fn items(&self, filter: Option<String>) -> Vec<Self::Item>;
+
+    /// `items`, for the table to draw from. Anything that already keeps its
+    /// items around, such as a store's snapshot, hands those out instead of
+    /// building them again every frame.
+    fn shared(&self, filter: Option<String>) -> Arc<Vec<Self::Item>> {
+        Arc::new(self.items(filter))
+    }
+
+    /// Names of the columns. These are usually the same for every table of
+    /// `Item`, views from the config are the exception.
+    fn columns(&self) -> Vec<&'static str> {
+        Self::Item::columns()
+    }
+
+    /// Changes whenever `items` would return something different, such as the
+    /// generation of the store they come from. Tables with a revision skip
+    /// drawing frames that would look the same as the last one.
+    fn revision(&self) -> Option<u64> {
+        None
+    }
+
+    /// Why there might be nothing to show, see `StoreBackend::error`. Tables
+    /// without any items show this instead of looking empty.
+    fn error(&self) -> Option<String> {
+        None
+    }
+
+    fn retry(&self) {}
+}
+
+/// A single revision for items that come from more than one place, such as
+/// pods and the nodes they're on.
+pub fn revision(parts: impl IntoIterator<Item = u64>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    for part in parts {
+        part.hash(&mut hasher);
+    }
+
+    hasher.finish()
 }
 
 pub struct Table<S>
@@ -101,7 +259,21 @@ where
     // Internal state
     items: S,
     view: TableState,
+    selected: Option<String>,
     filter: Rc<RefCell<Option<String>>>,
+    // Where the sort and filter are remembered, see `state`. Only tables with
+    // one of these can be sorted.
+    remember: Option<String>,
+    sort: Option<state::Sort>,
+    // Position in `items` of each row, in the order they're shown.
+    order: Vec<usize>,
+    // How many items are shown out of how many there are, as of the last draw.
+    matches: Rc<Cell<(usize, usize)>>,
+    // The labels and annotations of the selected row, while they're being
+    // looked at.
+    metadata: Option<BoxWidget>,
+    // The last frame, for items that have a revision.
+    rendered: Option<cache::Rendered>,
 
     _phantom: std::marker::PhantomData<S>,
 }
@@ -120,6 +292,10 @@ where
         items: S,
         #[builder(default)] filter: Rc<RefCell<Option<String>>>,
         #[builder(default = true)] border: bool,
+        remember: Option<String>,
+        /// Used until the table has been sorted some other way and that has
+        /// been remembered.
+        sort: Option<state::Sort>,
     ) -> Self {
         let view = if selected {
             TableState::default().with_selected(0)
@@ -127,13 +303,26 @@ where
             TableState::default()
         };
 
+        let saved = remember.as_deref().map(state::get).unwrap_or_default();
+
+        if saved.filter.is_some() {
+            filter.replace(saved.filter);
+        }
+
         Self {
             style,
             title,
             highlight,
             items,
             view,
+            selected: None,
             filter,
+            remember,
+            sort: saved.sort.or(sort),
+            order: Vec::new(),
+            matches: Rc::default(),
+            metadata: None,
+            rendered: None,
             border,
             _phantom: std::marker::PhantomData,
         }
@@ -142,17 +331,169 @@ where
     pub fn filter(&self) -> Rc<RefCell<Option<String>>> {
         self.filter.clone()
     }
+
+    pub fn matches(&self) -> Rc<Cell<(usize, usize)>> {
+        self.matches.clone()
+    }
+
+    /// Index of the selected item, in the items' own order like the one sent
+    /// with `Broadcast::Selected`.
+    pub fn selected(&self) -> Option<usize> {
+        let idx = self.view.selected()?;
+
+        Some(self.order.get(idx).copied().unwrap_or(idx))
+    }
+
+    // Move the sort to the next column, going back to the items' own order
+    // after the last one.
+    fn next_sort(&mut self) {
+        let columns = self.items.columns();
+
+        let next = match &self.sort {
+            None => 0,
+            Some(sort) => columns
+                .iter()
+                .position(|name| *name == sort.column)
+                .map_or(0, |idx| idx + 1),
+        };
+
+        self.sort = columns.get(next).map(|column| state::Sort {
+            column: (*column).to_string(),
+            descending: false,
+        });
+    }
+
+    fn sorted<'a>(&mut self, items: &'a [S::Item]) -> Vec<&'a S::Item> {
+        let column = self.sort.as_ref().and_then(|sort| {
+            self.items
+                .columns()
+                .iter()
+                .position(|name| *name == sort.column)
+        });
+
+        let Some(column) = column else {
+            self.order = (0..items.len()).collect();
+
+            return items.iter().collect();
+        };
+
+        let descending = self.sort.as_ref().is_some_and(|sort| sort.descending);
+
+        let mut keyed: Vec<_> = items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| (idx, item.cells().swap_remove(column), item))
+            .collect();
+
+        keyed.sort_by(|(_, a, _), (_, b, _)| {
+            let ordering = compare(a, b);
+
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        let (order, items) = keyed.into_iter().map(|(idx, _, item)| (idx, item)).unzip();
+        self.order = order;
+
+        items
+    }
+
+    fn header(&self) -> Option<widgets::Row<'static>> {
+        let Some(sort) = self.sort.as_ref() else {
+            let columns = self.items.columns();
+
+            // Rows can have a header of their own, which only applies when the
+            // columns haven't been swapped out.
+            if columns == S::Item::columns() {
+                return S::Item::header();
+            }
+
+            return Some(widgets::Row::new(columns));
+        };
+
+        Some(widgets::Row::new(self.items.columns().into_iter().map(
+            |name| {
+                if name != sort.column {
+                    return name.to_string();
+                }
+
+                format!("{name} {}", if sort.descending { "▼" } else { "▲" })
+            },
+        )))
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn constraints(&self, items: &[&S::Item], area: Rect) -> Vec<Constraint> {
+        let columns = self.items.columns();
+
+        if columns.is_empty() {
+            return S::Item::constraints();
+        }
+
+        let mut wanted: Vec<_> = columns
+            .iter()
+            .map(|name| Line::from(*name).width() as u16)
+            .collect();
+
+        for item in items {
+            for (wanted, cell) in wanted.iter_mut().zip(item.cells()) {
+                *wanted = (*wanted).max(Line::from(cell).width() as u16);
+            }
+        }
+
+        let available = area
+            .width
+            .saturating_sub(if self.border { 2 } else { 0 })
+            .saturating_sub(
+                columns.len().saturating_sub(1) as u16 * self.style.density.column_spacing(),
+            );
+
+        fit(&columns, &wanted, available)
+    }
+
+    // Follow the selected item to wherever it is now. If it has gone away, the
+    // selection stays at the same index (clamped to the end of the table) which
+    // ends up being the item that took its place.
+    fn track_selection(&mut self, items: &[&S::Item]) {
+        let Some(current) = self.view.selected() else {
+            return;
+        };
+
+        let idx = self
+            .selected
+            .as_ref()
+            .and_then(|uid| {
+                items
+                    .iter()
+                    .position(|item| item.uid().as_ref() == Some(uid))
+            })
+            .unwrap_or_else(|| current.min(items.len().saturating_sub(1)));
+
+        self.view.select(Some(idx));
+        self.selected = items.get(idx).and_then(|item| item.uid());
+    }
 }
 
 impl<S> Widget for Table<S>
 where
     S: Items,
 {
-    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
         let Some(key) = event.key() else {
             return Ok(Broadcast::Ignored);
         };
 
+        if let Some(metadata) = self.metadata.as_mut() {
+            if let Broadcast::Exited = metadata.dispatch(event, buffer, area)? {
+                self.metadata = None;
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
         if let Some(Movement::Y(y)) = move_cursor(key, area) {
             self.view.select(Some(
                 self.view
@@ -160,28 +501,140 @@ where
                     .unwrap_or_default()
                     .saturating_add_signed(y.into()),
             ));
+            self.selected = None;
 
             return Ok(Broadcast::Consumed);
         }
 
         if matches!(key, Keypress::Enter) {
+            // Whoever gets this looks it up in the items' own order, which isn't
+            // the one on screen when sorted.
+            let idx = self.view.selected().unwrap_or_default();
+
             return Ok(Broadcast::Selected(
-                self.view.selected().unwrap_or_default(),
+                self.order.get(idx).copied().unwrap_or(idx),
             ));
         }
 
+        if matches!(key, Keypress::Printable('r')) && self.items.error().is_some() {
+            self.items.retry();
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        if matches!(key, Keypress::Printable('a')) {
+            let filter = self.filter.borrow().clone();
+            let items = self.items.shared(filter);
+            let items = self.sorted(&items);
+
+            if let Some(meta) = self
+                .view
+                .selected()
+                .and_then(|idx| items.get(idx).copied())
+                .and_then(|item| item.meta())
+            {
+                self.metadata = Some(Metadata::new(meta).boxed());
+
+                return Ok(Broadcast::Consumed);
+            }
+        }
+
+        if self.remember.is_some() && !self.items.columns().is_empty() {
+            match key {
+                Keypress::Printable('s') => self.next_sort(),
+                Keypress::Printable('S') => {
+                    if let Some(sort) = self.sort.as_mut() {
+                        sort.descending = !sort.descending;
+                    }
+                }
+                _ => return Ok(Broadcast::Ignored),
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
         Ok(Broadcast::Ignored)
     }
 
+    fn actions(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        let has_meta = self.view.selected().is_some_and(|idx| {
+            let idx = self.order.get(idx).copied().unwrap_or(idx);
+
+            self.items
+                .shared(self.filter.borrow().clone())
+                .get(idx)
+                .and_then(Row::meta)
+                .is_some()
+        });
+
+        if has_meta {
+            actions.push(Action::new('a', "Show labels and annotations"));
+        }
+
+        if self.items.error().is_some() {
+            actions.push(Action::new('r', "Retry loading"));
+        }
+
+        if self.remember.is_some() && !self.items.columns().is_empty() {
+            actions.push(Action::new('s', "Sort by the next column"));
+            actions.push(Action::new('S', "Reverse the sort order"));
+        }
+
+        actions
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        let items = self.items.items(self.filter.borrow().clone());
+        let filter = self.filter.borrow().clone();
+
+        let key = self.items.revision().map(|revision| {
+            cache::Key::new(
+                revision,
+                filter.clone(),
+                self.sort.clone(),
+                self.view.selected(),
+                self.view.offset(),
+                area,
+            )
+        });
+
+        if let (Some(key), Some(rendered)) = (key.as_ref(), self.rendered.as_ref()) {
+            if rendered.restore(key, frame.buffer_mut()) {
+                if let Some(metadata) = self.metadata.as_mut() {
+                    metadata.draw(frame, area)?;
+                }
+
+                return Ok(());
+            }
+        }
+
+        let snapshot = self.items.shared(filter.clone());
+        let items = self.sorted(&snapshot);
+
+        // Getting everything a second time isn't free, so it only happens while
+        // there's something to compare against.
+        let total = match filter {
+            Some(_) => self.items.shared(None).len(),
+            None => items.len(),
+        };
+        self.matches.set((items.len(), total));
+
+        self.track_selection(&items);
+
+        // The theme and density can change at any time, the table follows them.
+        self.style.row = RowStyle::default();
+        self.style.density = density::current();
+
+        let density = self.style.density;
 
         let rows = items
             .iter()
-            .map(|item| item.row(&self.style.row))
+            .map(|item| item.row(&self.style.row).height(density.height()))
             .collect::<Vec<_>>();
 
-        let mut table = widgets::Table::new(rows, S::Item::constraints());
+        let mut table = widgets::Table::new(rows, self.constraints(&items, area))
+            .column_spacing(density.column_spacing());
         let mut border = Block::default()
             .borders(Borders::ALL)
             .style(self.style.border);
@@ -190,8 +643,13 @@ where
             table = table.highlight_style(self.style.selected);
         }
 
-        if let Some(header) = S::Item::header() {
-            table = table.header(header).style(self.style.header);
+        let mut header = 0;
+
+        if let Some(row) = self.header().filter(|_| density.header()) {
+            header = 1 + density.header_margin();
+            table = table
+                .header(row.bottom_margin(density.header_margin()))
+                .style(self.style.header);
         };
 
         if let Some(title) = self.title.as_ref() {
@@ -204,15 +662,85 @@ where
 
         frame.render_stateful_widget(table, area, &mut self.view);
 
+        if items.is_empty() {
+            if let Some(error) = self.items.error() {
+                let border = u16::from(self.border);
+
+                frame.render_widget(
+                    Paragraph::new(vec![
+                        Line::from(error).style(style::Style::default().fg(theme::unhealthy())),
+                        Line::default(),
+                        Line::from("retrying in the background, r to retry now")
+                            .style(style::Style::default().fg(tailwind::GRAY.c400)),
+                    ])
+                    .wrap(Wrap { trim: false }),
+                    Rect {
+                        x: area.x.saturating_add(border),
+                        y: area.y.saturating_add(border + header),
+                        width: area.width.saturating_sub(2 * border),
+                        height: area.height.saturating_sub(2 * border + header),
+                    },
+                );
+            }
+        }
+
+        // The offset is only known once the table has been rendered, it moves
+        // to keep the selection on screen.
+        if self.border {
+            let scroll = Scroll {
+                offset: self.view.offset(),
+                height: (area.height.saturating_sub(2 + header) / density.height()) as usize,
+                total: items.len(),
+            };
+
+            if let Some(line) = scroll.title(None) {
+                frame.render_widget(
+                    line,
+                    Rect {
+                        x: area.x.saturating_add(1),
+                        width: area.width.saturating_sub(2),
+                        height: 1.min(area.height),
+                        ..area
+                    },
+                );
+            }
+        }
+
+        self.rendered = key.map(|key| cache::Rendered::capture(key, frame.buffer_mut()));
+
+        if let Some(metadata) = self.metadata.as_mut() {
+            metadata.draw(frame, area)?;
+        }
+
         Ok(())
     }
 }
 
+impl<S> Drop for Table<S>
+where
+    S: Items,
+{
+    fn drop(&mut self) {
+        let Some(key) = self.remember.as_ref() else {
+            return;
+        };
+
+        state::set(
+            key,
+            state::State {
+                sort: self.sort.take(),
+                filter: self.filter.borrow().clone().filter(|f| !f.is_empty()),
+            },
+        );
+    }
+}
+
 pub type DetailFn = Box<dyn Fn(usize, Option<String>) -> Result<BoxWidget>>;
 
 pub struct Filtered {
     constructor: DetailFn,
     filter: Rc<RefCell<Option<String>>>,
+    matches: Rc<Cell<(usize, usize)>>,
     view: View,
 }
 
@@ -226,6 +754,7 @@ impl Filtered {
         Self {
             constructor,
             filter: table.filter(),
+            matches: table.matches(),
             view: View::builder().widgets(vec![table.boxed()]).build(),
         }
     }
@@ -258,10 +787,20 @@ impl Widget for Filtered {
         if let Some(Keypress::Printable('/')) = event.key() {
             TABLE_FILTER.inc();
 
+            let matches = self.matches.clone();
+            let filter = self.filter.clone();
+
             self.view.push(
                 Text::builder()
                     .title("Filter")
                     .content(self.filter.clone())
+                    .feedback(Box::new(move || {
+                        filter.try_borrow().ok()?.as_ref()?;
+
+                        let (shown, total) = matches.get();
+
+                        Some((format!("{shown}/{total} matches"), shown > 0))
+                    }))
                     .build()
                     .boxed(),
             );
@@ -284,6 +823,14 @@ impl Widget for Filtered {
         }
     }
 
+    // The filter is always a key away, even from the detail of a row.
+    fn actions(&self) -> Vec<Action> {
+        let mut actions = vec![Action::new('/', "Filter the table")];
+        actions.extend(self.view.actions());
+
+        actions
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         self.view.draw(frame, area)
     }
@@ -0,0 +1,106 @@
+use chrono::Utc;
+use lazy_static::lazy_static;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use super::{
+    density::{self, Density},
+    state,
+};
+use crate::widget::theme::{self, Theme};
+
+lazy_static! {
+    static ref TABLE_DRAWS: IntCounterVec = register_int_counter_vec!(
+        "table_draws_total",
+        "Number of times a table has been drawn, by whether the previous frame could be reused",
+        &["cached"]
+    )
+    .unwrap();
+}
+
+/// Everything that decides what a table looks like. When none of it has
+/// changed since the last draw, neither has the table.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Key {
+    pub revision: u64,
+    pub filter: Option<String>,
+    pub sort: Option<state::Sort>,
+    pub selected: Option<usize>,
+    pub offset: usize,
+    pub area: Rect,
+    theme: Theme,
+    density: Density,
+    // Ages and durations in the cells move on with the clock, a second is as
+    // fine grained as any of them get.
+    second: i64,
+}
+
+impl Key {
+    pub fn new(
+        revision: u64,
+        filter: Option<String>,
+        sort: Option<state::Sort>,
+        selected: Option<usize>,
+        offset: usize,
+        area: Rect,
+    ) -> Self {
+        Self {
+            revision,
+            filter,
+            sort,
+            selected,
+            offset,
+            area,
+            theme: theme::current(),
+            density: density::current(),
+            second: Utc::now().timestamp(),
+        }
+    }
+}
+
+/// The cells of the last frame a table drew, so that the render loop ticking
+/// while nothing is happening doesn't mean building and sizing every row
+/// again.
+pub struct Rendered {
+    key: Key,
+    cells: Buffer,
+}
+
+impl Rendered {
+    pub fn capture(key: Key, buffer: &Buffer) -> Self {
+        let area = key.area.intersection(buffer.area);
+        let mut cells = Buffer::empty(area);
+
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                cells[(x, y)] = buffer[(x, y)].clone();
+            }
+        }
+
+        Self { key, cells }
+    }
+
+    /// Put the last frame back into `buffer`, if it is still what `key` would
+    /// draw.
+    pub fn restore(&self, key: &Key, buffer: &mut Buffer) -> bool {
+        let hit = self.key == *key && self.cells.area.intersection(buffer.area) == self.cells.area;
+
+        TABLE_DRAWS
+            .with_label_values(&[if hit { "true" } else { "false" }])
+            .inc();
+
+        if !hit {
+            return false;
+        }
+
+        let area = self.cells.area;
+
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buffer[(x, y)] = self.cells[(x, y)].clone();
+            }
+        }
+
+        true
+    }
+}
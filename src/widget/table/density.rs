@@ -0,0 +1,76 @@
+use std::cell::Cell;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+thread_local! {
+    // Per session, the same way as `theme::CURRENT`. Until someone picks one,
+    // tables use whatever the config file says.
+    static CURRENT: Cell<Option<Density>> = const { Cell::new(None) };
+}
+
+/// How tightly rows are packed into tables. Small terminals fit the most rows
+/// with `Compact`, which also drops the header, and big monitors get some room
+/// between rows and columns with `Comfortable`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    Compact,
+    #[default]
+    Normal,
+    Comfortable,
+}
+
+impl Density {
+    pub fn next(self) -> Self {
+        match self {
+            Density::Compact => Density::Normal,
+            Density::Normal => Density::Comfortable,
+            Density::Comfortable => Density::Compact,
+        }
+    }
+
+    pub fn header(self) -> bool {
+        !matches!(self, Density::Compact)
+    }
+
+    /// Lines taken up by each row, the content only ever uses the first one.
+    pub fn height(self) -> u16 {
+        match self {
+            Density::Compact | Density::Normal => 1,
+            Density::Comfortable => 2,
+        }
+    }
+
+    /// Blank lines between the header and the first row.
+    pub fn header_margin(self) -> u16 {
+        u16::from(matches!(self, Density::Comfortable))
+    }
+
+    pub fn column_spacing(self) -> u16 {
+        match self {
+            Density::Compact | Density::Normal => 1,
+            Density::Comfortable => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for Density {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Density::Compact => write!(f, "compact"),
+            Density::Normal => write!(f, "normal"),
+            Density::Comfortable => write!(f, "comfortable"),
+        }
+    }
+}
+
+pub fn current() -> Density {
+    CURRENT.get().unwrap_or(config::get().tables.density)
+}
+
+pub fn set(density: Density) {
+    CURRENT.set(Some(density));
+}
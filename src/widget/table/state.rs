@@ -0,0 +1,56 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+thread_local! {
+    // Per session for the same reason as the theme, every dashboard has its own
+    // thread.
+    static STATES: RefCell<BTreeMap<String, State>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Column the table is sorted by. This is the column's header, not its
+/// position, so that it survives columns being added or moved around.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Sort {
+    pub column: String,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// How a table was last left, so that it comes back the same way.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct State {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<Sort>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+pub fn get(key: &str) -> State {
+    STATES.with_borrow(|states| states.get(key).cloned().unwrap_or_default())
+}
+
+pub fn set(key: &str, state: State) {
+    STATES.with_borrow_mut(|states| {
+        if state == State::default() {
+            states.remove(key);
+        } else {
+            states.insert(key.to_string(), state);
+        }
+    });
+}
+
+pub fn all() -> BTreeMap<String, State> {
+    STATES.with_borrow(Clone::clone)
+}
+
+/// Bring back tables from a previous session. Anything that has already been
+/// changed in this one wins.
+pub fn restore(saved: BTreeMap<String, State>) {
+    STATES.with_borrow_mut(|states| {
+        for (key, state) in saved {
+            states.entry(key).or_insert(state);
+        }
+    });
+}
@@ -10,7 +10,7 @@ use ratatui::{
 };
 use tachyonfx::{fx, EffectTimer, Interpolation};
 
-use super::{error::Error, view::View, Placement, Widget};
+use super::{error::Error, view::View, Action, Placement, Widget};
 use crate::{
     events::{Broadcast, Event},
     fx::{horizontal_wipe, Animated, Start},
@@ -145,9 +145,10 @@ impl Widget for TabbedView {
 
                 self.current = idx;
 
-                // TODO: this is *probably* a valid assumption, but it might need to be actually
-                // checked.
-                self.view.pop();
+                // Everything past the bar and divider belongs to the old tab, including
+                // any errors it left behind. Dropping all of it is what stops the tab's
+                // streams and tasks.
+                self.view.truncate(2);
                 self.view.push(
                     Animated::builder()
                         .widget(self.items[idx].widget())
@@ -169,6 +170,10 @@ impl Widget for TabbedView {
         }
     }
 
+    fn actions(&self) -> Vec<Action> {
+        self.view.actions()
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         if let Err(err) = self.view.draw(frame, area) {
             self.view.push(Error::from(err).boxed());
@@ -0,0 +1,87 @@
+use std::cell::Cell;
+
+use ratatui::style::{palette::tailwind, Color};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+thread_local! {
+    // Every dashboard renders on its own thread (see `Dashboard::start`), which
+    // makes this per session without having to pass it to every widget.
+    static CURRENT: Cell<Theme> = const { Cell::new(Theme::Standard) };
+}
+
+/// Colors used to tell good from bad. Red and green alone can't be told apart
+/// by a good number of people, so there's a theme that sticks to blue and
+/// orange instead. Status text also always comes with an `Indicator`, color is
+/// never the only thing that carries it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Standard,
+    Colorblind,
+}
+
+impl Theme {
+    pub fn next(self) -> Self {
+        match self {
+            Theme::Standard => Theme::Colorblind,
+            Theme::Colorblind => Theme::Standard,
+        }
+    }
+}
+
+impl std::fmt::Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Theme::Standard => write!(f, "standard"),
+            Theme::Colorblind => write!(f, "colorblind"),
+        }
+    }
+}
+
+pub fn current() -> Theme {
+    CURRENT.get()
+}
+
+pub fn set(theme: Theme) {
+    CURRENT.set(theme);
+}
+
+pub fn healthy() -> Color {
+    match current() {
+        Theme::Standard => tailwind::GREEN.c300,
+        Theme::Colorblind => tailwind::SKY.c300,
+    }
+}
+
+pub fn unhealthy() -> Color {
+    match current() {
+        Theme::Standard => tailwind::RED.c300,
+        Theme::Colorblind => tailwind::ORANGE.c400,
+    }
+}
+
+/// Shown next to status text so that it can be read without relying on color.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Indicator {
+    Healthy,
+    Unhealthy,
+    Progressing,
+    Warning,
+}
+
+impl Indicator {
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Indicator::Healthy => "✓",
+            Indicator::Unhealthy => "✗",
+            Indicator::Progressing => "◐",
+            Indicator::Warning => "!",
+        }
+    }
+
+    pub fn label(self, text: impl std::fmt::Display) -> String {
+        format!("{} {text}", self.glyph())
+    }
+}
@@ -7,7 +7,7 @@ use ratatui::{
     Frame,
 };
 
-use super::{propagate, BoxWidget, Widget};
+use super::{propagate, Action, BoxWidget, Widget};
 use crate::events::{Broadcast, Event};
 
 #[derive(Builder)]
@@ -28,6 +28,15 @@ impl View {
         self.widgets.pop()
     }
 
+    /// Drop everything past the first `len` widgets.
+    pub fn truncate(&mut self, len: usize) {
+        self.widgets.truncate(len);
+    }
+
+    pub fn replace(&mut self, idx: usize, widget: BoxWidget) -> BoxWidget {
+        std::mem::replace(&mut self.widgets[idx], widget)
+    }
+
     fn layers<'a>(
         widgets: impl Iterator<Item = &'a mut BoxWidget>,
         area: Rect,
@@ -64,6 +73,17 @@ impl Widget for View {
         Ok(Broadcast::Ignored)
     }
 
+    // Everything gets a chance at input, but whatever is on top is what's being
+    // looked at. Its keys are the ones worth listing.
+    fn actions(&self) -> Vec<Action> {
+        self.widgets
+            .iter()
+            .rev()
+            .map(|widget| widget.actions())
+            .find(|actions| !actions.is_empty())
+            .unwrap_or_default()
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         let show_all = self.show_all;
 
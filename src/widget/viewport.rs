@@ -9,13 +9,14 @@ use ratatui::{
     Frame,
 };
 
-use super::Widget;
+use super::{log::highlight::Highlighter, Widget};
 
 #[derive(Builder)]
 pub struct Viewport<'a> {
     buffer: &'a Vec<String>,
     #[builder(default)]
     view: Position,
+    highlight: Option<&'a Highlighter>,
 }
 
 impl<'a> Widget for Viewport<'a> {
@@ -34,7 +35,10 @@ impl<'a> Widget for Viewport<'a> {
 
         let txt = self.buffer[start..end]
             .iter()
-            .map(|l| l.as_str().into_text())
+            .map(|l| match self.highlight {
+                Some(highlighter) => highlighter.highlight(l),
+                None => Ok(l.as_str().into_text()?),
+            })
             .fold_ok(Text::default(), |txt, l| txt + l)?;
 
         frame.render_widget(Paragraph::new(txt), area);
@@ -1,5 +1,9 @@
+pub mod path;
+pub mod syntax;
+
 use std::{
     borrow::Borrow,
+    ops::Range,
     sync::{Arc, LazyLock},
 };
 
@@ -7,8 +11,9 @@ use eyre::Result;
 use kube::Resource;
 use ratatui::{
     buffer::Buffer,
-    layout::{Position, Rect},
-    text::Line,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
     widgets::Paragraph,
     Frame,
 };
@@ -16,18 +21,27 @@ use serde::Serialize;
 use syntect::{
     easy::HighlightLines,
     highlighting::{Theme, ThemeSet},
-    parsing::SyntaxSet,
+    parsing::SyntaxReference,
     util::LinesWithEndings,
 };
 use syntect_tui::into_span;
 
 use super::{
+    copy,
+    edit::QuickEdit,
+    input::{Content, Text},
     nav::{move_cursor, Movement},
-    Widget, WIDGET_VIEWS_VEC,
+    propagate,
+    scroll::Scroll,
+    theme::{self, Indicator},
+    Action, BoxWidget, Widget, WIDGET_VIEWS_VEC,
 };
 use crate::{
-    events::{Broadcast, Event},
-    resources::Yaml as YamlResource,
+    events::{Broadcast, Event, Keypress},
+    resources::{
+        deprecation::{self, Finding},
+        encoded, Yaml as YamlResource,
+    },
     widget::tabs::Tab,
 };
 
@@ -44,16 +58,13 @@ static THEME: LazyLock<Theme> = LazyLock::new(|| {
     theme
 });
 
-fn to_lines(txt: &str) -> Vec<Line> {
-    let ps = SyntaxSet::load_defaults_newlines();
-    let syntax = ps.find_syntax_by_extension("yaml").unwrap();
-
+pub fn highlight<'a>(txt: &'a str, syntax: &SyntaxReference) -> Vec<Line<'a>> {
     let mut highlighter = HighlightLines::new(syntax, &THEME);
 
     LinesWithEndings::from(txt)
         .map(|line| {
             highlighter
-                .highlight_line(line, &ps)
+                .highlight_line(line, syntax::set())
                 .unwrap()
                 .into_iter()
                 .filter_map(|segment| into_span(segment).ok())
@@ -62,12 +73,136 @@ fn to_lines(txt: &str) -> Vec<Line> {
         .collect()
 }
 
+/// Highlight the YAML, along with any literal blocks in it as whatever they
+/// contain. That way a `nginx.conf` or `settings.json` in a `ConfigMap` doesn't
+/// show up as one long string.
+fn to_lines(txt: &str) -> Vec<Line> {
+    let mut lines = highlight(txt, syntax::yaml());
+
+    for block in syntax::blocks(txt) {
+        let content = block.content();
+        let syntax = syntax::detect(&content, Some(&block.name));
+
+        if syntax::is_plain(syntax) {
+            continue;
+        }
+
+        for (i, line) in highlight(&content, syntax).into_iter().enumerate() {
+            let Some(target) = lines.get_mut(block.start + i) else {
+                break;
+            };
+
+            *target = Line::from(
+                std::iter::once(Span::raw(block.prefix(i).to_string()))
+                    .chain(
+                        line.spans
+                            .into_iter()
+                            .map(|span| Span::styled(span.content.into_owned(), span.style)),
+                    )
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    lines
+}
+
+/// Split the spans in a line so that `ranges` (byte offsets into the line) can
+/// be styled without losing the syntax highlighting underneath them.
+fn mark<'a>(line: Line<'a>, ranges: &[(Range<usize>, Style)]) -> Line<'a> {
+    if ranges.is_empty() {
+        return line;
+    }
+
+    let mut spans = Vec::new();
+    let mut offset = 0;
+
+    for span in line.spans {
+        let (start, end) = (offset, offset + span.content.len());
+        let mut pos = start;
+
+        for (range, style) in ranges
+            .iter()
+            .filter(|(range, _)| range.start < end && range.end > start)
+        {
+            let (from, to) = (range.start.max(start), range.end.min(end));
+
+            if from > pos {
+                spans.push(Span::styled(
+                    span.content[pos - start..from - start].to_string(),
+                    span.style,
+                ));
+            }
+
+            spans.push(Span::styled(
+                span.content[from - start..to - start].to_string(),
+                span.style.patch(*style),
+            ));
+            pos = to;
+        }
+
+        if pos < end {
+            spans.push(Span::styled(
+                span.content[pos - start..].to_string(),
+                span.style,
+            ));
+        }
+
+        offset = end;
+    }
+
+    Line::from(spans).style(line.style)
+}
+
+struct SearchStyle {
+    matched: Style,
+    current: Style,
+    count: Style,
+    cursor: Style,
+}
+
+impl Default for SearchStyle {
+    fn default() -> Self {
+        Self {
+            matched: Style::default()
+                .fg(tailwind::GRAY.c100)
+                .bg(tailwind::INDIGO.c700),
+            current: Style::default()
+                .fg(tailwind::GRAY.c900)
+                .bg(tailwind::YELLOW.c300),
+            count: Style::default().fg(tailwind::GRAY.c400),
+            cursor: Style::default().bg(tailwind::GRAY.c800),
+        }
+    }
+}
+
 // TODO:
 // - Need to cache the lines.
 // - See logs for performance improvements (eg. only render visible lines).
 pub struct Yaml {
     txt: String,
+    // Only set when the resource has base64 fields that can be decoded.
+    decoded: Option<String>,
+    show_decoded: bool,
+    // Set for text that isn't YAML at all, see `Yaml::value`.
+    syntax: Option<&'static SyntaxReference>,
     position: Position,
+
+    search: Option<Text>,
+    query: Content,
+    // Line the search was started from, matches before it are skipped while the
+    // query is being typed.
+    origin: u16,
+    current: usize,
+
+    // Line the path is shown for, while picking a field.
+    cursor: Option<usize>,
+    // Only set with a client, fields can't be patched otherwise.
+    object: Option<serde_json::Value>,
+    client: Option<kube::Client>,
+    popup: Option<BoxWidget>,
+    // Shown above the YAML for as long as it is open, see `deprecation::check`.
+    deprecations: Vec<Finding>,
 }
 
 impl Yaml {
@@ -75,35 +210,305 @@ impl Yaml {
     where
         K: Resource<DynamicType = ()> + Serialize + Send + Sync + 'static,
     {
-        WIDGET_VIEWS_VEC
-            .with_label_values(&[K::kind(&()).borrow(), "yaml"])
-            .inc();
+        Self::with_kind(K::kind(&()).borrow(), resource.as_ref())
+    }
+
+    /// Use for resources where the kind is only known at runtime, such as
+    /// `DynamicObject`.
+    pub fn with_kind<K>(kind: &str, resource: &K) -> Self
+    where
+        K: Resource + Serialize,
+    {
+        WIDGET_VIEWS_VEC.with_label_values(&[kind, "yaml"]).inc();
 
         let txt = resource.to_yaml().unwrap();
 
+        let decoded = serde_yaml::to_value(resource).ok().and_then(|mut value| {
+            encoded::decode_all(&mut value)
+                .then(|| serde_yaml::to_string(&value).ok())
+                .flatten()
+        });
+
         Self {
             txt,
+            decoded,
+            show_decoded: false,
+            syntax: None,
             position: Position::default(),
+            search: None,
+            query: Content::default(),
+            origin: 0,
+            current: 0,
+            cursor: None,
+            object: None,
+            client: None,
+            popup: None,
+            deprecations: Vec::new(),
+        }
+    }
+
+    /// Allow the field picked with `p` to be patched, see `QuickEdit::at`.
+    pub fn editable<K>(mut self, client: kube::Client, resource: &K) -> Self
+    where
+        K: Serialize,
+    {
+        self.object = serde_json::to_value(resource).ok();
+        self.client = Some(client);
+        self.deprecations = self
+            .object
+            .as_ref()
+            .map(deprecation::inspect)
+            .unwrap_or_default();
+
+        self
+    }
+
+    /// A single value, such as a key of a `ConfigMap`, highlighted as whatever
+    /// `name` and the content look like it is.
+    pub fn value(name: &str, txt: String) -> Self {
+        Self {
+            syntax: Some(syntax::detect(&txt, Some(name))),
+            txt,
+            decoded: None,
+            show_decoded: false,
+            position: Position::default(),
+            search: None,
+            query: Content::default(),
+            origin: 0,
+            current: 0,
+            cursor: None,
+            object: None,
+            client: None,
+            popup: None,
+            deprecations: Vec::new(),
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self.decoded.as_ref() {
+            Some(decoded) if self.show_decoded => decoded.as_str(),
+            _ => self.txt.as_str(),
+        }
+    }
+
+    /// Every match of the query in the unstyled text, as a line number and the
+    /// byte range within that line. The search is case insensitive unless the
+    /// query contains an uppercase character.
+    fn matches(&self) -> Result<Vec<(usize, Range<usize>)>> {
+        let Some(query) = self.query.try_borrow()?.clone().filter(|q| !q.is_empty()) else {
+            return Ok(Vec::new());
+        };
+
+        let sensitive = query.chars().any(char::is_uppercase);
+        let needle = if sensitive {
+            query
+        } else {
+            query.to_ascii_lowercase()
+        };
+
+        Ok(self
+            .text()
+            .lines()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                let haystack = if sensitive {
+                    line.to_string()
+                } else {
+                    line.to_ascii_lowercase()
+                };
+
+                haystack
+                    .match_indices(needle.as_str())
+                    .map(|(start, _)| (i, start..start + needle.len()))
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    /// Scroll so that the current match is visible, with some context above it.
+    #[allow(clippy::cast_possible_truncation)]
+    fn jump(&mut self, matches: &[(usize, Range<usize>)], area: Rect) {
+        if let Some((line, _)) = matches.get(self.current) {
+            self.position.y = (*line as u16).saturating_sub(area.height / 3);
+        }
+    }
+
+    fn dispatch_search(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        let Some(search) = self.search.as_mut() else {
+            return Ok(Broadcast::Ignored);
+        };
+
+        match search.dispatch(event, buffer, area)? {
+            Broadcast::Exited => {
+                self.search = None;
+            }
+            Broadcast::Consumed => {
+                let matches = self.matches()?;
+
+                self.current = matches
+                    .iter()
+                    .position(|(line, _)| *line >= self.origin as usize)
+                    .unwrap_or_default();
+                self.jump(&matches, area);
+            }
+            _ if matches!(event.key(), Some(Keypress::Enter)) => {
+                self.search = None;
+            }
+            _ => return Ok(Broadcast::Ignored),
+        }
+
+        Ok(Broadcast::Consumed)
+    }
+
+    fn path(&self) -> Option<path::Path> {
+        self.cursor
+            .map(|line| path::at(self.text(), line))
+            .filter(|path| !path.is_empty())
+    }
+
+    /// Move the cursor, scrolling along with it once it gets to the edge.
+    #[allow(clippy::cast_possible_truncation)]
+    fn move_to(&mut self, line: usize, area: Rect) {
+        let line = line.min(self.text().lines().count().saturating_sub(1));
+        let top = self.position.y as usize;
+        let height = area.height.saturating_sub(1).max(1) as usize;
+
+        if line < top {
+            self.position.y = line as u16;
+        } else if line >= top + height {
+            self.position.y = (line + 1 - height) as u16;
         }
+
+        self.cursor = Some(line);
+    }
+
+    fn dispatch_cursor(&mut self, key: &Keypress, area: Rect) -> Broadcast {
+        let Some(cursor) = self.cursor else {
+            return Broadcast::Ignored;
+        };
+
+        match key {
+            Keypress::Escape | Keypress::Printable('p') => self.cursor = None,
+            Keypress::Printable(c @ ('y' | 'Y')) => {
+                if let Some(path) = self.path() {
+                    copy::set(if *c == 'y' {
+                        path.pointer()
+                    } else {
+                        path.jsonpath()
+                    });
+                }
+            }
+            Keypress::Printable('e') => {
+                if let (Some(client), Some(object), Some(path)) =
+                    (self.client.as_ref(), self.object.as_ref(), self.path())
+                {
+                    self.popup =
+                        Some(QuickEdit::at(client.clone(), object, &path.pointer()).boxed());
+                }
+            }
+            key => match move_cursor(key, area) {
+                Some(Movement::Y(y)) => {
+                    self.move_to(cursor.saturating_add_signed(y as isize), area);
+                }
+                _ => return Broadcast::Ignored,
+            },
+        }
+
+        Broadcast::Consumed
+    }
+
+    fn draw_deprecations(&self, frame: &mut Frame, area: Rect) {
+        let minor = deprecation::minor();
+
+        frame.render_widget(
+            Paragraph::new(
+                self.deprecations
+                    .iter()
+                    .map(|finding| Line::from(Indicator::Warning.label(finding.message(minor))))
+                    .collect::<Vec<_>>(),
+            )
+            .style(Style::default().fg(theme::unhealthy())),
+            area,
+        );
     }
 
-    pub fn tab<K>(name: String, resource: Arc<K>) -> Tab
+    pub fn tab<K>(name: String, client: kube::Client, resource: Arc<K>) -> Tab
     where
         K: Resource<DynamicType = ()> + Serialize + Send + Sync + 'static,
     {
         Tab::builder()
             .name(name)
-            .constructor(Box::new(move || Self::new(&resource).boxed()))
+            .constructor(Box::new(move || {
+                Self::new(&resource)
+                    .editable(client.clone(), resource.as_ref())
+                    .boxed()
+            }))
             .build()
     }
 }
 
 impl Widget for Yaml {
-    fn dispatch(&mut self, event: &Event, _: &Buffer, area: Rect) -> Result<Broadcast> {
+    fn dispatch(&mut self, event: &Event, buffer: &Buffer, area: Rect) -> Result<Broadcast> {
+        if let Some(popup) = self.popup.as_mut() {
+            if let Broadcast::Exited = popup.dispatch(event, buffer, area)? {
+                self.popup = None;
+            }
+
+            return Ok(Broadcast::Consumed);
+        }
+
+        propagate!(self.dispatch_search(event, buffer, area));
+
         let Some(key) = event.key() else {
             return Ok(Broadcast::Ignored);
         };
 
+        if let Broadcast::Consumed = self.dispatch_cursor(key, area) {
+            return Ok(Broadcast::Consumed);
+        }
+
+        match key {
+            // Text that isn't YAML doesn't have fields to pick.
+            Keypress::Printable('p') if self.syntax.is_none() => {
+                self.move_to(self.position.y as usize, area);
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable('/') if self.search.is_none() => {
+                self.search = Some(
+                    Text::builder()
+                        .title("Search")
+                        .content(self.query.clone())
+                        .build(),
+                );
+                self.origin = self.position.y;
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable('d') if self.decoded.is_some() => {
+                self.show_decoded = !self.show_decoded;
+
+                return Ok(Broadcast::Consumed);
+            }
+            Keypress::Printable(c @ ('n' | 'N')) => {
+                let matches = self.matches()?;
+
+                if matches.is_empty() {
+                    return Ok(Broadcast::Ignored);
+                }
+
+                self.current = if *c == 'n' {
+                    (self.current + 1) % matches.len()
+                } else {
+                    (self.current + matches.len() - 1) % matches.len()
+                };
+                self.jump(&matches, area);
+
+                return Ok(Broadcast::Consumed);
+            }
+            _ => {}
+        }
+
         if let Some(Movement::Y(y)) = move_cursor(key, area) {
             self.position.y = self.position.y.saturating_add_signed(y);
 
@@ -113,17 +518,172 @@ impl Widget for Yaml {
         Ok(Broadcast::Ignored)
     }
 
+    fn actions(&self) -> Vec<Action> {
+        if self.search.is_some() || self.popup.is_some() {
+            return Vec::new();
+        }
+
+        if self.cursor.is_some() {
+            let mut actions = vec![
+                Action::new('y', "Copy the JSON Pointer"),
+                Action::new('Y', "Copy the JSONPath"),
+            ];
+
+            if self.client.is_some() {
+                actions.push(Action::new('e', "Patch the field"));
+            }
+
+            actions.push(Action::new('p', "Stop picking a field"));
+
+            return actions;
+        }
+
+        let mut actions = vec![
+            Action::new('/', "Search"),
+            Action::new('n', "Next match"),
+            Action::new('N', "Previous match"),
+        ];
+
+        if self.syntax.is_none() {
+            actions.push(Action::new('p', "Pick a field to get its path"));
+        }
+
+        if self.decoded.is_some() {
+            actions.push(Action::new(
+                'd',
+                if self.show_decoded {
+                    "Show encoded values"
+                } else {
+                    "Show decoded values"
+                },
+            ));
+        }
+
+        actions
+    }
+
     #[allow(clippy::cast_possible_truncation)]
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        let lines = to_lines(self.txt.as_str());
+        let style = SearchStyle::default();
+
+        let [warnings, area, footer, input] = Layout::vertical([
+            Constraint::Length(self.deprecations.len() as u16),
+            Constraint::Fill(0),
+            Constraint::Length(u16::from(self.cursor.is_some())),
+            Constraint::Length(if self.search.is_some() { 3 } else { 0 }),
+        ])
+        .areas(area);
+
+        self.draw_deprecations(frame, warnings);
+
+        let path = self.path();
+
+        let matches = self.matches()?;
+        self.current = self.current.min(matches.len().saturating_sub(1));
+
+        // Borrowing the fields directly, instead of through `text()`, keeps the
+        // position free to be updated below.
+        let txt = match self.decoded.as_ref() {
+            Some(decoded) if self.show_decoded => decoded.as_str(),
+            _ => self.txt.as_str(),
+        };
+        let mut lines = match self.syntax {
+            Some(syntax) => highlight(txt, syntax),
+            None => to_lines(txt),
+        };
+
+        for (i, (line, range)) in matches.iter().enumerate() {
+            let Some(target) = lines.get_mut(*line) else {
+                continue;
+            };
+
+            let highlight = if i == self.current {
+                style.current
+            } else {
+                style.matched
+            };
+
+            *target = mark(std::mem::take(target), &[(range.clone(), highlight)]);
+        }
+
+        if let Some(target) = self.cursor.and_then(|line| lines.get_mut(line)) {
+            *target = std::mem::take(target).patch_style(style.cursor);
+        }
 
         self.position.y = self.position.y.clamp(0, lines.len() as u16);
+        let lines_len = lines.len();
 
         frame.render_widget(
             Paragraph::new(lines).scroll((self.position.y, self.position.x)),
             area,
         );
 
+        if self
+            .query
+            .try_borrow()?
+            .as_ref()
+            .is_some_and(|q| !q.is_empty())
+        {
+            let count = if matches.is_empty() {
+                "no matches".to_string()
+            } else {
+                format!("{}/{}", self.current + 1, matches.len())
+            };
+
+            frame.render_widget(
+                Line::from(count).style(style.count).right_aligned(),
+                Rect {
+                    y: area.bottom().saturating_sub(1),
+                    height: 1.min(area.height),
+                    ..area
+                },
+            );
+        }
+
+        let hint = match (self.decoded.is_some(), self.show_decoded) {
+            (false, _) => None,
+            (true, true) => Some("decoded, d: show raw"),
+            (true, false) => Some("d: decode base64"),
+        };
+
+        if let Some(line) = (Scroll {
+            offset: self.position.y as usize,
+            height: area.height as usize,
+            total: lines_len,
+        })
+        .title(hint)
+        {
+            frame.render_widget(
+                line.style(style.count),
+                Rect {
+                    height: 1.min(area.height),
+                    ..area
+                },
+            );
+        }
+
+        if self.cursor.is_some() {
+            frame.render_widget(
+                Line::from(match path {
+                    Some(path) => vec![
+                        Span::raw(path.pointer()),
+                        Span::styled("  ", style.count),
+                        Span::styled(path.jsonpath(), style.count),
+                    ],
+                    None => vec![Span::styled("no field on this line", style.count)],
+                }),
+                footer,
+            );
+        }
+
+        if let Some(search) = self.search.as_mut() {
+            search.draw(frame, input)?;
+        }
+
+        if let Some(popup) = self.popup.as_mut() {
+            popup.draw(frame, area)?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,172 @@
+use std::fmt::Write;
+
+/// One step from the root of an object down to a field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Where a field lives in an object, written out the two ways patches and
+/// tools like `kubectl` expect.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Path(Vec<Segment>);
+
+impl Path {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// RFC 6901, what JSON patches use.
+    pub fn pointer(&self) -> String {
+        self.0.iter().fold(String::new(), |mut out, segment| {
+            match segment {
+                Segment::Key(key) => {
+                    let _ = write!(out, "/{}", key.replace('~', "~0").replace('/', "~1"));
+                }
+                Segment::Index(i) => {
+                    let _ = write!(out, "/{i}");
+                }
+            }
+
+            out
+        })
+    }
+
+    /// The `JSONPath` that `kubectl get -o jsonpath` takes. Keys that aren't
+    /// plain identifiers, such as most labels, end up in brackets.
+    pub fn jsonpath(&self) -> String {
+        self.0.iter().fold("$".to_string(), |mut out, segment| {
+            match segment {
+                Segment::Key(key) if is_identifier(key) => {
+                    let _ = write!(out, ".{key}");
+                }
+                Segment::Key(key) => {
+                    let _ = write!(out, "['{}']", key.replace('\'', "\\'"));
+                }
+                Segment::Index(i) => {
+                    let _ = write!(out, "[{i}]");
+                }
+            }
+
+            out
+        })
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+struct Entry {
+    column: usize,
+    segment: Segment,
+}
+
+/// The path of the field on line `target` of YAML as `serde_yaml` writes it
+/// out: block style, sequences at the same indentation as their key and long
+/// strings as literal blocks. Lines that are part of a multi-line value get
+/// the path of that value.
+pub fn at(txt: &str, target: usize) -> Path {
+    let mut stack: Vec<Entry> = Vec::new();
+    // Column of the key (or `-`) holding a scalar, the lines indented past it
+    // are the rest of that scalar.
+    let mut scalar: Option<usize> = None;
+
+    for (i, line) in txt.lines().enumerate() {
+        let content = line.trim_start();
+        let mut column = line.len() - content.len();
+
+        let continues = scalar.is_some_and(|col| content.is_empty() || column > col);
+
+        if !continues {
+            scalar = None;
+
+            let mut rest = content;
+
+            while let Some(item) = rest.strip_prefix("- ").or((rest == "-").then_some("")) {
+                let mut last = None;
+
+                while let Some(top) = stack.last() {
+                    match top.segment {
+                        Segment::Index(idx) if top.column == column => {
+                            last = Some(idx);
+                            stack.pop();
+                            break;
+                        }
+                        _ if top.column > column => {
+                            stack.pop();
+                        }
+                        _ => break,
+                    }
+                }
+
+                stack.push(Entry {
+                    column,
+                    segment: Segment::Index(last.map_or(0, |idx| idx + 1)),
+                });
+
+                scalar = Some(column);
+                column += rest.len() - item.len();
+                rest = item;
+            }
+
+            if let Some((key, value)) = key(rest) {
+                while stack.last().is_some_and(|top| top.column >= column) {
+                    stack.pop();
+                }
+
+                stack.push(Entry {
+                    column,
+                    segment: Segment::Key(key),
+                });
+
+                scalar = (!value.is_empty()).then_some(column);
+            }
+        }
+
+        if i == target {
+            return Path(stack.into_iter().map(|entry| entry.segment).collect());
+        }
+    }
+
+    Path::default()
+}
+
+/// Split a `key: value` line, with whatever comes after the colon (if
+/// anything) as the value.
+fn key(line: &str) -> Option<(String, &str)> {
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('\'' | '"') => {
+            let mut key = String::new();
+            let mut chars = line.char_indices().skip(1).peekable();
+
+            loop {
+                let (idx, c) = chars.next()?;
+
+                match c {
+                    '\\' if quote == '"' => key.push(chars.next()?.1),
+                    '\'' if quote == '\'' && chars.peek().is_some_and(|(_, c)| *c == '\'') => {
+                        chars.next();
+                        key.push('\'');
+                    }
+                    c if c == quote => break (key, line[idx + 1..].strip_prefix(':')?),
+                    c => key.push(c),
+                }
+            }
+        }
+        // Flow style is only ever used for empty collections, which aren't keys.
+        '[' | '{' => return None,
+        _ => match line.split_once(": ") {
+            Some((key, value)) => (key.to_string(), value),
+            None => (line.strip_suffix(':')?.to_string(), ""),
+        },
+    };
+
+    Some((key, rest.trim()))
+}
@@ -0,0 +1,245 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use rust_embed::Embed;
+use syntect::parsing::{SyntaxDefinition, SyntaxReference, SyntaxSet};
+
+#[derive(Embed)]
+#[folder = "syntaxes"]
+struct Definitions;
+
+// Extensions that say nothing about what's in the file, the content gets looked
+// at instead.
+static AMBIGUOUS: &[&str] = &["cnf", "conf", "config", "txt"];
+
+// Lines that start a literal block in YAML, such as `nginx.conf: |` in the data
+// of a `ConfigMap`, along with the key they belong to.
+static BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(\s*)(?:- )?["']?([^"':]+)["']?: \|[-+0-9]*\s*$"#).expect("valid regex")
+});
+
+/// Everything that comes with syntect, along with the definitions in
+/// `syntaxes/` for the formats it doesn't cover (TOML, INI and nginx).
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(|| {
+    let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+
+    for name in Definitions::iter() {
+        let file = Definitions::get(&name).expect("embedded file");
+
+        match std::str::from_utf8(&file.data)
+            .map_err(|err| err.to_string())
+            .and_then(|txt| {
+                SyntaxDefinition::load_from_str(txt, true, None).map_err(|err| err.to_string())
+            }) {
+            Ok(definition) => builder.add(definition),
+            Err(err) => tracing::warn!("unable to load syntax {name}: {err}"),
+        }
+    }
+
+    builder.build()
+});
+
+pub fn set() -> &'static SyntaxSet {
+    &SYNTAXES
+}
+
+fn by_name(name: &str) -> &'static SyntaxReference {
+    SYNTAXES
+        .find_syntax_by_name(name)
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text())
+}
+
+pub fn yaml() -> &'static SyntaxReference {
+    by_name("YAML")
+}
+
+pub fn is_plain(syntax: &SyntaxReference) -> bool {
+    syntax.name == SYNTAXES.find_syntax_plain_text().name
+}
+
+/// Pick the syntax to highlight `txt` with. `name`, such as a file name or a
+/// `ConfigMap` key, goes first when its extension is a useful one. Otherwise
+/// it is down to what the content looks like.
+pub fn detect(txt: &str, name: Option<&str>) -> &'static SyntaxReference {
+    let by_extension = name
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext)
+        .filter(|ext| !AMBIGUOUS.contains(ext))
+        .and_then(|ext| SYNTAXES.find_syntax_by_extension(ext));
+
+    by_extension.unwrap_or_else(|| by_name(sniff(txt)))
+}
+
+fn sniff(txt: &str) -> &'static str {
+    let trimmed = txt.trim_start();
+
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(txt).is_ok()
+    {
+        return "JSON";
+    }
+
+    let lines: Vec<_> = txt
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', ';']))
+        .collect();
+
+    if lines.is_empty() {
+        return "Plain Text";
+    }
+
+    if nginx(&lines) {
+        return "nginx";
+    }
+
+    if let Some(syntax) = assignments(&lines) {
+        return syntax;
+    }
+
+    if serde_yaml::from_str::<serde_yaml::Mapping>(txt).is_ok() {
+        return "YAML";
+    }
+
+    "Plain Text"
+}
+
+// Directives end with `;` and blocks are wrapped in braces, there's nothing
+// else on a line.
+fn nginx(lines: &[&str]) -> bool {
+    lines.iter().any(|line| line.ends_with('{'))
+        && lines
+            .iter()
+            .all(|line| line.ends_with([';', '{', '}']) || line.starts_with('}'))
+}
+
+// TOML, INI and properties files are all sections and `key = value`, they differ
+// in how strict the values are. Anything that spans lines, such as a TOML array,
+// isn't handled and ends up as YAML or plain text.
+fn assignments(lines: &[&str]) -> Option<&'static str> {
+    let mut sections = false;
+    let mut arrays = false;
+    let mut typed = true;
+
+    for line in lines {
+        if line.starts_with("[[") && line.ends_with("]]") {
+            arrays = true;
+
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            sections = true;
+
+            continue;
+        }
+
+        let (key, value) = line.split_once('=')?;
+
+        if key.trim().is_empty() {
+            return None;
+        }
+
+        typed &= toml_value(value.trim());
+    }
+
+    Some(match (sections, arrays, typed) {
+        (_, true, _) | (_, _, true) => "TOML",
+        (true, _, false) => "INI",
+        (false, _, false) => "Java Properties",
+    })
+}
+
+// Strings have to be quoted in TOML, anything else is a number, date, boolean,
+// array or table.
+fn toml_value(value: &str) -> bool {
+    value.starts_with(['"', '\'', '[', '{'])
+        || value.starts_with(|c: char| c.is_ascii_digit())
+        || matches!(value, "true" | "false")
+        || value.parse::<f64>().is_ok()
+}
+
+/// A literal block in YAML, such as a file in the data of a `ConfigMap`. It
+/// is a string as far as the YAML is concerned, but can be highlighted as
+/// whatever it actually is.
+pub struct Block<'a> {
+    pub name: String,
+    /// Line the content starts on.
+    pub start: usize,
+    pub indent: usize,
+    pub lines: Vec<&'a str>,
+}
+
+impl Block<'_> {
+    pub fn content(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.get(self.indent..).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The indentation stripped from the line, to go back in front of it.
+    pub fn prefix(&self, line: usize) -> &str {
+        self.lines
+            .get(line)
+            .map(|line| &line[..self.indent.min(line.len())])
+            .unwrap_or_default()
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Every literal block in the YAML. Blocks inside of blocks are part of the
+/// outer block's content and aren't returned on their own.
+pub fn blocks(txt: &str) -> Vec<Block> {
+    let lines: Vec<_> = txt.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let Some(captures) = BLOCK.captures(lines[i]) else {
+            i += 1;
+
+            continue;
+        };
+
+        let parent = captures[1].len();
+        let start = i + 1;
+
+        let mut end = start;
+        while end < lines.len()
+            && (lines[end].trim().is_empty() || indentation(lines[end]) > parent)
+        {
+            end += 1;
+        }
+
+        // Trailing blank lines belong to whatever comes next.
+        while end > start && lines[end - 1].trim().is_empty() {
+            end -= 1;
+        }
+
+        let content = &lines[start..end];
+        let indent = content
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| indentation(line))
+            .min()
+            .unwrap_or_default();
+
+        if !content.is_empty() {
+            blocks.push(Block {
+                name: captures[2].trim().to_string(),
+                start,
+                indent,
+                lines: content.to_vec(),
+            });
+        }
+
+        i = end.max(start);
+    }
+
+    blocks
+}